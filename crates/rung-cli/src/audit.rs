@@ -0,0 +1,128 @@
+//! Audit log recording - which command changed which branches, and who ran it.
+//!
+//! Every command that may change the repository snapshots local branch tips
+//! before and after running. If anything moved, an entry is appended to
+//! `.git/rung/audit.jsonl`, so the log covers every command (and hook) that
+//! rewrites history without each command having to report its own changes.
+//! Read it back with `rung audit`.
+
+use std::collections::BTreeMap;
+
+use chrono::Utc;
+use rung_core::{AuditEntry, RefChange, State};
+use rung_git::Repository;
+
+/// Captures branch tips at the start of a command.
+pub struct Recorder {
+    before: BTreeMap<String, String>,
+}
+
+impl Recorder {
+    /// Snapshot the branches of `repo`.
+    pub fn start(repo: &Repository) -> Self {
+        Self {
+            before: branch_tips(repo),
+        }
+    }
+
+    /// Record the command in `state`'s audit log if it changed any branch.
+    ///
+    /// Best effort: a failure to write the log never fails the command.
+    pub fn finish(self, repo: &Repository, state: &State, success: bool) {
+        let after = branch_tips(repo);
+        let refs = diff(&self.before, &after);
+        if refs.is_empty() {
+            return;
+        }
+
+        let entry = AuditEntry {
+            timestamp: Utc::now(),
+            user: user(repo),
+            command: command_line(),
+            success,
+            refs,
+        };
+        let _ = state.append_audit(&entry);
+    }
+}
+
+/// The git identity running rung.
+fn user(repo: &Repository) -> String {
+    repo.signature().map_or_else(
+        |_| "unknown".to_string(),
+        |sig| {
+            format!(
+                "{} <{}>",
+                sig.name().unwrap_or("unknown"),
+                sig.email().unwrap_or("unknown")
+            )
+        },
+    )
+}
+
+/// Current tip of every local branch.
+fn branch_tips(repo: &Repository) -> BTreeMap<String, String> {
+    repo.list_branches()
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|name| {
+            let tip = repo.branch_commit(&name).ok()?;
+            Some((name, tip.to_string()))
+        })
+        .collect()
+}
+
+/// Branches created, moved, or deleted between two snapshots.
+fn diff(before: &BTreeMap<String, String>, after: &BTreeMap<String, String>) -> Vec<RefChange> {
+    let mut names: Vec<&String> = before.keys().chain(after.keys()).collect();
+    names.sort();
+    names.dedup();
+
+    names
+        .into_iter()
+        .filter_map(|name| {
+            let old = before.get(name);
+            let new = after.get(name);
+            (old != new).then(|| RefChange {
+                branch: name.clone(),
+                old: old.cloned(),
+                new: new.cloned(),
+            })
+        })
+        .collect()
+}
+
+/// The invocation as typed, e.g. `rung sync --base main`.
+fn command_line() -> String {
+    std::iter::once("rung".to_string())
+        .chain(std::env::args().skip(1))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff_reports_created_moved_and_deleted() {
+        let before = BTreeMap::from([
+            ("kept".to_string(), "a".to_string()),
+            ("moved".to_string(), "b".to_string()),
+            ("deleted".to_string(), "c".to_string()),
+        ]);
+        let after = BTreeMap::from([
+            ("kept".to_string(), "a".to_string()),
+            ("moved".to_string(), "d".to_string()),
+            ("created".to_string(), "e".to_string()),
+        ]);
+
+        let changes = diff(&before, &after);
+        let branches: Vec<&str> = changes.iter().map(|c| c.branch.as_str()).collect();
+        assert_eq!(branches, vec!["created", "deleted", "moved"]);
+        assert_eq!(changes[0].old, None);
+        assert_eq!(changes[1].new, None);
+        assert_eq!(changes[2].new.as_deref(), Some("d"));
+    }
+}
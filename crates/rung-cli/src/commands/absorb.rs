@@ -1,109 +1,484 @@
 //! `rung absorb` command - Absorb staged changes into appropriate commits.
 
 use anyhow::{Context, Result, bail};
+use inquire::Select;
 use rung_core::State;
-use rung_core::absorb::{AbsorbAction, UnmapReason};
-use rung_git::Repository;
+use rung_core::absorb::{AbsorbAction, AbsorbPlan, AbsorbTarget, UnmapReason, UnmappedHunk};
+use rung_git::{BlameResult, Hunk, Oid, Repository};
+use serde::Serialize;
 use std::collections::HashMap;
+use std::collections::hash_map::Entry;
+use std::io::IsTerminal;
 
 use crate::commands::utils;
 use crate::output;
 use crate::services::AbsorbService;
 
+/// Options for the absorb command.
+#[derive(Debug)]
+#[allow(clippy::struct_excessive_bools)] // CLI options map directly to flags
+pub struct AbsorbOptions<'a> {
+    pub json: bool,
+    pub dry_run: bool,
+    pub base: Option<&'a str>,
+    pub fixup: bool,
+    pub unstaged: bool,
+    pub patch: bool,
+    pub select: bool,
+    pub and_restack: bool,
+    pub commit: Option<&'a str>,
+    pub branch: Option<&'a str>,
+}
+
+/// JSON output for absorb command.
+#[derive(Debug, Default, Serialize)]
+struct AbsorbOutput {
+    dry_run: bool,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    hunks: Vec<HunkOutput>,
+    targets: Vec<TargetOutput>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    unmapped: Vec<UnmappedOutput>,
+    fixups_created: usize,
+    fixup_branches: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    restacked: Vec<String>,
+}
+
+/// A staged hunk and the commit it maps to.
+#[derive(Debug, Serialize)]
+struct HunkOutput {
+    file: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    old_path: Option<String>,
+    old_start: u32,
+    old_lines: u32,
+    new_start: u32,
+    new_lines: u32,
+    status: HunkStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    target: Option<HunkTarget>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reason: Option<String>,
+    /// Commits the user could pick from when blame found several.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    candidates: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum HunkStatus {
+    Mapped,
+    Unmapped,
+}
+
+/// The commit a hunk is absorbed into, and the stack branch that owns it.
+#[derive(Debug, Serialize)]
+struct HunkTarget {
+    commit: String,
+    message: String,
+    branch: String,
+}
+
+/// A commit that receives absorbed hunks.
+#[derive(Debug, Serialize)]
+struct TargetOutput {
+    commit: String,
+    message: String,
+    hunks: usize,
+    files: Vec<String>,
+}
+
+/// A hunk that could not be absorbed.
+#[derive(Debug, Serialize)]
+struct UnmappedOutput {
+    file: String,
+    reason: String,
+}
+
 /// Run the absorb command.
-pub fn run(dry_run: bool, base: Option<&str>) -> Result<()> {
-    // Open repository
-    let repo = Repository::open_current().context("Not inside a git repository")?;
+pub fn run(opts: &AbsorbOptions<'_>) -> Result<()> {
+    let ctx = utils::CommandContext::open()?;
+    let utils::CommandContext { repo, state, .. } = &ctx;
+    let _lock = utils::lock_state(repo, state)?;
+
+    // Ensure on branch
+    utils::ensure_on_branch(repo)?;
 
-    // Get state manager
-    let workdir = repo.workdir().context("Cannot run in bare repository")?;
-    let state = State::new(workdir)?;
+    let mut output = if opts.unstaged || opts.patch {
+        // Stage on the user's behalf, restoring their index unless fixups were created
+        let snapshot = repo.snapshot_index()?;
+        if opts.patch {
+            repo.stage_interactive()?;
+        } else {
+            repo.stage_tracked()?;
+        }
 
-    // Ensure initialized
-    if !state.is_initialized() {
-        bail!("Rung not initialized - run `rung init` first");
+        let result = absorb(&ctx, opts, true);
+        if !matches!(&result, Ok(output) if !output.fixup_branches.is_empty()) {
+            repo.restore_index(snapshot)?;
+        }
+        result?
+    } else {
+        absorb(&ctx, opts, false)?
+    };
+
+    if !output.fixup_branches.is_empty()
+        && (opts.and_restack || state.load_config()?.absorb.restack)
+    {
+        output.restacked =
+            utils::restack_descendants(repo, state, &output.fixup_branches, opts.json)?;
     }
 
-    // Ensure on branch
-    utils::ensure_on_branch(&repo)?;
+    if opts.json {
+        println!("{}", serde_json::to_string_pretty(&output)?);
+    }
+
+    Ok(())
+}
+
+/// Plan and execute the absorb, reporting the branches that received fixups.
+///
+/// With `staged_for_user`, the index was staged by `--unstaged` or `--patch`,
+/// so hunks that cannot be absorbed stop the absorb instead of being swept
+/// into the fixup commit.
+fn absorb(
+    ctx: &utils::CommandContext,
+    opts: &AbsorbOptions<'_>,
+    staged_for_user: bool,
+) -> Result<AbsorbOutput> {
+    let utils::CommandContext { repo, state, .. } = ctx;
+    let json = opts.json;
 
     // Create service
-    let service = AbsorbService::new(&repo);
+    let service = AbsorbService::new(repo);
 
     // Check for staged changes
     if !service.has_staged_changes()? {
-        bail!("No staged changes to absorb. Stage changes with `git add` first.");
+        if staged_for_user {
+            bail!("No changes to absorb");
+        }
+        bail!(
+            "No staged changes to absorb. Stage changes with `git add` first, or use --unstaged or --patch."
+        );
     }
 
     // Determine base branch
-    let base_branch = if let Some(b) = base {
+    let base_branch = if let Some(b) = opts.base {
         b.to_string()
     } else {
-        let rt = tokio::runtime::Runtime::new()?;
-        rt.block_on(service.detect_base_branch())?
+        ctx.runtime()?.block_on(service.detect_base_branch())?
     };
 
     // Create absorb plan
-    let plan = service.create_plan(&state, &base_branch)?;
+    let mut plan = service.create_plan(state, &base_branch, &absorb_target(repo, opts)?)?;
 
-    if plan.actions.is_empty() && plan.unmapped.is_empty() {
-        output::info("Staged changes present but no absorbable hunks found");
-        return Ok(());
+    // Let the user pick targets for ambiguous hunks (or every hunk with --select)
+    if json {
+        if opts.select {
+            bail!("--select is interactive and cannot be used with --json");
+        }
+    } else if std::io::stdin().is_terminal() {
+        select_targets(&mut plan, opts.select)?;
+    } else if opts.select {
+        bail!("--select requires an interactive terminal");
     }
 
-    // Report unmapped hunks
-    if !plan.unmapped.is_empty() {
-        output::warn(&format!(
-            "{} hunk(s) could not be absorbed:",
-            plan.unmapped.len()
-        ));
-        for unmapped in &plan.unmapped {
-            let reason = match &unmapped.reason {
-                UnmapReason::NewFile => "new file".to_string(),
-                UnmapReason::InsertOnly => "insert-only (no lines to blame)".to_string(),
-                UnmapReason::MultipleCommits => "multiple commits touched these lines".to_string(),
-                UnmapReason::CommitNotInStack => "target commit not in stack".to_string(),
-                UnmapReason::CommitOnBaseBranch => {
-                    "target commit already on base branch".to_string()
-                }
-                UnmapReason::BlameError(e) => format!("blame error: {e}"),
-            };
-            output::detail(&format!("  {} ({})", unmapped.hunk.file_path, reason));
+    let mut output = AbsorbOutput {
+        dry_run: opts.dry_run,
+        hunks: plan_hunks(&service, state, &plan)?,
+        targets: plan_targets(&plan.actions),
+        unmapped: plan_unmapped(&plan.unmapped),
+        ..AbsorbOutput::default()
+    };
+
+    if plan.actions.is_empty() && plan.unmapped.is_empty() {
+        if !json {
+            output::info("Staged changes present but no absorbable hunks found");
         }
-        output::detail("");
+        return Ok(output);
+    }
+
+    if !output.unmapped.is_empty() && !json {
+        print_unmapped(&output.unmapped);
     }
 
     if plan.actions.is_empty() {
-        if !plan.unmapped.is_empty() {
-            bail!("All staged hunks could not be mapped to target commits");
+        // A JSON dry run still reports why each hunk was left out
+        if plan.unmapped.is_empty() || (json && opts.dry_run) {
+            return Ok(output);
         }
-        return Ok(());
+        bail!("All staged hunks could not be mapped to target commits");
     }
 
     // Show what will be absorbed
-    output::info(&format!("{} hunk(s) will be absorbed:", plan.actions.len()));
-    print_absorb_plan(&plan.actions);
+    if !json {
+        output::info(&format!("{} hunk(s) will be absorbed:", plan.actions.len()));
+        print_absorb_plan(&plan.actions);
+    }
 
-    if dry_run {
-        output::info("Dry run - no changes made");
-        return Ok(());
+    if opts.dry_run {
+        if !json {
+            output::info("Dry run - no changes made");
+        }
+        return Ok(output);
+    }
+
+    if staged_for_user && !plan.unmapped.is_empty() {
+        bail!("Some changes could not be absorbed - use --patch to pick only the absorbable hunks");
     }
 
     // Execute the absorb
+    if opts.fixup {
+        let result = service.execute_plan_as_fixups(state, &plan)?;
+        if !json {
+            let current = repo.current_branch()?;
+            for branch in &result.fixup_branches {
+                output::success(&format!("Created fixup commit on {branch}"));
+            }
+            if result.fixup_branches.iter().any(|b| *b != current) {
+                output::info(&format!(
+                    "The absorbed changes were removed from {current} - `rung sync` restacks it to bring them back"
+                ));
+            }
+            if result.fixups_created > 0 {
+                output::info("Run `rung sync --autosquash` to squash the fixups when ready");
+            }
+        }
+        output.fixups_created = result.fixups_created;
+        output.fixup_branches = result.fixup_branches;
+        return Ok(output);
+    }
+
     let result = service.execute_plan(&plan)?;
 
-    output::success(&format!(
-        "Created {} fixup commit(s)",
-        result.fixups_created
-    ));
+    if !json {
+        output::success(&format!(
+            "Created {} fixup commit(s)",
+            result.fixups_created
+        ));
+
+        if result.fixups_created > 0 {
+            output::info("Run `git rebase -i --autosquash` to apply the fixups");
+        }
+    }
+
+    output.fixups_created = result.fixups_created;
+    output.fixup_branches = result.fixup_branches;
+    Ok(output)
+}
 
-    if result.fixups_created > 0 {
-        output::info("Run `git rebase -i --autosquash` to apply the fixups");
+/// Where hunks may be absorbed, from `--commit` and `--branch`.
+fn absorb_target(repo: &Repository, opts: &AbsorbOptions<'_>) -> Result<AbsorbTarget> {
+    if let Some(rev) = opts.commit {
+        let commit = repo
+            .resolve_commit(rev)
+            .with_context(|| format!("No commit '{rev}'"))?;
+        let message = repo
+            .find_commit(commit)?
+            .summary()
+            .unwrap_or_default()
+            .to_string();
+        return Ok(AbsorbTarget::Commit(BlameResult { commit, message }));
+    }
+    Ok(opts.branch.map_or(AbsorbTarget::Blame, |branch| {
+        AbsorbTarget::Branch(branch.to_string())
+    }))
+}
+
+/// Describe why a hunk could not be absorbed.
+fn unmap_reason(reason: &UnmapReason) -> String {
+    match reason {
+        UnmapReason::NewFile => "new file".to_string(),
+        UnmapReason::InsertOnly => "insert-only (no lines to blame)".to_string(),
+        UnmapReason::MultipleCommits => "multiple commits touched these lines".to_string(),
+        UnmapReason::CommitNotInStack => "target commit not in stack".to_string(),
+        UnmapReason::CommitOnBaseBranch => "target commit already on base branch".to_string(),
+        UnmapReason::BlameError(e) => format!("blame error: {e}"),
+        UnmapReason::Skipped => "skipped".to_string(),
+        UnmapReason::NotOnBranch(branch) => format!("last touched outside {branch}"),
+    }
+}
+
+/// Prompt for the target commit of hunks that blame could not settle.
+///
+/// Hunks whose lines come from several stack commits are offered those
+/// commits to choose from. With `all`, every mapped hunk is confirmed too,
+/// and can be skipped.
+fn select_targets(plan: &mut AbsorbPlan, all: bool) -> Result<()> {
+    if all {
+        for action in std::mem::take(&mut plan.actions) {
+            let candidate = BlameResult {
+                commit: action.target_commit,
+                message: action.target_message.clone(),
+            };
+            match prompt_target(&action.hunk, &[candidate])? {
+                Some(target) => plan.actions.push(AbsorbAction {
+                    target_commit: target.commit,
+                    target_message: target.message,
+                    ..action
+                }),
+                None => plan.unmapped.push(UnmappedHunk {
+                    hunk: action.hunk,
+                    reason: UnmapReason::Skipped,
+                    candidates: vec![],
+                }),
+            }
+        }
+    }
+
+    for unmapped in std::mem::take(&mut plan.unmapped) {
+        if unmapped.candidates.len() < 2 {
+            plan.unmapped.push(unmapped);
+            continue;
+        }
+        match prompt_target(&unmapped.hunk, &unmapped.candidates)? {
+            Some(target) => plan.actions.push(AbsorbAction {
+                hunk: unmapped.hunk,
+                target_commit: target.commit,
+                target_message: target.message,
+            }),
+            None => plan.unmapped.push(unmapped),
+        }
     }
 
     Ok(())
 }
 
+/// Ask which commit a hunk should be absorbed into. `None` means skip.
+fn prompt_target(hunk: &Hunk, candidates: &[BlameResult]) -> Result<Option<BlameResult>> {
+    output::info(&format!(
+        "{} (lines {}-{})",
+        hunk.file_path,
+        hunk.old_start,
+        hunk.old_start + hunk.old_lines.saturating_sub(1)
+    ));
+    for line in hunk.content.lines() {
+        output::detail(&format!("  {line}"));
+    }
+
+    let mut options: Vec<String> = candidates
+        .iter()
+        .map(|c| {
+            let sha = c.commit.to_string();
+            format!("{} {}", &sha[..8.min(sha.len())], c.message)
+        })
+        .collect();
+    options.push("Skip this hunk".to_string());
+
+    let selection = Select::new("Absorb into:", options.clone())
+        .with_page_size(10)
+        .prompt()
+        .context("Selection cancelled")?;
+
+    Ok(options
+        .iter()
+        .position(|o| *o == selection)
+        .and_then(|i| candidates.get(i))
+        .cloned())
+}
+
+/// Report the hunks that could not be absorbed.
+fn print_unmapped(unmapped: &[UnmappedOutput]) {
+    output::warn(&format!(
+        "{} hunk(s) could not be absorbed:",
+        unmapped.len()
+    ));
+    for hunk in unmapped {
+        output::detail(&format!("  {} ({})", hunk.file, hunk.reason));
+    }
+    output::detail("");
+}
+
+/// List every staged hunk with its target or the reason it has none, for
+/// JSON output.
+fn plan_hunks<G: rung_git::AbsorbOps>(
+    service: &AbsorbService<'_, G>,
+    state: &State,
+    plan: &AbsorbPlan,
+) -> Result<Vec<HunkOutput>> {
+    let hunk_output = |hunk: &Hunk, status| HunkOutput {
+        file: hunk.file_path.clone(),
+        old_path: hunk.old_path.clone(),
+        old_start: hunk.old_start,
+        old_lines: hunk.old_lines,
+        new_start: hunk.new_start,
+        new_lines: hunk.new_lines,
+        status,
+        target: None,
+        reason: None,
+        candidates: vec![],
+    };
+
+    let mut branches: HashMap<Oid, String> = HashMap::new();
+    for action in &plan.actions {
+        if let Entry::Vacant(entry) = branches.entry(action.target_commit) {
+            entry.insert(service.owning_branch(state, action.target_commit)?);
+        }
+    }
+
+    let mut hunks = Vec::with_capacity(plan.actions.len() + plan.unmapped.len());
+    for action in &plan.actions {
+        hunks.push(HunkOutput {
+            target: Some(HunkTarget {
+                commit: action.target_commit.to_string(),
+                message: action.target_message.clone(),
+                branch: branches[&action.target_commit].clone(),
+            }),
+            ..hunk_output(&action.hunk, HunkStatus::Mapped)
+        });
+    }
+    for unmapped in &plan.unmapped {
+        hunks.push(HunkOutput {
+            reason: Some(unmap_reason(&unmapped.reason)),
+            candidates: unmapped
+                .candidates
+                .iter()
+                .map(|c| c.commit.to_string())
+                .collect(),
+            ..hunk_output(&unmapped.hunk, HunkStatus::Unmapped)
+        });
+    }
+    hunks.sort_by(|a, b| (&a.file, a.new_start).cmp(&(&b.file, b.new_start)));
+    Ok(hunks)
+}
+
+/// The hunks that could not be absorbed, with why.
+fn plan_unmapped(unmapped: &[UnmappedHunk]) -> Vec<UnmappedOutput> {
+    unmapped
+        .iter()
+        .map(|u| UnmappedOutput {
+            file: u.hunk.file_path.clone(),
+            reason: unmap_reason(&u.reason),
+        })
+        .collect()
+}
+
+/// Group the absorb plan by target commit, for JSON output.
+fn plan_targets(actions: &[AbsorbAction]) -> Vec<TargetOutput> {
+    let mut targets: Vec<TargetOutput> = vec![];
+    for action in actions {
+        let commit = action.target_commit.to_string();
+        let file = &action.hunk.file_path;
+        match targets.iter_mut().find(|t| t.commit == commit) {
+            Some(target) => {
+                target.hunks += 1;
+                if !target.files.contains(file) {
+                    target.files.push(file.clone());
+                }
+            }
+            None => targets.push(TargetOutput {
+                commit,
+                message: action.target_message.clone(),
+                hunks: 1,
+                files: vec![file.clone()],
+            }),
+        }
+    }
+    targets.sort_by(|a, b| a.commit.cmp(&b.commit));
+    targets
+}
+
 /// Print the absorb plan grouped by target commit.
 fn print_absorb_plan(actions: &[AbsorbAction]) {
     let mut by_target: HashMap<String, Vec<&AbsorbAction>> = HashMap::new();
@@ -2,26 +2,27 @@
 
 use anyhow::{Context, Result, bail};
 use inquire::Select;
-use rung_core::{BranchName, State};
-use rung_git::Repository;
+use rung_core::BranchName;
+use serde::Serialize;
 
 use crate::commands::utils;
 use crate::output;
 use crate::services::AdoptService;
 
-/// Run the adopt command.
-pub fn run(branch: Option<&str>, parent: Option<&str>, dry_run: bool) -> Result<()> {
-    // Open repository
-    let repo = Repository::open_current().context("Not inside a git repository")?;
-
-    // Get state manager
-    let workdir = repo.workdir().context("Cannot run in bare repository")?;
-    let state = State::new(workdir)?;
+/// JSON output for adopt command.
+#[derive(Debug, Serialize)]
+struct AdoptOutput {
+    branch: String,
+    parent: String,
+    dry_run: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stack_depth: Option<usize>,
+}
 
-    // Ensure initialized
-    if !state.is_initialized() {
-        bail!("Rung not initialized - run `rung init` first");
-    }
+/// Run the adopt command.
+pub fn run(json: bool, branch: Option<&str>, parent: Option<&str>, dry_run: bool) -> Result<()> {
+    let utils::CommandContext { repo, state, .. } = utils::CommandContext::open()?;
+    let _lock = utils::lock_state(&repo, &state)?;
 
     // Ensure on branch (not detached HEAD)
     utils::ensure_on_branch(&repo)?;
@@ -59,6 +60,8 @@ pub fn run(branch: Option<&str>, parent: Option<&str>, dry_run: bool) -> Result<
         if choices.len() == 1 {
             // Only base branch available
             base_branch
+        } else if json {
+            bail!("--parent is required with --json");
         } else {
             Select::new("Select parent branch:", choices)
                 .with_help_message("The branch that this branch should be based on")
@@ -71,6 +74,14 @@ pub fn run(branch: Option<&str>, parent: Option<&str>, dry_run: bool) -> Result<
     service.validate_parent(&state, &parent_name)?;
 
     if dry_run {
+        if json {
+            return output_json(&AdoptOutput {
+                branch: branch_name.to_string(),
+                parent: parent_name,
+                dry_run: true,
+                stack_depth: None,
+            });
+        }
         output::info(&format!(
             "Would adopt branch '{branch_name}' with parent '{parent_name}'"
         ));
@@ -80,6 +91,15 @@ pub fn run(branch: Option<&str>, parent: Option<&str>, dry_run: bool) -> Result<
     // Adopt the branch
     let result = service.adopt_branch(&state, &branch_name_validated, &parent_name)?;
 
+    if json {
+        return output_json(&AdoptOutput {
+            branch: result.branch_name,
+            parent: result.parent_name,
+            dry_run: false,
+            stack_depth: Some(result.stack_depth),
+        });
+    }
+
     output::success(&format!(
         "Adopted branch '{}' with parent '{}'",
         result.branch_name, result.parent_name
@@ -92,3 +112,9 @@ pub fn run(branch: Option<&str>, parent: Option<&str>, dry_run: bool) -> Result<
 
     Ok(())
 }
+
+/// Output adopt result as JSON.
+fn output_json(output: &AdoptOutput) -> Result<()> {
+    println!("{}", serde_json::to_string_pretty(output)?);
+    Ok(())
+}
@@ -0,0 +1,173 @@
+//! `rung apply` command - Commit a patch onto any branch in the stack.
+
+use std::io::{IsTerminal, Read};
+use std::path::Path;
+
+use anyhow::{Context, Result, bail};
+use serde::Serialize;
+
+use crate::commands::utils;
+use crate::output;
+
+/// JSON output for apply command.
+#[derive(Debug, Serialize)]
+struct ApplyOutput {
+    branch: String,
+    commit: String,
+    message: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    restacked: Vec<String>,
+}
+
+/// Run the apply command.
+pub fn run(
+    json: bool,
+    patch: Option<&Path>,
+    to: Option<&str>,
+    message: Option<&str>,
+) -> Result<()> {
+    let utils::CommandContext { repo, state, .. } = utils::CommandContext::open()?;
+    let _lock = utils::lock_state(&repo, &state)?;
+
+    let branch = if let Some(branch) = to {
+        branch.to_string()
+    } else {
+        utils::ensure_on_branch(&repo)?;
+        repo.current_branch()?
+    };
+    if state.load_stack()?.find_branch(&branch).is_none() {
+        bail!("Branch '{branch}' is not in the stack");
+    }
+    repo.protected_branches()
+        .check(&branch, "apply a patch to")?;
+
+    let patch = read_patch(patch)?;
+    if patch.iter().all(u8::is_ascii_whitespace) {
+        bail!("The patch is empty");
+    }
+    let message = match message {
+        Some(message) => message.to_string(),
+        None => patch_message(&String::from_utf8_lossy(&patch))
+            .context("The patch has no subject - pass --message")?,
+    };
+
+    let commit = repo.commit_patch_to(&branch, &patch, &message)?;
+    let subject = message.lines().next().unwrap_or_default().to_string();
+    if !json {
+        let sha = commit.to_string();
+        output::success(&format!(
+            "Applied patch to {branch} as {} {subject}",
+            &sha[..8]
+        ));
+    }
+
+    let restacked = utils::restack_descendants(&repo, &state, std::slice::from_ref(&branch), json)?;
+
+    if json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&ApplyOutput {
+                branch,
+                commit: commit.to_string(),
+                message: subject,
+                restacked,
+            })?
+        );
+    }
+    Ok(())
+}
+
+/// Read the patch from a file, or from stdin for `-` or no path.
+fn read_patch(path: Option<&Path>) -> Result<Vec<u8>> {
+    match path {
+        Some(path) if path != Path::new("-") => {
+            std::fs::read(path).with_context(|| format!("Failed to read {}", path.display()))
+        }
+        _ => {
+            let mut stdin = std::io::stdin();
+            if stdin.is_terminal() {
+                bail!("Pass a patch file, or pipe a diff on stdin");
+            }
+            let mut contents = Vec::new();
+            stdin
+                .read_to_end(&mut contents)
+                .context("Failed to read the patch from stdin")?;
+            Ok(contents)
+        }
+    }
+}
+
+/// Commit message from the mail headers of a `git format-patch` patch.
+///
+/// The subject loses its `[PATCH ...]` prefix and is followed by the body up
+/// to the `---` separator. A plain diff has no message.
+fn patch_message(patch: &str) -> Option<String> {
+    let mut lines = patch.lines();
+    if !lines.next()?.starts_with("From ") {
+        return None;
+    }
+
+    let mut subject: Option<String> = None;
+    for line in lines.by_ref() {
+        if line.is_empty() {
+            break;
+        }
+        if let Some(rest) = line.strip_prefix("Subject:") {
+            subject = Some(rest.trim().to_string());
+        } else if let Some(subject) = subject.as_mut()
+            && line.starts_with([' ', '\t'])
+        {
+            // Folded header line
+            subject.push(' ');
+            subject.push_str(line.trim());
+        }
+    }
+    let subject = subject?;
+    let subject = match subject.strip_prefix('[') {
+        Some(rest) => rest.split_once(']').map_or(subject.as_str(), |(_, s)| s),
+        None => &subject,
+    }
+    .trim();
+    if subject.is_empty() {
+        return None;
+    }
+
+    let body: Vec<&str> = lines
+        .take_while(|line| *line != "---" && !line.starts_with("diff --git "))
+        .collect();
+    let body = body.join("\n");
+    let body = body.trim();
+    Some(if body.is_empty() {
+        subject.to_string()
+    } else {
+        format!("{subject}\n\n{body}")
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_patch_message_from_format_patch() {
+        let patch = "From 1234567890abcdef Mon Sep 17 00:00:00 2001\n\
+                     From: Reviewer <reviewer@example.com>\n\
+                     Date: Mon, 1 Jan 2024 00:00:00 +0000\n\
+                     Subject: [PATCH 1/2] Handle empty tokens in\n the auth middleware\n\
+                     \n\
+                     An empty header used to panic.\n\
+                     ---\n \
+                     src/auth.rs | 2 +-\n";
+        assert_eq!(
+            patch_message(patch).as_deref(),
+            Some("Handle empty tokens in the auth middleware\n\nAn empty header used to panic.")
+        );
+    }
+
+    #[test]
+    fn test_patch_message_plain_diff() {
+        let patch =
+            "diff --git a/src/auth.rs b/src/auth.rs\n--- a/src/auth.rs\n+++ b/src/auth.rs\n";
+        assert_eq!(patch_message(patch), None);
+    }
+}
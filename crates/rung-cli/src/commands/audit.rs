@@ -0,0 +1,81 @@
+//! `rung audit` command - show the log of operations that changed branches.
+
+use anyhow::Result;
+use colored::Colorize;
+use rung_core::AuditEntry;
+use serde::Serialize;
+
+use super::utils::CommandContext;
+use crate::output;
+
+/// An entry with its operation id, as accepted by `rung undo <id>`.
+#[derive(Debug, Serialize)]
+struct AuditOutput<'a> {
+    id: usize,
+    #[serde(flatten)]
+    entry: &'a AuditEntry,
+}
+
+/// Run the audit command.
+pub fn run(json: bool, limit: Option<usize>) -> Result<()> {
+    let CommandContext { state, .. } = CommandContext::open()?;
+
+    let entries = state.load_audit()?;
+    let skip = limit.map_or(0, |n| entries.len().saturating_sub(n));
+    // Ids are 1-based positions in the log, which is only ever appended to
+    let entries: Vec<AuditOutput> = entries
+        .iter()
+        .enumerate()
+        .skip(skip)
+        .map(|(index, entry)| AuditOutput {
+            id: index + 1,
+            entry,
+        })
+        .collect();
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&entries)?);
+        return Ok(());
+    }
+
+    if entries.is_empty() {
+        output::info("No operations recorded yet");
+        return Ok(());
+    }
+
+    for AuditOutput { id, entry } in entries {
+        print_entry(id, entry);
+    }
+    Ok(())
+}
+
+/// Print one entry as a header line followed by its ref changes.
+fn print_entry(id: usize, entry: &AuditEntry) {
+    let status = if entry.success {
+        String::new()
+    } else {
+        format!(" {}", "(failed)".red())
+    };
+    output::info(&format!(
+        "{}  {}  {}  {}{status}",
+        format!("#{id}").yellow(),
+        entry
+            .timestamp
+            .format("%Y-%m-%d %H:%M:%S UTC")
+            .to_string()
+            .dimmed(),
+        entry.user,
+        entry.command.bold()
+    ));
+
+    for change in &entry.refs {
+        let old = change.old.as_deref().map_or("(created)", short);
+        let new = change.new.as_deref().map_or("(deleted)", short);
+        output::detail(&format!("  {}  {old} → {new}", change.branch));
+    }
+}
+
+/// Abbreviate a commit SHA for display.
+fn short(sha: &str) -> &str {
+    sha.get(..7).unwrap_or(sha)
+}
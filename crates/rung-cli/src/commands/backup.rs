@@ -0,0 +1,163 @@
+//! `rung backup` command - export and import rung's state as a tarball.
+
+use std::fs::File;
+use std::io::{BufReader, BufWriter};
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result, bail};
+use clap::Subcommand;
+use rung_core::BundleSummary;
+use serde::Serialize;
+
+use crate::commands::utils::{self, CommandContext};
+use crate::output;
+
+/// Backup subcommands.
+#[derive(Debug, Subcommand)]
+pub enum BackupCommand {
+    /// Write the stack, sync backups, and audit log to a tarball.
+    ///
+    /// Handy before risky experiments, and for attaching state to bug reports.
+    Export {
+        /// File to write, e.g. `rung-state.tar.gz`.
+        file: PathBuf,
+
+        /// Replace branch names with placeholders (`branch-1`, ...) and drop
+        /// command arguments from the audit log.
+        #[arg(long)]
+        redact: bool,
+    },
+
+    /// Restore the state from a tarball written by `rung backup export`.
+    ///
+    /// Replaces the stack, and adds the audit log entries and sync backups
+    /// to the existing ones.
+    Import {
+        /// File to read.
+        file: PathBuf,
+
+        /// Replace a stack that already has branches.
+        #[arg(long)]
+        force: bool,
+
+        /// Import a bundle exported with `--redact`, whose branch names are
+        /// placeholders.
+        #[arg(long)]
+        allow_redacted: bool,
+    },
+}
+
+/// JSON output for both subcommands.
+#[derive(Debug, Serialize)]
+struct BackupOutput {
+    file: String,
+    #[serde(flatten)]
+    summary: BundleSummary,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    missing_branches: Vec<String>,
+}
+
+/// Run a backup subcommand.
+pub fn run(json: bool, command: &BackupCommand) -> Result<()> {
+    match command {
+        BackupCommand::Export { file, redact } => export(json, file, *redact),
+        BackupCommand::Import {
+            file,
+            force,
+            allow_redacted,
+        } => import(json, file, *force, *allow_redacted),
+    }
+}
+
+/// Write the bundle.
+fn export(json: bool, file: &Path, redact: bool) -> Result<()> {
+    let CommandContext { state, .. } = CommandContext::open()?;
+
+    let out = File::create(file).with_context(|| format!("Failed to create {}", file.display()))?;
+    let summary = state.export_bundle(BufWriter::new(out), redact)?;
+
+    if json {
+        return print_json(file, summary, vec![]);
+    }
+    output::success(&format!(
+        "Exported {} to {}",
+        describe(&summary),
+        file.display()
+    ));
+    if redact {
+        output::detail("  Branch names are redacted");
+    }
+    Ok(())
+}
+
+/// Restore the bundle.
+fn import(json: bool, file: &Path, force: bool, allow_redacted: bool) -> Result<()> {
+    let CommandContext { repo, state, .. } = CommandContext::open_uninitialized()?;
+
+    let _lock = if state.is_initialized() {
+        let existing = state.load_stack()?.branches.len();
+        if existing > 0 && !force {
+            bail!(
+                "This repository already has a stack with {existing} branch(es) - use --force to replace it"
+            );
+        }
+        Some(utils::lock_state(&repo, &state)?)
+    } else {
+        None
+    };
+
+    let input = File::open(file).with_context(|| format!("Failed to open {}", file.display()))?;
+    let summary = state.import_bundle(BufReader::new(input), allow_redacted)?;
+
+    let missing_branches: Vec<String> = state
+        .load_stack()?
+        .branches
+        .iter()
+        .filter(|b| !repo.branch_exists(&b.name))
+        .map(|b| b.name.to_string())
+        .collect();
+
+    if json {
+        return print_json(file, summary, missing_branches);
+    }
+    output::success(&format!(
+        "Imported {} from {}",
+        describe(&summary),
+        file.display()
+    ));
+    if summary.redacted {
+        output::warn(
+            "This bundle was exported with --redact, so its branch names are placeholders",
+        );
+    }
+    if !missing_branches.is_empty() {
+        output::warn(&format!(
+            "{} stack branch(es) don't exist in this repository: {}",
+            missing_branches.len(),
+            missing_branches.join(", ")
+        ));
+    }
+    Ok(())
+}
+
+/// e.g. `3 branches, 2 backups and 14 operations`.
+fn describe(summary: &BundleSummary) -> String {
+    let plural =
+        |n: usize, one: &str, many: &str| format!("{n} {}", if n == 1 { one } else { many });
+    format!(
+        "{}, {} and {}",
+        plural(summary.branches, "branch", "branches"),
+        plural(summary.backups, "backup", "backups"),
+        plural(summary.operations, "operation", "operations")
+    )
+}
+
+fn print_json(file: &Path, summary: BundleSummary, missing_branches: Vec<String>) -> Result<()> {
+    let output = BackupOutput {
+        file: file.display().to_string(),
+        summary,
+        missing_branches,
+    };
+    println!("{}", serde_json::to_string_pretty(&output)?);
+    Ok(())
+}
@@ -0,0 +1,281 @@
+//! `rung ci` command - stack checks for pull-request pipelines.
+
+use std::fs;
+use std::path::Path;
+
+use anyhow::{Context, Result, bail};
+use clap::Subcommand;
+use colored::Colorize;
+use serde::Serialize;
+
+use crate::commands::merge::parse_merge_method;
+use crate::commands::utils::CommandContext;
+use crate::output;
+use crate::services::CiService;
+
+/// Where `rung ci setup` writes the workflow, relative to the repository root.
+const WORKFLOW_PATH: &str = ".github/workflows/rung.yml";
+
+/// GitHub Actions workflow written by `rung ci setup`.
+///
+/// `__DEFAULT_BRANCH__` and `__MERGE_METHOD__` are filled in when rendering.
+const WORKFLOW_TEMPLATE: &str = r#"# Generated by `rung ci setup` - re-run it to regenerate.
+name: rung
+
+on:
+  pull_request:
+    types: [opened, reopened, synchronize, edited, closed]
+
+permissions:
+  contents: write
+  pull-requests: write
+
+jobs:
+  validate:
+    if: github.event.action != 'closed'
+    runs-on: ubuntu-latest
+    steps:
+      - uses: actions/checkout@v4
+        with:
+          fetch-depth: 0
+      - name: Install rung
+        run: |
+          curl -sSf https://rungstack.com/install.sh | INSTALL_DIR="$HOME/.local/bin" sh
+          echo "$HOME/.local/bin" >> "$GITHUB_PATH"
+      - name: Validate stack
+        run: rung ci validate
+        env:
+          GITHUB_TOKEN: ${{ github.token }}
+
+  retarget:
+    # Stacks land bottom-up into __DEFAULT_BRANCH__
+    if: >-
+      github.event.action == 'closed' &&
+      github.event.pull_request.merged &&
+      github.event.pull_request.base.ref == '__DEFAULT_BRANCH__'
+    runs-on: ubuntu-latest
+    env:
+      GH_TOKEN: ${{ github.token }}
+      MERGED_PR: ${{ github.event.pull_request.number }}
+      MERGED_BRANCH: ${{ github.event.pull_request.head.ref }}
+      MERGED_TIP: ${{ github.event.pull_request.head.sha }}
+      MERGE_METHOD: __MERGE_METHOD__
+    steps:
+      - uses: actions/checkout@v4
+        with:
+          fetch-depth: 0
+      - name: Retarget child PRs
+        run: |
+          git config user.name "github-actions[bot]"
+          git config user.email "41898282+github-actions[bot]@users.noreply.github.com"
+          gh pr list --base "$MERGED_BRANCH" --json number,headRefName \
+            --jq '.[] | "\(.number) \(.headRefName)"' |
+          while read -r pr branch; do
+            gh pr edit "$pr" --base __DEFAULT_BRANCH__
+            if [ "$MERGE_METHOD" != "merge" ]; then
+              # The merge rewrote the parent's commits - replay only the child's own
+              git checkout -B "$branch" "origin/$branch"
+              git rebase --onto origin/__DEFAULT_BRANCH__ "$MERGED_TIP"
+              git push --force-with-lease origin "$branch"
+            fi
+          done
+      - name: Refresh stack comments
+        run: |
+          gh pr list --state open --json number --jq '.[].number' |
+          while read -r pr; do
+            gh api "repos/$GITHUB_REPOSITORY/issues/$pr/comments" \
+              --jq ".[] | select(.body | contains(\"<!-- rung-stack -->\")) | select(.body | contains(\"* **#$MERGED_PR**\")) | .id" |
+            while read -r id; do
+              body=$(gh api "repos/$GITHUB_REPOSITORY/issues/comments/$id" --jq .body |
+                sed "s/^\* \*\*#$MERGED_PR\*\*/* ~~**#$MERGED_PR**~~ ✓/")
+              gh api -X PATCH "repos/$GITHUB_REPOSITORY/issues/comments/$id" -f body="$body" > /dev/null
+            done
+          done
+"#;
+
+/// CI subcommands.
+#[derive(Debug, Subcommand)]
+pub enum CiCommand {
+    /// Verify the stack invariants for a PR branch.
+    ///
+    /// Checks that the branch contains its base's tip, that no ancestor PR is
+    /// behind its own base, and that the rung stack comment is up to date.
+    /// Exits nonzero if any check fails.
+    Validate {
+        /// PR branch to validate (default: `$GITHUB_HEAD_REF`, then the current branch).
+        #[arg(long)]
+        branch: Option<String>,
+    },
+
+    /// Write a GitHub Actions workflow for stacked PRs.
+    ///
+    /// The workflow runs `rung ci validate` on every PR, and when a PR merges
+    /// into the default branch it retargets the child PRs, rebases them if
+    /// the merge rewrote history, and marks the PR merged in stack comments.
+    Setup {
+        /// Merge method the repository uses: squash (default), merge, or rebase.
+        #[arg(long, short, default_value = "squash")]
+        method: String,
+
+        /// Overwrite an existing workflow file.
+        #[arg(long)]
+        force: bool,
+
+        /// Print the workflow instead of writing it.
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+/// JSON output for `rung ci setup`.
+#[derive(Debug, Serialize)]
+struct SetupOutput {
+    path: String,
+    default_branch: String,
+    merge_method: String,
+    dry_run: bool,
+    /// The generated workflow, only with `--dry-run`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    workflow: Option<String>,
+}
+
+/// Run a CI subcommand.
+pub fn run(json: bool, command: &CiCommand) -> Result<()> {
+    match command {
+        CiCommand::Validate { branch } => validate(json, branch.as_deref()),
+        CiCommand::Setup {
+            method,
+            force,
+            dry_run,
+        } => setup(json, method, *force, *dry_run),
+    }
+}
+
+/// Write the workflow file, or print it with `dry_run`.
+fn setup(json: bool, method: &str, force: bool, dry_run: bool) -> Result<()> {
+    parse_merge_method(method)?;
+    let method = method.to_lowercase();
+
+    let ctx = CommandContext::open_uninitialized()?;
+    let default_branch = if ctx.state.is_initialized() {
+        ctx.state.default_branch()?
+    } else {
+        ctx.repo
+            .detect_default_branch()
+            .unwrap_or_else(|| "main".to_string())
+    };
+
+    let workflow = render_workflow(&default_branch, &method);
+    if dry_run && !json {
+        print!("{workflow}");
+        return Ok(());
+    }
+
+    if !dry_run {
+        let workdir = ctx.repo.workdir().context("No working directory")?;
+        let path = workdir.join(WORKFLOW_PATH);
+        if path.exists() && !force {
+            bail!("{WORKFLOW_PATH} already exists - use --force to overwrite it");
+        }
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(&path, &workflow)
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+    }
+
+    if json {
+        let output = SetupOutput {
+            path: WORKFLOW_PATH.to_string(),
+            default_branch,
+            merge_method: method,
+            dry_run,
+            workflow: dry_run.then_some(workflow),
+        };
+        println!("{}", serde_json::to_string_pretty(&output)?);
+        return Ok(());
+    }
+
+    output::success(&format!("Wrote {}", Path::new(WORKFLOW_PATH).display()));
+    output::detail(&format!(
+        "  Default branch: {default_branch}, merge method: {method}"
+    ));
+    output::detail("  Commit it to enable stack checks on pull requests");
+    Ok(())
+}
+
+/// Fill in the workflow template.
+fn render_workflow(default_branch: &str, method: &str) -> String {
+    WORKFLOW_TEMPLATE
+        .replace("__DEFAULT_BRANCH__", default_branch)
+        .replace("__MERGE_METHOD__", method)
+}
+
+/// Validate the PR stack for a branch.
+fn validate(json: bool, branch: Option<&str>) -> Result<()> {
+    // CI checkouts usually don't have `.git/rung`
+    let ctx = CommandContext::open_uninitialized()?;
+    let branch = resolve_branch(&ctx, branch)?;
+
+    let repo_id = ctx.remote_info()?.repo;
+    let service = CiService::new(&ctx.repo, ctx.forge()?, repo_id);
+
+    if !json {
+        output::info(&format!("Validating stack for {branch}..."));
+    }
+    let report = ctx.runtime()?.block_on(service.validate(&branch))?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        let chain: Vec<String> = report
+            .stack
+            .iter()
+            .map(|p| format!("{} (#{})", p.branch, p.pr))
+            .chain(std::iter::once(report.trunk.clone()))
+            .collect();
+        output::detail(&format!("  {}", chain.join(" → ")));
+        for finding in &report.findings {
+            output::error(&format!("{} {}", finding.check.bold(), finding.message));
+        }
+    }
+
+    if !report.is_valid() {
+        bail!("{} stack check(s) failed", report.findings.len());
+    }
+    if !json {
+        output::success("Stack is valid");
+    }
+    Ok(())
+}
+
+/// The PR branch: `--branch`, then `$GITHUB_HEAD_REF`, then the checkout.
+fn resolve_branch(ctx: &CommandContext, branch: Option<&str>) -> Result<String> {
+    if let Some(branch) = branch {
+        return Ok(branch.to_string());
+    }
+    if let Ok(head_ref) = std::env::var("GITHUB_HEAD_REF")
+        && !head_ref.is_empty()
+    {
+        return Ok(head_ref);
+    }
+    ctx.repo
+        .current_branch()
+        .context("Could not determine the PR branch (detached HEAD) - pass --branch")
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_workflow_fills_parameters() {
+        let workflow = render_workflow("trunk", "rebase");
+        assert!(!workflow.contains("__"));
+        assert!(workflow.contains("github.event.pull_request.base.ref == 'trunk'"));
+        assert!(workflow.contains("git rebase --onto origin/trunk"));
+        assert!(workflow.contains("MERGE_METHOD: rebase"));
+        assert!(workflow.contains("run: rung ci validate"));
+    }
+}
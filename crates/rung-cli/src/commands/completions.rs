@@ -1,16 +1,260 @@
 //! Shell completion generation.
+//!
+//! The generated scripts call back into rung on every completion, so
+//! arguments can complete from the stack (branch names, PR numbers) as well
+//! as from the command definitions.
 
+use std::fs;
 use std::io;
+use std::path::{Path, PathBuf};
 
-use clap::CommandFactory;
-use clap_complete::{Shell, generate};
+use anyhow::{Context, bail};
+use clap_complete::Shell;
+use clap_complete::engine::CompletionCandidate;
+use clap_complete::env::Shells;
+use rung_core::{Stack, State};
+use rung_git::Repository;
 
-use super::Cli;
+use crate::output;
 
-/// Generate shell completions and print to stdout.
-#[allow(clippy::unnecessary_wraps)]
-pub fn run(shell: Shell) -> anyhow::Result<()> {
-    let mut cmd = Cli::command();
-    generate(shell, &mut cmd, "rung", &mut io::stdout());
+/// Environment variable the completion scripts set when calling rung.
+pub const COMPLETE_VAR: &str = "COMPLETE";
+
+/// Where `--install` writes a shell's completion script.
+#[derive(Debug, PartialEq, Eq)]
+struct InstallTarget {
+    path: PathBuf,
+    /// The startup file to edit and the lines it needs to load the script,
+    /// for shells that don't pick it up on their own.
+    rc: Option<(String, Vec<String>)>,
+}
+
+/// Print the completion script for a shell to stdout, or with `install`
+/// write it where the shell looks for completions.
+pub fn run(shell: Shell, install: bool) -> anyhow::Result<()> {
+    let name = shell.to_string();
+    let shells = Shells::builtins();
+    let completer = shells
+        .completer(&name)
+        .with_context(|| format!("No completion support for {name}"))?;
+    if !install {
+        completer.write_registration(COMPLETE_VAR, "rung", "rung", "rung", &mut io::stdout())?;
+        return Ok(());
+    }
+
+    let home = std::env::var_os("HOME")
+        .or_else(|| std::env::var_os("USERPROFILE"))
+        .map(PathBuf::from)
+        .context("Could not find your home directory")?;
+    let target = install_target(
+        shell,
+        &home,
+        std::env::var_os("XDG_CONFIG_HOME").map(PathBuf::from),
+        std::env::var_os("XDG_DATA_HOME").map(PathBuf::from),
+    )?;
+
+    let mut script = Vec::new();
+    completer.write_registration(COMPLETE_VAR, "rung", "rung", "rung", &mut script)?;
+    if let Some(dir) = target.path.parent() {
+        fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    }
+    fs::write(&target.path, script)
+        .with_context(|| format!("Failed to write {}", target.path.display()))?;
+
+    output::success(&format!(
+        "Installed {name} completions to {}",
+        tilde(&target.path, &home)
+    ));
+    if let Some((rc_file, lines)) = target.rc {
+        output::info(&format!("Add to {rc_file} if it isn't there yet:"));
+        for line in lines {
+            output::detail(&format!("  {line}"));
+        }
+    }
+    output::detail("Restart your shell to load them");
     Ok(())
 }
+
+/// Where each shell looks for completion scripts, from its conventions:
+/// bash-completion's per-user directory, a directory added to zsh's
+/// `fpath`, and fish's and elvish's config directories.
+fn install_target(
+    shell: Shell,
+    home: &Path,
+    xdg_config: Option<PathBuf>,
+    xdg_data: Option<PathBuf>,
+) -> anyhow::Result<InstallTarget> {
+    let config = xdg_config.unwrap_or_else(|| home.join(".config"));
+    let data = xdg_data.unwrap_or_else(|| home.join(".local/share"));
+    let target = match shell {
+        Shell::Bash => {
+            let path = data.join("bash-completion/completions/rung");
+            // bash-completion loads it on demand; sourcing covers bash without it
+            let rc = (
+                "~/.bashrc".to_string(),
+                vec![format!("source {}", tilde(&path, home))],
+            );
+            InstallTarget { path, rc: Some(rc) }
+        }
+        Shell::Zsh => {
+            let dir = home.join(".zsh/completions");
+            let rc = (
+                "~/.zshrc".to_string(),
+                vec![
+                    format!("fpath=({} $fpath)", tilde(&dir, home)),
+                    "autoload -Uz compinit && compinit".to_string(),
+                ],
+            );
+            InstallTarget {
+                path: dir.join("_rung"),
+                rc: Some(rc),
+            }
+        }
+        Shell::Fish => InstallTarget {
+            path: config.join("fish/completions/rung.fish"),
+            rc: None,
+        },
+        Shell::Elvish => InstallTarget {
+            path: config.join("elvish/lib/rung.elv"),
+            rc: Some((
+                tilde(&config.join("elvish/rc.elv"), home),
+                vec!["use rung".to_string()],
+            )),
+        },
+        _ => bail!(
+            "--install doesn't support {shell}; add \
+             `rung completions {shell} | Out-String | Invoke-Expression` to your $PROFILE instead"
+        ),
+    };
+    Ok(target)
+}
+
+/// `path` with the home directory shortened to `~`, for display and rc
+/// snippets.
+fn tilde(path: &Path, home: &Path) -> String {
+    path.strip_prefix(home).map_or_else(
+        |_| path.display().to_string(),
+        |rest| format!("~/{}", rest.display()),
+    )
+}
+
+/// Branches in the stack, for arguments that only accept stack members.
+pub fn stack_branches() -> Vec<CompletionCandidate> {
+    load_stack()
+        .map(|(_, stack)| branch_candidates(&stack))
+        .unwrap_or_default()
+}
+
+/// Stack branches plus the base branch, for arguments naming a new parent.
+pub fn parent_branches() -> Vec<CompletionCandidate> {
+    let Some((state, stack)) = load_stack() else {
+        return vec![];
+    };
+    let mut candidates = branch_candidates(&stack);
+    if let Ok(base) = state.default_branch() {
+        candidates.insert(0, CompletionCandidate::new(base).help(Some("base".into())));
+    }
+    candidates
+}
+
+/// PR numbers linked to stack branches, from the cached stack state.
+pub fn pr_numbers() -> Vec<CompletionCandidate> {
+    load_stack()
+        .map(|(_, stack)| pr_candidates(&stack))
+        .unwrap_or_default()
+}
+
+/// Load the stack of the repository in the current directory, if any.
+fn load_stack() -> Option<(State, Stack)> {
+    let repo = Repository::open_current().ok()?;
+    let state = State::from_git_dir(repo.common_dir()).ok()?;
+    let stack = state.load_stack().ok()?;
+    Some((state, stack))
+}
+
+/// One candidate per stack branch, with its PR as the description.
+fn branch_candidates(stack: &Stack) -> Vec<CompletionCandidate> {
+    stack
+        .branches
+        .iter()
+        .map(|b| {
+            CompletionCandidate::new(b.name.as_str()).help(b.pr.map(|pr| format!("#{pr}").into()))
+        })
+        .collect()
+}
+
+/// One candidate per linked PR, with its branch as the description.
+fn pr_candidates(stack: &Stack) -> Vec<CompletionCandidate> {
+    stack
+        .branches
+        .iter()
+        .filter_map(|b| {
+            b.pr.map(|pr| {
+                CompletionCandidate::new(pr.to_string()).help(Some(b.name.to_string().into()))
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use rung_core::StackBranch;
+
+    #[test]
+    fn test_stack_candidates() {
+        let mut stack = Stack::default();
+        let mut a = StackBranch::try_new("feature/a", Some("main")).unwrap();
+        a.pr = Some(42);
+        stack.add_branch(a);
+        stack.add_branch(StackBranch::try_new("feature/b", Some("feature/a")).unwrap());
+
+        let values = |candidates: Vec<CompletionCandidate>| -> Vec<String> {
+            candidates
+                .iter()
+                .map(|c| c.get_value().to_string_lossy().into_owned())
+                .collect()
+        };
+        assert_eq!(
+            values(branch_candidates(&stack)),
+            vec!["feature/a", "feature/b"]
+        );
+
+        let prs = pr_candidates(&stack);
+        assert_eq!(values(pr_candidates(&stack)), vec!["42"]);
+        assert_eq!(prs[0].get_help().unwrap().to_string(), "feature/a");
+    }
+
+    #[test]
+    fn test_install_targets() {
+        let home = Path::new("/home/me");
+
+        let bash = install_target(Shell::Bash, home, None, None).unwrap();
+        assert_eq!(
+            bash.path,
+            home.join(".local/share/bash-completion/completions/rung")
+        );
+        assert_eq!(
+            bash.rc.unwrap().1,
+            vec!["source ~/.local/share/bash-completion/completions/rung"]
+        );
+
+        let zsh = install_target(Shell::Zsh, home, None, None).unwrap();
+        assert_eq!(zsh.path, home.join(".zsh/completions/_rung"));
+        assert_eq!(
+            zsh.rc.unwrap().1[0],
+            "fpath=(~/.zsh/completions $fpath)".to_string()
+        );
+
+        let fish =
+            install_target(Shell::Fish, home, Some(PathBuf::from("/xdg/config")), None).unwrap();
+        assert_eq!(
+            fish.path,
+            Path::new("/xdg/config/fish/completions/rung.fish")
+        );
+        assert!(fish.rc.is_none());
+
+        assert!(install_target(Shell::PowerShell, home, None, None).is_err());
+    }
+}
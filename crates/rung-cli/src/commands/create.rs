@@ -1,25 +1,191 @@
 //! `rung create` command - Create a new branch in the stack.
 
+use std::fmt::Write as _;
+use std::io::IsTerminal;
+
 use anyhow::{Context, Result, bail};
-use rung_core::{BranchName, State, slugify};
-use rung_git::Repository;
+use chrono::Local;
+use inquire::Text;
+use rung_core::{BranchName, State, expand_branch_template, slugify};
+use rung_git::{Oid, Repository};
+use serde::Serialize;
 
 use crate::commands::utils;
 use crate::output;
 use crate::services::CreateService;
+use crate::services::create::{CreateResult, InitialCommit};
+
+/// JSON output for create command.
+#[derive(Debug, Serialize)]
+struct CreateOutput {
+    branch: String,
+    parent: String,
+    dry_run: bool,
+    commit_created: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    commit_message: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stack_depth: Option<usize>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    children_moved: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    restacked: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    start: Option<String>,
+}
+
+/// Options for the create command.
+#[allow(clippy::struct_excessive_bools)]
+pub struct CreateOptions<'a> {
+    /// Output as JSON.
+    pub json: bool,
+    /// Branch name. Derived from the message when omitted.
+    pub name: Option<&'a str>,
+    /// Commit message for staged changes.
+    pub message: Option<&'a str>,
+    /// Stage tracked modifications before committing (`git commit -a`).
+    pub all: bool,
+    /// Commit even when there are no changes.
+    pub allow_empty: bool,
+    /// Move the current branch's children onto the new branch.
+    pub insert: bool,
+    /// Commit or ref to start the branch at instead of HEAD.
+    pub from: Option<&'a str>,
+    /// Show what would be done without making changes.
+    pub dry_run: bool,
+}
 
 /// Run the create command.
-pub fn run(name: Option<&str>, message: Option<&str>, dry_run: bool) -> Result<()> {
-    // Determine the branch name: explicit > derived from message > error
-    let name = match (name, message) {
-        (Some(n), _) => n.to_string(),
-        (None, Some(msg)) => slugify(msg),
-        (None, None) => bail!("Either a branch name or --message must be provided"),
+pub fn run(opts: &CreateOptions<'_>) -> Result<()> {
+    let CreateOptions {
+        json,
+        name,
+        message,
+        all,
+        allow_empty,
+        insert,
+        from,
+        dry_run,
+    } = *opts;
+
+    let utils::CommandContext { repo, state, .. } = utils::CommandContext::open()?;
+    let _lock = utils::lock_state(&repo, &state)?;
+
+    // Ensure on branch
+    utils::ensure_on_branch(&repo)?;
+
+    // Create service
+    let service = CreateService::new(&repo);
+
+    let (name, branch_name) = resolve_name(&service, &repo, &state, name, message, json)?;
+
+    // Get current branch (will be parent)
+    let parent_str = service.current_branch()?;
+    let parent = BranchName::new(&parent_str).context("Invalid parent branch name")?;
+
+    let start = match from {
+        Some(rev) => {
+            let start = repo.resolve_commit(rev)?;
+            service.validate_start(&parent_str, start, rev)?;
+            Some(start)
+        }
+        None => None,
     };
 
-    // Validate branch name
-    let branch_name = BranchName::new(&name).context("Invalid branch name")?;
+    let children: Vec<String> = if insert {
+        state
+            .load_stack()?
+            .children_of(parent.as_str())
+            .iter()
+            .map(|b| b.name.to_string())
+            .collect()
+    } else {
+        vec![]
+    };
+
+    if dry_run && json {
+        let would_commit = message.is_some()
+            && (allow_empty || service.has_staged_changes()? || (all && !service.is_clean()?));
+        return output_json(&CreateOutput {
+            branch: name,
+            parent: parent.to_string(),
+            dry_run: true,
+            commit_created: would_commit,
+            commit_message: message.filter(|_| would_commit).map(String::from),
+            stack_depth: None,
+            children_moved: children,
+            restacked: vec![],
+            start: start.map(|oid| oid.to_string()),
+        });
+    }
+
+    if dry_run {
+        print_dry_run(&service, &name, &parent, opts, &children, start)?;
+    } else {
+        // Like `git commit -a`: tracked modifications only, never untracked files
+        if all {
+            repo.stage_tracked()?;
+        }
+
+        // Staged work without -m gets a message from the editor, like `git commit`
+        let edited = if message.is_none() && !json {
+            edit_message(&repo, &name)?
+        } else {
+            None
+        };
+        let message = message.or(edited.as_deref());
+
+        // Create the branch
+        let commit = message.map(|message| InitialCommit {
+            message,
+            allow_empty,
+        });
+        let result = service.create_branch(&state, &branch_name, &parent, commit, insert, start)?;
+
+        // A fresh branch sits at the parent's tip, so the moved children only
+        // need rebasing when it starts with a commit
+        let restacked = if result.commit_created && !result.children_moved.is_empty() {
+            utils::restack_descendants(
+                &repo,
+                &state,
+                std::slice::from_ref(&result.branch_name),
+                json,
+            )?
+        } else {
+            vec![]
+        };
+
+        if json {
+            return output_json(&CreateOutput {
+                branch: result.branch_name,
+                parent: result.parent_name,
+                dry_run: false,
+                commit_created: result.commit_created,
+                commit_message: result.commit_message,
+                stack_depth: Some(result.stack_depth),
+                children_moved: result.children_moved,
+                restacked,
+                start: start.map(|oid| oid.to_string()),
+            });
+        }
+
+        print_result(&service, &result, message)?;
+    }
+
+    Ok(())
+}
 
+/// Determine and validate the branch name: explicit > derived from message.
+///
+/// A derived name that's already taken prompts for another one.
+fn resolve_name(
+    service: &CreateService<'_, Repository>,
+    repo: &Repository,
+    state: &State,
+    name: Option<&str>,
+    message: Option<&str>,
+    json: bool,
+) -> Result<(String, BranchName)> {
     // Validate message content (even when name is provided explicitly)
     if let Some(msg) = message
         && slugify(msg).is_empty()
@@ -27,76 +193,184 @@ pub fn run(name: Option<&str>, message: Option<&str>, dry_run: bool) -> Result<(
         bail!("Commit message must contain at least one alphanumeric character");
     }
 
-    // Open repository
-    let repo = Repository::open_current().context("Not inside a git repository")?;
+    let explicit = name.is_some();
+    let mut name = match (name, message) {
+        (Some(n), _) => n.to_string(),
+        (None, Some(msg)) => {
+            let user = repo
+                .signature()
+                .ok()
+                .and_then(|sig| sig.name().map(String::from))
+                .unwrap_or_default();
+            let template = state.load_config()?.create.branch_template;
+            expand_branch_template(&template, msg, &user, Local::now().date_naive())
+        }
+        (None, None) => bail!("Either a branch name or --message must be provided"),
+    };
 
-    // Get state manager
-    let workdir = repo.workdir().context("Cannot run in bare repository")?;
-    let state = State::new(workdir)?;
+    // Validate branch name
+    BranchName::new(&name).context("Invalid branch name")?;
 
-    // Ensure initialized
-    if !state.is_initialized() {
-        bail!("Rung not initialized - run `rung init` first");
+    if service.branch_exists(&name) {
+        if explicit || json || !std::io::stdin().is_terminal() {
+            bail!("Branch '{name}' already exists");
+        }
+        name = prompt_free_name(service, &name)?;
     }
 
-    // Ensure on branch
-    utils::ensure_on_branch(&repo)?;
+    let branch_name = BranchName::new(&name).context("Invalid branch name")?;
+    Ok((name, branch_name))
+}
 
-    // Create service
-    let service = CreateService::new(&repo);
+/// Ask for another name when the derived one is taken, suggesting the first
+/// free `-2`, `-3`, ... variant.
+fn prompt_free_name(service: &CreateService<'_, Repository>, taken: &str) -> Result<String> {
+    let suggestion = (2..1000)
+        .map(|n| format!("{taken}-{n}"))
+        .find(|candidate| !service.branch_exists(candidate))
+        .unwrap_or_default();
 
-    // Get current branch (will be parent)
-    let parent_str = service.current_branch()?;
-    let parent = BranchName::new(&parent_str).context("Invalid parent branch name")?;
+    output::warn(&format!("Branch '{taken}' already exists"));
+    loop {
+        let name = Text::new("Name for the new branch:")
+            .with_default(&suggestion)
+            .prompt()
+            .context("Branch name input cancelled")?;
+        if let Err(e) = BranchName::new(&name) {
+            output::warn(&e.to_string());
+        } else if service.branch_exists(&name) {
+            output::warn(&format!("Branch '{name}' already exists"));
+        } else {
+            return Ok(name);
+        }
+    }
+}
 
-    // Check if branch already exists
-    if service.branch_exists(&name) {
-        bail!("Branch '{name}' already exists");
+/// Ask for the first commit's message when there are staged changes.
+///
+/// Returns `None` (no commit) when nothing is staged or there is no
+/// terminal to run the editor in.
+fn edit_message(repo: &Repository, name: &str) -> Result<Option<String>> {
+    let staged = repo.staged_files()?;
+    if staged.is_empty() {
+        return Ok(None);
+    }
+    if !std::io::stdin().is_terminal() {
+        output::warn("Staged changes were left uncommitted - pass -m to commit them");
+        return Ok(None);
     }
 
-    if dry_run {
-        output::info(&format!(
-            "Would create branch '{name}' with parent '{parent}'"
-        ));
+    // Like `git commit`, start from `commit.template` when one is set
+    let commit_template = repo.commit_template().unwrap_or_default();
+    let mut template = format!(
+        "{commit_template}\n# Enter the message for the first commit on '{name}'. Lines starting\n\
+         # with '#' are ignored, and an empty message aborts the create.\n#\n\
+         # Changes to be committed:\n"
+    );
+    for file in &staged {
+        let _ = writeln!(template, "#\t{file}");
+    }
 
-        if let Some(msg) = message {
-            if service.is_clean()? {
-                output::warn("Working directory is clean - branch would be created without commit");
-            } else if service.has_staged_changes()? {
-                output::info(&format!("Would create commit with message: {msg}"));
-            } else {
-                output::warn(
-                    "No staged changes - branch would be created without commit (unstaged/untracked files exist)",
-                );
-            }
-        }
-    } else {
-        // Create the branch
-        let result = service.create_branch(&state, &branch_name, &parent, message)?;
-
-        // Report commit status
-        if message.is_some() {
-            if result.commit_created {
-                if let Some(msg) = &result.commit_message {
-                    output::info(&format!("Created commit: {msg}"));
-                }
-            } else if service.is_clean()? {
-                output::warn("Working directory is clean - branch created without commit");
-            } else {
-                output::warn("No staged changes to commit (untracked files may exist)");
+    let message = repo.edit_message(&template)?;
+    if message.is_empty() {
+        bail!("Aborting create due to empty commit message");
+    }
+    if !commit_template.trim().is_empty() && message == rung_git::cleanup_message(&commit_template)
+    {
+        bail!("Aborting create; the commit message template was not edited");
+    }
+    Ok(Some(message))
+}
+
+/// Report a created branch.
+fn print_result(
+    service: &CreateService<'_, Repository>,
+    result: &CreateResult,
+    message: Option<&str>,
+) -> Result<()> {
+    // Report commit status
+    if message.is_some() {
+        if result.commit_created {
+            if let Some(msg) = &result.commit_message {
+                output::info(&format!("Created commit: {msg}"));
             }
+        } else if service.is_clean()? {
+            output::warn("Working directory is clean - branch created without commit");
+        } else {
+            output::warn("No staged changes to commit (untracked files may exist)");
         }
+    }
+
+    output::success(&format!(
+        "Created branch '{}' with parent '{}'",
+        result.branch_name, result.parent_name
+    ));
 
-        output::success(&format!(
-            "Created branch '{}' with parent '{}'",
-            result.branch_name, result.parent_name
+    if !result.children_moved.is_empty() {
+        output::info(&format!(
+            "Moved {} onto '{}'",
+            result.children_moved.join(", "),
+            result.branch_name
         ));
+        output::detail("  Run `rung submit` to open its PR and retarget theirs");
+    }
+
+    // Show position in stack
+    if result.stack_depth > 1 {
+        output::info(&format!("Stack depth: {}", result.stack_depth));
+    }
+    Ok(())
+}
 
-        // Show position in stack
-        if result.stack_depth > 1 {
-            output::info(&format!("Stack depth: {}", result.stack_depth));
+/// Describe what `create --dry-run` would do.
+fn print_dry_run(
+    service: &CreateService<'_, Repository>,
+    name: &str,
+    parent: &BranchName,
+    opts: &CreateOptions<'_>,
+    children: &[String],
+    start: Option<Oid>,
+) -> Result<()> {
+    output::info(&format!(
+        "Would create branch '{name}' with parent '{parent}'"
+    ));
+    if let Some(start) = start {
+        output::detail(&format!(
+            "  Starting at {} ({start})",
+            opts.from.unwrap_or_default()
+        ));
+    }
+    for child in children {
+        output::detail(&format!("  → {child} would move onto '{name}'"));
+    }
+
+    let Some(msg) = opts.message else {
+        if service.has_staged_changes()? {
+            output::info("Would open your editor for the staged changes' commit message");
         }
+        return Ok(());
+    };
+
+    if opts.all && !service.is_clean()? {
+        output::info(&format!(
+            "Would stage modified tracked files and commit with message: {msg}"
+        ));
+    } else if opts.allow_empty && !service.has_staged_changes()? {
+        output::info(&format!("Would create an empty commit with message: {msg}"));
+    } else if service.is_clean()? {
+        output::warn("Working directory is clean - branch would be created without commit");
+    } else if service.has_staged_changes()? {
+        output::info(&format!("Would create commit with message: {msg}"));
+    } else {
+        output::warn(
+            "No staged changes - branch would be created without commit (unstaged/untracked files exist)",
+        );
     }
+    Ok(())
+}
 
+/// Output create result as JSON.
+fn output_json(output: &CreateOutput) -> Result<()> {
+    println!("{}", serde_json::to_string_pretty(output)?);
     Ok(())
 }
@@ -0,0 +1,72 @@
+//! `rung describe` command - Edit a branch's description.
+//!
+//! The description is git's own `branch.<name>.description`, the same one
+//! `git branch --edit-description` writes. `rung submit` uses it as the PR
+//! body in place of the tip commit's body.
+
+use std::io::IsTerminal;
+
+use anyhow::{Result, bail};
+use serde::Serialize;
+
+use crate::commands::utils;
+use crate::output;
+
+/// JSON output for describe command.
+#[derive(Debug, Serialize)]
+struct DescribeOutput {
+    branch: String,
+    description: Option<String>,
+}
+
+/// Run the describe command.
+pub fn run(json: bool, branch: Option<String>, message: Option<String>, clear: bool) -> Result<()> {
+    let utils::CommandContext { repo, .. } = utils::CommandContext::open_uninitialized()?;
+    let branch = if let Some(branch) = branch {
+        branch
+    } else {
+        utils::ensure_on_branch(&repo)?;
+        repo.current_branch()?
+    };
+    if !repo.branch_exists(&branch) {
+        bail!("Branch '{branch}' does not exist");
+    }
+
+    let description = if clear {
+        String::new()
+    } else if let Some(message) = message {
+        message
+    } else {
+        if !std::io::stdin().is_terminal() {
+            bail!("No terminal to run the editor in - pass -m to set the description");
+        }
+        let current = repo.branch_description(&branch).unwrap_or_default();
+        repo.edit_message(&format!(
+            "{current}\n# Describe '{branch}'. `rung submit` uses this as the PR body.\n\
+             # Lines starting with '#' are ignored; an empty description removes it.\n"
+        ))?
+    };
+    repo.set_branch_description(&branch, &description)?;
+    let description = repo.branch_description(&branch);
+
+    if json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&DescribeOutput {
+                branch,
+                description,
+            })?
+        );
+        return Ok(());
+    }
+
+    if description.is_some() {
+        output::success(&format!("Updated the description of '{branch}'"));
+        output::detail(
+            "  New PRs use it as their body; `rung submit --update-bodies` refreshes existing ones",
+        );
+    } else {
+        output::success(&format!("Removed the description of '{branch}'"));
+    }
+    Ok(())
+}
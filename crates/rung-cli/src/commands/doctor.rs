@@ -3,11 +3,16 @@
 use anyhow::Result;
 use colored::Colorize;
 use rung_core::State;
-use rung_git::Repository;
+use rung_core::config::FailOn;
+use rung_git::{Repository, Toolchain};
 use serde::Serialize;
 
+use crate::commands::utils::{self, ExitStatus};
 use crate::output;
-use crate::services::{CheckResult, DiagnosticReport, DoctorService, Issue, Severity};
+use crate::services::{
+    CheckResult, DiagnosticReport, DoctorService, Environment, Issue, OriginCredentials, Severity,
+    orphaned_remotes_issue,
+};
 
 /// JSON output for doctor command.
 #[derive(Debug, Serialize)]
@@ -16,28 +21,33 @@ struct DoctorOutput {
     errors: usize,
     warnings: usize,
     issues: Vec<Issue>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    deleted: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    environment: Option<Environment>,
 }
 
 /// Run the doctor command.
-pub fn run(json: bool) -> Result<()> {
+///
+/// Exits 0 when healthy, 1 on warnings and 2 on errors; `fail_on` (or the
+/// `doctor.fail_on` setting) raises the severity that counts as failure.
+pub fn run(json: bool, fix: bool, fail_on: Option<FailOn>) -> Result<()> {
     // Check if we're in a git repo
-    let Ok(repo) = Repository::open_current() else {
-        if json {
-            return output_json(&[Issue::error("Not inside a git repository")]);
+    let Ok(mut repo) = Repository::open_current() else {
+        if !json {
+            output::error("Not inside a git repository");
         }
-        output::error("Not inside a git repository");
-        return Ok(());
+        return fail_early(json, fail_on, Issue::error("Not inside a git repository"));
     };
 
-    let Some(workdir) = repo.workdir() else {
-        if json {
-            return output_json(&[Issue::error("Cannot run in bare repository")]);
+    if repo.workdir().is_none() {
+        if !json {
+            output::error("Cannot run in bare repository");
         }
-        output::error("Cannot run in bare repository");
-        return Ok(());
-    };
+        return fail_early(json, fail_on, Issue::error("Cannot run in bare repository"));
+    }
 
-    let state = State::new(workdir)?;
+    let state = State::from_git_dir(repo.common_dir())?;
 
     // Check initialization
     if !json {
@@ -47,78 +57,246 @@ pub fn run(json: bool) -> Result<()> {
     if !state.is_initialized() {
         let issue = Issue::error("Rung not initialized in this repository")
             .with_suggestion("Run `rung init` to initialize");
-        if json {
-            return output_json(&[issue]);
+        if !json {
+            print_issues(&[&issue]);
         }
-        print_issues(&[&issue]);
-        return Ok(());
+        return fail_early(json, fail_on, issue);
     }
     if !json {
         print_ok();
     }
 
+    let config = state.load_config().unwrap_or_default();
+    utils::configure_repo(&mut repo, &config);
+    let fail_on = fail_on.unwrap_or(config.doctor.fail_on);
+
     // Load stack and create service
     let stack = state.load_stack()?;
-    let service = DoctorService::new(&repo, &state, &stack);
+    let environment = Environment::new(&Toolchain::detect(), origin_credentials(&repo));
+    let service = DoctorService::new(&repo, &state, &stack)
+        .with_repo_override(config.github.repo)
+        .with_removed_branches(removed_branches(&state))
+        .with_environment(environment.clone());
+
+    let (report, deleted) = run_checks(&service, &repo, json, fix)?;
+    let all_issues = report.all_issues();
+
+    // Output
+    if json {
+        let owned_issues: Vec<Issue> = all_issues.into_iter().cloned().collect();
+        output_json(&owned_issues, &deleted, Some(&environment))?;
+        return exit_status(fail_on, report.error_count(), report.warning_count());
+    }
+
+    print_environment(&environment);
+    println!();
+    if !deleted.is_empty() {
+        output::success(&format!(
+            "Deleted {} orphaned remote branch(es): {}",
+            deleted.len(),
+            deleted.join(", ")
+        ));
+        println!();
+    }
+    print_issues(&all_issues);
+    print_summary(&all_issues);
+
+    exit_status(fail_on, report.error_count(), report.warning_count())
+}
+
+/// Run each check, printing progress unless `json` is set.
+fn run_checks(
+    service: &DoctorService<'_, Repository, State>,
+    repo: &Repository,
+    json: bool,
+    fix: bool,
+) -> Result<(DiagnosticReport, Vec<String>)> {
+    if !json {
+        print_check("Checking environment...");
+    }
+    let environment = service.check_environment();
+    if !json {
+        print_status(&environment);
+    }
 
-    // Run diagnostics with progress output
     if !json {
         print_check("Checking git state...");
     }
-    let git_result = service.check_git_state();
+    let git_state = service.check_git_state();
     if !json {
-        print_status(&git_result);
+        print_status(&git_state);
     }
 
     if !json {
         print_check("Checking stack integrity...");
     }
-    let stack_result = service.check_stack_integrity();
+    let stack_integrity = service.check_stack_integrity();
     if !json {
-        print_status(&stack_result);
+        print_status(&stack_integrity);
     }
 
     if !json {
         print_check("Checking sync state...");
     }
-    let sync_result = service.check_sync_state()?;
+    let sync_state = service.check_sync_state()?;
     if !json {
-        print_status(&sync_result);
+        print_status(&sync_state);
     }
 
     if !json {
         print_check("Checking GitHub...");
     }
     let rt = tokio::runtime::Runtime::new()?;
-    let github_result = rt.block_on(service.check_github());
+    let github = rt.block_on(service.check_github());
     if !json {
-        print_status(&github_result);
+        print_status(&github);
+    }
+
+    if !json {
+        print_check("Checking remote branches...");
+    }
+    let (orphaned_remotes, deleted) =
+        check_orphaned_remotes(repo, &service.orphaned_remote_branches(), fix);
+    if !json {
+        print_status(&orphaned_remotes);
     }
 
-    // Collect all issues using DiagnosticReport
     let report = DiagnosticReport {
-        git_state: git_result,
-        stack_integrity: stack_result,
-        sync_state: sync_result,
-        github: github_result,
+        environment,
+        git_state,
+        stack_integrity,
+        sync_state,
+        github,
+        orphaned_remotes,
     };
-    let all_issues = report.all_issues();
+    Ok((report, deleted))
+}
 
-    // Output
+/// Print the versions worth quoting in a bug report.
+fn print_environment(env: &Environment) {
+    let features = if env.libgit2_features.is_empty() {
+        String::new()
+    } else {
+        format!(" ({})", env.libgit2_features.join(", "))
+    };
+    let credentials = env
+        .origin_credentials
+        .as_ref()
+        .and_then(|c| {
+            c.source
+                .as_deref()
+                .map(|source| format!(" · origin via {source}"))
+        })
+        .unwrap_or_default();
+    println!(
+        "  {}",
+        output::glyphs(&format!(
+            "rung {} · git {} · libgit2 {}{features}{credentials}",
+            env.rung,
+            env.git.as_deref().unwrap_or("not found"),
+            env.libgit2
+        ))
+        .dimmed()
+    );
+}
+
+/// How git will authenticate to origin, for remotes reached over the network.
+fn origin_credentials(repo: &Repository) -> Option<OriginCredentials> {
+    let transport = remote_transport(&repo.origin_url().ok()?)?;
+    let source = if transport == "ssh" {
+        ssh_credentials()
+    } else {
+        repo.https_credentials()
+            .or_else(|| std::env::var_os("GIT_ASKPASS").map(|_| "GIT_ASKPASS".to_string()))
+    };
+    Some(OriginCredentials { transport, source })
+}
+
+/// `ssh` or `https` for a remote URL; `None` for local paths.
+fn remote_transport(url: &str) -> Option<&'static str> {
+    if url.starts_with("https://") || url.starts_with("http://") {
+        return Some("https");
+    }
+    if url.starts_with("ssh://") || url.starts_with("git+ssh://") {
+        return Some("ssh");
+    }
+    // scp-like syntax: `git@github.com:owner/repo.git`
+    let (host, _) = url.split_once(':')?;
+    (!url.contains("://") && !host.contains('/') && host.len() > 1).then_some("ssh")
+}
+
+/// A running ssh-agent, or the first default key file.
+fn ssh_credentials() -> Option<String> {
+    if std::env::var_os("SSH_AUTH_SOCK").is_some_and(|sock| !sock.is_empty()) {
+        return Some("ssh-agent".to_string());
+    }
+    let home = std::env::var_os("HOME")?;
+    ["id_ed25519", "id_ecdsa", "id_rsa"]
+        .iter()
+        .find(|key| std::path::Path::new(&home).join(".ssh").join(key).exists())
+        .map(|key| format!("~/.ssh/{key}"))
+}
+
+/// Finish with an error that stopped the diagnosis before any checks ran.
+fn fail_early(json: bool, fail_on: Option<FailOn>, issue: Issue) -> Result<()> {
     if json {
-        let owned_issues: Vec<Issue> = all_issues.into_iter().cloned().collect();
-        return output_json(&owned_issues);
+        output_json(&[issue], &[], None)?;
     }
+    exit_status(fail_on.unwrap_or_default(), 1, 0)
+}
 
-    println!();
-    print_issues(&all_issues);
-    print_summary(&all_issues);
+/// The exit code for the diagnosis under `fail_on`, as an error if nonzero.
+fn exit_status(fail_on: FailOn, errors: usize, warnings: usize) -> Result<()> {
+    match fail_on.exit_code(errors, warnings) {
+        0 => Ok(()),
+        code => Err(ExitStatus(code).into()),
+    }
+}
 
-    Ok(())
+/// Branches that successful rung commands deleted locally.
+fn removed_branches(state: &State) -> Vec<String> {
+    state
+        .load_audit()
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|entry| entry.success)
+        .flat_map(|entry| entry.refs)
+        .filter(|change| change.new.is_none())
+        .map(|change| change.branch)
+        .collect()
+}
+
+/// Report orphaned remote branches, or with `fix` delete them and report
+/// any that couldn't be deleted.
+fn check_orphaned_remotes(
+    repo: &Repository,
+    orphans: &[String],
+    fix: bool,
+) -> (CheckResult, Vec<String>) {
+    let mut result = CheckResult::default();
+    if !fix {
+        result.issues.extend(orphaned_remotes_issue(orphans));
+        return (result, vec![]);
+    }
+    let mut deleted = vec![];
+    for branch in orphans {
+        match repo.delete_remote_branch(branch) {
+            Ok(()) => deleted.push(branch.clone()),
+            Err(e) => result.issues.push(
+                Issue::error(format!("Failed to delete remote branch '{branch}': {e}"))
+                    .with_suggestion(format!("Run `git push origin --delete {branch}`")),
+            ),
+        }
+    }
+    (result, deleted)
 }
 
 /// Output issues as JSON.
-fn output_json(issues: &[Issue]) -> Result<()> {
+fn output_json(
+    issues: &[Issue],
+    deleted: &[String],
+    environment: Option<&Environment>,
+) -> Result<()> {
     let errors = issues
         .iter()
         .filter(|i| i.severity == Severity::Error)
@@ -133,6 +311,8 @@ fn output_json(issues: &[Issue]) -> Result<()> {
         errors,
         warnings,
         issues: issues.to_vec(),
+        deleted: deleted.to_vec(),
+        environment: environment.cloned(),
     };
 
     println!("{}", serde_json::to_string_pretty(&output)?);
@@ -144,16 +324,16 @@ fn print_check(message: &str) {
 }
 
 fn print_ok() {
-    println!(" {}", "✓".green());
+    println!(" {}", output::glyphs("✓").green());
 }
 
 fn print_status(result: &CheckResult) {
     if result.has_errors() {
-        println!(" {}", "✗".red());
+        println!(" {}", output::glyphs("✗").red());
     } else if result.has_warnings() {
-        println!(" {}", "⚠".yellow());
+        println!(" {}", output::glyphs("⚠").yellow());
     } else {
-        println!(" {}", "✓".green());
+        println!(" {}", output::glyphs("✓").green());
     }
 }
 
@@ -164,14 +344,14 @@ fn print_issues(issues: &[&Issue]) {
 
     for issue in issues {
         let icon = match issue.severity {
-            Severity::Error => "✗".red(),
-            Severity::Warning => "⚠".yellow(),
+            Severity::Error => output::glyphs("✗").red(),
+            Severity::Warning => output::glyphs("⚠").yellow(),
         };
 
         println!("  {icon} {}", issue.message);
 
         if let Some(suggestion) = &issue.suggestion {
-            println!("    {} {suggestion}", "→".dimmed());
+            println!("    {} {suggestion}", output::glyphs("→").dimmed());
         }
     }
     println!();
@@ -204,3 +384,27 @@ fn print_summary(issues: &[&Issue]) {
     }
     println!();
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_remote_transport() {
+        assert_eq!(
+            remote_transport("https://github.com/owner/repo.git"),
+            Some("https")
+        );
+        assert_eq!(
+            remote_transport("git@github.com:owner/repo.git"),
+            Some("ssh")
+        );
+        assert_eq!(
+            remote_transport("ssh://git@github.com/owner/repo.git"),
+            Some("ssh")
+        );
+        assert_eq!(remote_transport("/tmp/remote.git"), None);
+        assert_eq!(remote_transport("file:///tmp/remote.git"), None);
+        assert_eq!(remote_transport("C:/repos/remote.git"), None);
+    }
+}
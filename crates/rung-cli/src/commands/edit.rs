@@ -0,0 +1,179 @@
+//! `rung edit` and `rung done` commands - Edit a branch mid-stack.
+//!
+//! `rung edit` checks out a branch and remembers it. Once the edit is
+//! finished, with `rung done` or the next `rung nxt`/`prv`/`move`, the
+//! branch's descendants are restacked onto whatever it became.
+
+use anyhow::{Result, bail};
+use rung_core::{EditState, State};
+use rung_git::Repository;
+use serde::Serialize;
+
+use crate::commands::utils;
+use crate::output;
+
+/// JSON output for edit command.
+#[derive(Debug, Serialize)]
+struct EditOutput {
+    branch: String,
+    original_branch: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    descendants: Vec<String>,
+}
+
+/// JSON output for done command.
+#[derive(Debug, Serialize)]
+struct DoneOutput {
+    branch: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    restacked: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    switched_to: Option<String>,
+}
+
+/// Run the edit command.
+pub fn run(json: bool, branch: &str) -> Result<()> {
+    let utils::CommandContext { repo, state, .. } = utils::CommandContext::open()?;
+    let _lock = utils::lock_state(&repo, &state)?;
+    utils::ensure_on_branch(&repo)?;
+    if state.is_sync_in_progress() || state.is_restack_in_progress() {
+        bail!("A sync or restack is in progress. Complete or abort it first.");
+    }
+
+    let mut stack = state.load_stack()?;
+    if stack.find_branch(branch).is_none() {
+        bail!("Branch '{branch}' is not in the stack");
+    }
+    repo.protected_branches().check(branch, "edit")?;
+    stack.check_unlocked(branch, "edit")?;
+
+    let mut original_branch = repo.current_branch()?;
+    if state.is_edit_in_progress() {
+        let edit = state.load_edit_state()?;
+        if edit.branch == branch {
+            if !json {
+                output::info(&format!("Already editing '{branch}'"));
+            }
+            return Ok(());
+        }
+        // Starting another edit finishes this one; `done` still goes back
+        // to where the first edit started
+        finish(&repo, &state, &edit, json)?;
+        original_branch = edit.original_branch;
+        stack = state.load_stack()?;
+    }
+
+    if repo.current_branch()? != branch {
+        repo.require_clean()?;
+        repo.checkout(branch)?;
+    }
+
+    let tip = repo.branch_commit(branch)?;
+    utils::record_child_bases(&repo, &mut stack, branch, tip);
+    state.save_stack(&stack)?;
+    state.save_edit_state(&EditState::new(
+        branch.to_string(),
+        tip.to_string(),
+        original_branch.clone(),
+    ))?;
+
+    let descendants: Vec<String> = stack
+        .descendants(branch)
+        .iter()
+        .map(|b| b.name.to_string())
+        .collect();
+
+    if json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&EditOutput {
+                branch: branch.to_string(),
+                original_branch,
+                descendants,
+            })?
+        );
+        return Ok(());
+    }
+
+    output::success(&format!("Editing '{branch}'"));
+    if descendants.is_empty() {
+        output::detail("  Amend or add commits, then run `rung done`");
+    } else {
+        output::detail(&format!(
+            "  Amend or add commits, then run `rung done` to restack {}",
+            descendants.join(", ")
+        ));
+    }
+    Ok(())
+}
+
+/// Run the done command.
+pub fn run_done(json: bool) -> Result<()> {
+    let utils::CommandContext { repo, state, .. } = utils::CommandContext::open()?;
+    let _lock = utils::lock_state(&repo, &state)?;
+    if !state.is_edit_in_progress() {
+        bail!("No edit in progress - start one with `rung edit <branch>`");
+    }
+
+    let edit = state.load_edit_state()?;
+    let restacked = finish(&repo, &state, &edit, json)?;
+
+    let switched_to = (repo.current_branch().ok().as_deref() != Some(&edit.original_branch)
+        && repo.branch_exists(&edit.original_branch))
+    .then(|| edit.original_branch.clone());
+    if let Some(original) = &switched_to {
+        repo.checkout(original)?;
+    }
+
+    if json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&DoneOutput {
+                branch: edit.branch,
+                restacked,
+                switched_to,
+            })?
+        );
+        return Ok(());
+    }
+
+    output::success(&format!("Finished editing '{}'", edit.branch));
+    if let Some(original) = switched_to {
+        output::info(&format!("Switched back to '{original}'"));
+    }
+    Ok(())
+}
+
+/// Finish an edit left open, before navigating away from the branch.
+///
+/// Does nothing unless `rung edit` was run; takes the state lock only then.
+pub fn finish_pending(repo: &Repository, state: &State, json: bool) -> Result<()> {
+    if !state.is_edit_in_progress() {
+        return Ok(());
+    }
+    let _lock = utils::lock_state(repo, state)?;
+    let edit = state.load_edit_state()?;
+    if !json {
+        output::info(&format!("Finishing edit of '{}'", edit.branch));
+    }
+    finish(repo, state, &edit, json)?;
+    Ok(())
+}
+
+/// End the edit session and restack the edited branch's descendants.
+fn finish(repo: &Repository, state: &State, edit: &EditState, json: bool) -> Result<Vec<String>> {
+    if !repo.branch_exists(&edit.branch) {
+        state.clear_edit_state()?;
+        return Ok(vec![]);
+    }
+    if !repo.is_clean()? {
+        bail!(
+            "Working tree has uncommitted changes - commit them to '{}' or stash them first",
+            edit.branch
+        );
+    }
+
+    // Cleared first: a conflict hands over to `rung restack --continue`
+    state.clear_edit_state()?;
+    utils::restack_descendants(repo, state, std::slice::from_ref(&edit.branch), json)
+}
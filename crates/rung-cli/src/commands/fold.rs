@@ -1,14 +1,16 @@
 //! `rung fold` command - Combine adjacent branches into one.
 
+use std::io::IsTerminal;
+
 use anyhow::{Context, Result, bail};
-use inquire::{Confirm, MultiSelect};
+use inquire::Confirm;
 use rung_core::State;
-use rung_git::Repository;
 use serde::Serialize;
 
 use crate::commands::utils;
 use crate::output;
-use crate::services::fold::{FoldConfig, FoldService};
+use crate::services::fold::{FoldAnalysis, FoldConfig, FoldPreview, FoldService};
+use crate::services::split::CommitInfo;
 
 /// JSON output for fold operation.
 #[derive(Serialize)]
@@ -26,6 +28,10 @@ struct FoldDryRunOutput {
     dry_run: bool,
     target_branch: String,
     branches_to_fold: Vec<String>,
+    total_commits: usize,
+    prs_to_close: Vec<u64>,
+    children_to_reparent: Vec<String>,
+    commits_to_move: Vec<CommitInfo>,
 }
 
 /// JSON output for abort.
@@ -54,13 +60,8 @@ pub struct FoldOptions<'a> {
 
 /// Run the fold command.
 pub fn run(opts: &FoldOptions<'_>) -> Result<()> {
-    let repo = Repository::open_current().context("Not inside a git repository")?;
-    let workdir = repo.workdir().context("Cannot run in bare repository")?;
-    let state = State::new(workdir)?;
-
-    if !state.is_initialized() {
-        bail!("Rung not initialized - run `rung init` first");
-    }
+    let utils::CommandContext { repo, state, .. } = utils::CommandContext::open()?;
+    let _lock = utils::lock_state(&repo, &state)?;
 
     let service = FoldService::new(&repo);
 
@@ -75,7 +76,7 @@ pub fn run(opts: &FoldOptions<'_>) -> Result<()> {
     let current_branch = repo.current_branch()?;
     let analysis = service.analyze(&state, &current_branch)?;
 
-    let fold_config = resolve_fold_config(opts, &state, &analysis, &current_branch)?;
+    let fold_config = resolve_fold_config(opts, &service, &state, &analysis, &current_branch)?;
 
     let Some(config) = fold_config else {
         if opts.json {
@@ -92,11 +93,19 @@ pub fn run(opts: &FoldOptions<'_>) -> Result<()> {
         return Ok(());
     };
 
+    // Folding rewrites the target and deletes the folded branches
+    repo.protected_branches()
+        .check(&config.target_branch, "rewrite")?;
+    for branch in &config.branches_to_fold {
+        repo.protected_branches().check(branch, "delete")?;
+    }
+
+    let preview = service.preview(&state, &config)?;
     if opts.dry_run {
-        return handle_dry_run(&config, opts.json);
+        return handle_dry_run(&config, &preview, opts.json);
     }
 
-    if !opts.json && !confirm_fold(&config)? {
+    if !opts.json && !confirm_fold(&config, &preview)? {
         return Ok(());
     }
 
@@ -124,8 +133,9 @@ fn check_in_progress_operations(state: &State) -> Result<()> {
 /// Resolve fold configuration based on options.
 fn resolve_fold_config(
     opts: &FoldOptions<'_>,
+    service: &FoldService<'_>,
     state: &State,
-    analysis: &crate::services::fold::FoldAnalysis,
+    analysis: &FoldAnalysis,
     current_branch: &str,
 ) -> Result<Option<FoldConfig>> {
     // Note: --into-parent and --include-children are mutually exclusive (enforced by clap)
@@ -136,40 +146,64 @@ fn resolve_fold_config(
     } else if !opts.branches.is_empty() {
         create_specified_branches_config(state, &opts.branches)
     } else {
-        interactive_fold_selection(state, analysis, current_branch)
+        interactive_fold_selection(service, state, analysis, current_branch)
     }
 }
 
 /// Handle dry-run output.
-fn handle_dry_run(config: &FoldConfig, json: bool) -> Result<()> {
+fn handle_dry_run(config: &FoldConfig, preview: &FoldPreview, json: bool) -> Result<()> {
     if json {
         let output = FoldDryRunOutput {
             dry_run: true,
             target_branch: config.target_branch.clone(),
             branches_to_fold: config.branches_to_fold.clone(),
+            total_commits: preview.total_commits,
+            prs_to_close: preview.prs_to_close.clone(),
+            children_to_reparent: preview.children_to_reparent.clone(),
+            commits_to_move: preview.commits_to_move.clone(),
         };
         println!("{}", serde_json::to_string_pretty(&output)?);
     } else {
         output::info(&format!(
-            "Would fold {} branch(es) into '{}'",
+            "Would fold {} branch(es) into '{}' ({} commits)",
             config.branches_to_fold.len(),
-            config.target_branch
+            config.target_branch,
+            preview.total_commits
         ));
         output::detail("Branches to fold:");
         for branch in &config.branches_to_fold {
             output::detail(&format!("  {branch}"));
         }
+        print_preview(config, preview);
     }
     Ok(())
 }
 
+/// Print the commits that would move and what happens to PRs and children.
+fn print_preview(config: &FoldConfig, preview: &FoldPreview) {
+    output::detail(&format!("Commits moving into '{}':", config.target_branch));
+    for commit in &preview.commits_to_move {
+        output::detail(&format!("  {} {}", commit.short_sha, commit.summary));
+    }
+    for child in &preview.children_to_reparent {
+        output::detail(&format!(
+            "  → {child} would move onto '{}'",
+            config.target_branch
+        ));
+    }
+    for pr in &preview.prs_to_close {
+        output::detail(&format!("  → PR #{pr} would need to be closed"));
+    }
+}
+
 /// Confirm fold operation with user.
-fn confirm_fold(config: &FoldConfig) -> Result<bool> {
+fn confirm_fold(config: &FoldConfig, preview: &FoldPreview) -> Result<bool> {
     let branches_str = config.branches_to_fold.join(", ");
     output::info(&format!(
         "Will fold [{}] into '{}'",
         branches_str, config.target_branch
     ));
+    print_preview(config, preview);
 
     let confirmed = Confirm::new("Proceed with fold?")
         .with_default(true)
@@ -239,7 +273,7 @@ fn handle_abort(service: &FoldService<'_>, state: &State, json: bool) -> Result<
 /// Create config for folding current branch into its parent.
 fn create_into_parent_config(
     state: &State,
-    analysis: &crate::services::fold::FoldAnalysis,
+    analysis: &FoldAnalysis,
     current_branch: &str,
 ) -> Result<Option<FoldConfig>> {
     let Some(parent) = &analysis.parent_branch else {
@@ -273,7 +307,7 @@ fn create_into_parent_config(
 /// Create config for folding children into current branch.
 fn create_include_children_config(
     state: &State,
-    analysis: &crate::services::fold::FoldAnalysis,
+    analysis: &FoldAnalysis,
     current_branch: &str,
 ) -> Result<Option<FoldConfig>> {
     if analysis.children.is_empty() {
@@ -301,63 +335,8 @@ fn create_specified_branches_config(
     state: &State,
     branches: &[&str],
 ) -> Result<Option<FoldConfig>> {
-    if branches.len() < 2 {
-        bail!("At least two branches must be specified for folding");
-    }
-
     let stack = state.load_stack()?;
-
-    // Verify all branches exist and are adjacent (form a parent-child chain)
-    let mut ordered_branches = Vec::new();
-
-    // Find the root of the chain (branch whose parent is not in the list)
-    let mut root = None;
-    for &branch in branches {
-        let stack_branch = stack
-            .find_branch(branch)
-            .ok_or_else(|| anyhow::anyhow!("Branch '{branch}' not found in stack"))?;
-
-        let parent_in_list = stack_branch
-            .parent
-            .as_ref()
-            .is_some_and(|p| branches.contains(&p.as_str()));
-
-        if !parent_in_list {
-            if root.is_some() {
-                bail!("Branches must form a single parent-child chain");
-            }
-            root = Some(branch);
-        }
-    }
-
-    let root = root.ok_or_else(|| anyhow::anyhow!("Could not determine root of branch chain"))?;
-
-    // Build the ordered chain
-    let mut current = root.to_string();
-    while ordered_branches.len() < branches.len() {
-        ordered_branches.push(current.clone());
-        // Find child in the list
-        let children: Vec<String> = stack
-            .children_of(&current)
-            .iter()
-            .filter(|c| branches.contains(&c.name.as_str()))
-            .map(|c| c.name.to_string())
-            .collect();
-
-        if children.len() > 1 {
-            bail!("Branches must form a linear chain (no branching)");
-        }
-
-        if let Some(child) = children.first() {
-            current.clone_from(child);
-        } else {
-            break;
-        }
-    }
-
-    if ordered_branches.len() != branches.len() {
-        bail!("Branches must form a connected chain");
-    }
+    let mut ordered_branches = FoldService::order_chain(&stack, branches)?;
 
     // The first branch is the target, rest are folded
     let target_branch = ordered_branches.remove(0);
@@ -381,122 +360,91 @@ fn create_specified_branches_config(
     }))
 }
 
-/// Fold operation choices for interactive selection.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-enum FoldChoice {
-    IntoParent,
-    Children,
-    Cancel,
-}
-
 /// Interactive fold selection.
+///
+/// Lists every fold available from the current branch - into its parent, or
+/// each run of its children - with the commits that would move and the PRs
+/// that would close.
 fn interactive_fold_selection(
+    service: &FoldService<'_>,
     state: &State,
-    analysis: &crate::services::fold::FoldAnalysis,
+    analysis: &FoldAnalysis,
     current_branch: &str,
 ) -> Result<Option<FoldConfig>> {
-    // Build options with their corresponding choices
-    let mut options: Vec<(String, FoldChoice)> = Vec::new();
-
-    // Option to fold into parent
-    if analysis.parent_branch.is_some() {
-        options.push((
-            format!("Fold into parent (merge {current_branch} into parent)"),
-            FoldChoice::IntoParent,
-        ));
+    if !std::io::stdin().is_terminal() {
+        bail!(
+            "Specify what to fold: branch names, --into-parent, or --include-children \
+             (interactive selection needs a terminal)"
+        );
     }
 
-    // Option to fold children
-    if !analysis.children.is_empty() {
-        let child_names: Vec<_> = analysis.children.iter().map(|c| c.name.as_str()).collect();
-        options.push((
-            format!(
-                "Fold children ({}) into {}",
-                child_names.join(", "),
-                current_branch
-            ),
-            FoldChoice::Children,
-        ));
+    let stack = state.load_stack()?;
+    let mut candidates = Vec::new();
+
+    if let Some(parent) = &analysis.parent_branch
+        && stack.find_branch(parent).is_some()
+        && let Some(config) = create_into_parent_config(state, analysis, current_branch)?
+    {
+        candidates.push(config);
     }
 
-    if options.is_empty() {
-        bail!("No branches available to fold (need parent or children in stack)");
+    // Children can only be folded as a run starting at the first child
+    let default_branch = state
+        .default_branch()
+        .unwrap_or_else(|_| "main".to_string());
+    let new_parent = analysis.parent_branch.clone().unwrap_or(default_branch);
+    for end in 1..=analysis.children.len() {
+        candidates.push(FoldConfig {
+            target_branch: current_branch.to_string(),
+            branches_to_fold: analysis.children[..end]
+                .iter()
+                .map(|c| c.name.clone())
+                .collect(),
+            new_parent: new_parent.clone(),
+        });
     }
 
-    // Add cancel option
-    options.push(("Cancel".to_string(), FoldChoice::Cancel));
+    if candidates.is_empty() {
+        bail!(
+            "Nothing to fold: '{current_branch}' has no parent or children in the stack.\n\
+             Fold combines a branch with its stacked parent or children."
+        );
+    }
 
-    output::info("Select fold operation:");
+    let mut labels = Vec::with_capacity(candidates.len() + 1);
+    for config in &candidates {
+        let preview = service.preview(state, config)?;
+        labels.push(candidate_label(config, &preview));
+    }
+    labels.push("Cancel".to_string());
 
-    let display_options: Vec<String> = options.iter().map(|(label, _)| label.clone()).collect();
-    let selection = inquire::Select::new("Fold operation:", display_options)
+    let selection = inquire::Select::new("Fold operation:", labels.clone())
+        .with_page_size(10)
         .prompt()
         .context("Selection cancelled")?;
 
-    // Find the choice corresponding to the selection
-    let selected_choice = options
+    Ok(labels
         .iter()
-        .find(|(label, _)| label == &selection)
-        .map_or(FoldChoice::Cancel, |(_, choice)| *choice);
-
-    match selected_choice {
-        FoldChoice::Cancel => Ok(None),
-        FoldChoice::IntoParent => create_into_parent_config(state, analysis, current_branch),
-        FoldChoice::Children => {
-            // If multiple children, let user select which ones
-            if analysis.children.len() > 1 {
-                let child_options: Vec<String> = analysis
-                    .children
-                    .iter()
-                    .map(|c| format!("{} ({} commits)", c.name, c.commit_count))
-                    .collect();
-
-                let selections =
-                    MultiSelect::new("Select children to fold:", child_options.clone())
-                        .with_all_selected_by_default()
-                        .prompt()
-                        .context("Selection cancelled")?;
-
-                if selections.is_empty() {
-                    return Ok(None);
-                }
+        .position(|label| label == &selection)
+        .and_then(|idx| candidates.get(idx).cloned()))
+}
 
-                // Get selected branch names
-                let mut selected_indices: Vec<usize> = selections
-                    .iter()
-                    .filter_map(|s| child_options.iter().position(|o| o == s))
-                    .collect();
-                selected_indices.sort_unstable();
-
-                // Validate contiguous selection - children must form a continuous chain
-                // to avoid orphaning intermediate branches
-                for window in selected_indices.windows(2) {
-                    if window[1] != window[0] + 1 {
-                        bail!(
-                            "Selected children must be contiguous. Cannot skip '{}' between selected branches.",
-                            analysis.children[window[0] + 1].name
-                        );
-                    }
-                }
-
-                let branches_to_fold: Vec<String> = selected_indices
-                    .iter()
-                    .map(|&i| analysis.children[i].name.clone())
-                    .collect();
-
-                let default_branch = state
-                    .default_branch()
-                    .unwrap_or_else(|_| "main".to_string());
-                let new_parent = analysis.parent_branch.clone().unwrap_or(default_branch);
-
-                Ok(Some(FoldConfig {
-                    target_branch: current_branch.to_string(),
-                    branches_to_fold,
-                    new_parent,
-                }))
-            } else {
-                create_include_children_config(state, analysis, current_branch)
-            }
-        }
-    }
+/// One-line summary of a fold for the selection list.
+fn candidate_label(config: &FoldConfig, preview: &FoldPreview) -> String {
+    let closes = if preview.prs_to_close.is_empty() {
+        String::new()
+    } else {
+        let prs: Vec<String> = preview
+            .prs_to_close
+            .iter()
+            .map(|pr| format!("#{pr}"))
+            .collect();
+        format!(", closes {}", prs.join(", "))
+    };
+    format!(
+        "Fold {} into '{}' ({} commit(s) move{closes})",
+        config.branches_to_fold.join(", "),
+        config.target_branch,
+        preview.commits_to_move.len()
+    )
 }
@@ -0,0 +1,108 @@
+//! `rung gc` command - Clean up rung's state directory.
+
+use anyhow::Result;
+use chrono::Utc;
+use rung_core::{GcOptions, GcReport};
+use serde::Serialize;
+
+use crate::commands::utils::{self, CommandContext};
+use crate::output;
+
+/// JSON output for gc command.
+#[derive(Debug, Serialize)]
+struct GcOutput {
+    dry_run: bool,
+    #[serde(flatten)]
+    report: GcReport,
+}
+
+/// Run the gc command.
+pub fn run(json: bool, dry_run: bool) -> Result<()> {
+    let CommandContext { repo, state, .. } = CommandContext::open()?;
+    let _lock = utils::lock_state(&repo, &state)?;
+
+    let config = state.load_config()?;
+    let options = GcOptions {
+        backup_retention: config.general.backup_retention,
+        audit_max_age: config.general.audit_max_age(),
+        remotes: repo.remote_names()?,
+        dry_run,
+    };
+    let report = state.gc(&options, Utc::now())?;
+
+    if json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&GcOutput { dry_run, report })?
+        );
+        return Ok(());
+    }
+
+    if report.is_empty() {
+        output::info("Nothing to clean up");
+        return Ok(());
+    }
+    let removed = describe(&report);
+    if dry_run {
+        output::info(&format!("Would remove {removed}"));
+        output::detail(&format!("  Would reclaim {}", format_bytes(report.bytes)));
+    } else {
+        output::success(&format!("Removed {removed}"));
+        output::detail(&format!("  Reclaimed {}", format_bytes(report.bytes)));
+    }
+    Ok(())
+}
+
+/// What was removed, e.g. `3 backups, 120 audit entries`.
+fn describe(report: &GcReport) -> String {
+    let plural = |count: usize, one: &str, many: &str| {
+        format!("{count} {}", if count == 1 { one } else { many })
+    };
+    [
+        (report.backups, "old backup", "old backups"),
+        (report.audit_entries, "audit entry", "audit entries"),
+        (
+            report.cache_entries,
+            "stale cache entry",
+            "stale cache entries",
+        ),
+        (report.leftovers, "leftover file", "leftover files"),
+    ]
+    .into_iter()
+    .filter(|(count, ..)| *count > 0)
+    .map(|(count, one, many)| plural(count, one, many))
+    .collect::<Vec<_>>()
+    .join(", ")
+}
+
+/// Human-readable size, e.g. `4.2 KB`.
+#[allow(clippy::cast_precision_loss)] // Only shown to one decimal
+fn format_bytes(bytes: u64) -> String {
+    match bytes {
+        0..1_000 => format!("{bytes} B"),
+        1_000..1_000_000 => format!("{:.1} KB", bytes as f64 / 1e3),
+        _ => format!("{:.1} MB", bytes as f64 / 1e6),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_describe_and_format_bytes() {
+        let report = GcReport {
+            backups: 3,
+            audit_entries: 1,
+            leftovers: 2,
+            ..GcReport::default()
+        };
+        assert_eq!(
+            describe(&report),
+            "3 old backups, 1 audit entry, 2 leftover files"
+        );
+        assert_eq!(format_bytes(512), "512 B");
+        assert_eq!(format_bytes(4_200), "4.2 KB");
+        assert_eq!(format_bytes(3_500_000), "3.5 MB");
+    }
+}
@@ -3,22 +3,42 @@
 use anyhow::{Context, Result};
 use rung_core::{Config, State};
 use rung_git::Repository;
+use serde::Serialize;
 
+use crate::commands::utils;
 use crate::output;
 
+/// JSON output for init command.
+#[derive(Debug, Serialize)]
+struct InitOutput {
+    already_initialized: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    default_branch: Option<String>,
+    rung_dir: String,
+}
+
 /// Run the init command.
-pub fn run() -> Result<()> {
+pub fn run(json: bool) -> Result<()> {
     // Open repository
-    let repo = Repository::open_current().context("Not inside a git repository")?;
+    let mut repo = Repository::open_current().context("Not inside a git repository")?;
 
-    // Get state manager
-    let workdir = repo
-        .workdir()
-        .context("Cannot initialize in bare repository")?;
-    let state = State::new(workdir)?;
+    // Get state manager. State lives in the common git dir so linked
+    // worktrees (including those of a bare repository) share it.
+    let state = State::from_git_dir(repo.common_dir())?;
+    // Before init there's no config file, but `RUNG_*` variables apply
+    if let Ok(config) = state.load_config() {
+        utils::configure_repo(&mut repo, &config);
+    }
 
     // Check if already initialized
     if state.is_initialized() {
+        if json {
+            return output_json(&InitOutput {
+                already_initialized: true,
+                default_branch: state.default_branch().ok(),
+                rung_dir: state.rung_dir().display().to_string(),
+            });
+        }
         output::warn("Rung is already initialized in this repository");
         return Ok(());
     }
@@ -27,10 +47,22 @@ pub fn run() -> Result<()> {
     state.init()?;
 
     // Detect and save default branch
-    if let Some(branch) = repo.detect_default_branch() {
+    let detected = repo.detect_default_branch();
+    if let Some(branch) = &detected {
         let mut config = Config::default();
         config.general.default_branch = Some(branch.clone());
         state.save_config(&config)?;
+    }
+
+    if json {
+        return output_json(&InitOutput {
+            already_initialized: false,
+            default_branch: Some(state.default_branch()?),
+            rung_dir: state.rung_dir().display().to_string(),
+        });
+    }
+
+    if let Some(branch) = &detected {
         output::info(&format!("Detected default branch: {branch}"));
     } else {
         output::info("Could not detect default branch, using \"main\" as fallback");
@@ -41,3 +73,9 @@ pub fn run() -> Result<()> {
 
     Ok(())
 }
+
+/// Output init result as JSON.
+fn output_json(output: &InitOutput) -> Result<()> {
+    println!("{}", serde_json::to_string_pretty(output)?);
+    Ok(())
+}
@@ -0,0 +1,60 @@
+//! `rung lock` / `rung unlock` commands - Keep a branch from being rewritten.
+//!
+//! A locked branch is left alone by sync, restack, move, edit, and absorb,
+//! e.g. while a reviewer is partway through its PR. The flag lives in
+//! `stack.json`.
+
+use anyhow::{Result, bail};
+use serde::Serialize;
+
+use crate::commands::utils;
+use crate::output;
+
+/// JSON output for lock and unlock.
+#[derive(Debug, Serialize)]
+struct LockOutput {
+    branch: String,
+    locked: bool,
+}
+
+/// Run the lock command, or unlock with `locked` false.
+pub fn run(json: bool, branch: Option<&str>, locked: bool) -> Result<()> {
+    let utils::CommandContext { repo, state, .. } = utils::CommandContext::open()?;
+    let _lock = utils::lock_state(&repo, &state)?;
+
+    let branch = if let Some(branch) = branch {
+        branch.to_string()
+    } else {
+        utils::ensure_on_branch(&repo)?;
+        repo.current_branch()?
+    };
+
+    let mut stack = state.load_stack()?;
+    let Some(tracked) = stack.find_branch_mut(&branch) else {
+        bail!("Branch '{branch}' is not in the stack");
+    };
+    let changed = tracked.locked != locked;
+    tracked.locked = locked;
+    if changed {
+        state.save_stack(&stack)?;
+    }
+
+    if json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&LockOutput { branch, locked })?
+        );
+        return Ok(());
+    }
+
+    match (locked, changed) {
+        (true, true) => {
+            output::success(&format!("Locked '{branch}'"));
+            output::detail("  sync, restack, and absorb won't rewrite it until `rung unlock`");
+        }
+        (true, false) => output::info(&format!("'{branch}' is already locked")),
+        (false, true) => output::success(&format!("Unlocked '{branch}'")),
+        (false, false) => output::info(&format!("'{branch}' isn't locked")),
+    }
+    Ok(())
+}
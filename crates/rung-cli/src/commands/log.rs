@@ -1,25 +1,56 @@
 //! `rung log` command - show commits between the base branch and HEAD.
 
-use anyhow::{Result, bail};
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::io::{IsTerminal, Write as _};
+use std::process::{Command, Stdio};
 
-use super::utils::open_repo_and_state;
+use anyhow::{Context, Result, bail};
+use chrono::Utc;
+use colored::Colorize;
+use rung_core::{CachedChecks, PrCache};
+use rung_forge::{CheckRun, CheckStatus, ForgeApi, PullRequestState, RemoteInfo};
+use rung_git::{DiffFormat, Repository};
+use serde::Serialize;
+
+use super::utils::{self, CommandContext};
 use crate::output;
-use crate::services::{CommitInfo, LogResult, LogService};
+use crate::services::{CommitInfo, LogPrInfo, LogResult, LogService};
 
 /// Run the log command.
-pub fn run(json: bool) -> Result<()> {
-    let (repo, state) = open_repo_and_state()?;
+pub fn run(json: bool, diff: Option<DiffFormat>, fetch: bool) -> Result<()> {
+    let ctx = CommandContext::open()?;
 
     // Create service
-    let service = LogService::new(&repo, &state);
+    let service = LogService::new(&ctx.repo, &ctx.state);
 
     let stack = service.load_stack()?;
     if stack.is_empty() {
         bail!("No branches in stack. Use `rung create <name>` to add one.");
     }
 
-    let current = service.current_branch()?;
-    let log_result = service.get_branch_log(&current)?;
+    let current = utils::resolve_current_branch(&ctx.repo, &stack)?;
+    let mut log_result = service.get_branch_log(&current, diff)?;
+
+    if let Some(number) = log_result.pr.as_ref().map(|pr| pr.number) {
+        let remote = ctx.remote_info().ok();
+        if fetch
+            && !utils::is_offline()
+            && let Err(e) = fetch_pr(&ctx, remote.as_ref(), number, &current, json)
+        {
+            if utils::is_network_error(&e) {
+                utils::go_offline(json);
+            } else if json {
+                eprintln!("Warning: Could not fetch PR #{number}: {e}");
+            } else {
+                output::warn(&format!("Could not fetch PR #{number}: {e}"));
+            }
+        }
+        service.annotate_pr(&mut log_result)?;
+        if let (Some(pr), Some(remote)) = (log_result.pr.as_mut(), remote) {
+            pr.url = Some(remote.kind.pr_url(&remote.repo, number));
+        }
+    }
 
     if log_result.commits.is_empty() && !json {
         output::warn("Current branch has no commits");
@@ -28,6 +59,13 @@ pub fn run(json: bool) -> Result<()> {
 
     if json {
         print_json(&log_result)?;
+        return Ok(());
+    }
+    if let Some(pr) = &log_result.pr {
+        print_pr(pr);
+    }
+    if diff.is_some() {
+        page(&ctx.repo, &render_diffs(&log_result.commits))?;
     } else {
         print_commits(&log_result.commits);
     }
@@ -35,6 +73,158 @@ pub fn run(json: bool) -> Result<()> {
     Ok(())
 }
 
+/// A branch's log in `rung log --stack --json`.
+#[derive(Serialize)]
+struct StackLogEntry {
+    #[serde(flatten)]
+    log: LogResult,
+    /// Children in the stack, in stack order.
+    children: Vec<String>,
+}
+
+/// Run `rung log --stack`: every branch's commits, drawn as the stack tree.
+pub fn run_stack(json: bool) -> Result<()> {
+    let ctx = CommandContext::open()?;
+    let service = LogService::new(&ctx.repo, &ctx.state);
+
+    let stack = service.load_stack()?;
+    if stack.is_empty() {
+        bail!("No branches in stack. Use `rung create <name>` to add one.");
+    }
+    // Listing the whole stack works from trunk or a detached HEAD too
+    let current = ctx.repo.current_branch().ok();
+
+    let mut entries = Vec::with_capacity(stack.len());
+    for branch in &stack.branches {
+        if !ctx.repo.branch_exists(&branch.name) {
+            continue;
+        }
+        let mut log = service.get_branch_log(&branch.name, None)?;
+        service.annotate_pr(&mut log)?;
+        let children = stack
+            .children_of(&branch.name)
+            .iter()
+            .map(|child| child.name.to_string())
+            .collect();
+        entries.push(StackLogEntry { log, children });
+    }
+
+    if json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&serde_json::json!({ "branches": entries }))?
+        );
+        return Ok(());
+    }
+
+    let nodes: Vec<(&str, Option<&str>)> = entries
+        .iter()
+        .map(|e| (e.log.branch.as_str(), Some(e.log.parent.as_str())))
+        .collect();
+    for row in output::tree_rows(&nodes) {
+        let log = &entries[row.index].log;
+        let name = output::branch_name(&log.branch, current.as_deref() == Some(&log.branch));
+        let pr = output::pr_ref(log.pr.as_ref().map(|pr| pr.number), None);
+        println!("  {}{name} {pr}", output::glyphs(&row.prefix).dimmed());
+        for commit in &log.commits {
+            println!(
+                "  {}  {} {}",
+                output::glyphs(&row.body).dimmed(),
+                commit.hash.yellow(),
+                commit.message.lines().next().unwrap_or_default()
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Fetch the PR's state and the CI result for the branch tip into the PR cache.
+fn fetch_pr(
+    ctx: &CommandContext,
+    remote: Option<&RemoteInfo>,
+    number: u64,
+    branch: &str,
+    json: bool,
+) -> Result<()> {
+    let remote = remote.context("Could not parse forge remote URL")?;
+    let client = ctx.forge()?;
+    let sha = ctx.repo.branch_commit(branch)?.to_string();
+
+    if !json {
+        output::info(&format!("Fetching PR #{number}..."));
+    }
+    let runtime = ctx.runtime()?;
+    let pr = runtime.block_on(client.get_pr(&remote.repo, number))?;
+    let runs = runtime.block_on(client.get_check_runs(&remote.repo, &sha))?;
+
+    let state = match (pr.state, pr.draft) {
+        (PullRequestState::Merged, _) => "merged",
+        (PullRequestState::Closed, _) => "closed",
+        (_, true) => "draft",
+        _ => "open",
+    };
+    let mut cache = ctx
+        .state
+        .load_pr_cache()
+        .ok()
+        .flatten()
+        .unwrap_or_else(|| PrCache {
+            fetched_at: Utc::now(),
+            prs: BTreeMap::new(),
+            checks: BTreeMap::new(),
+        });
+    cache.prs.insert(number, state.to_string());
+    cache.checks.insert(
+        number,
+        CachedChecks {
+            sha,
+            conclusion: combined_conclusion(&runs).to_string(),
+        },
+    );
+    ctx.state.save_pr_cache(&cache)?;
+    Ok(())
+}
+
+/// Fold check runs into one result: any failure fails, anything unfinished
+/// is pending.
+fn combined_conclusion(runs: &[CheckRun]) -> &'static str {
+    if runs.is_empty() {
+        "none"
+    } else if runs
+        .iter()
+        .any(|r| matches!(r.status, CheckStatus::Failure | CheckStatus::Cancelled))
+    {
+        "failure"
+    } else if runs.iter().any(|r| !r.status.is_success()) {
+        "pending"
+    } else {
+        "success"
+    }
+}
+
+/// Print the PR line above the commits.
+fn print_pr(pr: &LogPrInfo) {
+    let state = pr
+        .state
+        .as_deref()
+        .map(|state| format!(" {state}"))
+        .unwrap_or_default();
+    let checks = match pr.checks.as_deref() {
+        Some("success") => format!(" · {}", "✓ checks passed".green()),
+        Some("failure") => format!(" · {}", "✗ checks failed".red()),
+        Some("pending") => format!(" · {}", "● checks running".yellow()),
+        Some(_) => " · no checks".dimmed().to_string(),
+        None => String::new(),
+    };
+    output::info(&format!(
+        "{}{state}{checks}",
+        format!("#{}", pr.number).cyan()
+    ));
+    if let Some(url) = &pr.url {
+        output::detail(&format!("  {}", url.dimmed()));
+    }
+}
+
 /// Print commits in human-readable format.
 fn print_commits(commits: &[CommitInfo]) {
     for commit in commits {
@@ -46,9 +236,101 @@ fn print_commits(commits: &[CommitInfo]) {
     }
 }
 
+/// Render each commit's header followed by its diff, colored like `git log -p`.
+fn render_diffs(commits: &[CommitInfo]) -> String {
+    let mut text = String::new();
+    for commit in commits {
+        let _ = writeln!(
+            text,
+            "{} {} {}",
+            commit.hash.yellow(),
+            commit.message.lines().next().unwrap_or_default().bold(),
+            format!("({})", commit.author).dimmed()
+        );
+        for line in commit.diff.as_deref().unwrap_or_default().lines() {
+            let line = if line.starts_with("+++") || line.starts_with("---") {
+                line.bold().to_string()
+            } else if line.starts_with('+') {
+                line.green().to_string()
+            } else if line.starts_with('-') {
+                line.red().to_string()
+            } else if line.starts_with("@@") {
+                line.cyan().to_string()
+            } else {
+                line.to_string()
+            };
+            text.push_str(&line);
+            text.push('\n');
+        }
+        text.push('\n');
+    }
+    text
+}
+
+/// Show `text` through git's pager when writing to a terminal.
+///
+/// Falls back to printing directly if there's no pager or it can't start.
+fn page(repo: &Repository, text: &str) -> Result<()> {
+    let pager = repo.pager().filter(|_| std::io::stdout().is_terminal());
+    let child = pager.and_then(|pager| {
+        // Run through the shell like git does; LESS=FRX quits on short output
+        // and keeps the colors
+        Command::new("sh")
+            .args(["-c", &pager])
+            .env(
+                "LESS",
+                std::env::var("LESS").unwrap_or_else(|_| "FRX".into()),
+            )
+            .stdin(Stdio::piped())
+            .spawn()
+            .ok()
+    });
+    let Some(mut child) = child else {
+        print!("{text}");
+        return Ok(());
+    };
+
+    if let Some(mut stdin) = child.stdin.take() {
+        // The user quitting the pager early closes the pipe; that's fine
+        let _ = stdin.write_all(text.as_bytes());
+    }
+    child.wait()?;
+    Ok(())
+}
+
 /// Print log result as JSON.
 fn print_json(log_result: &LogResult) -> Result<()> {
     let json_output = serde_json::to_string_pretty(log_result)?;
     println!("{json_output}");
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(status: CheckStatus) -> CheckRun {
+        CheckRun {
+            name: "ci".to_string(),
+            status,
+            details_url: None,
+        }
+    }
+
+    #[test]
+    fn test_combined_conclusion() {
+        assert_eq!(combined_conclusion(&[]), "none");
+        assert_eq!(
+            combined_conclusion(&[run(CheckStatus::Success), run(CheckStatus::Skipped)]),
+            "success"
+        );
+        assert_eq!(
+            combined_conclusion(&[run(CheckStatus::Success), run(CheckStatus::InProgress)]),
+            "pending"
+        );
+        assert_eq!(
+            combined_conclusion(&[run(CheckStatus::Queued), run(CheckStatus::Failure)]),
+            "failure"
+        );
+    }
+}
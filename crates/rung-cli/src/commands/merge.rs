@@ -2,18 +2,19 @@
 
 use std::collections::HashMap;
 
-use anyhow::{Context, Result, bail};
+use anyhow::{Result, bail};
 use rung_core::State;
 use rung_core::stack::Stack;
 use rung_git::{Oid, Repository};
-use rung_github::{Auth, MergeMethod, RepoId};
+use rung_github::{MergeMethod, RepoId};
 
 use crate::forge::Forge;
 use serde::Serialize;
 
-use crate::commands::utils;
+use crate::commands::utils::{self, CommandContext};
+use crate::notify::{self, MergeEvent};
 use crate::output;
-use crate::services::{MergeService, SubmitService};
+use crate::services::{MergePlan, MergeService, SubmitService};
 
 /// JSON output for merge command.
 #[derive(Debug, Serialize)]
@@ -23,6 +24,36 @@ struct MergeOutput {
     merge_method: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     checked_out: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    retargeted_prs: Vec<u64>,
+    descendants_rebased: usize,
+    local_branch_deleted: bool,
+    base_fast_forwarded: bool,
+}
+
+/// JSON output for `merge --dry-run`.
+#[derive(Debug, Serialize)]
+struct MergeDryRunOutput {
+    dry_run: bool,
+    branch: String,
+    pr_number: u64,
+    merge_method: String,
+    merge_into: String,
+    retarget_prs: Vec<u64>,
+    descendants_to_rebase: Vec<String>,
+    delete_remote_branch: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    delete_local_branch: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    fast_forward: Option<String>,
+}
+
+/// What a completed merge did.
+struct MergeOutcome {
+    parent_branch: String,
+    pr_url: String,
+    sha: String,
+    retargeted_prs: Vec<u64>,
     descendants_rebased: usize,
 }
 
@@ -37,7 +68,7 @@ struct MergeContext {
 }
 
 /// Parse merge method from string.
-fn parse_merge_method(method: &str) -> Result<MergeMethod> {
+pub fn parse_merge_method(method: &str) -> Result<MergeMethod> {
     match method.to_lowercase().as_str() {
         "squash" => Ok(MergeMethod::Squash),
         "merge" => Ok(MergeMethod::Merge),
@@ -47,15 +78,33 @@ fn parse_merge_method(method: &str) -> Result<MergeMethod> {
 }
 
 /// Set up merge context: validate state and gather required info.
-fn setup_merge_context(repo: &Repository, state: &State) -> Result<(MergeContext, Stack)> {
-    if !state.is_initialized() {
-        bail!("Rung not initialized - run `rung init` first");
-    }
-
-    utils::ensure_on_branch(repo)?;
-    let current_branch = repo.current_branch()?;
+///
+/// With `pr`, the branch linked to that PR is merged instead of the current
+/// one, and checked out first unless this is a dry run.
+fn setup_merge_context(
+    cmd: &CommandContext,
+    pr: Option<u64>,
+    dry_run: bool,
+) -> Result<(MergeContext, Stack)> {
+    let CommandContext { repo, state, .. } = cmd;
 
     let stack = state.load_stack()?;
+    let current_branch = if let Some(pr) = pr {
+        let branch = stack
+            .branches
+            .iter()
+            .find(|b| b.pr == Some(pr))
+            .map(|b| b.name.to_string())
+            .ok_or_else(|| anyhow::anyhow!("PR #{pr} is not linked to a branch in the stack"))?;
+        if !dry_run && repo.current_branch().ok().as_deref() != Some(branch.as_str()) {
+            repo.checkout(&branch)?;
+        }
+        branch
+    } else {
+        utils::ensure_on_branch(repo)?;
+        repo.current_branch()?
+    };
+
     let branch = stack
         .find_branch(&current_branch)
         .ok_or_else(|| anyhow::anyhow!("Branch '{current_branch}' not in stack"))?;
@@ -66,8 +115,7 @@ fn setup_merge_context(repo: &Repository, state: &State) -> Result<(MergeContext
 
     let stack_parent_branch = branch.parent.as_ref().map(ToString::to_string);
 
-    let origin_url = repo.origin_url()?;
-    let rung_forge::RemoteInfo { repo: repo_id, .. } = rung_forge::parse_remote(&origin_url)?;
+    let repo_id = cmd.remote_info()?.repo;
 
     let descendants =
         MergeService::<Repository, Forge>::collect_descendants(&stack, &current_branch);
@@ -92,14 +140,23 @@ fn setup_merge_context(repo: &Repository, state: &State) -> Result<(MergeContext
     ))
 }
 
-/// Clean up local state after merge: checkout parent, delete local branch, pull.
-/// Checkout failures are non-fatal since the merge itself succeeded.
+/// What post-merge cleanup did.
+struct CleanupOutcome {
+    checked_out: Option<String>,
+    local_branch_deleted: bool,
+    base_fast_forwarded: bool,
+}
+
+/// Clean up local state after merge: checkout parent, delete local branch, pull,
+/// and fast-forward the base branch.
+/// Failures are non-fatal since the merge itself succeeded.
 fn cleanup_after_merge(
     repo: &Repository,
-    current_branch: &str,
+    plan: &MergePlan,
     parent_branch: &str,
+    base_branch: &str,
     json: bool,
-) -> Option<String> {
+) -> CleanupOutcome {
     // Checkout is non-fatal - the merge succeeded, so we continue with cleanup
     let checked_out = if let Err(e) = repo.checkout(parent_branch) {
         if !json {
@@ -111,34 +168,157 @@ fn cleanup_after_merge(
         Some(parent_branch.to_string())
     };
 
-    if let Err(e) = repo.delete_branch(current_branch) {
+    let local_branch_deleted = delete_local_branch(repo, plan, json);
+
+    // Only pull if checkout succeeded, otherwise we'd pull on the wrong branch
+    let pulled = checked_out.is_some()
+        && match repo.pull_ff() {
+            Ok(()) => true,
+            Err(e) => {
+                if !json {
+                    output::warn(&format!("Could not pull latest {parent_branch}: {e}"));
+                }
+                false
+            }
+        };
+
+    // The base branch gets the merge even when it isn't the one checked out
+    // (a stack branch was the parent, or the checkout failed)
+    let base_fast_forwarded = if checked_out.as_deref() == Some(base_branch) {
+        pulled
+    } else {
+        match repo.fetch(base_branch) {
+            Ok(()) => {
+                if !json {
+                    output::info(&format!("Fast-forwarded '{base_branch}'"));
+                }
+                true
+            }
+            Err(e) => {
+                if !json {
+                    output::warn(&format!("Could not fast-forward '{base_branch}': {e}"));
+                }
+                false
+            }
+        }
+    };
+
+    CleanupOutcome {
+        checked_out,
+        local_branch_deleted,
+        base_fast_forwarded,
+    }
+}
+
+/// Delete the merged local branch unless the plan keeps it.
+///
+/// Returns whether the branch was deleted.
+fn delete_local_branch(repo: &Repository, plan: &MergePlan, json: bool) -> bool {
+    let Some(branch) = &plan.delete_local else {
+        if !json {
+            output::info(&format!(
+                "Kept local branch '{}' (no longer tracked in the stack)",
+                plan.branch
+            ));
+        }
+        return false;
+    };
+
+    if let Err(e) = repo.delete_branch(branch) {
         if !json {
             output::warn(&format!("Could not delete local branch: {e}"));
         }
-    } else if !json {
-        output::info(&format!("Deleted local branch '{current_branch}'"));
+        return false;
     }
+    if !json {
+        output::info(&format!("Deleted local branch '{branch}'"));
+    }
+    true
+}
 
-    // Only pull if checkout succeeded, otherwise we'd pull on the wrong branch
-    if checked_out.is_some()
-        && let Err(e) = repo.pull_ff()
-        && !json
-    {
-        output::warn(&format!("Could not pull latest {parent_branch}: {e}"));
+/// Pick the merge method from `--method` and the repository's settings.
+///
+/// If the settings can't be read (offline, no credentials), the requested
+/// method or squash is used and the forge has the final say.
+fn resolve_merge_method(
+    cmd: &CommandContext,
+    ctx: &MergeContext,
+    requested: Option<MergeMethod>,
+    json: bool,
+) -> Result<MergeMethod> {
+    let fallback = requested.unwrap_or_default();
+    if utils::is_offline() {
+        return Ok(fallback);
     }
+    let Ok(client) = cmd.forge() else {
+        return Ok(fallback);
+    };
 
-    checked_out
+    let service = MergeService::new(&cmd.repo, client, ctx.repo_id.clone());
+    match cmd.runtime()?.block_on(service.allowed_merge_methods()) {
+        Ok(allowed) => service.choose_merge_method(requested, &allowed),
+        Err(e) => {
+            if !json {
+                output::warn(&format!("{e:#} - using {}", fallback.as_str()));
+            }
+            Ok(fallback)
+        }
+    }
 }
 
 /// Run the merge command.
-pub fn run(json: bool, method: &str, no_delete: bool) -> Result<()> {
-    let merge_method = parse_merge_method(method)?;
+#[allow(clippy::fn_params_excessive_bools)]
+pub fn run(
+    json: bool,
+    method: Option<&str>,
+    no_delete: bool,
+    delete_local: Option<bool>,
+    dry_run: bool,
+    sync: bool,
+    pr: Option<u64>,
+) -> Result<()> {
+    let requested = method.map(parse_merge_method).transpose()?;
+    if sync && json {
+        bail!("--sync can't be combined with --json - run `rung sync --json` after merging");
+    }
 
-    let repo = Repository::open_current().context("Not inside a git repository")?;
-    let workdir = repo.workdir().context("Cannot run in bare repository")?;
-    let state = State::new(workdir)?;
+    let cmd = CommandContext::open()?;
+    let lock = utils::lock_state(&cmd.repo, &cmd.state)?;
+    let (ctx, stack) = setup_merge_context(&cmd, pr, dry_run)?;
+    let CommandContext { repo, state, .. } = &cmd;
+
+    let merge_method = resolve_merge_method(&cmd, &ctx, requested, json)?;
+    let method = merge_method.as_str();
+    let config = state.load_config()?;
+    let delete_local = delete_local.unwrap_or(config.merge.delete_local);
+
+    // Root branches merge into their PR's base, which is the default branch
+    // unless the PR was retargeted by hand.
+    let base_branch = state.default_branch()?;
+    let merge_into = ctx
+        .stack_parent_branch
+        .clone()
+        .unwrap_or_else(|| base_branch.clone());
+    let plan = MergePlan::new(
+        &stack,
+        &ctx.current_branch,
+        ctx.pr_number,
+        &merge_into,
+        &base_branch,
+        merge_method,
+        !no_delete,
+        delete_local,
+    );
+
+    // Deleting the merged branch afterwards may be forbidden by the policy
+    if plan.delete_remote.is_some() || plan.delete_local.is_some() {
+        repo.protected_branches()
+            .check(&ctx.current_branch, "delete")?;
+    }
 
-    let (ctx, stack) = setup_merge_context(&repo, &state)?;
+    if dry_run {
+        return print_dry_run(&plan, sync, json);
+    }
 
     if !json {
         output::info(&format!(
@@ -147,53 +327,74 @@ pub fn run(json: bool, method: &str, no_delete: bool) -> Result<()> {
         ));
     }
 
-    let rt = tokio::runtime::Runtime::new()?;
-    let (parent_branch, descendants_rebased) = rt.block_on(execute_merge(
-        &repo,
-        &state,
+    let client = cmd.forge()?;
+    let outcome = cmd.runtime()?.block_on(execute_merge(
+        repo,
+        state,
+        client,
         &stack,
         &ctx,
-        merge_method,
-        no_delete,
+        plan.merge_method,
+        plan.delete_remote.is_none(),
         json,
     ))?;
 
-    let checked_out = cleanup_after_merge(&repo, &ctx.current_branch, &parent_branch, json);
+    let cleanup = cleanup_after_merge(repo, &plan, &outcome.parent_branch, &base_branch, json);
+    if let Some(workdir) = repo.workdir() {
+        let event = MergeEvent {
+            event: "merge",
+            repo: ctx.repo_id.to_string(),
+            pr: ctx.pr_number,
+            pr_url: &outcome.pr_url,
+            branch: &ctx.current_branch,
+            base: &outcome.parent_branch,
+            sha: &outcome.sha,
+            merge_method: method,
+        };
+        notify::send(&config.merge.notify, &event, workdir, json);
+    }
 
     if json {
         return output_json(&MergeOutput {
             merged_branch: ctx.current_branch,
             pr_number: ctx.pr_number,
             merge_method: method.to_string(),
-            checked_out,
-            descendants_rebased,
+            checked_out: cleanup.checked_out,
+            retargeted_prs: outcome.retargeted_prs,
+            descendants_rebased: outcome.descendants_rebased,
+            local_branch_deleted: cleanup.local_branch_deleted,
+            base_fast_forwarded: cleanup.base_fast_forwarded,
         });
     }
 
-    if checked_out.is_some() {
-        output::info(&format!("Checked out '{parent_branch}'"));
+    if cleanup.checked_out.is_some() {
+        output::info(&format!("Checked out '{}'", outcome.parent_branch));
     }
     output::success("Merge complete!");
 
+    if sync {
+        // Sync takes the state lock itself
+        drop(lock);
+        output::info("Syncing the remaining stack...");
+        return super::sync::run(false, &super::sync::SyncOptions::default());
+    }
+
     Ok(())
 }
 
 /// Execute the GitHub merge operation.
-/// Returns (`parent_branch`, `descendants_rebased_count`).
 #[allow(clippy::too_many_arguments, clippy::future_not_send)]
 async fn execute_merge(
     repo: &Repository,
     state: &State,
+    client: &Forge,
     stack: &Stack,
     ctx: &MergeContext,
     merge_method: MergeMethod,
     no_delete: bool,
     json: bool,
-) -> Result<(String, usize)> {
-    let auth = Auth::auto();
-    let origin_url = repo.origin_url()?;
-    let client = Forge::for_remote(&origin_url, &auth)?;
-    let service = MergeService::new(repo, &client, ctx.repo_id.clone());
+) -> Result<MergeOutcome> {
+    let service = MergeService::new(repo, client, ctx.repo_id.clone());
 
     // Step 1: Validate PR is mergeable
     let pr = service.validate_mergeable(ctx.pr_number).await?;
@@ -203,18 +404,25 @@ async fn execute_merge(
         .clone()
         .unwrap_or_else(|| pr.base_branch.clone());
 
-    // Step 2: Shift child PR bases before merge
-    print_child_relinks(stack, ctx, &parent_branch, json);
-
+    // Step 2: Shift child PR bases before merge, so no PR is ever based
+    // on the deleted branch
     let shifted_prs = service
         .shift_child_pr_bases(stack, &ctx.current_branch, &parent_branch, &ctx.descendants)
         .await?;
+    if !json {
+        for (pr, _) in &shifted_prs {
+            output::info(&format!("Retargeted PR #{pr} to '{parent_branch}'"));
+        }
+    }
 
     // Step 3: Merge the PR
-    if let Err(merge_err) = service.merge_pr(ctx.pr_number, merge_method).await {
-        rollback_on_failure(&service, &shifted_prs, json).await;
-        return Err(merge_err);
-    }
+    let sha = match service.merge_pr(ctx.pr_number, merge_method).await {
+        Ok(sha) => sha,
+        Err(merge_err) => {
+            rollback_on_failure(&service, &shifted_prs, json).await;
+            return Err(merge_err);
+        }
+    };
 
     if !json {
         output::success(&format!("Merged PR #{}", ctx.pr_number));
@@ -244,7 +452,7 @@ async fn execute_merge(
     }
 
     // Step 4b: Update stack comments on remaining PRs (non-fatal after merge)
-    update_stack_comments_after_merge(repo, state, &client, ctx, json).await;
+    update_stack_comments_after_merge(repo, state, client, ctx, json).await;
 
     // Step 5: Rebase descendants (non-fatal after merge)
     // rebase_descendants_after_merge already handles its own error messaging
@@ -259,30 +467,71 @@ async fn execute_merge(
         delete_remote_branch(&service, &ctx.current_branch, json).await;
     }
 
-    Ok((parent_branch, descendants_rebased))
+    Ok(MergeOutcome {
+        parent_branch,
+        pr_url: pr.html_url,
+        sha,
+        retargeted_prs: shifted_prs.into_iter().map(|(pr, _)| pr).collect(),
+        descendants_rebased,
+    })
 }
 
-/// Print info about child PR relinking.
-fn print_child_relinks(stack: &Stack, ctx: &MergeContext, parent_branch: &str, json: bool) {
-    if json || ctx.descendants.is_empty() {
-        return;
+/// Print what a merge would do without touching git or the forge.
+fn print_dry_run(plan: &MergePlan, sync: bool, json: bool) -> Result<()> {
+    if json {
+        return output_dry_run_json(&MergeDryRunOutput {
+            dry_run: true,
+            branch: plan.branch.clone(),
+            pr_number: plan.pr_number,
+            merge_method: plan.merge_method.as_str().to_string(),
+            merge_into: plan.merge_into.clone(),
+            retarget_prs: plan.retarget_prs.clone(),
+            descendants_to_rebase: plan.rebase.clone(),
+            delete_remote_branch: plan.delete_remote.is_some(),
+            delete_local_branch: plan.delete_local.clone(),
+            fast_forward: plan.fast_forward.clone(),
+        });
     }
 
-    for branch_name in &ctx.descendants {
-        if let Some(branch_info) = stack.find_branch(branch_name) {
-            let stack_parent = branch_info
-                .parent
-                .as_ref()
-                .map_or(parent_branch, |p| p.as_str());
-            if stack_parent == ctx.current_branch
-                && let Some(child_pr_num) = branch_info.pr
-            {
-                output::info(&format!(
-                    "Relinking PR #{child_pr_num} to '{parent_branch}' before merge..."
-                ));
-            }
-        }
+    let merge_into = &plan.merge_into;
+    output::info(&format!(
+        "Would merge PR #{} ({}) into '{merge_into}' using {}",
+        plan.pr_number,
+        plan.branch,
+        plan.merge_method.as_str()
+    ));
+    for pr in &plan.retarget_prs {
+        output::detail(&format!("  → retarget PR #{pr} to '{merge_into}'"));
     }
+    for branch in &plan.rebase {
+        output::detail(&format!("  → rebase and push {branch}"));
+    }
+    if let Some(remote) = &plan.delete_remote {
+        output::detail(&format!("  → delete remote branch '{remote}'"));
+    }
+    match &plan.delete_local {
+        Some(branch) => output::detail(&format!(
+            "  → checkout '{merge_into}' and delete local branch '{branch}'"
+        )),
+        None => output::detail(&format!(
+            "  → checkout '{merge_into}' and keep local branch '{}'",
+            plan.branch
+        )),
+    }
+    if let Some(base) = &plan.fast_forward {
+        output::detail(&format!("  → fast-forward '{base}'"));
+    }
+    if sync {
+        output::detail("  → sync the remaining stack");
+    }
+
+    Ok(())
+}
+
+/// Output merge dry-run plan as JSON.
+fn output_dry_run_json(output: &MergeDryRunOutput) -> Result<()> {
+    println!("{}", serde_json::to_string_pretty(output)?);
+    Ok(())
 }
 
 /// Rollback PR base changes on merge failure.
@@ -431,9 +680,10 @@ async fn update_stack_comments_after_merge(
     };
 
     let submit_service = SubmitService::new(repo, client, ctx.repo_id.clone());
+    let config = state.load_config().map(|c| c.submit).unwrap_or_default();
 
     if let Err(e) = submit_service
-        .update_stack_comments(&stack, &default_branch)
+        .update_stack_navigation(&stack, &default_branch, &config)
         .await
         && !json
     {
@@ -1,26 +1,41 @@
 //! CLI command definitions and handlers.
 
 use clap::{Parser, Subcommand};
+use clap_complete::engine::ArgValueCandidates;
 
 pub mod absorb;
 pub mod adopt;
+pub mod apply;
+pub mod audit;
+pub mod backup;
+pub mod ci;
 pub mod completions;
 pub mod create;
+pub mod describe;
 pub mod doctor;
+pub mod edit;
 pub mod fold;
+pub mod gc;
 pub mod init;
+pub mod lock;
 pub mod log;
 pub mod merge;
 pub mod mv;
 pub mod navigate;
+pub mod plugin;
 pub mod restack;
+pub mod snapshot;
 pub mod split;
 pub mod status;
 pub mod submit;
+pub mod switch;
 pub mod sync;
+pub mod telemetry;
+pub mod track;
+pub mod uncommit;
 pub mod undo;
 pub mod update;
-mod utils;
+pub mod utils;
 
 /// Rung - The developer's ladder for stacked PRs.
 ///
@@ -30,10 +45,12 @@ mod utils;
 #[command(name = "rung")]
 #[command(author, version, about, long_about = None)]
 #[command(propagate_version = true)]
+#[allow(clippy::struct_excessive_bools)]
 pub struct Cli {
     /// Output as JSON (for tooling integration).
     ///
-    /// Supported by: status, doctor, sync, submit, merge, log
+    /// Supported by every command except the interactive ones (move, split)
+    /// and completions, which prints a shell script.
     #[arg(long, global = true)]
     pub json: bool,
 
@@ -44,6 +61,36 @@ pub struct Cli {
     #[arg(short, long, global = true, conflicts_with = "json")]
     pub quiet: bool,
 
+    /// Wait for another running rung operation to finish instead of failing.
+    ///
+    /// Commands that modify the stack take a lock on `.git/rung/`; by default
+    /// they exit with an error if another rung process already holds it.
+    #[arg(long, global = true)]
+    pub wait: bool,
+
+    /// Skip all network access: no fetches, pushes, or forge API calls.
+    ///
+    /// `sync`, `status --fetch`, and `submit --dry-run` fall back to local and
+    /// cached data. Rung also switches to offline mode on its own when the
+    /// network turns out to be unreachable.
+    #[arg(long, global = true)]
+    pub offline: bool,
+
+    /// Print plain ASCII instead of Unicode glyphs like ✓ and box drawing.
+    ///
+    /// For terminals, logs, and screen readers that mangle them. Same as
+    /// `output.unicode = false` in the config.
+    #[arg(long, global = true)]
+    pub ascii: bool,
+
+    /// Refuse any command that would change branches, the working tree,
+    /// the stack, or GitHub; inspection commands still run.
+    ///
+    /// For build servers and shared review machines. Same as setting
+    /// `RUNG_READ_ONLY=1`.
+    #[arg(long, global = true)]
+    pub read_only: bool,
+
     #[command(subcommand)]
     pub command: Commands,
 }
@@ -73,6 +120,51 @@ pub enum Commands {
         dry_run: bool,
     },
 
+    /// Add a branch to the stack, inferring its parent.
+    ///
+    /// A quick alternative to `adopt` for one-off branches: the parent is
+    /// the closest stack branch (or the base branch) the branch builds on.
+    Track {
+        /// Branch to track. Defaults to the current branch.
+        branch: Option<String>,
+
+        /// Parent branch, instead of inferring it.
+        #[arg(long, short)]
+        parent: Option<String>,
+
+        /// Show what would be done without making changes.
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Remove a branch from the stack without deleting it.
+    ///
+    /// The git branch and its PR are left alone. Any children move onto
+    /// the branch's parent.
+    Untrack {
+        /// Branch to untrack. Defaults to the current branch.
+        branch: Option<String>,
+
+        /// Show what would be done without making changes.
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Keep a branch from being rewritten.
+    ///
+    /// sync, restack, move, edit, and absorb refuse to rewrite a locked
+    /// branch - useful while a reviewer is partway through its PR.
+    Lock {
+        /// Branch to lock. Defaults to the current branch.
+        branch: Option<String>,
+    },
+
+    /// Let rung rewrite a branch locked with `rung lock` again.
+    Unlock {
+        /// Branch to unlock. Defaults to the current branch.
+        branch: Option<String>,
+    },
+
     /// Create a new branch in the stack. [alias: c]
     ///
     /// Creates a new branch with the current branch as its parent.
@@ -80,6 +172,7 @@ pub enum Commands {
     ///
     /// If --message is provided without a branch name, the name is derived
     /// from the commit message (e.g., "feat: add auth" becomes "feat-add-auth").
+    /// With staged changes and no --message, your git editor opens for one.
     #[command(alias = "c")]
     #[command(group(
         clap::ArgGroup::new("create_input")
@@ -95,6 +188,29 @@ pub enum Commands {
         #[arg(long, short)]
         message: Option<String>,
 
+        /// Stage modified and deleted tracked files before committing,
+        /// like `git commit -a`. Untracked files are left alone.
+        #[arg(long, short = 'a', requires = "message")]
+        all: bool,
+
+        /// Create the commit even if there are no changes, for a
+        /// placeholder branch.
+        #[arg(long, requires = "message")]
+        allow_empty: bool,
+
+        /// Insert the branch between the current branch and its children.
+        ///
+        /// The children are moved onto the new branch, and restacked if it
+        /// starts with a commit.
+        #[arg(long, conflicts_with = "from")]
+        insert: bool,
+
+        /// Start the branch at this commit or ref instead of HEAD.
+        ///
+        /// It must build on the current branch, which stays the parent.
+        #[arg(long, value_name = "REF")]
+        from: Option<String>,
+
         /// Show what would be done without making changes.
         #[arg(long)]
         dry_run: bool,
@@ -109,6 +225,14 @@ pub enum Commands {
         /// Fetch latest remote state before showing status.
         #[arg(long)]
         fetch: bool,
+
+        /// Only show the current branch, its ancestors and its descendants.
+        #[arg(long)]
+        chain: bool,
+
+        /// Only show N levels below trunk (below the current branch with --chain).
+        #[arg(long, value_name = "N")]
+        depth: Option<usize>,
     },
 
     /// Sync the stack by rebasing all branches. [alias: sy]
@@ -134,13 +258,41 @@ pub enum Commands {
         #[arg(long)]
         abort: bool,
 
-        /// Skip pushing branches to remote after sync.
+        /// Leave the conflicted branch of a paused sync as it was and sync
+        /// the rest.
+        #[arg(long, conflicts_with_all = ["continue", "abort"])]
+        skip: bool,
+
+        /// With --skip, also leave the branches stacked on it untouched.
+        #[arg(long, requires = "skip")]
+        skip_descendants: bool,
+
+        /// Skip pushing branches to remote after sync (same as `--push none`).
         #[arg(long)]
         no_push: bool,
 
+        /// Which branches to push after sync: all (default), existing (only
+        /// branches with a PR), ancestors (only those below the current
+        /// branch), prompt, or none. Overrides `sync.push`.
+        #[arg(long, value_name = "MODE", conflicts_with = "no_push")]
+        push: Option<rung_core::config::PushMode>,
+
+        /// Squash pending `fixup!` commits into their targets before rebasing.
+        #[arg(long)]
+        autosquash: bool,
+
+        /// Prune remote-tracking refs of branches deleted on the remote.
+        #[arg(long)]
+        prune_remote: bool,
+
         /// Base branch to sync against (defaults to "main").
         #[arg(long, short)]
         base: Option<String>,
+
+        /// Only sync the tree containing this branch, leaving the stack's
+        /// other trees alone.
+        #[arg(long, value_name = "BRANCH", conflicts_with_all = ["continue", "abort", "skip"])]
+        tree: Option<String>,
     },
 
     /// Push branches and create/update PRs. [alias: sm]
@@ -160,6 +312,17 @@ pub enum Commands {
         #[arg(long)]
         draft: bool,
 
+        /// Create new PRs for branches stacked above this one as drafts.
+        ///
+        /// The branch and everything below it are opened for review.
+        #[arg(
+            long,
+            value_name = "BRANCH",
+            conflicts_with = "draft",
+            add = ArgValueCandidates::new(completions::stack_branches)
+        )]
+        draft_above: Option<String>,
+
         /// Show what would be done without making changes.
         #[arg(long)]
         dry_run: bool,
@@ -181,13 +344,42 @@ pub enum Commands {
         /// Stages all changes first if working directory is dirty.
         #[arg(long, short, conflicts_with = "amend")]
         message: Option<String>,
+
+        /// Only submit the current branch and its ancestors.
+        ///
+        /// Siblings and branches stacked above are left alone, for shipping
+        /// the ready bottom of a stack.
+        #[arg(long)]
+        downstack: bool,
+
+        /// Regenerate existing PRs' titles from their tip commits.
+        ///
+        /// Titles edited on the forge are left alone.
+        #[arg(long)]
+        update_titles: bool,
+
+        /// Regenerate the rung-managed part of existing PRs' bodies.
+        ///
+        /// Text outside the managed section is left alone.
+        #[arg(long)]
+        update_bodies: bool,
     },
 
     /// Undo the last sync operation. [alias: un]
     ///
-    /// Restores all branches to their state before the last sync.
+    /// Restores all branches to their state before the last sync. Given an
+    /// operation id from `rung audit`, reverts that operation instead, as
+    /// long as nothing after it changed the same branches.
     #[command(alias = "un")]
-    Undo,
+    Undo {
+        /// Operation to revert, as listed by `rung audit` (`12` or `#12`).
+        #[arg(value_parser = undo::parse_operation_id)]
+        operation: Option<usize>,
+
+        /// Show what would be restored without making changes.
+        #[arg(long)]
+        dry_run: bool,
+    },
 
     /// Merge the current branch's PR and clean up. [alias: m]
     ///
@@ -195,13 +387,35 @@ pub enum Commands {
     /// removes it from the stack, and checks out the parent.
     #[command(alias = "m")]
     Merge {
-        /// Merge method: squash (default), merge, or rebase.
-        #[arg(long, short, default_value = "squash")]
-        method: String,
+        /// PR to merge (`42` or `#42`). Defaults to the current branch's PR.
+        #[arg(value_parser = utils::parse_pr_number, add = ArgValueCandidates::new(completions::pr_numbers))]
+        pr: Option<u64>,
+
+        /// Merge method: squash, merge, or rebase.
+        ///
+        /// Defaults to the first method the repository allows, in that order.
+        #[arg(long, short)]
+        method: Option<String>,
 
         /// Don't delete the remote branch after merge.
         #[arg(long)]
         no_delete: bool,
+
+        /// Delete the merged branch locally (default, see `merge.delete_local`).
+        #[arg(long, overrides_with = "keep_local")]
+        delete_local: bool,
+
+        /// Keep the merged branch locally; it's still removed from the stack.
+        #[arg(long, overrides_with = "delete_local")]
+        keep_local: bool,
+
+        /// Show what would be done without making changes.
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Run `rung sync` once the merge is done.
+        #[arg(long)]
+        sync: bool,
     },
 
     /// Navigate to the next branch in the stack (child). [alias: n]
@@ -215,8 +429,33 @@ pub enum Commands {
     /// Interactive branch picker for quick navigation. [alias: mv]
     ///
     /// Opens a TUI list to select and jump to any branch in the stack.
+    /// With `--reorder`, swaps a branch with its parent or child instead.
     #[command(alias = "mv")]
-    Move,
+    Move {
+        /// Swap the branch with its parent (up) or its only child (down),
+        /// rebasing both and updating their PR bases.
+        #[arg(long, value_enum, value_name = "DIRECTION")]
+        reorder: Option<mv::ReorderDirection>,
+
+        /// Branch to reorder. Defaults to the current branch.
+        #[arg(requires = "reorder")]
+        branch: Option<String>,
+
+        /// Show what would be done without making changes.
+        #[arg(long, requires = "reorder")]
+        dry_run: bool,
+    },
+
+    /// Check out a PR's branch, adding it to the stack. [alias: sw]
+    ///
+    /// Fetches the PR's head branch and places it on the PR's base, pulling
+    /// in any parent PRs that aren't in the stack yet.
+    #[command(alias = "sw")]
+    Switch {
+        /// PR to check out (`42` or `#42`).
+        #[arg(value_parser = utils::parse_pr_number, add = ArgValueCandidates::new(completions::pr_numbers))]
+        pr: u64,
+    },
 
     /// Move a branch to a different parent in the stack. [alias: re]
     ///
@@ -225,10 +464,11 @@ pub enum Commands {
     #[command(alias = "re")]
     Restack {
         /// Branch to move. Defaults to the current branch.
+        #[arg(add = ArgValueCandidates::new(completions::stack_branches))]
         branch: Option<String>,
 
         /// New parent branch to rebase onto.
-        #[arg(long)]
+        #[arg(long, add = ArgValueCandidates::new(completions::parent_branches))]
         onto: Option<String>,
 
         /// Show what would be done without making changes.
@@ -243,6 +483,15 @@ pub enum Commands {
         #[arg(long)]
         abort: bool,
 
+        /// Leave the conflicted descendant of a paused restack as it was and
+        /// restack the rest.
+        #[arg(long, conflicts_with_all = ["continue", "abort"])]
+        skip: bool,
+
+        /// With --skip, also leave the branches stacked on it untouched.
+        #[arg(long, requires = "skip")]
+        skip_descendants: bool,
+
         /// Also move all descendant branches (children, grandchildren, etc.).
         #[arg(long)]
         include_children: bool,
@@ -254,9 +503,20 @@ pub enum Commands {
 
     /// Diagnose issues with the stack and repository. [alias: doc]
     ///
-    /// Checks stack integrity, git state, sync status, and GitHub connectivity.
+    /// Checks stack integrity, git state, sync status, and GitHub connectivity,
+    /// and lists remote branches left over from merged or deleted stack branches.
+    /// Exits 0 when healthy, 1 on warnings, and 2 on errors.
     #[command(alias = "doc")]
-    Doctor,
+    Doctor {
+        /// Delete remote branches left over from merged or deleted stack branches.
+        #[arg(long)]
+        fix: bool,
+
+        /// Lowest severity that fails: warning (default, exits 1 on warnings
+        /// and 2 on errors), error, or never.
+        #[arg(long, value_name = "SEVERITY")]
+        fail_on: Option<rung_core::config::FailOn>,
+    },
 
     /// Update rung to the latest version. [alias: up]
     ///
@@ -267,21 +527,101 @@ pub enum Commands {
         /// Only check for updates without installing.
         #[arg(long)]
         check: bool,
+
+        /// Release channel to update from: stable (default) or nightly,
+        /// which includes prereleases. Overrides `update.channel`.
+        #[arg(long, value_name = "CHANNEL")]
+        channel: Option<rung_core::config::UpdateChannel>,
+
+        /// Install this exact version instead, e.g. `--to v0.5.0`.
+        ///
+        /// Also moves back to an older release.
+        #[arg(long, value_name = "VERSION", conflicts_with = "channel")]
+        to: Option<String>,
+
+        /// Install a downloaded binary even if the release publishes no
+        /// checksum for it.
+        #[arg(long)]
+        allow_unverified: bool,
     },
 
     /// Generate shell completions. [alias: comp]
     ///
     /// Outputs completion script to stdout. Redirect to a file and
-    /// source it in your shell configuration.
+    /// source it in your shell configuration, or use --install.
     #[command(alias = "comp")]
     Completions {
         /// Shell to generate completions for.
         #[arg(value_enum)]
         shell: clap_complete::Shell,
+
+        /// Write the script where the shell looks for completions, and
+        /// print what to add to its startup file.
+        #[arg(long)]
+        install: bool,
     },
 
     /// Show commits between the base branch and HEAD
-    Log,
+    Log {
+        /// Show each commit's patch.
+        #[arg(long, short)]
+        patch: bool,
+
+        /// Show each commit's diffstat.
+        #[arg(long, conflicts_with = "patch")]
+        stat: bool,
+
+        /// Fetch the PR's state and CI result instead of using the cache.
+        #[arg(long)]
+        fetch: bool,
+
+        /// Show the commits of every branch, drawn as the stack tree.
+        #[arg(long, conflicts_with_all = ["patch", "stat", "fetch"])]
+        stack: bool,
+    },
+
+    /// Show the audit log of operations that changed branches.
+    ///
+    /// Every rung command that creates, moves, or deletes a local branch is
+    /// recorded with who ran it, when, and which refs changed. Use `--json`
+    /// to export the full log.
+    Audit {
+        /// Only show the most recent N operations.
+        #[arg(short = 'n', long)]
+        limit: Option<usize>,
+    },
+
+    /// Save and restore named snapshots of the stack. [alias: snap]
+    ///
+    /// A snapshot records the stack and the tip of every stack branch, so
+    /// you can go back to it after an experiment. Independent of the
+    /// automatic backups used by `rung undo`.
+    #[command(alias = "snap")]
+    Snapshot {
+        #[command(subcommand)]
+        command: snapshot::SnapshotCommand,
+    },
+
+    /// Export or import rung's state as a tarball.
+    ///
+    /// Bundles the stack, sync backups, and audit log, e.g. before a risky
+    /// experiment or to attach to a bug report.
+    Backup {
+        #[command(subcommand)]
+        command: backup::BackupCommand,
+    },
+
+    /// Clean up rung's state in `.git/rung`.
+    ///
+    /// Deletes sync backups past `general.backup_retention`, audit log
+    /// entries older than `general.audit_retention_days`, cached PR states
+    /// of branches no longer in the stack, and files left by interrupted
+    /// writes, then reports the space reclaimed.
+    Gc {
+        /// Show what would be removed without removing it.
+        #[arg(long)]
+        dry_run: bool,
+    },
 
     /// Absorb staged changes into the appropriate commits. [alias: ab]
     ///
@@ -296,6 +636,104 @@ pub enum Commands {
         /// Base branch to determine rebaseable range (defaults to auto-detect).
         #[arg(long, short)]
         base: Option<String>,
+
+        /// Create the fixup commit on the stack branch that owns the target
+        /// commit instead of on HEAD. Squash later with `rung sync --autosquash`.
+        #[arg(long)]
+        fixup: bool,
+
+        /// Stage modified tracked files before absorbing, so they don't need
+        /// to be staged by hand.
+        #[arg(long, conflicts_with = "patch")]
+        unstaged: bool,
+
+        /// Interactively choose hunks to absorb (like `git add -p`).
+        #[arg(long, short)]
+        patch: bool,
+
+        /// Restack descendant branches after absorbing.
+        ///
+        /// Can be made the default with `restack = true` under `[absorb]`
+        /// in `.git/rung/config.toml`.
+        #[arg(long)]
+        and_restack: bool,
+
+        /// Choose the target commit for every hunk interactively.
+        ///
+        /// Without this flag, only hunks whose lines come from several
+        /// commits are prompted for (when running in a terminal).
+        #[arg(long)]
+        select: bool,
+
+        /// Absorb every staged hunk into this commit, skipping blame.
+        ///
+        /// The commit must be between the base branch and HEAD.
+        #[arg(long, value_name = "SHA", conflicts_with_all = ["select", "branch"])]
+        commit: Option<String>,
+
+        /// Only absorb into commits on this stack branch.
+        ///
+        /// Hunks whose lines were last touched elsewhere are left staged.
+        #[arg(long, add = ArgValueCandidates::new(completions::stack_branches))]
+        branch: Option<String>,
+    },
+
+    /// Commit a patch onto any branch in the stack.
+    ///
+    /// Applies a patch file (or a diff on stdin) as a new commit on the
+    /// branch without checking it out, then restacks its descendants.
+    Apply {
+        /// Patch file to apply; reads stdin when omitted or `-`.
+        patch: Option<std::path::PathBuf>,
+
+        /// Branch to commit the patch on (defaults to the current branch).
+        #[arg(long, add = ArgValueCandidates::new(completions::stack_branches))]
+        to: Option<String>,
+
+        /// Commit message. Defaults to the subject of a `git format-patch`
+        /// patch; required for a plain diff.
+        #[arg(long, short)]
+        message: Option<String>,
+    },
+
+    /// Move the current branch's tip commit back into the index.
+    ///
+    /// Soft-resets the branch by one commit, leaving its changes staged.
+    /// Descendants still contain the commit and are restacked without it
+    /// on the next `rung sync`.
+    Uncommit,
+
+    /// Check out a branch mid-stack to edit it.
+    ///
+    /// Amend or add commits, then run `rung done` (or navigate away with
+    /// `rung nxt`, `prv` or `move`) to restack its descendants onto the
+    /// result.
+    Edit {
+        /// Branch to edit.
+        #[arg(add = ArgValueCandidates::new(completions::stack_branches))]
+        branch: String,
+    },
+
+    /// Finish a `rung edit`: restack descendants and go back.
+    Done,
+
+    /// Edit a branch's description, used as its PR body.
+    ///
+    /// Stored as git's `branch.<name>.description`, so it's shared with
+    /// `git branch --edit-description`. Opens your git editor unless a
+    /// description is given with `-m`.
+    Describe {
+        /// Branch to describe (defaults to the current branch).
+        #[arg(add = ArgValueCandidates::new(completions::stack_branches))]
+        branch: Option<String>,
+
+        /// Description to set, instead of opening the editor.
+        #[arg(long, short, conflicts_with = "clear")]
+        message: Option<String>,
+
+        /// Remove the description.
+        #[arg(long)]
+        clear: bool,
     },
 
     /// Split a branch into multiple stacked branches. [alias: sp]
@@ -305,8 +743,22 @@ pub enum Commands {
     #[command(alias = "sp")]
     Split {
         /// Branch to split. Defaults to the current branch.
+        #[arg(add = ArgValueCandidates::new(completions::stack_branches))]
         branch: Option<String>,
 
+        /// Split evenly into N branches instead of picking commits.
+        ///
+        /// Shows the partition first and lets you nudge the boundaries.
+        #[arg(long, value_name = "N", conflicts_with = "abort")]
+        into: Option<usize>,
+
+        /// Reparent the split branch's children onto this branch from the split.
+        ///
+        /// Without it you're asked which branch they should follow (they stay
+        /// on the split branch when not interactive).
+        #[arg(long, value_name = "BRANCH", conflicts_with = "abort")]
+        children_follow: Option<String>,
+
         /// Show what would be done without making changes.
         #[arg(long, conflicts_with = "abort")]
         dry_run: bool,
@@ -324,7 +776,10 @@ pub enum Commands {
     Fold {
         /// Branches to fold (must be adjacent in stack).
         /// If not specified, interactive selection is used.
-        #[arg(conflicts_with_all = ["into_parent", "include_children"])]
+        #[arg(
+            conflicts_with_all = ["into_parent", "include_children"],
+            add = ArgValueCandidates::new(completions::stack_branches)
+        )]
         branches: Vec<String>,
 
         /// Fold current branch into its parent.
@@ -343,4 +798,117 @@ pub enum Commands {
         #[arg(long, conflicts_with = "dry_run")]
         abort: bool,
     },
+
+    /// Opt in to or out of anonymous usage statistics.
+    ///
+    /// Telemetry is off by default. When on, rung records each command's
+    /// name, duration, and error class - never repository data.
+    Telemetry {
+        /// Turn telemetry on or off, or show the current setting.
+        #[arg(value_enum, default_value = "status")]
+        action: telemetry::TelemetryAction,
+    },
+
+    /// Checks for pull-request pipelines.
+    ///
+    /// Run in CI to gate merges on stack hygiene; works without `rung init`.
+    Ci {
+        #[command(subcommand)]
+        command: ci::CiCommand,
+    },
+
+    /// Run an external `rung-<name>` plugin from PATH.
+    #[command(external_subcommand)]
+    External(Vec<String>),
+}
+
+impl Commands {
+    /// Subcommand name, as used for `pre_<name>` / `post_<name>` hooks.
+    pub fn name(&self) -> &str {
+        match self {
+            Self::Init => "init",
+            Self::Adopt { .. } => "adopt",
+            Self::Track { .. } => "track",
+            Self::Untrack { .. } => "untrack",
+            Self::Lock { .. } => "lock",
+            Self::Unlock { .. } => "unlock",
+            Self::Create { .. } => "create",
+            Self::Status { .. } => "status",
+            Self::Sync { .. } => "sync",
+            Self::Submit { .. } => "submit",
+            Self::Undo { .. } => "undo",
+            Self::Merge { .. } => "merge",
+            Self::Nxt => "nxt",
+            Self::Prv => "prv",
+            Self::Move { .. } => "move",
+            Self::Switch { .. } => "switch",
+            Self::Restack { .. } => "restack",
+            Self::Doctor { .. } => "doctor",
+            Self::Update { .. } => "update",
+            Self::Completions { .. } => "completions",
+            Self::Log { .. } => "log",
+            Self::Audit { .. } => "audit",
+            Self::Backup { .. } => "backup",
+            Self::Gc { .. } => "gc",
+            Self::Snapshot { .. } => "snapshot",
+            Self::Telemetry { .. } => "telemetry",
+            Self::Ci { .. } => "ci",
+            Self::Absorb { .. } => "absorb",
+            Self::Apply { .. } => "apply",
+            Self::Uncommit => "uncommit",
+            Self::Edit { .. } => "edit",
+            Self::Done => "done",
+            Self::Describe { .. } => "describe",
+            Self::Split { .. } => "split",
+            Self::Fold { .. } => "fold",
+            Self::External(args) => args.first().map_or("", String::as_str),
+        }
+    }
+
+    /// Whether the command only inspects, leaving branches, the working
+    /// tree, the stack, and the forge as they are. Only these run in
+    /// read-only mode.
+    ///
+    /// Plugins are never read-only, since rung can't tell what they do.
+    /// `sync --dry-run` still reconciles merged PRs, so only `sync --check`
+    /// counts.
+    pub const fn is_read_only(&self) -> bool {
+        match self {
+            Self::Status { .. } | Self::Log { .. } | Self::Audit { .. } => true,
+            Self::Sync { check, .. } | Self::Update { check, .. } => *check,
+            Self::Restack {
+                dry_run,
+                continue_,
+                abort,
+                skip,
+                ..
+            } => *dry_run && !*continue_ && !*abort && !*skip,
+            Self::Doctor { fix, .. } => !*fix,
+            Self::Completions { install, .. } => !*install,
+            Self::Telemetry { action } => matches!(action, telemetry::TelemetryAction::Status),
+            Self::Snapshot { command } => matches!(
+                command,
+                snapshot::SnapshotCommand::List
+                    | snapshot::SnapshotCommand::Restore { dry_run: true, .. }
+            ),
+            Self::Backup { command } => matches!(command, backup::BackupCommand::Export { .. }),
+            Self::Ci { command } => matches!(
+                command,
+                ci::CiCommand::Validate { .. } | ci::CiCommand::Setup { dry_run: true, .. }
+            ),
+            Self::Adopt { dry_run, .. }
+            | Self::Track { dry_run, .. }
+            | Self::Untrack { dry_run, .. }
+            | Self::Create { dry_run, .. }
+            | Self::Submit { dry_run, .. }
+            | Self::Undo { dry_run, .. }
+            | Self::Merge { dry_run, .. }
+            | Self::Move { dry_run, .. }
+            | Self::Gc { dry_run }
+            | Self::Absorb { dry_run, .. }
+            | Self::Split { dry_run, .. }
+            | Self::Fold { dry_run, .. } => *dry_run,
+            _ => false,
+        }
+    }
 }
@@ -1,14 +1,50 @@
-//! `rung move` command - Interactive branch navigation.
+//! `rung move` command - Interactive branch navigation and reordering.
 
-use super::utils::open_repo_and_state;
+use super::utils::{self, CommandContext};
 use crate::output;
+use crate::services::{ReorderPlan, ReorderService};
 use anyhow::{Context, Result, bail};
+use clap::ValueEnum;
 use inquire::Select;
+use serde::Serialize;
 
-/// Run the move command - interactive branch picker.
-pub fn run() -> Result<()> {
-    let (repo, state) = open_repo_and_state()?;
-    let current = repo.current_branch()?;
+/// Which way `rung move --reorder` moves the branch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ReorderDirection {
+    /// Swap with the parent, so the branch lands first.
+    Up,
+    /// Swap with the only child, so the branch lands after it.
+    Down,
+}
+
+/// JSON output for `rung move --reorder`.
+#[derive(Debug, Serialize)]
+struct ReorderOutput {
+    #[serde(flatten)]
+    plan: ReorderPlan,
+    dry_run: bool,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    updated_prs: Vec<u64>,
+}
+
+/// Run the move command - interactive branch picker, or `--reorder`.
+pub fn run(
+    json: bool,
+    reorder: Option<ReorderDirection>,
+    branch: Option<&str>,
+    dry_run: bool,
+) -> Result<()> {
+    if let Some(direction) = reorder {
+        return run_reorder(json, direction, branch, dry_run);
+    }
+    if json {
+        bail!("`rung move` is interactive - use `rung nxt --json` or `rung prv --json` instead");
+    }
+
+    let CommandContext { repo, state, .. } = CommandContext::open()?;
+    super::edit::finish_pending(&repo, &state, json)?;
+    // A detached HEAD has no current branch, but jumping to one is still fine
+    let current = repo.current_branch().ok();
     let stack = state.load_stack()?;
 
     if stack.is_empty() {
@@ -20,7 +56,11 @@ pub fn run() -> Result<()> {
         .branches
         .iter()
         .map(|b| {
-            let marker = if b.name == current { " ◀" } else { "" };
+            let marker = if current.as_deref() == Some(b.name.as_str()) {
+                output::glyphs(" ◀")
+            } else {
+                "".into()
+            };
             let pr = b.pr.map(|n| format!(" #{n}")).unwrap_or_default();
             format!("{}{}{}", b.name, pr, marker)
         })
@@ -30,7 +70,7 @@ pub fn run() -> Result<()> {
     let start_idx = stack
         .branches
         .iter()
-        .position(|b| b.name == current)
+        .position(|b| current.as_deref() == Some(b.name.as_str()))
         .unwrap_or(0);
 
     let selection = Select::new("Jump to branch:", options)
@@ -45,7 +85,7 @@ pub fn run() -> Result<()> {
         .next()
         .context("Invalid selection")?;
 
-    if branch_name == current {
+    if current.as_deref() == Some(branch_name) {
         output::info("Already on this branch");
     } else {
         repo.checkout(branch_name)?;
@@ -54,3 +94,88 @@ pub fn run() -> Result<()> {
 
     Ok(())
 }
+
+/// Swap a branch with its parent or child in a linear chain.
+fn run_reorder(
+    json: bool,
+    direction: ReorderDirection,
+    branch: Option<&str>,
+    dry_run: bool,
+) -> Result<()> {
+    let ctx = CommandContext::open()?;
+    let _lock = utils::lock_state(&ctx.repo, &ctx.state)?;
+    utils::ensure_on_branch(&ctx.repo)?;
+    if ctx.state.is_sync_in_progress() || ctx.state.is_restack_in_progress() {
+        bail!("A sync or restack is in progress. Complete or abort it first.");
+    }
+
+    let current = ctx.repo.current_branch()?;
+    let branch = branch.unwrap_or(&current);
+    let stack = ctx.state.load_stack()?;
+    let default_branch = ctx.state.default_branch()?;
+    let plan = match direction {
+        ReorderDirection::Up => ReorderPlan::new(&stack, branch, &default_branch)?,
+        ReorderDirection::Down => ReorderPlan::down(&stack, branch, &default_branch)?,
+    };
+
+    if !dry_run {
+        ctx.repo.require_clean()?;
+        for name in [&plan.branch, &plan.parent] {
+            ctx.repo.protected_branches().check(name, "rebase")?;
+            stack.check_unlocked(name, "rebase")?;
+        }
+    }
+
+    if !json {
+        output::info(&format!(
+            "{} '{}' below '{}' (onto '{}')",
+            if dry_run { "Would move" } else { "Moving" },
+            plan.branch,
+            plan.parent,
+            plan.grandparent
+        ));
+        for child in &plan.children {
+            output::detail(&format!("  → {child} would move onto '{}'", plan.parent));
+        }
+    }
+
+    let updated_prs = if dry_run {
+        vec![]
+    } else {
+        ReorderService::new(&ctx.repo).execute(&ctx.state, &plan)?;
+        let mut updated = super::restack::update_pr_base(
+            &ctx,
+            &plan.branch,
+            Some(&plan.parent),
+            &plan.grandparent,
+            json,
+        );
+        updated.extend(super::restack::update_pr_base(
+            &ctx,
+            &plan.parent,
+            Some(&plan.grandparent),
+            &plan.branch,
+            json,
+        ));
+        updated
+    };
+
+    if json {
+        let output = ReorderOutput {
+            plan,
+            dry_run,
+            updated_prs,
+        };
+        println!("{}", serde_json::to_string_pretty(&output)?);
+    } else if dry_run {
+        output::info("Dry run - no changes made");
+    } else {
+        output::success(&format!("Moved '{}' below '{}'", plan.branch, plan.parent));
+        if plan.children.is_empty() {
+            output::detail("  Run `rung submit` to push the reordered branches");
+        } else {
+            output::detail("  Run `rung sync` to rebase the branches above them and push");
+        }
+    }
+    Ok(())
+}
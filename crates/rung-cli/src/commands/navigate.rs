@@ -1,34 +1,69 @@
 //! `rung nxt` and `rung prv` commands - Navigate the stack.
 
-use super::utils::open_repo_and_state;
+use super::utils::{self, CommandContext};
 use crate::output;
 use anyhow::{Result, bail};
+use serde::Serialize;
+
+/// JSON output for navigation commands.
+#[derive(Debug, Serialize)]
+struct NavigateOutput {
+    from: String,
+    /// Branch checked out, or `None` if there was nowhere to go.
+    to: Option<String>,
+    /// Children to choose from when `nxt` can't pick one.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    candidates: Vec<String>,
+}
 
 /// Navigate to the next (child) branch in the stack.
-pub fn run_next() -> Result<()> {
-    let (repo, state) = open_repo_and_state()?;
+pub fn run_next(json: bool) -> Result<()> {
+    let CommandContext { repo, state, .. } = CommandContext::open()?;
+    super::edit::finish_pending(&repo, &state, json)?;
 
-    let current = repo.current_branch()?;
     let stack = state.load_stack()?;
+    let current = utils::resolve_current_branch(&repo, &stack)?;
 
     // Find children of current branch
     let children = stack.children_of(&current);
 
     match children.len() {
         0 => {
+            if json {
+                return output_json(&NavigateOutput {
+                    from: current,
+                    to: None,
+                    candidates: vec![],
+                });
+            }
             output::info(&format!("'{current}' has no children in the stack"));
             Ok(())
         }
         1 => {
             let child = &children[0].name;
             repo.checkout(child)?;
+            if json {
+                return output_json(&NavigateOutput {
+                    from: current,
+                    to: Some(child.to_string()),
+                    candidates: vec![],
+                });
+            }
             output::success(&format!("Switched to '{child}'"));
             Ok(())
         }
         _ => {
+            if json {
+                output_json(&NavigateOutput {
+                    candidates: children.iter().map(|c| c.name.to_string()).collect(),
+                    from: current,
+                    to: None,
+                })?;
+                bail!("Multiple children - use `git checkout <branch>` to pick one");
+            }
             output::warn(&format!("'{current}' has multiple children. Choose one:"));
             for child in children {
-                println!("  → {}", child.name);
+                println!("  {} {}", output::glyphs("→"), child.name);
             }
             bail!("Use `git checkout <branch>` to switch to the desired branch");
         }
@@ -36,17 +71,29 @@ pub fn run_next() -> Result<()> {
 }
 
 /// Navigate to the previous (parent) branch in the stack.
-pub fn run_prev() -> Result<()> {
-    let (repo, state) = open_repo_and_state()?;
+pub fn run_prev(json: bool) -> Result<()> {
+    let CommandContext { repo, state, .. } = CommandContext::open()?;
+    super::edit::finish_pending(&repo, &state, json)?;
 
-    let current = repo.current_branch()?;
     let stack = state.load_stack()?;
+    let current = utils::resolve_current_branch(&repo, &stack)?;
 
     // Find current branch in stack
     let branch = stack.find_branch(&current);
+    let parent = branch.and_then(|b| b.parent.as_ref());
 
-    if let Some(parent) = branch.and_then(|b| b.parent.as_ref()) {
+    if let Some(parent) = parent {
         repo.checkout(parent)?;
+    }
+    if json {
+        return output_json(&NavigateOutput {
+            from: current,
+            to: parent.map(ToString::to_string),
+            candidates: vec![],
+        });
+    }
+
+    if let Some(parent) = parent {
         output::success(&format!("Switched to '{parent}'"));
     } else {
         output::info(&format!(
@@ -55,3 +102,9 @@ pub fn run_prev() -> Result<()> {
     }
     Ok(())
 }
+
+/// Output navigation result as JSON.
+fn output_json(output: &NavigateOutput) -> Result<()> {
+    println!("{}", serde_json::to_string_pretty(output)?);
+    Ok(())
+}
@@ -0,0 +1,82 @@
+//! External plugin subcommands - `rung <name>` runs `rung-<name>` from PATH.
+//!
+//! Plugins get the repository and stack context through environment variables
+//! so they don't have to rediscover it:
+//!
+//! - `RUNG_VERSION`: version of the invoking rung binary
+//! - `RUNG_REPO_ROOT`: working tree root
+//! - `RUNG_DIR`: rung state directory (`.git/rung`)
+//! - `RUNG_CURRENT_BRANCH`: checked-out branch
+//! - `RUNG_PARENT_BRANCH`: stack parent of the current branch
+//!
+//! Variables that don't apply (outside a repository, detached HEAD, branch
+//! not in a stack) are left unset.
+
+use std::io::ErrorKind;
+use std::process::Command;
+
+use anyhow::{Result, bail};
+use rung_core::State;
+use rung_git::Repository;
+
+use crate::commands::utils::ExitStatus;
+
+/// Run the `rung-<name>` plugin with the remaining arguments.
+///
+/// A failing plugin ends rung with the plugin's exit code, so rung behaves
+/// transparently as the plugin's launcher.
+pub fn run(args: &[String]) -> Result<()> {
+    let Some((name, rest)) = args.split_first() else {
+        bail!("No subcommand given");
+    };
+
+    let program = format!("rung-{name}");
+    let mut cmd = Command::new(&program);
+    cmd.args(rest)
+        .env("RUNG_VERSION", env!("CARGO_PKG_VERSION"));
+    set_context_env(&mut cmd);
+
+    let status = match cmd.status() {
+        Ok(status) => status,
+        Err(e) if e.kind() == ErrorKind::NotFound => {
+            bail!("unrecognized subcommand '{name}' - no `{program}` plugin found on PATH");
+        }
+        Err(e) => bail!("Failed to run {program}: {e}"),
+    };
+
+    if !status.success() {
+        return Err(ExitStatus(status.code().unwrap_or(1)).into());
+    }
+    Ok(())
+}
+
+/// Pass repository and stack context to the plugin.
+fn set_context_env(cmd: &mut Command) {
+    let Ok(repo) = Repository::open_current() else {
+        return;
+    };
+    if let Some(workdir) = repo.workdir() {
+        cmd.env("RUNG_REPO_ROOT", workdir);
+    }
+
+    let current = repo.current_branch().ok();
+    if let Some(branch) = &current {
+        cmd.env("RUNG_CURRENT_BRANCH", branch);
+    }
+
+    let Ok(state) = State::from_git_dir(repo.common_dir()) else {
+        return;
+    };
+    if !state.is_initialized() {
+        return;
+    }
+    cmd.env("RUNG_DIR", state.rung_dir());
+
+    let parent = current.and_then(|branch| {
+        let stack = state.load_stack().ok()?;
+        stack.find_branch(&branch)?.parent.clone()
+    });
+    if let Some(parent) = parent {
+        cmd.env("RUNG_PARENT_BRANCH", parent.as_str());
+    }
+}
@@ -6,12 +6,14 @@
 
 use anyhow::{Context, Result, bail};
 use inquire::Select;
+use rung_core::sync::{ReconcileResult, ReparentedBranch};
 use rung_core::{DivergenceRecord, State};
 use serde::Serialize;
 
-use crate::commands::utils;
+use crate::commands::utils::{self, CommandContext};
+use crate::conflict::ConflictReport;
 use crate::output;
-use crate::services::{DivergenceInfo, RestackConfig, RestackError, RestackService};
+use crate::services::{DivergenceInfo, RestackConfig, RestackError, RestackService, SyncService};
 
 /// JSON output for restack command.
 #[derive(Debug, Serialize)]
@@ -24,7 +26,11 @@ struct RestackOutput {
     #[serde(skip_serializing_if = "Vec::is_empty")]
     branches_rebased: Vec<String>,
     #[serde(skip_serializing_if = "Vec::is_empty")]
+    branches_skipped: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
     diverged_branches: Vec<DivergenceInfoOutput>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    updated_prs: Vec<u64>,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -74,14 +80,18 @@ pub struct RestackOptions<'a> {
     pub dry_run: bool,
     pub continue_: bool,
     pub abort: bool,
+    /// `Some(descendants)` when `--skip` was given.
+    pub skip: Option<bool>,
     pub include_children: bool,
     pub force: bool,
 }
 
 /// Run the restack command.
 pub fn run(opts: &RestackOptions<'_>) -> Result<()> {
-    let (repo, state) = utils::open_repo_and_state()?;
-    let service = RestackService::new(&repo);
+    let ctx = CommandContext::open()?;
+    let CommandContext { repo, state, .. } = &ctx;
+    let _lock = utils::lock_state(repo, state)?;
+    let service = RestackService::new(repo);
 
     // Check for conflicting flags
     if opts.continue_ && opts.abort {
@@ -90,12 +100,21 @@ pub fn run(opts: &RestackOptions<'_>) -> Result<()> {
 
     // Handle abort
     if opts.abort {
-        return handle_abort(&service, &state, opts.json);
+        return handle_abort(&service, state, opts.json);
     }
 
     // Handle continue
     if opts.continue_ {
-        return handle_continue(&service, &state, opts.json);
+        return handle_continue(&ctx, &service, opts.json);
+    }
+
+    // Handle skip
+    if let Some(descendants) = opts.skip {
+        if !opts.json {
+            output::info("Skipping the conflicted branch and continuing restack...");
+        }
+        let result = service.skip(state, descendants);
+        return handle_restack_result(&ctx, result, opts.json);
     }
 
     // Check for existing restack in progress
@@ -103,7 +122,10 @@ pub fn run(opts: &RestackOptions<'_>) -> Result<()> {
         bail!("Restack already in progress - use --continue to resume or --abort to cancel");
     }
 
-    utils::ensure_on_branch(&repo)?;
+    // Refuse to start on top of an unfinished merge, cherry-pick, bisect, etc.
+    repo.require_no_operation()?;
+
+    utils::ensure_on_branch(repo)?;
 
     // Determine branch to restack
     let current = repo.current_branch()?;
@@ -126,10 +148,18 @@ pub fn run(opts: &RestackOptions<'_>) -> Result<()> {
     };
 
     // Create plan
-    let plan = service.create_plan(&state, &config)?;
+    let plan = service.create_plan(state, &config)?;
+
+    // A protected branch may be a new parent, but is never moved itself
+    repo.protected_branches().check(target_branch, "restack")?;
+    stack.check_unlocked(target_branch, "restack")?;
+    for branch in &plan.branches_to_rebase {
+        repo.protected_branches().check(branch, "rebase")?;
+        stack.check_unlocked(branch, "rebase")?;
+    }
 
     // Handle special cases that don't require a full rebase
-    if let Some(result) = handle_early_exit(opts, &state, target_branch, &new_parent, &plan) {
+    if let Some(result) = handle_early_exit(opts, &ctx, target_branch, &new_parent, &plan) {
         return result;
     }
 
@@ -142,16 +172,16 @@ pub fn run(opts: &RestackOptions<'_>) -> Result<()> {
     print_restack_start(opts, target_branch, &new_parent, &plan);
 
     // Execute restack
-    let _restack_state = service.execute(&state, &plan, &current)?;
-    let result = service.execute_restack_loop(&state, &current);
+    let _restack_state = service.execute(state, &plan, &current)?;
+    let result = service.execute_restack_loop(state, &current);
 
-    handle_restack_result(result, opts.json)
+    handle_restack_result(&ctx, result, opts.json)
 }
 
 /// Handle early exit cases: already-based, no-rebase-needed, dry-run.
 fn handle_early_exit(
     opts: &RestackOptions<'_>,
-    state: &State,
+    ctx: &CommandContext,
     target_branch: &str,
     new_parent: &str,
     plan: &crate::services::restack::RestackPlan,
@@ -165,7 +195,7 @@ fn handle_early_exit(
     if !plan.needs_rebase {
         return Some(output_no_rebase_needed(
             opts,
-            state,
+            ctx,
             target_branch,
             new_parent,
             plan,
@@ -193,7 +223,9 @@ fn output_already_based(
             old_parent: plan.old_parent.clone(),
             new_parent: plan.new_parent.clone(),
             branches_rebased: vec![],
+            branches_skipped: vec![],
             diverged_branches: vec![],
+            updated_prs: vec![],
         };
         println!("{}", serde_json::to_string_pretty(&output)?);
     } else {
@@ -208,16 +240,25 @@ fn output_already_based(
 /// Output when no rebase is needed (topology-only update).
 fn output_no_rebase_needed(
     opts: &RestackOptions<'_>,
-    state: &State,
+    ctx: &CommandContext,
     target_branch: &str,
     new_parent: &str,
     plan: &crate::services::restack::RestackPlan,
 ) -> Result<()> {
-    if !opts.dry_run {
-        let mut stack = state.load_stack()?;
+    let updated_prs = if opts.dry_run {
+        vec![]
+    } else {
+        let mut stack = ctx.state.load_stack()?;
         stack.reparent(target_branch, Some(new_parent))?;
-        state.save_stack(&stack)?;
-    }
+        ctx.state.save_stack(&stack)?;
+        update_pr_base(
+            ctx,
+            target_branch,
+            plan.old_parent.as_deref(),
+            new_parent,
+            opts.json,
+        )
+    };
 
     if opts.json {
         let output = RestackOutput {
@@ -230,7 +271,9 @@ fn output_no_rebase_needed(
             old_parent: plan.old_parent.clone(),
             new_parent: new_parent.to_string(),
             branches_rebased: vec![],
+            branches_skipped: vec![],
             diverged_branches: vec![],
+            updated_prs,
         };
         println!("{}", serde_json::to_string_pretty(&output)?);
     } else if opts.dry_run {
@@ -261,7 +304,9 @@ fn output_dry_run(
             old_parent: plan.old_parent.clone(),
             new_parent: new_parent.to_string(),
             branches_rebased: plan.branches_to_rebase.clone(),
+            branches_skipped: vec![],
             diverged_branches: vec![],
+            updated_prs: vec![],
         };
         println!("{}", serde_json::to_string_pretty(&output)?);
     } else {
@@ -299,7 +344,9 @@ fn check_divergence(
             old_parent: plan.old_parent.clone(),
             new_parent: new_parent.to_string(),
             branches_rebased: vec![],
+            branches_skipped: vec![],
             diverged_branches: diverged_output,
+            updated_prs: vec![],
         };
         println!("{}", serde_json::to_string_pretty(&output)?);
         return Err(anyhow::anyhow!("divergence_detected"));
@@ -341,11 +388,19 @@ fn print_restack_start(
 
 /// Handle the result of a restack operation.
 fn handle_restack_result(
+    ctx: &CommandContext,
     result: Result<crate::services::restack::RestackResult, RestackError>,
     json: bool,
 ) -> Result<()> {
     match result {
         Ok(result) => {
+            let updated_prs = update_pr_base(
+                ctx,
+                &result.target_branch,
+                result.old_parent.as_deref(),
+                &result.new_parent,
+                json,
+            );
             if json {
                 let diverged_output: Vec<DivergenceInfoOutput> = result
                     .diverged_branches
@@ -358,10 +413,14 @@ fn handle_restack_result(
                     old_parent: result.old_parent,
                     new_parent: result.new_parent,
                     branches_rebased: result.branches_rebased,
+                    branches_skipped: result.branches_skipped,
                     diverged_branches: diverged_output,
+                    updated_prs,
                 };
                 println!("{}", serde_json::to_string_pretty(&output)?);
-            } else if result.branches_rebased.len() > 1 {
+                return Ok(());
+            }
+            if result.branches_rebased.len() > 1 {
                 output::success(&format!(
                     "Restacked '{}' and {} descendant(s) onto '{}'",
                     result.target_branch,
@@ -374,10 +433,18 @@ fn handle_restack_result(
                     result.target_branch, result.new_parent
                 ));
             }
+            if !result.branches_skipped.is_empty() {
+                output::warn(&format!(
+                    "Skipped {} branch(es), left as before the restack: {}",
+                    result.branches_skipped.len(),
+                    result.branches_skipped.join(", ")
+                ));
+            }
             Ok(())
         }
         Err(RestackError::Conflict { branch, files }) => {
-            output_conflict(&files, json)?;
+            let report = ConflictReport::for_restack(&ctx.repo, &ctx.state, &branch, files);
+            output_conflict(&report, json)?;
             bail!("Rebase conflict in '{branch}' - resolve and run `rung restack --continue`");
         }
         Err(RestackError::Other(e)) => Err(e),
@@ -399,7 +466,9 @@ fn handle_abort<G: rung_git::GitOps>(
             old_parent: result.old_parent,
             new_parent: result.new_parent,
             branches_rebased: vec![],
+            branches_skipped: vec![],
             diverged_branches: vec![],
+            updated_prs: vec![],
         };
         println!("{}", serde_json::to_string_pretty(&output)?);
     } else {
@@ -411,43 +480,98 @@ fn handle_abort<G: rung_git::GitOps>(
 
 /// Handle --continue flag
 fn handle_continue<G: rung_git::GitOps>(
+    ctx: &CommandContext,
     service: &RestackService<'_, G>,
-    state: &State,
     json: bool,
 ) -> Result<()> {
     if !json {
         output::info("Continuing restack...");
     }
 
-    let result = service.continue_restack(state);
+    let result = service.continue_restack(&ctx.state);
 
     // Reuse handle_restack_result for consistent error handling
-    handle_restack_result(result, json)
+    handle_restack_result(ctx, result, json)
+}
+
+/// Point the restacked branch's PR at its new parent, as `rung sync` does
+/// for reparented branches.
+///
+/// Non-fatal: the restack already succeeded, so failures only warn and the
+/// next sync retries. Returns the PRs whose base was changed.
+pub fn update_pr_base(
+    ctx: &CommandContext,
+    branch: &str,
+    old_parent: Option<&str>,
+    new_parent: &str,
+    json: bool,
+) -> Vec<u64> {
+    let Some(pr_number) = ctx
+        .state
+        .load_stack()
+        .ok()
+        .and_then(|stack| stack.find_branch(branch).and_then(|b| b.pr))
+    else {
+        return vec![];
+    };
+
+    if utils::is_offline() {
+        if !json {
+            output::info(&format!(
+                "Offline - run `rung sync` to point PR #{pr_number} at '{new_parent}'"
+            ));
+        }
+        return vec![];
+    }
+
+    let reparented = ReparentedBranch {
+        name: branch.to_string(),
+        old_parent: old_parent.unwrap_or_default().to_string(),
+        new_parent: new_parent.to_string(),
+        pr_number: Some(pr_number),
+    };
+    match try_update_pr_base(ctx, reparented) {
+        Ok(updated) => {
+            if !json {
+                for pr in &updated {
+                    output::info(&format!("Updated PR #{pr} base to '{new_parent}'"));
+                }
+            }
+            updated
+        }
+        Err(e) => {
+            if !json {
+                output::warn(&format!(
+                    "Could not update PR #{pr_number} base: {e:#} - `rung sync` will retry"
+                ));
+            }
+            vec![]
+        }
+    }
+}
+
+fn try_update_pr_base(ctx: &CommandContext, reparented: ReparentedBranch) -> Result<Vec<u64>> {
+    let repo_id = ctx.remote_info()?.repo;
+    let service = SyncService::new(&ctx.repo, ctx.forge()?, repo_id);
+    let reconcile_result = ReconcileResult {
+        reparented: vec![reparented],
+        ..ReconcileResult::default()
+    };
+    ctx.runtime()?
+        .block_on(service.update_pr_bases(&reconcile_result))
 }
 
 /// Output conflict information
-fn output_conflict(files: &[String], json: bool) -> Result<()> {
+fn output_conflict(report: &ConflictReport, json: bool) -> Result<()> {
     if json {
         let output = serde_json::json!({
             "status": "conflict",
-            "conflict_files": files
+            "conflict_files": report.files,
+            "conflict": report,
         });
         println!("{}", serde_json::to_string_pretty(&output)?);
     } else {
-        output::error("Rebase conflict detected");
-        output::detail("Resolve conflicts, then run:");
-        output::detail("  git add <resolved-files>");
-        output::detail("  rung restack --continue");
-        output::detail("");
-        output::detail("Or abort and restore with:");
-        output::detail("  rung restack --abort");
-        if !files.is_empty() {
-            output::hr();
-            output::detail("Conflicting files:");
-            for file in files {
-                output::detail(&format!("  {file}"));
-            }
-        }
+        report.print();
     }
     Ok(())
 }
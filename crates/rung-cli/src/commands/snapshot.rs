@@ -0,0 +1,276 @@
+//! `rung snapshot` command - save and restore labeled snapshots of the stack.
+
+use std::collections::BTreeMap;
+
+use anyhow::{Result, bail};
+use chrono::Utc;
+use clap::Subcommand;
+use colored::Colorize;
+use rung_core::journal::{self, Journal, JournalRef};
+use rung_core::{Snapshot, State};
+use rung_git::Repository;
+use serde::Serialize;
+
+use crate::commands::utils::{self, CommandContext};
+use crate::output;
+
+/// Snapshot subcommands.
+#[derive(Debug, Subcommand)]
+pub enum SnapshotCommand {
+    /// Record the stack and the tip of every stack branch under a name.
+    Save {
+        /// Name for the snapshot, e.g. "before big rebase".
+        name: String,
+
+        /// Replace an existing snapshot with the same name.
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// List saved snapshots, oldest first.
+    #[command(alias = "ls")]
+    List,
+
+    /// Reset the stack branches and stack metadata to a snapshot.
+    Restore {
+        /// Snapshot to restore.
+        name: String,
+
+        /// Show what would be reset without changing anything.
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Delete a snapshot.
+    #[command(alias = "rm")]
+    Delete {
+        /// Snapshot to delete.
+        name: String,
+    },
+}
+
+/// JSON output for a snapshot in `save` and `list`.
+#[derive(Debug, Serialize)]
+struct SnapshotOutput<'a> {
+    name: &'a str,
+    created_at: String,
+    branches: &'a BTreeMap<String, String>,
+}
+
+impl<'a> From<&'a Snapshot> for SnapshotOutput<'a> {
+    fn from(snapshot: &'a Snapshot) -> Self {
+        Self {
+            name: &snapshot.name,
+            created_at: snapshot.created_at.to_rfc3339(),
+            branches: &snapshot.branches,
+        }
+    }
+}
+
+/// JSON output for `restore`.
+#[derive(Debug, Serialize)]
+struct RestoreOutput<'a> {
+    dry_run: bool,
+    name: &'a str,
+    branches: Vec<BranchReset>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    untracked: Vec<String>,
+}
+
+/// A stack branch a restore moves.
+#[derive(Debug, Serialize)]
+struct BranchReset {
+    name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    current: Option<String>,
+    restore_to: String,
+}
+
+/// Run a snapshot subcommand.
+pub fn run(json: bool, command: &SnapshotCommand) -> Result<()> {
+    let CommandContext { repo, state, .. } = CommandContext::open()?;
+    match command {
+        SnapshotCommand::Save { name, force } => save(&repo, &state, json, name, *force),
+        SnapshotCommand::List => list(&state, json),
+        SnapshotCommand::Restore { name, dry_run } => restore(&repo, &state, json, name, *dry_run),
+        SnapshotCommand::Delete { name } => {
+            state.delete_snapshot(name)?;
+            if json {
+                println!("{}", serde_json::json!({ "deleted": name }));
+            } else {
+                output::success(&format!("Deleted snapshot '{name}'"));
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Take a snapshot of the stack.
+fn save(repo: &Repository, state: &State, json: bool, name: &str, force: bool) -> Result<()> {
+    if state.has_snapshot(name) && !force {
+        bail!("A snapshot named '{name}' already exists - use --force to replace it");
+    }
+
+    let stack = state.load_stack()?;
+    let branches = stack
+        .branches
+        .iter()
+        .filter_map(|b| {
+            let tip = repo.branch_commit(&b.name).ok()?;
+            Some((b.name.to_string(), tip.to_string()))
+        })
+        .collect();
+    let snapshot = Snapshot {
+        name: name.to_string(),
+        created_at: Utc::now(),
+        branches,
+        stack,
+    };
+    state.save_snapshot(&snapshot)?;
+
+    if json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&SnapshotOutput::from(&snapshot))?
+        );
+    } else {
+        output::success(&format!(
+            "Saved snapshot '{name}' of {} branch{}",
+            snapshot.branches.len(),
+            if snapshot.branches.len() == 1 {
+                ""
+            } else {
+                "es"
+            }
+        ));
+    }
+    Ok(())
+}
+
+/// Print the saved snapshots.
+fn list(state: &State, json: bool) -> Result<()> {
+    let snapshots = state.list_snapshots()?;
+
+    if json {
+        let output: Vec<SnapshotOutput> = snapshots.iter().map(SnapshotOutput::from).collect();
+        println!("{}", serde_json::to_string_pretty(&output)?);
+        return Ok(());
+    }
+
+    if snapshots.is_empty() {
+        output::info("No snapshots - save one with `rung snapshot save <name>`");
+        return Ok(());
+    }
+    for snapshot in &snapshots {
+        output::info(&format!(
+            "{}  {}  {} branch{}",
+            snapshot.name.bold(),
+            snapshot
+                .created_at
+                .format("%Y-%m-%d %H:%M:%S UTC")
+                .to_string()
+                .dimmed(),
+            snapshot.branches.len(),
+            if snapshot.branches.len() == 1 {
+                ""
+            } else {
+                "es"
+            }
+        ));
+    }
+    Ok(())
+}
+
+/// Reset the stack branches and stack metadata to a snapshot.
+fn restore(repo: &Repository, state: &State, json: bool, name: &str, dry_run: bool) -> Result<()> {
+    let _lock = utils::lock_state(repo, state)?;
+    if state.is_sync_in_progress() || state.is_restack_in_progress() {
+        bail!("Finish or abort the operation in progress before restoring a snapshot");
+    }
+
+    let snapshot = state.load_snapshot(name)?;
+    let resets: Vec<BranchReset> = snapshot
+        .branches
+        .iter()
+        .filter_map(|(branch, sha)| {
+            let current = repo.branch_commit(branch).ok().map(|oid| oid.to_string());
+            (current.as_deref() != Some(sha.as_str())).then(|| BranchReset {
+                name: branch.clone(),
+                current,
+                restore_to: sha.clone(),
+            })
+        })
+        .collect();
+    let untracked: Vec<String> = state
+        .load_stack()?
+        .branches
+        .iter()
+        .filter(|b| snapshot.stack.find_branch(&b.name).is_none())
+        .map(|b| b.name.to_string())
+        .collect();
+
+    if !dry_run {
+        // Resetting the checked-out branch also resets the working tree
+        let current = repo.current_branch().ok();
+        if resets
+            .iter()
+            .any(|r| current.as_deref() == Some(r.name.as_str()))
+        {
+            repo.require_clean()?;
+        }
+        let refs = resets
+            .iter()
+            .map(|r| JournalRef {
+                branch: r.name.clone(),
+                old: r.current.clone(),
+                new: Some(r.restore_to.clone()),
+            })
+            .collect();
+        journal::apply(repo, state, &Journal::replay("snapshot restore", refs))?;
+        state.save_stack(&snapshot.stack)?;
+    }
+
+    if json {
+        let output = RestoreOutput {
+            dry_run,
+            name: &snapshot.name,
+            branches: resets,
+            untracked,
+        };
+        println!("{}", serde_json::to_string_pretty(&output)?);
+        return Ok(());
+    }
+
+    if dry_run {
+        output::info(&format!(
+            "Would restore snapshot '{}' from {}:",
+            snapshot.name,
+            snapshot.created_at.format("%Y-%m-%d %H:%M:%S UTC")
+        ));
+    } else {
+        output::success(&format!("Restored snapshot '{}'", snapshot.name));
+    }
+    for reset in &resets {
+        let current = reset.current.as_deref().map_or("(deleted)", short);
+        output::detail(&format!(
+            "  {}: {current} → {}",
+            reset.name,
+            short(&reset.restore_to)
+        ));
+    }
+    if resets.is_empty() {
+        output::detail("  All branches already match the snapshot");
+    }
+    if !untracked.is_empty() {
+        output::warn(&format!(
+            "No longer tracked (the branches are kept): {}",
+            untracked.join(", ")
+        ));
+    }
+    Ok(())
+}
+
+/// Abbreviate a commit SHA for display.
+fn short(sha: &str) -> &str {
+    sha.get(..7).unwrap_or(sha)
+}
@@ -1,7 +1,9 @@
 //! `rung split` command - Split a branch into multiple stacked branches.
 
+use std::io::IsTerminal;
+
 use anyhow::{Context, Result, bail};
-use inquire::{MultiSelect, Text};
+use inquire::{CustomType, MultiSelect, Select, Text};
 use rung_core::{SplitPoint, State};
 use rung_git::Repository;
 
@@ -20,17 +22,16 @@ pub struct SplitOptions<'a> {
     pub json: bool,
     /// Branch to split. Defaults to current branch.
     pub branch: Option<&'a str>,
+    /// Split evenly into this many branches.
+    pub into: Option<usize>,
+    /// Branch from the split that the source's children move onto.
+    pub children_follow: Option<&'a str>,
 }
 
 /// Run the split command.
 pub fn run(opts: &SplitOptions<'_>) -> Result<()> {
-    let repo = Repository::open_current().context("Not inside a git repository")?;
-    let workdir = repo.workdir().context("Cannot run in bare repository")?;
-    let state = State::new(workdir)?;
-
-    if !state.is_initialized() {
-        bail!("Rung not initialized - run `rung init` first");
-    }
+    let utils::CommandContext { repo, state, .. } = utils::CommandContext::open()?;
+    let _lock = utils::lock_state(&repo, &state)?;
 
     let service = SplitService::new(&repo);
 
@@ -73,6 +74,17 @@ pub fn run(opts: &SplitOptions<'_>) -> Result<()> {
         bail!("Only one commit on branch - nothing to split");
     }
 
+    if let Some(parts) = opts.into {
+        let Some(mut split_config) =
+            select_even_split(&repo, &analysis, branch_name, parts, opts.dry_run)?
+        else {
+            return Ok(());
+        };
+        split_config.children_follow =
+            choose_children_parent(&state, &split_config, opts.children_follow)?;
+        return execute_split(&service, &state, &split_config);
+    }
+
     if opts.dry_run {
         output::info(&format!(
             "Would split '{}' ({} commits) into multiple branches",
@@ -87,19 +99,29 @@ pub fn run(opts: &SplitOptions<'_>) -> Result<()> {
     }
 
     // Phase 3: Interactive commit selection UI
-    let split_config = select_split_points(&analysis, branch_name)?;
+    let mut split_config = select_split_points(&analysis, branch_name)?;
 
     if split_config.split_points.is_empty() {
         output::info("No split points selected - nothing to do");
         return Ok(());
     }
+    split_config.children_follow =
+        choose_children_parent(&state, &split_config, opts.children_follow)?;
 
     output::info(&format!(
         "Will create {} new branch(es) from '{}'",
         split_config.split_points.len(),
         branch_name
     ));
+    execute_split(&service, &state, &split_config)
+}
 
+/// Create the branches for a split and report them.
+fn execute_split(
+    service: &SplitService<'_>,
+    state: &State,
+    split_config: &SplitConfig,
+) -> Result<()> {
     for point in &split_config.split_points {
         output::detail(&format!(
             "  {} → branch '{}'",
@@ -109,7 +131,7 @@ pub fn run(opts: &SplitOptions<'_>) -> Result<()> {
     }
 
     // Execute the split
-    let result = service.execute(&state, &split_config)?;
+    let result = service.execute(state, split_config)?;
 
     output::success(&format!(
         "Split complete: created {} branch(es)",
@@ -120,9 +142,71 @@ pub fn run(opts: &SplitOptions<'_>) -> Result<()> {
         output::detail(&format!("  • {branch}"));
     }
 
+    if let Some(follow) = &split_config.children_follow
+        && !result.children_moved.is_empty()
+    {
+        output::info(&format!(
+            "Moved {} onto '{follow}' - run `rung sync` to rebase",
+            result.children_moved.join(", ")
+        ));
+    }
+
     Ok(())
 }
 
+/// Pick the branch the source's children should follow after the split.
+///
+/// Returns `None` to leave them on the source branch, which stays at the top
+/// of the split. Prompts when interactive and no `--children-follow` is given.
+fn choose_children_parent(
+    state: &State,
+    config: &SplitConfig,
+    requested: Option<&str>,
+) -> Result<Option<String>> {
+    let stack = state.load_stack()?;
+    let children: Vec<String> = stack
+        .children_of(&config.source_branch)
+        .iter()
+        .map(|child| child.name.to_string())
+        .collect();
+    let candidates: Vec<&str> = config
+        .split_points
+        .iter()
+        .map(|point| point.branch_name.as_str())
+        .chain(std::iter::once(config.source_branch.as_str()))
+        .collect();
+
+    if let Some(requested) = requested {
+        if !candidates.contains(&requested) {
+            bail!(
+                "--children-follow must name a branch from the split: {}",
+                candidates.join(", ")
+            );
+        }
+        if children.is_empty() {
+            output::warn(&format!(
+                "'{}' has no children - ignoring --children-follow",
+                config.source_branch
+            ));
+            return Ok(None);
+        }
+        return Ok((requested != config.source_branch).then(|| requested.to_string()));
+    }
+
+    if children.is_empty() || !std::io::stdin().is_terminal() {
+        return Ok(None);
+    }
+
+    let choice = Select::new(
+        &format!("Which branch should {} follow?", children.join(", ")),
+        candidates.clone(),
+    )
+    .with_starting_cursor(candidates.len() - 1)
+    .prompt()
+    .context("Selection cancelled")?;
+    Ok((choice != config.source_branch).then(|| choice.to_string()))
+}
+
 /// Handle --abort flag.
 fn handle_abort(service: &SplitService<'_>, state: &State, _json: bool) -> Result<()> {
     service.abort(state)?;
@@ -157,6 +241,7 @@ fn select_split_points(analysis: &SplitAnalysis, source_branch: &str) -> Result<
             source_branch: source_branch.to_string(),
             parent_branch: analysis.parent_branch.clone(),
             split_points: vec![],
+            children_follow: None,
         });
     }
 
@@ -196,9 +281,152 @@ fn select_split_points(analysis: &SplitAnalysis, source_branch: &str) -> Result<
         source_branch: source_branch.to_string(),
         parent_branch: analysis.parent_branch.clone(),
         split_points,
+        children_follow: None,
     })
 }
 
+/// Partition the branch into `parts` even groups, preview them, and let the
+/// user nudge the boundaries before splitting.
+///
+/// Returns `None` for `--dry-run` or when the user cancels.
+fn select_even_split(
+    repo: &Repository,
+    analysis: &SplitAnalysis,
+    source_branch: &str,
+    parts: usize,
+    dry_run: bool,
+) -> Result<Option<SplitConfig>> {
+    let mut boundaries = SplitService::even_boundaries(analysis.commits.len(), parts)?;
+    let interactive = std::io::stdin().is_terminal();
+
+    loop {
+        let config = even_split_config(repo, analysis, source_branch, &boundaries);
+        print_even_split(analysis, source_branch, &boundaries, &config);
+
+        if dry_run {
+            return Ok(None);
+        }
+        if !interactive {
+            return Ok(Some(config));
+        }
+
+        let choice = Select::new(
+            "Split like this?",
+            vec!["Split", "Adjust boundaries", "Cancel"],
+        )
+        .prompt()
+        .context("Selection cancelled")?;
+        match choice {
+            "Split" => return Ok(Some(config)),
+            "Adjust boundaries" => boundaries = adjust_boundaries(analysis, &boundaries)?,
+            _ => {
+                output::info("Split cancelled - nothing changed");
+                return Ok(None);
+            }
+        }
+    }
+}
+
+/// Build the split config for the given boundaries, naming each new branch
+/// after its last commit.
+fn even_split_config(
+    repo: &Repository,
+    analysis: &SplitAnalysis,
+    source_branch: &str,
+    boundaries: &[usize],
+) -> SplitConfig {
+    let mut split_points: Vec<SplitPoint> = Vec::new();
+    for (idx, &boundary) in boundaries.iter().enumerate() {
+        let commit = &analysis.commits[boundary];
+        let mut name = SplitService::suggest_branch_name(&commit.summary, source_branch, idx);
+        let taken = |name: &str| {
+            name == source_branch
+                || repo.branch_exists(name)
+                || split_points.iter().any(|p| p.branch_name == name)
+        };
+        if taken(&name) {
+            name = format!("{source_branch}-part-{}", idx + 1);
+        }
+        split_points.push(SplitPoint {
+            commit_sha: commit.oid.clone(),
+            message: commit.summary.clone(),
+            branch_name: name,
+        });
+    }
+
+    SplitConfig {
+        source_branch: source_branch.to_string(),
+        parent_branch: analysis.parent_branch.clone(),
+        split_points,
+        children_follow: None,
+    }
+}
+
+/// Print the commits of each part of an even split.
+fn print_even_split(
+    analysis: &SplitAnalysis,
+    source_branch: &str,
+    boundaries: &[usize],
+    config: &SplitConfig,
+) {
+    let names = config
+        .split_points
+        .iter()
+        .map(|p| p.branch_name.as_str())
+        .chain(std::iter::once(source_branch));
+    let ends = boundaries
+        .iter()
+        .copied()
+        .chain(std::iter::once(analysis.commits.len() - 1));
+
+    output::info(&format!(
+        "Split '{source_branch}' ({} commits) into {} branches:",
+        analysis.commits.len(),
+        boundaries.len() + 1
+    ));
+    let mut start = 0;
+    for (name, end) in names.zip(ends) {
+        output::detail(&format!("  {name} ({} commit(s))", end + 1 - start));
+        for (i, commit) in analysis.commits[start..=end].iter().enumerate() {
+            output::detail(&format!(
+                "    [{}/{}] {} {}",
+                start + i + 1,
+                analysis.commits.len(),
+                commit.short_sha,
+                commit.summary
+            ));
+        }
+        start = end + 1;
+    }
+}
+
+/// Ask for each boundary in turn, keeping every part non-empty.
+fn adjust_boundaries(analysis: &SplitAnalysis, boundaries: &[usize]) -> Result<Vec<usize>> {
+    let count = analysis.commits.len();
+    let mut adjusted: Vec<usize> = Vec::with_capacity(boundaries.len());
+    for (k, &boundary) in boundaries.iter().enumerate() {
+        // 1-based commit positions, as shown in the preview
+        let min = adjusted.last().map_or(1, |prev| prev + 2);
+        let max = count - (boundaries.len() - k);
+        let position = CustomType::<usize>::new(&format!("Part {} ends after commit:", k + 1))
+            .with_default((boundary + 1).clamp(min, max))
+            .with_help_message(&format!("A position from {min} to {max}"))
+            .with_validator(move |p: &usize| {
+                Ok(if (min..=max).contains(p) {
+                    inquire::validator::Validation::Valid
+                } else {
+                    inquire::validator::Validation::Invalid(
+                        format!("Pick a position from {min} to {max}").into(),
+                    )
+                })
+            })
+            .prompt()
+            .context("Boundary input cancelled")?;
+        adjusted.push(position - 1);
+    }
+    Ok(adjusted)
+}
+
 /// Truncate a string to a maximum length, adding "..." if truncated.
 fn truncate(s: &str, max_len: usize) -> String {
     if s.chars().count() <= max_len {
@@ -1,53 +1,53 @@
 //! `rung status` command - Display the current stack status.
 
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 
-use anyhow::{Context, Result, bail};
+use anyhow::Result;
+use chrono::{DateTime, TimeDelta, Utc};
 use colored::Colorize;
-use rung_core::State;
-use rung_git::Repository;
-use rung_github::{Auth, ForgeApi, PullRequestState};
+use rung_core::PrCache;
+use rung_git::WorktreeStatus;
+use rung_github::{ForgeApi, PullRequestState};
 
-use crate::forge::Forge;
+use crate::commands::utils::{self, CommandContext};
 use serde::Serialize;
 
 use crate::output::{self, PrStatus};
-use crate::services::{BranchStatusInfo, RemoteDivergenceInfo, StatusService};
+use crate::services::{BranchStatusInfo, RemoteDivergenceInfo, StatusFilter, StatusService};
 
 /// Run the status command.
-pub fn run(json: bool, fetch: bool) -> Result<()> {
-    // Open repository
-    let repo = Repository::open_current().context("Not inside a git repository")?;
-
-    // Get state manager
-    let workdir = repo.workdir().context("Cannot run in bare repository")?;
-    let state = State::new(workdir)?;
-
-    // Ensure initialized
-    if !state.is_initialized() {
-        bail!("Rung not initialized - run `rung init` first");
-    }
+pub fn run(json: bool, fetch: bool, filter: StatusFilter) -> Result<()> {
+    let ctx = CommandContext::open()?;
 
     // Load stack
-    let stack = state.load_stack()?;
+    let stack = ctx.state.load_stack()?;
 
     // Create service
-    let service = StatusService::new(&repo, &stack);
+    let service = StatusService::new(&ctx.repo, &stack);
+
+    let mut fetched_at = last_fetched(&ctx);
+    let fetch = fetch || auto_fetch_due(&ctx, fetched_at, json)?;
 
     // Fetch latest from remote if requested
-    if fetch {
+    if fetch && !utils::is_offline() {
         if !json {
             output::info("Fetching from remote...");
         }
-        service
-            .fetch_remote()
-            .context("Failed to fetch from remote")?;
+        match service.fetch_remote() {
+            Ok(()) => {
+                // Only feeds the freshness indicator, so failing to record isn't fatal
+                let _ = ctx.state.record_fetch(ctx.repo.remote());
+                fetched_at = Some(Utc::now());
+            }
+            Err(e) if utils::is_network_error(&e) => utils::go_offline(json),
+            Err(e) => return Err(e.context("Failed to fetch from remote")),
+        }
     }
 
     // Compute status
-    let status = service.compute_status()?;
+    let status = service.compute_status(&filter)?;
 
-    if status.is_empty() {
+    if status.is_empty() && status.hidden == 0 {
         if json {
             println!("{}", serde_json::to_string_pretty(&JsonOutput::empty())?);
         } else {
@@ -57,75 +57,203 @@ pub fn run(json: bool, fetch: bool) -> Result<()> {
     }
 
     // Fetch PR statuses if requested (best-effort - don't fail status command on GitHub errors)
-    let mut pr_cache = HashMap::new();
-    if fetch && let Err(e) = fetch_pr_statuses(&repo, &stack, &mut pr_cache, json) {
-        if json {
-            eprintln!("Warning: Could not fetch PR statuses: {e}");
-        } else {
-            output::warn(&format!("Could not fetch PR statuses: {e}"));
+    let mut pr_states = BTreeMap::new();
+    let mut reviewed_at = BTreeMap::new();
+    if fetch && !utils::is_offline() {
+        match fetch_pr_statuses(&ctx, &stack, &mut pr_states, json) {
+            Ok(activity) => reviewed_at = activity,
+            Err(e) => warn_pr_fetch_failed(&e, json),
         }
     }
 
-    // Enrich branches with PR status info
+    // Offline, fall back to the statuses from the last successful fetch
+    let mut cached_at = None;
+    if utils::is_offline()
+        && let Ok(Some(cache)) = ctx.state.load_pr_cache()
+    {
+        cached_at = Some(cache.fetched_at);
+        pr_states = cache.prs;
+    }
+
+    // Enrich branches with PR status info and staleness
+    let stale_after = ctx.state.load_config()?.status.stale_after();
+    let now = Utc::now();
     let branches_with_pr_status: Vec<BranchWithPrStatus> = status
         .branches
         .into_iter()
         .map(|branch| {
-            let (pr_state, display_status) = branch.pr.map_or((None, None), |pr_num| {
-                pr_cache.get(&pr_num).map_or((None, None), |pr| {
-                    let status = match (pr.state, pr.draft) {
-                        (PullRequestState::Merged, _) => PrStatus::Merged,
-                        (PullRequestState::Closed, _) => PrStatus::Closed,
-                        (_, true) => PrStatus::Draft,
-                        _ => PrStatus::Open,
-                    };
-                    let pr_state = match status {
-                        PrStatus::Open => "open",
-                        PrStatus::Draft => "draft",
-                        PrStatus::Merged => "merged",
-                        PrStatus::Closed => "closed",
-                    };
-                    (Some(pr_state.to_string()), Some(status))
-                })
-            });
+            let pr_state = branch.pr.and_then(|pr_num| pr_states.get(&pr_num).cloned());
+            let display_status = pr_state.as_deref().and_then(parse_pr_state);
+            let last_commit_at = ctx
+                .repo
+                .branch_commit(&branch.name)
+                .and_then(|oid| ctx.repo.commit_author_time(oid))
+                .ok()
+                .map(DateTime::<Utc>::from);
+            let review_idle_since = branch
+                .pr
+                .and_then(|pr_num| reviewed_at.get(&pr_num).copied())
+                .filter(|&at| is_stale(at, now, stale_after));
             BranchWithPrStatus {
+                stale: last_commit_at.is_some_and(|at| is_stale(at, now, stale_after)),
                 info: branch,
                 pr_state,
                 display_status,
+                last_commit_at,
+                review_idle_since,
             }
         })
         .collect();
 
+    // Best-effort: a status that can't be read just leaves the markers out
+    let worktree = ctx.repo.worktree_status().ok();
+
     // Output
     if json {
-        let output = JsonOutput::from_branches(&branches_with_pr_status, status.current_branch);
+        let mut output =
+            JsonOutput::from_branches(&branches_with_pr_status, status.current_branch, cached_at);
+        output.remote_fetched_at = fetched_at;
+        output.worktree = worktree.map(JsonWorktree::from);
+        output.hidden = status.hidden;
         println!("{}", serde_json::to_string_pretty(&output)?);
     } else {
-        print_tree(&branches_with_pr_status);
+        print_tree(&branches_with_pr_status, worktree.as_ref());
+        print_footer(
+            &ctx,
+            status.hidden,
+            worktree.as_ref(),
+            fetched_at,
+            cached_at,
+        );
     }
 
     Ok(())
 }
 
-/// Fetch PR statuses from GitHub (best-effort).
+/// Lines below the tree: hidden branches, uncommitted work, remote freshness.
+fn print_footer(
+    ctx: &CommandContext,
+    hidden: usize,
+    worktree: Option<&WorktreeStatus>,
+    fetched_at: Option<DateTime<Utc>>,
+    cached_at: Option<DateTime<Utc>>,
+) {
+    if hidden > 0 {
+        output::detail(&format!(
+            "  {} more {} hidden - run `rung status` without --chain/--depth to see them",
+            hidden,
+            if hidden == 1 { "branch" } else { "branches" }
+        ));
+    }
+    if let Some(summary) = worktree.and_then(worktree_summary) {
+        output::detail(&format!("  {summary}"));
+    }
+    if let Some(at) = fetched_at {
+        output::detail(&format!(
+            "  Remote data as of {}",
+            format_age(Utc::now() - at)
+        ));
+    } else if ctx.repo.origin_url().is_ok() {
+        output::detail("  Remote data never fetched - run `rung status --fetch`");
+    }
+    if utils::is_offline() {
+        let as_of = cached_at.map_or_else(
+            || "unavailable".to_string(),
+            |at| {
+                format!(
+                    "as of {}",
+                    at.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M")
+                )
+            },
+        );
+        output::warn(&format!(
+            "Offline - remote info may be stale (PR status {as_of})"
+        ));
+    }
+    if ctx.repo.head_detached().unwrap_or(false) {
+        output::info("HEAD is detached - checkout a branch to modify the stack");
+    }
+}
+
+/// When the remote was last fetched, by rung or by a plain `git fetch`.
+fn last_fetched(ctx: &CommandContext) -> Option<DateTime<Utc>> {
+    let by_rung = ctx.state.last_fetch(ctx.repo.remote()).ok().flatten();
+    let by_git = ctx
+        .repo
+        .last_fetched(ctx.repo.remote())
+        .map(DateTime::<Utc>::from);
+    by_rung.max(by_git)
+}
+
+/// Whether `status.auto_fetch` asks for a fetch because the remote data is
+/// older than the configured age.
+fn auto_fetch_due(
+    ctx: &CommandContext,
+    fetched_at: Option<DateTime<Utc>>,
+    json: bool,
+) -> Result<bool> {
+    let config = ctx.state.load_config()?;
+    let Some(max_age) = config.status.auto_fetch_after() else {
+        return Ok(false);
+    };
+    if ctx.repo.origin_url().is_err() {
+        return Ok(false);
+    }
+    let due = fetched_at.is_none_or(|at| Utc::now() - at > max_age);
+    if due && !json {
+        output::detail(&format!(
+            "Remote data is older than {} (status.auto_fetch)",
+            config.status.auto_fetch.unwrap_or_default()
+        ));
+    }
+    Ok(due)
+}
+
+/// Report PR statuses that couldn't be fetched; status carries on without them.
+fn warn_pr_fetch_failed(e: &anyhow::Error, json: bool) {
+    if utils::is_network_error(e) {
+        utils::go_offline(json);
+    } else if json {
+        eprintln!("Warning: Could not fetch PR statuses: {e}");
+    } else {
+        output::warn(&format!("Could not fetch PR statuses: {e}"));
+    }
+}
+
+/// Whether `at` is further back than `stale_after` (never, when unset).
+fn is_stale(at: DateTime<Utc>, now: DateTime<Utc>, stale_after: Option<TimeDelta>) -> bool {
+    stale_after.is_some_and(|max| now - at > max)
+}
+
+/// Human-readable age, e.g. `5m ago`.
+fn format_age(age: TimeDelta) -> String {
+    match age {
+        _ if age.num_minutes() < 1 => "just now".to_string(),
+        _ if age.num_hours() < 1 => format!("{}m ago", age.num_minutes()),
+        _ if age.num_hours() < 48 => format!("{}h ago", age.num_hours()),
+        _ => format!("{}d ago", age.num_days()),
+    }
+}
+
+/// Fetch PR statuses from GitHub (best-effort), caching them for offline use.
+///
+/// Returns when each open, ready-for-review PR was last reviewed, or opened
+/// if it hasn't been reviewed yet.
 fn fetch_pr_statuses(
-    repo: &Repository,
+    ctx: &CommandContext,
     stack: &rung_core::Stack,
-    pr_cache: &mut HashMap<u64, rung_github::PullRequest>,
+    pr_states: &mut BTreeMap<u64, String>,
     json: bool,
-) -> Result<()> {
+) -> Result<BTreeMap<u64, DateTime<Utc>>> {
     // Early return if no PRs to fetch
     let pr_numbers: Vec<u64> = stack.branches.iter().filter_map(|b| b.pr).collect();
     if pr_numbers.is_empty() {
-        return Ok(());
+        return Ok(BTreeMap::new());
     }
 
-    let origin_url = repo.origin_url().context("No origin remote configured")?;
-    let rung_forge::RemoteInfo { repo: repo_id, .. } =
-        rung_forge::parse_remote(&origin_url).context("Could not parse forge remote URL")?;
+    let repo_id = ctx.remote_info()?.repo;
 
-    let client = Forge::for_remote(&origin_url, &Auth::auto())?;
-    let rt = tokio::runtime::Runtime::new()?;
+    let client = ctx.forge()?;
 
     if !json {
         let label = if pr_numbers.len() == 1 { "PR" } else { "PRs" };
@@ -134,26 +262,116 @@ fn fetch_pr_statuses(
             pr_numbers.len(),
         ));
     }
-    *pr_cache = rt.block_on(client.get_prs_batch(&repo_id, &pr_numbers))?;
-    Ok(())
+    let prs = ctx
+        .runtime()?
+        .block_on(client.get_prs_batch(&repo_id, &pr_numbers))?;
+    let reviewed_at = prs
+        .iter()
+        .filter(|(_, pr)| pr.state == PullRequestState::Open && !pr.draft)
+        .filter_map(|(&number, pr)| Some((number, pr.last_reviewed_at.or(pr.created_at)?)))
+        .collect();
+    *pr_states = prs
+        .into_iter()
+        .map(|(number, pr)| {
+            let state = match (pr.state, pr.draft) {
+                (PullRequestState::Merged, _) => "merged",
+                (PullRequestState::Closed, _) => "closed",
+                (_, true) => "draft",
+                _ => "open",
+            };
+            (number, state.to_string())
+        })
+        .collect();
+
+    // A stale cache is only a fallback, so failing to write it isn't fatal.
+    // Check results are tied to a commit, so the ones `rung log` fetched stay.
+    let checks = ctx
+        .state
+        .load_pr_cache()
+        .ok()
+        .flatten()
+        .map(|cache| cache.checks)
+        .unwrap_or_default();
+    let _ = ctx.state.save_pr_cache(&PrCache {
+        fetched_at: Utc::now(),
+        prs: pr_states.clone(),
+        checks,
+    });
+    Ok(reviewed_at)
+}
+
+/// Display status for a PR state string.
+fn parse_pr_state(state: &str) -> Option<PrStatus> {
+    match state {
+        "open" => Some(PrStatus::Open),
+        "draft" => Some(PrStatus::Draft),
+        "merged" => Some(PrStatus::Merged),
+        "closed" => Some(PrStatus::Closed),
+        _ => None,
+    }
+}
+
+/// Marker for uncommitted work on the current branch: `+` staged, `*` modified.
+fn worktree_marker(worktree: &WorktreeStatus) -> Option<String> {
+    if worktree.is_clean() {
+        return None;
+    }
+    let staged = if worktree.staged > 0 { "+" } else { "" };
+    let modified = if worktree.modified > 0 { "*" } else { "" };
+    Some(format!("{}{}", staged.green(), modified.yellow()))
+}
+
+/// One-line count of uncommitted files, if there are any.
+fn worktree_summary(worktree: &WorktreeStatus) -> Option<String> {
+    let parts: Vec<String> = [
+        (worktree.staged, "staged"),
+        (worktree.modified, "modified"),
+        (worktree.untracked, "untracked"),
+    ]
+    .into_iter()
+    .filter(|(count, _)| *count > 0)
+    .map(|(count, label)| format!("{count} {label}"))
+    .collect();
+    if parts.is_empty() {
+        return None;
+    }
+    let hint = if worktree.is_clean() {
+        ""
+    } else {
+        " - commit or stash before `rung sync`"
+    };
+    Some(format!("Uncommitted: {}{hint}", parts.join(", ")))
 }
 
 /// Print a tree view of the stack.
-fn print_tree(branches: &[BranchWithPrStatus]) {
+fn print_tree(branches: &[BranchWithPrStatus], worktree: Option<&WorktreeStatus>) {
     println!();
     println!("  {}", "Stack".bold());
     output::hr();
 
-    for branch in branches {
+    let nodes: Vec<(&str, Option<&str>)> = branches
+        .iter()
+        .map(|b| (b.info.name.as_str(), b.info.parent.as_deref()))
+        .collect();
+    for row in output::tree_rows(&nodes) {
+        let branch = &branches[row.index];
         let state_icon = output::state_indicator(&branch.info.state);
-        let name = output::branch_name(&branch.info.name, branch.info.is_current);
+        let mut name = output::branch_name(&branch.info.name, branch.info.is_current);
+        if branch.info.is_current
+            && let Some(marker) = worktree.and_then(worktree_marker)
+        {
+            name = format!("{name} {marker}");
+        }
+        if branch.info.locked {
+            name = format!("{name} {}", "[locked]".cyan());
+        }
         let pr = output::pr_ref(branch.info.pr, branch.display_status);
 
         let parent_info = branch
             .info
             .parent
             .as_ref()
-            .map(|p| format!(" ← {}", p.dimmed()))
+            .map(|p| format!(" {} {}", output::glyphs("←"), p.dimmed()))
             .unwrap_or_default();
 
         // Add remote divergence indicator if present
@@ -165,7 +383,22 @@ fn print_tree(branches: &[BranchWithPrStatus]) {
             .map(|s| format!(" {s}"))
             .unwrap_or_default();
 
-        println!("  {state_icon} {name} {pr}{parent_info}{divergence}");
+        let age = branch
+            .last_commit_at
+            .map(|at| {
+                let age = format_age(Utc::now() - at);
+                if branch.stale {
+                    format!(" {}", age.yellow())
+                } else {
+                    format!(" {}", age.dimmed())
+                }
+            })
+            .unwrap_or_default();
+
+        println!(
+            "  {}{state_icon} {name} {pr}{parent_info}{divergence}{age}",
+            output::glyphs(&row.prefix).dimmed()
+        );
     }
 
     output::hr();
@@ -174,9 +407,9 @@ fn print_tree(branches: &[BranchWithPrStatus]) {
     // Legend
     println!(
         "  {} synced  {} needs sync  {} conflict",
-        "●".green(),
-        "●".yellow(),
-        "●".red()
+        output::glyphs("●").green(),
+        output::glyphs("●").yellow(),
+        output::glyphs("●").red()
     );
     println!();
 
@@ -205,22 +438,65 @@ fn print_tree(branches: &[BranchWithPrStatus]) {
         output::detail("  Run `rung submit --force` to safely update (uses --force-with-lease)");
         println!();
     }
-}
 
-/// Format remote divergence info as a compact indicator.
-fn remote_divergence_indicator(divergence: &RemoteDivergenceInfo) -> Option<String> {
-    match divergence {
-        RemoteDivergenceInfo::InSync | RemoteDivergenceInfo::NoRemote => None,
-        RemoteDivergenceInfo::Ahead { commits } => {
-            Some(format!("({commits}↑)").dimmed().to_string())
+    let gone: Vec<_> = branches
+        .iter()
+        .filter(|b| matches!(b.info.remote_divergence, Some(RemoteDivergenceInfo::Gone)))
+        .collect();
+    if !gone.is_empty() {
+        for b in &gone {
+            output::warn(&format!("{}'s remote branch was deleted", b.info.name));
         }
-        RemoteDivergenceInfo::Behind { commits } => {
-            Some(format!("({commits}↓)").yellow().to_string())
+        output::detail("  Run `rung sync` to re-push or remove them from the stack");
+        println!();
+    }
+
+    print_stale(branches);
+}
+
+/// Warn about branches and PRs that haven't moved in a while.
+fn print_stale(branches: &[BranchWithPrStatus]) {
+    let now = Utc::now();
+    let mut any = false;
+    for b in branches {
+        if let Some(at) = b.last_commit_at.filter(|_| b.stale) {
+            output::warn(&format!(
+                "{} has had no commits in {} days",
+                b.info.name,
+                (now - at).num_days()
+            ));
+            any = true;
         }
-        RemoteDivergenceInfo::Diverged { ahead, behind } => {
-            Some(format!("({ahead}↑ {behind}↓)").yellow().to_string())
+        if let (Some(pr), Some(at)) = (b.info.pr, b.review_idle_since) {
+            output::warn(&format!(
+                "#{pr} ({}) has had no review activity in {} days",
+                b.info.name,
+                (now - at).num_days()
+            ));
+            any = true;
         }
     }
+    if any {
+        output::detail(
+            "  Finish, merge, or `rung untrack` them; `status.stale_after_days` sets the threshold",
+        );
+        println!();
+    }
+}
+
+/// Format remote divergence info as a compact indicator.
+fn remote_divergence_indicator(divergence: &RemoteDivergenceInfo) -> Option<String> {
+    if matches!(divergence, RemoteDivergenceInfo::Gone) {
+        return Some("(remote deleted)".red().to_string());
+    }
+    let (ahead, behind) = divergence.counts()?;
+    match (ahead, behind) {
+        (0, 0) => None,
+        (ahead, 0) => Some(format!("↑{ahead}").dimmed().to_string()),
+        (0, behind) => Some(format!("↓{behind}").yellow().to_string()),
+        (ahead, behind) => Some(format!("↑{ahead} ↓{behind}").yellow().to_string()),
+    }
+    .map(|indicator| output::glyphs(&indicator).into_owned())
 }
 
 /// Branch info with PR status for display.
@@ -228,6 +504,13 @@ struct BranchWithPrStatus {
     info: BranchStatusInfo,
     pr_state: Option<String>,
     display_status: Option<PrStatus>,
+    /// When the tip commit was authored.
+    last_commit_at: Option<DateTime<Utc>>,
+    /// No commits for longer than `status.stale_after_days`.
+    stale: bool,
+    /// Last review (or opening) of an open PR that has gone without review
+    /// for longer than `status.stale_after_days`.
+    review_idle_since: Option<DateTime<Utc>>,
 }
 
 /// JSON output wrapper (preserves existing JSON structure).
@@ -235,6 +518,44 @@ struct BranchWithPrStatus {
 struct JsonOutput {
     branches: Vec<JsonBranchInfo>,
     current: Option<String>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    offline: bool,
+    /// When the cached PR states were fetched, if they came from the cache.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pr_state_cached_at: Option<DateTime<Utc>>,
+    /// When the remote was last fetched, if ever.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    remote_fetched_at: Option<DateTime<Utc>>,
+    /// Uncommitted file counts for the working tree.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    worktree: Option<JsonWorktree>,
+    /// Branches left out by --chain/--depth.
+    #[serde(skip_serializing_if = "is_zero")]
+    hidden: usize,
+}
+
+#[allow(clippy::trivially_copy_pass_by_ref)] // serde passes fields by reference
+const fn is_zero(n: &usize) -> bool {
+    *n == 0
+}
+
+#[derive(Debug, Serialize)]
+struct JsonWorktree {
+    staged: usize,
+    modified: usize,
+    untracked: usize,
+    clean: bool,
+}
+
+impl From<WorktreeStatus> for JsonWorktree {
+    fn from(status: WorktreeStatus) -> Self {
+        Self {
+            staged: status.staged,
+            modified: status.modified,
+            untracked: status.untracked,
+            clean: status.is_clean(),
+        }
+    }
 }
 
 #[derive(Debug, Serialize)]
@@ -243,6 +564,20 @@ struct JsonBranchInfo {
     info: BranchStatusInfo,
     #[serde(skip_serializing_if = "Option::is_none")]
     pr_state: Option<String>,
+    /// Commits ahead of the upstream tracking ref, if the branch has one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    ahead: Option<usize>,
+    /// Commits behind the upstream tracking ref, if the branch has one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    behind: Option<usize>,
+    /// When the tip commit was authored.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    last_commit_at: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    stale: bool,
+    /// Since when the open PR has had no review activity, if that's too long.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    review_idle_since: Option<DateTime<Utc>>,
 }
 
 impl JsonOutput {
@@ -250,19 +585,82 @@ impl JsonOutput {
         Self {
             branches: vec![],
             current: None,
+            offline: false,
+            pr_state_cached_at: None,
+            remote_fetched_at: None,
+            worktree: None,
+            hidden: 0,
         }
     }
 
-    fn from_branches(branches: &[BranchWithPrStatus], current: Option<String>) -> Self {
+    fn from_branches(
+        branches: &[BranchWithPrStatus],
+        current: Option<String>,
+        pr_state_cached_at: Option<DateTime<Utc>>,
+    ) -> Self {
         Self {
             branches: branches
                 .iter()
-                .map(|b| JsonBranchInfo {
-                    info: b.info.clone(),
-                    pr_state: b.pr_state.clone(),
+                .map(|b| {
+                    let counts = b
+                        .info
+                        .remote_divergence
+                        .as_ref()
+                        .and_then(RemoteDivergenceInfo::counts);
+                    JsonBranchInfo {
+                        info: b.info.clone(),
+                        pr_state: b.pr_state.clone(),
+                        ahead: counts.map(|(ahead, _)| ahead),
+                        behind: counts.map(|(_, behind)| behind),
+                        last_commit_at: b.last_commit_at,
+                        stale: b.stale,
+                        review_idle_since: b.review_idle_since,
+                    }
                 })
                 .collect(),
             current,
+            offline: utils::is_offline(),
+            pr_state_cached_at,
+            remote_fetched_at: None,
+            worktree: None,
+            hidden: 0,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_worktree_summary() {
+        let status = |staged, modified, untracked| WorktreeStatus {
+            staged,
+            modified,
+            untracked,
+        };
+
+        assert_eq!(worktree_summary(&status(0, 0, 0)), None);
+        assert_eq!(
+            worktree_summary(&status(0, 0, 2)).as_deref(),
+            Some("Uncommitted: 2 untracked")
+        );
+        assert_eq!(
+            worktree_summary(&status(1, 3, 0)).as_deref(),
+            Some("Uncommitted: 1 staged, 3 modified - commit or stash before `rung sync`")
+        );
+        assert!(worktree_marker(&status(0, 0, 2)).is_none());
+        assert!(worktree_marker(&status(1, 0, 0)).is_some());
+    }
+
+    #[test]
+    fn test_is_stale() {
+        let now = Utc::now();
+        let days = |n| TimeDelta::try_days(n);
+        let ago = |n| now - days(n).unwrap_or_default();
+
+        assert!(is_stale(ago(20), now, days(14)));
+        assert!(!is_stale(ago(3), now, days(14)));
+        assert!(!is_stale(ago(400), now, None));
+    }
+}
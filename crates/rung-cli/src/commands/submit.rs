@@ -1,18 +1,19 @@
 //! `rung submit` command - Push branches and create/update PRs.
 
+use std::fmt::Write;
+
 use anyhow::{Context, Result, bail};
 use inquire::{Select, Text};
-use rung_core::{State, stack::Stack, sync};
+use rung_core::{StateLock, stack::Stack, sync};
 use rung_git::{RemoteDivergence, Repository};
-use rung_github::Auth;
-
-use crate::forge::Forge;
 use serde::Serialize;
 
-use crate::commands::utils;
+use crate::commands::utils::{self, CommandContext};
+use crate::forge::Forge;
 use crate::output;
 use crate::services::{
     BranchSubmitResult, PlannedBranchAction, SubmitAction, SubmitConfig, SubmitPlan, SubmitService,
+    create_offline_plan,
 };
 
 /// JSON output for submit command.
@@ -58,6 +59,12 @@ struct PlannedBranchInfo {
     pr_url: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     target_base: Option<String>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    draft: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    new_title: Option<String>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    updates_body: bool,
     action: OutputAction,
 }
 
@@ -68,59 +75,129 @@ enum OutputAction {
     Updated,
 }
 
-/// Run the submit command.
-#[allow(clippy::fn_params_excessive_bools)]
-pub fn run(
+/// Options for the submit command.
+#[allow(clippy::struct_excessive_bools)]
+#[derive(Debug, Clone, Copy)]
+pub struct SubmitOptions<'a> {
+    /// Show what would be done without making changes.
+    pub dry_run: bool,
+    /// Create PRs as drafts.
+    pub draft: bool,
+    /// Create PRs for branches stacked above this one as drafts.
+    pub draft_above: Option<&'a str>,
+    /// Force push even if lease check fails.
+    pub force: bool,
+    /// Custom PR title for the current branch.
+    pub custom_title: Option<&'a str>,
+    /// Amend uncommitted changes to the last commit first.
+    pub amend: bool,
+    /// Commit uncommitted changes with this message first.
+    pub message: Option<&'a str>,
+    /// Only submit the current branch and its ancestors.
+    pub downstack: bool,
+    /// Regenerate existing PRs' titles.
+    pub update_titles: bool,
+    /// Regenerate existing PRs' bodies.
+    pub update_bodies: bool,
+}
+
+/// Update the stack navigation on every PR, and the stack commit statuses
+/// when `submit.commit_status` is set.
+#[allow(clippy::future_not_send)]
+async fn update_stack_info(
+    service: &SubmitService<'_, Repository, Forge>,
+    stack: &Stack,
+    default_branch: &str,
+    submit_config: &rung_core::config::SubmitConfig,
     json: bool,
-    dry_run: bool,
-    draft: bool,
-    force: bool,
-    custom_title: Option<&str>,
-    amend: bool,
-    message: Option<&str>,
 ) -> Result<()> {
-    let (repo, state, mut stack) = setup_submit(json, amend, message)?;
+    if !json {
+        output::info(if submit_config.stack_in_body {
+            "Updating stack sections..."
+        } else {
+            "Updating stack comments..."
+        });
+    }
+    service
+        .update_stack_navigation(stack, default_branch, submit_config)
+        .await?;
+    if submit_config.commit_status {
+        if !json {
+            output::info("Setting stack commit statuses...");
+        }
+        service.publish_stack_statuses(stack).await?;
+    }
+    Ok(())
+}
+
+/// Run the submit command.
+pub fn run(json: bool, opts: &SubmitOptions<'_>) -> Result<()> {
+    let SubmitOptions {
+        dry_run,
+        draft,
+        draft_above,
+        force,
+        custom_title,
+        amend,
+        message,
+        downstack,
+        update_titles,
+        update_bodies,
+    } = *opts;
+    if utils::is_offline() && !dry_run {
+        bail!(
+            "Cannot submit while offline - pushing and updating PRs needs the network. Use --dry-run to preview"
+        );
+    }
+
+    let (ctx, _lock, mut stack) = setup_submit(json, amend, message)?;
+    let CommandContext { repo, state, .. } = &ctx;
 
     if stack.is_empty() {
-        if json {
-            if dry_run {
-                return output_dry_run_json(&SubmitPlan::empty());
-            }
-            return output_json(&SubmitOutput {
-                prs_created: 0,
-                prs_updated: 0,
-                branches: vec![],
-                dry_run: false,
-            });
-        }
-        output::info("No branches in stack - nothing to submit");
-        return Ok(());
+        return output_empty(json, dry_run);
     }
 
+    let submit_config = state.load_config()?.submit;
     let config = SubmitConfig {
-        draft,
+        draft: draft || submit_config.draft,
+        draft_branches: draft_branches(&stack, draft_above, submit_config.draft_above_depth)?,
         custom_title,
         current_branch: repo.current_branch().ok(),
         default_branch: state
             .default_branch()
             .context("Failed to load default branch from config")?,
+        update_titles: update_titles || submit_config.update_titles,
+        update_bodies: update_bodies || submit_config.update_bodies,
     };
 
-    let repo_id = get_remote_info(&repo)?;
-
-    let origin_url = repo.origin_url().context("No origin remote configured")?;
-    let client = Forge::for_remote(&origin_url, &Auth::auto())?;
-    let rt = tokio::runtime::Runtime::new()?;
+    let scope = submit_scope(&stack, downstack, config.current_branch.as_deref())?;
 
-    let service = SubmitService::new(&repo, &client, repo_id.clone());
+    let repo_id = ctx.remote_info()?.repo;
 
     // Phase 0: Sync Protection
     if !force {
-        validate_sync_state(&repo, &stack, &config.default_branch, json)?;
+        validate_sync_state(repo, &scope, &config.default_branch, json)?;
     }
 
+    if dry_run && utils::is_offline() {
+        let plan = create_offline_plan(repo, &scope, &config, &repo_id)?;
+        return handle_dry_run_output(&plan, json, &config.default_branch);
+    }
+
+    let client = ctx.forge()?;
+    let rt = ctx.runtime()?;
+
+    let service = SubmitService::new(repo, client, repo_id.clone());
+
     // Phase 1: Create the plan (read-only, checks existing PRs)
-    let plan = rt.block_on(service.create_plan(&stack, &config))?;
+    let plan = match rt.block_on(service.create_plan(&scope, &config)) {
+        Ok(plan) => plan,
+        Err(e) if dry_run && utils::is_network_error(&e) => {
+            utils::go_offline(json);
+            create_offline_plan(repo, &scope, &config, &repo_id)?
+        }
+        Err(e) => return Err(e),
+    };
 
     // Single dry-run check point
     if dry_run {
@@ -138,34 +215,25 @@ pub fn run(
             PlannedBranchAction::Update { branch, .. }
             | PlannedBranchAction::Create { branch, .. } => branch,
         };
-        warn_if_diverged(&repo, branch, force, json);
+        warn_if_diverged(repo, branch, force, json);
     }
 
     let results = rt.block_on(service.execute(&mut stack, &plan, force))?;
 
-    // Print progress for each result
     if !json {
-        for result in &results {
-            match result.action {
-                SubmitAction::Created => {
-                    output::success(&format!(
-                        "  Created PR #{}: {}",
-                        result.pr_number, result.pr_url
-                    ));
-                }
-                SubmitAction::Updated => {
-                    output::info(&format!("  Updated PR #{}", result.pr_number));
-                }
-            }
-        }
+        print_results(&results);
     }
 
     // Save state and update comments (only after real execution)
     state.save_stack(&stack)?;
-    if !json {
-        output::info("Updating stack comments...");
-    }
-    rt.block_on(service.update_stack_comments(&stack, &config.default_branch))?;
+    utils::set_stack_upstreams(repo, &stack, json);
+    rt.block_on(update_stack_info(
+        &service,
+        &stack,
+        &config.default_branch,
+        &submit_config,
+        json,
+    ))?;
 
     let (created, updated) = results
         .iter()
@@ -189,37 +257,98 @@ pub fn run(
     Ok(())
 }
 
+/// The branches to submit: the whole stack, or with `--downstack` the
+/// current branch and its ancestors as a stack of their own.
+///
+/// Siblings and descendants are left out, so they aren't pushed.
+fn submit_scope(stack: &Stack, downstack: bool, current: Option<&str>) -> Result<Stack> {
+    if !downstack {
+        return Ok(stack.clone());
+    }
+    let Some(current) = current.filter(|c| stack.find_branch(c).is_some()) else {
+        bail!(
+            "--downstack submits the current branch and its ancestors - check out a stack branch first"
+        );
+    };
+    Ok(Stack {
+        branches: stack.ancestry(current).into_iter().cloned().collect(),
+        merged: stack.merged.clone(),
+    })
+}
+
+/// The branches whose new PRs are drafts under the stack-position policy:
+/// those above `--draft-above`, or with `submit.draft_above_depth = N`
+/// everything but the bottom N.
+fn draft_branches(
+    stack: &Stack,
+    draft_above: Option<&str>,
+    depth: Option<usize>,
+) -> Result<Vec<String>> {
+    if let Some(branch) = draft_above {
+        if stack.find_branch(branch).is_none() {
+            bail!("Branch '{branch}' is not in the stack");
+        }
+        return Ok(stack
+            .descendants(branch)
+            .iter()
+            .map(|b| b.name.to_string())
+            .collect());
+    }
+    let Some(depth) = depth else {
+        return Ok(vec![]);
+    };
+    Ok(stack
+        .index()
+        .depths()
+        .into_iter()
+        .filter(|&(_, d)| d >= depth)
+        .map(|(name, _)| name.to_string())
+        .collect())
+}
+
+/// Report that an empty stack has nothing to submit.
+fn output_empty(json: bool, dry_run: bool) -> Result<()> {
+    if json {
+        if dry_run {
+            return output_dry_run_json(&SubmitPlan::empty());
+        }
+        return output_json(&SubmitOutput {
+            prs_created: 0,
+            prs_updated: 0,
+            branches: vec![],
+            dry_run: false,
+        });
+    }
+    output::info("No branches in stack - nothing to submit");
+    Ok(())
+}
+
 /// Output submit result as JSON.
 fn output_json(output: &SubmitOutput) -> Result<()> {
     println!("{}", serde_json::to_string_pretty(output)?);
     Ok(())
 }
 
-/// Set up repository, state, and stack for submit.
+/// Set up repository, state lock, and stack for submit.
 ///
 /// Handles uncommitted changes based on flags or interactive prompt.
 fn setup_submit(
     json: bool,
     amend: bool,
     message: Option<&str>,
-) -> Result<(Repository, State, rung_core::stack::Stack)> {
-    let repo = Repository::open_current().context("Not inside a git repository")?;
-    let workdir = repo.workdir().context("Cannot run in bare repository")?;
-    let state = State::new(workdir)?;
-
-    if !state.is_initialized() {
-        bail!("Rung not initialized - run `rung init` first");
-    }
+) -> Result<(CommandContext, StateLock, rung_core::stack::Stack)> {
+    let ctx = CommandContext::open()?;
+    let lock = utils::lock_state(&ctx.repo, &ctx.state)?;
 
     // Validate branch context BEFORE any history-changing operations
-    utils::ensure_on_branch(&repo)?;
+    utils::ensure_on_branch(&ctx.repo)?;
 
     // Handle uncommitted changes (may amend/commit)
-    handle_uncommitted_changes(&repo, json, amend, message)?;
+    handle_uncommitted_changes(&ctx.repo, json, amend, message)?;
 
-    let stack = state.load_stack()?;
+    let stack = ctx.state.load_stack()?;
 
-    Ok((repo, state, stack))
+    Ok((ctx, lock, stack))
 }
 
 /// Handle uncommitted changes before submit.
@@ -326,18 +455,12 @@ fn prompt_and_handle_uncommitted(repo: &Repository) -> Result<()> {
     Ok(())
 }
 
-/// Get the forge-neutral repository identifier from the origin remote.
-fn get_remote_info(repo: &Repository) -> Result<rung_forge::RepoId> {
-    let origin_url = repo.origin_url().context("No origin remote configured")?;
-    let info = rung_forge::parse_remote(&origin_url).context("Could not parse forge remote URL")?;
-    Ok(info.repo)
-}
-
 /// Create a commit using git CLI.
 ///
 /// Uses `git commit -m` for consistency with `stage_all` which also uses CLI.
 fn create_commit_cli(repo: &Repository, message: &str) -> Result<()> {
     let workdir = repo.workdir().context("Cannot run in bare repository")?;
+    repo.identity()?;
 
     let output = std::process::Command::new("git")
         .args(["commit", "-m", message])
@@ -378,6 +501,8 @@ struct DryRunOutput {
     #[serde(skip_serializing_if = "Vec::is_empty")]
     branches: Vec<PlannedBranchInfo>,
     dry_run: bool,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    offline: bool,
 }
 
 /// Handle dry-run output (both JSON and human-readable).
@@ -400,19 +525,32 @@ fn output_dry_run_json(plan: &SubmitPlan) -> Result<()> {
                 branch,
                 pr_number,
                 pr_url,
+                title,
+                body,
                 ..
             } => PlannedBranchInfo {
                 branch: branch.clone(),
                 pr_number: Some(*pr_number),
                 pr_url: Some(pr_url.clone()),
                 target_base: None,
+                draft: false,
+                new_title: title.clone(),
+                updates_body: body.is_some(),
                 action: OutputAction::Updated,
             },
-            PlannedBranchAction::Create { branch, base, .. } => PlannedBranchInfo {
+            PlannedBranchAction::Create {
+                branch,
+                base,
+                draft,
+                ..
+            } => PlannedBranchInfo {
                 branch: branch.clone(),
                 pr_number: None,
                 pr_url: None,
                 target_base: Some(base.clone()),
+                draft: *draft,
+                new_title: None,
+                updates_body: false,
                 action: OutputAction::Created,
             },
         })
@@ -423,12 +561,30 @@ fn output_dry_run_json(plan: &SubmitPlan) -> Result<()> {
         prs_would_update: plan.count_updates(),
         branches,
         dry_run: true,
+        offline: utils::is_offline(),
     };
 
     println!("{}", serde_json::to_string_pretty(&output)?);
     Ok(())
 }
 
+/// Print progress for each submitted branch.
+fn print_results(results: &[BranchSubmitResult]) {
+    for result in results {
+        match result.action {
+            SubmitAction::Created => {
+                output::success(&format!(
+                    "  Created PR #{}: {}",
+                    result.pr_number, result.pr_url
+                ));
+            }
+            SubmitAction::Updated => {
+                output::info(&format!("  Updated PR #{}", result.pr_number));
+            }
+        }
+    }
+}
+
 /// Print human-readable summary for dry-run mode.
 fn print_dry_run_summary(plan: &SubmitPlan, default_branch: &str) {
     if plan.is_empty() {
@@ -441,8 +597,12 @@ fn print_dry_run_summary(plan: &SubmitPlan, default_branch: &str) {
         .iter()
         .filter_map(|a| match a {
             PlannedBranchAction::Update {
-                branch, pr_number, ..
-            } => Some((branch, pr_number)),
+                branch,
+                pr_number,
+                title,
+                body,
+                ..
+            } => Some((branch, pr_number, title, body.is_some())),
             PlannedBranchAction::Create { .. } => None,
         })
         .collect();
@@ -451,7 +611,12 @@ fn print_dry_run_summary(plan: &SubmitPlan, default_branch: &str) {
         .actions
         .iter()
         .filter_map(|a| match a {
-            PlannedBranchAction::Create { branch, base, .. } => Some((branch, base)),
+            PlannedBranchAction::Create {
+                branch,
+                base,
+                draft,
+                ..
+            } => Some((branch, base, *draft)),
             PlannedBranchAction::Update { .. } => None,
         })
         .collect();
@@ -460,8 +625,15 @@ fn print_dry_run_summary(plan: &SubmitPlan, default_branch: &str) {
 
     if !updates.is_empty() {
         parts.push(format!("→ Would push {} branches:", updates.len()));
-        for (branch, pr_number) in &updates {
-            parts.push(format!("  - {branch} (PR #{pr_number})"));
+        for (branch, pr_number, title, updates_body) in &updates {
+            let mut line = format!("  - {branch} (PR #{pr_number})");
+            if let Some(title) = title {
+                let _ = write!(line, ", new title \"{title}\"");
+            }
+            if *updates_body {
+                line.push_str(", body refreshed");
+            }
+            parts.push(line);
         }
         parts.push(String::new());
     }
@@ -471,17 +643,23 @@ fn print_dry_run_summary(plan: &SubmitPlan, default_branch: &str) {
             "→ Would create {} new PRs for branches:",
             creates.len()
         ));
-        for (branch, base) in &creates {
+        for (branch, base, draft) in &creates {
             let target = if base.is_empty() {
                 default_branch
             } else {
                 base
             };
-            parts.push(format!("  - {branch} → {target}"));
+            let draft = if *draft { " (draft)" } else { "" };
+            parts.push(format!("  - {branch} → {target}{draft}"));
         }
         parts.push(String::new());
     }
 
+    if utils::is_offline() {
+        parts.push(
+            "(offline - based on PRs recorded locally; PRs opened elsewhere show as new)".into(),
+        );
+    }
     parts.push("(dry run - no changes made)".into());
     output::essential(&parts.join("\n"));
 }
@@ -498,10 +676,15 @@ fn validate_sync_state(
     }
 
     // 1. Fetch latest from remote (updates local tracking branch)
-    if let Err(e) = repo.fetch(base_branch)
-        && !json
+    if !utils::is_offline()
+        && let Err(e) = repo.fetch(base_branch)
     {
-        output::warn(&format!("Could not fetch {base_branch}: {e}"));
+        let e = anyhow::Error::from(e);
+        if utils::is_network_error(&e) {
+            utils::go_offline(json);
+        } else if !json {
+            output::warn(&format!("Could not fetch {base_branch}: {e}"));
+        }
     }
 
     // 2. Check if stack needs syncing
@@ -0,0 +1,199 @@
+//! `rung switch` command - Check out a PR's branch and place it in the stack.
+
+use anyhow::{Context, Result, bail};
+use rung_core::{Stack, stack::StackBranch};
+use rung_forge::{ForgeApi, PullRequest, PullRequestState, RepoId};
+use serde::Serialize;
+
+use crate::commands::utils::{self, CommandContext};
+use crate::output;
+
+/// JSON output for switch command.
+#[derive(Debug, Serialize)]
+struct SwitchOutput {
+    branch: String,
+    pr: u64,
+    parent: String,
+    /// Branches newly added to the stack, parents first.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    added: Vec<String>,
+}
+
+/// Run the switch command.
+pub fn run(json: bool, number: u64) -> Result<()> {
+    let cmd = CommandContext::open()?;
+    let CommandContext { repo, state, .. } = &cmd;
+    let _lock = utils::lock_state(repo, state)?;
+
+    let repo_id = cmd.remote_info()?.repo;
+    let default_branch = state.default_branch()?;
+    let mut stack = state.load_stack()?;
+
+    if !json {
+        output::info(&format!("Fetching PR #{number}..."));
+    }
+    let chain = cmd.runtime()?.block_on(resolve_chain(
+        cmd.forge()?,
+        &repo_id,
+        &stack,
+        &default_branch,
+        number,
+    ))?;
+
+    // Git won't fetch into the checked-out branch, so that one is used as is
+    let current = repo.current_branch().ok();
+    let mut added = vec![];
+    for pr in &chain {
+        let branch = &pr.head_branch;
+        if stack.find_branch(branch).is_some() {
+            continue;
+        }
+        if current.as_ref() != Some(branch) {
+            repo.fetch(branch)
+                .with_context(|| format!("Failed to fetch '{branch}' for PR #{}", pr.number))?;
+        }
+        let parent = (pr.base_branch != default_branch).then_some(pr.base_branch.as_str());
+        let mut entry = StackBranch::try_new(branch.as_str(), parent)
+            .with_context(|| format!("PR #{} has an invalid branch name", pr.number))?;
+        entry.pr = Some(pr.number);
+        stack.add_branch(entry);
+        added.push(branch.clone());
+    }
+    state.save_stack(&stack)?;
+    utils::set_stack_upstreams(repo, &stack, json);
+
+    let pr = chain.last().context("No PR to switch to")?;
+    let branch = &pr.head_branch;
+    repo.checkout(branch)?;
+
+    if json {
+        let output = SwitchOutput {
+            branch: branch.clone(),
+            pr: pr.number,
+            parent: pr.base_branch.clone(),
+            added,
+        };
+        println!("{}", serde_json::to_string_pretty(&output)?);
+        return Ok(());
+    }
+
+    for name in &added {
+        output::success(&format!("Added '{name}' to the stack"));
+    }
+    output::success(&format!(
+        "Switched to '{branch}' (PR #{}, on {})",
+        pr.number, pr.base_branch
+    ));
+    Ok(())
+}
+
+/// Fetch PR `number` and every ancestor PR that isn't in the stack yet,
+/// following each PR's base until it reaches the default branch or a
+/// tracked branch. Returned parents first.
+async fn resolve_chain<F: ForgeApi>(
+    forge: &F,
+    repo_id: &RepoId,
+    stack: &Stack,
+    default_branch: &str,
+    number: u64,
+) -> Result<Vec<PullRequest>> {
+    let mut pr = forge
+        .get_pr(repo_id, number)
+        .await
+        .with_context(|| format!("Failed to fetch PR #{number}"))?;
+    match pr.state {
+        PullRequestState::Open => {}
+        PullRequestState::Merged => bail!("PR #{number} is already merged"),
+        PullRequestState::Closed => bail!("PR #{number} is closed"),
+    }
+
+    let mut chain = vec![];
+    loop {
+        let base = pr.base_branch.clone();
+        let tracked = stack.find_branch(&pr.head_branch).is_some();
+        chain.push(pr);
+        if tracked || base == default_branch || stack.find_branch(&base).is_some() {
+            break;
+        }
+        if chain.iter().any(|p| p.head_branch == base) {
+            bail!("PR bases loop back to '{base}'");
+        }
+        pr = forge
+            .find_pr_for_branch(repo_id, &base)
+            .await?
+            .with_context(|| {
+                format!("Base branch '{base}' has no open PR - `rung adopt` it first")
+            })?;
+    }
+    chain.reverse();
+    Ok(chain)
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use rung_github::testing::MockGitHubClient;
+
+    fn pr(number: u64, head: &str, base: &str) -> PullRequest {
+        PullRequest {
+            number,
+            title: head.to_string(),
+            body: None,
+            state: PullRequestState::Open,
+            draft: false,
+            head_branch: head.to_string(),
+            base_branch: base.to_string(),
+            html_url: String::new(),
+            mergeable: None,
+            mergeable_state: None,
+            created_at: None,
+            last_reviewed_at: None,
+        }
+    }
+
+    fn heads(chain: &[PullRequest]) -> Vec<&str> {
+        chain.iter().map(|p| p.head_branch.as_str()).collect()
+    }
+
+    #[tokio::test]
+    async fn test_resolve_chain_follows_untracked_bases() {
+        let forge = MockGitHubClient::new()
+            .with_pr(pr(1, "a", "main"))
+            .with_pr(pr(2, "b", "a"))
+            .with_pr(pr(3, "c", "b"));
+        let repo = RepoId::new("owner/repo");
+
+        let chain = resolve_chain(&forge, &repo, &Stack::default(), "main", 3)
+            .await
+            .unwrap();
+        assert_eq!(heads(&chain), ["a", "b", "c"]);
+
+        let mut stack = Stack::default();
+        stack.add_branch(StackBranch::try_new("a", None::<&str>).unwrap());
+        let chain = resolve_chain(&forge, &repo, &stack, "main", 3)
+            .await
+            .unwrap();
+        assert_eq!(heads(&chain), ["b", "c"]);
+    }
+
+    #[tokio::test]
+    async fn test_resolve_chain_rejects_unknown_base_and_merged_prs() {
+        let mut merged = pr(2, "b", "main");
+        merged.state = PullRequestState::Merged;
+        let forge = MockGitHubClient::new()
+            .with_pr(pr(1, "a", "local-only"))
+            .with_pr(merged);
+        let repo = RepoId::new("owner/repo");
+
+        let err = resolve_chain(&forge, &repo, &Stack::default(), "main", 1)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("'local-only' has no open PR"));
+
+        let err = resolve_chain(&forge, &repo, &Stack::default(), "main", 2)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("already merged"));
+    }
+}
@@ -5,20 +5,26 @@
 //! 2. Updates stack topology for merged branches
 //! 3. Rebases remaining branches onto their new parents
 //! 4. Updates GitHub PR base branches
-//! 5. Pushes all synced branches
+//! 5. Pushes the synced branches `sync.push` selects (all by default)
+
+use std::io::IsTerminal;
 
 use anyhow::{Context, Result, bail};
-use rung_core::State;
+use inquire::{Confirm, Select};
+use rung_core::absorb::{self, PendingFixups};
+use rung_core::config::PushMode;
 use rung_core::sync::{
-    self, ReconcileResult, SyncConflictPrediction, SyncResult, predict_sync_conflicts,
+    self, ReconcileResult, SyncConflictPrediction, SyncPlan, SyncResult, predict_sync_conflicts,
 };
+use rung_core::{Stack, State};
 use rung_git::Repository;
-use rung_github::{Auth, ForgeApi, RepoId};
+use rung_github::{ForgeApi, RepoId};
 use serde::Serialize;
 
 use crate::forge::Forge;
 
-use crate::commands::utils;
+use crate::commands::utils::{self, CommandContext};
+use crate::conflict::ConflictReport;
 use crate::output;
 use crate::services::SyncService;
 
@@ -34,8 +40,23 @@ struct SyncOutput {
     conflict_branch: Option<String>,
     #[serde(skip_serializing_if = "Vec::is_empty")]
     conflict_files: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    conflict: Option<ConflictReport>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    skipped_commits: Vec<SkippedCommitsOutput>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    skipped_branches: Vec<String>,
     #[serde(skip_serializing_if = "std::ops::Not::not")]
     forge_auth_unavailable: bool,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    offline: bool,
+}
+
+/// Commits dropped from a branch because they were already upstream.
+#[derive(Debug, Serialize)]
+struct SkippedCommitsOutput {
+    branch: String,
+    commits: Vec<String>,
 }
 
 #[derive(Debug, Serialize)]
@@ -55,8 +76,12 @@ struct DryRunOutput {
     merged_prs: Vec<DryRunMergedPr>,
     #[serde(skip_serializing_if = "Vec::is_empty")]
     branches_to_rebase: Vec<DryRunRebase>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    branches_to_autosquash: Vec<String>,
     #[serde(skip_serializing_if = "std::ops::Not::not")]
     forge_auth_unavailable: bool,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    offline: bool,
 }
 
 #[derive(Debug, Serialize)]
@@ -70,6 +95,8 @@ struct DryRunMergedPr {
 struct DryRunRebase {
     branch: String,
     new_base: String,
+    applied_commits: usize,
+    parent_rewritten: bool,
 }
 
 /// JSON output for conflict prediction.
@@ -95,24 +122,53 @@ struct CommitConflictOutput {
     files: Vec<String>,
 }
 
-/// Run the sync command.
-#[allow(clippy::fn_params_excessive_bools)]
-pub fn run(
-    json: bool,
-    dry_run: bool,
-    check: bool,
-    continue_: bool,
-    abort: bool,
-    no_push: bool,
-    base: Option<&str>,
-) -> Result<()> {
-    let repo = Repository::open_current().context("Not inside a git repository")?;
-    let workdir = repo.workdir().context("Cannot run in bare repository")?;
-    let state = State::new(workdir)?;
+/// Options for the sync command. The default is a plain `rung sync`.
+#[allow(clippy::struct_excessive_bools)]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SyncOptions<'a> {
+    /// Show what would be done without making changes.
+    pub dry_run: bool,
+    /// Only report whether the stack needs syncing.
+    pub check: bool,
+    /// Resume a paused sync.
+    pub continue_: bool,
+    /// Abort a paused sync.
+    pub abort: bool,
+    /// `Some(descendants)` when `--skip` was given.
+    pub skip: Option<bool>,
+    /// Which branches to push; `None` uses `sync.push`.
+    pub push: Option<PushMode>,
+    /// Squash `fixup!` commits while rebasing.
+    pub autosquash: bool,
+    /// Prune stale remote tracking refs first.
+    pub prune_remote: bool,
+    /// Base branch to sync onto instead of the default branch.
+    pub base: Option<&'a str>,
+    /// Only sync the tree containing this branch.
+    pub tree: Option<&'a str>,
+}
 
-    if !state.is_initialized() {
-        bail!("Rung not initialized - run `rung init` first");
-    }
+/// Run the sync command.
+pub fn run(json: bool, opts: &SyncOptions<'_>) -> Result<()> {
+    let SyncOptions {
+        dry_run,
+        check,
+        continue_,
+        abort,
+        skip,
+        push,
+        autosquash,
+        prune_remote,
+        base,
+        tree,
+    } = *opts;
+    let ctx = CommandContext::open()?;
+    let CommandContext { repo, state, .. } = &ctx;
+    let _lock = utils::lock_state(repo, state)?;
+    let push = match push {
+        Some(push) => push,
+        None => state.load_config()?.sync.push,
+    };
 
     if continue_ && abort {
         bail!("Cannot use --continue and --abort together");
@@ -120,12 +176,12 @@ pub fn run(
 
     // Handle abort (no GitHub needed)
     if abort {
-        return handle_abort(&repo, &state, json);
+        return handle_abort(&ctx, json);
     }
 
-    // Handle continue (no GitHub needed)
-    if continue_ {
-        return handle_continue(&repo, &state, json, no_push);
+    // Handle continue and skip (no GitHub needed)
+    if continue_ || skip.is_some() {
+        return handle_continue(&ctx, json, push, skip);
     }
 
     // Check for existing sync in progress (before branch validation for better error messages)
@@ -133,82 +189,122 @@ pub fn run(
         bail!("Sync already in progress - use --continue to resume or --abort to cancel");
     }
 
+    // Refuse to start on top of an unfinished merge, cherry-pick, bisect, etc.
+    repo.require_no_operation()?;
+
     // Ensure on branch
-    utils::ensure_on_branch(&repo)?;
+    utils::ensure_on_branch(repo)?;
 
     repo.require_clean()?;
 
     // Try to get the forge remote info (optional - needed for PR operations)
-    let origin_url = repo.origin_url().ok();
-    let forge_info = origin_url
-        .as_deref()
-        .and_then(|url| rung_forge::parse_remote(url).ok())
-        .map(|info| info.repo);
-
-    // Create runtime once for all async operations
-    let rt = tokio::runtime::Runtime::new()?;
+    let remote_info = ctx.remote_info().ok();
+    let forge_info = remote_info.as_ref().map(|info| info.repo.clone());
 
     // Determine base branch
-    let base_branch = determine_base_branch(base, origin_url.as_deref(), &rt)?;
+    let base_branch = determine_base_branch(&ctx, base, forge_info.as_ref(), json)?;
 
     // Fetch base branch (skip for --check to keep it side-effect free)
-    if !check {
+    if !check && !utils::is_offline() {
         if !json {
             output::info(&format!("Fetching {base_branch}..."));
         }
-        if let Err(e) = repo.fetch(&base_branch)
-            && !json
-        {
-            output::warn(&format!("Could not fetch {base_branch}: {e}"));
+        if let Err(e) = repo.fetch(&base_branch) {
+            let e = anyhow::Error::from(e);
+            if utils::is_network_error(&e) {
+                utils::go_offline(json);
+            } else if !json {
+                output::warn(&format!("Could not fetch {base_branch}: {e}"));
+            }
         }
     }
+    // Stale tracking refs of deleted branches skew divergence (read-only modes skip this)
+    if prune_remote && !check && !dry_run && !utils::is_offline() {
+        prune_remote_refs(&ctx, json)?;
+    }
+    if utils::is_offline() && !json {
+        output::info(&format!(
+            "Offline - using local {base_branch}, skipping merge detection and push"
+        ));
+    }
 
     // Create the forge client (if available)
     let mut forge_auth_unavailable = false;
-    let client = match (forge_info.as_ref(), origin_url.as_deref()) {
-        (Some(_), Some(url)) => Forge::for_remote(url, &Auth::auto())
+    let client = match &remote_info {
+        _ if utils::is_offline() => None,
+        Some(info) => ctx
+            .forge()
             .map_err(|_| {
                 forge_auth_unavailable = true;
                 if !json {
-                    let forge_name = rung_forge::ForgeKind::detect(url)
-                        .map_or("Forge", |kind| kind.display_name());
                     output::warn(&format!(
-                        "{forge_name} auth unavailable - skipping merge detection"
+                        "{} auth unavailable - skipping merge detection",
+                        info.kind.display_name()
                     ));
                 }
             })
             .ok(),
-        _ => None,
+        None => None,
     };
 
     // Run the main sync phases
     run_sync_phases(
-        &repo,
-        &state,
+        &ctx,
         &base_branch,
         forge_info.as_ref(),
-        client.as_ref(),
-        &rt,
+        client,
         json,
         dry_run,
         check,
-        no_push,
+        push,
+        autosquash,
+        tree,
         forge_auth_unavailable,
     )
 }
 
+/// Prune remote-tracking refs of branches deleted on the remote, and
+/// report the stack branches among them.
+fn prune_remote_refs(ctx: &CommandContext, json: bool) -> Result<()> {
+    let CommandContext { repo, state, .. } = ctx;
+    let pruned = match repo.prune_remote() {
+        Ok(pruned) => pruned,
+        Err(e) => {
+            let e = anyhow::Error::from(e);
+            if utils::is_network_error(&e) {
+                utils::go_offline(json);
+            } else if !json {
+                output::warn(&format!("Could not prune remote refs: {e}"));
+            }
+            return Ok(());
+        }
+    };
+    if json || pruned.is_empty() {
+        return Ok(());
+    }
+
+    output::info(&format!(
+        "Pruned {} stale remote-tracking ref(s)",
+        pruned.len()
+    ));
+    let stack = state.load_stack()?;
+    for branch in pruned.iter().filter(|b| stack.find_branch(b).is_some()) {
+        output::detail(&format!("  → {branch}: remote branch was deleted"));
+    }
+    Ok(())
+}
+
 /// Whether a forge remote exists but its auth is unavailable.
 ///
 /// Used to annotate `--json` output: `true` only when there is a recognized
 /// forge remote but a client for it cannot be constructed (auth failure).
-fn forge_auth_unavailable(repo: &Repository) -> bool {
-    repo.origin_url().ok().as_deref().is_some_and(|url| {
-        rung_forge::parse_remote(url).is_ok() && Forge::for_remote(url, &Auth::auto()).is_err()
-    })
+fn forge_auth_unavailable(ctx: &CommandContext) -> bool {
+    ctx.remote_info().is_ok() && ctx.forge().is_err()
 }
 
 /// Handle --abort flag.
-fn handle_abort(repo: &Repository, state: &State, json: bool) -> Result<()> {
+fn handle_abort(ctx: &CommandContext, json: bool) -> Result<()> {
+    let CommandContext { repo, state, .. } = ctx;
     if !state.is_sync_in_progress() {
         bail!("No sync in progress to abort");
     }
@@ -220,76 +316,114 @@ fn handle_abort(repo: &Repository, state: &State, json: bool) -> Result<()> {
             backup_id: None,
             conflict_branch: None,
             conflict_files: vec![],
-            forge_auth_unavailable: forge_auth_unavailable(repo),
+            conflict: None,
+            skipped_commits: vec![],
+            skipped_branches: vec![],
+            forge_auth_unavailable: forge_auth_unavailable(ctx),
+            offline: utils::is_offline(),
         });
     }
     output::success("Sync aborted - branches restored from backup");
     Ok(())
 }
 
-/// Handle --continue flag.
-fn handle_continue(repo: &Repository, state: &State, json: bool, no_push: bool) -> Result<()> {
+/// Handle --continue and --skip flags.
+fn handle_continue(
+    ctx: &CommandContext,
+    json: bool,
+    push: PushMode,
+    skip: Option<bool>,
+) -> Result<()> {
+    let CommandContext { repo, state, .. } = ctx;
     if !state.is_sync_in_progress() {
-        bail!("No sync in progress to continue");
-    }
-    if !json {
-        output::info("Continuing sync...");
+        bail!(
+            "No sync in progress to {}",
+            if skip.is_some() { "skip" } else { "continue" }
+        );
     }
-    let result = sync::continue_sync(repo, state)?;
+    let result = if let Some(descendants) = skip {
+        if !json {
+            output::info(&format!(
+                "Skipping '{}' and continuing sync...",
+                state.load_sync_state()?.current_branch
+            ));
+        }
+        sync::skip_sync(repo, state, descendants)?
+    } else {
+        if !json {
+            output::info("Continuing sync...");
+        }
+        sync::continue_sync(repo, state)?
+    };
 
     // If sync completed successfully, push the branches
     if let SyncResult::Complete { .. } = &result
-        && !no_push
+        && push != PushMode::None
+        && !utils::is_offline()
     {
-        push_stack_branches(repo, state, json)?;
+        let held = held_back(repo, state, push, json)?;
+        push_stack_branches(repo, state, &held, json)?;
     }
 
-    handle_sync_result(result, json, forge_auth_unavailable(repo))
+    handle_sync_result(ctx, result, json, forge_auth_unavailable(ctx))
 }
 
 /// Determine base branch from --base flag or the forge API.
+///
+/// Offline, the default branch recorded at `rung init` is used instead.
 fn determine_base_branch(
+    ctx: &CommandContext,
     base: Option<&str>,
-    origin_url: Option<&str>,
-    rt: &tokio::runtime::Runtime,
+    forge_info: Option<&RepoId>,
+    json: bool,
 ) -> Result<String> {
     if let Some(b) = base {
         return Ok(b.to_string());
     }
+    if utils::is_offline() {
+        return Ok(ctx.state.default_branch()?);
+    }
 
-    let url = origin_url.ok_or_else(|| {
+    let repo_id = forge_info.ok_or_else(|| {
         anyhow::anyhow!(
             "Could not detect forge remote (no origin or unsupported URL). Use --base <branch> to specify manually."
         )
     })?;
-    let rung_forge::RemoteInfo { repo: repo_id, .. } =
-        rung_forge::parse_remote(url).map_err(|_| {
-            anyhow::anyhow!(
-                "Could not detect forge remote (unsupported URL). Use --base <branch> to specify manually."
-            )
-        })?;
-    let client = Forge::for_remote(url, &Auth::auto()).context(
+    let client = ctx.forge().context(
         "Forge auth required to detect default branch. Use --base <branch> to specify manually.",
     )?;
-    rt.block_on(client.get_default_branch(&repo_id))
-        .context("Could not fetch default branch. Use --base <branch> to specify manually.")
+    match ctx.runtime()?.block_on(client.get_default_branch(repo_id)) {
+        Ok(branch) => Ok(branch),
+        Err(e) => {
+            let e = anyhow::Error::from(e);
+            if !utils::is_network_error(&e) {
+                return Err(e.context(
+                    "Could not fetch default branch. Use --base <branch> to specify manually.",
+                ));
+            }
+            utils::go_offline(json);
+            Ok(ctx.state.default_branch()?)
+        }
+    }
 }
 
 /// Run the main sync phases.
 #[allow(clippy::too_many_arguments, clippy::fn_params_excessive_bools)]
 fn run_sync_phases(
-    repo: &Repository,
-    state: &State,
+    ctx: &CommandContext,
     base_branch: &str,
     forge_info: Option<&RepoId>,
     client: Option<&Forge>,
-    rt: &tokio::runtime::Runtime,
     json: bool,
     dry_run: bool,
     check: bool,
-    no_push: bool,
+    push: PushMode,
+    autosquash: bool,
+    tree: Option<&str>,
     forge_auth_unavailable: bool,
 ) -> Result<()> {
+    let CommandContext { repo, state, .. } = ctx;
+
     // Create SyncService once if GitHub is available
     let service = match (client, forge_info) {
         (Some(client), Some(repo_id)) => Some(SyncService::new(repo, client, repo_id.clone())),
@@ -314,18 +448,13 @@ fn run_sync_phases(
             return Ok(());
         }
 
-        let plan = if let Some(service) = &service {
-            service.create_sync_plan(&stack, base_branch)?
-        } else {
-            sync::create_sync_plan(repo, &stack, base_branch)?
-        };
-
+        let plan = create_plan(repo, service.as_ref(), &stack, base_branch, tree)?;
         let predictions = predict_sync_conflicts(repo, &plan)?;
         return print_conflict_predictions(&predictions, json);
     }
 
     // Phase 1: Detect merged PRs
-    let reconcile_result = run_phase_detect_merged(service.as_ref(), state, base_branch, rt, json)?;
+    let reconcile_result = run_phase_detect_merged(ctx, service.as_ref(), base_branch, json)?;
 
     // Phase 2: Remove stale branches
     run_phase_remove_stale(repo, service.as_ref(), state, json)?;
@@ -336,16 +465,56 @@ fn run_sync_phases(
         return handle_empty_stack(json, forge_auth_unavailable);
     }
 
+    // Squash pending fixup commits (e.g. from `rung absorb --fixup`) first,
+    // so the rebase plan sees the rewritten branches
+    let mut pending_fixups = absorb::pending_fixups(repo, &stack, base_branch)?;
+    if autosquash && !dry_run && !pending_fixups.is_empty() {
+        for pending in &pending_fixups {
+            stack.check_unlocked(&pending.branch, "autosquash")?;
+        }
+        run_phase_autosquash(repo, state, &stack, base_branch, json)?;
+        pending_fixups.clear();
+    }
+
     // Phase 3: Create sync plan
-    let plan = if let Some(service) = &service {
-        service.create_sync_plan(&stack, base_branch)?
-    } else {
-        sync::create_sync_plan(repo, &stack, base_branch)?
-    };
+    let plan = create_plan(repo, service.as_ref(), &stack, base_branch, tree)?;
+
+    // Refuse up front rather than failing partway through the rebases
+    for action in &plan.branches {
+        repo.protected_branches().check(&action.branch, "rebase")?;
+        stack.check_unlocked(&action.branch, "rebase")?;
+    }
 
     // Handle --dry-run mode
     if dry_run {
-        return print_dry_run(&plan, &reconcile_result, json, forge_auth_unavailable);
+        let autosquash_branches = if autosquash {
+            pending_fixups.as_slice()
+        } else {
+            &[]
+        };
+        return print_dry_run(
+            &plan,
+            &reconcile_result,
+            autosquash_branches,
+            json,
+            forge_auth_unavailable,
+        );
+    }
+
+    if !json {
+        for action in &plan.branches {
+            if action.parent_rewritten {
+                output::detail(&format!(
+                    "  → {}: {} was rewritten, replaying only its own commits",
+                    action.branch, action.parent_branch
+                ));
+            } else if action.applied_commits > 0 {
+                output::detail(&format!(
+                    "  → {}: dropping {} commit(s) already in {}",
+                    action.branch, action.applied_commits, action.parent_branch
+                ));
+            }
+        }
     }
 
     // Execute sync
@@ -353,29 +522,72 @@ fn run_sync_phases(
 
     // If paused on conflict, return early
     if let SyncResult::Paused { .. } = &sync_result {
-        return handle_sync_result(sync_result, json, forge_auth_unavailable);
+        return handle_sync_result(ctx, sync_result, json, forge_auth_unavailable);
     }
 
     // Phase 4 & 5: Update PR bases and push
-    run_phase_finalize(
-        service.as_ref(),
-        state,
-        repo,
-        &reconcile_result,
-        rt,
-        json,
-        no_push,
-    )?;
+    run_phase_finalize(ctx, service.as_ref(), &reconcile_result, json, push)?;
 
-    handle_sync_result(sync_result, json, forge_auth_unavailable)
+    handle_sync_result(ctx, sync_result, json, forge_auth_unavailable)?;
+
+    if !json && !pending_fixups.is_empty() {
+        output::info(&format!(
+            "{} branch(es) have fixup commits waiting - run `rung sync --autosquash` to squash them",
+            pending_fixups.len()
+        ));
+    }
+    Ok(())
+}
+
+/// Plan the rebases, keeping only the tree containing `tree` if given.
+fn create_plan(
+    repo: &Repository,
+    service: Option<&SyncService<'_, Repository, Forge>>,
+    stack: &Stack,
+    base_branch: &str,
+    tree: Option<&str>,
+) -> Result<SyncPlan> {
+    let mut plan = match service {
+        Some(service) => service.create_sync_plan(stack, base_branch)?,
+        None => sync::create_sync_plan(repo, stack, base_branch)?,
+    };
+    if let Some(branch) = tree {
+        let Some(root) = stack.ancestry(branch).first().map(|b| b.name.to_string()) else {
+            bail!("Branch '{branch}' is not in the stack");
+        };
+        plan.retain_tree(&root);
+    }
+    Ok(plan)
+}
+
+/// Squash pending fixup commits into their targets across the stack.
+fn run_phase_autosquash(
+    repo: &Repository,
+    state: &State,
+    stack: &rung_core::Stack,
+    base_branch: &str,
+    json: bool,
+) -> Result<()> {
+    if !json {
+        output::info("Squashing fixup commits...");
+    }
+    let result = absorb::autosquash_fixups(repo, state, stack, base_branch)?;
+    if !json {
+        for branch in &result.squashed {
+            output::detail(&format!("  → {branch} (squashed)"));
+        }
+        for branch in &result.restacked {
+            output::detail(&format!("  → {branch} (restacked)"));
+        }
+    }
+    Ok(())
 }
 
 /// Phase 1: Detect merged PRs and reconcile stack.
 fn run_phase_detect_merged(
+    ctx: &CommandContext,
     service: Option<&SyncService<'_, Repository, Forge>>,
-    state: &State,
     base_branch: &str,
-    rt: &tokio::runtime::Runtime,
     json: bool,
 ) -> Result<ReconcileResult> {
     let Some(service) = service else {
@@ -385,7 +597,9 @@ fn run_phase_detect_merged(
     if !json {
         output::info("Checking PRs and validating bases...");
     }
-    let result = rt.block_on(service.detect_and_reconcile_merged(state, base_branch))?;
+    let result = ctx
+        .runtime()?
+        .block_on(service.detect_and_reconcile_merged(&ctx.state, base_branch))?;
     print_reconcile_results(&result, json);
     Ok(result)
 }
@@ -409,7 +623,7 @@ fn run_phase_remove_stale(
             stale_result.removed.len()
         ));
         for branch in &stale_result.removed {
-            println!("  → {branch}");
+            println!("  {} {branch}", output::glyphs("→"));
         }
     }
     Ok(())
@@ -424,7 +638,11 @@ fn handle_empty_stack(json: bool, forge_auth_unavailable: bool) -> Result<()> {
             backup_id: None,
             conflict_branch: None,
             conflict_files: vec![],
+            conflict: None,
+            skipped_commits: vec![],
+            skipped_branches: vec![],
             forge_auth_unavailable,
+            offline: utils::is_offline(),
         });
     }
     output::info("No branches in stack - nothing to sync");
@@ -456,13 +674,11 @@ fn execute_sync_plan(
 
 /// Phase 4 & 5: Update PR bases on GitHub and push branches.
 fn run_phase_finalize(
+    ctx: &CommandContext,
     service: Option<&SyncService<'_, Repository, Forge>>,
-    state: &State,
-    repo: &Repository,
     reconcile_result: &ReconcileResult,
-    rt: &tokio::runtime::Runtime,
     json: bool,
-    no_push: bool,
+    push: PushMode,
 ) -> Result<()> {
     // Update PR bases if needed
     if let Some(service) = service
@@ -471,27 +687,36 @@ fn run_phase_finalize(
         if !json {
             output::info("Updating PR base branches on GitHub...");
         }
-        rt.block_on(service.update_pr_bases(reconcile_result))?;
+        ctx.runtime()?
+            .block_on(service.update_pr_bases(reconcile_result))?;
         print_pr_updates(reconcile_result, json);
     }
 
     // Push branches
-    if !no_push {
-        push_branches(service, state, repo, json)?;
+    if utils::is_offline() {
+        if !json && push != PushMode::None {
+            output::info("Offline - not pushing; run `rung submit` when back online");
+        }
+    } else if push != PushMode::None {
+        push_branches(service, &ctx.state, &ctx.repo, push, json)?;
     }
 
     Ok(())
 }
 
-/// Push all stack branches to remote.
+/// Push the stack branches `mode` selects to remote.
 fn push_branches(
     service: Option<&SyncService<'_, Repository, Forge>>,
     state: &State,
     repo: &Repository,
+    mode: PushMode,
     json: bool,
 ) -> Result<()> {
+    let mut skip = held_back(repo, state, mode, json)?;
     if let Some(service) = service {
-        let push_results = service.push_stack_branches(state)?;
+        skip.extend(handle_deleted_remotes(service, state, &skip, json)?);
+        let push_results = service.push_stack_branches(state, &skip)?;
+        utils::set_stack_upstreams(repo, &state.load_stack()?, json);
         if !json {
             let pushed = push_results.iter().filter(|p| p.success).count();
             for result in push_results.iter().filter(|p| !p.success) {
@@ -502,11 +727,131 @@ fn push_branches(
             }
         }
     } else {
-        push_stack_branches(repo, state, json)?;
+        push_stack_branches(repo, state, &skip, json)?;
     }
     Ok(())
 }
 
+/// Stack branches `mode` leaves unpushed.
+fn held_back(repo: &Repository, state: &State, mode: PushMode, json: bool) -> Result<Vec<String>> {
+    let stack = state.load_stack()?;
+    let local = stack
+        .branches
+        .iter()
+        .filter(|b| repo.branch_exists(&b.name));
+    let held: Vec<String> = match mode {
+        PushMode::All | PushMode::None => return Ok(vec![]),
+        PushMode::Prompt => return prompt_held_back(repo, &stack, json),
+        PushMode::Existing => local
+            .filter(|b| b.pr.is_none())
+            .map(|b| b.name.to_string())
+            .collect(),
+        PushMode::Ancestors => {
+            let current = repo.current_branch()?;
+            let below: Vec<&str> = stack
+                .ancestry(&current)
+                .iter()
+                .map(|b| b.name.as_str())
+                .filter(|name| *name != current)
+                .collect();
+            local
+                .filter(|b| !below.contains(&b.name.as_str()))
+                .map(|b| b.name.to_string())
+                .collect()
+        }
+    };
+    if !json && !held.is_empty() {
+        output::detail(&format!(
+            "  Not pushing {} branch(es) (push mode: {mode})",
+            held.len()
+        ));
+    }
+    Ok(held)
+}
+
+/// Ask which branches to push, only for those that differ from the remote.
+///
+/// Without a terminal to ask in, nothing is pushed.
+fn prompt_held_back(repo: &Repository, stack: &Stack, json: bool) -> Result<Vec<String>> {
+    let changed = stack.branches.iter().filter(|b| {
+        repo.branch_exists(&b.name)
+            && repo.branch_commit(&b.name).ok() != repo.remote_branch_commit(&b.name).ok()
+    });
+    if json || !std::io::stdin().is_terminal() {
+        if !json {
+            output::warn("No terminal to ask in - not pushing; run `rung submit` to push");
+        }
+        return Ok(changed.map(|b| b.name.to_string()).collect());
+    }
+
+    let mut held = Vec::new();
+    for branch in changed {
+        let push = Confirm::new(&format!("Push {}?", branch.name))
+            .with_default(true)
+            .prompt()
+            .context("Confirmation cancelled")?;
+        if !push {
+            held.push(branch.name.to_string());
+        }
+    }
+    Ok(held)
+}
+
+/// Deal with stack branches whose remote branch was deleted.
+///
+/// Interactively offers to re-push or untrack each one; otherwise warns.
+/// Branches in `held` aren't being pushed anyway and are left alone.
+/// Returns the branches that should be left out of the regular push.
+fn handle_deleted_remotes(
+    service: &SyncService<'_, Repository, Forge>,
+    state: &State,
+    held: &[String],
+    json: bool,
+) -> Result<Vec<String>> {
+    // Failing to query the remote shouldn't block the push itself
+    let Ok(mut deleted) = service.find_deleted_remote_branches(state) else {
+        return Ok(vec![]);
+    };
+    deleted.retain(|branch| !held.contains(branch));
+
+    let interactive = !json && std::io::stdin().is_terminal();
+    for branch in &deleted {
+        if !interactive {
+            if !json {
+                output::warn(&format!(
+                    "Remote branch for '{branch}' was deleted - skipping push"
+                ));
+                output::detail(
+                    "  Run `rung sync` interactively to re-push it or remove it from the stack",
+                );
+            }
+            continue;
+        }
+
+        let options = vec!["Re-push", "Remove from stack", "Leave as is"];
+        let choice = Select::new(
+            &format!("Remote branch for '{branch}' was deleted:"),
+            options,
+        )
+        .prompt()
+        .context("Selection cancelled")?;
+
+        match choice {
+            "Re-push" => match service.repush_branch(branch) {
+                Ok(()) => output::success(&format!("Re-pushed {branch}")),
+                Err(e) => output::warn(&format!("Could not push {branch}: {e}")),
+            },
+            "Remove from stack" => {
+                service.untrack_branch(state, branch)?;
+                output::success(&format!("Removed {branch} from the stack"));
+            }
+            _ => {}
+        }
+    }
+
+    Ok(deleted)
+}
+
 /// Print reconcile results.
 fn print_reconcile_results(result: &ReconcileResult, json: bool) {
     if json {
@@ -539,6 +884,7 @@ fn print_reconcile_results(result: &ReconcileResult, json: bool) {
 fn print_dry_run(
     plan: &rung_core::sync::SyncPlan,
     reconcile_result: &ReconcileResult,
+    autosquash: &[PendingFixups],
     json: bool,
     forge_auth_unavailable: bool,
 ) -> Result<()> {
@@ -560,9 +906,13 @@ fn print_dry_run(
                 .map(|action| DryRunRebase {
                     branch: action.branch.clone(),
                     new_base: action.new_base.clone(),
+                    applied_commits: action.applied_commits,
+                    parent_rewritten: action.parent_rewritten,
                 })
                 .collect(),
+            branches_to_autosquash: autosquash.iter().map(|p| p.branch.clone()).collect(),
             forge_auth_unavailable,
+            offline: utils::is_offline(),
         };
         println!("{}", serde_json::to_string_pretty(&output)?);
         return Ok(());
@@ -571,12 +921,44 @@ fn print_dry_run(
     if !reconcile_result.merged.is_empty() {
         println!("  Merged PRs detected: {}", reconcile_result.merged.len());
     }
+    if !autosquash.is_empty() {
+        println!("  Fixups to squash:");
+        for pending in autosquash {
+            println!(
+                "    {} {} ({} fixup(s))",
+                output::glyphs("→"),
+                pending.branch,
+                pending.count
+            );
+        }
+    }
     if !plan.is_empty() {
         println!("  Branches to rebase:");
         for action in &plan.branches {
             // Use char-safe truncation to avoid UTF-8 boundary panic
             let base_short: String = action.new_base.chars().take(8).collect();
-            println!("    → {} (onto {base_short})", action.branch);
+            if action.parent_rewritten {
+                println!(
+                    "    {} {} (onto {base_short}, {} was rewritten)",
+                    output::glyphs("→"),
+                    action.branch,
+                    action.parent_branch
+                );
+            } else if action.applied_commits > 0 {
+                println!(
+                    "    {} {} (onto {base_short}, dropping {} commit(s) already in {})",
+                    output::glyphs("→"),
+                    action.branch,
+                    action.applied_commits,
+                    action.parent_branch
+                );
+            } else {
+                println!(
+                    "    {} {} (onto {base_short})",
+                    output::glyphs("→"),
+                    action.branch
+                );
+            }
         }
     }
     Ok(())
@@ -623,13 +1005,20 @@ fn print_conflict_predictions(predictions: &SyncConflictPrediction, json: bool)
 
     for branch_prediction in &predictions.branches {
         println!(
-            "  {} → {}",
-            branch_prediction.branch, branch_prediction.onto
+            "  {} {} {}",
+            branch_prediction.branch,
+            output::glyphs("→"),
+            branch_prediction.onto
         );
         for conflict in &branch_prediction.conflicts {
             // Truncate commit hash for display
             let hash_short: String = conflict.commit_hash.chars().take(7).collect();
-            println!("    • {} (\"{}\"):", hash_short, conflict.commit_summary);
+            println!(
+                "    {} {} (\"{}\"):",
+                output::glyphs("•"),
+                hash_short,
+                conflict.commit_summary
+            );
             for file in &conflict.files {
                 println!("      - {file}");
             }
@@ -660,8 +1049,13 @@ fn print_pr_updates(reconcile_result: &ReconcileResult, json: bool) {
     }
 }
 
-/// Push all branches in the stack to remote.
-fn push_stack_branches(repo: &Repository, state: &State, json: bool) -> Result<()> {
+/// Push the branches in the stack to remote, except those in `skip`.
+fn push_stack_branches(
+    repo: &Repository,
+    state: &State,
+    skip: &[String],
+    json: bool,
+) -> Result<()> {
     let stack = state.load_stack()?;
 
     if stack.is_empty() {
@@ -674,6 +1068,9 @@ fn push_stack_branches(repo: &Repository, state: &State, json: bool) -> Result<(
 
     let mut pushed = 0;
     for branch in &stack.branches {
+        if skip.iter().any(|s| s == branch.name.as_str()) {
+            continue;
+        }
         if repo.branch_exists(&branch.name) {
             match repo.push(&branch.name, true) {
                 Ok(()) => pushed += 1,
@@ -686,6 +1083,8 @@ fn push_stack_branches(repo: &Repository, state: &State, json: bool) -> Result<(
         }
     }
 
+    utils::set_stack_upstreams(repo, &stack, json);
+
     if !json && pushed > 0 {
         output::success(&format!("Pushed {pushed} branch(es)"));
     }
@@ -694,7 +1093,12 @@ fn push_stack_branches(repo: &Repository, state: &State, json: bool) -> Result<(
 }
 
 #[allow(clippy::unnecessary_wraps)]
-fn handle_sync_result(result: SyncResult, json: bool, forge_auth_unavailable: bool) -> Result<()> {
+fn handle_sync_result(
+    ctx: &CommandContext,
+    result: SyncResult,
+    json: bool,
+    forge_auth_unavailable: bool,
+) -> Result<()> {
     match result {
         SyncResult::AlreadySynced => {
             if json {
@@ -704,7 +1108,11 @@ fn handle_sync_result(result: SyncResult, json: bool, forge_auth_unavailable: bo
                     backup_id: None,
                     conflict_branch: None,
                     conflict_files: vec![],
+                    conflict: None,
+                    skipped_commits: vec![],
+                    skipped_branches: vec![],
                     forge_auth_unavailable,
+                    offline: utils::is_offline(),
                 });
             }
             output::success("Stack is already up-to-date");
@@ -712,6 +1120,8 @@ fn handle_sync_result(result: SyncResult, json: bool, forge_auth_unavailable: bo
         SyncResult::Complete {
             branches_rebased,
             backup_id,
+            skipped,
+            skipped_branches,
         } => {
             if json {
                 return output_json(&SyncOutput {
@@ -720,20 +1130,49 @@ fn handle_sync_result(result: SyncResult, json: bool, forge_auth_unavailable: bo
                     backup_id: Some(backup_id),
                     conflict_branch: None,
                     conflict_files: vec![],
+                    conflict: None,
+                    skipped_commits: skipped
+                        .into_iter()
+                        .map(|s| SkippedCommitsOutput {
+                            branch: s.branch,
+                            commits: s.commits,
+                        })
+                        .collect(),
+                    skipped_branches,
                     forge_auth_unavailable,
+                    offline: utils::is_offline(),
                 });
             }
+            for entry in &skipped {
+                output::info(&format!(
+                    "Skipped {} commit(s) in {} already applied upstream:",
+                    entry.commits.len(),
+                    entry.branch
+                ));
+                for commit in &entry.commits {
+                    output::detail(&format!("  → {commit}"));
+                }
+            }
             // Use char-safe truncation for backup_id display
             let backup_short: String = backup_id.chars().take(8).collect();
             output::success(&format!(
                 "Synced {branches_rebased} branches (backup: {backup_short})"
             ));
+            if !skipped_branches.is_empty() {
+                output::warn(&format!(
+                    "Skipped {} branch(es), left as before the sync: {}",
+                    skipped_branches.len(),
+                    skipped_branches.join(", ")
+                ));
+            }
         }
         SyncResult::Paused {
             at_branch,
             conflict_files,
             backup_id,
         } => {
+            let report =
+                ConflictReport::for_sync(&ctx.repo, &ctx.state, &at_branch, conflict_files.clone());
             if json {
                 return output_json(&SyncOutput {
                     status: SyncStatus::Conflict,
@@ -741,17 +1180,14 @@ fn handle_sync_result(result: SyncResult, json: bool, forge_auth_unavailable: bo
                     backup_id: Some(backup_id),
                     conflict_branch: Some(at_branch),
                     conflict_files,
+                    conflict: Some(report),
+                    skipped_commits: vec![],
+                    skipped_branches: vec![],
                     forge_auth_unavailable,
+                    offline: utils::is_offline(),
                 });
             }
-            output::warn(&format!("Conflict in branch '{at_branch}'"));
-            output::info("Conflicting files:");
-            for file in &conflict_files {
-                println!("  → {file}");
-            }
-            println!();
-            output::info("Resolve conflicts, then run: rung sync --continue");
-            output::info("Or abort with: rung sync --abort");
+            report.print();
         }
     }
     Ok(())
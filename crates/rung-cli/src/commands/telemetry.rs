@@ -0,0 +1,75 @@
+//! `rung telemetry` command - opt in to or out of anonymous usage statistics.
+
+use anyhow::Result;
+use clap::ValueEnum;
+use serde::Serialize;
+
+use crate::output;
+use crate::telemetry::{self, Settings};
+
+/// What to do with the telemetry setting.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum TelemetryAction {
+    /// Send anonymous usage statistics.
+    On,
+    /// Stop sending statistics and discard any not yet sent.
+    Off,
+    /// Show the current setting.
+    Status,
+}
+
+/// JSON output for telemetry command.
+#[derive(Debug, Serialize)]
+struct TelemetryOutput {
+    enabled: bool,
+    queued_events: usize,
+    endpoint: Option<String>,
+}
+
+/// Run the telemetry command.
+pub fn run(json: bool, action: TelemetryAction) -> Result<()> {
+    match action {
+        TelemetryAction::On => {
+            telemetry::save_settings(Settings { enabled: true })?;
+            if !json {
+                output::success("Telemetry enabled - thank you!");
+                output::detail(
+                    "  Collected: command name, duration, error class, rung version, OS",
+                );
+                output::detail(
+                    "  Never collected: branch names, paths, remotes, or error messages",
+                );
+            }
+        }
+        TelemetryAction::Off => {
+            telemetry::save_settings(Settings { enabled: false })?;
+            telemetry::clear_queue();
+            if !json {
+                output::success("Telemetry disabled");
+            }
+        }
+        TelemetryAction::Status => {}
+    }
+
+    let enabled = telemetry::load_settings().is_some_and(|s| s.enabled);
+    if json {
+        let output = TelemetryOutput {
+            enabled,
+            queued_events: telemetry::queued_events(),
+            endpoint: telemetry::endpoint(),
+        };
+        println!("{}", serde_json::to_string_pretty(&output)?);
+    } else if action == TelemetryAction::Status {
+        output::info(&format!(
+            "Telemetry is {}",
+            if enabled { "on" } else { "off" }
+        ));
+        if enabled {
+            output::detail(&format!(
+                "  {} event(s) waiting to be sent",
+                telemetry::queued_events()
+            ));
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,145 @@
+//! `rung track` / `rung untrack` commands - Quickly add a branch to the
+//! stack or drop it from the stack.
+
+use anyhow::{Context, Result, bail};
+use rung_core::BranchName;
+use rung_git::Repository;
+use serde::Serialize;
+
+use crate::commands::utils;
+use crate::output;
+use crate::services::AdoptService;
+
+/// JSON output for track and untrack.
+#[derive(Debug, Serialize)]
+struct TrackOutput {
+    branch: String,
+    parent: String,
+    tracked: bool,
+    dry_run: bool,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    children_moved: Vec<String>,
+}
+
+/// Run the track command.
+pub fn run(json: bool, branch: Option<&str>, parent: Option<&str>, dry_run: bool) -> Result<()> {
+    let utils::CommandContext { repo, state, .. } = utils::CommandContext::open()?;
+    let _lock = utils::lock_state(&repo, &state)?;
+
+    let service = AdoptService::new(&repo);
+    let branch_name = target_branch(&repo, branch)?;
+    let branch_validated = BranchName::new(&branch_name).context("Invalid branch name")?;
+
+    if !service.branch_exists(&branch_name) {
+        bail!("Branch '{branch_name}' does not exist");
+    }
+    if service.is_in_stack(&state, &branch_name)? {
+        bail!("Branch '{branch_name}' is already in the stack");
+    }
+    if branch_name == service.default_branch(&state)? {
+        bail!("'{branch_name}' is the base branch - it can't be tracked as part of a stack");
+    }
+
+    let parent_name = match parent {
+        Some(p) => {
+            service.validate_parent(&state, p)?;
+            p.to_string()
+        }
+        None => service.infer_parent(&state, &branch_name)?,
+    };
+
+    if !dry_run {
+        service.adopt_branch(&state, &branch_validated, &parent_name)?;
+    }
+
+    if json {
+        return output_json(&TrackOutput {
+            branch: branch_name,
+            parent: parent_name,
+            tracked: true,
+            dry_run,
+            children_moved: vec![],
+        });
+    }
+
+    if dry_run {
+        output::info(&format!(
+            "Would track '{branch_name}' with parent '{parent_name}'"
+        ));
+    } else {
+        output::success(&format!(
+            "Tracking '{branch_name}' with parent '{parent_name}'"
+        ));
+        if parent.is_none() {
+            output::detail("  Wrong parent? Run `rung restack --onto <branch>` to move it");
+        }
+    }
+    Ok(())
+}
+
+/// Run the untrack command.
+pub fn untrack(json: bool, branch: Option<&str>, dry_run: bool) -> Result<()> {
+    let utils::CommandContext { repo, state, .. } = utils::CommandContext::open()?;
+    let _lock = utils::lock_state(&repo, &state)?;
+
+    let service = AdoptService::new(&repo);
+    let branch_name = target_branch(&repo, branch)?;
+
+    let (parent_name, children_moved) = if dry_run {
+        let stack = state.load_stack()?;
+        let Some(tracked) = stack.find_branch(&branch_name) else {
+            bail!("Branch '{branch_name}' is not in the stack");
+        };
+        let parent = match &tracked.parent {
+            Some(p) => p.to_string(),
+            None => service.default_branch(&state)?,
+        };
+        let children = stack
+            .children_of(&branch_name)
+            .iter()
+            .map(|c| c.name.to_string())
+            .collect();
+        (parent, children)
+    } else {
+        let result = service.untrack_branch(&state, &branch_name)?;
+        (result.parent_name, result.children_moved)
+    };
+
+    if json {
+        return output_json(&TrackOutput {
+            branch: branch_name,
+            parent: parent_name,
+            tracked: false,
+            dry_run,
+            children_moved,
+        });
+    }
+
+    let verb = if dry_run { "Would stop" } else { "Stopped" };
+    output::success(&format!(
+        "{verb} tracking '{branch_name}' - the git branch is left as it is"
+    ));
+    if !children_moved.is_empty() {
+        output::info(&format!(
+            "{} {} onto '{parent_name}', keeping '{branch_name}''s commits",
+            if dry_run { "Would move" } else { "Moved" },
+            children_moved.join(", ")
+        ));
+    }
+    Ok(())
+}
+
+/// The named branch, or the current one.
+fn target_branch(repo: &Repository, branch: Option<&str>) -> Result<String> {
+    if let Some(branch) = branch {
+        return Ok(branch.to_string());
+    }
+    utils::ensure_on_branch(repo)?;
+    Ok(repo.current_branch()?)
+}
+
+/// Output track result as JSON.
+fn output_json(output: &TrackOutput) -> Result<()> {
+    println!("{}", serde_json::to_string_pretty(output)?);
+    Ok(())
+}
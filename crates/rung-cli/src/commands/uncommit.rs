@@ -0,0 +1,91 @@
+//! `rung uncommit` command - Move the current branch's tip commit back into the index.
+
+use anyhow::{Context, Result, bail};
+use serde::Serialize;
+
+use crate::commands::utils;
+use crate::output;
+
+/// JSON output for uncommit command.
+#[derive(Debug, Serialize)]
+struct UncommitOutput {
+    branch: String,
+    commit: String,
+    message: String,
+    head: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    needs_restack: Vec<String>,
+}
+
+/// Run the uncommit command.
+pub fn run(json: bool) -> Result<()> {
+    let utils::CommandContext { repo, state, .. } = utils::CommandContext::open()?;
+    let _lock = utils::lock_state(&repo, &state)?;
+    utils::ensure_on_branch(&repo)?;
+    if state.is_sync_in_progress() || state.is_restack_in_progress() {
+        bail!("A sync or restack is in progress. Complete or abort it first.");
+    }
+
+    let branch = repo.current_branch()?;
+    let mut stack = state.load_stack()?;
+    let Some(stack_branch) = stack.find_branch(&branch) else {
+        bail!("Branch '{branch}' is not in the stack");
+    };
+    let parent = match &stack_branch.parent {
+        Some(parent) => parent.to_string(),
+        None => state.default_branch()?,
+    };
+
+    // Only a commit of the branch's own can go; the parent's tip stays put
+    let tip = repo.head_commit()?;
+    let parent_tip = repo.branch_commit(&parent)?;
+    if repo.merge_base(tip, parent_tip)? == tip {
+        bail!("'{branch}' has no commits of its own on top of '{parent}'");
+    }
+    let commit = repo.find_commit(tip)?;
+    let message = commit.summary().unwrap_or_default().to_string();
+    let new_head = commit
+        .parent_id(0)
+        .context("The tip commit has no parent to reset to")?;
+    drop(commit);
+
+    // Children still contain the uncommitted commit; the next sync drops it
+    utils::record_child_bases(&repo, &mut stack, &branch, tip);
+    state.save_stack(&stack)?;
+
+    repo.soft_reset(new_head)?;
+    let tip_str = tip.to_string();
+
+    let needs_restack: Vec<String> = stack
+        .descendants(&branch)
+        .iter()
+        .map(|b| b.name.to_string())
+        .collect();
+
+    if json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&UncommitOutput {
+                branch,
+                commit: tip_str,
+                message,
+                head: new_head.to_string(),
+                needs_restack,
+            })?
+        );
+        return Ok(());
+    }
+
+    output::success(&format!(
+        "Uncommitted {} {message} from {branch}",
+        &tip_str[..8]
+    ));
+    output::detail("  Its changes are staged; `git commit -c ORIG_HEAD` reuses the message");
+    if !needs_restack.is_empty() {
+        output::warn(&format!(
+            "{} still contain the commit - run `rung sync` after recommitting to restack them",
+            needs_restack.join(", ")
+        ));
+    }
+    Ok(())
+}
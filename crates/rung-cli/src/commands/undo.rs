@@ -1,34 +1,236 @@
-//! `rung undo` command - Undo the last sync operation.
+//! `rung undo` command - Undo the last sync, or a specific earlier operation.
 
-use anyhow::{Context, Result, bail};
-use rung_core::State;
-use rung_core::sync;
+use anyhow::Result;
+use rung_core::sync::{self, OperationUndo, UndoBlocker, UndoPreview};
 use rung_git::Repository;
+use serde::Serialize;
 
+use crate::commands::utils::{self, CommandContext};
 use crate::output;
 
+/// JSON output for undo command.
+#[derive(Debug, Serialize)]
+struct UndoOutput {
+    dry_run: bool,
+    backup_id: String,
+    branches: Vec<UndoBranchOutput>,
+}
+
+#[derive(Debug, Serialize)]
+struct UndoBranchOutput {
+    name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    current: Option<String>,
+    restore_to: String,
+}
+
+impl UndoOutput {
+    fn new(preview: UndoPreview, dry_run: bool) -> Self {
+        Self {
+            dry_run,
+            backup_id: preview.backup_id,
+            branches: preview
+                .branches
+                .into_iter()
+                .map(|b| UndoBranchOutput {
+                    name: b.name,
+                    current: b.current,
+                    restore_to: b.restore_to,
+                })
+                .collect(),
+        }
+    }
+}
+
+/// JSON output for undoing an operation from the audit log.
+#[derive(Debug, Serialize)]
+struct OperationUndoOutput {
+    dry_run: bool,
+    operation: usize,
+    command: String,
+    branches: Vec<OperationBranchOutput>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    blockers: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct OperationBranchOutput {
+    name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    current: Option<String>,
+    /// `null` when the branch is deleted.
+    restore_to: Option<String>,
+}
+
+impl OperationUndoOutput {
+    fn new(plan: &OperationUndo, dry_run: bool) -> Self {
+        Self {
+            dry_run,
+            operation: plan.id,
+            command: plan.command.clone(),
+            branches: plan
+                .branches
+                .iter()
+                .map(|b| OperationBranchOutput {
+                    name: b.name.clone(),
+                    current: b.current.clone(),
+                    restore_to: b.restore_to.clone(),
+                })
+                .collect(),
+            blockers: plan.blockers.iter().map(ToString::to_string).collect(),
+        }
+    }
+}
+
+/// Parse an operation id as listed by `rung audit`, as `12` or `#12`.
+pub fn parse_operation_id(value: &str) -> Result<usize, String> {
+    value
+        .strip_prefix('#')
+        .unwrap_or(value)
+        .parse()
+        .ok()
+        .filter(|&id| id > 0)
+        .ok_or_else(|| format!("'{value}' is not an operation id - see `rung audit`"))
+}
+
 /// Run the undo command.
-pub fn run() -> Result<()> {
-    // Open repository
-    let repo = Repository::open_current().context("Not inside a git repository")?;
+pub fn run(json: bool, operation: Option<usize>, dry_run: bool) -> Result<()> {
+    let CommandContext { repo, state, .. } = CommandContext::open()?;
+    let _lock = utils::lock_state(&repo, &state)?;
 
-    // Get state manager
-    let workdir = repo.workdir().context("Cannot run in bare repository")?;
-    let state = State::new(workdir)?;
+    if let Some(id) = operation {
+        return undo_operation(&repo, &state, id, json, dry_run);
+    }
 
-    // Ensure initialized
-    if !state.is_initialized() {
-        bail!("Rung not initialized - run `rung init` first");
+    let preview = sync::preview_undo(&repo, &state)?;
+
+    if dry_run {
+        if json {
+            return output_json(&UndoOutput::new(preview, true));
+        }
+        output::info(&format!(
+            "Would restore {} branches from backup {}:",
+            preview.branches.len(),
+            short(&preview.backup_id, 8)
+        ));
+        for branch in &preview.branches {
+            let current = branch
+                .current
+                .as_deref()
+                .map_or("(deleted)", |c| short(c, 7));
+            output::detail(&format!(
+                "  {}: {current} → {}",
+                branch.name,
+                short(&branch.restore_to, 7)
+            ));
+        }
+        return Ok(());
     }
 
     // Perform undo
     let result = sync::undo_sync(&repo, &state)?;
 
+    if json {
+        return output_json(&UndoOutput::new(preview, false));
+    }
+
     output::success(&format!(
         "Restored {} branches from backup {}",
         result.branches_restored,
-        &result.backup_id[..8.min(result.backup_id.len())]
+        short(&result.backup_id, 8)
+    ));
+
+    Ok(())
+}
+
+/// Revert a single operation from the audit log.
+fn undo_operation(
+    repo: &Repository,
+    state: &rung_core::State,
+    id: usize,
+    json: bool,
+    dry_run: bool,
+) -> Result<()> {
+    let entries = state.load_audit()?;
+    let plan = sync::plan_operation_undo(repo, state, &entries, id)?;
+
+    if !plan.is_safe() {
+        if json {
+            output_json(&OperationUndoOutput::new(&plan, dry_run))?;
+        } else {
+            output::info(&format!("Can't undo #{id} ({}):", plan.command));
+            for blocker in &plan.blockers {
+                output::detail(&format!("  {blocker}"));
+            }
+            if plan
+                .blockers
+                .iter()
+                .any(|b| matches!(b, UndoBlocker::LaterOperation { .. }))
+            {
+                output::detail("Undo the later operations first, newest first.");
+            }
+        }
+        return Err(rung_core::Error::UndoBlocked(id).into());
+    }
+
+    if dry_run {
+        if json {
+            return output_json(&OperationUndoOutput::new(&plan, true));
+        }
+        output::info(&format!("Would undo #{id} ({}):", plan.command));
+        print_operation_branches(&plan);
+        return Ok(());
+    }
+
+    let restored = sync::undo_operation(repo, state, &plan)?;
+
+    if json {
+        return output_json(&OperationUndoOutput::new(&plan, false));
+    }
+    output::success(&format!(
+        "Undid #{id} ({}), restoring {restored} branch{}",
+        plan.command,
+        if restored == 1 { "" } else { "es" }
     ));
+    print_operation_branches(&plan);
+    Ok(())
+}
+
+/// Print each branch of a targeted undo as `name: current → target`.
+fn print_operation_branches(plan: &OperationUndo) {
+    for branch in &plan.branches {
+        let current = branch
+            .current
+            .as_deref()
+            .map_or("(deleted)", |c| short(c, 7));
+        let target = branch
+            .restore_to
+            .as_deref()
+            .map_or("(deleted)", |c| short(c, 7));
+        output::detail(&format!("  {}: {current} → {target}", branch.name));
+    }
+}
 
+/// Truncate an id for display.
+fn short(id: &str, len: usize) -> &str {
+    &id[..len.min(id.len())]
+}
+
+/// Output undo result as JSON.
+fn output_json(output: &impl Serialize) -> Result<()> {
+    println!("{}", serde_json::to_string_pretty(output)?);
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_operation_id() {
+        assert_eq!(parse_operation_id("12"), Ok(12));
+        assert_eq!(parse_operation_id("#3"), Ok(3));
+        assert!(parse_operation_id("0").is_err());
+        assert!(parse_operation_id("sync").is_err());
+    }
+}
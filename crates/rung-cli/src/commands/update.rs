@@ -3,52 +3,548 @@
 //! This module is excluded from test coverage as it performs self-update
 //! operations that cannot be meaningfully tested in isolation.
 
-use std::path::PathBuf;
-use std::process::Command;
+use std::cmp::Ordering;
+use std::fmt::Write;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
 
-use anyhow::{Context, Result};
+use anyhow::{Context, Result, bail};
+use rung_core::config::UpdateChannel;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
 
+use crate::commands::utils;
 use crate::output;
 
 const CURRENT_VERSION: &str = env!("CARGO_PKG_VERSION");
 const CRATE_NAME: &str = "rung-cli";
+const RELEASES_URL: &str = concat!(env!("CARGO_PKG_REPOSITORY"), "/releases/download");
+const CHECKSUMS_FILE: &str = "SHA256SUMS";
+const SIGNATURE_BUNDLE: &str = "SHA256SUMS.cosign.bundle";
+const OIDC_ISSUER: &str = "https://token.actions.githubusercontent.com";
+
+/// How the running binary was installed, which decides who updates it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum InstallMethod {
+    /// A Homebrew formula; `brew upgrade` replaces it.
+    Homebrew,
+    /// `cargo install`, tracked in cargo's install metadata.
+    Cargo,
+    /// `cargo binstall`, tracked in binstall's install metadata.
+    Binstall,
+    /// A Nix store path, which is read-only.
+    Nix,
+    /// A system package in `/usr/bin` or `/bin`.
+    System,
+    /// The install script or a release archive: rung replaces itself.
+    Manual,
+}
+
+impl InstallMethod {
+    /// Detect how the running binary was installed.
+    fn detect() -> Self {
+        let Ok(exe) = std::env::current_exe() else {
+            return Self::Manual;
+        };
+        let exe = exe.canonicalize().unwrap_or(exe);
+        let cargo_bin = cargo_bin_dir().map(|dir| dir.canonicalize().unwrap_or(dir));
+        let binstalled = cargo_bin_dir()
+            .and_then(|bin| {
+                bin.parent()
+                    .map(|home| home.join("binstall/crates-v1.json"))
+            })
+            .and_then(|path| fs::read_to_string(path).ok())
+            .is_some_and(|records| records.contains(&format!("\"{CRATE_NAME}\"")));
+        Self::from_path(&exe, cargo_bin.as_deref(), binstalled)
+    }
+
+    /// Classify an executable path. `binstalled` is whether binstall's
+    /// records list rung, which tells its installs from cargo's.
+    fn from_path(exe: &Path, cargo_bin: Option<&Path>, binstalled: bool) -> Self {
+        let path = exe.to_string_lossy().replace('\\', "/");
+        if ["/Cellar/", "/homebrew/", "/.linuxbrew/"]
+            .iter()
+            .any(|dir| path.contains(dir))
+        {
+            Self::Homebrew
+        } else if path.starts_with("/nix/store/") {
+            Self::Nix
+        } else if cargo_bin.is_some_and(|bin| exe.starts_with(bin)) {
+            if binstalled {
+                Self::Binstall
+            } else {
+                Self::Cargo
+            }
+        } else if ["/usr/bin/", "/bin/", "/usr/sbin/"]
+            .iter()
+            .any(|dir| path.starts_with(dir))
+        {
+            Self::System
+        } else {
+            Self::Manual
+        }
+    }
+
+    const fn describe(self) -> &'static str {
+        match self {
+            Self::Homebrew => "Homebrew",
+            Self::Cargo => "cargo install",
+            Self::Binstall => "cargo-binstall",
+            Self::Nix => "Nix",
+            Self::System => "a system package",
+            Self::Manual => "a release download",
+        }
+    }
+}
+
+/// How an installed update was checked before it replaced the binary.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum Verification {
+    /// Checksum matched and the checksums' signature verified.
+    Signature,
+    /// Checksum matched `SHA256SUMS`.
+    Checksum,
+    /// Installed without checks, with `--allow-unverified`.
+    Unverified,
+    /// Built from crates.io source by `cargo install`.
+    Source,
+    /// Installed by the package manager that owns the binary.
+    Delegated,
+}
+
+/// JSON output for update command.
+#[derive(Debug, Serialize)]
+struct UpdateOutput<'a> {
+    current_version: &'a str,
+    latest_version: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    channel: Option<UpdateChannel>,
+    install_method: InstallMethod,
+    update_available: bool,
+    updated: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    verification: Option<Verification>,
+}
 
 /// Run the update command.
-pub fn run(check_only: bool) -> Result<()> {
-    output::info(&format!("Current version: {CURRENT_VERSION}"));
+///
+/// Installs the newest release on `channel` (from config by default), or
+/// with `to` that exact version. Downloaded binaries must match the
+/// release's checksums unless `allow_unverified`. A binary owned by a
+/// package manager is updated through it, or not at all.
+pub fn run(
+    json: bool,
+    check_only: bool,
+    channel: Option<UpdateChannel>,
+    to: Option<&str>,
+    allow_unverified: bool,
+) -> Result<()> {
+    let channel = match channel {
+        Some(channel) => channel,
+        None => utils::load_config_anywhere()?.update.channel,
+    };
+    if !json {
+        output::info(&format!("Current version: {CURRENT_VERSION}"));
+    }
+
+    // Check the target version on crates.io
+    let info = fetch_crate_info()?;
+    let (target, channel) = match to {
+        Some(version) => (pinned_version(&info, version)?, None),
+        None => (channel_version(&info, channel)?, Some(channel)),
+    };
+    // A channel only moves forward; a pinned version may go back
+    let update_available = if channel.is_some() {
+        compare_versions(&target, CURRENT_VERSION) == Ordering::Greater
+    } else {
+        target != CURRENT_VERSION
+    };
+    let method = InstallMethod::detect();
 
-    // Check latest version from crates.io
-    let latest_version = fetch_latest_version()?;
+    if json {
+        let verification = if update_available && !check_only {
+            // Keep the installer's output off stdout
+            Some(update(method, &target, channel, true, allow_unverified)?)
+        } else {
+            None
+        };
+        let output = UpdateOutput {
+            current_version: CURRENT_VERSION,
+            latest_version: &target,
+            channel,
+            install_method: method,
+            update_available,
+            updated: update_available && !check_only,
+            verification,
+        };
+        println!("{}", serde_json::to_string_pretty(&output)?);
+        return Ok(());
+    }
 
-    if latest_version == CURRENT_VERSION {
+    if !update_available {
         output::success("Already up to date!");
         return Ok(());
     }
 
-    output::info(&format!("Latest version: {latest_version}"));
+    match channel {
+        Some(UpdateChannel::Stable) => output::info(&format!("Latest version: {target}")),
+        Some(channel) => output::info(&format!("Latest {channel} version: {target}")),
+        None => output::info(&format!("Requested version: {target}")),
+    }
 
     if check_only {
-        output::warn(&format!(
-            "Update available: {CURRENT_VERSION} → {latest_version}"
-        ));
+        output::warn(&format!("Update available: {CURRENT_VERSION} → {target}"));
         output::info("Run `rung update` to install");
         return Ok(());
     }
 
-    // Warn if binary is not in ~/.cargo/bin (cargo install won't replace it)
-    check_install_location();
+    if method != InstallMethod::Manual {
+        output::info(&format!("Installed via {}", method.describe()));
+    }
+    update(method, &target, channel, false, allow_unverified)?;
+
+    output::success(&format!("Updated: {CURRENT_VERSION} → {target}"));
+    Ok(())
+}
+
+/// Install `version` the way rung was installed, sending installer output
+/// to stderr with `json`.
+///
+/// Homebrew, cargo, and binstall installs go through their tool so its
+/// records stay right; Nix and system packages are refused. A manual
+/// install prefers the prebuilt release binary (fast), which must match the
+/// release's `SHA256SUMS` unless `allow_unverified`. Platforms without one
+/// build from source with `cargo install`, which checks crates.io downloads
+/// itself.
+fn update(
+    method: InstallMethod,
+    version: &str,
+    channel: Option<UpdateChannel>,
+    json: bool,
+    allow_unverified: bool,
+) -> Result<Verification> {
+    let exact = format!("={version}");
+    match method {
+        InstallMethod::Homebrew => {
+            if channel != Some(UpdateChannel::Stable) {
+                bail!(
+                    "rung is installed with Homebrew, which only has the latest stable release; \
+                     run `brew uninstall rung && cargo install rung-cli` to install other versions"
+                );
+            }
+            run_tool("brew", &["upgrade", "rung"], json)?;
+            return Ok(Verification::Delegated);
+        }
+        InstallMethod::Nix => {
+            bail!("rung is installed from the Nix store, which is read-only; update it through Nix")
+        }
+        InstallMethod::System => bail!(
+            "rung is installed as a system package; update it with your system package manager"
+        ),
+        InstallMethod::Binstall => {
+            run_cargo(
+                &["binstall", CRATE_NAME, "--version", &exact, "-y", "--force"],
+                json,
+            )?;
+            return Ok(Verification::Delegated);
+        }
+        InstallMethod::Cargo => return cargo_install(&exact, json),
+        InstallMethod::Manual => {}
+    }
+
+    if let Some(target) = release_target() {
+        if !json {
+            output::info("Downloading prebuilt binary...");
+        }
+        return install_release(version, target, json, allow_unverified);
+    }
 
-    // Try cargo-binstall first (fast, pre-built binary), fall back to cargo install
+    if !json {
+        // Warn if binary is not in ~/.cargo/bin (cargo won't replace it)
+        check_install_location();
+    }
+    cargo_install(&exact, json)
+}
+
+/// Install `exact` (a `=x.y.z` requirement) with cargo: cargo-binstall
+/// first when available (fast, pre-built binary), otherwise build from
+/// source with `cargo install`.
+fn cargo_install(exact: &str, json: bool) -> Result<Verification> {
     if has_cargo_binstall() {
-        output::info("Updating via cargo-binstall...");
-        run_cargo_binstall()?;
-    } else {
+        if !json {
+            output::info("Updating via cargo-binstall...");
+        }
+        run_cargo(
+            &["binstall", CRATE_NAME, "--version", exact, "-y", "--force"],
+            json,
+        )?;
+        return Ok(Verification::Delegated);
+    }
+    if !json {
         output::info("Updating via cargo install (this may take a minute)...");
-        run_cargo_install()?;
     }
+    run_cargo(
+        &["install", CRATE_NAME, "--version", exact, "--force"],
+        json,
+    )?;
+    Ok(Verification::Source)
+}
 
-    output::success(&format!("Updated: {CURRENT_VERSION} → {latest_version}"));
-    Ok(())
+/// The release target triple for this platform, if binaries are published
+/// for it.
+fn release_target() -> Option<&'static str> {
+    match (std::env::consts::OS, std::env::consts::ARCH) {
+        ("linux", "x86_64") if cfg!(target_env = "gnu") => Some("x86_64-unknown-linux-gnu"),
+        ("macos", "aarch64") => Some("aarch64-apple-darwin"),
+        ("macos", "x86_64") => Some("x86_64-apple-darwin"),
+        ("windows", "x86_64") => Some("x86_64-pc-windows-msvc"),
+        _ => None,
+    }
+}
+
+/// Download, verify, and install the release binary for `target`.
+fn install_release(
+    version: &str,
+    target: &str,
+    json: bool,
+    allow_unverified: bool,
+) -> Result<Verification> {
+    // A fresh, private directory: a predictable path could be created
+    // beforehand by another user to swap files after verification
+    let dir = tempfile::Builder::new()
+        .prefix("rung-update-")
+        .tempdir()
+        .context("Failed to create download directory")?;
+    download_and_install(dir.path(), version, target, json, allow_unverified)
+}
+
+fn download_and_install(
+    dir: &Path,
+    version: &str,
+    target: &str,
+    json: bool,
+    allow_unverified: bool,
+) -> Result<Verification> {
+    let base = format!("{RELEASES_URL}/v{version}");
+    let archive_ext = if target.contains("windows") {
+        "zip"
+    } else {
+        "tar.gz"
+    };
+    let asset = format!("rung-{version}-{target}.{archive_ext}");
+    let archive = dir.join(&asset);
+    if !download(&format!("{base}/{asset}"), &archive)? {
+        bail!("Release v{version} has no binary for {target}");
+    }
+
+    let verification = verify_release(dir, &base, &asset, &archive, json, allow_unverified)?;
+    if verification == Verification::Unverified {
+        if !allow_unverified {
+            bail!(
+                "Release v{version} publishes no checksum for {asset}; refusing to install an \
+                 unverified binary (use --allow-unverified to install anyway)"
+            );
+        }
+        if !json {
+            output::warn(&format!("Installing {asset} without verifying it"));
+        }
+    }
+
+    let binary = extract_binary(&archive, dir)?;
+    replace_current_exe(&binary)?;
+    Ok(verification)
+}
+
+/// Check `archive` against the release's `SHA256SUMS` and, when cosign is
+/// installed, the checksums' signature.
+///
+/// A mismatch or bad signature is an error, and so is a missing signature
+/// unless `allow_unverified`; a release without checksums is `Unverified`.
+fn verify_release(
+    dir: &Path,
+    base: &str,
+    asset: &str,
+    archive: &Path,
+    json: bool,
+    allow_unverified: bool,
+) -> Result<Verification> {
+    let sums_path = dir.join(CHECKSUMS_FILE);
+    if !download(&format!("{base}/{CHECKSUMS_FILE}"), &sums_path)? {
+        return Ok(Verification::Unverified);
+    }
+    let sums = fs::read_to_string(&sums_path).context("Failed to read SHA256SUMS")?;
+    let Some(expected) = find_checksum(&sums, asset) else {
+        return Ok(Verification::Unverified);
+    };
+
+    let bytes = fs::read(archive).context("Failed to read downloaded binary")?;
+    let actual = sha256_hex(&bytes);
+    if !actual.eq_ignore_ascii_case(expected) {
+        bail!("Checksum mismatch for {asset}: expected {expected}, got {actual}");
+    }
+    if !json {
+        output::detail("  Checksum matches SHA256SUMS");
+    }
+
+    if !has_cosign() {
+        if !json {
+            output::detail("  Install cosign to also verify the release signature");
+        }
+        return Ok(Verification::Checksum);
+    }
+    // Releases always publish the bundle, so a missing one means the
+    // assets were tampered with, unless the user accepts the risk
+    let bundle = dir.join(SIGNATURE_BUNDLE);
+    if !download(&format!("{base}/{SIGNATURE_BUNDLE}"), &bundle)? {
+        if !allow_unverified {
+            bail!(
+                "Release is missing {SIGNATURE_BUNDLE}; refusing to install {asset} without \
+                 checking its signature (use --allow-unverified to install anyway)"
+            );
+        }
+        if !json {
+            output::warn(&format!(
+                "Release is missing {SIGNATURE_BUNDLE}; installing on the checksum alone"
+            ));
+        }
+        return Ok(Verification::Checksum);
+    }
+    let status = Command::new("cosign")
+        .arg("verify-blob")
+        .arg("--bundle")
+        .arg(&bundle)
+        .args(["--certificate-identity-regexp", &signer_identity()])
+        .args(["--certificate-oidc-issuer", OIDC_ISSUER])
+        .arg(&sums_path)
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .context("Failed to run cosign")?;
+    if !status.success() {
+        bail!("Signature on {CHECKSUMS_FILE} did not verify; refusing to install {asset}");
+    }
+    if !json {
+        output::detail("  Signature verified");
+    }
+    Ok(Verification::Signature)
+}
+
+/// The signing identity of release builds: the release workflow at a
+/// version tag.
+fn signer_identity() -> String {
+    let repo = env!("CARGO_PKG_REPOSITORY").replace('.', "\\.");
+    format!("^{repo}/\\.github/workflows/release\\.yml@refs/tags/v")
+}
+
+/// Find `asset`'s checksum in `sha256sum` output.
+fn find_checksum<'a>(sums: &'a str, asset: &str) -> Option<&'a str> {
+    sums.lines().find_map(|line| {
+        let (hash, name) = line.split_once(char::is_whitespace)?;
+        // Binary mode marks the file name with `*`
+        let name = name.trim_start();
+        (name.strip_prefix('*').unwrap_or(name) == asset).then_some(hash)
+    })
+}
+
+/// Lowercase hex SHA-256 of `bytes`.
+fn sha256_hex(bytes: &[u8]) -> String {
+    Sha256::digest(bytes)
+        .iter()
+        .fold(String::new(), |mut hex, byte| {
+            let _ = write!(hex, "{byte:02x}");
+            hex
+        })
+}
+
+/// Extract the rung binary from a release archive into `dir`.
+fn extract_binary(archive: &Path, dir: &Path) -> Result<PathBuf> {
+    let name = format!("rung{}", std::env::consts::EXE_SUFFIX);
+    let out = dir.join("extracted");
+    fs::create_dir_all(&out)?;
+
+    if archive.extension().is_some_and(|ext| ext == "zip") {
+        // bsdtar reads zip archives, and ships with Windows 10+
+        let status = Command::new("tar")
+            .arg("-xf")
+            .arg(archive)
+            .arg("-C")
+            .arg(&out)
+            .status()
+            .context("Failed to run tar")?;
+        if !status.success() {
+            bail!("Failed to extract {}", archive.display());
+        }
+    } else {
+        let file = fs::File::open(archive).context("Failed to open downloaded archive")?;
+        tar::Archive::new(flate2::read::GzDecoder::new(file))
+            .unpack(&out)
+            .context("Failed to extract downloaded archive")?;
+    }
+
+    let binary = out.join(&name);
+    if !binary.is_file() {
+        bail!("Release archive does not contain {name}");
+    }
+    Ok(binary)
+}
+
+/// Swap the running executable for `binary`.
+///
+/// The new binary is staged next to the old one so the final rename stays
+/// on one filesystem. Windows can't overwrite a running executable but can
+/// rename it, so the old one moves aside first.
+fn replace_current_exe(binary: &Path) -> Result<()> {
+    let exe = std::env::current_exe().context("Failed to locate the running binary")?;
+    let exe = exe.canonicalize().unwrap_or(exe);
+    let staged = exe.with_extension("new");
+    fs::copy(binary, &staged).with_context(|| format!("Failed to write {}", staged.display()))?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&staged, fs::Permissions::from_mode(0o755))?;
+    }
+    #[cfg(windows)]
+    {
+        let old = exe.with_extension("old");
+        let _ = fs::remove_file(&old);
+        fs::rename(&exe, &old)
+            .with_context(|| format!("Failed to move aside {}", exe.display()))?;
+    }
+
+    fs::rename(&staged, &exe).with_context(|| format!("Failed to replace {}", exe.display()))
+}
+
+/// Download `url` to `dest`; false if the server doesn't have it.
+///
+/// Only an HTTP error response (curl's exit code 22) counts as missing;
+/// network failures are errors.
+fn download(url: &str, dest: &Path) -> Result<bool> {
+    let output = Command::new("curl")
+        .args(["-sSfL", "-o"])
+        .arg(dest)
+        .arg(url)
+        .output()
+        .context("Failed to run curl")?;
+    match output.status.code() {
+        Some(0) => Ok(true),
+        Some(22) => Ok(false),
+        _ => bail!(
+            "Failed to download {url}: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ),
+    }
+}
+
+/// Check if cosign is available.
+fn has_cosign() -> bool {
+    Command::new("cosign")
+        .arg("version")
+        .output()
+        .is_ok_and(|o| o.status.success())
 }
 
 /// Check if the current binary is in ~/.cargo/bin and warn if not.
@@ -83,8 +579,8 @@ fn cargo_bin_dir() -> Option<PathBuf> {
         .map(|p| p.join("bin"))
 }
 
-/// Fetch the latest version from crates.io.
-fn fetch_latest_version() -> Result<String> {
+/// Fetch the crate's metadata and published versions from crates.io.
+fn fetch_crate_info() -> Result<serde_json::Value> {
     let url = format!("https://crates.io/api/v1/crates/{CRATE_NAME}");
 
     let output = Command::new("curl")
@@ -93,18 +589,86 @@ fn fetch_latest_version() -> Result<String> {
         .context("Failed to run curl")?;
 
     if !output.status.success() {
-        anyhow::bail!("Failed to fetch crate info from crates.io");
+        bail!("Failed to fetch crate info from crates.io");
     }
 
-    let json: serde_json::Value =
-        serde_json::from_slice(&output.stdout).context("Failed to parse crates.io response")?;
+    serde_json::from_slice(&output.stdout).context("Failed to parse crates.io response")
+}
 
-    json["crate"]["max_version"]
+/// The newest version on `channel`: the latest stable release, or for
+/// nightly the latest release of any kind.
+fn channel_version(info: &serde_json::Value, channel: UpdateChannel) -> Result<String> {
+    let field = match channel {
+        UpdateChannel::Stable => "max_stable_version",
+        UpdateChannel::Nightly => "max_version",
+    };
+    info["crate"][field]
         .as_str()
         .map(String::from)
         .context("Could not find version in crates.io response")
 }
 
+/// Check that a pinned version (`0.5.0` or `v0.5.0`) is published and not
+/// yanked.
+fn pinned_version(info: &serde_json::Value, version: &str) -> Result<String> {
+    let version = version.trim().trim_start_matches('v');
+    let published = info["versions"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .find(|v| v["num"].as_str() == Some(version));
+    match published {
+        Some(v) if v["yanked"].as_bool() == Some(true) => {
+            bail!("Version {version} of {CRATE_NAME} was yanked")
+        }
+        Some(_) => Ok(version.to_string()),
+        None => bail!("Version {version} of {CRATE_NAME} is not on crates.io"),
+    }
+}
+
+/// Compare two `MAJOR.MINOR.PATCH[-PRERELEASE]` versions by semver
+/// precedence: a prerelease sorts before its release.
+fn compare_versions(a: &str, b: &str) -> Ordering {
+    fn parse(version: &str) -> (Vec<u64>, Option<&str>) {
+        let (core, pre) = version
+            .split_once('-')
+            .map_or((version, None), |(core, pre)| (core, Some(pre)));
+        let core = core.split('.').map(|n| n.parse().unwrap_or(0)).collect();
+        (core, pre)
+    }
+    fn compare_pre(a: &str, b: &str) -> Ordering {
+        let mut a = a.split('.');
+        let mut b = b.split('.');
+        loop {
+            match (a.next(), b.next()) {
+                (None, None) => return Ordering::Equal,
+                (None, Some(_)) => return Ordering::Less,
+                (Some(_), None) => return Ordering::Greater,
+                (Some(x), Some(y)) => {
+                    let ord = match (x.parse::<u64>(), y.parse::<u64>()) {
+                        (Ok(x), Ok(y)) => x.cmp(&y),
+                        (Ok(_), Err(_)) => Ordering::Less,
+                        (Err(_), Ok(_)) => Ordering::Greater,
+                        (Err(_), Err(_)) => x.cmp(y),
+                    };
+                    if ord != Ordering::Equal {
+                        return ord;
+                    }
+                }
+            }
+        }
+    }
+
+    let (a_core, a_pre) = parse(a);
+    let (b_core, b_pre) = parse(b);
+    a_core.cmp(&b_core).then_with(|| match (a_pre, b_pre) {
+        (None, None) => Ordering::Equal,
+        (None, Some(_)) => Ordering::Greater,
+        (Some(_), None) => Ordering::Less,
+        (Some(a), Some(b)) => compare_pre(a, b),
+    })
+}
+
 /// Check if cargo-binstall is available.
 fn has_cargo_binstall() -> bool {
     Command::new("cargo")
@@ -113,28 +677,197 @@ fn has_cargo_binstall() -> bool {
         .is_ok_and(|o| o.status.success())
 }
 
-/// Update using cargo-binstall (fast, downloads pre-built binary).
-fn run_cargo_binstall() -> Result<()> {
-    let status = Command::new("cargo")
-        .args(["binstall", CRATE_NAME, "-y", "--force"])
+/// Run a cargo install subcommand: `binstall` downloads a pre-built binary,
+/// `install` compiles from source.
+fn run_cargo(args: &[&str], json: bool) -> Result<()> {
+    run_tool("cargo", args, json)
+}
+
+/// Run an installer, keeping its output off stdout with `json`.
+fn run_tool(program: &str, args: &[&str], json: bool) -> Result<()> {
+    let mut cmd = Command::new(program);
+    cmd.args(args);
+    if json {
+        cmd.stdout(std::io::stderr());
+    }
+    let status = cmd
         .status()
-        .context("Failed to run cargo binstall")?;
+        .with_context(|| format!("Failed to run {program} {}", args[0]))?;
 
     if !status.success() {
-        anyhow::bail!("cargo binstall failed");
+        bail!("{program} {} failed", args[0]);
     }
     Ok(())
 }
 
-/// Update using cargo install (slower, compiles from source).
-fn run_cargo_install() -> Result<()> {
-    let status = Command::new("cargo")
-        .args(["install", CRATE_NAME, "--force"])
-        .status()
-        .context("Failed to run cargo install")?;
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    if !status.success() {
-        anyhow::bail!("cargo install failed");
+    #[test]
+    fn test_compare_versions() {
+        assert_eq!(compare_versions("0.10.0", "0.9.3"), Ordering::Greater);
+        assert_eq!(compare_versions("0.9.0", "0.9.0"), Ordering::Equal);
+        assert_eq!(compare_versions("1.0.0-rc.1", "1.0.0"), Ordering::Less);
+        assert_eq!(
+            compare_versions("1.0.0-nightly.10", "1.0.0-nightly.9"),
+            Ordering::Greater
+        );
+        assert_eq!(
+            compare_versions("1.0.0-alpha", "1.0.0-alpha.1"),
+            Ordering::Less
+        );
+        assert_eq!(
+            compare_versions("1.0.0-beta", "1.0.0-alpha.9"),
+            Ordering::Greater
+        );
+        assert_eq!(
+            compare_versions("0.10.0-nightly.1", "0.9.9"),
+            Ordering::Greater
+        );
+    }
+
+    #[test]
+    fn test_channel_and_pinned_versions() {
+        let info = serde_json::json!({
+            "crate": { "max_version": "0.10.0-nightly.2", "max_stable_version": "0.9.1" },
+            "versions": [
+                { "num": "0.10.0-nightly.2", "yanked": false },
+                { "num": "0.9.1", "yanked": false },
+                { "num": "0.9.0", "yanked": true },
+            ],
+        });
+        assert_eq!(
+            channel_version(&info, UpdateChannel::Stable)
+                .ok()
+                .as_deref(),
+            Some("0.9.1")
+        );
+        assert_eq!(
+            channel_version(&info, UpdateChannel::Nightly)
+                .ok()
+                .as_deref(),
+            Some("0.10.0-nightly.2")
+        );
+        assert_eq!(
+            pinned_version(&info, "v0.9.1").ok().as_deref(),
+            Some("0.9.1")
+        );
+        assert!(pinned_version(&info, "0.9.0").is_err());
+        assert!(pinned_version(&info, "0.1.0").is_err());
+    }
+
+    #[test]
+    fn test_find_checksum() {
+        let sums = "\
+aaaa  rung-0.9.0-x86_64-unknown-linux-gnu.tar.gz
+bbbb *rung-0.9.0-x86_64-pc-windows-msvc.zip
+";
+        assert_eq!(
+            find_checksum(sums, "rung-0.9.0-x86_64-unknown-linux-gnu.tar.gz"),
+            Some("aaaa")
+        );
+        assert_eq!(
+            find_checksum(sums, "rung-0.9.0-x86_64-pc-windows-msvc.zip"),
+            Some("bbbb")
+        );
+        assert_eq!(
+            find_checksum(sums, "rung-0.9.0-aarch64-apple-darwin.tar.gz"),
+            None
+        );
+    }
+
+    #[test]
+    #[cfg(unix)]
+    #[allow(clippy::expect_used)]
+    fn test_download_failure_is_not_a_missing_file() {
+        let dir = tempfile::TempDir::new().expect("temp dir");
+        let missing = dir.path().join("missing");
+        let url = format!("file://{}", missing.display());
+        let err = download(&url, &dir.path().join("out")).expect_err("unreadable source");
+        assert!(err.to_string().contains("Failed to download"));
+    }
+
+    #[test]
+    fn test_sha256_hex() {
+        assert_eq!(
+            sha256_hex(b"abc"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+
+    #[test]
+    fn test_signer_identity_matches_release_workflow() {
+        assert_eq!(
+            signer_identity(),
+            r"^https://github\.com/auswm85/rung/\.github/workflows/release\.yml@refs/tags/v"
+        );
+    }
+
+    #[test]
+    #[cfg(unix)]
+    #[allow(clippy::expect_used)]
+    fn test_extract_binary_from_tar_gz() {
+        let dir = tempfile::TempDir::new().expect("temp dir");
+        let archive = dir
+            .path()
+            .join("rung-0.9.0-x86_64-unknown-linux-gnu.tar.gz");
+        let file = fs::File::create(&archive).expect("create archive");
+        let mut builder = tar::Builder::new(flate2::write::GzEncoder::new(
+            file,
+            flate2::Compression::default(),
+        ));
+        let contents = b"#!/bin/sh\n";
+        let mut header = tar::Header::new_gnu();
+        header.set_size(contents.len() as u64);
+        header.set_mode(0o755);
+        header.set_cksum();
+        builder
+            .append_data(&mut header, "rung", &contents[..])
+            .expect("append binary");
+        builder
+            .into_inner()
+            .and_then(flate2::write::GzEncoder::finish)
+            .expect("finish archive");
+
+        let binary = extract_binary(&archive, dir.path()).expect("extract");
+        assert_eq!(fs::read(binary).expect("read binary"), contents);
+    }
+
+    #[test]
+    fn test_install_method_from_path() {
+        let cargo_bin = Path::new("/home/me/.cargo/bin");
+        let method = |exe: &str, binstalled| {
+            InstallMethod::from_path(Path::new(exe), Some(cargo_bin), binstalled)
+        };
+        assert_eq!(
+            method("/opt/homebrew/Cellar/rung/0.9.0/bin/rung", false),
+            InstallMethod::Homebrew
+        );
+        assert_eq!(
+            method(
+                "/home/linuxbrew/.linuxbrew/Cellar/rung/0.9.0/bin/rung",
+                false
+            ),
+            InstallMethod::Homebrew
+        );
+        assert_eq!(
+            method("/home/me/.cargo/bin/rung", false),
+            InstallMethod::Cargo
+        );
+        assert_eq!(
+            method("/home/me/.cargo/bin/rung", true),
+            InstallMethod::Binstall
+        );
+        assert_eq!(
+            method("/nix/store/abc-rung-0.9.0/bin/rung", false),
+            InstallMethod::Nix
+        );
+        assert_eq!(method("/usr/bin/rung", false), InstallMethod::System);
+        assert_eq!(method("/usr/local/bin/rung", true), InstallMethod::Manual);
+        assert_eq!(
+            method("/home/me/.local/bin/rung", false),
+            InstallMethod::Manual
+        );
     }
-    Ok(())
 }
@@ -1,20 +1,336 @@
+use std::cell::OnceCell;
+use std::sync::atomic::{AtomicBool, Ordering};
+
 use anyhow::{Context, Result, bail};
-use rung_core::State;
-use rung_git::Repository;
+use rung_core::{Config, JournalMode, Stack, State, StateLock};
+use rung_forge::RemoteInfo;
+use rung_git::{Oid, Repository};
+use rung_github::Auth;
+use tokio::runtime::Runtime;
 
+use crate::conflict::ConflictReport;
+use crate::forge::Forge;
 use crate::output;
+use crate::services::{self, Issue, RestackError, RestackService};
+
+/// Repository and state handles shared by a single command invocation.
+///
+/// Opening a `git2` repository re-discovers the git dir and re-reads config,
+/// so each command opens it exactly once here and threads `&ctx.repo` and
+/// `&ctx.state` through services and helpers instead of reopening it.
+///
+/// The forge client and async runtime are built lazily on first use, so
+/// commands that stay local never resolve a token or start a runtime.
+pub struct CommandContext {
+    pub repo: Repository,
+    pub state: State,
+    origin_url: OnceCell<Option<String>>,
+    /// `github.repo`, naming the repository when the remote URL can't.
+    repo_override: Option<String>,
+    forge: OnceCell<Forge>,
+    runtime: OnceCell<Runtime>,
+}
+
+impl CommandContext {
+    /// Open the current repository and require rung to be initialized.
+    pub fn open() -> Result<Self> {
+        let ctx = Self::open_uninitialized()?;
+
+        if !ctx.state.is_initialized() {
+            bail!("Rung not initialized - run `rung init` first");
+        }
+
+        Ok(ctx)
+    }
+
+    /// Open the current repository without requiring `rung init`.
+    pub fn open_uninitialized() -> Result<Self> {
+        let mut repo = Repository::open_current().context("Not inside a git repository")?;
+        if repo.workdir().is_none() {
+            bail!("Cannot run in bare repository - run rung from one of its worktrees");
+        }
+        // Shared by all linked worktrees, so each one sees the same stack
+        let state = State::from_git_dir(repo.common_dir())?;
+        let repo_override = state.load_config().ok().and_then(|config| {
+            configure_repo(&mut repo, &config);
+            config.github.repo
+        });
+
+        Ok(Self {
+            repo,
+            state,
+            origin_url: OnceCell::new(),
+            repo_override,
+            forge: OnceCell::new(),
+            runtime: OnceCell::new(),
+        })
+    }
+
+    /// Get rung's remote URL, reading git config at most once.
+    pub fn origin_url(&self) -> Result<String> {
+        self.origin_url
+            .get_or_init(|| self.repo.origin_url().ok())
+            .clone()
+            .with_context(|| format!("No {} remote configured", self.repo.remote()))
+    }
+
+    /// The forge repository rung works against: `github.repo` if set,
+    /// otherwise read from the remote's URL.
+    pub fn remote_info(&self) -> Result<RemoteInfo> {
+        if let Some(repo) = &self.repo_override {
+            return RemoteInfo::github(repo)
+                .with_context(|| format!("Invalid github.repo '{repo}' - expected owner/name"));
+        }
+        rung_forge::parse_remote(&self.origin_url()?)
+            .context("Could not parse forge remote URL - set github.repo to its owner/name")
+    }
+
+    /// Get the forge client for rung's remote, authenticating on first use.
+    ///
+    /// Failures are not cached, so a later call retries authentication.
+    pub fn forge(&self) -> Result<&Forge> {
+        if let Some(forge) = self.forge.get() {
+            return Ok(forge);
+        }
+        let forge = if self.repo_override.is_some() {
+            Forge::for_kind(self.remote_info()?.kind, &Auth::auto())?
+        } else {
+            Forge::for_remote(&self.origin_url()?, &Auth::auto())?
+        };
+        Ok(self.forge.get_or_init(|| forge))
+    }
+
+    /// Get the async runtime, starting it on first use.
+    pub fn runtime(&self) -> Result<&Runtime> {
+        if let Some(rt) = self.runtime.get() {
+            return Ok(rt);
+        }
+        let rt = Runtime::new().context("Failed to start async runtime")?;
+        Ok(self.runtime.get_or_init(|| rt))
+    }
+}
+
+/// Apply the config's rebase, protection, and remote settings to `repo`.
+pub fn configure_repo(repo: &mut Repository, config: &Config) {
+    repo.set_rebase_dates(config.rebase.dates());
+    repo.set_protected_branches(config.policy.protected_branches());
+    let remote = pick_remote(repo, config.general.default_remote.as_deref());
+    repo.set_remote(remote);
+}
+
+/// The remote rung should push to and fetch from.
+///
+/// A configured remote is used as-is, even `origin`. Otherwise it's
+/// `origin`, unless `origin` isn't a forge remote - a company mirror, say -
+/// and the repository has exactly one forge remote.
+fn pick_remote(repo: &Repository, configured: Option<&str>) -> String {
+    const DEFAULT: &str = "origin";
+    if let Some(remote) = configured {
+        return remote.to_string();
+    }
+    let is_forge = |name: &str| {
+        repo.remote_url(name)
+            .is_ok_and(|url| rung_forge::parse_remote(&url).is_ok())
+    };
+    if is_forge(DEFAULT) {
+        return DEFAULT.to_string();
+    }
+    let forge_remotes: Vec<String> = repo
+        .remote_names()
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|name| is_forge(name))
+        .collect();
+    match <[String; 1]>::try_from(forge_remotes) {
+        Ok([only]) => only,
+        Err(_) => DEFAULT.to_string(),
+    }
+}
+
+/// Ends a command with a specific exit code and no further message, for
+/// commands that have already reported their result (e.g. `rung doctor`).
+#[derive(Debug)]
+pub struct ExitStatus(pub i32);
+
+impl std::fmt::Display for ExitStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "exited with status {}", self.0)
+    }
+}
+
+impl std::error::Error for ExitStatus {}
+
+/// Open the current repository and its rung state, if rung is initialized.
+pub fn open_initialized() -> Option<(Repository, State)> {
+    let repo = Repository::open_current().ok()?;
+    let state = State::from_git_dir(repo.common_dir())
+        .ok()
+        .filter(State::is_initialized)?;
+    Some((repo, state))
+}
+
+/// Load the config for commands that also run outside a repository.
+///
+/// Inside an initialized repository this is its config; elsewhere only
+/// `RUNG_*` environment variables apply.
+///
+/// # Errors
+/// Returns error if the config file or a variable has an invalid value.
+pub fn load_config_anywhere() -> Result<Config> {
+    load_config_for(open_initialized().as_ref().map(|(_, state)| state))
+}
+
+/// Load the config of `state`, or from `RUNG_*` variables alone without one.
+///
+/// # Errors
+/// Returns error if the config file or a variable has an invalid value.
+pub fn load_config_for(state: Option<&State>) -> Result<Config> {
+    if let Some(state) = state {
+        return Ok(state.load_config()?);
+    }
+    let mut config = Config::default();
+    config.apply_env()?;
+    Ok(config)
+}
+
+/// Global flag: block on the state lock instead of failing fast.
+static WAIT_FOR_LOCK: AtomicBool = AtomicBool::new(false);
+
+/// Set whether mutating commands wait for a concurrent rung operation.
+pub fn set_wait_for_lock(wait: bool) {
+    WAIT_FOR_LOCK.store(wait, Ordering::Relaxed);
+}
+
+/// Global flag: skip network access (set by `--offline` or a failed request).
+static OFFLINE: AtomicBool = AtomicBool::new(false);
 
-/// Helper to open repo and state.
-pub fn open_repo_and_state() -> Result<(Repository, State)> {
-    let repo = Repository::open_current().context("Not inside a git repository")?;
-    let workdir = repo.workdir().context("Cannot run in bare repository")?;
-    let state = State::new(workdir)?;
+/// Set whether commands avoid the network entirely.
+pub fn set_offline(offline: bool) {
+    OFFLINE.store(offline, Ordering::Relaxed);
+}
+
+/// Whether the current command is running offline.
+pub fn is_offline() -> bool {
+    OFFLINE.load(Ordering::Relaxed)
+}
 
-    if !state.is_initialized() {
-        bail!("Rung not initialized - run `rung init` first");
+/// Whether `RUNG_READ_ONLY` turns on read-only mode (`1`, `true`, `yes`,
+/// or `on`).
+pub fn read_only_from_env() -> bool {
+    std::env::var("RUNG_READ_ONLY")
+        .is_ok_and(|value| matches!(value.to_lowercase().as_str(), "1" | "true" | "yes" | "on"))
+}
+
+/// Switch to offline mode after a network failure, telling the user once.
+pub fn go_offline(json: bool) {
+    if !OFFLINE.swap(true, Ordering::Relaxed) && !json {
+        output::warn("Network unavailable - continuing offline, remote info may be stale");
     }
+}
 
-    Ok((repo, state))
+/// Whether an error is the forge refusing a request, as it does when the
+/// token is invalid or lacks a scope.
+///
+/// GitHub answers 404 rather than 403 for private repositories the token
+/// can't see.
+fn is_forge_permission_error(err: &anyhow::Error) -> bool {
+    err.chain().any(|cause| {
+        matches!(
+            cause.downcast_ref::<rung_forge::ForgeError>(),
+            Some(
+                rung_forge::ForgeError::AuthenticationFailed
+                    | rung_forge::ForgeError::RepoNotFound(_)
+                    | rung_forge::ForgeError::ApiError {
+                        status: 403 | 404,
+                        ..
+                    }
+            )
+        )
+    })
+}
+
+/// Explain a forge permission error by checking the token behind it.
+///
+/// The forge's response body rarely says what's wrong, so this asks the
+/// forge who the token belongs to and which scopes it has. Returns an issue
+/// only when the token is the cause.
+pub fn diagnose_forge_error(err: &anyhow::Error) -> Option<Issue> {
+    if !is_forge_permission_error(err) || is_offline() {
+        return None;
+    }
+    let ctx = CommandContext::open_uninitialized().ok()?;
+    let kind = ctx.remote_info().ok()?.kind;
+    let token = ctx.runtime().ok()?.block_on(ctx.forge().ok()?.token_info());
+    services::token_issue(kind, &token)
+}
+
+/// Whether an error was caused by the network being unreachable.
+///
+/// Forge requests surface transport errors directly. Git runs as a
+/// subprocess, so its fetch and push failures are recognized by message.
+pub fn is_network_error(err: &anyhow::Error) -> bool {
+    const GIT_NETWORK_ERRORS: &[&str] = &[
+        "Could not resolve host",
+        "Could not resolve hostname",
+        "Connection refused",
+        "Connection timed out",
+        "Operation timed out",
+        "Network is unreachable",
+        "No route to host",
+        "Failed to connect",
+    ];
+
+    err.chain().any(|cause| {
+        if let Some(e) = cause.downcast_ref::<rung_forge::ForgeError>() {
+            return matches!(e, rung_forge::ForgeError::Network(_));
+        }
+        if let Some(rung_git::Error::FetchFailed(msg) | rung_git::Error::PushFailed(msg)) =
+            cause.downcast_ref::<rung_git::Error>()
+        {
+            return GIT_NETWORK_ERRORS
+                .iter()
+                .any(|pattern| msg.contains(pattern));
+        }
+        false
+    })
+}
+
+/// Acquire the state lock for a mutating command.
+///
+/// Fails immediately if another rung process holds it, unless `--wait` was
+/// given. The returned guard must be held until the command finishes.
+///
+/// Once the lock is held, an operation interrupted by a crash is finished or
+/// rolled back from its journal before the command goes on.
+pub fn lock_state(repo: &Repository, state: &State) -> Result<StateLock> {
+    let lock = match state.try_lock() {
+        Err(rung_core::Error::Locked) if WAIT_FOR_LOCK.load(Ordering::Relaxed) => {
+            output::notice("Waiting for another rung operation to finish...");
+            state.lock()?
+        }
+        result => result?,
+    };
+    recover_interrupted(repo, state)?;
+    Ok(lock)
+}
+
+/// Recover an operation a previous rung process didn't finish.
+fn recover_interrupted(repo: &Repository, state: &State) -> Result<()> {
+    let recovery = rung_core::journal::recover(repo, state)
+        .context("Failed to recover an interrupted operation (see .git/rung/journal.json)")?;
+    if let Some(recovery) = recovery {
+        let action = match recovery.mode {
+            JournalMode::Replay => "Finished",
+            JournalMode::Rollback => "Rolled back",
+        };
+        output::warn(&format!(
+            "{action} interrupted `rung {}` ({} branch{})",
+            recovery.operation,
+            recovery.branches,
+            if recovery.branches == 1 { "" } else { "es" }
+        ));
+    }
+    Ok(())
 }
 
 /// Ensure the repository is not in detached HEAD state.
@@ -26,3 +342,221 @@ pub fn ensure_on_branch(repo: &Repository) -> Result<()> {
     }
     Ok(())
 }
+
+/// Resolve the stack branch the user is working on.
+///
+/// On a branch this is just the current branch. With a detached HEAD (bisect,
+/// CI checkouts) it falls back to the stack branch whose tip HEAD points at,
+/// so read-only commands keep working; otherwise prints the detached-HEAD
+/// message and returns an error.
+pub fn resolve_current_branch(repo: &Repository, stack: &Stack) -> Result<String> {
+    match repo.current_branch() {
+        Err(rung_git::Error::DetachedHead) => {
+            let head = repo.head_commit()?;
+            if let Some(branch) = stack
+                .branches
+                .iter()
+                .find(|b| repo.branch_commit(&b.name).is_ok_and(|oid| oid == head))
+            {
+                return Ok(branch.name.to_string());
+            }
+            output::error_detached_head();
+            bail!("");
+        }
+        result => Ok(result?),
+    }
+}
+
+/// Record `tip` as the base of `branch`'s children that still contain it.
+///
+/// Call before rewriting `branch` in place: once `tip` is no longer on the
+/// branch, sync and restack replay only the children's own commits.
+pub fn record_child_bases(repo: &Repository, stack: &mut Stack, branch: &str, tip: Oid) {
+    let children: Vec<String> = stack
+        .children_of(branch)
+        .iter()
+        .map(|child| child.name.to_string())
+        .collect();
+    for name in children {
+        let contains_tip = repo
+            .branch_commit(&name)
+            .is_ok_and(|oid| repo.merge_base(tip, oid).is_ok_and(|mb| mb == tip));
+        if let Some(child) = stack.find_branch_mut(&name)
+            && contains_tip
+        {
+            child.base = Some(tip.to_string());
+        }
+    }
+}
+
+/// Point stack branches that have no upstream at their remote tracking
+/// branch. Only warns on failure, since the push itself went through.
+pub fn set_stack_upstreams(repo: &Repository, stack: &Stack, json: bool) {
+    let names: Vec<&str> = stack.branches.iter().map(|b| b.name.as_str()).collect();
+    if let Err(e) = repo.set_upstreams(&names)
+        && !json
+    {
+        output::warn(&format!("Could not set upstream tracking: {e}"));
+    }
+}
+
+/// Parse a PR number given on the command line, as `42` or `#42`.
+pub fn parse_pr_number(value: &str) -> Result<u64, String> {
+    value
+        .strip_prefix('#')
+        .unwrap_or(value)
+        .parse()
+        .map_err(|_| format!("'{value}' is not a PR number"))
+}
+
+/// Restack the branches stacked on each of `branches` onto its new tip,
+/// returning the branches that were rebased.
+///
+/// Skipped with a warning when the working tree is dirty, since rebasing
+/// needs to check the branches out.
+pub fn restack_descendants(
+    repo: &Repository,
+    state: &State,
+    branches: &[String],
+    json: bool,
+) -> Result<Vec<String>> {
+    if state.is_restack_in_progress() {
+        output::warn("A restack is already in progress - skipping restack of descendants");
+        return Ok(vec![]);
+    }
+    if !repo.is_clean()? {
+        output::warn(
+            "Working tree has uncommitted changes - skipping restack. Run `rung sync` once it is clean",
+        );
+        return Ok(vec![]);
+    }
+
+    let service = RestackService::new(repo);
+    let original_branch = repo.current_branch()?;
+    let stack = state.load_stack()?;
+    let mut restacked = vec![];
+
+    for branch in branches {
+        for plan in service.plan_descendants(state, branch)? {
+            if let Some(locked) = plan
+                .branches_to_rebase
+                .iter()
+                .find(|b| stack.check_unlocked(b, "rebase").is_err())
+            {
+                output::warn(&format!(
+                    "'{locked}' is locked - not restacking {}",
+                    plan.target_branch
+                ));
+                continue;
+            }
+            service.execute(state, &plan, &original_branch)?;
+            match service.execute_restack_loop(state, &original_branch) {
+                Ok(result) => {
+                    for rebased in result.branches_rebased {
+                        if !json {
+                            output::success(&format!("Restacked {rebased}"));
+                        }
+                        restacked.push(rebased);
+                    }
+                }
+                Err(RestackError::Conflict { branch, files }) => {
+                    if !json {
+                        ConflictReport::for_restack(repo, state, &branch, files).print();
+                    }
+                    bail!(
+                        "Rebase conflict in '{branch}' - resolve and run `rung restack --continue`"
+                    );
+                }
+                Err(RestackError::Other(e)) => return Err(e),
+            }
+        }
+    }
+
+    Ok(restacked)
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_network_error() {
+        let err = anyhow::Error::new(rung_git::Error::FetchFailed(
+            "fatal: unable to access 'https://github.com/o/r/': Could not resolve host: github.com"
+                .into(),
+        ));
+        assert!(is_network_error(&err));
+
+        let err = anyhow::Error::new(rung_git::Error::FetchFailed(
+            "fatal: 'origin' does not appear to be a git repository".into(),
+        ));
+        assert!(!is_network_error(&err));
+
+        let err = anyhow::Error::new(rung_forge::ForgeError::RateLimited).context("Failed");
+        assert!(!is_network_error(&err));
+    }
+
+    #[test]
+    fn test_is_forge_permission_error() {
+        let forbidden = rung_forge::ForgeError::ApiError {
+            status: 403,
+            message: r#"{"message":"Resource not accessible by integration"}"#.into(),
+        };
+        assert!(is_forge_permission_error(
+            &anyhow::Error::new(forbidden).context("Failed to create PR")
+        ));
+        assert!(is_forge_permission_error(&anyhow::Error::new(
+            rung_forge::ForgeError::AuthenticationFailed
+        )));
+
+        let invalid = rung_forge::ForgeError::ApiError {
+            status: 422,
+            message: "Validation failed".into(),
+        };
+        assert!(!is_forge_permission_error(&anyhow::Error::new(invalid)));
+        assert!(!is_forge_permission_error(&anyhow::Error::new(
+            rung_forge::ForgeError::RateLimited
+        )));
+    }
+
+    #[test]
+    fn test_parse_pr_number() {
+        assert_eq!(parse_pr_number("42"), Ok(42));
+        assert_eq!(parse_pr_number("#42"), Ok(42));
+        assert!(parse_pr_number("feature").is_err());
+        assert!(parse_pr_number("#").is_err());
+    }
+
+    #[test]
+    fn test_pick_remote() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let git = |args: &[&str]| {
+            std::process::Command::new("git")
+                .args(args)
+                .current_dir(temp.path())
+                .output()
+                .unwrap();
+        };
+        git(&["init"]);
+        git(&[
+            "remote",
+            "add",
+            "origin",
+            "https://git.example.com/mirror.git",
+        ]);
+        let repo = Repository::open(temp.path()).unwrap();
+
+        // No forge remote to switch to
+        assert_eq!(pick_remote(&repo, None), "origin");
+
+        git(&["remote", "add", "github", "git@github.com:owner/repo.git"]);
+        assert_eq!(pick_remote(&repo, None), "github");
+        // An explicit choice is never second-guessed, even `origin`
+        assert_eq!(pick_remote(&repo, Some("origin")), "origin");
+        assert_eq!(pick_remote(&repo, Some("mirror")), "mirror");
+
+        git(&["remote", "add", "fork", "https://github.com/me/repo.git"]);
+        assert_eq!(pick_remote(&repo, None), "origin");
+    }
+}
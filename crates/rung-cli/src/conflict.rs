@@ -0,0 +1,198 @@
+//! Report printed when `rung sync` or `rung restack` pauses on a rebase conflict.
+//!
+//! Spells out which branch is being replayed onto which, the commit git
+//! stopped on, the conflicted files and the exact commands to run next, so
+//! the same guidance is available as text and under `--json`.
+
+use rung_core::State;
+use rung_git::Repository;
+use serde::Serialize;
+
+use crate::output;
+
+/// A paused rebase and how to get out of it.
+#[derive(Debug, Serialize)]
+pub struct ConflictReport {
+    /// Branch being rebased onto; "ours" (HEAD) in conflict markers.
+    pub ours: String,
+    /// Branch whose commits are being replayed; "theirs" in conflict markers.
+    pub theirs: String,
+    /// Commit the rebase stopped on.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub commit: Option<ConflictCommit>,
+    pub files: Vec<String>,
+    /// Commands to run, in order; alternatives come last.
+    pub next_steps: Vec<NextStep>,
+}
+
+/// The commit being replayed when the rebase stopped.
+#[derive(Debug, Serialize)]
+pub struct ConflictCommit {
+    pub sha: String,
+    pub summary: String,
+}
+
+/// A copy-pastable command and what it does.
+#[derive(Debug, Serialize)]
+pub struct NextStep {
+    pub command: String,
+    pub description: String,
+}
+
+impl ConflictReport {
+    /// Build the report for a paused `rung sync`.
+    ///
+    /// The parent comes from the saved sync plan, falling back to the stack.
+    pub fn for_sync(repo: &Repository, state: &State, branch: &str, files: Vec<String>) -> Self {
+        let planned = state.load_sync_state().ok().and_then(|sync_state| {
+            sync_state
+                .planned_action(branch)
+                .map(|action| action.parent_branch.clone())
+        });
+        let ours = planned.unwrap_or_else(|| stack_parent(state, branch));
+        Self::new(repo, "sync", ours, branch, files, true)
+    }
+
+    /// Build the report for a paused `rung restack`.
+    ///
+    /// The restacked branch itself is rebased onto its new parent and can't
+    /// be skipped; descendants are rebased onto their parent in the stack.
+    pub fn for_restack(repo: &Repository, state: &State, branch: &str, files: Vec<String>) -> Self {
+        let target = state
+            .load_restack_state()
+            .ok()
+            .filter(|restack| restack.target_branch == branch);
+        let skippable = target.is_none();
+        let ours = target.map_or_else(|| stack_parent(state, branch), |t| t.new_parent);
+        Self::new(repo, "restack", ours, branch, files, skippable)
+    }
+
+    fn new(
+        repo: &Repository,
+        command: &str,
+        ours: String,
+        branch: &str,
+        files: Vec<String>,
+        skippable: bool,
+    ) -> Self {
+        let commit = repo.rebase_stopped_commit().and_then(|oid| {
+            let commit = repo.find_commit(oid).ok()?;
+            Some(ConflictCommit {
+                sha: oid.to_string()[..7].to_string(),
+                summary: commit.summary().unwrap_or_default().to_string(),
+            })
+        });
+
+        let mut next_steps = vec![
+            NextStep {
+                command: add_command(&files),
+                description: "after fixing the conflict markers, mark the files resolved".into(),
+            },
+            NextStep {
+                command: format!("rung {command} --continue"),
+                description: format!("finish the rebase and continue the {command}"),
+            },
+        ];
+        if skippable {
+            next_steps.push(NextStep {
+                command: format!("rung {command} --skip"),
+                description: format!("or leave '{branch}' as it was and move on"),
+            });
+        }
+        next_steps.push(NextStep {
+            command: format!("rung {command} --abort"),
+            description: "or restore every branch from the backup".into(),
+        });
+
+        Self {
+            ours,
+            theirs: branch.to_string(),
+            commit,
+            files,
+            next_steps,
+        }
+    }
+
+    /// Print the report for a terminal.
+    pub fn print(&self) {
+        output::warn(&format!(
+            "Conflict rebasing '{}' onto '{}'",
+            self.theirs, self.ours
+        ));
+        if let Some(commit) = &self.commit {
+            output::detail(&format!("  Stopped at {} {}", commit.sha, commit.summary));
+        }
+        if !self.files.is_empty() {
+            output::detail("  Conflicted files:");
+            for file in &self.files {
+                output::detail(&format!("    {file}"));
+            }
+        }
+        output::detail(&format!(
+            "  In the conflict markers, \"ours\" (HEAD) is '{}' and \"theirs\" is '{}'",
+            self.ours, self.theirs
+        ));
+        output::detail("");
+        output::info("Next steps:");
+        let width = self
+            .next_steps
+            .iter()
+            .map(|step| step.command.len())
+            .max()
+            .unwrap_or(0);
+        for step in &self.next_steps {
+            output::detail(&format!(
+                "  {:width$}  # {}",
+                step.command, step.description
+            ));
+        }
+    }
+}
+
+/// Parent of `branch` in the stack, or a placeholder when it isn't tracked.
+fn stack_parent(state: &State, branch: &str) -> String {
+    state
+        .load_stack()
+        .ok()
+        .and_then(|stack| {
+            stack
+                .find_branch(branch)
+                .and_then(|b| b.parent.as_ref().map(ToString::to_string))
+        })
+        .unwrap_or_else(|| "its parent".to_string())
+}
+
+/// `git add` for the conflicted files, quoting any that need it.
+fn add_command(files: &[String]) -> String {
+    if files.is_empty() {
+        return "git add <resolved-files>".to_string();
+    }
+    let quoted: Vec<String> = files
+        .iter()
+        .map(|file| {
+            if file
+                .chars()
+                .any(|c| c.is_whitespace() || "'\"$`\\".contains(c))
+            {
+                format!("'{}'", file.replace('\'', r"'\''"))
+            } else {
+                file.clone()
+            }
+        })
+        .collect();
+    format!("git add {}", quoted.join(" "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_add_command_quotes_paths() {
+        assert_eq!(add_command(&[]), "git add <resolved-files>");
+        assert_eq!(
+            add_command(&["src/a.rs".into(), "my file.txt".into(), "it's.md".into()]),
+            r"git add src/a.rs 'my file.txt' 'it'\''s.md'"
+        );
+    }
+}
@@ -10,11 +10,11 @@ use std::collections::HashMap;
 
 use anyhow::{Context, Result, anyhow};
 use rung_forge::{
-    CheckRun, CreateComment, CreatePullRequest, ForgeApi, ForgeKind, IssueComment,
-    MergePullRequest, MergeResult, PullRequest, RepoId, Result as ForgeResult, UpdateComment,
-    UpdatePullRequest,
+    CheckRun, CommitStatus, CreateComment, CreatePullRequest, ForgeApi, ForgeKind, IssueComment,
+    MergeMethod, MergePullRequest, MergeResult, PullRequest, RepoId, Result as ForgeResult,
+    UpdateComment, UpdatePullRequest,
 };
-use rung_github::{Auth, GitHubClient};
+use rung_github::{Auth, GitHubClient, TokenInfo};
 
 /// A forge client, statically dispatched by backend kind.
 pub enum Forge {
@@ -29,8 +29,24 @@ impl Forge {
     /// Returns an error if the remote is not a recognized forge, or if
     /// authentication for the detected forge fails.
     pub fn for_remote(remote_url: &str, auth: &Auth) -> Result<Self> {
-        match ForgeKind::detect(remote_url) {
-            Some(kind @ ForgeKind::GitHub) => {
+        ForgeKind::detect(remote_url).map_or_else(
+            || {
+                Err(anyhow!(
+                    "unsupported forge: remote is not a recognized forge repository (supported: {})",
+                    ForgeKind::supported_label()
+                ))
+            },
+            |kind| Self::for_kind(kind, auth),
+        )
+    }
+
+    /// Build a client for a known forge, e.g. one named in the config.
+    ///
+    /// # Errors
+    /// Returns an error if authentication fails.
+    pub fn for_kind(kind: ForgeKind, auth: &Auth) -> Result<Self> {
+        match kind {
+            ForgeKind::GitHub => {
                 let client = GitHubClient::new(auth).with_context(|| {
                     format!(
                         "Failed to authenticate with {} - {}",
@@ -40,10 +56,16 @@ impl Forge {
                 })?;
                 Ok(Self::GitHub(client))
             }
-            None => Err(anyhow!(
-                "unsupported forge: remote is not a recognized forge repository (supported: {})",
-                ForgeKind::supported_label()
-            )),
+        }
+    }
+
+    /// Look up the account and permissions behind the forge token.
+    ///
+    /// # Errors
+    /// Returns an error if the token is rejected or the request fails.
+    pub async fn token_info(&self) -> ForgeResult<TokenInfo> {
+        match self {
+            Self::GitHub(c) => c.token_info().await,
         }
     }
 }
@@ -78,6 +100,16 @@ impl ForgeApi for Forge {
         }
     }
 
+    async fn find_open_prs_for_branch(
+        &self,
+        repo: &RepoId,
+        branch: &str,
+    ) -> ForgeResult<Vec<PullRequest>> {
+        match self {
+            Self::GitHub(c) => ForgeApi::find_open_prs_for_branch(c, repo, branch).await,
+        }
+    }
+
     async fn create_pr(&self, repo: &RepoId, pr: CreatePullRequest) -> ForgeResult<PullRequest> {
         match self {
             Self::GitHub(c) => ForgeApi::create_pr(c, repo, pr).await,
@@ -101,6 +133,17 @@ impl ForgeApi for Forge {
         }
     }
 
+    async fn set_commit_status(
+        &self,
+        repo: &RepoId,
+        commit_sha: &str,
+        status: CommitStatus,
+    ) -> ForgeResult<()> {
+        match self {
+            Self::GitHub(c) => ForgeApi::set_commit_status(c, repo, commit_sha, status).await,
+        }
+    }
+
     async fn merge_pr(
         &self,
         repo: &RepoId,
@@ -124,6 +167,12 @@ impl ForgeApi for Forge {
         }
     }
 
+    async fn get_merge_methods(&self, repo: &RepoId) -> ForgeResult<Vec<MergeMethod>> {
+        match self {
+            Self::GitHub(c) => ForgeApi::get_merge_methods(c, repo).await,
+        }
+    }
+
     async fn list_pr_comments(
         &self,
         repo: &RepoId,
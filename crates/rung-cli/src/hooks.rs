@@ -0,0 +1,131 @@
+//! User-configured hooks run before and after commands.
+//!
+//! `[hooks]` in `.git/rung/config.toml` maps `pre_<command>` and
+//! `post_<command>` to shell commands. Each hook runs from the repository root
+//! with a JSON description of the invocation on stdin. A failing pre hook
+//! aborts the command before it starts; a failing post hook fails the command
+//! after it has completed.
+
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+use anyhow::{Context, Result, bail};
+use rung_core::State;
+use rung_core::stack::StackBranch;
+use rung_git::Repository;
+use serde::Serialize;
+
+/// When a hook runs relative to its command.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stage {
+    Pre,
+    Post,
+}
+
+impl Stage {
+    const fn prefix(self) -> &'static str {
+        match self {
+            Self::Pre => "pre",
+            Self::Post => "post",
+        }
+    }
+}
+
+/// JSON context written to the hook's stdin.
+#[derive(Debug, Serialize)]
+struct HookContext<'a> {
+    hook: &'a str,
+    command: &'a str,
+    args: Vec<String>,
+    repo: &'a Path,
+    current_branch: Option<String>,
+    stack: Vec<StackBranch>,
+}
+
+/// Run the configured pre or post hook for `command`, if any.
+///
+/// `state` must be initialized; outside a rung repository there is no config,
+/// so the caller has no hooks to run.
+/// With `json` the hook's stdout is sent to stderr to keep rung's output parseable.
+///
+/// # Errors
+/// Returns error if the hook can't be started or exits unsuccessfully.
+pub fn run(repo: &Repository, state: &State, command: &str, when: Stage, json: bool) -> Result<()> {
+    let Some(workdir) = repo.workdir() else {
+        return Ok(());
+    };
+    let Ok(config) = state.load_config() else {
+        return Ok(());
+    };
+
+    let hook = format!("{}_{command}", when.prefix());
+    let Some(script) = config.hooks.get(&hook) else {
+        return Ok(());
+    };
+
+    let context = HookContext {
+        hook: &hook,
+        command,
+        args: std::env::args().skip(1).collect(),
+        repo: workdir,
+        current_branch: repo.current_branch().ok(),
+        stack: state.load_stack().map(|s| s.branches).unwrap_or_default(),
+    };
+
+    let input = serde_json::to_vec(&context)?;
+    execute(&hook, when, script, workdir, &input, json)
+}
+
+/// A command running `script` through the platform's shell.
+pub fn shell(script: &str) -> Command {
+    if cfg!(windows) {
+        let mut cmd = Command::new("cmd");
+        cmd.args(["/C", script]);
+        cmd
+    } else {
+        let mut cmd = Command::new("sh");
+        cmd.args(["-c", script]);
+        cmd
+    }
+}
+
+/// Run a hook script through the shell, feeding it `input` on stdin.
+fn execute(
+    hook: &str,
+    when: Stage,
+    script: &str,
+    workdir: &Path,
+    input: &[u8],
+    json: bool,
+) -> Result<()> {
+    let mut cmd = shell(script);
+    cmd.current_dir(workdir)
+        .env("RUNG_HOOK", hook)
+        .stdin(Stdio::piped());
+    if json {
+        cmd.stdout(std::io::stderr());
+    }
+
+    let mut child = cmd
+        .spawn()
+        .with_context(|| format!("Failed to run {hook} hook: {script}"))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        // A hook that ignores its input may exit before reading it
+        let _ = stdin.write_all(input);
+    }
+    let status = child.wait()?;
+
+    if !status.success() {
+        let code = status.code().map_or_else(
+            || "killed by signal".to_string(),
+            |c| format!("exit code {c}"),
+        );
+        if when == Stage::Pre {
+            bail!("{hook} hook failed ({code}) - command aborted");
+        }
+        bail!("{hook} hook failed ({code})");
+    }
+
+    Ok(())
+}
@@ -1,81 +1,233 @@
 //! Rung CLI - The developer's ladder for stacked PRs.
 
-use clap::Parser;
+use clap::{CommandFactory, Parser};
 
+mod audit;
 mod commands;
+mod conflict;
 mod forge;
+mod hooks;
+mod notify;
 mod output;
 mod services;
+mod telemetry;
 
 use commands::{Cli, Commands};
 
-#[allow(clippy::too_many_lines)]
 fn main() {
     // Respect NO_COLOR environment variable (https://no-color.org/)
     if std::env::var("NO_COLOR").is_ok() {
         colored::control::set_override(false);
     }
 
+    // Answer shell completion requests from the generated scripts
+    clap_complete::CompleteEnv::with_factory(Cli::command)
+        .var(commands::completions::COMPLETE_VAR)
+        .complete();
+
     let cli = Cli::parse();
+    // One handle on the repository for the config, hooks, and audit log
+    let rung = commands::utils::open_initialized();
     output::set_quiet(cli.quiet);
+    output::set_ascii(
+        cli.ascii
+            || commands::utils::load_config_for(rung.as_ref().map(|(_, state)| state))
+                .is_ok_and(|config| !config.output.unicode),
+    );
+    commands::utils::set_wait_for_lock(cli.wait);
+    commands::utils::set_offline(cli.offline);
     let json = cli.json;
+    let read_only = cli.read_only || commands::utils::read_only_from_env();
+
+    let name = cli.command.name().to_string();
+    let mutating = !cli.command.is_read_only();
+    let blocked = read_only && mutating;
+    if name != "telemetry" {
+        telemetry::first_run_notice();
+    }
+    let session = telemetry::Session::start(&cli.command);
+
+    // Read-only commands can't move branches, so there's nothing to audit
+    let recorder = rung
+        .as_ref()
+        .filter(|_| mutating)
+        .map(|(repo, _)| audit::Recorder::start(repo));
+
+    // Hooks wrap the command: a failing pre hook stops it from running
+    let run_hook = |when| {
+        rung.as_ref().map_or(Ok(()), |(repo, state)| {
+            hooks::run(repo, state, &name, when, json)
+        })
+    };
+    let result = if blocked {
+        Err(anyhow::anyhow!(
+            "Read-only mode: `rung {name}` would change the repository or GitHub (--read-only / RUNG_READ_ONLY)"
+        ))
+    } else {
+        run_hook(hooks::Stage::Pre)
+            .and_then(|()| dispatch(cli.command, json))
+            .and_then(|()| run_hook(hooks::Stage::Post))
+    };
+    if let (Some(recorder), Some((repo, state))) = (recorder, &rung) {
+        recorder.finish(repo, state, result.is_ok());
+    }
+    if let Some(session) = session {
+        session.finish(&result);
+    }
+
+    if let Err(e) = result {
+        if let Some(status) = e.downcast_ref::<commands::utils::ExitStatus>() {
+            std::process::exit(status.0);
+        }
+        // A bare 403 body says little; say what's wrong with the token instead
+        if let Some(issue) = commands::utils::diagnose_forge_error(&e) {
+            output::error(&issue.message);
+            if let Some(suggestion) = &issue.suggestion {
+                eprintln!("  {suggestion}");
+            }
+        } else {
+            output::error(&e.to_string());
+        }
+        std::process::exit(1);
+    }
+}
 
-    let result = match cli.command {
-        Commands::Init => commands::init::run(),
+/// Run the selected command.
+#[allow(clippy::too_many_lines)]
+fn dispatch(command: Commands, json: bool) -> anyhow::Result<()> {
+    match command {
+        Commands::Init => commands::init::run(json),
         Commands::Adopt {
             branch,
             parent,
             dry_run,
-        } => commands::adopt::run(branch.as_deref(), parent.as_deref(), dry_run),
+        } => commands::adopt::run(json, branch.as_deref(), parent.as_deref(), dry_run),
+        Commands::Track {
+            branch,
+            parent,
+            dry_run,
+        } => commands::track::run(json, branch.as_deref(), parent.as_deref(), dry_run),
+        Commands::Untrack { branch, dry_run } => {
+            commands::track::untrack(json, branch.as_deref(), dry_run)
+        }
+        Commands::Lock { branch } => commands::lock::run(json, branch.as_deref(), true),
+        Commands::Unlock { branch } => commands::lock::run(json, branch.as_deref(), false),
         Commands::Create {
             name,
             message,
+            all,
+            allow_empty,
+            insert,
+            from,
             dry_run,
-        } => commands::create::run(name.as_deref(), message.as_deref(), dry_run),
-        Commands::Status { fetch } => commands::status::run(json, fetch),
+        } => {
+            let opts = commands::create::CreateOptions {
+                json,
+                name: name.as_deref(),
+                message: message.as_deref(),
+                all,
+                allow_empty,
+                insert,
+                from: from.as_deref(),
+                dry_run,
+            };
+            commands::create::run(&opts)
+        }
+        Commands::Status {
+            fetch,
+            chain,
+            depth,
+        } => commands::status::run(json, fetch, services::StatusFilter { chain, depth }),
         Commands::Sync {
             dry_run,
             check,
             continue_,
             abort,
+            skip,
+            skip_descendants,
             no_push,
+            push,
+            autosquash,
+            prune_remote,
             base,
+            tree,
         } => commands::sync::run(
             json,
-            dry_run,
-            check,
-            continue_,
-            abort,
-            no_push,
-            base.as_deref(),
+            &commands::sync::SyncOptions {
+                dry_run,
+                check,
+                continue_,
+                abort,
+                skip: skip.then_some(skip_descendants),
+                push: no_push
+                    .then_some(rung_core::config::PushMode::None)
+                    .or(push),
+                autosquash,
+                prune_remote,
+                base: base.as_deref(),
+                tree: tree.as_deref(),
+            },
         ),
         Commands::Submit {
             draft,
+            draft_above,
             dry_run,
             force,
             title,
             amend,
             message,
+            downstack,
+            update_titles,
+            update_bodies,
         } => commands::submit::run(
             json,
+            &commands::submit::SubmitOptions {
+                dry_run,
+                draft,
+                draft_above: draft_above.as_deref(),
+                force,
+                custom_title: title.as_deref(),
+                amend,
+                message: message.as_deref(),
+                downstack,
+                update_titles,
+                update_bodies,
+            },
+        ),
+        Commands::Undo { operation, dry_run } => commands::undo::run(json, operation, dry_run),
+        Commands::Merge {
+            pr,
+            method,
+            no_delete,
+            delete_local,
+            keep_local,
             dry_run,
-            draft,
-            force,
-            title.as_deref(),
-            amend,
-            message.as_deref(),
+            sync,
+        } => commands::merge::run(
+            json,
+            method.as_deref(),
+            no_delete,
+            (delete_local || keep_local).then_some(delete_local),
+            dry_run,
+            sync,
+            pr,
         ),
-        Commands::Undo => commands::undo::run(),
-        Commands::Merge { method, no_delete } => commands::merge::run(json, &method, no_delete),
-        Commands::Nxt => commands::navigate::run_next(),
-        Commands::Prv => commands::navigate::run_prev(),
-        Commands::Move => commands::mv::run(),
+        Commands::Nxt => commands::navigate::run_next(json),
+        Commands::Prv => commands::navigate::run_prev(json),
+        Commands::Move {
+            reorder,
+            branch,
+            dry_run,
+        } => commands::mv::run(json, reorder, branch.as_deref(), dry_run),
+        Commands::Switch { pr } => commands::switch::run(json, pr),
         Commands::Restack {
             branch,
             onto,
             dry_run,
             continue_,
             abort,
+            skip,
+            skip_descendants,
             include_children,
             force,
         } => {
@@ -86,24 +238,87 @@ fn main() {
                 dry_run,
                 continue_,
                 abort,
+                skip: skip.then_some(skip_descendants),
                 include_children,
                 force,
             };
             commands::restack::run(&opts)
         }
-        Commands::Doctor => commands::doctor::run(json),
-        Commands::Update { check } => commands::update::run(check),
-        Commands::Completions { shell } => commands::completions::run(shell),
-        Commands::Log => commands::log::run(json),
-        Commands::Absorb { dry_run, base } => commands::absorb::run(dry_run, base.as_deref()),
+        Commands::Doctor { fix, fail_on } => commands::doctor::run(json, fix, fail_on),
+        Commands::Update {
+            check,
+            channel,
+            to,
+            allow_unverified,
+        } => commands::update::run(json, check, channel, to.as_deref(), allow_unverified),
+        Commands::Completions { shell, install } => commands::completions::run(shell, install),
+        Commands::Log { stack: true, .. } => commands::log::run_stack(json),
+        Commands::Log {
+            patch, stat, fetch, ..
+        } => {
+            let diff = if patch {
+                Some(rung_git::DiffFormat::Patch)
+            } else if stat {
+                Some(rung_git::DiffFormat::Stat)
+            } else {
+                None
+            };
+            commands::log::run(json, diff, fetch)
+        }
+        Commands::Audit { limit } => commands::audit::run(json, limit),
+        Commands::Backup { command } => commands::backup::run(json, &command),
+        Commands::Gc { dry_run } => commands::gc::run(json, dry_run),
+        Commands::Snapshot { command } => commands::snapshot::run(json, &command),
+        Commands::Telemetry { action } => commands::telemetry::run(json, action),
+        Commands::Ci { command } => commands::ci::run(json, &command),
+        Commands::Absorb {
+            dry_run,
+            base,
+            fixup,
+            unstaged,
+            patch,
+            select,
+            and_restack,
+            commit,
+            branch,
+        } => {
+            let opts = commands::absorb::AbsorbOptions {
+                json,
+                dry_run,
+                base: base.as_deref(),
+                fixup,
+                unstaged,
+                patch,
+                select,
+                and_restack,
+                commit: commit.as_deref(),
+                branch: branch.as_deref(),
+            };
+            commands::absorb::run(&opts)
+        }
+        Commands::Apply { patch, to, message } => {
+            commands::apply::run(json, patch.as_deref(), to.as_deref(), message.as_deref())
+        }
+        Commands::Uncommit => commands::uncommit::run(json),
+        Commands::Edit { branch } => commands::edit::run(json, &branch),
+        Commands::Done => commands::edit::run_done(json),
+        Commands::Describe {
+            branch,
+            message,
+            clear,
+        } => commands::describe::run(json, branch, message, clear),
         Commands::Split {
             branch,
+            into,
+            children_follow,
             dry_run,
             abort,
         } => {
             let opts = commands::split::SplitOptions {
                 json,
                 branch: branch.as_deref(),
+                into,
+                children_follow: children_follow.as_deref(),
                 dry_run,
                 abort,
             };
@@ -126,10 +341,6 @@ fn main() {
             };
             commands::fold::run(&opts)
         }
-    };
-
-    if let Err(e) = result {
-        output::error(&e.to_string());
-        std::process::exit(1);
+        Commands::External(args) => commands::plugin::run(&args),
     }
 }
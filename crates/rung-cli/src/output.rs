@@ -1,5 +1,6 @@
 //! Terminal output formatting utilities.
 
+use std::borrow::Cow;
 use std::sync::atomic::{AtomicBool, Ordering};
 
 use colored::Colorize;
@@ -16,16 +17,81 @@ fn is_quiet() -> bool {
     QUIET_MODE.load(Ordering::Relaxed)
 }
 
+static ASCII_MODE: AtomicBool = AtomicBool::new(false);
+
+/// Set ASCII-only output globally. Call once at startup.
+pub fn set_ascii(ascii: bool) {
+    ASCII_MODE.store(ascii, Ordering::Relaxed);
+}
+
+fn is_ascii() -> bool {
+    ASCII_MODE.load(Ordering::Relaxed)
+}
+
+/// Glyphs and their plain ASCII stand-ins, longest first.
+const ASCII_GLYPHS: &[(&str, &str)] = &[
+    ("⚠️", "!"),
+    ("⚠", "!"),
+    ("✓", "+"),
+    ("✗", "x"),
+    ("→", "->"),
+    ("←", "<-"),
+    ("👈", "<-"),
+    ("↑", "+"),
+    ("↓", "-"),
+    ("●", "*"),
+    ("○", "o"),
+    ("▶", ">"),
+    ("◀", "<"),
+    ("•", "*"),
+    ("·", "-"),
+    ("…", "..."),
+    ("—", "-"),
+    ("├─", "|-"),
+    ("└─", "`-"),
+    ("─", "-"),
+    ("│", "|"),
+];
+
+/// Replace rung's glyphs with plain ASCII.
+#[must_use]
+pub fn to_ascii(text: &str) -> Cow<'_, str> {
+    if text.is_ascii() {
+        return Cow::Borrowed(text);
+    }
+    let mut plain = text.to_string();
+    for (glyph, ascii) in ASCII_GLYPHS {
+        if plain.contains(glyph) {
+            plain = plain.replace(glyph, ascii);
+        }
+    }
+    Cow::Owned(plain)
+}
+
+/// Render text for the terminal: as is, or with `--ascii` (or
+/// `output.unicode = false`) its glyphs swapped for plain ASCII.
+///
+/// Everything printed through this module goes through here already; use
+/// it for lines printed directly.
+#[must_use]
+pub fn glyphs(text: &str) -> Cow<'_, str> {
+    if is_ascii() {
+        to_ascii(text)
+    } else {
+        Cow::Borrowed(text)
+    }
+}
+
 /// Print a success message (suppressed in quiet mode).
 pub fn success(msg: &str) {
     if !is_quiet() {
-        println!("{} {}", "✓".green(), msg);
+        println!("{} {}", glyphs("✓").green(), glyphs(msg));
     }
 }
 
 /// Print an error message (always prints to stderr).
 pub fn error(msg: &str) {
-    eprintln!("{} {}", "✗".red(), msg);
+    eprintln!("{} {}", glyphs("✗").red(), glyphs(msg));
 }
 
 /// Print the detached HEAD error message with guidance (always to stderr).
@@ -41,13 +107,22 @@ pub fn error_detached_head() {
 
 /// Print a warning message (always prints to stderr).
 pub fn warn(msg: &str) {
-    eprintln!("{} {}", "!".yellow(), msg);
+    eprintln!("{} {}", "!".yellow(), glyphs(msg));
+}
+
+/// Print a one-off notice to stderr (suppressed in quiet mode).
+///
+/// Goes to stderr so it never mixes with `--json` output.
+pub fn notice(msg: &str) {
+    if !is_quiet() {
+        eprintln!("{} {}", glyphs("→").blue(), glyphs(msg));
+    }
 }
 
 /// Print an info message (suppressed in quiet mode).
 pub fn info(msg: &str) {
     if !is_quiet() {
-        println!("{} {}", "→".blue(), msg);
+        println!("{} {}", glyphs("→").blue(), glyphs(msg));
     }
 }
 
@@ -56,7 +131,7 @@ pub fn info(msg: &str) {
 /// Use for indented detail lines that accompany info or warn messages.
 pub fn detail(msg: &str) {
     if !is_quiet() {
-        println!("{msg}");
+        println!("{}", glyphs(msg));
     }
 }
 
@@ -64,27 +139,28 @@ pub fn detail(msg: &str) {
 ///
 /// Use for results that should be available for piping, like PR URLs.
 pub fn essential(msg: &str) {
-    println!("{msg}");
+    println!("{}", glyphs(msg));
 }
 
 /// Get the status indicator for a branch state.
 #[must_use]
 pub fn state_indicator(state: &BranchState) -> String {
-    match state {
+    let indicator = match state {
         BranchState::Synced => "●".green().to_string(),
         BranchState::Diverged { commits_behind } => {
             format!("{} ({}↓)", "●".yellow(), commits_behind)
         }
         BranchState::Conflict { .. } => "●".red().to_string(),
         BranchState::Detached => "○".dimmed().to_string(),
-    }
+    };
+    glyphs(&indicator).into_owned()
 }
 
 /// Get a colored branch name with current indicator.
 #[must_use]
 pub fn branch_name(name: &str, is_current: bool) -> String {
     if is_current {
-        format!("{} {}", "▶".cyan(), name.cyan().bold())
+        format!("{} {}", glyphs("▶").cyan(), name.cyan().bold())
     } else {
         format!("  {name}")
     }
@@ -124,7 +200,110 @@ pub fn pr_ref(number: Option<u64>, status: Option<PrStatus>) -> String {
 /// Print a horizontal line (suppressed in quiet mode).
 pub fn hr() {
     if !is_quiet() {
-        println!("{}", "─".repeat(50).dimmed());
+        println!("{}", glyphs("─").repeat(50).dimmed());
+    }
+}
+
+/// A row of a branch tree: the node and the connectors drawn before it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TreeRow {
+    /// Index of the node in the laid out slice.
+    pub index: usize,
+    /// Connectors before the node's own line.
+    pub prefix: String,
+    /// Connectors before lines printed beneath the node, like its commits.
+    pub body: String,
+}
+
+/// Lay out `(name, parent)` nodes as a tree, depth first.
+///
+/// Nodes whose parent isn't among them are roots. Children keep their order
+/// in `nodes`, so the layout is stable. A chain of only children stays in
+/// one column; connectors appear where a branch has several children.
+#[must_use]
+pub fn tree_rows(nodes: &[(&str, Option<&str>)]) -> Vec<TreeRow> {
+    let mut rows = Vec::with_capacity(nodes.len());
+    let mut placed = vec![false; nodes.len()];
+    let is_root = |parent: Option<&str>| parent.is_none_or(|p| !nodes.iter().any(|(n, _)| *n == p));
+
+    for (index, (_, parent)) in nodes.iter().enumerate() {
+        if is_root(*parent) {
+            push_subtree(
+                nodes,
+                index,
+                String::new(),
+                String::new(),
+                &mut placed,
+                &mut rows,
+            );
+        }
+    }
+    // A parent cycle has no root; show those nodes flat rather than drop them
+    for index in 0..nodes.len() {
+        if !placed[index] {
+            push_subtree(
+                nodes,
+                index,
+                String::new(),
+                String::new(),
+                &mut placed,
+                &mut rows,
+            );
+        }
+    }
+    rows
+}
+
+fn push_subtree(
+    nodes: &[(&str, Option<&str>)],
+    index: usize,
+    prefix: String,
+    continuation: String,
+    placed: &mut [bool],
+    rows: &mut Vec<TreeRow>,
+) {
+    placed[index] = true;
+    let name = nodes[index].0;
+    let children: Vec<usize> = (0..nodes.len())
+        .filter(|&i| !placed[i] && nodes[i].1 == Some(name))
+        .collect();
+
+    let body = format!(
+        "{continuation}{}",
+        if children.len() > 1 { "│  " } else { "   " }
+    );
+    rows.push(TreeRow {
+        index,
+        prefix,
+        body,
+    });
+
+    if let [only] = children[..] {
+        push_subtree(
+            nodes,
+            only,
+            continuation.clone(),
+            continuation,
+            placed,
+            rows,
+        );
+        return;
+    }
+    for (n, &child) in children.iter().enumerate() {
+        let last = n + 1 == children.len();
+        let (branch, rest) = if last {
+            ("└─ ", "   ")
+        } else {
+            ("├─ ", "│  ")
+        };
+        push_subtree(
+            nodes,
+            child,
+            format!("{continuation}{branch}"),
+            format!("{continuation}{rest}"),
+            placed,
+            rows,
+        );
     }
 }
 
@@ -168,6 +347,7 @@ mod tests {
     }
 
     #[test]
+    #[serial]
     fn test_state_indicator_diverged() {
         let indicator = state_indicator(&BranchState::Diverged { commits_behind: 3 });
         assert!(indicator.contains('3'));
@@ -189,6 +369,7 @@ mod tests {
     }
 
     #[test]
+    #[serial]
     fn test_branch_name_current() {
         let name = branch_name("feature/test", true);
         assert!(name.contains("feature/test"));
@@ -231,4 +412,63 @@ mod tests {
         // Reset
         set_quiet(false);
     }
+
+    #[test]
+    fn test_to_ascii_replaces_glyphs() {
+        assert_eq!(to_ascii("plain text"), "plain text");
+        assert_eq!(to_ascii("✓ a → b"), "+ a -> b");
+        assert_eq!(to_ascii("├─ a\n│  └─ b"), "|- a\n|  `- b");
+        assert_eq!(to_ascii("* **#2** 👈 ↑1 ↓3"), "* **#2** <- +1 -3");
+        assert_eq!(to_ascii("⚠️  careful"), "!  careful");
+        assert!(ASCII_GLYPHS.iter().all(|(_, ascii)| ascii.is_ascii()));
+    }
+
+    #[test]
+    #[serial]
+    fn test_glyphs_follow_ascii_mode() {
+        assert_eq!(glyphs("a → b"), "a → b");
+        set_ascii(true);
+        assert_eq!(glyphs("a → b"), "a -> b");
+        set_ascii(false);
+    }
+
+    #[test]
+    fn test_tree_rows_linear_chain_stays_flat() {
+        let nodes = [("a", Some("main")), ("b", Some("a")), ("c", Some("b"))];
+        let rows = tree_rows(&nodes);
+        let layout: Vec<(usize, &str)> =
+            rows.iter().map(|r| (r.index, r.prefix.as_str())).collect();
+        assert_eq!(layout, [(0, ""), (1, ""), (2, "")]);
+    }
+
+    #[test]
+    fn test_tree_rows_branching() {
+        // a has children b and c (in stack order); b has d; e is another root
+        let nodes = [
+            ("a", Some("main")),
+            ("c", Some("a")),
+            ("e", None),
+            ("b", Some("a")),
+            ("d", Some("b")),
+            ("f", Some("b")),
+        ];
+        let rows = tree_rows(&nodes);
+        let layout: Vec<(&str, &str)> = rows
+            .iter()
+            .map(|r| (nodes[r.index].0, r.prefix.as_str()))
+            .collect();
+        assert_eq!(
+            layout,
+            [
+                ("a", ""),
+                ("c", "├─ "),
+                ("b", "└─ "),
+                ("d", "   ├─ "),
+                ("f", "   └─ "),
+                ("e", ""),
+            ]
+        );
+        assert_eq!(rows[0].body, "│  ");
+        assert_eq!(rows[1].body, "│     ");
+    }
 }
@@ -5,8 +5,8 @@
 
 use anyhow::{Context, Result};
 use rung_core::StateStore;
-use rung_core::absorb::{self, AbsorbPlan, AbsorbResult};
-use rung_git::AbsorbOps;
+use rung_core::absorb::{self, AbsorbPlan, AbsorbResult, AbsorbTarget};
+use rung_git::{AbsorbOps, Oid};
 use rung_github::{Auth, ForgeApi};
 
 use crate::forge::Forge;
@@ -54,20 +54,50 @@ impl<'a, G: AbsorbOps> AbsorbService<'a, G> {
     }
 
     /// Create an absorb plan for the given base branch.
-    pub fn create_plan<S: StateStore>(&self, state: &S, base_branch: &str) -> Result<AbsorbPlan> {
-        Ok(absorb::create_absorb_plan(self.repo, state, base_branch)?)
+    pub fn create_plan<S: StateStore>(
+        &self,
+        state: &S,
+        base_branch: &str,
+        target: &AbsorbTarget,
+    ) -> Result<AbsorbPlan> {
+        Ok(absorb::create_absorb_plan(
+            self.repo,
+            state,
+            base_branch,
+            target,
+        )?)
+    }
+
+    /// Find the stack branch that introduced `commit`.
+    pub fn owning_branch<S: StateStore>(&self, state: &S, commit: Oid) -> Result<String> {
+        let current_branch = self.repo.current_branch()?;
+        Ok(absorb::owning_branch(
+            self.repo,
+            &state.load_stack()?,
+            &current_branch,
+            commit,
+        )?)
     }
 
     /// Execute an absorb plan.
     pub fn execute_plan(&self, plan: &AbsorbPlan) -> Result<AbsorbResult> {
         Ok(absorb::execute_absorb(self.repo, plan)?)
     }
+
+    /// Execute an absorb plan as `fixup!` commits on the owning stack branches.
+    pub fn execute_plan_as_fixups<S: StateStore>(
+        &self,
+        state: &S,
+        plan: &AbsorbPlan,
+    ) -> Result<AbsorbResult> {
+        Ok(absorb::execute_absorb_as_fixups(self.repo, state, plan)?)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::services::test_mocks::MockGitOps;
+    use rung_git::testing::MockGitOps;
     use rung_git::{AbsorbOps, BlameResult, Hunk, Oid};
 
     /// Wrapper that implements `AbsorbOps` for testing.
@@ -103,6 +133,9 @@ mod tests {
         fn is_rebasing(&self) -> bool {
             self.inner.is_rebasing()
         }
+        fn rebasing_branch(&self) -> Option<String> {
+            self.inner.rebasing_branch()
+        }
         fn branch_exists(&self, name: &str) -> bool {
             self.inner.branch_exists(name)
         }
@@ -118,6 +151,9 @@ mod tests {
         fn list_branches(&self) -> rung_git::Result<Vec<String>> {
             self.inner.list_branches()
         }
+        fn branch_description(&self, branch: &str) -> Option<String> {
+            self.inner.branch_description(branch)
+        }
         fn branch_commit(&self, branch: &str) -> rung_git::Result<Oid> {
             self.inner.branch_commit(branch)
         }
@@ -133,9 +169,33 @@ mod tests {
         fn commits_between(&self, from: Oid, to: Oid) -> rung_git::Result<Vec<Oid>> {
             self.inner.commits_between(from, to)
         }
+        fn commit_diff(
+            &self,
+            commit: Oid,
+            format: rung_git::DiffFormat,
+        ) -> rung_git::Result<String> {
+            self.inner.commit_diff(commit, format)
+        }
         fn count_commits_between(&self, from: Oid, to: Oid) -> rung_git::Result<usize> {
             self.inner.count_commits_between(from, to)
         }
+        fn find_squash_applied(
+            &self,
+            fork: Oid,
+            branch: Oid,
+            upstream: Oid,
+        ) -> rung_git::Result<Option<Oid>> {
+            self.inner.find_squash_applied(fork, branch, upstream)
+        }
+        fn dropped_commits(
+            &self,
+            from: Oid,
+            to: Oid,
+            onto: Oid,
+            new_tip: Oid,
+        ) -> rung_git::Result<Vec<String>> {
+            self.inner.dropped_commits(from, to, onto, new_tip)
+        }
         fn is_clean(&self) -> rung_git::Result<bool> {
             self.inner.is_clean()
         }
@@ -154,6 +214,15 @@ mod tests {
         fn amend_commit(&self, new_message: Option<&str>) -> rung_git::Result<Oid> {
             self.inner.amend_commit(new_message)
         }
+        fn identity(&self) -> rung_git::Result<rung_git::Identity> {
+            self.inner.identity()
+        }
+        fn signing_config(&self) -> rung_git::Result<rung_git::SigningConfig> {
+            self.inner.signing_config()
+        }
+        fn is_commit_signed(&self, commit: Oid) -> rung_git::Result<bool> {
+            self.inner.is_commit_signed(commit)
+        }
         fn rebase_onto(&self, target: Oid) -> rung_git::Result<()> {
             self.inner.rebase_onto(target)
         }
@@ -176,6 +245,9 @@ mod tests {
         fn rebase_continue(&self) -> rung_git::Result<()> {
             self.inner.rebase_continue()
         }
+        fn skip_applied_commits(&self) -> rung_git::Result<usize> {
+            self.inner.skip_applied_commits()
+        }
         fn origin_url(&self) -> rung_git::Result<String> {
             self.inner.origin_url()
         }
@@ -188,6 +260,9 @@ mod tests {
         fn push(&self, branch: &str, force: bool) -> rung_git::Result<()> {
             self.inner.push(branch, force)
         }
+        fn remote_branches(&self) -> rung_git::Result<Vec<String>> {
+            self.inner.remote_branches()
+        }
         fn fetch_all(&self) -> rung_git::Result<()> {
             self.inner.fetch_all()
         }
@@ -223,6 +298,18 @@ mod tests {
         fn create_fixup_commit(&self, _target: Oid) -> rung_git::Result<Oid> {
             Ok(Oid::zero())
         }
+
+        fn create_fixup_commit_on(&self, _branch: &str, _target: Oid) -> rung_git::Result<Oid> {
+            Ok(Oid::zero())
+        }
+
+        fn count_fixup_commits(&self, _from: Oid, _to: Oid) -> rung_git::Result<usize> {
+            Ok(0)
+        }
+
+        fn rebase_autosquash(&self, _onto: Oid, _upstream: Oid) -> rung_git::Result<()> {
+            Ok(())
+        }
     }
 
     #[test]
@@ -18,6 +18,15 @@ pub struct AdoptResult {
     pub stack_depth: usize,
 }
 
+/// Result of removing a branch from the stack.
+#[derive(Debug)]
+pub struct UntrackResult {
+    /// Its parent, which its children now sit on.
+    pub parent_name: String,
+    /// Children moved onto the parent.
+    pub children_moved: Vec<String>,
+}
+
 /// Service for adopting branches into the stack with trait-based dependencies.
 pub struct AdoptService<'a, G: GitOps> {
     repo: &'a G,
@@ -87,6 +96,73 @@ impl<'a, G: GitOps> AdoptService<'a, G> {
         Ok(())
     }
 
+    /// Guess a branch's parent: the closest stack branch (or the base
+    /// branch) whose tip the branch contains.
+    ///
+    /// Falls back to the base branch when the branch contains none of them,
+    /// e.g. because trunk has moved on since it was cut.
+    pub fn infer_parent<S: StateStore>(&self, state: &S, branch: &str) -> Result<String> {
+        let base_branch = state.default_branch()?;
+        let stack = state.load_stack()?;
+        let tip = self.repo.branch_commit(branch)?;
+
+        let mut best: Option<(String, rung_git::Oid)> = None;
+        let candidates = std::iter::once(base_branch.clone())
+            .chain(stack.branches.iter().map(|b| b.name.to_string()))
+            .filter(|name| name != branch);
+        for name in candidates {
+            let Ok(candidate) = self.repo.branch_commit(&name) else {
+                continue;
+            };
+            if self.repo.merge_base(candidate, tip)? != candidate {
+                continue;
+            }
+            // A candidate on top of the current best is closer to the branch
+            let closer = match &best {
+                Some((_, best_oid)) => self.repo.merge_base(*best_oid, candidate)? == *best_oid,
+                None => true,
+            };
+            if closer {
+                best = Some((name, candidate));
+            }
+        }
+
+        Ok(best.map_or(base_branch, |(name, _)| name))
+    }
+
+    /// Remove a branch from the stack, leaving the git branch alone.
+    ///
+    /// Its children move onto its parent. Their recorded base becomes the
+    /// branch's own, so the next sync keeps the untracked branch's commits
+    /// in them rather than dropping them.
+    #[allow(clippy::unused_self)]
+    pub fn untrack_branch<S: StateStore>(&self, state: &S, name: &str) -> Result<UntrackResult> {
+        let mut stack = state.load_stack()?;
+        let Some(branch) = stack.remove_branch(name) else {
+            bail!("Branch '{name}' is not in the stack");
+        };
+
+        let children_moved: Vec<String> = stack
+            .children_of(name)
+            .iter()
+            .map(|c| c.name.to_string())
+            .collect();
+        for child in &children_moved {
+            stack.reparent(child, branch.parent.as_deref())?;
+            if let Some(child) = stack.find_branch_mut(child) {
+                child.base.clone_from(&branch.base);
+            }
+        }
+        state.save_stack(&stack)?;
+
+        Ok(UntrackResult {
+            parent_name: branch
+                .parent
+                .map_or_else(|| state.default_branch(), |p| Ok(p.to_string()))?,
+            children_moved,
+        })
+    }
+
     /// Adopt a branch into the stack.
     ///
     /// Validates that the branch exists in git, is not already in the stack,
@@ -139,9 +215,10 @@ impl<'a, G: GitOps> AdoptService<'a, G> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::services::test_mocks::{MockGitOps, MockStateStore};
     use rung_core::stack::StackBranch;
+    use rung_core::testing::MockStateStore;
     use rung_git::Oid;
+    use rung_git::testing::MockGitOps;
 
     #[test]
     #[allow(clippy::unwrap_used)]
@@ -288,6 +365,32 @@ mod tests {
         assert_eq!(result.stack_depth, 1);
     }
 
+    #[test]
+    #[allow(clippy::unwrap_used)]
+    fn test_untrack_branch_moves_children() {
+        let mut stack = rung_core::Stack::default();
+        for (name, parent) in [("feature/a", "main"), ("feature/b", "feature/a")] {
+            stack.add_branch(StackBranch::try_new(name, Some(parent)).unwrap());
+        }
+        stack.add_branch(StackBranch::try_new("feature/c", Some("feature/b")).unwrap());
+        stack.find_branch_mut("feature/b").unwrap().base = Some("abc".to_string());
+        let mock_state = MockStateStore::new().with_stack(stack);
+        let mock_repo = MockGitOps::new();
+        let service = AdoptService::new(&mock_repo);
+
+        let result = service.untrack_branch(&mock_state, "feature/b").unwrap();
+        assert_eq!(result.parent_name, "feature/a");
+        assert_eq!(result.children_moved, vec!["feature/c"]);
+
+        let stack = mock_state.load_stack().unwrap();
+        assert!(stack.find_branch("feature/b").is_none());
+        let child = stack.find_branch("feature/c").unwrap();
+        assert_eq!(child.parent.as_deref(), Some("feature/a"));
+        assert_eq!(child.base.as_deref(), Some("abc"));
+
+        assert!(service.untrack_branch(&mock_state, "feature/b").is_err());
+    }
+
     #[test]
     #[allow(clippy::unwrap_used)]
     fn test_adopt_branch_already_in_stack() {
@@ -0,0 +1,395 @@
+//! CI service for validating a pull request's stack.
+//!
+//! CI checkouts don't carry `.git/rung`, so the stack is rebuilt from the
+//! forge: each PR's base is followed down to the first branch without an
+//! open PR (the trunk).
+
+use anyhow::{Context, Result, bail};
+use rung_git::GitOps;
+use rung_github::{ForgeApi, RepoId};
+use serde::Serialize;
+
+use super::submit::STACK_COMMENT_MARKER;
+
+/// A branch whose parent's tip is missing from its history.
+pub const CHECK_PARENT_NOT_ANCESTOR: &str = "parent-not-ancestor";
+
+/// An ancestor PR whose branch is behind its own base.
+pub const CHECK_BEHIND_BASE: &str = "behind-base";
+
+/// The rung stack comment is missing or lists a different stack.
+pub const CHECK_STACK_COMMENT: &str = "stack-comment-outdated";
+
+/// A single failed invariant.
+#[derive(Debug, Clone, Serialize)]
+pub struct Finding {
+    pub check: &'static str,
+    pub branch: String,
+    pub message: String,
+}
+
+/// A PR in the stack, from the validated branch down to the trunk.
+#[derive(Debug, Clone, Serialize)]
+pub struct StackPr {
+    pub branch: String,
+    pub pr: u64,
+    pub base: String,
+}
+
+/// Result of validating a PR branch.
+#[derive(Debug, Serialize)]
+pub struct ValidationReport {
+    pub branch: String,
+    pub pr: u64,
+    /// The PR and its ancestors, nearest first.
+    pub stack: Vec<StackPr>,
+    pub trunk: String,
+    pub findings: Vec<Finding>,
+}
+
+impl ValidationReport {
+    /// Whether every check passed.
+    pub const fn is_valid(&self) -> bool {
+        self.findings.is_empty()
+    }
+}
+
+/// Service for CI checks with injected dependencies.
+pub struct CiService<'a, G, H>
+where
+    G: GitOps,
+    H: ForgeApi,
+{
+    git: &'a G,
+    forge: &'a H,
+    repo: RepoId,
+}
+
+#[allow(clippy::future_not_send)] // Git operations are sync; futures don't need to be Send
+impl<'a, G, H> CiService<'a, G, H>
+where
+    G: GitOps,
+    H: ForgeApi,
+{
+    /// Create a new CI service.
+    pub const fn new(git: &'a G, forge: &'a H, repo: RepoId) -> Self {
+        Self { git, forge, repo }
+    }
+
+    /// Check the stack invariants for `branch`'s PR.
+    ///
+    /// # Errors
+    /// Returns error if the branch has no open PR or forge calls fail.
+    pub async fn validate(&self, branch: &str) -> Result<ValidationReport> {
+        let (stack, trunk) = self.load_stack(branch).await?;
+        let pr = stack[0].pr;
+
+        let mut findings = Vec::new();
+        for (i, entry) in stack.iter().enumerate() {
+            if self.parent_is_ancestor(&entry.branch, &entry.base)? {
+                continue;
+            }
+            let (check, message) = if i == 0 {
+                (
+                    CHECK_PARENT_NOT_ANCESTOR,
+                    format!(
+                        "{} does not contain the tip of its base {} - run `rung sync`",
+                        entry.branch, entry.base
+                    ),
+                )
+            } else {
+                (
+                    CHECK_BEHIND_BASE,
+                    format!(
+                        "ancestor {} (#{}) is behind its base {} - run `rung sync`",
+                        entry.branch, entry.pr, entry.base
+                    ),
+                )
+            };
+            findings.push(Finding {
+                check,
+                branch: entry.branch.clone(),
+                message,
+            });
+        }
+
+        if let Some(message) = self.check_stack_comment(&stack, &trunk).await? {
+            findings.push(Finding {
+                check: CHECK_STACK_COMMENT,
+                branch: branch.to_string(),
+                message,
+            });
+        }
+
+        Ok(ValidationReport {
+            branch: branch.to_string(),
+            pr,
+            stack,
+            trunk,
+            findings,
+        })
+    }
+
+    /// Follow PR bases from `branch` down to the trunk.
+    async fn load_stack(&self, branch: &str) -> Result<(Vec<StackPr>, String)> {
+        let mut stack: Vec<StackPr> = Vec::new();
+        let mut current = branch.to_string();
+
+        loop {
+            let pr = self
+                .forge
+                .find_pr_for_branch(&self.repo, &current)
+                .await
+                .with_context(|| format!("Failed to look up PR for {current}"))?;
+
+            match pr {
+                Some(pr) => {
+                    if stack.iter().any(|p| p.branch == pr.base_branch) {
+                        bail!("PR bases form a cycle at {}", pr.base_branch);
+                    }
+                    let base = pr.base_branch.clone();
+                    stack.push(StackPr {
+                        branch: current,
+                        pr: pr.number,
+                        base: pr.base_branch,
+                    });
+                    current = base;
+                }
+                None if stack.is_empty() => bail!("No open PR for branch '{branch}'"),
+                None => return Ok((stack, current)),
+            }
+        }
+    }
+
+    /// Whether `branch` contains the tip of `parent`, using remote refs.
+    fn parent_is_ancestor(&self, branch: &str, parent: &str) -> Result<bool> {
+        let tip = self.tip(branch)?;
+        let parent_tip = self.tip(parent)?;
+        Ok(self.git.merge_base(parent_tip, tip)? == parent_tip)
+    }
+
+    /// Remote tip of a branch, falling back to the local branch.
+    fn tip(&self, branch: &str) -> Result<rung_git::Oid> {
+        // Shallow CI checkouts may not have the branch yet; a failed fetch
+        // surfaces below as a missing ref
+        let _ = self.git.fetch(branch);
+        self.git
+            .remote_branch_commit(branch)
+            .or_else(|_| self.git.branch_commit(branch))
+            .with_context(|| format!("Branch '{branch}' not found locally or on the remote"))
+    }
+
+    /// Compare the PR's stack comment with the stack on the forge.
+    ///
+    /// Returns a description of the mismatch, if any.
+    async fn check_stack_comment(&self, stack: &[StackPr], trunk: &str) -> Result<Option<String>> {
+        let pr = stack[0].pr;
+        let comments = self
+            .forge
+            .list_pr_comments(&self.repo, pr)
+            .await
+            .with_context(|| format!("Failed to list comments on PR #{pr}"))?;
+
+        let Some(body) = comments
+            .iter()
+            .filter_map(|c| c.body.as_deref())
+            .find(|b| b.contains(STACK_COMMENT_MARKER))
+        else {
+            return Ok(Some(format!(
+                "PR #{pr} has no rung stack comment - run `rung submit`"
+            )));
+        };
+
+        let expected = CommentAncestors {
+            current: Some(pr),
+            prs: stack[1..].iter().map(|p| p.pr).collect(),
+            base: Some(trunk.to_string()),
+        };
+        let listed = parse_comment_ancestors(body);
+        if listed == expected {
+            return Ok(None);
+        }
+        Ok(Some(format!(
+            "stack comment on PR #{pr} lists {}, but the PR stack is {} - run `rung submit`",
+            listed.describe(),
+            expected.describe()
+        )))
+    }
+}
+
+/// The part of a stack comment at and below the current PR.
+#[derive(Debug, Default, PartialEq, Eq)]
+struct CommentAncestors {
+    /// PR marked as the current one.
+    current: Option<u64>,
+    /// Open ancestor PRs, nearest first.
+    prs: Vec<u64>,
+    /// Base branch at the bottom of the list.
+    base: Option<String>,
+}
+
+impl CommentAncestors {
+    /// e.g. `#12 → #11 → main`
+    fn describe(&self) -> String {
+        self.current
+            .iter()
+            .chain(&self.prs)
+            .map(|pr| format!("#{pr}"))
+            .chain(self.base.clone())
+            .collect::<Vec<_>>()
+            .join(" → ")
+    }
+}
+
+/// Read the current PR, its open ancestors, and the base from a stack comment.
+///
+/// The comment lists the stack top-down, so ancestors follow the line marked
+/// 👈. Merged ancestors are struck through and skipped.
+fn parse_comment_ancestors(body: &str) -> CommentAncestors {
+    let mut result = CommentAncestors::default();
+    let mut lines = body
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix("* "))
+        .skip_while(|line| !line.contains('👈'));
+
+    if let Some(line) = lines.next() {
+        result.current = pr_number(line);
+    }
+    for line in lines {
+        if line.starts_with("~~") {
+            continue;
+        }
+        if let Some(pr) = pr_number(line) {
+            result.prs.push(pr);
+        } else if let Some(name) = line.strip_prefix('`').and_then(|l| l.strip_suffix('`')) {
+            result.base = Some(name.to_string());
+        }
+    }
+    result
+}
+
+/// PR number from a `**#N**` entry.
+fn pr_number(line: &str) -> Option<u64> {
+    let rest = line.split("**#").nth(1)?;
+    rest.split("**").next()?.parse().ok()
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_comment_ancestors() {
+        let body = "<!-- rung-stack -->\n\
+                    * **#13**\n\
+                    * **#12** 👈\n\
+                    * ~~**#10**~~ ✓\n\
+                    * **#11**\n\
+                    * `main`\n\
+                    \n---\n*Managed by [rung](https://github.com/auswm85/rung)*";
+        let parsed = parse_comment_ancestors(body);
+        assert_eq!(parsed.current, Some(12));
+        assert_eq!(parsed.prs, vec![11]);
+        assert_eq!(parsed.base.as_deref(), Some("main"));
+        assert_eq!(parsed.describe(), "#12 → #11 → main");
+    }
+
+    #[test]
+    fn test_parse_comment_without_pointer() {
+        let parsed = parse_comment_ancestors("<!-- rung-stack -->\n* **#5**\n* `main`");
+        assert_eq!(parsed, CommentAncestors::default());
+    }
+
+    #[test]
+    fn test_report_validity() {
+        let mut report = ValidationReport {
+            branch: "feature".into(),
+            pr: 1,
+            stack: vec![],
+            trunk: "main".into(),
+            findings: vec![],
+        };
+        assert!(report.is_valid());
+        report.findings.push(Finding {
+            check: CHECK_BEHIND_BASE,
+            branch: "feature".into(),
+            message: String::new(),
+        });
+        assert!(!report.is_valid());
+    }
+
+    mod mock_tests {
+        use super::super::*;
+        use rung_git::Oid;
+        use rung_git::testing::MockGitOps;
+        use rung_github::testing::MockGitHubClient;
+        use rung_github::{PullRequest, PullRequestState};
+
+        fn pr(number: u64, head: &str, base: &str) -> PullRequest {
+            PullRequest {
+                number,
+                title: String::new(),
+                body: None,
+                state: PullRequestState::Open,
+                draft: false,
+                head_branch: head.to_string(),
+                base_branch: base.to_string(),
+                html_url: String::new(),
+                mergeable: None,
+                mergeable_state: None,
+                created_at: None,
+                last_reviewed_at: None,
+            }
+        }
+
+        fn git() -> MockGitOps {
+            let oid = Oid::zero();
+            MockGitOps::new()
+                .with_branch("main", oid)
+                .with_branch("feature/a", oid)
+                .with_branch("feature/b", oid)
+        }
+
+        #[tokio::test]
+        async fn test_validate_follows_pr_bases() {
+            let git = git();
+            let forge = MockGitHubClient::new()
+                .with_pr(pr(1, "feature/a", "main"))
+                .with_pr(pr(2, "feature/b", "feature/a"))
+                .with_comment(2, "<!-- rung-stack -->\n* **#2** 👈\n* **#1**\n* `main`");
+            let service = CiService::new(&git, &forge, RepoId::new("owner/repo"));
+
+            let report = service.validate("feature/b").await.unwrap();
+            assert_eq!(report.pr, 2);
+            assert_eq!(report.trunk, "main");
+            assert_eq!(report.stack.len(), 2);
+            assert!(report.is_valid(), "{:?}", report.findings);
+        }
+
+        #[tokio::test]
+        async fn test_validate_reports_outdated_comment() {
+            let git = git();
+            let forge = MockGitHubClient::new()
+                .with_pr(pr(1, "feature/a", "main"))
+                .with_pr(pr(2, "feature/b", "feature/a"))
+                .with_comment(2, "<!-- rung-stack -->\n* **#2** 👈\n* `main`");
+            let service = CiService::new(&git, &forge, RepoId::new("owner/repo"));
+
+            let report = service.validate("feature/b").await.unwrap();
+            assert_eq!(report.findings.len(), 1);
+            assert_eq!(report.findings[0].check, CHECK_STACK_COMMENT);
+            assert!(report.findings[0].message.contains("#2 → #1 → main"));
+        }
+
+        #[tokio::test]
+        async fn test_validate_requires_pr() {
+            let git = git();
+            let forge = MockGitHubClient::new();
+            let service = CiService::new(&git, &forge, RepoId::new("owner/repo"));
+
+            let err = service.validate("feature/a").await.unwrap_err();
+            assert!(err.to_string().contains("No open PR"));
+        }
+    }
+}
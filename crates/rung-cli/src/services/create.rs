@@ -3,9 +3,9 @@
 //! This module handles the logic for creating new branches in the stack,
 //! separated from CLI presentation concerns.
 
-use anyhow::{Context, Result};
+use anyhow::{Context, Result, bail};
 use rung_core::{BranchName, Stack, StateStore, stack::StackBranch};
-use rung_git::GitOps;
+use rung_git::{GitOps, Oid};
 
 /// Result of a branch creation operation.
 #[derive(Debug)]
@@ -20,6 +20,17 @@ pub struct CreateResult {
     pub commit_message: Option<String>,
     /// Stack depth after creation.
     pub stack_depth: usize,
+    /// Branches moved from the parent onto the new branch (`--insert`).
+    pub children_moved: Vec<String>,
+}
+
+/// The commit a new branch starts with.
+#[derive(Debug, Clone, Copy)]
+pub struct InitialCommit<'a> {
+    /// Commit message.
+    pub message: &'a str,
+    /// Commit even when there is nothing to commit (`--allow-empty`).
+    pub allow_empty: bool,
 }
 
 /// Service for creating branches in the stack with trait-based dependencies.
@@ -54,13 +65,30 @@ impl<'a, G: GitOps> CreateService<'a, G> {
         Ok(self.repo.has_staged_changes()?)
     }
 
+    /// Check that a branch started at `start` would sit on `parent`.
+    ///
+    /// The new branch must contain its parent's tip, or the stack would
+    /// record a parent it doesn't build on.
+    pub fn validate_start(&self, parent: &str, start: Oid, rev: &str) -> Result<()> {
+        let parent_tip = self.repo.branch_commit(parent)?;
+        if self.repo.merge_base(parent_tip, start)? != parent_tip {
+            bail!(
+                "'{rev}' doesn't build on '{parent}': a new branch has to contain its parent's tip.\n\
+                 Check out the branch it builds on first, or rebase the work onto '{parent}'."
+            );
+        }
+        Ok(())
+    }
+
     /// Create a new branch in the stack.
     ///
     /// This will:
-    /// 1. Create the git branch at current HEAD
+    /// 1. Create the git branch at `start`, or the current HEAD
     /// 2. Checkout the new branch
-    /// 3. Optionally stage all changes and create a commit
-    /// 4. Add it to the stack (only after git operations succeed)
+    /// 3. Optionally stage all changes and create a commit (an empty one
+    ///    if `allow_empty` is set and there is nothing to commit)
+    /// 4. Add it to the stack (only after git operations succeed), moving
+    ///    the parent's children onto it when `insert` is set
     ///
     /// If any step fails after the branch is created, the branch is deleted
     /// to maintain consistency between git and stack state.
@@ -69,13 +97,21 @@ impl<'a, G: GitOps> CreateService<'a, G> {
         state: &S,
         branch_name: &BranchName,
         parent: &BranchName,
-        message: Option<&str>,
+        commit: Option<InitialCommit<'_>>,
+        insert: bool,
+        start: Option<Oid>,
     ) -> Result<CreateResult> {
         let name = branch_name.as_str();
         let parent_str = parent.as_str();
 
-        // Create the branch at current HEAD (parent's tip)
+        // Create the branch at current HEAD (parent's tip) or the requested commit
         self.repo.create_branch(name)?;
+        if let Some(start) = start
+            && let Err(e) = self.repo.reset_branch(name, start)
+        {
+            let _ = self.repo.delete_branch(name);
+            return Err(anyhow::Error::from(e).context("Failed to move new branch to its start"));
+        }
 
         // Checkout the new branch (rollback on failure)
         if let Err(e) = self.repo.checkout(name) {
@@ -85,8 +121,8 @@ impl<'a, G: GitOps> CreateService<'a, G> {
         }
 
         // Handle optional commit (rollback on failure)
-        let (commit_created, commit_message) = if let Some(msg) = message {
-            match self.create_initial_commit(msg) {
+        let (commit_created, commit_message) = if let Some(commit) = commit {
+            match self.create_initial_commit(commit) {
                 Ok(result) => result,
                 Err(e) => {
                     // Clean up: checkout parent and delete the branch
@@ -109,8 +145,26 @@ impl<'a, G: GitOps> CreateService<'a, G> {
                 return Err(e.into());
             }
         };
-        let branch = StackBranch::new(branch_name.clone(), Some(parent.clone()));
+        let mut branch = StackBranch::new(branch_name.clone(), Some(parent.clone()));
+        branch.base = self
+            .repo
+            .branch_commit(parent_str)
+            .ok()
+            .map(|oid| oid.to_string());
+        // Children keep their base: the new branch starts at the parent's tip
+        let children_moved: Vec<String> = if insert {
+            stack
+                .children_of(parent_str)
+                .iter()
+                .map(|b| b.name.to_string())
+                .collect()
+        } else {
+            vec![]
+        };
         stack.add_branch(branch);
+        for child in &children_moved {
+            stack.reparent(child, Some(name))?;
+        }
         if let Err(e) = state.save_stack(&stack) {
             // Clean up: checkout parent and delete the branch
             let _ = self.repo.checkout(parent_str);
@@ -127,11 +181,13 @@ impl<'a, G: GitOps> CreateService<'a, G> {
             commit_created,
             commit_message,
             stack_depth,
+            children_moved,
         })
     }
 
     /// Stage all changes and create a commit if there are staged changes.
-    fn create_initial_commit(&self, message: &str) -> Result<(bool, Option<String>)> {
+    fn create_initial_commit(&self, commit: InitialCommit<'_>) -> Result<(bool, Option<String>)> {
+        let message = commit.message;
         // Check for pre-staged changes first (user may have staged specific files)
         if self.repo.has_staged_changes()? {
             self.repo
@@ -142,7 +198,7 @@ impl<'a, G: GitOps> CreateService<'a, G> {
 
         // No staged changes - check if there are unstaged changes to stage
         if self.repo.is_clean()? {
-            return Ok((false, None));
+            return self.commit_if_empty_allowed(commit);
         }
 
         // Stage all unstaged changes
@@ -154,10 +210,21 @@ impl<'a, G: GitOps> CreateService<'a, G> {
                 .context("Failed to create commit")?;
             Ok((true, Some(message.to_string())))
         } else {
-            Ok((false, None))
+            self.commit_if_empty_allowed(commit)
         }
     }
 
+    /// Create an empty commit when the caller asked for one.
+    fn commit_if_empty_allowed(&self, commit: InitialCommit<'_>) -> Result<(bool, Option<String>)> {
+        if !commit.allow_empty {
+            return Ok((false, None));
+        }
+        self.repo
+            .create_commit(commit.message)
+            .context("Failed to create commit")?;
+        Ok((true, Some(commit.message.to_string())))
+    }
+
     /// Get the stack for reading (useful for dry-run scenarios).
     #[allow(dead_code, clippy::unused_self)]
     pub fn load_stack<S: StateStore>(&self, state: &S) -> Result<Stack> {
@@ -168,8 +235,8 @@ impl<'a, G: GitOps> CreateService<'a, G> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::services::test_mocks::{MockGitOps, MockStateStore};
-    use rung_git::Oid;
+    use rung_core::testing::MockStateStore;
+    use rung_git::testing::MockGitOps;
 
     #[test]
     #[allow(clippy::unwrap_used)]
@@ -227,7 +294,7 @@ mod tests {
         let parent = BranchName::new("main").unwrap();
 
         let result = service
-            .create_branch(&mock_state, &branch_name, &parent, None)
+            .create_branch(&mock_state, &branch_name, &parent, None, false, None)
             .unwrap();
 
         assert_eq!(result.branch_name, "feature/new");
@@ -250,7 +317,17 @@ mod tests {
         let parent = BranchName::new("main").unwrap();
 
         let result = service
-            .create_branch(&mock_state, &branch_name, &parent, Some("Initial commit"))
+            .create_branch(
+                &mock_state,
+                &branch_name,
+                &parent,
+                Some(InitialCommit {
+                    message: "Initial commit",
+                    allow_empty: false,
+                }),
+                false,
+                None,
+            )
             .unwrap();
 
         assert_eq!(result.branch_name, "feature/with-commit");
@@ -274,7 +351,17 @@ mod tests {
         let parent = BranchName::new("main").unwrap();
 
         let result = service
-            .create_branch(&mock_state, &branch_name, &parent, Some("Message"))
+            .create_branch(
+                &mock_state,
+                &branch_name,
+                &parent,
+                Some(InitialCommit {
+                    message: "Message",
+                    allow_empty: false,
+                }),
+                false,
+                None,
+            )
             .unwrap();
 
         assert_eq!(result.branch_name, "feature/clean");
@@ -299,7 +386,17 @@ mod tests {
 
         // Note: mock stage_all doesn't actually stage anything, so no commit
         let result = service
-            .create_branch(&mock_state, &branch_name, &parent, Some("Staged changes"))
+            .create_branch(
+                &mock_state,
+                &branch_name,
+                &parent,
+                Some(InitialCommit {
+                    message: "Staged changes",
+                    allow_empty: false,
+                }),
+                false,
+                None,
+            )
             .unwrap();
 
         assert_eq!(result.branch_name, "feature/dirty");
@@ -307,6 +404,66 @@ mod tests {
         assert!(!result.commit_created);
     }
 
+    #[test]
+    #[allow(clippy::unwrap_used)]
+    fn test_create_branch_allow_empty_clean_repo() {
+        let mock_repo = MockGitOps::new()
+            .with_current_branch("main")
+            .with_branch("main", Oid::zero())
+            .with_clean(true)
+            .with_staged_changes(false);
+        let mock_state = MockStateStore::new();
+
+        let service = CreateService::new(&mock_repo);
+        let branch_name = BranchName::new("feature/placeholder").unwrap();
+        let parent = BranchName::new("main").unwrap();
+        let commit = InitialCommit {
+            message: "Placeholder",
+            allow_empty: true,
+        };
+
+        let result = service
+            .create_branch(
+                &mock_state,
+                &branch_name,
+                &parent,
+                Some(commit),
+                false,
+                None,
+            )
+            .unwrap();
+
+        assert!(result.commit_created);
+        assert_eq!(result.commit_message, Some("Placeholder".to_string()));
+    }
+
+    #[test]
+    #[allow(clippy::unwrap_used)]
+    fn test_create_branch_insert_moves_children() {
+        let mut stack = Stack::default();
+        for (name, parent) in [("feature/a", "main"), ("feature/b", "feature/a")] {
+            stack.add_branch(StackBranch::try_new(name, Some(parent)).unwrap());
+        }
+        let mock_repo = MockGitOps::new()
+            .with_current_branch("feature/a")
+            .with_branch("feature/a", Oid::zero());
+        let mock_state = MockStateStore::new().with_stack(stack);
+
+        let service = CreateService::new(&mock_repo);
+        let branch_name = BranchName::new("feature/prep").unwrap();
+        let parent = BranchName::new("feature/a").unwrap();
+
+        let result = service
+            .create_branch(&mock_state, &branch_name, &parent, None, true, None)
+            .unwrap();
+
+        assert_eq!(result.children_moved, vec!["feature/b"]);
+        let stack = mock_state.load_stack().unwrap();
+        let child = stack.find_branch("feature/b").unwrap();
+        assert_eq!(child.parent.as_deref(), Some("feature/prep"));
+        assert_eq!(result.stack_depth, 2);
+    }
+
     #[test]
     #[allow(clippy::unwrap_used)]
     fn test_create_service_load_stack() {
@@ -327,6 +484,7 @@ mod tests {
             commit_created: true,
             commit_message: Some("Initial commit".to_string()),
             stack_depth: 2,
+            children_moved: vec![],
         };
 
         assert_eq!(result.branch_name, "feature/test");
@@ -344,6 +502,7 @@ mod tests {
             commit_created: false,
             commit_message: None,
             stack_depth: 1,
+            children_moved: vec![],
         };
 
         assert!(!result.commit_created);
@@ -358,6 +517,7 @@ mod tests {
             commit_created: false,
             commit_message: None,
             stack_depth: 5,
+            children_moved: vec![],
         };
 
         assert_eq!(result.stack_depth, 5);
@@ -372,6 +532,7 @@ mod tests {
             commit_created: true,
             commit_message: Some("msg".to_string()),
             stack_depth: 1,
+            children_moved: vec![],
         };
         // Test that Debug is implemented
         let debug_str = format!("{result:?}");
@@ -387,6 +548,7 @@ mod tests {
             commit_created: true,
             commit_message: Some("Initial work".to_string()),
             stack_depth: 1,
+            children_moved: vec![],
         };
 
         assert_eq!(result.stack_depth, 1);
@@ -402,6 +564,7 @@ mod tests {
             commit_created: true,
             commit_message: Some(long_message.to_string()),
             stack_depth: 1,
+            children_moved: vec![],
         };
 
         assert_eq!(result.commit_message, Some(long_message.to_string()));
@@ -3,11 +3,12 @@
 //! This module contains the diagnostic logic separated from CLI concerns,
 //! enabling testing and reuse.
 
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashSet};
 
 use anyhow::{Context, Result};
 use rung_core::Stack;
-use rung_github::{Auth, ForgeApi, PullRequestState};
+use rung_forge::ForgeError;
+use rung_github::{Auth, ForgeApi, PullRequest, PullRequestState, TokenInfo};
 
 use crate::forge::Forge;
 use serde::Serialize;
@@ -80,26 +81,81 @@ impl CheckResult {
     }
 }
 
+/// The tooling and credentials rung depends on, gathered up front so the
+/// environment check stays independent of the machine it runs on.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Environment {
+    /// rung version.
+    pub rung: String,
+    /// `git` version, `None` if git isn't on `PATH`.
+    pub git: Option<String>,
+    /// Whether `git` is new enough for everything rung runs it for.
+    #[serde(skip)]
+    pub git_supported: bool,
+    /// libgit2 version.
+    pub libgit2: String,
+    /// libgit2 features, e.g. `https` and `ssh`.
+    pub libgit2_features: Vec<String>,
+    /// How git authenticates to origin; `None` for local or missing remotes.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub origin_credentials: Option<OriginCredentials>,
+}
+
+impl Environment {
+    /// Describe the environment from the detected toolchain.
+    pub fn new(
+        toolchain: &rung_git::Toolchain,
+        origin_credentials: Option<OriginCredentials>,
+    ) -> Self {
+        let features = [("https", toolchain.https), ("ssh", toolchain.ssh)];
+        Self {
+            rung: env!("CARGO_PKG_VERSION").to_string(),
+            git: toolchain.git.clone(),
+            git_supported: toolchain.git_is_supported(),
+            libgit2: toolchain.libgit2.clone(),
+            libgit2_features: features
+                .iter()
+                .filter(|(_, on)| *on)
+                .map(|(name, _)| (*name).to_string())
+                .collect(),
+            origin_credentials,
+        }
+    }
+}
+
+/// How git can authenticate to the origin remote.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct OriginCredentials {
+    /// `ssh` or `https`.
+    pub transport: &'static str,
+    /// What supplies the credentials (an agent, key, or helper), if anything.
+    pub source: Option<String>,
+}
+
 /// Complete diagnostic report.
 #[derive(Debug, Default)]
 #[allow(dead_code)]
 pub struct DiagnosticReport {
+    pub environment: CheckResult,
     pub git_state: CheckResult,
     pub stack_integrity: CheckResult,
     pub sync_state: CheckResult,
     pub github: CheckResult,
+    pub orphaned_remotes: CheckResult,
 }
 
 #[allow(dead_code)]
 impl DiagnosticReport {
     /// Get all issues from all categories.
     pub fn all_issues(&self) -> Vec<&Issue> {
-        self.git_state
+        self.environment
             .issues
             .iter()
+            .chain(self.git_state.issues.iter())
             .chain(self.stack_integrity.issues.iter())
             .chain(self.sync_state.issues.iter())
             .chain(self.github.issues.iter())
+            .chain(self.orphaned_remotes.issues.iter())
             .collect()
     }
 
@@ -130,12 +186,46 @@ pub struct DoctorService<'a, G: rung_git::GitOps, S: rung_core::StateStore> {
     repo: &'a G,
     state: &'a S,
     stack: &'a Stack,
+    /// Branches rung deleted locally (from the audit log).
+    removed: Vec<String>,
+    /// Tooling and credentials to check, if gathered.
+    environment: Option<Environment>,
+    /// `github.repo`, naming the repository when the remote URL can't.
+    repo_override: Option<String>,
 }
 
 impl<'a, G: rung_git::GitOps, S: rung_core::StateStore> DoctorService<'a, G, S> {
     /// Create a new doctor service.
     pub const fn new(repo: &'a G, state: &'a S, stack: &'a Stack) -> Self {
-        Self { repo, state, stack }
+        Self {
+            repo,
+            state,
+            stack,
+            removed: Vec::new(),
+            environment: None,
+            repo_override: None,
+        }
+    }
+
+    /// Also check this environment's tooling and credentials.
+    #[must_use]
+    pub fn with_environment(mut self, environment: Environment) -> Self {
+        self.environment = Some(environment);
+        self
+    }
+
+    /// Use this `owner/name` instead of reading it from the remote URL.
+    #[must_use]
+    pub fn with_repo_override(mut self, repo: Option<String>) -> Self {
+        self.repo_override = repo;
+        self
+    }
+
+    /// Also look for remote leftovers of these locally deleted branches.
+    #[must_use]
+    pub fn with_removed_branches(mut self, removed: Vec<String>) -> Self {
+        self.removed = removed;
+        self
     }
 
     /// Run all diagnostic checks and return a complete report.
@@ -144,13 +234,59 @@ impl<'a, G: rung_git::GitOps, S: rung_core::StateStore> DoctorService<'a, G, S>
     pub async fn run_diagnostics(&self) -> Result<DiagnosticReport> {
         let github_result = self.check_github().await;
         Ok(DiagnosticReport {
+            environment: self.check_environment(),
             git_state: self.check_git_state(),
             stack_integrity: self.check_stack_integrity(),
             sync_state: self.check_sync_state()?,
             github: github_result,
+            orphaned_remotes: self.check_orphaned_remotes(),
         })
     }
 
+    /// Check the git tooling and the credentials for origin.
+    pub fn check_environment(&self) -> CheckResult {
+        let mut result = CheckResult::default();
+        let Some(env) = &self.environment else {
+            return result;
+        };
+
+        let (major, minor, _) = rung_git::MIN_GIT_VERSION;
+        match &env.git {
+            None => result
+                .issues
+                .push(Issue::error("git was not found on PATH").with_suggestion(
+                format!(
+                    "Install git {major}.{minor} or newer - rung runs it to rebase, fetch and push"
+                ),
+            )),
+            Some(version) if !env.git_supported => result.issues.push(
+                Issue::warning(format!("git {version} is older than {major}.{minor}"))
+                    .with_suggestion(
+                        "Upgrade git - `rung sync --check` needs `git merge-tree --write-tree`",
+                    ),
+            ),
+            Some(_) => {}
+        }
+
+        match &env.origin_credentials {
+            Some(OriginCredentials {
+                transport: "ssh",
+                source: None,
+            }) => result.issues.push(
+                Issue::warning("No SSH agent or key found for origin").with_suggestion(
+                    "Start ssh-agent and run `ssh-add`, or switch origin to an HTTPS URL",
+                ),
+            ),
+            Some(OriginCredentials { source: None, .. }) => result.issues.push(
+                Issue::warning("No git credential helper configured for the HTTPS origin")
+                    .with_suggestion("Run `gh auth setup-git`, or set `credential.helper`"),
+            ),
+            _ => {}
+        }
+
+        result
+    }
+
     /// Check git repository state.
     pub fn check_git_state(&self) -> CheckResult {
         let mut result = CheckResult::default();
@@ -188,6 +324,70 @@ impl<'a, G: rung_git::GitOps, S: rung_core::StateStore> DoctorService<'a, G, S>
             );
         }
 
+        result.issues.extend(self.check_identity().issues);
+        result.issues.extend(self.check_signing().issues);
+
+        result
+    }
+
+    /// Check that commits rung creates have an author and committer.
+    pub fn check_identity(&self) -> CheckResult {
+        let mut result = CheckResult::default();
+        if let Err(rung_git::Error::MissingIdentity(key)) = self.repo.identity() {
+            result.issues.push(
+                Issue::error(format!("No git identity: {key} is not set"))
+                    .with_suggestion(format!(
+                        "Run `git config --global {key} <value>` - rung can't create, amend, or rebase commits without it"
+                    )),
+            );
+        }
+        result
+    }
+
+    /// Check that commits rewritten by sync/restack will be signed as expected.
+    pub fn check_signing(&self) -> CheckResult {
+        let mut result = CheckResult::default();
+
+        let Ok(signing) = self.repo.signing_config() else {
+            return result;
+        };
+
+        if signing.enabled {
+            if !signing.program_available() {
+                result.issues.push(
+                    Issue::error(format!(
+                        "Commit signing is enabled but '{}' was not found",
+                        signing.program
+                    ))
+                    .with_suggestion(
+                        "Install it or point git at it with `git config gpg.program <path>`",
+                    ),
+                );
+            } else if !signing.key_available() {
+                let key = signing.key.as_deref().unwrap_or("(not set)");
+                result.issues.push(
+                    Issue::error(format!(
+                        "Commit signing is enabled but signing key {key} is not available"
+                    ))
+                    .with_suggestion("Check `user.signingkey` - rebases will fail to sign commits"),
+                );
+            }
+            return result;
+        }
+
+        // Signed history on the base branch suggests the repo requires signatures
+        if let Ok(base) = self.state.default_branch()
+            && let Ok(tip) = self.repo.branch_commit(&base)
+            && self.repo.is_commit_signed(tip).unwrap_or(false)
+        {
+            result.issues.push(
+                Issue::warning(format!(
+                    "Commits on '{base}' are signed but commit signing is disabled - rebased commits will be unsigned"
+                ))
+                .with_suggestion("Enable signing with `git config commit.gpgsign true`"),
+            );
+        }
+
         result
     }
 
@@ -220,6 +420,8 @@ impl<'a, G: rung_git::GitOps, S: rung_core::StateStore> DoctorService<'a, G, S>
             }
         }
 
+        result.issues.extend(self.check_duplicate_prs().issues);
+
         // Check for circular dependencies (report each cycle only once)
         let mut reported: HashSet<String> = HashSet::new();
         for branch in &self.stack.branches {
@@ -241,6 +443,34 @@ impl<'a, G: rung_git::GitOps, S: rung_core::StateStore> DoctorService<'a, G, S>
         result
     }
 
+    /// Check that no PR number is linked to more than one stack branch.
+    ///
+    /// The GitHub check names the branch to keep, since only the PR's head
+    /// says which mapping is right.
+    pub fn check_duplicate_prs(&self) -> CheckResult {
+        let mut result = CheckResult::default();
+
+        let mut by_pr: BTreeMap<u64, Vec<&str>> = BTreeMap::new();
+        for branch in &self.stack.branches {
+            if let Some(pr) = branch.pr {
+                by_pr.entry(pr).or_default().push(branch.name.as_str());
+            }
+        }
+        for (pr, branches) in by_pr.into_iter().filter(|(_, b)| b.len() > 1) {
+            result.issues.push(
+                Issue::error(format!(
+                    "PR #{pr} is linked to several branches: {}",
+                    branches.join(", ")
+                ))
+                .with_suggestion(format!(
+                    "Keep it on the PR's head branch and remove \"pr\": {pr} from the others in .git/rung/stack.json"
+                )),
+            );
+        }
+
+        result
+    }
+
     /// Find a circular dependency starting from the given branch.
     /// Returns `Some(cycle_nodes)` if a cycle is found, `None` otherwise.
     fn find_circular_dependency(&self, branch_name: &str) -> Option<Vec<String>> {
@@ -325,6 +555,28 @@ impl<'a, G: rung_git::GitOps, S: rung_core::StateStore> DoctorService<'a, G, S>
         Ok(result)
     }
 
+    /// The forge repository to check: `github.repo`, or read from the remote.
+    fn forge_repo(&self) -> Result<rung_forge::RemoteInfo, Issue> {
+        if let Some(repo) = &self.repo_override {
+            return rung_forge::RemoteInfo::github(repo).ok_or_else(|| {
+                Issue::error(format!(
+                    "Invalid github.repo '{repo}' - expected owner/name"
+                ))
+            });
+        }
+        let origin_url = self
+            .repo
+            .origin_url()
+            .map_err(|_| Issue::warning("No origin remote configured"))?;
+        rung_forge::parse_remote(&origin_url).map_err(|_| {
+            Issue::warning(format!(
+                "Origin is not a recognized repository (supported: {})",
+                rung_forge::ForgeKind::supported_label()
+            ))
+            .with_suggestion("Set github.repo to its owner/name if it's on GitHub")
+        })
+    }
+
     /// Check GitHub connectivity and PR state.
     #[allow(clippy::future_not_send)] // Git operations are sync; future doesn't need Send
     pub async fn check_github(&self) -> CheckResult {
@@ -332,28 +584,20 @@ impl<'a, G: rung_git::GitOps, S: rung_core::StateStore> DoctorService<'a, G, S>
 
         // Resolve the remote first so non-forge remotes are reported before any
         // forge API call (the forge kind gates which backend we talk to).
-        let Ok(origin_url) = self.repo.origin_url() else {
-            result
-                .issues
-                .push(Issue::warning("No origin remote configured"));
-            return result;
-        };
-
-        let Ok(rung_forge::RemoteInfo {
+        let rung_forge::RemoteInfo {
             repo: repo_id,
             kind,
-        }) = rung_forge::parse_remote(&origin_url)
-        else {
-            result.issues.push(Issue::warning(format!(
-                "Origin is not a recognized repository (supported: {})",
-                rung_forge::ForgeKind::supported_label()
-            )));
-            return result;
+        } = match self.forge_repo() {
+            Ok(info) => info,
+            Err(issue) => {
+                result.issues.push(issue);
+                return result;
+            }
         };
 
         // Authenticate with the detected forge.
         let auth = Auth::auto();
-        let Ok(client) = Forge::for_remote(&origin_url, &auth) else {
+        let Ok(client) = Forge::for_kind(kind, &auth) else {
             result.issues.push(
                 Issue::error(format!("{} authentication failed", kind.display_name()))
                     .with_suggestion(kind.auth_hint()),
@@ -361,6 +605,16 @@ impl<'a, G: rung_git::GitOps, S: rung_core::StateStore> DoctorService<'a, G, S>
             return result;
         };
 
+        // A rejected token makes every later check fail the same way
+        let token = client.token_info().await;
+        if let Some(issue) = token_issue(kind, &token) {
+            let invalid = issue.severity == Severity::Error;
+            result.issues.push(issue);
+            if invalid {
+                return result;
+            }
+        }
+
         // Check PRs for branches that have them
         for branch in &self.stack.branches {
             let Some(pr_number) = branch.pr else {
@@ -370,6 +624,18 @@ impl<'a, G: rung_git::GitOps, S: rung_core::StateStore> DoctorService<'a, G, S>
             // Check if PR is still open
             match client.get_pr(&repo_id, pr_number).await {
                 Ok(pr) => {
+                    if pr.head_branch != branch.name.as_str() {
+                        result.issues.push(
+                            Issue::warning(format!(
+                                "PR #{pr_number} is linked to '{}' but was opened from '{}'",
+                                branch.name, pr.head_branch
+                            ))
+                            .with_suggestion(format!(
+                                "Keep the mapping on '{}' - remove \"pr\": {pr_number} from '{}' in .git/rung/stack.json",
+                                pr.head_branch, branch.name
+                            )),
+                        );
+                    }
                     let state_str = match pr.state {
                         PullRequestState::Open => continue,
                         PullRequestState::Closed => "closed",
@@ -392,8 +658,184 @@ impl<'a, G: rung_git::GitOps, S: rung_core::StateStore> DoctorService<'a, G, S>
             }
         }
 
+        // Several open PRs from one head happen when PRs are opened by hand
+        for branch in &self.stack.branches {
+            if self.repo.remote_branch_commit(&branch.name).is_err() {
+                continue;
+            }
+            let Ok(prs) = client
+                .find_open_prs_for_branch(&repo_id, &branch.name)
+                .await
+            else {
+                continue;
+            };
+            if let Some(issue) = multiple_prs_issue(&branch.name, branch.pr, &prs) {
+                result.issues.push(issue);
+            }
+        }
+
+        result.issues.extend(self.check_remote_branches().issues);
+
         result
     }
+
+    /// Check for stack branches whose remote branch was deleted.
+    ///
+    /// Only branches that have been pushed (have a remote-tracking ref) are
+    /// considered. Failing to query the remote is not reported here.
+    pub fn check_remote_branches(&self) -> CheckResult {
+        let mut result = CheckResult::default();
+
+        let Ok(remote) = self.repo.remote_branches() else {
+            return result;
+        };
+
+        for branch in &self.stack.branches {
+            if self.repo.branch_exists(&branch.name)
+                && self.repo.remote_branch_commit(&branch.name).is_ok()
+                && !remote.iter().any(|r| r == branch.name.as_str())
+            {
+                result.issues.push(
+                    Issue::warning(format!(
+                        "Branch '{}' was deleted on the remote",
+                        branch.name
+                    ))
+                    .with_suggestion("Run `rung sync` to re-push it or remove it from the stack"),
+                );
+            }
+        }
+
+        result
+    }
+
+    /// Branches still on origin whose stack entry was merged or deleted.
+    ///
+    /// Candidates are the stack's merged branches plus the branches rung
+    /// deleted locally; anything still tracked, present locally, or the
+    /// default branch is left alone. Failing to query the remote yields none.
+    pub fn orphaned_remote_branches(&self) -> Vec<String> {
+        let Ok(remote) = self.repo.remote_branches() else {
+            return vec![];
+        };
+        let default_branch = self.state.default_branch().ok();
+
+        let mut orphans: Vec<String> = self
+            .stack
+            .merged
+            .iter()
+            .map(|m| m.name.to_string())
+            .chain(self.removed.iter().cloned())
+            .filter(|name| {
+                remote.contains(name)
+                    && self.stack.find_branch(name).is_none()
+                    && !self.repo.branch_exists(name)
+                    && default_branch.as_deref() != Some(name.as_str())
+            })
+            .collect();
+        orphans.sort();
+        orphans.dedup();
+        orphans
+    }
+
+    /// Check for remote branches left behind by merged or deleted branches.
+    pub fn check_orphaned_remotes(&self) -> CheckResult {
+        CheckResult {
+            issues: orphaned_remotes_issue(&self.orphaned_remote_branches())
+                .into_iter()
+                .collect(),
+        }
+    }
+}
+
+/// Report a forge token that was rejected or can't write to repositories.
+pub fn token_issue(
+    kind: rung_forge::ForgeKind,
+    token: &rung_forge::Result<TokenInfo>,
+) -> Option<Issue> {
+    match token {
+        Err(ForgeError::AuthenticationFailed) => Some(
+            Issue::error(format!(
+                "{} token is invalid or expired",
+                kind.display_name()
+            ))
+            .with_suggestion(kind.auth_hint()),
+        ),
+        Ok(info) if !info.can_write_repos() => {
+            let scopes = info.scopes.as_deref().unwrap_or_default();
+            Some(
+                Issue::warning(format!(
+                    "{} token for '{}' lacks the 'repo' scope (has: {})",
+                    kind.display_name(),
+                    info.login,
+                    if scopes.is_empty() {
+                        "none".to_string()
+                    } else {
+                        scopes.join(", ")
+                    }
+                ))
+                .with_suggestion(
+                    "Run `gh auth refresh -s repo`, or regenerate the token with the `repo` \
+                     scope at https://github.com/settings/tokens",
+                ),
+            )
+        }
+        // Network trouble shows up in the other checks
+        _ => None,
+    }
+}
+
+/// Report remote branches that no longer back a stack entry.
+pub fn orphaned_remotes_issue(orphans: &[String]) -> Option<Issue> {
+    if orphans.is_empty() {
+        return None;
+    }
+    Some(
+        Issue::warning(format!(
+            "{} remote branch(es) left over from merged or deleted stack branches: {}",
+            orphans.len(),
+            orphans.join(", ")
+        ))
+        .with_suggestion(format!(
+            "Run `rung doctor --fix` to delete them, or `git push origin --delete {}`",
+            orphans.join(" ")
+        )),
+    )
+}
+
+/// Report a branch that is the head of more than one open PR, suggesting
+/// the one rung already tracks (or the oldest) as the one to keep.
+fn multiple_prs_issue(branch: &str, tracked: Option<u64>, prs: &[PullRequest]) -> Option<Issue> {
+    if prs.len() < 2 {
+        return None;
+    }
+    let mut numbers: Vec<u64> = prs.iter().map(|pr| pr.number).collect();
+    numbers.sort_unstable();
+    let keep = tracked
+        .filter(|n| numbers.contains(n))
+        .unwrap_or(numbers[0]);
+    let close: Vec<String> = numbers
+        .iter()
+        .filter(|&&n| n != keep)
+        .map(|n| format!("#{n}"))
+        .collect();
+    let list: Vec<String> = numbers.iter().map(|n| format!("#{n}")).collect();
+
+    Some(
+        Issue::warning(format!(
+            "'{branch}' has {} open PRs: {}",
+            numbers.len(),
+            list.join(", ")
+        ))
+        .with_suggestion(format!(
+            "Keep #{keep}{} and close {}",
+            if tracked == Some(keep) {
+                " (linked in the stack)"
+            } else {
+                ""
+            },
+            close.join(", ")
+        )),
+    )
 }
 
 #[cfg(test)]
@@ -597,9 +1039,10 @@ mod tests {
     #[allow(clippy::unwrap_used)]
     mod mock_tests {
         use super::*;
-        use crate::services::test_mocks::{MockGitOps, MockStateStore};
         use rung_core::stack::StackBranch;
-        use rung_git::Oid;
+        use rung_core::testing::MockStateStore;
+        use rung_git::testing::MockGitOps;
+        use rung_git::{Oid, SigningConfig};
 
         #[test]
         fn test_check_git_state_clean() {
@@ -648,6 +1091,170 @@ mod tests {
             );
         }
 
+        #[test]
+        fn test_check_remote_branches_reports_deleted() {
+            let oid = Oid::zero();
+            let git = MockGitOps::new()
+                .with_branch("feature/a", oid)
+                .with_branch("feature/b", oid)
+                .with_remote_branches(&["feature/a"]);
+
+            let state = MockStateStore::new();
+            let mut stack = Stack::default();
+            stack.add_branch(StackBranch::try_new("feature/a", None::<&str>).unwrap());
+            stack.add_branch(StackBranch::try_new("feature/b", Some("feature/a")).unwrap());
+
+            let service = DoctorService::new(&git, &state, &stack);
+            let result = service.check_remote_branches();
+
+            assert_eq!(result.issues.len(), 1);
+            assert!(result.issues[0].message.contains("'feature/b'"));
+            assert!(result.issues[0].message.contains("deleted on the remote"));
+        }
+
+        #[test]
+        fn test_check_remote_branches_clean() {
+            let oid = Oid::zero();
+            let git = MockGitOps::new().with_branch("feature/a", oid);
+
+            let state = MockStateStore::new();
+            let mut stack = Stack::default();
+            stack.add_branch(StackBranch::try_new("feature/a", None::<&str>).unwrap());
+
+            let service = DoctorService::new(&git, &state, &stack);
+            let result = service.check_remote_branches();
+
+            assert!(result.is_clean());
+        }
+
+        #[test]
+        fn test_orphaned_remote_branches() {
+            let oid = Oid::zero();
+            let git = MockGitOps::new()
+                .with_branch("feature/a", oid)
+                .with_branch("feature/kept", oid)
+                .with_remote_branches(&[
+                    "main",
+                    "feature/a",
+                    "feature/merged",
+                    "feature/removed",
+                    "feature/kept",
+                ]);
+
+            let state = MockStateStore::new();
+            let mut stack = Stack::default();
+            stack.add_branch(StackBranch::try_new("feature/a", None::<&str>).unwrap());
+            let mut merged = StackBranch::try_new("feature/merged", None::<&str>).unwrap();
+            merged.pr = Some(7);
+            stack.add_branch(merged);
+            stack.mark_merged("feature/merged");
+
+            // 'feature/kept' still exists locally, 'feature/gone' was never pushed
+            let removed = ["feature/removed", "feature/kept", "feature/gone", "main"]
+                .map(String::from)
+                .to_vec();
+            let service = DoctorService::new(&git, &state, &stack).with_removed_branches(removed);
+
+            assert_eq!(
+                service.orphaned_remote_branches(),
+                vec!["feature/merged", "feature/removed"]
+            );
+            let result = service.check_orphaned_remotes();
+            assert!(result.has_warnings());
+            assert!(
+                result.issues[0]
+                    .suggestion
+                    .as_deref()
+                    .unwrap()
+                    .contains("rung doctor --fix")
+            );
+        }
+
+        #[test]
+        fn test_check_identity() {
+            let state = MockStateStore::new();
+            let stack = Stack::default();
+
+            let git = MockGitOps::new();
+            assert!(
+                DoctorService::new(&git, &state, &stack)
+                    .check_identity()
+                    .is_clean()
+            );
+
+            let git = MockGitOps::new().with_identity(None);
+            let result = DoctorService::new(&git, &state, &stack).check_identity();
+            assert!(result.has_errors());
+            assert!(result.issues[0].message.contains("user.name"));
+        }
+
+        #[test]
+        fn test_check_signing_disabled_unsigned_base() {
+            let git = MockGitOps::new().with_branch("main", Oid::zero());
+            let state = MockStateStore::new();
+            let stack = Stack::default();
+
+            let service = DoctorService::new(&git, &state, &stack);
+            assert!(service.check_signing().is_clean());
+        }
+
+        #[test]
+        fn test_check_signing_disabled_signed_base() {
+            let oid = Oid::zero();
+            let git = MockGitOps::new()
+                .with_branch("main", oid)
+                .with_signed_commit(oid);
+            let state = MockStateStore::new();
+            let stack = Stack::default();
+
+            let service = DoctorService::new(&git, &state, &stack);
+            let result = service.check_signing();
+
+            assert!(result.has_warnings());
+            assert!(result.issues[0].message.contains("will be unsigned"));
+        }
+
+        #[test]
+        fn test_check_signing_missing_program() {
+            let git = MockGitOps::new().with_signing_config(SigningConfig {
+                enabled: true,
+                program: "rung-test-no-such-signer".into(),
+                ..SigningConfig::default()
+            });
+            let state = MockStateStore::new();
+            let stack = Stack::default();
+
+            let service = DoctorService::new(&git, &state, &stack);
+            let result = service.check_signing();
+
+            assert!(result.has_errors());
+            assert!(
+                result.issues[0]
+                    .message
+                    .contains("rung-test-no-such-signer")
+            );
+        }
+
+        #[test]
+        fn test_check_signing_ssh_without_key() {
+            // Any existing file stands in for the signing program
+            let program = std::env::current_exe().unwrap();
+            let git = MockGitOps::new().with_signing_config(SigningConfig {
+                enabled: true,
+                format: "ssh".into(),
+                key: None,
+                program: program.display().to_string(),
+            });
+            let state = MockStateStore::new();
+            let stack = Stack::default();
+
+            let service = DoctorService::new(&git, &state, &stack);
+            let result = service.check_signing();
+
+            assert!(result.has_errors());
+            assert!(result.issues[0].message.contains("signing key"));
+        }
+
         #[test]
         fn test_check_stack_integrity_empty_stack() {
             let git = MockGitOps::new();
@@ -717,6 +1324,125 @@ mod tests {
             assert!(result.is_clean());
         }
 
+        #[test]
+        fn test_check_stack_integrity_duplicate_prs() {
+            let oid = Oid::zero();
+            let git = MockGitOps::new()
+                .with_branch("main", oid)
+                .with_branch("feature/a", oid)
+                .with_branch("feature/b", oid);
+
+            let state = MockStateStore::new();
+            let mut stack = Stack::default();
+            for name in ["feature/a", "feature/b"] {
+                let mut branch = StackBranch::try_new(name, Some("main")).unwrap();
+                branch.pr = Some(42);
+                stack.add_branch(branch);
+            }
+
+            let service = DoctorService::new(&git, &state, &stack);
+            let result = service.check_stack_integrity();
+
+            assert!(result.has_errors());
+            assert_eq!(
+                result.issues[0].message,
+                "PR #42 is linked to several branches: feature/a, feature/b"
+            );
+        }
+
+        #[test]
+        fn test_multiple_prs_issue() {
+            let pr = |number| PullRequest {
+                number,
+                title: String::new(),
+                body: None,
+                state: PullRequestState::Open,
+                draft: false,
+                head_branch: "feature".to_string(),
+                base_branch: "main".to_string(),
+                html_url: String::new(),
+                mergeable: None,
+                mergeable_state: None,
+                created_at: None,
+                last_reviewed_at: None,
+            };
+
+            assert!(multiple_prs_issue("feature", None, &[pr(7)]).is_none());
+
+            let issue = multiple_prs_issue("feature", Some(9), &[pr(9), pr(7)]).unwrap();
+            assert_eq!(issue.message, "'feature' has 2 open PRs: #7, #9");
+            assert_eq!(
+                issue.suggestion.as_deref(),
+                Some("Keep #9 (linked in the stack) and close #7")
+            );
+
+            // Not tracked yet: keep the oldest
+            let issue = multiple_prs_issue("feature", None, &[pr(9), pr(7)]).unwrap();
+            assert_eq!(issue.suggestion.as_deref(), Some("Keep #7 and close #9"));
+        }
+
+        #[test]
+        fn test_token_issue() {
+            let kind = rung_forge::ForgeKind::GitHub;
+            let token = |scopes: Option<&[&str]>| {
+                Ok(TokenInfo {
+                    login: "octocat".to_string(),
+                    scopes: scopes.map(|s| s.iter().map(ToString::to_string).collect()),
+                })
+            };
+
+            let issue = token_issue(kind, &Err(ForgeError::AuthenticationFailed)).unwrap();
+            assert_eq!(issue.severity, Severity::Error);
+            assert!(issue.message.contains("invalid or expired"));
+
+            let issue = token_issue(kind, &token(Some(&["gist"]))).unwrap();
+            assert_eq!(issue.severity, Severity::Warning);
+            assert!(issue.message.contains("lacks the 'repo' scope (has: gist)"));
+
+            assert!(token_issue(kind, &token(Some(&["repo", "gist"]))).is_none());
+            assert!(token_issue(kind, &token(None)).is_none());
+            assert!(token_issue(kind, &Err(ForgeError::RateLimited)).is_none());
+        }
+
+        #[test]
+        fn test_check_environment() {
+            let git = MockGitOps::new();
+            let state = MockStateStore::new();
+            let stack = Stack::default();
+            let env = |git: Option<&str>, source: Option<&str>| Environment {
+                rung: "0.0.0".to_string(),
+                git: git.map(String::from),
+                git_supported: git.is_some_and(|v| v != "2.30.0"),
+                libgit2: "1.8.1".to_string(),
+                libgit2_features: vec!["https".to_string()],
+                origin_credentials: Some(OriginCredentials {
+                    transport: "ssh",
+                    source: source.map(String::from),
+                }),
+            };
+            let check = |env: Environment| {
+                DoctorService::new(&git, &state, &stack)
+                    .with_environment(env)
+                    .check_environment()
+            };
+
+            assert!(check(env(Some("2.43.0"), Some("ssh-agent"))).is_clean());
+            assert!(
+                DoctorService::new(&git, &state, &stack)
+                    .check_environment()
+                    .is_clean()
+            );
+
+            let result = check(env(None, Some("ssh-agent")));
+            assert!(result.has_errors());
+            assert!(result.issues[0].message.contains("not found on PATH"));
+
+            let result = check(env(Some("2.30.0"), None));
+            assert_eq!(result.issues.len(), 2);
+            assert!(result.issues[0].message.contains("older than 2.38"));
+            assert!(result.issues[1].message.contains("No SSH agent or key"));
+        }
+
         #[test]
         fn test_check_sync_state_clean() {
             let oid = Oid::zero();
@@ -4,10 +4,16 @@
 //! which is the inverse of the split command.
 
 use anyhow::{Context, Result, bail};
-use rung_core::{FoldState, StackBranch, StateStore};
+use rung_core::{FoldState, Stack, StackBranch, StateStore};
 use rung_git::{Oid, Repository};
 use serde::Serialize;
 
+use crate::services::split::CommitInfo;
+
+/// What `rung fold <branches>` accepts, appended to validation errors.
+const CHAIN_HINT: &str = "Fold takes a parent-child chain: every branch after the first must be \
+     the direct child of another listed branch (e.g. `rung fold a b c` for a → b → c).";
+
 /// Information about a branch that can be folded.
 #[derive(Debug, Clone, Serialize)]
 pub struct FoldBranchInfo {
@@ -54,6 +60,19 @@ pub struct FoldResult {
     pub prs_to_close: Vec<u64>,
 }
 
+/// What a fold would do, computed for `--dry-run`.
+#[derive(Debug, Clone, Serialize)]
+pub struct FoldPreview {
+    /// Number of commits the combined branch would contain.
+    pub total_commits: usize,
+    /// PRs of folded branches that would be closed.
+    pub prs_to_close: Vec<u64>,
+    /// Children of the last folded branch that would move onto the target.
+    pub children_to_reparent: Vec<String>,
+    /// Commits from the folded branches that would move into the target (oldest first).
+    pub commits_to_move: Vec<CommitInfo>,
+}
+
 /// Service for fold operations.
 pub struct FoldService<'a> {
     repo: &'a Repository,
@@ -129,6 +148,110 @@ impl<'a> FoldService<'a> {
         Ok(commits.len())
     }
 
+    /// Compute what a fold would do without changing anything.
+    pub fn preview<S: StateStore>(&self, state: &S, config: &FoldConfig) -> Result<FoldPreview> {
+        let stack = state.load_stack()?;
+
+        let Some(last_branch) = config.branches_to_fold.last() else {
+            bail!("No branches specified to fold");
+        };
+
+        let parent_oid = self.repo.branch_commit(&config.new_parent)?;
+        let target_oid = self.repo.branch_commit(&config.target_branch)?;
+        let final_commit = self.repo.branch_commit(last_branch)?;
+        let total_commits = self.repo.commits_between(parent_oid, final_commit)?.len();
+        let commits_to_move = self
+            .repo
+            .commits_between(target_oid, final_commit)?
+            .into_iter()
+            .rev()
+            .map(|oid| CommitInfo::load(self.repo, oid))
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(FoldPreview {
+            total_commits,
+            prs_to_close: config
+                .branches_to_fold
+                .iter()
+                .filter_map(|name| stack.find_branch(name).and_then(|b| b.pr))
+                .collect(),
+            children_to_reparent: stack
+                .children_of(last_branch)
+                .iter()
+                .map(|b| b.name.to_string())
+                .collect(),
+            commits_to_move,
+        })
+    }
+
+    /// Order the given branches into the parent-child chain they form, root first.
+    ///
+    /// # Errors
+    /// Returns an error explaining the problem if the branches aren't all in
+    /// the stack, don't form a single unbranched chain, or folding them would
+    /// leave another branch without its parent.
+    pub fn order_chain(stack: &Stack, branches: &[&str]) -> Result<Vec<String>> {
+        if branches.len() < 2 {
+            bail!("At least two branches must be specified for folding");
+        }
+
+        let listed = |name: &str| branches.contains(&name);
+        let mut roots = Vec::new();
+        for &branch in branches {
+            let stack_branch = stack
+                .find_branch(branch)
+                .ok_or_else(|| anyhow::anyhow!("Branch '{branch}' not found in stack"))?;
+            if !stack_branch.parent.as_deref().is_some_and(listed) {
+                roots.push(branch);
+            }
+        }
+        if roots.len() > 1 {
+            bail!(
+                "Can't fold non-adjacent branches: {} aren't connected to each other.\n{CHAIN_HINT}",
+                roots
+                    .iter()
+                    .map(|b| format!("'{b}'"))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            );
+        }
+        let root = roots
+            .first()
+            .ok_or_else(|| anyhow::anyhow!("Could not determine root of branch chain"))?;
+
+        let mut chain = vec![root.to_string()];
+        loop {
+            let current = &chain[chain.len() - 1];
+            let children = stack.children_of(current);
+            let (in_list, others): (Vec<&StackBranch>, Vec<&StackBranch>) =
+                children.iter().partition(|c| listed(&c.name));
+
+            match in_list.as_slice() {
+                [] => break,
+                [child] => {
+                    // Intermediate branches are deleted, so any other children
+                    // would lose their parent
+                    if chain.len() > 1 && !others.is_empty() {
+                        bail!(
+                            "Folding would remove '{current}', which '{}' also builds on - \
+                             include it or stop the fold at '{current}'.\n{CHAIN_HINT}",
+                            others[0].name
+                        );
+                    }
+                    chain.push(child.name.to_string());
+                }
+                [first, second, ..] => bail!(
+                    "Can't fold both '{}' and '{}': they are siblings on '{current}', \
+                     not a chain.\n{CHAIN_HINT}",
+                    first.name,
+                    second.name
+                ),
+            }
+        }
+
+        Ok(chain)
+    }
+
     /// Execute a fold operation.
     ///
     /// This combines multiple adjacent branches into one by:
@@ -380,6 +503,59 @@ mod tests {
         assert_eq!(config.branches_to_fold.len(), 1);
     }
 
+    #[allow(clippy::unwrap_used)]
+    fn chain_stack() -> Stack {
+        // main → a → b → c, plus a → side
+        let mut stack = Stack::default();
+        for (name, parent) in [("a", "main"), ("b", "a"), ("c", "b"), ("side", "a")] {
+            stack.add_branch(StackBranch::try_new(name, Some(parent)).unwrap());
+        }
+        stack
+    }
+
+    #[test]
+    #[allow(clippy::unwrap_used)]
+    fn test_order_chain() {
+        let stack = chain_stack();
+        assert_eq!(
+            FoldService::order_chain(&stack, &["c", "a", "b"]).unwrap(),
+            vec!["a", "b", "c"]
+        );
+        assert_eq!(
+            FoldService::order_chain(&stack, &["b", "c"]).unwrap(),
+            vec!["b", "c"]
+        );
+    }
+
+    #[test]
+    #[allow(clippy::unwrap_used)]
+    fn test_order_chain_rejects_invalid_selections() {
+        let stack = chain_stack();
+        let err = |branches: &[&str]| {
+            FoldService::order_chain(&stack, branches)
+                .unwrap_err()
+                .to_string()
+        };
+
+        assert!(err(&["a", "c"]).contains("non-adjacent branches: 'a', 'c'"));
+        assert!(err(&["a", "b", "side"]).contains("siblings on 'a'"));
+        assert!(err(&["main", "a"]).contains("'main' not found"));
+        assert!(err(&["a"]).contains("At least two"));
+    }
+
+    #[test]
+    #[allow(clippy::unwrap_used)]
+    fn test_order_chain_rejects_orphaning_children() {
+        let mut stack = chain_stack();
+        stack.add_branch(StackBranch::try_new("d", Some("b")).unwrap());
+
+        // 'b' would be deleted while 'd' still builds on it
+        let err = FoldService::order_chain(&stack, &["a", "b", "c"]).unwrap_err();
+        assert!(err.to_string().contains("which 'd' also builds on"));
+        // Children of the last folded branch just move onto the target
+        assert!(FoldService::order_chain(&stack, &["a", "b"]).is_ok());
+    }
+
     #[test]
     fn test_fold_result() {
         let result = FoldResult {
@@ -5,7 +5,7 @@
 
 use anyhow::{Result, bail};
 use rung_core::{Stack, State};
-use rung_git::Repository;
+use rung_git::{DiffFormat, Repository};
 use serde::Serialize;
 
 /// Information about a single commit.
@@ -14,6 +14,24 @@ pub struct CommitInfo {
     pub hash: String,
     pub message: String,
     pub author: String,
+    /// The commit's patch or diffstat, when asked for.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub diff: Option<String>,
+}
+
+/// The branch's pull request, with its last known state and CI result.
+#[derive(Debug, Clone, Serialize)]
+pub struct LogPrInfo {
+    pub number: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    /// `open`, `draft`, `merged`, or `closed`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub state: Option<String>,
+    /// Combined CI result for the branch tip: `success`, `failure`,
+    /// `pending`, or `none`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub checks: Option<String>,
 }
 
 /// Complete log output for a branch.
@@ -22,6 +40,8 @@ pub struct LogResult {
     pub commits: Vec<CommitInfo>,
     pub branch: String,
     pub parent: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pr: Option<LogPrInfo>,
 }
 
 /// Service for retrieving commit logs.
@@ -36,18 +56,14 @@ impl<'a> LogService<'a> {
         Self { repo, state }
     }
 
-    /// Get the current branch name.
-    pub fn current_branch(&self) -> Result<String> {
-        Ok(self.repo.current_branch()?)
-    }
-
     /// Load the stack.
     pub fn load_stack(&self) -> Result<Stack> {
         Ok(self.state.load_stack()?)
     }
 
-    /// Get commits between the current branch and its parent.
-    pub fn get_branch_log(&self, branch_name: &str) -> Result<LogResult> {
+    /// Get commits between the current branch and its parent, with each
+    /// commit's diff rendered in `diff` format if given.
+    pub fn get_branch_log(&self, branch_name: &str, diff: Option<DiffFormat>) -> Result<LogResult> {
         let stack = self.state.load_stack()?;
 
         let Some(head) = stack.find_branch(branch_name) else {
@@ -71,11 +87,15 @@ impl<'a> LogService<'a> {
                 let message = commit.message().unwrap_or("").trim().to_owned();
                 let sig = commit.author();
                 let author = sig.name().unwrap_or("unknown").to_owned();
+                let diff = diff
+                    .map(|format| self.repo.commit_diff(oid, format))
+                    .transpose()?;
 
                 Ok(CommitInfo {
                     hash,
                     message,
                     author,
+                    diff,
                 })
             })
             .collect();
@@ -84,8 +104,36 @@ impl<'a> LogService<'a> {
             commits: commits_info?,
             branch: branch_name.to_string(),
             parent: parent.to_string(),
+            pr: head.pr.map(|number| LogPrInfo {
+                number,
+                url: None,
+                state: None,
+                checks: None,
+            }),
         })
     }
+
+    /// Fill in the PR's state and CI result from the PR cache.
+    ///
+    /// A cached CI result only counts if it was fetched for the commit the
+    /// branch is at now.
+    pub fn annotate_pr(&self, log: &mut LogResult) -> Result<()> {
+        let Some(pr) = log.pr.as_mut() else {
+            return Ok(());
+        };
+        let Some(cache) = self.state.load_pr_cache()? else {
+            return Ok(());
+        };
+        let tip = self.repo.branch_commit(&log.branch)?.to_string();
+
+        pr.state = cache.prs.get(&pr.number).cloned();
+        pr.checks = cache
+            .checks
+            .get(&pr.number)
+            .filter(|checks| checks.sha == tip)
+            .map(|checks| checks.conclusion.clone());
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -99,6 +147,7 @@ mod tests {
             hash: "abc1234".to_string(),
             message: "Test commit".to_string(),
             author: "Test Author".to_string(),
+            diff: None,
         };
         let json = serde_json::to_string(&info).expect("serialization should succeed");
         assert!(json.contains("abc1234"));
@@ -114,15 +163,18 @@ mod tests {
                     hash: "abc1234".to_string(),
                     message: "First commit".to_string(),
                     author: "Alice".to_string(),
+                    diff: None,
                 },
                 CommitInfo {
                     hash: "def5678".to_string(),
                     message: "Second commit".to_string(),
                     author: "Bob".to_string(),
+                    diff: Some("+line".to_string()),
                 },
             ],
             branch: "feature/test".to_string(),
             parent: "main".to_string(),
+            pr: None,
         };
 
         let json = serde_json::to_string(&result).expect("serialization should succeed");
@@ -130,6 +182,8 @@ mod tests {
         assert!(json.contains("main"));
         assert!(json.contains("First commit"));
         assert!(json.contains("Second commit"));
+        // Only commits with a rendered diff carry the field
+        assert_eq!(json.matches("\"diff\"").count(), 1);
     }
 
     #[test]
@@ -138,6 +192,7 @@ mod tests {
             commits: vec![],
             branch: "empty-branch".to_string(),
             parent: "main".to_string(),
+            pr: None,
         };
 
         assert!(result.commits.is_empty());
@@ -150,6 +205,7 @@ mod tests {
             hash: "abc1234".to_string(),
             message: "Test".to_string(),
             author: "Author".to_string(),
+            diff: None,
         };
         let cloned = info.clone();
         assert_eq!(info.hash, cloned.hash);
@@ -22,6 +22,58 @@ pub struct DescendantResult {
     pub error: Option<String>,
 }
 
+/// What a merge will do, computed from local state before touching the forge.
+#[derive(Debug, Clone)]
+pub struct MergePlan {
+    pub branch: String,
+    pub pr_number: u64,
+    pub merge_method: MergeMethod,
+    /// Branch the PR merges into.
+    pub merge_into: String,
+    /// PRs of direct children, retargeted to `merge_into` before merging.
+    pub retarget_prs: Vec<u64>,
+    /// Descendant branches rebased and pushed after the merge.
+    pub rebase: Vec<String>,
+    /// Remote branch deleted after the merge (`None` with `--no-delete`).
+    pub delete_remote: Option<String>,
+    /// Local branch deleted after the merge (`None` when it's kept).
+    pub delete_local: Option<String>,
+    /// Base branch fast-forwarded without a checkout, when it isn't `merge_into`.
+    pub fast_forward: Option<String>,
+}
+
+impl MergePlan {
+    /// Plan merging `branch`'s PR into `merge_into`.
+    #[must_use]
+    #[allow(clippy::too_many_arguments, clippy::fn_params_excessive_bools)]
+    pub fn new(
+        stack: &Stack,
+        branch: &str,
+        pr_number: u64,
+        merge_into: &str,
+        base_branch: &str,
+        merge_method: MergeMethod,
+        delete_remote: bool,
+        delete_local: bool,
+    ) -> Self {
+        Self {
+            branch: branch.to_string(),
+            pr_number,
+            merge_method,
+            merge_into: merge_into.to_string(),
+            retarget_prs: stack
+                .children_of(branch)
+                .iter()
+                .filter_map(|b| b.pr)
+                .collect(),
+            rebase: collect_descendants(stack, branch),
+            delete_remote: delete_remote.then(|| branch.to_string()),
+            delete_local: delete_local.then(|| branch.to_string()),
+            fast_forward: (merge_into != base_branch).then(|| base_branch.to_string()),
+        }
+    }
+}
+
 /// Service for merge operations with trait-based dependencies.
 pub struct MergeService<'a, G: GitOps, H: ForgeApi> {
     repo: &'a G,
@@ -82,6 +134,10 @@ impl<'a, G: GitOps, H: ForgeApi> MergeService<'a, G, H> {
 
     /// Shift child PR bases to parent before merge.
     ///
+    /// Children without a recorded PR number are looked up on the forge, so
+    /// a PR opened outside `rung submit` is retargeted too rather than left
+    /// based on the deleted branch.
+    ///
     /// Returns the list of PRs that were shifted (for potential rollback).
     /// If an update fails mid-loop, attempts best-effort rollback of already-shifted PRs.
     pub async fn shift_child_pr_bases(
@@ -104,9 +160,14 @@ impl<'a, G: GitOps, H: ForgeApi> MergeService<'a, G, H> {
                 .map_or(parent_branch, |p| p.as_str());
 
             // Only shift direct children of the merging branch
-            if stack_parent == current_branch
-                && let Some(child_pr_num) = branch_info.pr
-            {
+            if stack_parent != current_branch {
+                continue;
+            }
+            let child_pr = match branch_info.pr {
+                Some(number) => Some(number),
+                None => self.find_child_pr(branch_name, current_branch).await,
+            };
+            if let Some(child_pr_num) = child_pr {
                 let update = UpdatePullRequest {
                     title: None,
                     body: None,
@@ -133,6 +194,19 @@ impl<'a, G: GitOps, H: ForgeApi> MergeService<'a, G, H> {
         Ok(shifted_prs)
     }
 
+    /// Find an open PR for a branch that is based on `base`.
+    ///
+    /// Lookup failures are treated as no PR; the next sync retargets it.
+    async fn find_child_pr(&self, branch: &str, base: &str) -> Option<u64> {
+        self.client
+            .find_pr_for_branch(&self.repo_id, branch)
+            .await
+            .ok()
+            .flatten()
+            .filter(|pr| pr.base_branch == base)
+            .map(|pr| pr.number)
+    }
+
     /// Rollback PR base changes after a failed merge.
     ///
     /// Returns a list of `(pr_number, error_message)` tuples for PRs that failed to rollback.
@@ -155,20 +229,54 @@ impl<'a, G: GitOps, H: ForgeApi> MergeService<'a, G, H> {
         failures
     }
 
-    /// Merge a PR on GitHub.
-    pub async fn merge_pr(&self, pr_number: u64, merge_method: MergeMethod) -> Result<()> {
+    /// Fetch the merge methods the repository allows.
+    pub async fn allowed_merge_methods(&self) -> Result<Vec<MergeMethod>> {
+        self.client
+            .get_merge_methods(&self.repo_id)
+            .await
+            .context("Failed to read repository merge settings")
+    }
+
+    /// Pick the merge method, checked against the repository's allowed methods.
+    ///
+    /// Without a requested method, the first allowed method is used.
+    /// Requesting a method the repository disables is an error listing the
+    /// allowed ones.
+    pub fn choose_merge_method(
+        &self,
+        requested: Option<MergeMethod>,
+        allowed: &[MergeMethod],
+    ) -> Result<MergeMethod> {
+        match requested {
+            Some(method) if allowed.is_empty() || allowed.contains(&method) => Ok(method),
+            Some(method) => {
+                let names: Vec<&str> = allowed.iter().map(|m| m.as_str()).collect();
+                bail!(
+                    "Merge method '{}' is not allowed in {} - allowed: {}",
+                    method.as_str(),
+                    self.repo_id,
+                    names.join(", ")
+                )
+            }
+            None => Ok(allowed.first().copied().unwrap_or_default()),
+        }
+    }
+
+    /// Merge a PR on GitHub, returning the SHA of the resulting commit.
+    pub async fn merge_pr(&self, pr_number: u64, merge_method: MergeMethod) -> Result<String> {
         let merge_request = MergePullRequest {
             commit_title: None,
             commit_message: None,
             merge_method,
         };
 
-        self.client
+        let result = self
+            .client
             .merge_pr(&self.repo_id, pr_number, merge_request)
             .await
             .context("Failed to merge PR")?;
 
-        Ok(())
+        Ok(result.sha)
     }
 
     /// Update the stack after a successful merge.
@@ -354,24 +462,26 @@ impl<'a, G: GitOps, H: ForgeApi> MergeService<'a, G, H> {
     /// Collect all descendants of a branch in topological order (BFS).
     #[must_use]
     pub fn collect_descendants(stack: &Stack, root: &str) -> Vec<String> {
-        let mut descendants = Vec::new();
-        let mut queue = VecDeque::from([root.to_string()]);
-        let mut visited = HashSet::new();
-        visited.insert(root.to_string());
-
-        while let Some(parent) = queue.pop_front() {
-            for branch in &stack.branches {
-                if branch.parent.as_ref().is_some_and(|p| p == &parent)
-                    && !visited.contains(branch.name.as_str())
-                {
-                    visited.insert(branch.name.to_string());
-                    descendants.push(branch.name.to_string());
-                    queue.push_back(branch.name.to_string());
-                }
+        collect_descendants(stack, root)
+    }
+}
+
+/// Collect all descendants of a branch in topological order (BFS).
+fn collect_descendants(stack: &Stack, root: &str) -> Vec<String> {
+    let index = stack.index();
+    let mut descendants = Vec::new();
+    let mut queue = VecDeque::from([root]);
+    let mut visited = HashSet::from([root]);
+
+    while let Some(parent) = queue.pop_front() {
+        for branch in index.children_of(parent) {
+            if visited.insert(branch.name.as_str()) {
+                descendants.push(branch.name.to_string());
+                queue.push_back(branch.name.as_str());
             }
         }
-        descendants
     }
+    descendants
 }
 
 #[cfg(test)]
@@ -379,6 +489,54 @@ mod tests {
     use super::*;
     use rung_core::stack::StackBranch;
 
+    #[test]
+    #[allow(clippy::expect_used)]
+    fn test_merge_plan() {
+        let mut stack = Stack::default();
+        let mut a = StackBranch::try_new("a", None::<&str>).expect("valid");
+        a.pr = Some(1);
+        stack.add_branch(a);
+        let mut b = StackBranch::try_new("b", Some("a")).expect("valid");
+        b.pr = Some(2);
+        stack.add_branch(b);
+        stack.add_branch(StackBranch::try_new("c", Some("b")).expect("valid"));
+        stack.add_branch(StackBranch::try_new("d", Some("a")).expect("valid"));
+
+        let plan = MergePlan::new(
+            &stack,
+            "a",
+            1,
+            "main",
+            "main",
+            MergeMethod::Rebase,
+            true,
+            true,
+        );
+        assert_eq!(plan.retarget_prs, vec![2]);
+        assert_eq!(plan.rebase, vec!["b", "d", "c"]);
+        assert_eq!(plan.delete_remote.as_deref(), Some("a"));
+        assert_eq!(plan.delete_local.as_deref(), Some("a"));
+        assert_eq!(plan.fast_forward, None);
+        assert_eq!(plan.merge_method, MergeMethod::Rebase);
+
+        // Merging mid-stack with --no-delete --keep-local
+        let plan = MergePlan::new(
+            &stack,
+            "b",
+            2,
+            "a",
+            "main",
+            MergeMethod::Squash,
+            false,
+            false,
+        );
+        assert!(plan.retarget_prs.is_empty());
+        assert_eq!(plan.rebase, vec!["c"]);
+        assert_eq!(plan.delete_remote, None);
+        assert_eq!(plan.delete_local, None);
+        assert_eq!(plan.fast_forward.as_deref(), Some("main"));
+    }
+
     #[test]
     fn test_collect_descendants_empty() {
         let stack = Stack::default();
@@ -687,14 +845,18 @@ mod tests {
     #[allow(clippy::manual_async_fn, clippy::unwrap_used, clippy::expect_used)]
     mod mock_tests {
         use super::*;
-        use crate::services::test_mocks::{MockGitOps, MockStateStore};
         use rung_core::stack::StackBranch;
+        use rung_core::testing::MockStateStore;
         use rung_git::Oid;
+        use rung_git::testing::MockGitOps;
         use std::sync::atomic::{AtomicBool, Ordering};
 
         // Mock ForgeApi for merge testing
         struct MockGitHubClient {
             pr_mergeable: Option<bool>,
+            /// Open PRs by head branch: (number, base branch).
+            branch_prs: HashMap<String, (u64, String)>,
+            merge_methods: Vec<MergeMethod>,
             merge_should_fail: bool,
             delete_should_fail: bool,
             update_pr_should_fail: bool,
@@ -705,6 +867,12 @@ mod tests {
             fn new() -> Self {
                 Self {
                     pr_mergeable: Some(true),
+                    branch_prs: HashMap::new(),
+                    merge_methods: vec![
+                        MergeMethod::Squash,
+                        MergeMethod::Merge,
+                        MergeMethod::Rebase,
+                    ],
                     merge_should_fail: false,
                     delete_should_fail: false,
                     update_pr_should_fail: false,
@@ -722,6 +890,17 @@ mod tests {
                 self
             }
 
+            fn with_branch_pr(mut self, branch: &str, number: u64, base: &str) -> Self {
+                self.branch_prs
+                    .insert(branch.to_string(), (number, base.to_string()));
+                self
+            }
+
+            fn with_merge_methods(mut self, methods: &[MergeMethod]) -> Self {
+                self.merge_methods = methods.to_vec();
+                self
+            }
+
             fn with_merge_failure(mut self) -> Self {
                 self.merge_should_fail = true;
                 self
@@ -762,6 +941,8 @@ mod tests {
                             None => "unknown".to_string(),
                         }),
                         draft: false,
+                        created_at: None,
+                        last_reviewed_at: None,
                     })
                 }
             }
@@ -781,11 +962,38 @@ mod tests {
             fn find_pr_for_branch(
                 &self,
                 _repo: &rung_github::RepoId,
-                _branch: &str,
+                branch: &str,
             ) -> impl std::future::Future<
                 Output = rung_github::Result<Option<rung_github::PullRequest>>,
             > + Send {
-                async { Ok(None) }
+                let pr =
+                    self.branch_prs
+                        .get(branch)
+                        .map(|(number, base)| rung_github::PullRequest {
+                            number: *number,
+                            title: "Child PR".to_string(),
+                            body: None,
+                            state: rung_github::PullRequestState::Open,
+                            draft: false,
+                            head_branch: branch.to_string(),
+                            base_branch: base.clone(),
+                            html_url: format!("https://github.com/test/repo/pull/{number}"),
+                            mergeable: None,
+                            mergeable_state: None,
+                            created_at: None,
+                            last_reviewed_at: None,
+                        });
+                async move { Ok(pr) }
+            }
+
+            fn find_open_prs_for_branch(
+                &self,
+                _repo: &rung_github::RepoId,
+                _branch: &str,
+            ) -> impl std::future::Future<
+                Output = rung_github::Result<Vec<rung_github::PullRequest>>,
+            > + Send {
+                async { Ok(vec![]) }
             }
 
             fn create_pr(
@@ -823,6 +1031,8 @@ mod tests {
                         html_url: format!("https://github.com/test/repo/pull/{number}"),
                         mergeable: None,
                         mergeable_state: None,
+                        created_at: None,
+                        last_reviewed_at: None,
                         draft: false,
                     })
                 }
@@ -837,6 +1047,15 @@ mod tests {
                 async { Ok(vec![]) }
             }
 
+            fn set_commit_status(
+                &self,
+                _repo: &rung_github::RepoId,
+                _commit_sha: &str,
+                _status: rung_github::CommitStatus,
+            ) -> impl std::future::Future<Output = rung_github::Result<()>> + Send {
+                async { Ok(()) }
+            }
+
             fn merge_pr(
                 &self,
                 _repo: &rung_github::RepoId,
@@ -886,6 +1105,16 @@ mod tests {
                 async { Ok("main".to_string()) }
             }
 
+            fn get_merge_methods(
+                &self,
+                _repo: &rung_github::RepoId,
+            ) -> impl std::future::Future<
+                Output = rung_github::Result<Vec<rung_github::MergeMethod>>,
+            > + Send {
+                let methods = self.merge_methods.clone();
+                async move { Ok(methods) }
+            }
+
             fn list_pr_comments(
                 &self,
                 _repo: &rung_github::RepoId,
@@ -984,6 +1213,39 @@ mod tests {
             assert!(err.contains("State: unknown"));
         }
 
+        #[tokio::test]
+        async fn test_choose_merge_method_defaults_to_first_allowed() {
+            let git = MockGitOps::new();
+            let github = MockGitHubClient::new()
+                .with_merge_methods(&[MergeMethod::Merge, MergeMethod::Rebase]);
+            let service = MergeService::new(&git, &github, RepoId::new("owner/repo"));
+
+            let allowed = service.allowed_merge_methods().await.unwrap();
+            let method = service.choose_merge_method(None, &allowed).unwrap();
+            assert_eq!(method, MergeMethod::Merge);
+
+            let method = service
+                .choose_merge_method(Some(MergeMethod::Rebase), &allowed)
+                .unwrap();
+            assert_eq!(method, MergeMethod::Rebase);
+        }
+
+        #[tokio::test]
+        async fn test_choose_merge_method_rejects_disabled_method() {
+            let git = MockGitOps::new();
+            let github = MockGitHubClient::new().with_merge_methods(&[MergeMethod::Squash]);
+            let service = MergeService::new(&git, &github, RepoId::new("owner/repo"));
+
+            let allowed = service.allowed_merge_methods().await.unwrap();
+            let err = service
+                .choose_merge_method(Some(MergeMethod::Rebase), &allowed)
+                .unwrap_err();
+            assert_eq!(
+                err.to_string(),
+                "Merge method 'rebase' is not allowed in owner/repo - allowed: squash"
+            );
+        }
+
         #[tokio::test]
         async fn test_merge_pr_success() {
             let oid = Oid::zero();
@@ -1125,6 +1387,39 @@ mod tests {
             assert_eq!(shifted[0].1, "feature/parent"); // Original base
         }
 
+        #[tokio::test]
+        #[allow(clippy::expect_used)]
+        async fn test_shift_child_pr_bases_finds_unrecorded_prs() {
+            let git = MockGitOps::new();
+            // feature/child has no PR in stack.json, feature/other's PR is based elsewhere
+            let github = MockGitHubClient::new()
+                .with_branch_pr("feature/child", 21, "feature/parent")
+                .with_branch_pr("feature/other", 31, "main");
+
+            let mut stack = Stack::default();
+            let mut parent_branch = StackBranch::try_new("feature/parent", None::<&str>).unwrap();
+            parent_branch.pr = Some(10);
+            stack.add_branch(parent_branch);
+            for child in ["feature/child", "feature/other", "feature/no-pr"] {
+                stack.add_branch(StackBranch::try_new(child, Some("feature/parent")).unwrap());
+            }
+
+            let service = MergeService::new(&git, &github, RepoId::new("owner/repo"));
+
+            let descendants = vec![
+                "feature/child".to_string(),
+                "feature/other".to_string(),
+                "feature/no-pr".to_string(),
+            ];
+            let shifted = service
+                .shift_child_pr_bases(&stack, "feature/parent", "main", &descendants)
+                .await
+                .expect("shift should succeed");
+
+            assert_eq!(shifted, vec![(21, "feature/parent".to_string())]);
+            assert!(github.update_pr_called.load(Ordering::SeqCst));
+        }
+
         #[tokio::test]
         async fn test_rollback_pr_bases() {
             let oid = Oid::zero();
@@ -5,32 +5,37 @@
 
 pub mod absorb;
 pub mod adopt;
+pub mod ci;
 pub mod create;
 pub mod doctor;
 pub mod fold;
 pub mod log;
 pub mod merge;
+pub mod reorder;
 pub mod restack;
 pub mod split;
 pub mod status;
 pub mod submit;
 pub mod sync;
 
-#[cfg(test)]
-pub mod test_mocks;
-
 pub use absorb::AbsorbService;
 pub use adopt::AdoptService;
+pub use ci::CiService;
 pub use create::CreateService;
-pub use doctor::{CheckResult, DiagnosticReport, DoctorService, Issue, Severity};
+pub use doctor::{
+    CheckResult, DiagnosticReport, DoctorService, Environment, Issue, OriginCredentials, Severity,
+    orphaned_remotes_issue, token_issue,
+};
 #[allow(unused_imports)] // Re-exported for public API consistency
-pub use fold::{FoldAnalysis, FoldBranchInfo, FoldConfig, FoldResult, FoldService};
-pub use log::{CommitInfo, LogResult, LogService};
-pub use merge::MergeService;
+pub use fold::{FoldAnalysis, FoldBranchInfo, FoldConfig, FoldPreview, FoldResult, FoldService};
+pub use log::{CommitInfo, LogPrInfo, LogResult, LogService};
+pub use merge::{MergePlan, MergeService};
+pub use reorder::{ReorderPlan, ReorderService};
 pub use restack::{DivergenceInfo, RestackConfig, RestackError, RestackService};
 pub use split::SplitService;
-pub use status::{BranchStatusInfo, RemoteDivergenceInfo, StatusService};
+pub use status::{BranchStatusInfo, RemoteDivergenceInfo, StatusFilter, StatusService};
 pub use submit::{
     BranchSubmitResult, PlannedBranchAction, SubmitAction, SubmitConfig, SubmitPlan, SubmitService,
+    create_offline_plan,
 };
 pub use sync::SyncService;
@@ -0,0 +1,241 @@
+//! Reorder service for swapping a branch with its parent in a linear chain.
+//!
+//! Backs `rung move --reorder`: the branch's own commits are replayed onto
+//! its grandparent, then the parent's commits are replayed on top of it.
+
+use anyhow::{Result, bail};
+use rung_core::{Stack, StateStore};
+use rung_git::{Oid, Repository};
+use serde::Serialize;
+
+/// A planned swap of a branch with its parent.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ReorderPlan {
+    /// Branch moving one position toward trunk.
+    pub branch: String,
+    /// Its current parent, which moves on top of it.
+    pub parent: String,
+    /// The parent's parent, which becomes the branch's parent.
+    pub grandparent: String,
+    /// Children of `branch` that move onto `parent`, keeping their position.
+    pub children: Vec<String>,
+}
+
+impl ReorderPlan {
+    /// Plan moving `branch` below its parent.
+    ///
+    /// # Errors
+    /// Returns an error if the branch has no parent in the stack to swap with,
+    /// or the parent has other children (the swap only works within a linear
+    /// chain).
+    pub fn new(stack: &Stack, branch: &str, default_branch: &str) -> Result<Self> {
+        let Some(stack_branch) = stack.find_branch(branch) else {
+            bail!("Branch '{branch}' not found in stack");
+        };
+        let Some(parent) = stack_branch
+            .parent
+            .as_deref()
+            .and_then(|p| stack.find_branch(p))
+        else {
+            bail!("'{branch}' is already at the bottom of its stack");
+        };
+
+        let siblings: Vec<&str> = stack
+            .children_of(&parent.name)
+            .iter()
+            .map(|c| c.name.as_str())
+            .filter(|name| *name != branch)
+            .collect();
+        if !siblings.is_empty() {
+            bail!(
+                "Can't reorder '{branch}' and '{}': '{}' also has {} stacked on it. \
+                 Reordering only works within a linear chain.",
+                parent.name,
+                parent.name,
+                siblings.join(", ")
+            );
+        }
+
+        Ok(Self {
+            branch: branch.to_string(),
+            parent: parent.name.to_string(),
+            grandparent: parent
+                .parent
+                .as_ref()
+                .map_or_else(|| default_branch.to_string(), ToString::to_string),
+            children: stack
+                .children_of(branch)
+                .iter()
+                .map(|c| c.name.to_string())
+                .collect(),
+        })
+    }
+
+    /// Plan moving `branch` above its only child (the child moves below it).
+    ///
+    /// # Errors
+    /// Returns an error if the branch doesn't have exactly one child.
+    pub fn down(stack: &Stack, branch: &str, default_branch: &str) -> Result<Self> {
+        let children = stack.children_of(branch);
+        match children.as_slice() {
+            [child] => Self::new(stack, &child.name, default_branch),
+            [] => bail!("'{branch}' is already at the top of its stack"),
+            _ => bail!(
+                "Can't move '{branch}' down: it has several children ({}). \
+                 Reordering only works within a linear chain.",
+                children
+                    .iter()
+                    .map(|c| c.name.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        }
+    }
+}
+
+/// Service for reorder operations.
+pub struct ReorderService<'a> {
+    repo: &'a Repository,
+}
+
+impl<'a> ReorderService<'a> {
+    /// Create a new reorder service.
+    #[must_use]
+    pub const fn new(repo: &'a Repository) -> Self {
+        Self { repo }
+    }
+
+    /// Perform the swap: two rebases, then the stack update.
+    ///
+    /// If either rebase conflicts, both branches are restored and nothing
+    /// in the stack changes.
+    ///
+    /// # Errors
+    /// Returns error if a rebase conflicts or a git operation fails.
+    pub fn execute<S: StateStore>(&self, state: &S, plan: &ReorderPlan) -> Result<()> {
+        let original_branch = self.repo.current_branch()?;
+        let grandparent_oid = self.repo.branch_commit(&plan.grandparent)?;
+        let parent_oid = self.repo.branch_commit(&plan.parent)?;
+        let branch_oid = self.repo.branch_commit(&plan.branch)?;
+
+        let backup_id = state.create_backup(&[
+            (plan.parent.as_str(), parent_oid.to_string().as_str()),
+            (plan.branch.as_str(), branch_oid.to_string().as_str()),
+        ])?;
+
+        let rebased = self
+            .rebase_branch(&plan.branch, grandparent_oid, parent_oid)
+            .and_then(|new_branch_oid| {
+                self.rebase_branch(&plan.parent, new_branch_oid, grandparent_oid)
+                    .map(|_| new_branch_oid)
+            });
+        let new_branch_oid = match rebased {
+            Ok(oid) => oid,
+            Err(e) => {
+                self.repo.reset_branch(&plan.branch, branch_oid)?;
+                self.repo.reset_branch(&plan.parent, parent_oid)?;
+                self.repo.checkout(&original_branch)?;
+                let _ = state.delete_backup(&backup_id);
+                return Err(e);
+            }
+        };
+
+        let mut stack = state.load_stack()?;
+        stack.reparent(&plan.branch, Some(&plan.grandparent))?;
+        stack.reparent(&plan.parent, Some(&plan.branch))?;
+        if let Some(branch) = stack.find_branch_mut(&plan.branch) {
+            branch.base = Some(grandparent_oid.to_string());
+        }
+        if let Some(parent) = stack.find_branch_mut(&plan.parent) {
+            parent.base = Some(new_branch_oid.to_string());
+        }
+        // The children still sit on the old tip; recording it as their base
+        // lets the next sync replay only their own commits
+        for child in &plan.children {
+            stack.reparent(child, Some(&plan.parent))?;
+            if let Some(child) = stack.find_branch_mut(child) {
+                child.base = Some(branch_oid.to_string());
+            }
+        }
+        // Keep the stack listed in its new order
+        let position = |name: &str| stack.branches.iter().position(|b| b.name == name);
+        if let (Some(a), Some(b)) = (position(&plan.parent), position(&plan.branch)) {
+            stack.branches.swap(a, b);
+        }
+        state.save_stack(&stack)?;
+
+        self.repo.checkout(&original_branch)?;
+        let _ = state.delete_backup(&backup_id);
+        Ok(())
+    }
+
+    /// Replay the commits of `branch` after `old_base` onto `new_base`.
+    fn rebase_branch(&self, branch: &str, new_base: Oid, old_base: Oid) -> Result<Oid> {
+        self.repo.checkout(branch)?;
+        if let Err(e) = self.repo.rebase_onto_from(new_base, old_base) {
+            if self.repo.is_rebasing() {
+                let _ = self.repo.rebase_abort();
+            }
+            if let rung_git::Error::RebaseConflict(files) = &e {
+                bail!(
+                    "Reordering conflicts in {} while rebasing '{branch}' - \
+                     the branches touch the same lines. Nothing was changed.",
+                    files.join(", ")
+                );
+            }
+            return Err(e.into());
+        }
+        Ok(self.repo.branch_commit(branch)?)
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use rung_core::StackBranch;
+
+    fn stack(branches: &[(&str, &str)]) -> Stack {
+        let mut stack = Stack::default();
+        for (name, parent) in branches {
+            stack.add_branch(StackBranch::try_new(*name, Some(*parent)).unwrap());
+        }
+        stack
+    }
+
+    #[test]
+    fn test_reorder_plan() {
+        let stack = stack(&[("a", "main"), ("b", "a"), ("c", "b"), ("d", "c")]);
+
+        let plan = ReorderPlan::new(&stack, "c", "main").unwrap();
+        assert_eq!(plan.parent, "b");
+        assert_eq!(plan.grandparent, "a");
+        assert_eq!(plan.children, vec!["d"]);
+
+        // 'a' sits on trunk, so swapping 'b' below it lands 'b' on main
+        assert_eq!(
+            ReorderPlan::new(&stack, "b", "main").unwrap().grandparent,
+            "main"
+        );
+        assert_eq!(
+            ReorderPlan::down(&stack, "a", "main").unwrap(),
+            ReorderPlan {
+                branch: "b".to_string(),
+                parent: "a".to_string(),
+                grandparent: "main".to_string(),
+                children: vec!["c".to_string()],
+            }
+        );
+    }
+
+    #[test]
+    fn test_reorder_plan_rejects_non_linear() {
+        let stack = stack(&[("a", "main"), ("b", "a"), ("side", "a")]);
+        let err = |result: Result<ReorderPlan>| result.unwrap_err().to_string();
+
+        assert!(err(ReorderPlan::new(&stack, "b", "main")).contains("also has side"));
+        assert!(err(ReorderPlan::new(&stack, "a", "main")).contains("bottom of its stack"));
+        assert!(err(ReorderPlan::down(&stack, "a", "main")).contains("several children"));
+        assert!(err(ReorderPlan::down(&stack, "b", "main")).contains("top of its stack"));
+    }
+}
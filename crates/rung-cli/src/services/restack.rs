@@ -7,8 +7,9 @@ use std::collections::VecDeque;
 
 use anyhow::{Result, bail};
 use chrono::Utc;
+use rung_core::journal::{self, Journal};
 use rung_core::{DivergenceRecord, RestackState, StateStore};
-use rung_git::{GitOps, Oid, RemoteDivergence};
+use rung_git::{GitOps, RemoteDivergence};
 use serde::Serialize;
 use thiserror::Error;
 
@@ -89,6 +90,7 @@ pub struct RestackResult {
     pub old_parent: Option<String>,
     pub new_parent: String,
     pub branches_rebased: Vec<String>,
+    pub branches_skipped: Vec<String>,
     pub diverged_branches: Vec<DivergenceInfo>,
 }
 
@@ -175,6 +177,48 @@ impl<'a, G: GitOps> RestackService<'a, G> {
         })
     }
 
+    /// Plan restacking everything stacked on `branch` onto its current tip.
+    ///
+    /// Used after `branch` gains or rewrites commits in place. Returns one
+    /// plan per direct child that has fallen behind `branch`, each covering
+    /// that child and its descendants; the stack topology is unchanged.
+    pub fn plan_descendants<S: StateStore>(
+        &self,
+        state: &S,
+        branch: &str,
+    ) -> Result<Vec<RestackPlan>> {
+        let stack = state.load_stack()?;
+        let tip = self.repo.branch_commit(branch)?;
+
+        let mut plans = Vec::new();
+        for child in stack.children_of(branch) {
+            let child_tip = self.repo.branch_commit(&child.name)?;
+            if self.repo.merge_base(child_tip, tip)? == tip {
+                continue;
+            }
+
+            let mut branches_to_rebase = vec![child.name.to_string()];
+            branches_to_rebase.extend(
+                stack
+                    .descendants(&child.name)
+                    .iter()
+                    .map(|b| b.name.to_string()),
+            );
+            let diverged = self.check_divergence(&branches_to_rebase);
+
+            plans.push(RestackPlan {
+                target_branch: child.name.to_string(),
+                new_parent: branch.to_string(),
+                old_parent: Some(branch.to_string()),
+                branches_to_rebase,
+                needs_rebase: true,
+                diverged,
+            });
+        }
+
+        Ok(plans)
+    }
+
     /// Execute a restack plan.
     ///
     /// Returns the restack state for interruption recovery.
@@ -204,6 +248,7 @@ impl<'a, G: GitOps> RestackService<'a, G> {
                 completed: vec![plan.target_branch.clone()],
                 remaining: VecDeque::new(),
                 stack_updated: true,
+                skipped: vec![],
                 diverged_branches: diverged_records,
             });
         }
@@ -221,6 +266,10 @@ impl<'a, G: GitOps> RestackService<'a, G> {
             .map(|(name, sha)| (name.as_str(), sha.as_str()))
             .collect();
         let backup_id = state.create_backup(&backup_refs)?;
+        state.save_journal(&Journal::rollback(
+            "restack",
+            plan.branches_to_rebase.iter().cloned().zip(backup_commits),
+        ))?;
 
         // Create restack state
         let diverged_records: Vec<DivergenceRecord> =
@@ -272,26 +321,49 @@ impl<'a, G: GitOps> RestackService<'a, G> {
             self.repo.checkout(&current_branch)?;
 
             // Determine the rebase target
+            let stack_branch = stack.find_branch(&current_branch);
+            let stack_parent =
+                stack_branch.and_then(|b| b.parent.as_ref().map(ToString::to_string));
             let rebase_onto = if current_branch == restack_state.target_branch {
                 restack_state.new_parent.clone()
             } else {
-                stack
-                    .find_branch(&current_branch)
-                    .and_then(|b| b.parent.as_ref().map(ToString::to_string))
+                stack_parent
+                    .clone()
                     .unwrap_or_else(|| restack_state.target_branch.clone())
             };
 
             // Get the parent's current commit
             let parent_commit = self.repo.branch_commit(&rebase_onto)?;
 
+            // A parent rewritten in place (amended, uncommitted) no longer has
+            // the commits this branch was based on; replay only its own
+            let rewritten_base = stack_branch
+                .filter(|_| stack_parent.as_deref() == Some(rebase_onto.as_str()))
+                .and_then(|b| {
+                    let branch_commit = self.repo.branch_commit(&b.name).ok()?;
+                    rung_core::sync::rewritten_parent_base(
+                        self.repo,
+                        b,
+                        branch_commit,
+                        parent_commit,
+                    )
+                });
+            // Recovery aborts this rebase if rung dies part-way through it
+            update_journal(state, &restack_state, false)?;
+            let rebased = rewritten_base.map_or_else(
+                || self.repo.rebase_onto(parent_commit),
+                |base| self.repo.rebase_onto_from(parent_commit, base),
+            );
+
             // Rebase onto the parent
-            match self.repo.rebase_onto(parent_commit) {
+            match rebased {
                 Ok(()) => {
                     restack_state.advance();
                     state.save_restack_state(&restack_state)?;
                 }
                 Err(rung_git::Error::RebaseConflict(files)) => {
                     state.save_restack_state(&restack_state)?;
+                    update_journal(state, &restack_state, true)?;
                     return Err(RestackError::Conflict {
                         branch: current_branch,
                         files,
@@ -332,6 +404,12 @@ impl<'a, G: GitOps> RestackService<'a, G> {
             state.save_restack_state(&restack_state)?;
         }
 
+        // The branches and stack are final; nothing to roll back from here
+        state.clear_journal()?;
+
+        // Remember the bases the rebased branches now sit on
+        rung_core::sync::record_bases(self.repo, state, &restack_state.completed)?;
+
         // Only clear restack state after all updates are successfully persisted
         state.clear_restack_state()?;
 
@@ -351,6 +429,7 @@ impl<'a, G: GitOps> RestackService<'a, G> {
             old_parent: restack_state.old_parent,
             new_parent: restack_state.new_parent,
             branches_rebased: restack_state.completed,
+            branches_skipped: restack_state.skipped,
             diverged_branches: diverged_info,
         })
     }
@@ -364,11 +443,7 @@ impl<'a, G: GitOps> RestackService<'a, G> {
     ) {
         let _ = self.repo.rebase_abort();
         if let Ok(refs) = state.load_backup(&restack_state.backup_id) {
-            for (branch_name, sha) in refs {
-                if let Ok(oid) = Oid::from_str(&sha) {
-                    let _ = self.repo.reset_branch(&branch_name, oid);
-                }
-            }
+            let _ = journal::restore(self.repo, state, "restack --abort", refs);
         }
         let _ = self.repo.checkout(original_branch);
         let _ = state.clear_restack_state();
@@ -389,11 +464,7 @@ impl<'a, G: GitOps> RestackService<'a, G> {
 
         // Restore all branches from backup
         let refs = state.load_backup(&restack_state.backup_id)?;
-        for (branch_name, sha) in refs {
-            let oid = Oid::from_str(&sha)
-                .map_err(|e| anyhow::anyhow!("Invalid backup ref for {branch_name}: {e}"))?;
-            self.repo.reset_branch(&branch_name, oid)?;
-        }
+        journal::restore(self.repo, state, "restack --abort", refs)?;
 
         // Restore original branch
         let _ = self.repo.checkout(&restack_state.original_branch);
@@ -406,6 +477,7 @@ impl<'a, G: GitOps> RestackService<'a, G> {
             old_parent: restack_state.old_parent,
             new_parent: restack_state.new_parent,
             branches_rebased: vec![],
+            branches_skipped: vec![],
             diverged_branches: vec![],
         })
     }
@@ -441,16 +513,20 @@ impl<'a, G: GitOps> RestackService<'a, G> {
         let original_branch = restack_state.original_branch.clone();
 
         // Continue the in-progress rebase
+        update_journal(state, &restack_state, false)?;
         match self.repo.rebase_continue() {
             Ok(()) => {
                 restack_state.advance();
                 state.save_restack_state(&restack_state)?;
                 self.execute_restack_loop(state, &original_branch)
             }
-            Err(rung_git::Error::RebaseConflict(files)) => Err(RestackError::Conflict {
-                branch: current_branch,
-                files,
-            }),
+            Err(rung_git::Error::RebaseConflict(files)) => {
+                update_journal(state, &restack_state, true)?;
+                Err(RestackError::Conflict {
+                    branch: current_branch,
+                    files,
+                })
+            }
             Err(e) => {
                 self.restore_from_backup(state, &restack_state, &original_branch);
                 Err(RestackError::from(e))
@@ -458,6 +534,63 @@ impl<'a, G: GitOps> RestackService<'a, G> {
         }
     }
 
+    /// Handle --skip flag.
+    ///
+    /// Leaves the conflicted branch, and with `descendants` the branches
+    /// stacked on it, where they were before the restack and continues with
+    /// the rest. The branch being restacked itself can't be skipped.
+    ///
+    /// # Errors
+    ///
+    /// Returns `RestackError::Conflict` if a later rebase conflicts.
+    pub fn skip<S: StateStore>(
+        &self,
+        state: &S,
+        descendants: bool,
+    ) -> Result<RestackResult, RestackError> {
+        if !state.is_restack_in_progress() {
+            return Err(RestackError::Other(anyhow::anyhow!(
+                "No restack in progress to skip"
+            )));
+        }
+
+        let mut restack_state = state.load_restack_state()?;
+        let branch = restack_state.current_branch.clone();
+        if branch == restack_state.target_branch {
+            return Err(RestackError::Other(anyhow::anyhow!(
+                "Can't skip '{branch}', the branch being restacked - resolve the conflicts \
+                 and run `rung restack --continue`, or run `rung restack --abort`"
+            )));
+        }
+
+        // Put the branch back where it was before the restack
+        if self.repo.is_rebasing() {
+            self.repo.rebase_abort()?;
+        }
+        let original_tips = state.load_backup(&restack_state.backup_id)?;
+        if let Some(tip) = original_tips.into_iter().find(|(name, _)| *name == branch) {
+            // Already covered by the restack's own journal
+            journal::roll_back(self.repo, &Journal::rollback("restack", [tip]))?;
+        }
+
+        let also: Vec<String> = if descendants {
+            state
+                .load_stack()?
+                .descendants(&branch)
+                .iter()
+                .map(|b| b.name.to_string())
+                .collect()
+        } else {
+            vec![]
+        };
+        restack_state.skip(&also);
+        state.save_restack_state(&restack_state)?;
+        update_journal(state, &restack_state, false)?;
+
+        let original_branch = restack_state.original_branch.clone();
+        self.execute_restack_loop(state, &original_branch)
+    }
+
     /// Check if any branches have diverged from their remote tracking branches.
     fn check_divergence(&self, branches: &[String]) -> Vec<DivergenceInfo> {
         let mut diverged = Vec::new();
@@ -478,6 +611,26 @@ impl<'a, G: GitOps> RestackService<'a, G> {
     }
 }
 
+/// Record the branch the restack is rebasing in its journal, and whether
+/// it's paused for conflicts.
+///
+/// Restacks paused by an older rung have no journal, so one is rebuilt
+/// from the restack's backup.
+fn update_journal<S: StateStore>(
+    state: &S,
+    restack_state: &RestackState,
+    paused: bool,
+) -> Result<()> {
+    let mut journal = match state.load_journal()? {
+        Some(journal) => journal,
+        None => Journal::rollback("restack", state.load_backup(&restack_state.backup_id)?),
+    };
+    journal.paused = paused;
+    journal.rebasing = Some(restack_state.current_branch.clone()).filter(|b| !b.is_empty());
+    state.save_journal(&journal)?;
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -642,6 +795,7 @@ mod tests {
             old_parent: Some("develop".to_string()),
             new_parent: "main".to_string(),
             branches_rebased: vec!["feature/x".to_string()],
+            branches_skipped: vec![],
             diverged_branches: vec![],
         };
         assert_eq!(result.target_branch, "feature/x");
@@ -657,6 +811,7 @@ mod tests {
             old_parent: None,
             new_parent: "main".to_string(),
             branches_rebased: vec!["test".to_string()],
+            branches_skipped: vec![],
             diverged_branches: vec![DivergenceInfo {
                 branch: "test".to_string(),
                 ahead: 1,
@@ -674,6 +829,7 @@ mod tests {
             old_parent: Some("old".to_string()),
             new_parent: "new".to_string(),
             branches_rebased: vec!["feature/serialize".to_string()],
+            branches_skipped: vec![],
             diverged_branches: vec![],
         };
         let json = serde_json::to_string(&result).expect("serialization should succeed");
@@ -689,6 +845,7 @@ mod tests {
             old_parent: None,
             new_parent: "main".to_string(),
             branches_rebased: vec!["test".to_string()],
+            branches_skipped: vec![],
             diverged_branches: vec![],
         };
         let cloned = result.clone();
@@ -708,6 +865,7 @@ mod tests {
                 "child2".to_string(),
                 "grandchild".to_string(),
             ],
+            branches_skipped: vec![],
             diverged_branches: vec![],
         };
         assert_eq!(result.branches_rebased.len(), 4);
@@ -718,9 +876,10 @@ mod tests {
     #[allow(clippy::unwrap_used)]
     mod mock_tests {
         use super::*;
-        use crate::services::test_mocks::{MockGitOps, MockStateStore};
         use rung_core::stack::{Stack, StackBranch};
+        use rung_core::testing::MockStateStore;
         use rung_git::Oid;
+        use rung_git::testing::MockGitOps;
 
         #[test]
         fn test_create_plan_branch_not_in_stack() {
@@ -850,6 +1009,44 @@ mod tests {
             assert_eq!(plan.branches_to_rebase.len(), 2);
         }
 
+        #[test]
+        fn test_plan_descendants_skips_up_to_date_children() {
+            let parent_oid = Oid::from_str("1111111111111111111111111111111111111111").unwrap();
+            let stale_oid = Oid::from_str("2222222222222222222222222222222222222222").unwrap();
+            let git = MockGitOps::new()
+                .with_branch("feature/parent", parent_oid)
+                .with_branch("feature/stale", stale_oid)
+                .with_branch("feature/grandchild", stale_oid)
+                .with_branch("feature/fresh", parent_oid);
+
+            let mut stack = Stack::default();
+            stack.add_branch(StackBranch::try_new("feature/parent", Some("main")).unwrap());
+            stack
+                .add_branch(StackBranch::try_new("feature/stale", Some("feature/parent")).unwrap());
+            stack.add_branch(
+                StackBranch::try_new("feature/grandchild", Some("feature/stale")).unwrap(),
+            );
+            stack
+                .add_branch(StackBranch::try_new("feature/fresh", Some("feature/parent")).unwrap());
+
+            let state = MockStateStore::new().with_stack(stack);
+
+            let service = RestackService::new(&git);
+            let plans = service.plan_descendants(&state, "feature/parent").unwrap();
+
+            assert_eq!(plans.len(), 1);
+            assert_eq!(plans[0].target_branch, "feature/stale");
+            assert_eq!(plans[0].new_parent, "feature/parent");
+            assert_eq!(
+                plans[0].branches_to_rebase,
+                vec![
+                    "feature/stale".to_string(),
+                    "feature/grandchild".to_string()
+                ]
+            );
+            assert!(plans[0].needs_rebase);
+        }
+
         #[test]
         fn test_execute_no_rebase_needed() {
             let oid = Oid::zero();
@@ -917,6 +1114,31 @@ mod tests {
             );
         }
 
+        #[test]
+        fn test_skip_refuses_target_branch() {
+            let git = MockGitOps::new();
+            let state = MockStateStore::new().with_restack_state(RestackState::new(
+                "backup".to_string(),
+                "feature".to_string(),
+                "main".to_string(),
+                Some("develop".to_string()),
+                "main".to_string(),
+                vec!["feature".to_string(), "child".to_string()],
+                vec![],
+            ));
+
+            let service = RestackService::new(&git);
+
+            let result = service.skip(&state, false);
+            assert!(
+                result
+                    .unwrap_err()
+                    .to_string()
+                    .contains("Can't skip 'feature'")
+            );
+            assert!(service.skip(&MockStateStore::new(), false).is_err());
+        }
+
         #[test]
         fn test_check_divergence_no_divergence() {
             let git = MockGitOps::new();
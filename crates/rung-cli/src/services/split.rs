@@ -19,6 +19,22 @@ pub struct CommitInfo {
     pub summary: String,
 }
 
+impl CommitInfo {
+    /// Look up a commit's display information.
+    pub(crate) fn load(repo: &Repository, oid: Oid) -> Result<Self> {
+        let commit = repo.find_commit(oid)?;
+        let sha = oid.to_string();
+        let short_sha = sha[..8.min(sha.len())].to_string();
+        let summary = commit.summary().unwrap_or("(no message)").to_string();
+
+        Ok(Self {
+            oid: sha,
+            short_sha,
+            summary,
+        })
+    }
+}
+
 /// Configuration for a split operation.
 #[derive(Debug, Clone)]
 #[allow(dead_code)] // Used in Phase 4
@@ -29,6 +45,9 @@ pub struct SplitConfig {
     pub parent_branch: String,
     /// Split points defining where to create new branches.
     pub split_points: Vec<SplitPoint>,
+    /// Branch the source branch's children are reparented onto.
+    /// `None` keeps them on the source branch.
+    pub children_follow: Option<String>,
 }
 
 /// Result of analyzing a branch for splitting.
@@ -50,6 +69,9 @@ pub struct SplitResult {
     pub source_branch: String,
     /// Branches that were created.
     pub branches_created: Vec<String>,
+    /// Children moved off the source branch (see `SplitConfig::children_follow`).
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub children_moved: Vec<String>,
 }
 
 /// Service for split operations.
@@ -85,7 +107,7 @@ impl<'a> SplitService<'a> {
         let commits: Vec<CommitInfo> = commit_oids
             .into_iter()
             .rev()
-            .map(|oid| self.commit_info(oid))
+            .map(|oid| CommitInfo::load(self.repo, oid))
             .collect::<Result<Vec<_>>>()?;
 
         Ok(SplitAnalysis {
@@ -95,20 +117,6 @@ impl<'a> SplitService<'a> {
         })
     }
 
-    /// Get information about a commit.
-    fn commit_info(&self, oid: Oid) -> Result<CommitInfo> {
-        let commit = self.repo.find_commit(oid)?;
-        let sha = oid.to_string();
-        let short_sha = sha[..8.min(sha.len())].to_string();
-        let summary = commit.summary().unwrap_or("(no message)").to_string();
-
-        Ok(CommitInfo {
-            oid: sha,
-            short_sha,
-            summary,
-        })
-    }
-
     /// Suggest a branch name based on a commit summary.
     ///
     /// Derives a kebab-case name from the first few words of the summary.
@@ -137,6 +145,24 @@ impl<'a> SplitService<'a> {
         }
     }
 
+    /// Partition `commit_count` commits into `parts` groups of even size.
+    ///
+    /// Returns the index of the last commit in each group except the final
+    /// one, which stays on the source branch. When the commits don't divide
+    /// evenly, the later groups get the extra commits.
+    ///
+    /// # Errors
+    /// Returns error if `parts` is less than 2 or more than `commit_count`.
+    pub fn even_boundaries(commit_count: usize, parts: usize) -> Result<Vec<usize>> {
+        if parts < 2 {
+            bail!("--into needs at least 2 parts");
+        }
+        if parts > commit_count {
+            bail!("Can't split {commit_count} commit(s) into {parts} branches");
+        }
+        Ok((1..parts).map(|i| i * commit_count / parts - 1).collect())
+    }
+
     /// Execute a split operation.
     ///
     /// Creates new branches at each split point and updates the stack topology.
@@ -226,6 +252,29 @@ impl<'a> SplitService<'a> {
             stack.reparent(&config.source_branch, Some(&previous_parent))?;
         }
 
+        // The children still sit on the source's commits. Recording the source
+        // tip as their base lets the next sync replay only their own commits
+        // onto the branch they now follow.
+        let mut children_moved = Vec::new();
+        if let Some(follow) = config
+            .children_follow
+            .as_deref()
+            .filter(|follow| *follow != config.source_branch)
+        {
+            let source_tip = self.repo.branch_commit(&config.source_branch)?.to_string();
+            children_moved = stack
+                .children_of(&config.source_branch)
+                .iter()
+                .map(|child| child.name.to_string())
+                .collect();
+            for child in &children_moved {
+                stack.reparent(child, Some(follow))?;
+                if let Some(branch) = stack.find_branch_mut(child) {
+                    branch.base = Some(source_tip.clone());
+                }
+            }
+        }
+
         // Save updated stack
         state.save_stack(&stack)?;
 
@@ -237,6 +286,7 @@ impl<'a> SplitService<'a> {
         Ok(SplitResult {
             source_branch: config.source_branch.clone(),
             branches_created: created_branches,
+            children_moved,
         })
     }
 
@@ -366,6 +416,7 @@ mod tests {
             source_branch: "feature".to_string(),
             parent_branch: "main".to_string(),
             split_points: vec![],
+            children_follow: None,
         };
         assert_eq!(config.source_branch, "feature");
         assert!(config.split_points.is_empty());
@@ -387,6 +438,7 @@ mod tests {
         let result = SplitResult {
             source_branch: "feature".to_string(),
             branches_created: vec!["feature-1".to_string(), "feature-2".to_string()],
+            children_moved: vec![],
         };
         assert_eq!(result.branches_created.len(), 2);
     }
@@ -410,6 +462,17 @@ mod tests {
         assert_eq!(name, "feat-api-add-endpoint");
     }
 
+    #[test]
+    #[allow(clippy::unwrap_used)]
+    fn test_even_boundaries() {
+        assert_eq!(SplitService::even_boundaries(4, 2).unwrap(), vec![1]);
+        assert_eq!(SplitService::even_boundaries(5, 2).unwrap(), vec![1]);
+        assert_eq!(SplitService::even_boundaries(7, 3).unwrap(), vec![1, 3]);
+        assert_eq!(SplitService::even_boundaries(3, 3).unwrap(), vec![0, 1]);
+        assert!(SplitService::even_boundaries(3, 4).is_err());
+        assert!(SplitService::even_boundaries(3, 1).is_err());
+    }
+
     #[test]
     fn test_suggest_branch_name_fallback() {
         // Empty summary falls back to prefix with index
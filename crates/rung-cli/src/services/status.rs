@@ -13,12 +13,17 @@ use serde::Serialize;
 pub struct BranchStatusInfo {
     pub name: String,
     pub parent: Option<String>,
+    /// Shown children, in stack order.
+    pub children: Vec<String>,
     pub state: BranchState,
     pub pr: Option<u64>,
     #[serde(skip_serializing_if = "std::ops::Not::not")]
     pub is_current: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub remote_divergence: Option<RemoteDivergenceInfo>,
+    /// Locked with `rung lock`.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub locked: bool,
 }
 
 /// Serializable remote divergence info.
@@ -30,6 +35,7 @@ pub enum RemoteDivergenceInfo {
     Behind { commits: usize },
     Diverged { ahead: usize, behind: usize },
     NoRemote,
+    Gone,
 }
 
 impl From<&RemoteDivergence> for RemoteDivergenceInfo {
@@ -43,15 +49,42 @@ impl From<&RemoteDivergence> for RemoteDivergenceInfo {
                 behind: *behind,
             },
             RemoteDivergence::NoRemote => Self::NoRemote,
+            RemoteDivergence::Gone => Self::Gone,
+        }
+    }
+}
+
+impl RemoteDivergenceInfo {
+    /// Commits `(ahead, behind)` the upstream, or `None` when there's no
+    /// upstream to compare against.
+    pub const fn counts(&self) -> Option<(usize, usize)> {
+        match *self {
+            Self::InSync => Some((0, 0)),
+            Self::Ahead { commits } => Some((commits, 0)),
+            Self::Behind { commits } => Some((0, commits)),
+            Self::Diverged { ahead, behind } => Some((ahead, behind)),
+            Self::NoRemote | Self::Gone => None,
         }
     }
 }
 
+/// Which branches `rung status` shows.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StatusFilter {
+    /// Only the current branch, its ancestors and its descendants.
+    pub chain: bool,
+    /// Only this many levels below trunk, or below the current branch
+    /// with `chain`.
+    pub depth: Option<usize>,
+}
+
 /// Complete status report for the stack.
 #[derive(Debug, Clone, Serialize)]
 pub struct StackStatus {
     pub branches: Vec<BranchStatusInfo>,
     pub current_branch: Option<String>,
+    /// Branches left out by the [`StatusFilter`].
+    pub hidden: usize,
 }
 
 impl StackStatus {
@@ -61,6 +94,7 @@ impl StackStatus {
         Self {
             branches: Vec::new(),
             current_branch: None,
+            hidden: 0,
         }
     }
 
@@ -88,20 +122,22 @@ impl<'a, G: GitOps> StatusService<'a, G> {
         Ok(())
     }
 
-    /// Compute the complete status of the stack.
-    pub fn compute_status(&self) -> Result<StackStatus> {
+    /// Compute the status of the branches the filter selects.
+    pub fn compute_status(&self, filter: &StatusFilter) -> Result<StackStatus> {
         let current = self.repo.current_branch().ok();
 
         if self.stack.is_empty() {
             return Ok(StackStatus {
                 branches: vec![],
                 current_branch: current,
+                hidden: 0,
             });
         }
 
-        let mut branches = Vec::with_capacity(self.stack.branches.len());
+        let visible = self.visible_branches(current.as_deref(), filter);
+        let mut branches = Vec::with_capacity(visible.len());
 
-        for branch in &self.stack.branches {
+        for branch in &visible {
             let state = self.compute_branch_state(branch)?;
             let remote_divergence = self
                 .repo
@@ -109,22 +145,63 @@ impl<'a, G: GitOps> StatusService<'a, G> {
                 .ok()
                 .map(|d| RemoteDivergenceInfo::from(&d));
 
+            let children = visible
+                .iter()
+                .filter(|b| b.parent.as_ref() == Some(&branch.name))
+                .map(|b| b.name.to_string())
+                .collect();
             branches.push(BranchStatusInfo {
                 name: branch.name.to_string(),
                 parent: branch.parent.as_ref().map(ToString::to_string),
+                children,
                 state,
                 pr: branch.pr,
                 is_current: current.as_deref() == Some(branch.name.as_str()),
                 remote_divergence,
+                locked: branch.locked,
             });
         }
 
         Ok(StackStatus {
+            hidden: self.stack.len() - branches.len(),
             branches,
             current_branch: current,
         })
     }
 
+    /// The stack branches the filter keeps, in stack order.
+    ///
+    /// `chain` only applies when the current branch is in the stack; on
+    /// trunk every stack is part of the chain.
+    fn visible_branches(&self, current: Option<&str>, filter: &StatusFilter) -> Vec<&StackBranch> {
+        let index = self.stack.index();
+        let depths = index.depths();
+        let anchor = current.filter(|c| filter.chain && index.contains(c));
+
+        self.stack
+            .branches
+            .iter()
+            .filter(|b| {
+                let Some(anchor) = anchor else {
+                    // Levels below trunk: trunk's children are level 1
+                    return filter
+                        .depth
+                        .is_none_or(|max| depths.get(b.name.as_str()).is_some_and(|&d| d < max));
+                };
+                if b.name == anchor || index.is_descendant(anchor, &b.name) {
+                    return true;
+                }
+                if !index.is_descendant(&b.name, anchor) {
+                    return false;
+                }
+                filter.depth.is_none_or(|max| {
+                    let level = |name: &str| depths.get(name).copied().unwrap_or_default();
+                    level(&b.name) - level(anchor) <= max
+                })
+            })
+            .collect()
+    }
+
     /// Compute the sync state of a branch relative to its parent.
     pub fn compute_branch_state(&self, branch: &StackBranch) -> Result<BranchState> {
         let Some(parent_name) = &branch.parent else {
@@ -154,13 +231,22 @@ impl<'a, G: GitOps> StatusService<'a, G> {
         // Find merge base
         let merge_base = self.repo.merge_base(branch_commit, parent_commit)?;
 
-        // If merge base is the parent commit, we're synced
-        if merge_base == parent_commit {
+        // If merge base is the parent commit, we're synced - unless the parent
+        // dropped commits this branch was based on (e.g. `rung uncommit`)
+        let rewritten =
+            rung_core::sync::rewritten_parent_base(self.repo, branch, branch_commit, parent_commit)
+                .is_some();
+        if merge_base == parent_commit && !rewritten {
             return Ok(BranchState::Synced);
         }
 
-        // Count how many commits behind
+        // Count how many commits behind; a rewritten parent is at least one
         let commits_behind = self.repo.count_commits_between(merge_base, parent_commit)?;
+        let commits_behind = if rewritten {
+            commits_behind.max(1)
+        } else {
+            commits_behind
+        };
 
         Ok(BranchState::Diverged { commits_behind })
     }
@@ -169,10 +255,10 @@ impl<'a, G: GitOps> StatusService<'a, G> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::services::test_mocks::MockGitOps;
     use rung_core::stack::StackBranch;
     use rung_core::{BranchName, BranchState};
     use rung_git::Oid;
+    use rung_git::testing::MockGitOps;
 
     #[test]
     #[allow(clippy::unwrap_used)]
@@ -181,7 +267,7 @@ mod tests {
         let stack = Stack::default();
         let service = StatusService::new(&mock_repo, &stack);
 
-        let status = service.compute_status().unwrap();
+        let status = service.compute_status(&StatusFilter::default()).unwrap();
         assert!(status.is_empty());
         assert_eq!(status.current_branch, Some("main".to_string()));
     }
@@ -200,7 +286,7 @@ mod tests {
 
         let service = StatusService::new(&mock_repo, &stack);
 
-        let status = service.compute_status().unwrap();
+        let status = service.compute_status(&StatusFilter::default()).unwrap();
         assert!(!status.is_empty());
         assert_eq!(status.branches.len(), 1);
         assert_eq!(status.branches[0].name, "feature/test");
@@ -295,12 +381,51 @@ mod tests {
 
         let service = StatusService::new(&mock_repo, &stack);
 
-        let status = service.compute_status().unwrap();
+        let status = service.compute_status(&StatusFilter::default()).unwrap();
         assert_eq!(status.branches.len(), 2);
         assert!(!status.branches[0].is_current); // feature/a
         assert!(status.branches[1].is_current); // feature/b
     }
 
+    #[test]
+    #[allow(clippy::unwrap_used)]
+    fn test_status_service_filters_chain_and_depth() {
+        // main ← a ← b ← c, and a ← side
+        let mut mock_repo = MockGitOps::new().with_current_branch("b");
+        let mut stack = Stack::default();
+        for (name, parent) in [("a", "main"), ("b", "a"), ("c", "b"), ("side", "a")] {
+            mock_repo = mock_repo.with_branch(name, Oid::zero());
+            stack.add_branch(StackBranch::try_new(name, Some(parent)).unwrap());
+        }
+        let service = StatusService::new(&mock_repo, &stack);
+        let names = |filter: StatusFilter| {
+            let status = service.compute_status(&filter).unwrap();
+            let names: Vec<String> = status.branches.into_iter().map(|b| b.name).collect();
+            (names, status.hidden)
+        };
+
+        let chain = StatusFilter {
+            chain: true,
+            depth: None,
+        };
+        assert_eq!(names(chain), (vec!["a".into(), "b".into(), "c".into()], 1));
+
+        let chain_depth = StatusFilter {
+            chain: true,
+            depth: Some(0),
+        };
+        assert_eq!(names(chain_depth), (vec!["a".into(), "b".into()], 2));
+
+        let depth = StatusFilter {
+            chain: false,
+            depth: Some(2),
+        };
+        assert_eq!(
+            names(depth),
+            (vec!["a".into(), "b".into(), "side".into()], 1)
+        );
+    }
+
     #[test]
     fn test_stack_status_empty() {
         let status = StackStatus::empty();
@@ -314,12 +439,15 @@ mod tests {
             branches: vec![BranchStatusInfo {
                 name: "feature/test".to_string(),
                 parent: Some("main".to_string()),
+                children: vec![],
                 state: BranchState::Synced,
                 pr: Some(123),
                 is_current: true,
                 remote_divergence: Some(RemoteDivergenceInfo::InSync),
+                locked: false,
             }],
             current_branch: Some("feature/test".to_string()),
+            hidden: 0,
         };
         assert!(!status.is_empty());
         assert_eq!(status.branches.len(), 1);
@@ -331,15 +459,19 @@ mod tests {
         let info = BranchStatusInfo {
             name: "feature/auth".to_string(),
             parent: Some("main".to_string()),
+            children: vec!["feature/auth-ui".to_string()],
             state: BranchState::Synced,
             pr: Some(42),
             is_current: true,
             remote_divergence: Some(RemoteDivergenceInfo::Ahead { commits: 2 }),
+            locked: true,
         };
         let json = serde_json::to_string(&info).expect("serialization should succeed");
         assert!(json.contains("feature/auth"));
         assert!(json.contains("42"));
         assert!(json.contains("is_current"));
+        assert!(json.contains(r#""children":["feature/auth-ui"]"#));
+        assert!(json.contains(r#""locked":true"#));
     }
 
     #[test]
@@ -348,16 +480,19 @@ mod tests {
         let info = BranchStatusInfo {
             name: "other".to_string(),
             parent: None,
+            children: vec![],
             state: BranchState::Synced,
             pr: None,
             is_current: false,
             remote_divergence: None,
+            locked: false,
         };
         let json = serde_json::to_string(&info).expect("serialization should succeed");
         // is_current: false should be skipped
         assert!(!json.contains("is_current"));
         // remote_divergence: None should be skipped
         assert!(!json.contains("remote_divergence"));
+        assert!(!json.contains("locked"));
     }
 
     #[test]
@@ -406,6 +541,28 @@ mod tests {
 
         let no_remote = RemoteDivergenceInfo::from(&RemoteDivergence::NoRemote);
         assert!(matches!(no_remote, RemoteDivergenceInfo::NoRemote));
+
+        let gone = RemoteDivergenceInfo::from(&RemoteDivergence::Gone);
+        assert!(matches!(gone, RemoteDivergenceInfo::Gone));
+    }
+
+    #[test]
+    fn test_remote_divergence_counts() {
+        assert_eq!(RemoteDivergenceInfo::InSync.counts(), Some((0, 0)));
+        assert_eq!(
+            RemoteDivergenceInfo::Behind { commits: 2 }.counts(),
+            Some((0, 2))
+        );
+        assert_eq!(
+            RemoteDivergenceInfo::Diverged {
+                ahead: 3,
+                behind: 1
+            }
+            .counts(),
+            Some((3, 1))
+        );
+        assert_eq!(RemoteDivergenceInfo::NoRemote.counts(), None);
+        assert_eq!(RemoteDivergenceInfo::Gone.counts(), None);
     }
 
     #[test]
@@ -10,7 +10,8 @@ use anyhow::{Context, Result, bail};
 use rung_core::stack::Stack;
 use rung_git::GitOps;
 use rung_github::{
-    CreateComment, CreatePullRequest, ForgeApi, RepoId, UpdateComment, UpdatePullRequest,
+    CommitState, CommitStatus, CreateComment, CreatePullRequest, ForgeApi, RepoId, UpdateComment,
+    UpdatePullRequest,
 };
 use serde::Serialize;
 
@@ -23,6 +24,10 @@ pub enum PlannedBranchAction {
         pr_number: u64,
         pr_url: String,
         base: String,
+        /// Regenerated title, when refreshing changes it.
+        title: Option<String>,
+        /// Regenerated body, when refreshing changes it.
+        body: Option<String>,
     },
     /// Create a new PR.
     Create {
@@ -95,12 +100,18 @@ pub enum SubmitAction {
 pub struct SubmitConfig<'a> {
     /// Create PRs as drafts.
     pub draft: bool,
+    /// Branches whose new PRs are drafts even without `draft`.
+    pub draft_branches: Vec<String>,
     /// Custom title for the current branch's PR.
     pub custom_title: Option<&'a str>,
     /// Current branch name (for custom title matching).
     pub current_branch: Option<String>,
     /// Default base branch (from config, falls back to "main").
     pub default_branch: String,
+    /// Regenerate existing PRs' titles from their tip commits.
+    pub update_titles: bool,
+    /// Regenerate the rung-managed part of existing PRs' bodies.
+    pub update_bodies: bool,
 }
 
 /// Service for submit operations with injected dependencies.
@@ -157,14 +168,28 @@ where
                 .unwrap_or(&config.default_branch)
                 .to_string();
 
+            let refresh = config.update_titles || config.update_bodies;
+
             // Check if PR already exists
             if let Some(pr_number) = branch.pr {
                 let pr_url = format!("https://github.com/{}/pull/{pr_number}", self.repo);
+                let (title, body) = if refresh {
+                    let pr = self
+                        .github
+                        .get_pr(&self.repo, pr_number)
+                        .await
+                        .with_context(|| format!("Failed to fetch PR #{pr_number}"))?;
+                    refreshed_title_and_body(self.git, branch_name, &pr, config)
+                } else {
+                    (None, None)
+                };
                 actions.push(PlannedBranchAction::Update {
                     branch: branch_name.to_string(),
                     pr_number,
                     pr_url,
                     base: base_branch,
+                    title,
+                    body,
                 });
             } else {
                 let existing = self
@@ -174,27 +199,22 @@ where
                     .context("Failed to check for existing PR")?;
 
                 if let Some(pr) = existing {
+                    let (title, body) = if refresh {
+                        refreshed_title_and_body(self.git, branch_name, &pr, config)
+                    } else {
+                        (None, None)
+                    };
                     actions.push(PlannedBranchAction::Update {
                         branch: branch_name.to_string(),
                         pr_number: pr.number,
                         pr_url: pr.html_url,
                         base: base_branch,
-                    });
-                } else {
-                    // Only extract title/body when we need to create a new PR
-                    let (mut title, body) = self.get_pr_title_and_body(branch_name);
-                    if config.current_branch.as_deref() == Some(branch_name.as_str())
-                        && let Some(custom) = config.custom_title
-                    {
-                        title = custom.to_string();
-                    }
-                    actions.push(PlannedBranchAction::Create {
-                        branch: branch_name.to_string(),
                         title,
                         body,
-                        base: base_branch,
-                        draft: config.draft,
                     });
+                } else {
+                    // Only extract title/body when we need to create a new PR
+                    actions.push(planned_create(self.git, branch_name, base_branch, config));
                 }
             }
         }
@@ -223,16 +243,18 @@ where
                     pr_number,
                     pr_url,
                     base,
+                    title,
+                    body,
                 } => {
                     // Push branch
                     self.git
                         .push(branch, force)
                         .with_context(|| format!("Failed to push {branch}"))?;
 
-                    // Update PR base
+                    // Update PR base, and the title and body when refreshed
                     let update = UpdatePullRequest {
-                        title: None,
-                        body: None,
+                        title: title.clone(),
+                        body: body.clone(),
                         base: Some(base.clone()),
                     };
                     self.github
@@ -288,10 +310,12 @@ where
 
                         (pr.number, pr.html_url, false)
                     } else {
-                        // Create new PR
+                        // Create new PR, linked to the parent's PR (created
+                        // earlier in this run if it's new)
+                        let parent_pr = stack.find_branch(base).and_then(|b| b.pr);
                         let create = CreatePullRequest {
                             title: title.clone(),
-                            body: body.clone(),
+                            body: with_parent_link(body, parent_pr),
                             head: branch.clone(),
                             base: base.clone(),
                             draft: *draft,
@@ -329,17 +353,43 @@ where
         Ok(results)
     }
 
+    /// Update the stack navigation on all PRs: in a comment, or in a
+    /// section of the body with `submit.stack_in_body`.
+    ///
+    /// # Errors
+    /// Returns error if GitHub API calls fail.
+    pub async fn update_stack_navigation(
+        &self,
+        stack: &Stack,
+        default_branch: &str,
+        config: &rung_core::config::SubmitConfig,
+    ) -> Result<()> {
+        if config.stack_in_body {
+            self.update_stack_sections(stack, default_branch, config.collapse_stack_above)
+                .await
+        } else {
+            self.update_stack_comments(stack, default_branch, config.collapse_stack_above)
+                .await
+        }
+    }
+
     /// Update stack navigation comments on all PRs.
     ///
     /// # Errors
     /// Returns error if GitHub API calls fail.
-    pub async fn update_stack_comments(&self, stack: &Stack, default_branch: &str) -> Result<()> {
+    pub async fn update_stack_comments(
+        &self,
+        stack: &Stack,
+        default_branch: &str,
+        collapse_above: usize,
+    ) -> Result<()> {
         for branch in &stack.branches {
             let Some(pr_number) = branch.pr else {
                 continue;
             };
 
-            let comment_body = generate_stack_comment(stack, pr_number, default_branch);
+            let comment_body =
+                generate_stack_comment(stack, pr_number, default_branch, collapse_above);
 
             // Find existing rung comment
             let comments = self
@@ -372,32 +422,330 @@ where
         Ok(())
     }
 
-    /// Get PR title and body from the branch's tip commit message.
-    fn get_pr_title_and_body(&self, branch_name: &str) -> (String, String) {
-        if let Ok(message) = self.git.branch_commit_message(branch_name) {
-            let mut lines = message.lines();
-            let title = lines.next().unwrap_or("").trim().to_string();
-
-            let body: String = lines
-                .skip_while(|line| line.trim().is_empty())
-                .collect::<Vec<_>>()
-                .join("\n")
-                .trim_end()
-                .to_string();
+    /// Update the stack section in the body of every PR, instead of a
+    /// comment.
+    ///
+    /// Only the text between the stack markers is rewritten; a body
+    /// without them gets the section appended, and the rest is kept as is.
+    ///
+    /// # Errors
+    /// Returns error if GitHub API calls fail.
+    pub async fn update_stack_sections(
+        &self,
+        stack: &Stack,
+        default_branch: &str,
+        collapse_above: usize,
+    ) -> Result<()> {
+        for branch in &stack.branches {
+            let Some(pr_number) = branch.pr else {
+                continue;
+            };
 
-            if !title.is_empty() {
-                return (title, body);
+            let pr = self
+                .github
+                .get_pr(&self.repo, pr_number)
+                .await
+                .with_context(|| format!("Failed to fetch PR #{pr_number}"))?;
+            let current = pr.body.unwrap_or_default();
+            let body = with_stack_section(
+                &current,
+                &generate_stack_comment(stack, pr_number, default_branch, collapse_above),
+            );
+            if body == current {
+                continue;
             }
+
+            let update = UpdatePullRequest {
+                title: None,
+                body: Some(body),
+                base: None,
+            };
+            self.github
+                .update_pr(&self.repo, pr_number, update)
+                .await
+                .with_context(|| format!("Failed to update PR #{pr_number}"))?;
+        }
+
+        Ok(())
+    }
+
+    /// Set a [`STACK_STATUS_CONTEXT`] commit status on the head of every PR
+    /// in the stack.
+    ///
+    /// # Errors
+    /// Returns error if a branch can't be resolved or a GitHub API call fails.
+    pub async fn publish_stack_statuses(&self, stack: &Stack) -> Result<()> {
+        for branch in stack.branches.iter().filter(|b| b.pr.is_some()) {
+            let head = self.git.branch_commit(&branch.name)?;
+            self.github
+                .set_commit_status(
+                    &self.repo,
+                    &head.to_string(),
+                    stack_status(stack, &branch.name),
+                )
+                .await
+                .with_context(|| format!("Failed to set commit status on {}", branch.name))?;
         }
+        Ok(())
+    }
+}
 
-        (generate_title(branch_name), String::new())
+/// Context of the commit status giving a PR's place in the stack.
+const STACK_STATUS_CONTEXT: &str = "rung/stack";
+
+/// The commit status for `branch_name`: its position in its tree of the
+/// stack, pending while its parent is unmerged so a required status keeps
+/// PRs from merging out of order.
+fn stack_status(stack: &Stack, branch_name: &str) -> CommitStatus {
+    let ancestry = stack.ancestry(branch_name);
+    let position = ancestry.len();
+    let total = ancestry
+        .first()
+        .map_or(0, |root| stack.descendants(&root.name).len() + 1);
+    let parent = position
+        .checked_sub(2)
+        .and_then(|i| ancestry.get(i))
+        .map(|parent| {
+            parent
+                .pr
+                .map_or_else(|| parent.name.to_string(), |pr| format!("#{pr}"))
+        });
+
+    let (state, description) = parent.map_or_else(
+        || {
+            (
+                CommitState::Success,
+                format!("Part of stack: {position} of {total}"),
+            )
+        },
+        |parent| {
+            (
+                CommitState::Pending,
+                format!("Part of stack: {position} of {total}, parent {parent} not merged"),
+            )
+        },
+    );
+    CommitStatus {
+        state,
+        description,
+        context: STACK_STATUS_CONTEXT.to_string(),
     }
 }
 
+/// Create a submit plan from local stack state alone, without the forge.
+///
+/// Used offline: branches with a recorded PR are updates and everything else
+/// is a create, so a PR opened outside rung shows up as a create.
+///
+/// # Errors
+/// Returns error if the stack has a cycle.
+pub fn create_offline_plan<G: GitOps>(
+    git: &G,
+    stack: &Stack,
+    config: &SubmitConfig<'_>,
+    repo: &RepoId,
+) -> Result<SubmitPlan> {
+    let sorted_branches = topological_sort(&stack.branches, &config.default_branch)?;
+
+    let actions = sorted_branches
+        .into_iter()
+        .map(|branch| {
+            let base_branch = branch
+                .parent
+                .as_deref()
+                .unwrap_or(&config.default_branch)
+                .to_string();
+            match branch.pr {
+                Some(pr_number) => PlannedBranchAction::Update {
+                    branch: branch.name.to_string(),
+                    pr_number,
+                    pr_url: format!("https://github.com/{repo}/pull/{pr_number}"),
+                    base: base_branch,
+                    title: None,
+                    body: None,
+                },
+                None => planned_create(git, &branch.name, base_branch, config),
+            }
+        })
+        .collect();
+
+    Ok(SubmitPlan { actions })
+}
+
 // === Helper Functions ===
 
-/// Marker to identify rung stack comments.
-const STACK_COMMENT_MARKER: &str = "<!-- rung-stack -->";
+/// Plan a new PR for `branch_name`, titled from its tip commit.
+fn planned_create<G: GitOps>(
+    git: &G,
+    branch_name: &str,
+    base: String,
+    config: &SubmitConfig<'_>,
+) -> PlannedBranchAction {
+    let (title, body) = generated_title_and_body(git, branch_name, config);
+    PlannedBranchAction::Create {
+        branch: branch_name.to_string(),
+        body: managed_body(&title, &body),
+        title,
+        base,
+        draft: config.draft || config.draft_branches.iter().any(|b| b == branch_name),
+    }
+}
+
+/// The title and body rung generates for `branch_name`'s PR: the tip
+/// commit's message, or the custom title for the current branch.
+fn generated_title_and_body<G: GitOps>(
+    git: &G,
+    branch_name: &str,
+    config: &SubmitConfig<'_>,
+) -> (String, String) {
+    let (mut title, body) = get_pr_title_and_body(git, branch_name);
+    if config.current_branch.as_deref() == Some(branch_name)
+        && let Some(custom) = config.custom_title
+    {
+        title = custom.to_string();
+    }
+    (title, body)
+}
+
+/// Marker recording the title rung last gave a PR, kept in its body.
+pub const TITLE_MARKER_PREFIX: &str = "<!-- rung-title: ";
+
+/// Markers around the part of a PR body that rung generated.
+pub const BODY_START_MARKER: &str = "<!-- rung-body -->";
+pub const BODY_END_MARKER: &str = "<!-- /rung-body -->";
+
+/// Build a new PR's body with its title recorded and the generated text
+/// between markers, so a later refresh can tell it from manual edits.
+fn managed_body(title: &str, body: &str) -> String {
+    let title = title.replace("-->", "-- >");
+    if body.is_empty() {
+        format!("{TITLE_MARKER_PREFIX}{title} -->\n{BODY_START_MARKER}\n{BODY_END_MARKER}")
+    } else {
+        format!("{TITLE_MARKER_PREFIX}{title} -->\n{BODY_START_MARKER}\n{body}\n{BODY_END_MARKER}")
+    }
+}
+
+/// Work out an existing PR's refreshed title and body, each `None` when
+/// it stays as it is.
+///
+/// Only text rung still owns is rewritten: the title when it matches the
+/// one recorded in the body, and the body between its markers. PRs edited
+/// by hand, or opened without the markers, are left alone.
+fn refreshed_title_and_body<G: GitOps>(
+    git: &G,
+    branch_name: &str,
+    pr: &rung_github::PullRequest,
+    config: &SubmitConfig<'_>,
+) -> (Option<String>, Option<String>) {
+    let Some(current_body) = pr.body.as_deref() else {
+        return (None, None);
+    };
+    let (title, body) = generated_title_and_body(git, branch_name, config);
+    let mut new_body = current_body.to_string();
+
+    let recorded_title = current_body
+        .lines()
+        .find_map(|line| line.strip_prefix(TITLE_MARKER_PREFIX))
+        .and_then(|rest| rest.strip_suffix(" -->"));
+    let new_title =
+        (config.update_titles && recorded_title == Some(pr.title.as_str()) && pr.title != title)
+            .then(|| {
+                let marker = format!("{TITLE_MARKER_PREFIX}{} -->", pr.title);
+                let updated = format!("{TITLE_MARKER_PREFIX}{} -->", title.replace("-->", "-- >"));
+                new_body = new_body.replacen(&marker, &updated, 1);
+                title
+            });
+
+    if config.update_bodies {
+        let section = if body.is_empty() {
+            format!("{BODY_START_MARKER}\n{BODY_END_MARKER}")
+        } else {
+            format!("{BODY_START_MARKER}\n{body}\n{BODY_END_MARKER}")
+        };
+        if let Some(merged) = merge_section(&new_body, BODY_START_MARKER, BODY_END_MARKER, &section)
+        {
+            new_body = merged;
+        }
+    }
+
+    let new_body = (new_body != current_body).then_some(new_body);
+    (new_title, new_body)
+}
+
+/// Append a `Depends on #N` reference to the parent's PR, so the forge
+/// lists the dependency even for readers who skip the stack navigation.
+///
+/// The line sits outside the rung-managed section, so refreshing the body
+/// keeps it.
+fn with_parent_link(body: &str, parent_pr: Option<u64>) -> String {
+    parent_pr.map_or_else(
+        || body.to_string(),
+        |number| format!("{body}\n\nDepends on #{number}"),
+    )
+}
+
+/// Replace the section of `body` from `start` through `end` with
+/// `section`, leaving the text around it untouched.
+///
+/// Returns `None` if the body doesn't have both markers.
+fn merge_section(body: &str, start: &str, end: &str, section: &str) -> Option<String> {
+    let from = body.find(start)?;
+    let to = from + body[from..].find(end)? + end.len();
+    let mut merged = body.to_string();
+    merged.replace_range(from..to, section);
+    Some(merged)
+}
+
+/// Put the stack navigation into `body`'s stack section, appending the
+/// section if the body doesn't have one yet.
+fn with_stack_section(body: &str, stack_comment: &str) -> String {
+    let section = format!("{stack_comment}\n{STACK_SECTION_END_MARKER}");
+    merge_section(
+        body,
+        STACK_COMMENT_MARKER,
+        STACK_SECTION_END_MARKER,
+        &section,
+    )
+    .unwrap_or_else(|| {
+        if body.trim().is_empty() {
+            section
+        } else {
+            format!("{}\n\n{section}", body.trim_end())
+        }
+    })
+}
+
+/// Get PR title and body from the branch's tip commit message.
+///
+/// A branch description (`git branch --edit-description` or `rung describe`)
+/// takes the place of the commit body.
+fn get_pr_title_and_body<G: GitOps>(git: &G, branch_name: &str) -> (String, String) {
+    let description = git.branch_description(branch_name);
+    if let Ok(message) = git.branch_commit_message(branch_name) {
+        let mut lines = message.lines();
+        let title = lines.next().unwrap_or("").trim().to_string();
+
+        let body: String = lines
+            .skip_while(|line| line.trim().is_empty())
+            .collect::<Vec<_>>()
+            .join("\n")
+            .trim_end()
+            .to_string();
+
+        if !title.is_empty() {
+            return (title, description.unwrap_or(body));
+        }
+    }
+
+    (generate_title(branch_name), description.unwrap_or_default())
+}
+
+/// Marker to identify rung stack comments, and the start of the stack
+/// section in PR bodies.
+pub const STACK_COMMENT_MARKER: &str = "<!-- rung-stack -->";
+
+/// Marker ending the stack section in PR bodies.
+pub const STACK_SECTION_END_MARKER: &str = "<!-- /rung-stack -->";
 
 /// Generate PR title from branch name.
 fn generate_title(branch_name: &str) -> String {
@@ -423,8 +771,8 @@ fn generate_title(branch_name: &str) -> String {
 /// Traverses parent links with cycle detection until finding a branch
 /// that isn't in the active stack branches.
 fn find_stack_base<'a>(stack: &'a Stack, branch_name: &str, default_branch: &'a str) -> &'a str {
-    let branches = &stack.branches;
-    let Some(start) = branches.iter().find(|b| b.name == branch_name) else {
+    let index = stack.index();
+    let Some(start) = index.get(branch_name) else {
         return default_branch;
     };
 
@@ -437,7 +785,7 @@ fn find_stack_base<'a>(stack: &'a Stack, branch_name: &str, default_branch: &'a
             return default_branch;
         }
         if let Some(ref parent) = current.parent {
-            if let Some(p) = branches.iter().find(|br| &br.name == parent) {
+            if let Some(p) = index.get(parent) {
                 current = p;
             } else {
                 // Parent not in active branches (may be merged or base branch)
@@ -449,29 +797,63 @@ fn find_stack_base<'a>(stack: &'a Stack, branch_name: &str, default_branch: &'a
     }
 }
 
+/// How many PRs on each side of the current one stay visible in a
+/// collapsed stack comment.
+const COLLAPSE_CONTEXT: usize = 2;
+
 /// Generate stack comment for a PR.
-fn generate_stack_comment(stack: &Stack, current_pr: u64, default_branch: &str) -> String {
+///
+/// When the stack has more than `collapse_above` PRs, only the current
+/// PR's neighborhood is listed outright and the rest is folded into
+/// `<details>` blocks, so deep stacks stay readable.
+fn generate_stack_comment(
+    stack: &Stack,
+    current_pr: u64,
+    default_branch: &str,
+    collapse_above: usize,
+) -> String {
     let mut comment = String::from(STACK_COMMENT_MARKER);
     comment.push('\n');
 
-    let branches = &stack.branches;
-    let current_branch = branches.iter().find(|b| b.pr == Some(current_pr));
+    let index = stack.index();
+    let current_branch = stack.branches.iter().find(|b| b.pr == Some(current_pr));
     let current_name = current_branch.map_or("", |b| b.name.as_str());
 
     let chain = build_branch_chain(stack, current_name);
 
+    let mut current = 0;
+    let mut lines = vec![];
     for branch_name in chain.iter().rev() {
         let is_current = branch_name == current_name;
         let pointer = if is_current { " 👈" } else { "" };
 
-        if let Some(merged) = stack.find_merged(branch_name) {
-            let _ = writeln!(comment, "* ~~**#{}**~~ ✓{pointer}", merged.pr);
-        } else if let Some(b) = branches.iter().find(|b| &b.name == branch_name) {
-            if let Some(pr_num) = b.pr {
-                let _ = writeln!(comment, "* **#{pr_num}**{pointer}");
-            } else {
-                let _ = writeln!(comment, "* *(pending)* `{branch_name}`{pointer}");
-            }
+        let line = if let Some(merged) = stack.find_merged(branch_name) {
+            format!("* ~~**#{}**~~ ✓{pointer}", merged.pr)
+        } else if let Some(b) = index.get(branch_name) {
+            b.pr.map_or_else(
+                || format!("* *(pending)* `{branch_name}`{pointer}"),
+                |pr_num| format!("* **#{pr_num}**{pointer}"),
+            )
+        } else {
+            continue;
+        };
+        if is_current {
+            current = lines.len();
+        }
+        lines.push(line);
+    }
+
+    if lines.len() > collapse_above {
+        let start = current.saturating_sub(COLLAPSE_CONTEXT);
+        let end = (current + COLLAPSE_CONTEXT + 1).min(lines.len());
+        push_collapsed(&mut comment, &lines[..start], "above");
+        for line in &lines[start..end] {
+            let _ = writeln!(comment, "{line}");
+        }
+        push_collapsed(&mut comment, &lines[end..], "below");
+    } else {
+        for line in &lines {
+            let _ = writeln!(comment, "{line}");
         }
     }
 
@@ -482,35 +864,53 @@ fn generate_stack_comment(stack: &Stack, current_pr: u64, default_branch: &str)
     comment
 }
 
+/// Fold stack comment lines into a collapsed `<details>` block.
+fn push_collapsed(comment: &mut String, lines: &[String], position: &str) {
+    if lines.is_empty() {
+        return;
+    }
+    let plural = if lines.len() == 1 { "" } else { "s" };
+    let _ = writeln!(
+        comment,
+        "<details>\n<summary>{} more PR{plural} {position}</summary>\n",
+        lines.len()
+    );
+    for line in lines {
+        let _ = writeln!(comment, "{line}");
+    }
+    let _ = writeln!(comment, "\n</details>\n");
+}
+
 /// Build a chain of branches from root ancestor to all descendants.
 fn build_branch_chain(stack: &Stack, current_name: &str) -> Vec<String> {
-    let branches = &stack.branches;
-    let mut ancestors: Vec<String> = vec![];
-    let mut current = current_name.to_string();
+    let index = stack.index();
+    let merged: HashMap<&str, Option<&str>> = stack
+        .merged
+        .iter()
+        .map(|m| (m.name.as_str(), m.parent.as_deref()))
+        .collect();
+
+    let mut ancestors: Vec<&str> = vec![];
+    let mut current = current_name;
     let mut visited = HashSet::new();
 
     loop {
         // Cycle detection: if we've seen this branch, stop
-        if !visited.insert(current.clone()) {
+        if !visited.insert(current) {
             break;
         }
 
-        let parent = branches
-            .iter()
-            .find(|b| b.name == current)
-            .and_then(|b| b.parent.as_ref())
-            .or_else(|| stack.find_merged(&current).and_then(|m| m.parent.as_ref()))
-            .map(ToString::to_string);
+        let parent = index.get(current).map_or_else(
+            || merged.get(current).copied().flatten(),
+            |b| b.parent.as_deref(),
+        );
 
         let Some(parent_name) = parent else {
             break;
         };
 
-        let in_active = branches.iter().any(|b| b.name == parent_name);
-        let in_merged = stack.find_merged(&parent_name).is_some();
-
-        if in_active || in_merged {
-            ancestors.push(parent_name.clone());
+        if index.contains(parent_name) || merged.contains_key(parent_name) {
+            ancestors.push(parent_name);
             current = parent_name;
         } else {
             break;
@@ -520,22 +920,20 @@ fn build_branch_chain(stack: &Stack, current_name: &str) -> Vec<String> {
     ancestors.reverse();
 
     let mut chain = ancestors;
-    chain.push(current_name.to_string());
+    chain.push(current_name);
+    let mut seen: HashSet<&str> = chain.iter().copied().collect();
 
     let mut i = 0;
     while i < chain.len() {
-        let parent_name = chain[i].clone();
-        for branch in branches {
-            if branch.parent.as_ref().is_some_and(|p| p == &parent_name)
-                && !chain.contains(&branch.name.to_string())
-            {
-                chain.push(branch.name.to_string());
+        for child in index.children_of(chain[i]) {
+            if seen.insert(child.name.as_str()) {
+                chain.push(child.name.as_str());
             }
         }
         i += 1;
     }
 
-    chain
+    chain.into_iter().map(String::from).collect()
 }
 
 /// Sort branches topologically so parents come before children.
@@ -651,6 +1049,8 @@ mod tests {
                     pr_number: 1,
                     pr_url: "url".into(),
                     base: "main".into(),
+                    title: None,
+                    body: None,
                 },
                 PlannedBranchAction::Create {
                     branch: "c".into(),
@@ -731,6 +1131,8 @@ mod tests {
             pr_number: 123,
             pr_url: "https://github.com/owner/repo/pull/123".into(),
             base: "main".into(),
+            title: None,
+            body: None,
         };
         assert!(matches!(
             action,
@@ -955,7 +1357,7 @@ mod tests {
             b.pr = Some(42);
         }
 
-        let comment = generate_stack_comment(&stack, 42, "main");
+        let comment = generate_stack_comment(&stack, 42, "main", 8);
         assert!(comment.contains(STACK_COMMENT_MARKER));
         assert!(comment.contains("#42"));
         assert!(comment.contains("main"));
@@ -990,12 +1392,55 @@ mod tests {
             b.pr = Some(20);
         }
 
-        let comment = generate_stack_comment(&stack, 20, "main");
+        let comment = generate_stack_comment(&stack, 20, "main", 8);
         assert!(comment.contains("#10"));
         assert!(comment.contains("#20"));
         assert!(comment.contains("👈")); // Current PR marker
     }
 
+    #[test]
+    #[allow(clippy::expect_used)]
+    fn test_generate_stack_comment_collapses_deep_stacks() {
+        use rung_core::{Stack, stack::StackBranch};
+
+        // main ← b1 ← ... ← b10, PRs #1 to #10
+        let mut stack = Stack::default();
+        for i in 1..=10 {
+            let parent = (i > 1).then(|| format!("b{}", i - 1));
+            let mut branch = StackBranch::try_new(format!("b{i}"), parent).expect("valid");
+            branch.pr = Some(i);
+            stack.add_branch(branch);
+        }
+
+        let comment = generate_stack_comment(&stack, 5, "main", 8);
+        let visible: Vec<&str> = comment
+            .split("</details>")
+            .nth(1)
+            .and_then(|rest| rest.split("<details>").next())
+            .expect("collapsed above")
+            .lines()
+            .filter(|l| l.starts_with("* **"))
+            .collect();
+        assert_eq!(
+            visible,
+            [
+                "* **#7**",
+                "* **#6**",
+                "* **#5** 👈",
+                "* **#4**",
+                "* **#3**"
+            ]
+        );
+        assert!(comment.contains("<summary>3 more PRs above</summary>"));
+        assert!(comment.contains("<summary>2 more PRs below</summary>"));
+        assert!(comment.contains("* `main`"));
+
+        // Under the threshold the list stays flat
+        let flat = generate_stack_comment(&stack, 5, "main", 10);
+        assert!(!flat.contains("<details>"));
+        assert!(flat.contains("* **#10**"));
+    }
+
     #[test]
     fn test_submit_plan_all_updates() {
         let plan = SubmitPlan {
@@ -1005,12 +1450,16 @@ mod tests {
                     pr_number: 1,
                     pr_url: "url1".into(),
                     base: "main".into(),
+                    title: None,
+                    body: None,
                 },
                 PlannedBranchAction::Update {
                     branch: "b".into(),
                     pr_number: 2,
                     pr_url: "url2".into(),
                     base: "a".into(),
+                    title: None,
+                    body: None,
                 },
             ],
         };
@@ -1052,13 +1501,63 @@ mod tests {
         assert!(STACK_COMMENT_MARKER.contains("rung"));
     }
 
+    #[test]
+    fn test_merge_section_keeps_surrounding_text() {
+        let body = "Before\n<!-- a -->\nold\n<!-- /a -->\nAfter";
+        assert_eq!(
+            merge_section(
+                body,
+                "<!-- a -->",
+                "<!-- /a -->",
+                "<!-- a -->\nnew\n<!-- /a -->"
+            ),
+            Some("Before\n<!-- a -->\nnew\n<!-- /a -->\nAfter".to_string())
+        );
+        assert_eq!(
+            merge_section("No markers", "<!-- a -->", "<!-- /a -->", "x"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_with_stack_section_appends_then_replaces() {
+        let first = with_stack_section(
+            "Hand-written description\n",
+            &format!("{STACK_COMMENT_MARKER}\n* **#1**"),
+        );
+        assert_eq!(
+            first,
+            format!(
+                "Hand-written description\n\n{STACK_COMMENT_MARKER}\n* **#1**\n{STACK_SECTION_END_MARKER}"
+            )
+        );
+
+        // A later update only rewrites the section, keeping edits around it
+        let edited = format!("{first}\n\nNotes added later");
+        let second = with_stack_section(
+            &edited,
+            &format!("{STACK_COMMENT_MARKER}\n* **#1**\n* **#2**"),
+        );
+        assert_eq!(
+            second,
+            format!(
+                "Hand-written description\n\n{STACK_COMMENT_MARKER}\n* **#1**\n* **#2**\n{STACK_SECTION_END_MARKER}\n\nNotes added later"
+            )
+        );
+
+        assert_eq!(
+            with_stack_section("", STACK_COMMENT_MARKER),
+            format!("{STACK_COMMENT_MARKER}\n{STACK_SECTION_END_MARKER}")
+        );
+    }
+
     // Tests using mock implementations
     #[allow(clippy::manual_async_fn, clippy::unwrap_used)]
     mod mock_tests {
         use super::*;
-        use crate::services::test_mocks::MockGitOps;
         use rung_core::stack::{Stack, StackBranch};
         use rung_git::Oid;
+        use rung_git::testing::MockGitOps;
 
         // Mock ForgeApi for submit testing
         struct MockGitHubClient {
@@ -1112,6 +1611,16 @@ mod tests {
                 async move { Ok(result) }
             }
 
+            fn find_open_prs_for_branch(
+                &self,
+                _repo: &rung_github::RepoId,
+                _branch: &str,
+            ) -> impl std::future::Future<
+                Output = rung_github::Result<Vec<rung_github::PullRequest>>,
+            > + Send {
+                async { Ok(vec![]) }
+            }
+
             fn create_pr(
                 &self,
                 _repo: &rung_github::RepoId,
@@ -1129,6 +1638,8 @@ mod tests {
                         html_url: "https://github.com/test/repo/pull/100".to_string(),
                         mergeable: None,
                         mergeable_state: None,
+                        created_at: None,
+                        last_reviewed_at: None,
                         draft: params.draft,
                     })
                 }
@@ -1152,6 +1663,8 @@ mod tests {
                         html_url: format!("https://github.com/test/repo/pull/{number}"),
                         mergeable: None,
                         mergeable_state: None,
+                        created_at: None,
+                        last_reviewed_at: None,
                         draft: false,
                     })
                 }
@@ -1166,6 +1679,15 @@ mod tests {
                 async { Ok(vec![]) }
             }
 
+            fn set_commit_status(
+                &self,
+                _repo: &rung_github::RepoId,
+                _commit_sha: &str,
+                _status: rung_github::CommitStatus,
+            ) -> impl std::future::Future<Output = rung_github::Result<()>> + Send {
+                async { Ok(()) }
+            }
+
             fn merge_pr(
                 &self,
                 _repo: &rung_github::RepoId,
@@ -1197,6 +1719,15 @@ mod tests {
                 async { Ok("main".to_string()) }
             }
 
+            fn get_merge_methods(
+                &self,
+                _repo: &rung_github::RepoId,
+            ) -> impl std::future::Future<
+                Output = rung_github::Result<Vec<rung_github::MergeMethod>>,
+            > + Send {
+                async { Ok(vec![rung_github::MergeMethod::Squash]) }
+            }
+
             fn list_pr_comments(
                 &self,
                 _repo: &rung_github::RepoId,
@@ -1248,9 +1779,12 @@ mod tests {
             let stack = Stack::default();
             let config = SubmitConfig {
                 draft: false,
+                draft_branches: vec![],
                 custom_title: None,
                 current_branch: None,
                 default_branch: "main".to_string(),
+                update_titles: false,
+                update_bodies: false,
             };
 
             let plan = service.create_plan(&stack, &config).await.unwrap();
@@ -1272,9 +1806,12 @@ mod tests {
 
             let config = SubmitConfig {
                 draft: false,
+                draft_branches: vec![],
                 custom_title: None,
                 current_branch: None,
                 default_branch: "main".to_string(),
+                update_titles: false,
+                update_bodies: false,
             };
 
             let plan = service.create_plan(&stack, &config).await.unwrap();
@@ -1299,9 +1836,12 @@ mod tests {
 
             let config = SubmitConfig {
                 draft: false,
+                draft_branches: vec![],
                 custom_title: None,
                 current_branch: None,
                 default_branch: "main".to_string(),
+                update_titles: false,
+                update_bodies: false,
             };
 
             let plan = service.create_plan(&stack, &config).await.unwrap();
@@ -1324,9 +1864,12 @@ mod tests {
 
             let config = SubmitConfig {
                 draft: true,
+                draft_branches: vec![],
                 custom_title: None,
                 current_branch: None,
                 default_branch: "main".to_string(),
+                update_titles: false,
+                update_bodies: false,
             };
 
             let plan = service.create_plan(&stack, &config).await.unwrap();
@@ -1340,17 +1883,175 @@ mod tests {
         }
 
         #[tokio::test]
-        async fn test_get_pr_title_and_body() {
+        async fn test_create_plan_with_draft_branches() {
             let oid = Oid::zero();
-            let git = MockGitOps::new().with_branch("feature/test", oid);
+            let git = MockGitOps::new()
+                .with_branch("main", oid)
+                .with_branch("feature/a", oid)
+                .with_branch("feature/b", oid);
             let github = MockGitHubClient::new();
 
             let service = SubmitService::new(&git, &github, RepoId::new("owner/repo"));
 
+            let mut stack = Stack::default();
+            stack.add_branch(StackBranch::try_new("feature/a", None::<&str>).unwrap());
+            stack.add_branch(StackBranch::try_new("feature/b", Some("feature/a")).unwrap());
+
+            let config = SubmitConfig {
+                draft: false,
+                draft_branches: vec!["feature/b".to_string()],
+                custom_title: None,
+                current_branch: None,
+                default_branch: "main".to_string(),
+                update_titles: false,
+                update_bodies: false,
+            };
+
+            let plan = service.create_plan(&stack, &config).await.unwrap();
+            let drafts: Vec<(&str, bool)> = plan
+                .actions
+                .iter()
+                .filter_map(|action| match action {
+                    PlannedBranchAction::Create { branch, draft, .. } => {
+                        Some((branch.as_str(), *draft))
+                    }
+                    PlannedBranchAction::Update { .. } => None,
+                })
+                .collect();
+            assert_eq!(drafts, vec![("feature/a", false), ("feature/b", true)]);
+        }
+
+        fn refresh_config(update_titles: bool, update_bodies: bool) -> SubmitConfig<'static> {
+            SubmitConfig {
+                draft: false,
+                draft_branches: vec![],
+                custom_title: None,
+                current_branch: None,
+                default_branch: "main".to_string(),
+                update_titles,
+                update_bodies,
+            }
+        }
+
+        fn pr_with(title: &str, body: &str) -> rung_github::PullRequest {
+            rung_github::PullRequest {
+                number: 1,
+                title: title.to_string(),
+                body: Some(body.to_string()),
+                state: rung_github::PullRequestState::Open,
+                base_branch: "main".to_string(),
+                head_branch: "feature".to_string(),
+                html_url: "https://github.com/owner/repo/pull/1".to_string(),
+                mergeable: None,
+                mergeable_state: None,
+                created_at: None,
+                last_reviewed_at: None,
+                draft: false,
+            }
+        }
+
+        #[test]
+        fn test_refresh_rewrites_managed_title_and_body() {
+            let git = MockGitOps::new().with_branch("feature", Oid::zero());
+            let body = format!(
+                "Intro by hand\n{}",
+                managed_body("Old title", "Old description")
+            );
+            let pr = pr_with("Old title", &body);
+
+            let (title, new_body) =
+                refreshed_title_and_body(&git, "feature", &pr, &refresh_config(true, true));
+            assert_eq!(title.as_deref(), Some("Test commit message"));
+            let new_body = new_body.unwrap();
+            assert!(new_body.starts_with("Intro by hand\n"));
+            assert!(new_body.contains("<!-- rung-title: Test commit message -->"));
+            assert!(!new_body.contains("Old description"));
+            assert!(new_body.ends_with(&format!("{BODY_START_MARKER}\n{BODY_END_MARKER}")));
+        }
+
+        #[test]
+        fn test_refresh_respects_manual_edits() {
+            let git = MockGitOps::new().with_branch("feature", Oid::zero());
+
+            // A title changed on the forge no longer matches the recorded one
+            let pr = pr_with("Hand-written title", &managed_body("Old title", ""));
+            let (title, body) =
+                refreshed_title_and_body(&git, "feature", &pr, &refresh_config(true, false));
+            assert_eq!(title, None);
+            assert_eq!(body, None);
+
+            // A body without markers is left alone
+            let pr = pr_with("Old title", "Written entirely by hand");
+            let (title, body) =
+                refreshed_title_and_body(&git, "feature", &pr, &refresh_config(true, true));
+            assert_eq!(title, None);
+            assert_eq!(body, None);
+        }
+
+        #[test]
+        fn test_refresh_only_what_was_asked() {
+            let git = MockGitOps::new().with_branch("feature", Oid::zero());
+            let pr = pr_with("Old title", &managed_body("Old title", "Old description"));
+
+            let (title, body) =
+                refreshed_title_and_body(&git, "feature", &pr, &refresh_config(false, true));
+            assert_eq!(title, None);
+            let body = body.unwrap();
+            assert!(body.contains("<!-- rung-title: Old title -->"));
+            assert!(!body.contains("Old description"));
+        }
+
+        #[test]
+        fn test_get_pr_title_and_body() {
+            let oid = Oid::zero();
+            let git = MockGitOps::new().with_branch("feature/test", oid);
+
             // MockGitOps returns "Test commit message" for branch_commit_message
-            let (title, body) = service.get_pr_title_and_body("feature/test");
+            let (title, body) = get_pr_title_and_body(&git, "feature/test");
             assert_eq!(title, "Test commit message");
             assert!(body.is_empty());
+
+            let git = git.with_branch_description("feature/test", "Why this change\n\nDetails");
+            let (title, body) = get_pr_title_and_body(&git, "feature/test");
+            assert_eq!(title, "Test commit message");
+            assert_eq!(body, "Why this change\n\nDetails");
+        }
+
+        #[test]
+        fn test_create_offline_plan_uses_recorded_prs() {
+            let oid = Oid::zero();
+            let git = MockGitOps::new()
+                .with_branch("feature/a", oid)
+                .with_branch("feature/b", oid);
+
+            let mut stack = Stack::default();
+            let mut a = StackBranch::try_new("feature/a", None::<&str>).unwrap();
+            a.pr = Some(7);
+            stack.add_branch(a);
+            stack.add_branch(StackBranch::try_new("feature/b", Some("feature/a")).unwrap());
+
+            let config = SubmitConfig {
+                draft: false,
+                draft_branches: vec![],
+                custom_title: None,
+                current_branch: None,
+                default_branch: "main".to_string(),
+                update_titles: false,
+                update_bodies: false,
+            };
+            let plan =
+                create_offline_plan(&git, &stack, &config, &RepoId::new("owner/repo")).unwrap();
+
+            assert_eq!(plan.count_updates(), 1);
+            assert_eq!(plan.count_creates(), 1);
+            assert!(matches!(
+                &plan.actions[0],
+                PlannedBranchAction::Update { pr_number: 7, base, .. } if base == "main"
+            ));
+            assert!(matches!(
+                &plan.actions[1],
+                PlannedBranchAction::Create { base, .. } if base == "feature/a"
+            ));
         }
 
         #[test]
@@ -1397,6 +2098,78 @@ mod tests {
             assert_eq!(stack.branches[0].pr, Some(100));
         }
 
+        #[tokio::test]
+        async fn test_execute_links_new_prs_to_parent() {
+            let oid = Oid::zero();
+            let git = MockGitOps::new()
+                .with_branch("main", oid)
+                .with_branch("a", oid)
+                .with_branch("b", oid)
+                .with_push_result("a", true)
+                .with_push_result("b", true);
+            let github = rung_github::testing::MockGitHubClient::new();
+            let service = SubmitService::new(&git, &github, RepoId::new("owner/repo"));
+
+            let mut stack = Stack::default();
+            stack.add_branch(StackBranch::try_new("a", None::<&str>).unwrap());
+            stack.add_branch(StackBranch::try_new("b", Some("a")).unwrap());
+            let create = |branch: &str, base: &str| PlannedBranchAction::Create {
+                branch: branch.to_string(),
+                title: branch.to_string(),
+                body: "Description".to_string(),
+                base: base.to_string(),
+                draft: false,
+            };
+            let plan = SubmitPlan {
+                actions: vec![create("a", "main"), create("b", "a")],
+            };
+
+            service.execute(&mut stack, &plan, false).await.unwrap();
+
+            let body = |number| github.pr(number).unwrap().body.unwrap();
+            assert_eq!(body(1), "Description");
+            assert_eq!(body(2), "Description\n\nDepends on #1");
+        }
+
+        #[tokio::test]
+        async fn test_publish_stack_statuses() {
+            let oid = |n: u64| Oid::from_str(&format!("{n:040x}")).unwrap();
+            let git = MockGitOps::new()
+                .with_branch("a", oid(1))
+                .with_branch("b", oid(2))
+                .with_branch("c", oid(3));
+            let github = rung_github::testing::MockGitHubClient::new();
+            let service = SubmitService::new(&git, &github, RepoId::new("owner/repo"));
+
+            // main ← a (#1) ← b (#2) ← c (no PR yet)
+            let mut stack = Stack::default();
+            for (name, parent, pr) in [("a", None, Some(1)), ("b", Some("a"), Some(2))] {
+                let mut branch = StackBranch::try_new(name, parent).unwrap();
+                branch.pr = pr;
+                stack.add_branch(branch);
+            }
+            stack.add_branch(StackBranch::try_new("c", Some("b")).unwrap());
+
+            let c = stack_status(&stack, "c");
+            assert_eq!(c.state, CommitState::Pending);
+            assert_eq!(c.description, "Part of stack: 3 of 3, parent #2 not merged");
+
+            service.publish_stack_statuses(&stack).await.unwrap();
+
+            let a = github.statuses(&oid(1).to_string());
+            assert_eq!(a.len(), 1);
+            assert_eq!(a[0].state, CommitState::Success);
+            assert_eq!(a[0].description, "Part of stack: 1 of 3");
+            assert_eq!(a[0].context, "rung/stack");
+            let b = github.statuses(&oid(2).to_string());
+            assert_eq!(b[0].state, CommitState::Pending);
+            assert_eq!(
+                b[0].description,
+                "Part of stack: 2 of 3, parent #1 not merged"
+            );
+            assert!(github.statuses(&oid(3).to_string()).is_empty());
+        }
+
         #[tokio::test]
         async fn test_execute_update_pr() {
             let oid = Oid::zero();
@@ -1419,6 +2192,8 @@ mod tests {
                     pr_number: 42,
                     pr_url: "https://github.com/owner/repo/pull/42".to_string(),
                     base: "main".to_string(),
+                    title: None,
+                    body: None,
                 }],
             };
 
@@ -1456,6 +2231,8 @@ mod tests {
                         pr_number: 10,
                         pr_url: "https://github.com/owner/repo/pull/10".to_string(),
                         base: "main".to_string(),
+                        title: None,
+                        body: None,
                     },
                     PlannedBranchAction::Create {
                         branch: "feature/b".to_string(),
@@ -11,7 +11,7 @@ use rung_core::stack::Stack;
 use rung_core::sync::{
     self, ExternalMergeInfo, ReconcileResult, ReparentedBranch, StaleBranches, SyncPlan, SyncResult,
 };
-use rung_git::GitOps;
+use rung_git::{GitOps, RemoteDivergence};
 use rung_github::{ForgeApi, PullRequestState, RepoId, UpdatePullRequest};
 
 /// Threshold for switching from individual REST calls to batched GraphQL query.
@@ -231,7 +231,10 @@ impl<'a, G: GitOps, H: ForgeApi> SyncService<'a, G, H> {
     }
 
     /// Update GitHub PR base branches for reparented and repaired branches.
-    pub async fn update_pr_bases(&self, reconcile_result: &ReconcileResult) -> Result<()> {
+    ///
+    /// Returns the PRs whose base was changed; PRs already on the right base
+    /// are skipped and failures only warn.
+    pub async fn update_pr_bases(&self, reconcile_result: &ReconcileResult) -> Result<Vec<u64>> {
         // Collect all PRs that need updating
         let updates_needed: Vec<_> = reconcile_result
             .reparented
@@ -244,7 +247,7 @@ impl<'a, G: GitOps, H: ForgeApi> SyncService<'a, G, H> {
             .collect();
 
         if updates_needed.is_empty() {
-            return Ok(());
+            return Ok(vec![]);
         }
 
         // Re-fetch current PR states to implement no-op check
@@ -263,6 +266,7 @@ impl<'a, G: GitOps, H: ForgeApi> SyncService<'a, G, H> {
         };
 
         // Apply updates with no-op check
+        let mut updated = Vec::new();
         for (pr_number, new_base, _old_base) in updates_needed {
             // No-op check: skip if PR base is already what we want
             if let Some(current_base) = current_states.get(&pr_number)
@@ -283,10 +287,12 @@ impl<'a, G: GitOps, H: ForgeApi> SyncService<'a, G, H> {
                 .await
             {
                 eprintln!("Warning: Failed to update PR #{pr_number} base to '{new_base}': {e}");
+            } else {
+                updated.push(pr_number);
             }
         }
 
-        Ok(())
+        Ok(updated)
     }
 
     /// Fetch current base branches for a list of PRs individually.
@@ -300,12 +306,80 @@ impl<'a, G: GitOps, H: ForgeApi> SyncService<'a, G, H> {
         result
     }
 
-    /// Push all branches in the stack to remote.
-    pub fn push_stack_branches<S: StateStore>(&self, state: &S) -> Result<Vec<PushInfo>> {
+    /// Find stack branches whose remote branch was deleted.
+    ///
+    /// A branch counts as deleted when it was pushed at some point but no
+    /// longer exists on the remote, e.g. after the forge auto-deleted it on
+    /// merge: either its remote-tracking ref is stale, or it was already
+    /// pruned and only the upstream config is left.
+    pub fn find_deleted_remote_branches<S: StateStore>(&self, state: &S) -> Result<Vec<String>> {
+        let stack = state.load_stack()?;
+        if stack.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let remote = self.repo.remote_branches()?;
+        Ok(stack
+            .branches
+            .iter()
+            .filter(|b| self.repo.branch_exists(&b.name))
+            .filter(|b| match self.repo.remote_divergence(&b.name) {
+                Ok(RemoteDivergence::Gone) => true,
+                Ok(RemoteDivergence::NoRemote) | Err(_) => false,
+                Ok(_) => !remote.iter().any(|r| r == b.name.as_str()),
+            })
+            .map(|b| b.name.to_string())
+            .collect())
+    }
+
+    /// Push a branch whose remote was deleted, recreating it.
+    ///
+    /// Uses a plain push because the lease would be checked against the
+    /// stale tracking ref of the deleted branch.
+    pub fn repush_branch(&self, branch: &str) -> Result<()> {
+        self.repo.push(branch, false)?;
+        Ok(())
+    }
+
+    /// Remove a branch from the stack, re-parenting its children onto its parent.
+    ///
+    /// The local git branch is left untouched.
+    #[allow(clippy::unused_self)]
+    pub fn untrack_branch<S: StateStore>(&self, state: &S, branch: &str) -> Result<()> {
+        let mut stack = state.load_stack()?;
+        let parent = stack
+            .find_branch(branch)
+            .ok_or_else(|| anyhow::anyhow!("Branch '{branch}' is not in the stack"))?
+            .parent
+            .as_ref()
+            .map(ToString::to_string);
+
+        let children: Vec<String> = stack
+            .children_of(branch)
+            .iter()
+            .map(|b| b.name.to_string())
+            .collect();
+        for child in &children {
+            stack.reparent(child, parent.as_deref())?;
+        }
+        stack.remove_branch(branch);
+        state.save_stack(&stack)?;
+        Ok(())
+    }
+
+    /// Push all branches in the stack to remote, except those in `skip`.
+    pub fn push_stack_branches<S: StateStore>(
+        &self,
+        state: &S,
+        skip: &[String],
+    ) -> Result<Vec<PushInfo>> {
         let stack = state.load_stack()?;
         let mut results = Vec::new();
 
         for branch in &stack.branches {
+            if skip.iter().any(|s| s == branch.name.as_str()) {
+                continue;
+            }
             if self.repo.branch_exists(&branch.name) {
                 match self.repo.push(&branch.name, true) {
                     Ok(()) => {
@@ -389,6 +463,8 @@ mod tests {
             html_url: "https://github.com/test/test/pull/42".to_string(),
             mergeable: None,
             mergeable_state: None,
+            created_at: None,
+            last_reviewed_at: None,
             draft: false,
         };
 
@@ -424,6 +500,8 @@ mod tests {
             html_url: "https://github.com/test/test/pull/43".to_string(),
             mergeable: Some(true),
             mergeable_state: None,
+            created_at: None,
+            last_reviewed_at: None,
             draft: false,
         };
 
@@ -456,6 +534,8 @@ mod tests {
             html_url: "https://github.com/test/test/pull/44".to_string(),
             mergeable: Some(true),
             mergeable_state: None,
+            created_at: None,
+            last_reviewed_at: None,
             draft: false,
         };
 
@@ -495,6 +575,8 @@ mod tests {
             html_url: "https://github.com/test/test/pull/45".to_string(),
             mergeable: Some(true),
             mergeable_state: None,
+            created_at: None,
+            last_reviewed_at: None,
             draft: false,
         };
 
@@ -529,6 +611,8 @@ mod tests {
             html_url: "https://github.com/test/test/pull/46".to_string(),
             mergeable: None,
             mergeable_state: None,
+            created_at: None,
+            last_reviewed_at: None,
             draft: false,
         };
 
@@ -564,6 +648,8 @@ mod tests {
             html_url: "https://github.com/test/test/pull/47".to_string(),
             mergeable: None,
             mergeable_state: None,
+            created_at: None,
+            last_reviewed_at: None,
             draft: false,
         };
 
@@ -627,9 +713,10 @@ mod tests {
     #[allow(clippy::manual_async_fn, clippy::unwrap_used)]
     mod mock_tests {
         use super::super::*;
-        use crate::services::test_mocks::{MockGitOps, MockStateStore};
         use rung_core::stack::{Stack, StackBranch};
+        use rung_core::testing::MockStateStore;
         use rung_git::Oid;
+        use rung_git::testing::MockGitOps;
 
         // Mock ForgeApi for testing
         struct MockGitHubClient;
@@ -666,6 +753,16 @@ mod tests {
                 async { Ok(None) }
             }
 
+            fn find_open_prs_for_branch(
+                &self,
+                _repo: &rung_github::RepoId,
+                _branch: &str,
+            ) -> impl std::future::Future<
+                Output = rung_github::Result<Vec<rung_github::PullRequest>>,
+            > + Send {
+                async { Ok(vec![]) }
+            }
+
             fn create_pr(
                 &self,
                 _repo: &rung_github::RepoId,
@@ -694,6 +791,15 @@ mod tests {
                 async { Ok(vec![]) }
             }
 
+            fn set_commit_status(
+                &self,
+                _repo: &rung_github::RepoId,
+                _commit_sha: &str,
+                _status: rung_github::CommitStatus,
+            ) -> impl std::future::Future<Output = rung_github::Result<()>> + Send {
+                async { Ok(()) }
+            }
+
             fn merge_pr(
                 &self,
                 _repo: &rung_github::RepoId,
@@ -725,6 +831,15 @@ mod tests {
                 async { Ok("main".to_string()) }
             }
 
+            fn get_merge_methods(
+                &self,
+                _repo: &rung_github::RepoId,
+            ) -> impl std::future::Future<
+                Output = rung_github::Result<Vec<rung_github::MergeMethod>>,
+            > + Send {
+                async { Ok(vec![rung_github::MergeMethod::Squash]) }
+            }
+
             fn list_pr_comments(
                 &self,
                 _repo: &rung_github::RepoId,
@@ -773,7 +888,7 @@ mod tests {
             let client = MockGitHubClient;
 
             let service = SyncService::new(&git, &client, RepoId::new("owner/repo"));
-            let result = service.push_stack_branches(&state).unwrap();
+            let result = service.push_stack_branches(&state, &[]).unwrap();
 
             assert!(result.is_empty());
         }
@@ -793,7 +908,7 @@ mod tests {
             let client = MockGitHubClient;
 
             let service = SyncService::new(&git, &client, RepoId::new("owner/repo"));
-            let result = service.push_stack_branches(&state).unwrap();
+            let result = service.push_stack_branches(&state, &[]).unwrap();
 
             assert_eq!(result.len(), 2);
             assert!(result.iter().all(|r| r.success));
@@ -815,7 +930,7 @@ mod tests {
             let client = MockGitHubClient;
 
             let service = SyncService::new(&git, &client, RepoId::new("owner/repo"));
-            let result = service.push_stack_branches(&state).unwrap();
+            let result = service.push_stack_branches(&state, &[]).unwrap();
 
             assert_eq!(result.len(), 2);
             assert!(result[0].success); // feature/a succeeds
@@ -836,13 +951,112 @@ mod tests {
             let client = MockGitHubClient;
 
             let service = SyncService::new(&git, &client, RepoId::new("owner/repo"));
-            let result = service.push_stack_branches(&state).unwrap();
+            let result = service.push_stack_branches(&state, &[]).unwrap();
 
             // Only feature/a should be pushed (feature/b doesn't exist in git)
             assert_eq!(result.len(), 1);
             assert_eq!(result[0].branch, "feature/a");
         }
 
+        #[test]
+        fn test_find_deleted_remote_branches() {
+            let oid = Oid::zero();
+            let git = MockGitOps::new()
+                .with_branch("feature/a", oid)
+                .with_branch("feature/b", oid)
+                .with_remote_branches(&["feature/a"]);
+
+            let mut stack = Stack::default();
+            stack.add_branch(StackBranch::try_new("feature/a", None::<&str>).unwrap());
+            stack.add_branch(StackBranch::try_new("feature/b", Some("feature/a")).unwrap());
+
+            let state = MockStateStore::new().with_stack(stack);
+            let client = MockGitHubClient;
+
+            let service = SyncService::new(&git, &client, RepoId::new("owner/repo"));
+            let deleted = service.find_deleted_remote_branches(&state).unwrap();
+
+            assert_eq!(deleted, vec!["feature/b".to_string()]);
+        }
+
+        #[test]
+        fn test_find_deleted_remote_branches_after_prune() {
+            let oid = Oid::zero();
+            let git = MockGitOps::new()
+                .with_branch("feature/a", oid)
+                .with_branch("feature/b", oid)
+                .with_branch("feature/c", oid)
+                .with_remote_branches(&["feature/a"]);
+            git.remote_divergence_map
+                .borrow_mut()
+                .insert("feature/b".into(), RemoteDivergence::Gone);
+            git.remote_divergence_map
+                .borrow_mut()
+                .insert("feature/c".into(), RemoteDivergence::NoRemote);
+
+            let mut stack = Stack::default();
+            stack.add_branch(StackBranch::try_new("feature/a", None::<&str>).unwrap());
+            stack.add_branch(StackBranch::try_new("feature/b", Some("feature/a")).unwrap());
+            stack.add_branch(StackBranch::try_new("feature/c", Some("feature/b")).unwrap());
+
+            let state = MockStateStore::new().with_stack(stack);
+            let client = MockGitHubClient;
+
+            let service = SyncService::new(&git, &client, RepoId::new("owner/repo"));
+            let deleted = service.find_deleted_remote_branches(&state).unwrap();
+
+            // feature/c was never pushed
+            assert_eq!(deleted, vec!["feature/b".to_string()]);
+        }
+
+        #[test]
+        fn test_push_stack_branches_skips_listed() {
+            let oid = Oid::zero();
+            let git = MockGitOps::new()
+                .with_branch("feature/a", oid)
+                .with_branch("feature/b", oid);
+
+            let mut stack = Stack::default();
+            stack.add_branch(StackBranch::try_new("feature/a", None::<&str>).unwrap());
+            stack.add_branch(StackBranch::try_new("feature/b", Some("feature/a")).unwrap());
+
+            let state = MockStateStore::new().with_stack(stack);
+            let client = MockGitHubClient;
+
+            let service = SyncService::new(&git, &client, RepoId::new("owner/repo"));
+            let result = service
+                .push_stack_branches(&state, &["feature/a".to_string()])
+                .unwrap();
+
+            assert_eq!(result.len(), 1);
+            assert_eq!(result[0].branch, "feature/b");
+        }
+
+        #[test]
+        fn test_untrack_branch_reparents_children() {
+            let oid = Oid::zero();
+            let git = MockGitOps::new()
+                .with_branch("feature/a", oid)
+                .with_branch("feature/b", oid)
+                .with_branch("feature/c", oid);
+
+            let mut stack = Stack::default();
+            stack.add_branch(StackBranch::try_new("feature/a", None::<&str>).unwrap());
+            stack.add_branch(StackBranch::try_new("feature/b", Some("feature/a")).unwrap());
+            stack.add_branch(StackBranch::try_new("feature/c", Some("feature/b")).unwrap());
+
+            let state = MockStateStore::new().with_stack(stack);
+            let client = MockGitHubClient;
+
+            let service = SyncService::new(&git, &client, RepoId::new("owner/repo"));
+            service.untrack_branch(&state, "feature/b").unwrap();
+
+            let stack = state.load_stack().unwrap();
+            assert!(stack.find_branch("feature/b").is_none());
+            let child = stack.find_branch("feature/c").unwrap();
+            assert_eq!(child.parent.as_deref(), Some("feature/a"));
+        }
+
         #[test]
         fn test_fetch_base_success() {
             let git = MockGitOps::new();
@@ -919,6 +1133,8 @@ mod tests {
                         html_url: format!("https://github.com/test/repo/pull/{number}"),
                         mergeable: Some(true),
                         mergeable_state: None,
+                        created_at: None,
+                        last_reviewed_at: None,
                         draft: false,
                     })
                 }
@@ -957,6 +1173,8 @@ mod tests {
                             html_url: format!("https://github.com/test/repo/pull/{number}"),
                             mergeable: Some(true),
                             mergeable_state: None,
+                            created_at: None,
+                            last_reviewed_at: None,
                             draft: false,
                         },
                     );
@@ -974,6 +1192,16 @@ mod tests {
                 async { Ok(None) }
             }
 
+            fn find_open_prs_for_branch(
+                &self,
+                _repo: &rung_github::RepoId,
+                _branch: &str,
+            ) -> impl std::future::Future<
+                Output = rung_github::Result<Vec<rung_github::PullRequest>>,
+            > + Send {
+                async { Ok(vec![]) }
+            }
+
             fn create_pr(
                 &self,
                 _repo: &rung_github::RepoId,
@@ -1001,6 +1229,8 @@ mod tests {
                         html_url: format!("https://github.com/test/repo/pull/{number}"),
                         mergeable: Some(true),
                         mergeable_state: None,
+                        created_at: None,
+                        last_reviewed_at: None,
                         draft: false,
                     })
                 }
@@ -1015,6 +1245,15 @@ mod tests {
                 async { Ok(vec![]) }
             }
 
+            fn set_commit_status(
+                &self,
+                _repo: &rung_github::RepoId,
+                _commit_sha: &str,
+                _status: rung_github::CommitStatus,
+            ) -> impl std::future::Future<Output = rung_github::Result<()>> + Send {
+                async { Ok(()) }
+            }
+
             fn merge_pr(
                 &self,
                 _repo: &rung_github::RepoId,
@@ -1046,6 +1285,15 @@ mod tests {
                 async { Ok("main".to_string()) }
             }
 
+            fn get_merge_methods(
+                &self,
+                _repo: &rung_github::RepoId,
+            ) -> impl std::future::Future<
+                Output = rung_github::Result<Vec<rung_github::MergeMethod>>,
+            > + Send {
+                async { Ok(vec![rung_github::MergeMethod::Squash]) }
+            }
+
             fn list_pr_comments(
                 &self,
                 _repo: &rung_github::RepoId,
@@ -1208,8 +1456,30 @@ mod tests {
                 repaired: vec![],
             };
 
-            let result = service.update_pr_bases(&reconcile_result).await;
-            assert!(result.is_ok());
+            let updated = service.update_pr_bases(&reconcile_result).await.unwrap();
+            assert_eq!(updated, vec![10]);
+        }
+
+        #[tokio::test]
+        async fn test_update_pr_bases_skips_current_base() {
+            let git = MockGitOps::new();
+            let client = ConfigurableMockGitHubClient::new().with_pr_base(10, "main");
+
+            let service = SyncService::new(&git, &client, RepoId::new("owner/repo"));
+
+            let reconcile_result = rung_core::sync::ReconcileResult {
+                merged: vec![],
+                reparented: vec![rung_core::sync::ReparentedBranch {
+                    name: "feature/a".to_string(),
+                    old_parent: "old-parent".to_string(),
+                    new_parent: "main".to_string(),
+                    pr_number: Some(10),
+                }],
+                repaired: vec![],
+            };
+
+            let updated = service.update_pr_bases(&reconcile_result).await.unwrap();
+            assert!(updated.is_empty());
         }
     }
 }
@@ -0,0 +1,290 @@
+//! Opt-in anonymous usage telemetry.
+//!
+//! Off unless the user runs `rung telemetry on`. When enabled, each command
+//! records its name, duration, and outcome (plus an error class such as
+//! `RebaseConflict` on failure) - never branch names, paths, remotes, or
+//! error messages. Events are queued in the user config directory and sent
+//! in batches to the endpoint baked into release builds (or set with
+//! `RUNG_TELEMETRY_URL`); without an endpoint they stay local.
+
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use crate::commands::Commands;
+use crate::output;
+
+/// Endpoint events are sent to, if any.
+const DEFAULT_ENDPOINT: Option<&str> = option_env!("RUNG_TELEMETRY_URL");
+
+/// Queued events are sent once this many have accumulated.
+const BATCH_SIZE: usize = 20;
+
+/// The queue is trimmed to this many events when nothing is sending them.
+const MAX_QUEUED: usize = 1000;
+
+/// How long sending a batch may hold up the command.
+const SEND_TIMEOUT: Duration = Duration::from_secs(2);
+
+const SETTINGS_FILE: &str = "telemetry.json";
+const QUEUE_FILE: &str = "telemetry-events.jsonl";
+
+/// Persisted telemetry choice.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct Settings {
+    pub enabled: bool,
+}
+
+/// A single recorded command run.
+#[derive(Debug, Serialize, Deserialize)]
+struct Event {
+    command: String,
+    duration_ms: u128,
+    success: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error_class: Option<String>,
+    version: String,
+    os: String,
+}
+
+/// Directory for user-level rung settings.
+///
+/// `RUNG_CONFIG_DIR` overrides the platform default
+/// (`$XDG_CONFIG_HOME/rung`, `~/.config/rung`, or `%APPDATA%\rung`).
+pub fn config_dir() -> Option<PathBuf> {
+    if let Some(dir) = std::env::var_os("RUNG_CONFIG_DIR") {
+        return Some(PathBuf::from(dir));
+    }
+    std::env::var_os("XDG_CONFIG_HOME")
+        .map(|dir| PathBuf::from(dir).join("rung"))
+        .or_else(|| std::env::var_os("HOME").map(|h| PathBuf::from(h).join(".config/rung")))
+        .or_else(|| std::env::var_os("APPDATA").map(|dir| PathBuf::from(dir).join("rung")))
+}
+
+/// Load the telemetry setting; `None` if the user has never been asked.
+pub fn load_settings() -> Option<Settings> {
+    let content = fs::read_to_string(config_dir()?.join(SETTINGS_FILE)).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+/// Save the telemetry setting.
+///
+/// # Errors
+/// Returns error if the config directory can't be written.
+pub fn save_settings(settings: Settings) -> anyhow::Result<()> {
+    let dir = config_dir().ok_or_else(|| anyhow::anyhow!("No user config directory found"))?;
+    fs::create_dir_all(&dir)?;
+    fs::write(
+        dir.join(SETTINGS_FILE),
+        serde_json::to_string_pretty(&settings)?,
+    )?;
+    Ok(())
+}
+
+/// Path of the local event queue.
+pub fn queue_path() -> Option<PathBuf> {
+    config_dir().map(|dir| dir.join(QUEUE_FILE))
+}
+
+/// Number of events waiting to be sent.
+pub fn queued_events() -> usize {
+    queue_path()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .map_or(0, |content| content.lines().count())
+}
+
+/// Delete any queued events.
+pub fn clear_queue() {
+    if let Some(path) = queue_path() {
+        let _ = fs::remove_file(path);
+    }
+}
+
+/// Where events are sent, if anywhere.
+pub fn endpoint() -> Option<String> {
+    std::env::var("RUNG_TELEMETRY_URL")
+        .ok()
+        .or_else(|| DEFAULT_ENDPOINT.map(String::from))
+        .filter(|url| !url.is_empty())
+}
+
+/// Explain telemetry the first time rung runs, and record that the user saw it.
+///
+/// Telemetry stays off; this only writes the default setting.
+pub fn first_run_notice() {
+    if load_settings().is_some() {
+        return;
+    }
+    if save_settings(Settings::default()).is_ok() {
+        output::notice(
+            "rung can send anonymous usage statistics (command name, duration, error class) \
+             to help prioritize fixes. It is off; run `rung telemetry on` to opt in.",
+        );
+    }
+}
+
+/// Timing for one command run, present only when telemetry is enabled.
+pub struct Session {
+    command: String,
+    started: Instant,
+}
+
+impl Session {
+    /// Start timing `command` if the user opted in.
+    pub fn start(command: &Commands) -> Option<Self> {
+        load_settings().filter(|s| s.enabled).map(|_| Self {
+            command: event_name(command).to_string(),
+            started: Instant::now(),
+        })
+    }
+
+    /// Queue the event, sending the batch once it is full.
+    ///
+    /// Best effort: telemetry never fails or slows down the command.
+    pub fn finish(self, result: &anyhow::Result<()>) {
+        // The command may have been `rung telemetry off`
+        if !load_settings().is_some_and(|s| s.enabled) {
+            return;
+        }
+        let event = Event {
+            command: self.command,
+            duration_ms: self.started.elapsed().as_millis(),
+            success: result.is_ok(),
+            error_class: result.as_ref().err().map(error_class),
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            os: std::env::consts::OS.to_string(),
+        };
+        let _ = queue(&event);
+    }
+}
+
+/// Append an event to the queue and hand off a full batch.
+fn queue(event: &Event) -> anyhow::Result<()> {
+    let path = queue_path().ok_or_else(|| anyhow::anyhow!("No user config directory found"))?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+
+    let mut line = serde_json::to_string(event)?;
+    line.push('\n');
+    fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)?
+        .write_all(line.as_bytes())?;
+
+    let content = fs::read_to_string(&path)?;
+    let events: Vec<&str> = content.lines().collect();
+    if events.len() < BATCH_SIZE {
+        return Ok(());
+    }
+
+    // Unsent batches stay queued and go out with the next one
+    if let Some(url) = endpoint()
+        && !crate::commands::utils::is_offline()
+        && send(&url, format!("[{}]", events.join(",")))
+    {
+        fs::remove_file(&path)?;
+        return Ok(());
+    }
+    if events.len() > MAX_QUEUED {
+        let keep = &events[events.len() - MAX_QUEUED..];
+        fs::write(&path, keep.join("\n") + "\n")?;
+    }
+    Ok(())
+}
+
+/// Post a batch, returning whether the endpoint accepted it.
+fn send(url: &str, body: String) -> bool {
+    reqwest::blocking::Client::builder()
+        .timeout(SEND_TIMEOUT)
+        .build()
+        .and_then(|client| {
+            client
+                .post(url)
+                .header(reqwest::header::CONTENT_TYPE, "application/json")
+                .body(body)
+                .send()
+        })
+        .is_ok_and(|response| response.status().is_success())
+}
+
+/// The command name events report. Plugins all count as `plugin`, since
+/// an external subcommand's name is whatever the user typed.
+fn event_name(command: &Commands) -> &str {
+    match command {
+        Commands::External(_) => "plugin",
+        command => command.name(),
+    }
+}
+
+/// Classify an error by its variant name, without any of its message.
+fn error_class(err: &anyhow::Error) -> String {
+    for cause in err.chain() {
+        if let Some(e) = cause.downcast_ref::<rung_git::Error>() {
+            return variant_name(e);
+        }
+    }
+    for cause in err.chain() {
+        if let Some(e) = cause.downcast_ref::<rung_core::Error>() {
+            return variant_name(e);
+        }
+    }
+    "Other".to_string()
+}
+
+/// The enum variant name from a `Debug` representation.
+fn variant_name(value: &impl std::fmt::Debug) -> String {
+    format!("{value:?}")
+        .chars()
+        .take_while(char::is_ascii_alphanumeric)
+        .collect()
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_class_uses_variant_only() {
+        let err = anyhow::Error::new(rung_git::Error::RebaseConflict(vec![
+            "secret/path.rs".into(),
+        ]));
+        assert_eq!(error_class(&err), "RebaseConflict");
+
+        let err = anyhow::Error::new(rung_core::Error::Git(rung_git::Error::DetachedHead));
+        assert_eq!(error_class(&err), "DetachedHead");
+
+        let err = anyhow::anyhow!("branch feature/secret not found");
+        assert_eq!(error_class(&err), "Other");
+    }
+
+    #[test]
+    fn test_event_name_hides_external_subcommands() {
+        let typo = Commands::External(vec!["stauts".into(), "--secret".into()]);
+        assert_eq!(event_name(&typo), "plugin");
+        assert_eq!(event_name(&Commands::Nxt), "nxt");
+    }
+
+    #[test]
+    fn test_event_has_no_repo_data() {
+        let event = Event {
+            command: "sync".into(),
+            duration_ms: 12,
+            success: true,
+            error_class: None,
+            version: "1.0.0".into(),
+            os: "linux".into(),
+        };
+        let json = serde_json::to_value(&event).unwrap();
+        let keys: Vec<&String> = json.as_object().unwrap().keys().collect();
+        assert_eq!(
+            keys,
+            vec!["command", "duration_ms", "os", "success", "version"]
+        );
+    }
+}
@@ -0,0 +1,97 @@
+//! Benchmarks for stack tree walks on large stacks.
+//!
+//! Run with `cargo bench -p rung-core --bench stack`.
+
+#![allow(clippy::unwrap_used, missing_docs)]
+
+use criterion::{BenchmarkId, Criterion, black_box, criterion_group, criterion_main};
+use rung_core::{Stack, StackBranch};
+
+const SIZES: [usize; 3] = [10, 100, 1000];
+
+/// A single linear chain: `main → b0 → b1 → ... → b{n-1}`.
+fn linear_stack(n: usize) -> Stack {
+    let mut stack = Stack::new();
+    for i in 0..n {
+        let parent = if i == 0 {
+            "main".to_string()
+        } else {
+            format!("b{}", i - 1)
+        };
+        stack.add_branch(StackBranch::try_new(format!("b{i}"), Some(parent)).unwrap());
+    }
+    stack
+}
+
+/// A binary tree rooted on `main`, with branches listed in creation order.
+fn tree_stack(n: usize) -> Stack {
+    let mut stack = Stack::new();
+    for i in 0..n {
+        let parent = if i == 0 {
+            "main".to_string()
+        } else {
+            format!("b{}", (i - 1) / 2)
+        };
+        stack.add_branch(StackBranch::try_new(format!("b{i}"), Some(parent)).unwrap());
+    }
+    stack
+}
+
+fn bench_descendants(c: &mut Criterion) {
+    let mut group = c.benchmark_group("descendants");
+    for n in SIZES {
+        let linear = linear_stack(n);
+        group.bench_with_input(BenchmarkId::new("linear", n), &linear, |b, stack| {
+            b.iter(|| stack.descendants(black_box("b0")).len());
+        });
+        let tree = tree_stack(n);
+        group.bench_with_input(BenchmarkId::new("tree", n), &tree, |b, stack| {
+            b.iter(|| stack.descendants(black_box("b0")).len());
+        });
+    }
+    group.finish();
+}
+
+fn bench_ancestry(c: &mut Criterion) {
+    let mut group = c.benchmark_group("ancestry");
+    for n in SIZES {
+        let stack = linear_stack(n);
+        let tip = format!("b{}", n - 1);
+        group.bench_with_input(BenchmarkId::new("linear", n), &stack, |b, stack| {
+            b.iter(|| stack.ancestry(black_box(&tip)).len());
+        });
+    }
+    group.finish();
+}
+
+fn bench_would_create_cycle(c: &mut Criterion) {
+    let mut group = c.benchmark_group("would_create_cycle");
+    for n in SIZES {
+        let stack = linear_stack(n);
+        let tip = format!("b{}", n - 1);
+        group.bench_with_input(BenchmarkId::new("linear", n), &stack, |b, stack| {
+            b.iter(|| stack.would_create_cycle(black_box("b0"), black_box(&tip)));
+        });
+    }
+    group.finish();
+}
+
+fn bench_depths(c: &mut Criterion) {
+    let mut group = c.benchmark_group("depths");
+    for n in SIZES {
+        let stack = tree_stack(n);
+        group.bench_with_input(BenchmarkId::new("tree", n), &stack, |b, stack| {
+            b.iter(|| stack.index().depths().len());
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    bench_descendants,
+    bench_ancestry,
+    bench_would_create_cycle,
+    bench_depths
+);
+criterion_main!(benches);
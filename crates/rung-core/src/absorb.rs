@@ -3,10 +3,13 @@
 //! Analyzes staged changes and automatically creates fixup commits
 //! targeting the appropriate commits in the local history.
 
+use std::collections::{HashMap, HashSet};
+
 use rung_git::{AbsorbOps, BlameResult, Hunk, Oid};
 
 use crate::StateStore;
 use crate::error::Result;
+use crate::stack::Stack;
 
 /// A planned fixup operation mapping a hunk to its target commit.
 #[derive(Debug, Clone)]
@@ -35,6 +38,10 @@ pub struct UnmappedHunk {
     pub hunk: Hunk,
     /// Reason why mapping failed.
     pub reason: UnmapReason,
+    /// Commits in the rebaseable range the hunk could be absorbed into.
+    ///
+    /// Set when blame finds several candidates, so the user can pick one.
+    pub candidates: Vec<BlameResult>,
 }
 
 /// Reason why a hunk couldn't be mapped.
@@ -52,6 +59,10 @@ pub enum UnmapReason {
     CommitOnBaseBranch,
     /// Blame query failed.
     BlameError(String),
+    /// The user chose not to absorb the hunk.
+    Skipped,
+    /// Target commit is not on the branch absorbing was restricted to.
+    NotOnBranch(String),
 }
 
 impl std::fmt::Display for UnmapReason {
@@ -63,17 +74,33 @@ impl std::fmt::Display for UnmapReason {
             Self::CommitNotInStack => write!(f, "target commit not in stack"),
             Self::CommitOnBaseBranch => write!(f, "target commit already on base branch"),
             Self::BlameError(e) => write!(f, "blame error: {e}"),
+            Self::Skipped => write!(f, "skipped"),
+            Self::NotOnBranch(branch) => write!(f, "target commit not on {branch}"),
         }
     }
 }
 
+/// Where staged hunks may be absorbed.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum AbsorbTarget {
+    /// Blame each hunk to find its commit between the base branch and HEAD.
+    #[default]
+    Blame,
+    /// Absorb every hunk into this commit, without consulting blame.
+    Commit(BlameResult),
+    /// Blame each hunk, accepting only commits on this stack branch.
+    Branch(String),
+}
+
 /// Result of executing an absorb plan.
-#[derive(Debug)]
+#[derive(Debug, Default)]
 pub struct AbsorbResult {
     /// Number of fixup commits created.
     pub fixups_created: usize,
     /// Commits that were targeted.
     pub targeted_commits: Vec<Oid>,
+    /// Branches the fixup commits were created on.
+    pub fixup_branches: Vec<String>,
 }
 
 /// Create an absorb plan by analyzing staged changes.
@@ -87,10 +114,17 @@ pub struct AbsorbResult {
 /// * `repo` - The git repository (implementing `AbsorbOps`)
 /// * `state` - Rung state for stack information (implementing `StateStore`)
 /// * `base_branch` - The base branch name (e.g., "main")
+/// * `target` - Where hunks may be absorbed
 ///
 /// # Errors
-/// Returns error if git operations fail.
-pub fn create_absorb_plan<G, S>(repo: &G, state: &S, base_branch: &str) -> Result<AbsorbPlan>
+/// Returns error if git operations fail, or if an explicit target commit or
+/// branch is not between the base branch and HEAD.
+pub fn create_absorb_plan<G, S>(
+    repo: &G,
+    state: &S,
+    base_branch: &str,
+    target: &AbsorbTarget,
+) -> Result<AbsorbPlan>
 where
     G: AbsorbOps,
     S: StateStore,
@@ -115,104 +149,221 @@ where
     let head_commit = repo.branch_commit(&current_branch)?;
 
     // Get commits in the rebaseable range (between base and HEAD)
-    let rebaseable_commits: std::collections::HashSet<Oid> = repo
+    let rebaseable_commits: HashSet<Oid> = repo
         .commits_between(base_commit, head_commit)?
         .into_iter()
         .collect();
 
-    // Load stack (reserved for future validation enhancements)
-    let _stack = state.load_stack()?;
-
-    for hunk in hunks {
+    // Narrow the candidates to an explicit target
+    let candidate_commits = match target {
+        AbsorbTarget::Blame => rebaseable_commits.clone(),
+        AbsorbTarget::Commit(commit) => {
+            return plan_into_commit(hunks, commit, &rebaseable_commits, base_branch);
+        }
+        AbsorbTarget::Branch(branch) => branch_commits(
+            repo,
+            &state.load_stack()?,
+            branch,
+            base_branch,
+            &rebaseable_commits,
+        )?,
+    };
+
+    // Blame every hunk of a file in one query rather than one per hunk
+    let blame_by_hunk = blame_hunks(repo, &hunks);
+
+    for (hunk, blame) in hunks.into_iter().zip(blame_by_hunk) {
         // New files have no blame history
-        if hunk.is_new_file {
+        let Some(blame) = blame else {
             unmapped.push(UnmappedHunk {
                 hunk,
                 reason: UnmapReason::NewFile,
+                candidates: vec![],
             });
             continue;
-        }
-
-        // Determine blame range
-        // For insert-only hunks (old_lines == 0), blame an adjacent line instead
-        let (blame_start, blame_end) = if hunk.old_lines == 0 {
-            // Insert-only hunk: blame the line at old_start (or line 1 if at file start)
-            // old_start is the line number where insertion happens (1-indexed)
-            // If old_start is 0, the insertion is at the very start; blame line 1
-            let line = hunk.old_start.max(1);
-            (line, line)
-        } else {
-            (
-                hunk.old_start,
-                hunk.old_start
-                    .saturating_add(hunk.old_lines)
-                    .saturating_sub(1),
-            )
         };
 
-        // Query blame for the original lines (or adjacent line for insert-only hunks)
-        let blame_result: Vec<BlameResult> =
-            match repo.blame_lines(&hunk.file_path, blame_start, blame_end) {
-                Ok(results) => results,
-                Err(e) => {
-                    unmapped.push(UnmappedHunk {
-                        hunk,
-                        reason: UnmapReason::BlameError(e.to_string()),
-                    });
-                    continue;
-                }
-            };
+        let blame_result = match blame {
+            Ok(results) => results,
+            Err(e) => {
+                unmapped.push(UnmappedHunk {
+                    hunk,
+                    reason: UnmapReason::BlameError(e),
+                    candidates: vec![],
+                });
+                continue;
+            }
+        };
 
         // Check if all blamed lines point to the same commit
         if blame_result.is_empty() {
             unmapped.push(UnmappedHunk {
                 hunk,
                 reason: UnmapReason::BlameError("no blame results".to_string()),
+                candidates: vec![],
             });
             continue;
         }
 
         if blame_result.len() > 1 {
+            let candidates = blame_result
+                .into_iter()
+                .filter(|b| candidate_commits.contains(&b.commit))
+                .collect();
             unmapped.push(UnmappedHunk {
                 hunk,
                 reason: UnmapReason::MultipleCommits,
+                candidates,
             });
             continue;
         }
 
-        let target = &blame_result[0];
+        let blamed = &blame_result[0];
 
         // Validate target is in rebaseable range
-        if !rebaseable_commits.contains(&target.commit) {
-            // Check if it's on base branch
-            if repo
-                .is_ancestor(target.commit, base_commit)
-                .unwrap_or(false)
-                || target.commit == base_commit
-            {
-                unmapped.push(UnmappedHunk {
-                    hunk,
-                    reason: UnmapReason::CommitOnBaseBranch,
-                });
-            } else {
-                unmapped.push(UnmappedHunk {
-                    hunk,
-                    reason: UnmapReason::CommitNotInStack,
-                });
-            }
+        if !candidate_commits.contains(&blamed.commit) {
+            unmapped.push(UnmappedHunk {
+                hunk,
+                reason: out_of_range_reason(
+                    repo,
+                    blamed.commit,
+                    base_commit,
+                    target,
+                    &rebaseable_commits,
+                ),
+                candidates: vec![],
+            });
             continue;
         }
 
         actions.push(AbsorbAction {
             hunk,
-            target_commit: target.commit,
-            target_message: target.message.clone(),
+            target_commit: blamed.commit,
+            target_message: blamed.message.clone(),
         });
     }
 
     Ok(AbsorbPlan { actions, unmapped })
 }
 
+/// Why a blamed commit outside the candidates can't be absorbed into.
+fn out_of_range_reason<G: AbsorbOps>(
+    repo: &G,
+    commit: Oid,
+    base_commit: Oid,
+    target: &AbsorbTarget,
+    rebaseable_commits: &HashSet<Oid>,
+) -> UnmapReason {
+    if let AbsorbTarget::Branch(branch) = target
+        && rebaseable_commits.contains(&commit)
+    {
+        return UnmapReason::NotOnBranch(branch.clone());
+    }
+    // Check if it's on base branch
+    if repo.is_ancestor(commit, base_commit).unwrap_or(false) || commit == base_commit {
+        UnmapReason::CommitOnBaseBranch
+    } else {
+        UnmapReason::CommitNotInStack
+    }
+}
+
+/// Plan absorbing every hunk into `commit`, which must be rebaseable.
+fn plan_into_commit(
+    hunks: Vec<Hunk>,
+    commit: &BlameResult,
+    rebaseable_commits: &HashSet<Oid>,
+    base_branch: &str,
+) -> Result<AbsorbPlan> {
+    if !rebaseable_commits.contains(&commit.commit) {
+        let oid_str = commit.commit.to_string();
+        let short_sha = oid_str.get(..8).unwrap_or(&oid_str);
+        return Err(crate::error::Error::Absorb(format!(
+            "commit {short_sha} is not between {base_branch} and HEAD"
+        )));
+    }
+    let actions = hunks
+        .into_iter()
+        .map(|hunk| AbsorbAction {
+            hunk,
+            target_commit: commit.commit,
+            target_message: commit.message.clone(),
+        })
+        .collect();
+    Ok(AbsorbPlan {
+        actions,
+        unmapped: vec![],
+    })
+}
+
+/// An inclusive, 1-indexed line range in the original file.
+type LineRange = (u32, u32);
+
+/// Blame outcome for one hunk: `None` for new files, otherwise the blame
+/// results or the error message from the failed query.
+type HunkBlame = Option<std::result::Result<Vec<BlameResult>, String>>;
+
+/// Line range to blame for a hunk, or `None` for new files.
+///
+/// Insert-only hunks (`old_lines == 0`) have no original lines, so the
+/// adjacent line at `old_start` is blamed instead (line 1 if inserting at
+/// the very start of the file).
+const fn blame_range(hunk: &Hunk) -> Option<LineRange> {
+    if hunk.is_new_file {
+        return None;
+    }
+    if hunk.old_lines == 0 {
+        let line = if hunk.old_start == 0 {
+            1
+        } else {
+            hunk.old_start
+        };
+        return Some((line, line));
+    }
+    Some((
+        hunk.old_start,
+        hunk.old_start
+            .saturating_add(hunk.old_lines)
+            .saturating_sub(1),
+    ))
+}
+
+/// Blame all hunks, batching the queries per file.
+///
+/// Returns one entry per hunk, in the same order as `hunks`.
+fn blame_hunks<G: AbsorbOps>(repo: &G, hunks: &[Hunk]) -> Vec<HunkBlame> {
+    let mut by_file: HashMap<&str, Vec<(usize, LineRange)>> = HashMap::new();
+    for (idx, hunk) in hunks.iter().enumerate() {
+        if let Some(range) = blame_range(hunk) {
+            // Staged renames are blamed under the path the lines live at in HEAD
+            by_file
+                .entry(hunk.old_path.as_deref().unwrap_or(&hunk.file_path))
+                .or_default()
+                .push((idx, range));
+        }
+    }
+
+    let mut results: Vec<HunkBlame> = vec![None; hunks.len()];
+
+    for (file_path, entries) in by_file {
+        let ranges: Vec<LineRange> = entries.iter().map(|&(_, range)| range).collect();
+        match repo.blame_ranges(file_path, &ranges) {
+            Ok(blames) => {
+                let mut blames = blames.into_iter();
+                for &(idx, _) in &entries {
+                    results[idx] = Some(Ok(blames.next().unwrap_or_default()));
+                }
+            }
+            Err(e) => {
+                for &(idx, _) in &entries {
+                    results[idx] = Some(Err(e.to_string()));
+                }
+            }
+        }
+    }
+
+    results
+}
+
 /// Execute an absorb plan by creating fixup commits.
 ///
 /// Creates a single fixup commit targeting the identified commit.
@@ -224,16 +375,66 @@ where
 /// staging area, making it impossible to create separate fixup commits for
 /// different targets without per-hunk staging (a future enhancement).
 pub fn execute_absorb<G: AbsorbOps>(repo: &G, plan: &AbsorbPlan) -> Result<AbsorbResult> {
-    if plan.actions.is_empty() {
-        return Ok(AbsorbResult {
-            fixups_created: 0,
-            targeted_commits: vec![],
-        });
+    let Some(target) = single_target(plan)? else {
+        return Ok(AbsorbResult::default());
+    };
+
+    repo.create_fixup_commit(target)?;
+
+    Ok(AbsorbResult {
+        fixups_created: 1,
+        targeted_commits: vec![target],
+        fixup_branches: vec![repo.current_branch()?],
+    })
+}
+
+/// Execute an absorb plan by creating `fixup!` commits on the owning branches.
+///
+/// Unlike [`execute_absorb`], which always commits on `HEAD`, the fixup is
+/// appended to the stack branch that introduced the target commit. Branches
+/// with open PRs only gain a commit; nothing is rewritten until the fixups
+/// are squashed with [`autosquash_fixups`].
+///
+/// # Errors
+/// Returns error if commit creation fails or if hunks target multiple commits.
+pub fn execute_absorb_as_fixups<G, S>(
+    repo: &G,
+    state: &S,
+    plan: &AbsorbPlan,
+) -> Result<AbsorbResult>
+where
+    G: AbsorbOps,
+    S: StateStore,
+{
+    let Some(target) = single_target(plan)? else {
+        return Ok(AbsorbResult::default());
+    };
+
+    let current_branch = repo.current_branch()?;
+    let stack = state.load_stack()?;
+    let branch = owning_branch(repo, &stack, &current_branch, target)?;
+    if branch == current_branch {
+        repo.create_fixup_commit(target)?;
+    } else {
+        stack.check_unlocked(&branch, "absorb into")?;
+        repo.create_fixup_commit_on(&branch, target)?;
     }
 
-    // Group actions by target commit
-    let mut by_target: std::collections::HashMap<Oid, Vec<&AbsorbAction>> =
-        std::collections::HashMap::new();
+    Ok(AbsorbResult {
+        fixups_created: 1,
+        targeted_commits: vec![target],
+        fixup_branches: vec![branch],
+    })
+}
+
+/// Get the single commit targeted by a plan, if it has any actions.
+///
+/// # Errors
+/// Returns error if the plan's hunks target more than one commit - git commit
+/// consumes the entire index, so separate fixup commits would need per-hunk
+/// staging.
+fn single_target(plan: &AbsorbPlan) -> Result<Option<Oid>> {
+    let mut by_target: HashMap<Oid, Vec<&AbsorbAction>> = HashMap::new();
     for action in &plan.actions {
         by_target
             .entry(action.target_commit)
@@ -241,8 +442,6 @@ pub fn execute_absorb<G: AbsorbOps>(repo: &G, plan: &AbsorbPlan) -> Result<Absor
             .push(action);
     }
 
-    // Reject multi-target plans - git commit consumes the entire index,
-    // so we can't create separate fixup commits without per-hunk staging.
     if by_target.len() > 1 {
         let target_descriptions: Vec<String> = by_target
             .iter()
@@ -261,18 +460,225 @@ pub fn execute_absorb<G: AbsorbOps>(repo: &G, plan: &AbsorbPlan) -> Result<Absor
         )));
     }
 
-    let mut targeted_commits = Vec::new();
+    Ok(by_target.into_keys().next())
+}
 
-    // Create fixup commit for the single target
-    for target in by_target.keys() {
-        repo.create_fixup_commit(*target)?;
-        targeted_commits.push(*target);
+/// Find the stack branch that introduced `target`.
+///
+/// Walks the current branch's ancestry from the root and returns the first
+/// branch whose tip contains the commit. Falls back to the current branch.
+///
+/// # Errors
+/// Returns error if a branch tip can't be read.
+pub fn owning_branch<G: AbsorbOps>(
+    repo: &G,
+    stack: &Stack,
+    current_branch: &str,
+    target: Oid,
+) -> Result<String> {
+    for branch in stack.ancestry(current_branch) {
+        let tip = repo.branch_commit(&branch.name)?;
+        if tip == target || repo.is_ancestor(target, tip)? {
+            return Ok(branch.name.to_string());
+        }
     }
+    Ok(current_branch.to_string())
+}
 
-    Ok(AbsorbResult {
-        fixups_created: by_target.len(),
-        targeted_commits,
-    })
+/// Commits `branch` adds on top of its parent (the base branch for a root).
+///
+/// # Errors
+/// Returns error if the branch is not in the stack or none of its commits
+/// are rebaseable.
+fn branch_commits<G: AbsorbOps>(
+    repo: &G,
+    stack: &Stack,
+    branch: &str,
+    base_branch: &str,
+    rebaseable_commits: &HashSet<Oid>,
+) -> Result<HashSet<Oid>> {
+    let entry = stack
+        .find_branch(branch)
+        .ok_or_else(|| crate::error::Error::NotInStack(branch.to_string()))?;
+    let parent = entry.parent.as_deref().unwrap_or(base_branch);
+    let parent_tip = repo
+        .branch_commit(parent)
+        .or_else(|_| repo.remote_branch_commit(parent))?;
+    let own: HashSet<Oid> = repo
+        .commits_between(parent_tip, repo.branch_commit(branch)?)?
+        .into_iter()
+        .collect();
+    if own.is_disjoint(rebaseable_commits) {
+        return Err(crate::error::Error::Absorb(format!(
+            "branch '{branch}' has no commits between {base_branch} and HEAD"
+        )));
+    }
+    Ok(own)
+}
+
+/// A stack branch carrying `fixup!` commits that have not been squashed yet.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PendingFixups {
+    /// The branch holding the fixups.
+    pub branch: String,
+    /// Number of fixup commits that would be squashed.
+    pub count: usize,
+}
+
+/// Find stack branches with `fixup!` commits that autosquash would fold in.
+///
+/// Only commits unique to each branch (since its parent, or `base_branch`
+/// for root branches) are considered. Branches whose parent is missing
+/// locally are skipped.
+///
+/// # Errors
+/// Returns error if git operations fail.
+pub fn pending_fixups<G: AbsorbOps>(
+    repo: &G,
+    stack: &Stack,
+    base_branch: &str,
+) -> Result<Vec<PendingFixups>> {
+    let mut pending = Vec::new();
+    for branch in &stack.branches {
+        let parent = branch.parent.as_deref().unwrap_or(base_branch);
+        let (Ok(tip), Ok(parent_tip)) =
+            (repo.branch_commit(&branch.name), repo.branch_commit(parent))
+        else {
+            continue;
+        };
+        let fork = repo.merge_base(parent_tip, tip)?;
+        let count = repo.count_fixup_commits(fork, tip)?;
+        if count > 0 {
+            pending.push(PendingFixups {
+                branch: branch.name.to_string(),
+                count,
+            });
+        }
+    }
+    Ok(pending)
+}
+
+/// Result of squashing pending fixups across the stack.
+#[derive(Debug, Default)]
+pub struct AutosquashResult {
+    /// Branches whose fixups were squashed.
+    pub squashed: Vec<String>,
+    /// Descendant branches rebased onto their rewritten parents.
+    pub restacked: Vec<String>,
+    /// Backup taken before rewriting, if anything was rewritten.
+    pub backup_id: Option<String>,
+}
+
+/// Squash pending `fixup!` commits into their targets across the stack.
+///
+/// Each branch with fixups is rebased in place with `--autosquash`, and
+/// every descendant is replayed onto its rewritten parent with
+/// `rebase --onto`, so the stack stays consistent. A backup is taken first
+/// so the operation can be undone. On conflict the rebase is aborted, all
+/// rewritten branches are restored, and an error is returned.
+///
+/// # Errors
+/// Returns error if git operations fail or a rebase conflicts.
+pub fn autosquash_fixups<G, S>(
+    repo: &G,
+    state: &S,
+    stack: &Stack,
+    base_branch: &str,
+) -> Result<AutosquashResult>
+where
+    G: AbsorbOps,
+    S: StateStore,
+{
+    let pending = pending_fixups(repo, stack, base_branch)?;
+    if pending.is_empty() {
+        return Ok(AutosquashResult::default());
+    }
+
+    // Every branch with fixups plus everything stacked on top of it,
+    // parents before children.
+    let index = stack.index();
+    let depths = index.depths();
+    let mut affected: Vec<&str> = Vec::new();
+    for p in &pending {
+        for name in std::iter::once(p.branch.as_str()).chain(
+            index
+                .descendants(&p.branch)
+                .into_iter()
+                .map(|b| b.name.as_str()),
+        ) {
+            if !affected.contains(&name) {
+                affected.push(name);
+            }
+        }
+    }
+    affected.sort_by_key(|name| depths.get(name).copied().unwrap_or(usize::MAX));
+
+    let original_tips = affected
+        .iter()
+        .map(|name| Ok((*name, repo.branch_commit(name)?)))
+        .collect::<Result<Vec<_>>>()?;
+    let backup_refs: Vec<(String, String)> = original_tips
+        .iter()
+        .map(|(name, oid)| ((*name).to_string(), oid.to_string()))
+        .collect();
+    let backup_refs: Vec<(&str, &str)> = backup_refs
+        .iter()
+        .map(|(b, c)| (b.as_str(), c.as_str()))
+        .collect();
+    let backup_id = state.create_backup(&backup_refs)?;
+
+    let original_branch = repo.current_branch().ok();
+    let mut result = AutosquashResult {
+        backup_id: Some(backup_id),
+        ..AutosquashResult::default()
+    };
+    let mut rewritten: HashMap<&str, (Oid, Oid)> = HashMap::new();
+
+    for &(name, tip) in &original_tips {
+        let parent = index
+            .get(name)
+            .and_then(|b| b.parent.as_deref())
+            .unwrap_or(base_branch);
+        let (onto, upstream) = if let Some(&(old, new)) = rewritten.get(parent) {
+            (new, old)
+        } else {
+            let fork = repo.merge_base(repo.branch_commit(parent)?, tip)?;
+            (fork, fork)
+        };
+
+        let outcome = repo
+            .checkout(name)
+            .and_then(|()| repo.rebase_autosquash(onto, upstream));
+        if let Err(e) = outcome {
+            if repo.is_rebasing() {
+                let _ = repo.rebase_abort();
+            }
+            for &(done, old) in &original_tips {
+                if rewritten.contains_key(done) {
+                    let _ = repo.reset_branch(done, old);
+                }
+            }
+            if let Some(branch) = &original_branch {
+                let _ = repo.checkout(branch);
+            }
+            return Err(crate::error::Error::Absorb(format!(
+                "autosquash of '{name}' failed ({e}); branches were left unchanged"
+            )));
+        }
+
+        rewritten.insert(name, (tip, repo.branch_commit(name)?));
+        if pending.iter().any(|p| p.branch == name) {
+            result.squashed.push(name.to_string());
+        } else {
+            result.restacked.push(name.to_string());
+        }
+    }
+
+    if let Some(branch) = &original_branch {
+        repo.checkout(branch)?;
+    }
+
+    Ok(result)
 }
 
 #[cfg(test)]
@@ -280,7 +686,7 @@ pub fn execute_absorb<G: AbsorbOps>(repo: &G, plan: &AbsorbPlan) -> Result<Absor
 mod tests {
     use super::*;
     use crate::config::Config;
-    use crate::stack::Stack;
+    use crate::stack::{Stack, StackBranch};
     use crate::state::{RestackState, SyncState};
     use rung_git::{GitOps, RemoteDivergence};
     use std::cell::RefCell;
@@ -295,9 +701,12 @@ mod tests {
         blame_errors: HashMap<String, String>,
         branch_commits: HashMap<String, Oid>,
         commits_between: Vec<Oid>,
+        /// Per-range overrides of `commits_between`, keyed by `(from, to)`.
+        commits_in_range: HashMap<(Oid, Oid), Vec<Oid>>,
         current_branch: String,
         is_ancestor_results: HashMap<(Oid, Oid), bool>,
         fixup_commits_created: RefCell<Vec<Oid>>,
+        fixup_commits_on: RefCell<Vec<(String, Oid)>>,
     }
 
     impl Default for MockRepo {
@@ -308,9 +717,11 @@ mod tests {
                 blame_errors: HashMap::new(),
                 branch_commits: HashMap::new(),
                 commits_between: vec![],
+                commits_in_range: HashMap::new(),
                 current_branch: "feature".to_string(),
                 is_ancestor_results: HashMap::new(),
                 fixup_commits_created: RefCell::new(vec![]),
+                fixup_commits_on: RefCell::new(vec![]),
             }
         }
     }
@@ -328,6 +739,9 @@ mod tests {
         fn is_rebasing(&self) -> bool {
             false
         }
+        fn rebasing_branch(&self) -> Option<String> {
+            None
+        }
         fn branch_exists(&self, _name: &str) -> bool {
             true
         }
@@ -343,6 +757,9 @@ mod tests {
         fn list_branches(&self) -> rung_git::Result<Vec<String>> {
             unimplemented!()
         }
+        fn branch_description(&self, _branch: &str) -> Option<String> {
+            unimplemented!()
+        }
         fn branch_commit(&self, branch: &str) -> rung_git::Result<Oid> {
             self.branch_commits
                 .get(branch)
@@ -361,12 +778,40 @@ mod tests {
         fn merge_base(&self, _one: Oid, _two: Oid) -> rung_git::Result<Oid> {
             unimplemented!()
         }
-        fn commits_between(&self, _from: Oid, _to: Oid) -> rung_git::Result<Vec<Oid>> {
-            Ok(self.commits_between.clone())
+        fn commits_between(&self, from: Oid, to: Oid) -> rung_git::Result<Vec<Oid>> {
+            Ok(self
+                .commits_in_range
+                .get(&(from, to))
+                .unwrap_or(&self.commits_between)
+                .clone())
+        }
+        fn commit_diff(
+            &self,
+            _commit: Oid,
+            _format: rung_git::DiffFormat,
+        ) -> rung_git::Result<String> {
+            unimplemented!()
         }
         fn count_commits_between(&self, _from: Oid, _to: Oid) -> rung_git::Result<usize> {
             unimplemented!()
         }
+        fn find_squash_applied(
+            &self,
+            _fork: Oid,
+            _branch: Oid,
+            _upstream: Oid,
+        ) -> rung_git::Result<Option<Oid>> {
+            unimplemented!()
+        }
+        fn dropped_commits(
+            &self,
+            _from: Oid,
+            _to: Oid,
+            _onto: Oid,
+            _new_tip: Oid,
+        ) -> rung_git::Result<Vec<String>> {
+            unimplemented!()
+        }
         fn is_clean(&self) -> rung_git::Result<bool> {
             Ok(true)
         }
@@ -385,6 +830,15 @@ mod tests {
         fn amend_commit(&self, _new_message: Option<&str>) -> rung_git::Result<Oid> {
             unimplemented!()
         }
+        fn identity(&self) -> rung_git::Result<rung_git::Identity> {
+            unimplemented!()
+        }
+        fn signing_config(&self) -> rung_git::Result<rung_git::SigningConfig> {
+            unimplemented!()
+        }
+        fn is_commit_signed(&self, _commit: Oid) -> rung_git::Result<bool> {
+            unimplemented!()
+        }
         fn rebase_onto(&self, _target: Oid) -> rung_git::Result<()> {
             unimplemented!()
         }
@@ -407,6 +861,9 @@ mod tests {
         fn rebase_continue(&self) -> rung_git::Result<()> {
             unimplemented!()
         }
+        fn skip_applied_commits(&self) -> rung_git::Result<usize> {
+            unimplemented!()
+        }
         fn origin_url(&self) -> rung_git::Result<String> {
             unimplemented!()
         }
@@ -419,6 +876,9 @@ mod tests {
         fn push(&self, _branch: &str, _force: bool) -> rung_git::Result<()> {
             unimplemented!()
         }
+        fn remote_branches(&self) -> rung_git::Result<Vec<String>> {
+            unimplemented!()
+        }
         fn fetch_all(&self) -> rung_git::Result<()> {
             unimplemented!()
         }
@@ -467,6 +927,21 @@ mod tests {
             // Return a new "fixup" commit OID
             Ok(Oid::from_str("aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa").unwrap())
         }
+
+        fn create_fixup_commit_on(&self, branch: &str, target: Oid) -> rung_git::Result<Oid> {
+            self.fixup_commits_on
+                .borrow_mut()
+                .push((branch.to_string(), target));
+            Ok(Oid::from_str("bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb").unwrap())
+        }
+
+        fn count_fixup_commits(&self, _from: Oid, _to: Oid) -> rung_git::Result<usize> {
+            Ok(0)
+        }
+
+        fn rebase_autosquash(&self, _onto: Oid, _upstream: Oid) -> rung_git::Result<()> {
+            unimplemented!()
+        }
     }
 
     // Mock implementation for StateStore
@@ -548,6 +1023,15 @@ mod tests {
         fn clear_fold_state(&self) -> crate::Result<()> {
             Ok(())
         }
+        fn load_journal(&self) -> crate::Result<Option<crate::journal::Journal>> {
+            Ok(None)
+        }
+        fn save_journal(&self, _journal: &crate::journal::Journal) -> crate::Result<()> {
+            Ok(())
+        }
+        fn clear_journal(&self) -> crate::Result<()> {
+            Ok(())
+        }
         fn create_backup(&self, _branches: &[(&str, &str)]) -> crate::Result<String> {
             unimplemented!()
         }
@@ -596,6 +1080,10 @@ mod tests {
             UnmapReason::BlameError("test".to_string()).to_string(),
             "blame error: test"
         );
+        assert_eq!(
+            UnmapReason::NotOnBranch("feature".to_string()).to_string(),
+            "target commit not on feature"
+        );
     }
 
     #[test]
@@ -613,7 +1101,7 @@ mod tests {
         let repo = MockRepo::default();
         let state = MockState::default();
 
-        let plan = create_absorb_plan(&repo, &state, "main").unwrap();
+        let plan = create_absorb_plan(&repo, &state, "main", &AbsorbTarget::Blame).unwrap();
 
         assert!(plan.actions.is_empty());
         assert!(plan.unmapped.is_empty());
@@ -624,6 +1112,7 @@ mod tests {
         let mut repo = MockRepo::default();
         repo.hunks = vec![Hunk {
             file_path: "new_file.rs".to_string(),
+            old_path: None,
             old_start: 0,
             old_lines: 0,
             new_start: 1,
@@ -639,7 +1128,7 @@ mod tests {
 
         let state = MockState::default();
 
-        let plan = create_absorb_plan(&repo, &state, "main").unwrap();
+        let plan = create_absorb_plan(&repo, &state, "main", &AbsorbTarget::Blame).unwrap();
 
         assert!(plan.actions.is_empty());
         assert_eq!(plan.unmapped.len(), 1);
@@ -653,6 +1142,7 @@ mod tests {
         let mut repo = MockRepo::default();
         repo.hunks = vec![Hunk {
             file_path: "src/lib.rs".to_string(),
+            old_path: None,
             old_start: 10,
             old_lines: 5,
             new_start: 10,
@@ -676,7 +1166,7 @@ mod tests {
 
         let state = MockState::default();
 
-        let plan = create_absorb_plan(&repo, &state, "main").unwrap();
+        let plan = create_absorb_plan(&repo, &state, "main", &AbsorbTarget::Blame).unwrap();
 
         assert_eq!(plan.actions.len(), 1);
         assert!(plan.unmapped.is_empty());
@@ -684,6 +1174,94 @@ mod tests {
         assert_eq!(plan.actions[0].target_message, "Add feature");
     }
 
+    #[test]
+    fn test_create_plan_blames_old_path_of_staged_rename() {
+        let target_commit = test_oid(3);
+
+        let mut repo = MockRepo::default();
+        repo.hunks = vec![Hunk {
+            file_path: "src/new.rs".to_string(),
+            old_path: Some("src/old.rs".to_string()),
+            old_start: 10,
+            old_lines: 1,
+            new_start: 10,
+            new_lines: 1,
+            content: String::new(),
+            is_new_file: false,
+        }];
+        repo.branch_commits.insert("main".to_string(), test_oid(1));
+        repo.branch_commits
+            .insert("feature".to_string(), test_oid(2));
+        repo.commits_between = vec![target_commit];
+        repo.blame_results.insert(
+            "src/old.rs".to_string(),
+            vec![BlameResult {
+                commit: target_commit,
+                message: "Add old".to_string(),
+            }],
+        );
+
+        let state = MockState::default();
+
+        let plan = create_absorb_plan(&repo, &state, "main", &AbsorbTarget::Blame).unwrap();
+
+        assert!(plan.unmapped.is_empty());
+        assert_eq!(plan.actions.len(), 1);
+        assert_eq!(plan.actions[0].target_commit, target_commit);
+        assert_eq!(plan.actions[0].hunk.file_path, "src/new.rs");
+    }
+
+    #[test]
+    fn test_create_plan_preserves_hunk_order_across_files() {
+        let target_commit = test_oid(3);
+        let hunk = |file: &str, old_start: u32, is_new_file: bool| Hunk {
+            file_path: file.to_string(),
+            old_path: None,
+            old_start,
+            old_lines: u32::from(!is_new_file),
+            new_start: old_start,
+            new_lines: 1,
+            content: String::new(),
+            is_new_file,
+        };
+
+        let mut repo = MockRepo::default();
+        // Hunks from the same file are interleaved with other files
+        repo.hunks = vec![
+            hunk("src/lib.rs", 10, false),
+            hunk("src/broken.rs", 1, false),
+            hunk("src/new.rs", 0, true),
+            hunk("src/lib.rs", 40, false),
+        ];
+        repo.branch_commits.insert("main".to_string(), test_oid(1));
+        repo.branch_commits
+            .insert("feature".to_string(), test_oid(2));
+        repo.commits_between = vec![target_commit];
+        repo.blame_results.insert(
+            "src/lib.rs".to_string(),
+            vec![BlameResult {
+                commit: target_commit,
+                message: "Add feature".to_string(),
+            }],
+        );
+        repo.blame_errors
+            .insert("src/broken.rs".to_string(), "bad file".to_string());
+
+        let state = MockState::default();
+
+        let plan = create_absorb_plan(&repo, &state, "main", &AbsorbTarget::Blame).unwrap();
+
+        let mapped: Vec<u32> = plan.actions.iter().map(|a| a.hunk.old_start).collect();
+        assert_eq!(mapped, vec![10, 40]);
+        assert_eq!(plan.unmapped.len(), 2);
+        assert_eq!(plan.unmapped[0].hunk.file_path, "src/broken.rs");
+        assert!(matches!(
+            plan.unmapped[0].reason,
+            UnmapReason::BlameError(_)
+        ));
+        assert_eq!(plan.unmapped[1].reason, UnmapReason::NewFile);
+    }
+
     #[test]
     fn test_create_plan_multiple_commits_unmapped() {
         let commit1 = test_oid(3);
@@ -692,6 +1270,7 @@ mod tests {
         let mut repo = MockRepo::default();
         repo.hunks = vec![Hunk {
             file_path: "src/lib.rs".to_string(),
+            old_path: None,
             old_start: 10,
             old_lines: 5,
             new_start: 10,
@@ -721,11 +1300,17 @@ mod tests {
 
         let state = MockState::default();
 
-        let plan = create_absorb_plan(&repo, &state, "main").unwrap();
+        let plan = create_absorb_plan(&repo, &state, "main", &AbsorbTarget::Blame).unwrap();
 
         assert!(plan.actions.is_empty());
         assert_eq!(plan.unmapped.len(), 1);
         assert_eq!(plan.unmapped[0].reason, UnmapReason::MultipleCommits);
+        let candidates: Vec<Oid> = plan.unmapped[0]
+            .candidates
+            .iter()
+            .map(|c| c.commit)
+            .collect();
+        assert_eq!(candidates, vec![commit1, commit2]);
     }
 
     #[test]
@@ -735,6 +1320,7 @@ mod tests {
         let mut repo = MockRepo::default();
         repo.hunks = vec![Hunk {
             file_path: "src/lib.rs".to_string(),
+            old_path: None,
             old_start: 10,
             old_lines: 5,
             new_start: 10,
@@ -758,7 +1344,7 @@ mod tests {
 
         let state = MockState::default();
 
-        let plan = create_absorb_plan(&repo, &state, "main").unwrap();
+        let plan = create_absorb_plan(&repo, &state, "main", &AbsorbTarget::Blame).unwrap();
 
         assert!(plan.actions.is_empty());
         assert_eq!(plan.unmapped.len(), 1);
@@ -773,6 +1359,7 @@ mod tests {
         let mut repo = MockRepo::default();
         repo.hunks = vec![Hunk {
             file_path: "src/lib.rs".to_string(),
+            old_path: None,
             old_start: 10,
             old_lines: 5,
             new_start: 10,
@@ -799,7 +1386,7 @@ mod tests {
 
         let state = MockState::default();
 
-        let plan = create_absorb_plan(&repo, &state, "main").unwrap();
+        let plan = create_absorb_plan(&repo, &state, "main", &AbsorbTarget::Blame).unwrap();
 
         assert!(plan.actions.is_empty());
         assert_eq!(plan.unmapped.len(), 1);
@@ -811,6 +1398,7 @@ mod tests {
         let mut repo = MockRepo::default();
         repo.hunks = vec![Hunk {
             file_path: "src/lib.rs".to_string(),
+            old_path: None,
             old_start: 10,
             old_lines: 5,
             new_start: 10,
@@ -828,7 +1416,7 @@ mod tests {
 
         let state = MockState::default();
 
-        let plan = create_absorb_plan(&repo, &state, "main").unwrap();
+        let plan = create_absorb_plan(&repo, &state, "main", &AbsorbTarget::Blame).unwrap();
 
         assert!(plan.actions.is_empty());
         assert_eq!(plan.unmapped.len(), 1);
@@ -843,6 +1431,7 @@ mod tests {
         let mut repo = MockRepo::default();
         repo.hunks = vec![Hunk {
             file_path: "src/lib.rs".to_string(),
+            old_path: None,
             old_start: 10,
             old_lines: 5,
             new_start: 10,
@@ -859,7 +1448,7 @@ mod tests {
 
         let state = MockState::default();
 
-        let plan = create_absorb_plan(&repo, &state, "main").unwrap();
+        let plan = create_absorb_plan(&repo, &state, "main", &AbsorbTarget::Blame).unwrap();
 
         assert!(plan.actions.is_empty());
         assert_eq!(plan.unmapped.len(), 1);
@@ -878,6 +1467,7 @@ mod tests {
         let mut repo = MockRepo::default();
         repo.hunks = vec![Hunk {
             file_path: "src/lib.rs".to_string(),
+            old_path: None,
             old_start: 10, // Line where insertion happens
             old_lines: 0,  // Insert-only: no lines deleted
             new_start: 10,
@@ -901,7 +1491,7 @@ mod tests {
 
         let state = MockState::default();
 
-        let plan = create_absorb_plan(&repo, &state, "main").unwrap();
+        let plan = create_absorb_plan(&repo, &state, "main", &AbsorbTarget::Blame).unwrap();
 
         // Insert-only hunks should be mappable if adjacent line points to valid target
         assert_eq!(plan.actions.len(), 1);
@@ -932,6 +1522,7 @@ mod tests {
             actions: vec![AbsorbAction {
                 hunk: Hunk {
                     file_path: "src/lib.rs".to_string(),
+                    old_path: None,
                     old_start: 10,
                     old_lines: 5,
                     new_start: 10,
@@ -957,6 +1548,143 @@ mod tests {
         assert_eq!(created[0], target_commit);
     }
 
+    /// A stack of `parent` (commit 3) and `feature` (commit 4) on main,
+    /// with one staged hunk in `src/lib.rs`.
+    fn two_branch_repo() -> (MockRepo, MockState) {
+        let mut repo = MockRepo::default();
+        repo.hunks = vec![Hunk {
+            file_path: "src/lib.rs".to_string(),
+            old_path: None,
+            old_start: 10,
+            old_lines: 5,
+            new_start: 10,
+            new_lines: 7,
+            content: String::new(),
+            is_new_file: true,
+        }];
+        repo.branch_commits.insert("main".to_string(), test_oid(1));
+        repo.branch_commits
+            .insert("parent".to_string(), test_oid(3));
+        repo.branch_commits
+            .insert("feature".to_string(), test_oid(4));
+        repo.commits_between = vec![test_oid(3), test_oid(4)];
+        repo.commits_in_range
+            .insert((test_oid(1), test_oid(3)), vec![test_oid(3)]);
+        repo.commits_in_range
+            .insert((test_oid(3), test_oid(4)), vec![test_oid(4)]);
+
+        let mut state = MockState::default();
+        state
+            .stack
+            .add_branch(StackBranch::try_new("parent", Some("main")).unwrap());
+        state
+            .stack
+            .add_branch(StackBranch::try_new("feature", Some("parent")).unwrap());
+        (repo, state)
+    }
+
+    #[test]
+    fn test_create_plan_explicit_commit() {
+        let (repo, state) = two_branch_repo();
+        let target = BlameResult {
+            commit: test_oid(3),
+            message: "Parent commit".to_string(),
+        };
+
+        // Even a new file is absorbed when the commit is given
+        let plan =
+            create_absorb_plan(&repo, &state, "main", &AbsorbTarget::Commit(target)).unwrap();
+        assert!(plan.unmapped.is_empty());
+        assert_eq!(plan.actions.len(), 1);
+        assert_eq!(plan.actions[0].target_commit, test_oid(3));
+        assert_eq!(plan.actions[0].target_message, "Parent commit");
+
+        let outside = BlameResult {
+            commit: test_oid(9),
+            message: "Elsewhere".to_string(),
+        };
+        let err =
+            create_absorb_plan(&repo, &state, "main", &AbsorbTarget::Commit(outside)).unwrap_err();
+        assert!(err.to_string().contains("is not between main and HEAD"));
+    }
+
+    #[test]
+    fn test_create_plan_restricted_to_branch() {
+        let (mut repo, state) = two_branch_repo();
+        repo.hunks[0].is_new_file = false;
+        repo.blame_results.insert(
+            "src/lib.rs".to_string(),
+            vec![BlameResult {
+                commit: test_oid(4),
+                message: "Feature commit".to_string(),
+            }],
+        );
+
+        let target = AbsorbTarget::Branch("feature".to_string());
+        let plan = create_absorb_plan(&repo, &state, "main", &target).unwrap();
+        assert_eq!(plan.actions.len(), 1);
+        assert_eq!(plan.actions[0].target_commit, test_oid(4));
+
+        let target = AbsorbTarget::Branch("parent".to_string());
+        let plan = create_absorb_plan(&repo, &state, "main", &target).unwrap();
+        assert!(plan.actions.is_empty());
+        assert_eq!(
+            plan.unmapped[0].reason,
+            UnmapReason::NotOnBranch("parent".to_string())
+        );
+
+        let target = AbsorbTarget::Branch("untracked".to_string());
+        assert!(create_absorb_plan(&repo, &state, "main", &target).is_err());
+    }
+
+    #[test]
+    fn test_execute_absorb_as_fixups_targets_owning_branch() {
+        let target_commit = test_oid(3);
+        let mut repo = MockRepo::default();
+        repo.branch_commits
+            .insert("parent".to_string(), test_oid(5));
+        repo.branch_commits
+            .insert("feature".to_string(), test_oid(6));
+        repo.is_ancestor_results
+            .insert((target_commit, test_oid(5)), true);
+
+        let mut state = MockState::default();
+        state
+            .stack
+            .add_branch(StackBranch::try_new("parent", Some("main")).unwrap());
+        state
+            .stack
+            .add_branch(StackBranch::try_new("feature", Some("parent")).unwrap());
+
+        let plan = AbsorbPlan {
+            actions: vec![AbsorbAction {
+                hunk: Hunk {
+                    file_path: "src/lib.rs".to_string(),
+                    old_path: None,
+                    old_start: 10,
+                    old_lines: 5,
+                    new_start: 10,
+                    new_lines: 7,
+                    content: String::new(),
+                    is_new_file: false,
+                },
+                target_commit,
+                target_message: "Parent commit".to_string(),
+            }],
+            unmapped: vec![],
+        };
+
+        let result = execute_absorb_as_fixups(&repo, &state, &plan).unwrap();
+
+        assert_eq!(result.fixups_created, 1);
+        assert_eq!(result.fixup_branches, vec!["parent".to_string()]);
+        assert!(repo.fixup_commits_created.borrow().is_empty());
+        assert_eq!(
+            *repo.fixup_commits_on.borrow(),
+            vec![("parent".to_string(), target_commit)]
+        );
+    }
+
     #[test]
     fn test_execute_absorb_multiple_hunks_same_target() {
         let target_commit = test_oid(3);
@@ -967,6 +1695,7 @@ mod tests {
                 AbsorbAction {
                     hunk: Hunk {
                         file_path: "src/lib.rs".to_string(),
+                        old_path: None,
                         old_start: 10,
                         old_lines: 5,
                         new_start: 10,
@@ -980,6 +1709,7 @@ mod tests {
                 AbsorbAction {
                     hunk: Hunk {
                         file_path: "src/main.rs".to_string(),
+                        old_path: None,
                         old_start: 20,
                         old_lines: 3,
                         new_start: 20,
@@ -1012,6 +1742,7 @@ mod tests {
                 AbsorbAction {
                     hunk: Hunk {
                         file_path: "src/lib.rs".to_string(),
+                        old_path: None,
                         old_start: 10,
                         old_lines: 5,
                         new_start: 10,
@@ -1025,6 +1756,7 @@ mod tests {
                 AbsorbAction {
                     hunk: Hunk {
                         file_path: "src/main.rs".to_string(),
+                        old_path: None,
                         old_start: 20,
                         old_lines: 3,
                         new_start: 20,
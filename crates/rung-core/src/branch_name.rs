@@ -3,7 +3,8 @@
 //! Provides a [`BranchName`] type that enforces git branch name rules
 //! and prevents security issues like path traversal and shell injection.
 //!
-//! Also provides [`slugify`] to convert arbitrary text into a valid branch name.
+//! Also provides [`slugify`] to convert arbitrary text into a valid branch name,
+//! and [`expand_branch_template`] to build one from a configured template.
 
 use std::fmt;
 
@@ -341,6 +342,39 @@ pub fn slugify(text: &str) -> String {
     )
 }
 
+/// Build a branch name from a commit message using a naming template.
+///
+/// Supported placeholders:
+/// - `{slug}` - the slugified message (see [`slugify`])
+/// - `{user}` - the slugified git user name
+/// - `{date}` - the given date as `YYYY-MM-DD`
+///
+/// # Examples
+///
+/// ```
+/// use chrono::NaiveDate;
+/// use rung_core::expand_branch_template;
+///
+/// let date = NaiveDate::from_ymd_opt(2025, 3, 14).unwrap();
+/// assert_eq!(
+///     expand_branch_template("{user}/{slug}", "Add rate limiter", "Ada Lovelace", date),
+///     "ada-lovelace/add-rate-limiter"
+/// );
+/// ```
+#[must_use]
+#[allow(clippy::literal_string_with_formatting_args)] // `{slug}` etc. are template placeholders
+pub fn expand_branch_template(
+    template: &str,
+    message: &str,
+    user: &str,
+    date: chrono::NaiveDate,
+) -> String {
+    template
+        .replace("{slug}", &slugify(message))
+        .replace("{user}", &slugify(user))
+        .replace("{date}", &date.format("%Y-%m-%d").to_string())
+}
+
 #[cfg(test)]
 #[allow(clippy::unwrap_used)]
 mod tests {
@@ -593,6 +627,18 @@ mod tests {
         assert_eq!(slugify("fix: path/to/file"), "fix-path-to-file");
     }
 
+    #[test]
+    #[allow(clippy::literal_string_with_formatting_args)]
+    fn test_expand_branch_template() {
+        let date = chrono::NaiveDate::from_ymd_opt(2025, 1, 2).unwrap();
+        let expand = |template| expand_branch_template(template, "Add rate limiter", "Jo", date);
+
+        assert_eq!(expand("{slug}"), "add-rate-limiter");
+        assert_eq!(expand("{user}/{slug}"), "jo/add-rate-limiter");
+        assert_eq!(expand("{date}-{slug}"), "2025-01-02-add-rate-limiter");
+        assert_eq!(expand("feature/{slug}"), "feature/add-rate-limiter");
+    }
+
     // === from_message tests ===
 
     #[test]
@@ -0,0 +1,404 @@
+//! State bundles: the stack, sync backups, and audit log in one tarball.
+//!
+//! A bundle is a gzipped tar of JSON files, so it can be inspected with
+//! `tar -xzf` as well as restored with [`State::import_bundle`]:
+//!
+//! ```text
+//! manifest.json        format version, rung version, creation time
+//! stack.json           the stack, as stored in .git/rung/stack.json
+//! audit.json           the audit log entries
+//! backups/<id>.json    one sync backup, as [branch, commit] pairs
+//! ```
+
+use std::collections::HashMap;
+use std::io::{Read, Write};
+
+use chrono::{DateTime, Utc};
+use flate2::Compression;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use serde::{Deserialize, Serialize};
+
+use crate::BranchName;
+use crate::error::{Error, Result};
+use crate::stack::Stack;
+use crate::state::{AuditEntry, State};
+
+/// Bundle layout version, bumped on incompatible changes.
+const FORMAT_VERSION: u32 = 1;
+
+const MANIFEST_FILE: &str = "manifest.json";
+const STACK_FILE: &str = "stack.json";
+const AUDIT_FILE: &str = "audit.json";
+const BACKUPS_DIR: &str = "backups/";
+
+/// Describes a bundle; stored as `manifest.json`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BundleManifest {
+    /// Bundle layout version.
+    pub format: u32,
+
+    /// Version of rung that wrote the bundle.
+    pub rung_version: String,
+
+    /// When the bundle was written.
+    pub created_at: DateTime<Utc>,
+
+    /// Whether branch names were replaced with placeholders.
+    #[serde(default)]
+    pub redacted: bool,
+}
+
+/// What a bundle holds.
+#[derive(Debug, Clone, Serialize)]
+pub struct BundleSummary {
+    /// Branches in the stack.
+    pub branches: usize,
+    /// Sync backups.
+    pub backups: usize,
+    /// Audit log entries.
+    pub operations: usize,
+    /// Whether branch names were replaced with placeholders.
+    pub redacted: bool,
+}
+
+impl State {
+    /// Write the stack, backups, and audit log to `writer` as a bundle.
+    ///
+    /// With `redact`, every branch name except the base branch is replaced
+    /// with a placeholder (`branch-1`, `branch-2`, ...), consistently across
+    /// the bundle, and audited command lines are cut to the subcommand.
+    ///
+    /// # Errors
+    /// Returns error if the state can't be read or the bundle can't be written.
+    pub fn export_bundle(&self, writer: impl Write, redact: bool) -> Result<BundleSummary> {
+        let mut stack = self.load_stack()?;
+        let mut audit = self.load_audit()?;
+        let mut backups = self
+            .list_backups()?
+            .into_iter()
+            .map(|id| Ok((self.load_backup(&id)?, id)))
+            .collect::<Result<Vec<_>>>()?;
+
+        if redact {
+            let mut redactor = Redactor::new(self.default_branch().ok());
+            redactor.stack(&mut stack)?;
+            for (refs, _) in &mut backups {
+                for (name, _) in refs.iter_mut() {
+                    *name = redactor.name(name);
+                }
+            }
+            for entry in &mut audit {
+                redactor.audit(entry);
+            }
+        }
+
+        let manifest = BundleManifest {
+            format: FORMAT_VERSION,
+            rung_version: env!("CARGO_PKG_VERSION").to_string(),
+            created_at: Utc::now(),
+            redacted: redact,
+        };
+
+        let mut archive = tar::Builder::new(GzEncoder::new(writer, Compression::default()));
+        append_json(&mut archive, MANIFEST_FILE, &manifest)?;
+        append_json(&mut archive, STACK_FILE, &stack)?;
+        append_json(&mut archive, AUDIT_FILE, &audit)?;
+        for (refs, id) in &backups {
+            append_json(&mut archive, &format!("{BACKUPS_DIR}{id}.json"), refs)?;
+        }
+        archive.into_inner()?.finish()?;
+
+        Ok(BundleSummary {
+            branches: stack.branches.len(),
+            backups: backups.len(),
+            operations: audit.len(),
+            redacted: redact,
+        })
+    }
+
+    /// Restore a bundle written by [`State::export_bundle`].
+    ///
+    /// Replaces the stack, appends the bundle's audit entries that aren't in
+    /// the log yet, and adds the bundle's backups alongside existing ones (a
+    /// backup with the same ID is replaced). Initializes rung first if needed.
+    ///
+    /// # Errors
+    /// Returns error if the bundle is malformed or the state can't be written.
+    /// Returns `RedactedBundle`, before writing anything, if the bundle was
+    /// redacted and `allow_redacted` isn't set.
+    pub fn import_bundle(&self, reader: impl Read, allow_redacted: bool) -> Result<BundleSummary> {
+        let mut manifest: Option<BundleManifest> = None;
+        let mut stack: Option<Stack> = None;
+        let mut audit: Vec<AuditEntry> = vec![];
+        let mut backups: Vec<(String, Vec<(String, String)>)> = vec![];
+
+        let mut archive = tar::Archive::new(GzDecoder::new(reader));
+        for entry in archive.entries()? {
+            let mut entry = entry?;
+            let path = entry.path()?.to_string_lossy().into_owned();
+            let mut content = String::new();
+            entry.read_to_string(&mut content)?;
+
+            match path.as_str() {
+                MANIFEST_FILE => manifest = Some(parse(&path, &content)?),
+                STACK_FILE => stack = Some(parse(&path, &content)?),
+                AUDIT_FILE => audit = parse(&path, &content)?,
+                _ => {
+                    let id = path
+                        .strip_prefix(BACKUPS_DIR)
+                        .and_then(|name| name.strip_suffix(".json"))
+                        .filter(|id| id.parse::<i64>().is_ok())
+                        .ok_or_else(|| Error::InvalidBundle(format!("unexpected file {path}")))?;
+                    backups.push((id.to_string(), parse(&path, &content)?));
+                }
+            }
+        }
+
+        let manifest = manifest.ok_or_else(|| Error::InvalidBundle("no manifest".into()))?;
+        if manifest.format > FORMAT_VERSION {
+            return Err(Error::InvalidBundle(format!(
+                "written by rung {} in a newer format - upgrade rung to import it",
+                manifest.rung_version
+            )));
+        }
+        if manifest.redacted && !allow_redacted {
+            return Err(Error::RedactedBundle);
+        }
+        let stack = stack.ok_or_else(|| Error::InvalidBundle("no stack".into()))?;
+
+        self.init()?;
+        self.save_stack(&stack)?;
+        let existing = self.load_audit()?;
+        for entry in audit.iter().filter(|entry| !existing.contains(entry)) {
+            self.append_audit(entry)?;
+        }
+        for (id, refs) in &backups {
+            let refs: Vec<(&str, &str)> = refs
+                .iter()
+                .map(|(name, sha)| (name.as_str(), sha.as_str()))
+                .collect();
+            self.write_backup(id, &refs)?;
+        }
+
+        Ok(BundleSummary {
+            branches: stack.branches.len(),
+            backups: backups.len(),
+            operations: audit.len(),
+            redacted: manifest.redacted,
+        })
+    }
+}
+
+/// Append a value to the archive as a pretty-printed JSON file.
+fn append_json<W: Write>(
+    archive: &mut tar::Builder<W>,
+    path: &str,
+    value: &impl Serialize,
+) -> Result<()> {
+    let content = serde_json::to_vec_pretty(value)?;
+    let mut header = tar::Header::new_gnu();
+    header.set_size(content.len() as u64);
+    header.set_mode(0o644);
+    header.set_mtime(u64::try_from(Utc::now().timestamp()).unwrap_or_default());
+    header.set_cksum();
+    archive.append_data(&mut header, path, content.as_slice())?;
+    Ok(())
+}
+
+/// Parse one file of a bundle.
+fn parse<T: serde::de::DeserializeOwned>(path: &str, content: &str) -> Result<T> {
+    serde_json::from_str(content).map_err(|e| Error::InvalidBundle(format!("{path}: {e}")))
+}
+
+/// Replaces branch names with stable placeholders.
+struct Redactor {
+    keep: Option<String>,
+    names: HashMap<String, String>,
+}
+
+impl Redactor {
+    fn new(keep: Option<String>) -> Self {
+        Self {
+            keep,
+            names: HashMap::new(),
+        }
+    }
+
+    /// The placeholder for a branch, allocated on first use.
+    fn name(&mut self, name: &str) -> String {
+        if self.keep.as_deref() == Some(name) {
+            return name.to_string();
+        }
+        let next = self.names.len() + 1;
+        self.names
+            .entry(name.to_string())
+            .or_insert_with(|| format!("branch-{next}"))
+            .clone()
+    }
+
+    fn branch_name(&mut self, name: &BranchName) -> Result<BranchName> {
+        BranchName::new(self.name(name.as_str()))
+    }
+
+    fn stack(&mut self, stack: &mut Stack) -> Result<()> {
+        for branch in &mut stack.branches {
+            branch.name = self.branch_name(&branch.name)?;
+            if let Some(parent) = &branch.parent {
+                branch.parent = Some(self.branch_name(parent)?);
+            }
+        }
+        for merged in &mut stack.merged {
+            merged.name = self.branch_name(&merged.name)?;
+            if let Some(parent) = &merged.parent {
+                merged.parent = Some(self.branch_name(parent)?);
+            }
+        }
+        Ok(())
+    }
+
+    /// Redact an entry's refs, and drop its command's arguments, which
+    /// often name branches.
+    fn audit(&mut self, entry: &mut AuditEntry) {
+        for change in &mut entry.refs {
+            change.branch = self.name(&change.branch);
+        }
+        entry.command = entry
+            .command
+            .split_whitespace()
+            .take(2)
+            .collect::<Vec<_>>()
+            .join(" ");
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::StackBranch;
+    use crate::state::RefChange;
+    use tempfile::TempDir;
+
+    fn state_with_history() -> (TempDir, State) {
+        let temp = TempDir::new().unwrap();
+        std::fs::create_dir(temp.path().join(".git")).unwrap();
+        let state = State::new(temp.path()).unwrap();
+        state.init().unwrap();
+
+        let mut stack = Stack::new();
+        let mut auth = StackBranch::try_new("feature/auth", Some("main")).unwrap();
+        auth.pr = Some(7);
+        stack.add_branch(auth);
+        stack.add_branch(StackBranch::try_new("feature/secret-api", Some("feature/auth")).unwrap());
+        state.save_stack(&stack).unwrap();
+
+        state
+            .append_audit(&AuditEntry {
+                timestamp: Utc::now(),
+                user: "Test <test@example.com>".into(),
+                command: "rung create feature/secret-api".into(),
+                success: true,
+                refs: vec![RefChange {
+                    branch: "feature/secret-api".into(),
+                    old: None,
+                    new: Some("abc123".into()),
+                }],
+            })
+            .unwrap();
+        state
+            .write_backup("1700000000", &[("feature/auth", "def456")])
+            .unwrap();
+        (temp, state)
+    }
+
+    #[test]
+    fn test_bundle_roundtrip() {
+        let (_temp, state) = state_with_history();
+        let mut bundle = vec![];
+        let summary = state.export_bundle(&mut bundle, false).unwrap();
+        assert_eq!(summary.branches, 2);
+        assert_eq!(summary.backups, 1);
+        assert_eq!(summary.operations, 1);
+
+        let target = TempDir::new().unwrap();
+        std::fs::create_dir(target.path().join(".git")).unwrap();
+        let restored = State::new(target.path()).unwrap();
+        let summary = restored.import_bundle(bundle.as_slice(), false).unwrap();
+        assert!(!summary.redacted);
+
+        let stack = restored.load_stack().unwrap();
+        assert_eq!(stack.branches[1].name, "feature/secret-api");
+        assert_eq!(stack.branches[0].pr, Some(7));
+        assert_eq!(restored.load_audit().unwrap().len(), 1);
+        assert_eq!(restored.latest_backup().unwrap(), "1700000000");
+        assert_eq!(
+            restored.load_backup("1700000000").unwrap(),
+            vec![("feature/auth".to_string(), "def456".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_import_keeps_existing_audit_log() {
+        let (_temp, state) = state_with_history();
+        let mut bundle = vec![];
+        state.export_bundle(&mut bundle, false).unwrap();
+
+        let local = AuditEntry {
+            timestamp: Utc::now(),
+            user: "Test <test@example.com>".into(),
+            command: "rung sync".into(),
+            success: true,
+            refs: vec![],
+        };
+        state.append_audit(&local).unwrap();
+
+        // Entries already in the log aren't added twice
+        state.import_bundle(bundle.as_slice(), false).unwrap();
+        let audit = state.load_audit().unwrap();
+        assert_eq!(audit.len(), 2);
+        assert_eq!(audit[0].command, "rung create feature/secret-api");
+        assert_eq!(audit[1], local);
+    }
+
+    #[test]
+    fn test_bundle_redacts_branch_names() {
+        let (_temp, state) = state_with_history();
+        let mut bundle = vec![];
+        state.export_bundle(&mut bundle, true).unwrap();
+
+        let target = TempDir::new().unwrap();
+        std::fs::create_dir(target.path().join(".git")).unwrap();
+        let restored = State::new(target.path()).unwrap();
+        assert!(matches!(
+            restored.import_bundle(bundle.as_slice(), false),
+            Err(Error::RedactedBundle)
+        ));
+        assert!(!restored.is_initialized());
+        assert!(
+            restored
+                .import_bundle(bundle.as_slice(), true)
+                .unwrap()
+                .redacted
+        );
+
+        let stack = restored.load_stack().unwrap();
+        assert_eq!(stack.branches[0].name, "branch-1");
+        assert_eq!(stack.branches[0].parent.as_ref().unwrap(), "main");
+        assert_eq!(stack.branches[1].parent.as_ref().unwrap(), "branch-1");
+
+        let audit = restored.load_audit().unwrap();
+        assert_eq!(audit[0].command, "rung create");
+        assert_eq!(audit[0].refs[0].branch, "branch-2");
+        assert_eq!(restored.load_backup("1700000000").unwrap()[0].0, "branch-1");
+    }
+
+    #[test]
+    fn test_import_rejects_garbage() {
+        let target = TempDir::new().unwrap();
+        std::fs::create_dir(target.path().join(".git")).unwrap();
+        let state = State::new(target.path()).unwrap();
+        assert!(state.import_bundle(&b"not a bundle"[..], false).is_err());
+        assert!(!state.is_initialized());
+    }
+}
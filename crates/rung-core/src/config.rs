@@ -1,11 +1,19 @@
 //! Configuration management for Rung.
+//!
+//! Settings come from `.git/rung/config.toml`, and any of them can be
+//! overridden with a `RUNG_<SECTION>_<KEY>` environment variable, e.g.
+//! `RUNG_SUBMIT_DRAFT=true` or `RUNG_GENERAL_DEFAULT_BRANCH=develop`.
 
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::Path;
 
 use serde::{Deserialize, Serialize};
 
-use crate::error::Result;
+use crate::error::{Error, Result};
+
+/// Prefix of environment variables that override config values.
+const ENV_PREFIX: &str = "RUNG_";
 
 /// Rung configuration loaded from .git/rung/config.toml.
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
@@ -17,6 +25,54 @@ pub struct Config {
     /// GitHub-specific settings.
     #[serde(default)]
     pub github: GitHubConfig,
+
+    /// Absorb settings.
+    #[serde(default)]
+    pub absorb: AbsorbConfig,
+
+    /// Rebase settings.
+    #[serde(default)]
+    pub rebase: RebaseConfig,
+
+    /// Branch protection policy.
+    #[serde(default)]
+    pub policy: PolicyConfig,
+
+    /// User scripts run before and after commands.
+    #[serde(default)]
+    pub hooks: HooksConfig,
+
+    /// Sync settings.
+    #[serde(default)]
+    pub sync: SyncConfig,
+
+    /// Submit settings.
+    #[serde(default)]
+    pub submit: SubmitConfig,
+
+    /// Merge settings.
+    #[serde(default)]
+    pub merge: MergeConfig,
+
+    /// Create settings.
+    #[serde(default)]
+    pub create: CreateConfig,
+
+    /// Status settings.
+    #[serde(default)]
+    pub status: StatusConfig,
+
+    /// Doctor settings.
+    #[serde(default)]
+    pub doctor: DoctorConfig,
+
+    /// Terminal output settings.
+    #[serde(default)]
+    pub output: OutputConfig,
+
+    /// Self-update settings.
+    #[serde(default)]
+    pub update: UpdateConfig,
 }
 
 impl Config {
@@ -35,6 +91,134 @@ impl Config {
         Ok(config)
     }
 
+    /// Apply `RUNG_*` environment variable overrides.
+    ///
+    /// # Errors
+    /// Returns error if a variable has a value of the wrong type.
+    pub fn apply_env(&mut self) -> Result<()> {
+        let vars: BTreeMap<String, String> = std::env::vars()
+            .filter(|(name, _)| name.starts_with(ENV_PREFIX))
+            .collect();
+        self.apply_overrides(&vars)
+    }
+
+    /// Apply overrides from a map of environment variables.
+    #[allow(clippy::too_many_lines)] // One flat entry per setting
+    fn apply_overrides(&mut self, vars: &BTreeMap<String, String>) -> Result<()> {
+        let var = |names: &[&str]| {
+            names.iter().find_map(|name| {
+                vars.get(&format!("{ENV_PREFIX}{name}"))
+                    .map(|value| (format!("{ENV_PREFIX}{name}"), value.trim().to_string()))
+            })
+        };
+
+        if let Some((_, value)) = var(&["GENERAL_DEFAULT_REMOTE", "REMOTE"]) {
+            self.general.default_remote = Some(value).filter(|v| !v.is_empty());
+        }
+        if let Some((_, value)) = var(&["GENERAL_DEFAULT_BRANCH", "BASE_BRANCH"]) {
+            self.general.default_branch = Some(value).filter(|v| !v.is_empty());
+        }
+        if let Some((name, value)) = var(&["GENERAL_BACKUP_RETENTION"]) {
+            self.general.backup_retention = parse_number(name, value)?;
+        }
+        if let Some((name, value)) = var(&["GENERAL_AUDIT_RETENTION_DAYS"]) {
+            self.general.audit_retention_days = parse_number(name, value)?;
+        }
+        if let Some((name, value)) = var(&["GENERAL_AUTO_SYNC"]) {
+            self.general.auto_sync = parse_bool(name, value)?;
+        }
+        if let Some((_, value)) = var(&["GITHUB_API_URL"]) {
+            self.github.api_url = Some(value).filter(|v| !v.is_empty());
+        }
+        if let Some((_, value)) = var(&["GITHUB_REPO"]) {
+            self.github.repo = Some(value).filter(|v| !v.is_empty());
+        }
+        if let Some((name, value)) = var(&["ABSORB_RESTACK"]) {
+            self.absorb.restack = parse_bool(name, value)?;
+        }
+        if let Some((name, value)) = var(&["REBASE_PRESERVE_AUTHOR_DATE"]) {
+            self.rebase.preserve_author_date = parse_bool(name, value)?;
+        }
+        if let Some((name, value)) = var(&["REBASE_RESET_COMMITTER_DATE"]) {
+            self.rebase.reset_committer_date = parse_bool(name, value)?;
+        }
+        if let Some((_, value)) = var(&["POLICY_PROTECTED"]) {
+            self.policy.protected = split_list(&value);
+        }
+        if let Some((name, value)) = var(&["SYNC_PUSH"]) {
+            self.sync.push =
+                parse_choice(name, value, "all, existing, ancestors, prompt, or none")?;
+        }
+        if let Some((name, value)) = var(&["SUBMIT_DRAFT"]) {
+            self.submit.draft = parse_bool(name, value)?;
+        }
+        if let Some((name, value)) = var(&["SUBMIT_DRAFT_ABOVE_DEPTH"]) {
+            self.submit.draft_above_depth = if value.is_empty() {
+                None
+            } else {
+                Some(parse_number(name, value)?)
+            };
+        }
+        if let Some((name, value)) = var(&["SUBMIT_UPDATE_TITLES"]) {
+            self.submit.update_titles = parse_bool(name, value)?;
+        }
+        if let Some((name, value)) = var(&["SUBMIT_UPDATE_BODIES"]) {
+            self.submit.update_bodies = parse_bool(name, value)?;
+        }
+        if let Some((name, value)) = var(&["SUBMIT_STACK_IN_BODY"]) {
+            self.submit.stack_in_body = parse_bool(name, value)?;
+        }
+        if let Some((name, value)) = var(&["SUBMIT_COLLAPSE_STACK_ABOVE"]) {
+            self.submit.collapse_stack_above = parse_number(name, value)?;
+        }
+        if let Some((name, value)) = var(&["SUBMIT_COMMIT_STATUS"]) {
+            self.submit.commit_status = parse_bool(name, value)?;
+        }
+        if let Some((name, value)) = var(&["MERGE_DELETE_LOCAL"]) {
+            self.merge.delete_local = parse_bool(name, value)?;
+        }
+        if let Some((_, value)) = var(&["MERGE_NOTIFY"]) {
+            self.merge.notify = split_list(&value);
+        }
+        if let Some((_, value)) = var(&["CREATE_BRANCH_TEMPLATE"]) {
+            self.create.branch_template = value;
+        }
+        if let Some((name, value)) = var(&["STATUS_AUTO_FETCH"]) {
+            if !value.is_empty() && parse_age(&value).is_none() {
+                return Err(Error::InvalidEnvOverride {
+                    var: name,
+                    value,
+                    expected: "a duration like 30m, 2h, or 1d",
+                });
+            }
+            self.status.auto_fetch = Some(value).filter(|v| !v.is_empty());
+        }
+        if let Some((name, value)) = var(&["STATUS_STALE_AFTER_DAYS"]) {
+            self.status.stale_after_days = parse_number(name, value)?;
+        }
+        if let Some((name, value)) = var(&["DOCTOR_FAIL_ON"]) {
+            self.doctor.fail_on = parse_choice(name, value, "warning, error, or never")?;
+        }
+        if let Some((name, value)) = var(&["UPDATE_CHANNEL"]) {
+            self.update.channel = parse_choice(name, value, "stable or nightly")?;
+        }
+        if let Some((name, value)) = var(&["OUTPUT_UNICODE"]) {
+            self.output.unicode = parse_bool(name, value)?;
+        }
+
+        // RUNG_HOOKS_PRE_SUBMIT sets the `pre_submit` hook
+        let hooks_prefix = format!("{ENV_PREFIX}HOOKS_");
+        for (name, value) in vars {
+            if let Some(hook) = name.strip_prefix(&hooks_prefix) {
+                self.hooks
+                    .commands
+                    .insert(hook.to_lowercase(), value.clone());
+            }
+        }
+
+        Ok(())
+    }
+
     /// Save config to a TOML file.
     ///
     /// # Errors
@@ -42,17 +226,18 @@ impl Config {
     pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
         let content =
             toml::to_string_pretty(self).map_err(|e| std::io::Error::other(e.to_string()))?;
-        fs::write(path, content)?;
-        Ok(())
+        crate::state::write_atomic(path.as_ref(), content)
     }
 }
 
 /// General Rung settings.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GeneralConfig {
-    /// Default remote to push to.
-    #[serde(default = "default_remote")]
-    pub default_remote: String,
+    /// Remote to push to, fetch from, and detect the forge repository from.
+    /// When unset, `origin` is used, or the only forge remote if `origin`
+    /// isn't one.
+    #[serde(default, alias = "remote", skip_serializing_if = "Option::is_none")]
+    pub default_remote: Option<String>,
 
     /// Default base branch (e.g., "main" or "master").
     /// Detected during `rung init` from the remote's HEAD.
@@ -63,6 +248,10 @@ pub struct GeneralConfig {
     #[serde(default = "default_backup_retention")]
     pub backup_retention: usize,
 
+    /// Days of audit log `rung gc` keeps; 0 keeps it all.
+    #[serde(default = "default_audit_retention_days")]
+    pub audit_retention_days: usize,
+
     /// Whether to automatically sync on checkout.
     #[serde(default)]
     pub auto_sync: bool,
@@ -71,28 +260,505 @@ pub struct GeneralConfig {
 impl Default for GeneralConfig {
     fn default() -> Self {
         Self {
-            default_remote: default_remote(),
+            default_remote: None,
             default_branch: None,
             backup_retention: default_backup_retention(),
+            audit_retention_days: default_audit_retention_days(),
             auto_sync: false,
         }
     }
 }
 
-fn default_remote() -> String {
-    "origin".into()
+impl GeneralConfig {
+    /// How old an audit log entry may get before `rung gc` drops it.
+    #[must_use]
+    pub fn audit_max_age(&self) -> Option<chrono::TimeDelta> {
+        let days = i64::try_from(self.audit_retention_days).ok()?;
+        (days > 0)
+            .then(|| chrono::TimeDelta::try_days(days))
+            .flatten()
+    }
 }
 
 const fn default_backup_retention() -> usize {
     5
 }
 
+const fn default_audit_retention_days() -> usize {
+    90
+}
+
 /// GitHub-specific settings.
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct GitHubConfig {
     /// Custom API URL for GitHub Enterprise.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub api_url: Option<String>,
+
+    /// The `owner/name` of the repository, for remotes rung can't read it
+    /// from.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub repo: Option<String>,
+}
+
+/// Absorb settings.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct AbsorbConfig {
+    /// Restack descendant branches after absorbing (as with `--and-restack`).
+    #[serde(default)]
+    pub restack: bool,
+}
+
+/// Rebase settings, applied whenever rung rewrites commits (sync, restack, absorb).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RebaseConfig {
+    /// Keep each commit's original author date.
+    /// When false, author dates are reset to the time of the rebase.
+    #[serde(default = "default_true")]
+    pub preserve_author_date: bool,
+
+    /// Set committer dates to the time of the rebase.
+    /// When false, each commit's committer date is set to its author date.
+    #[serde(default = "default_true")]
+    pub reset_committer_date: bool,
+}
+
+impl Default for RebaseConfig {
+    fn default() -> Self {
+        Self {
+            preserve_author_date: true,
+            reset_committer_date: true,
+        }
+    }
+}
+
+impl RebaseConfig {
+    /// Date handling to pass to the git layer.
+    #[must_use]
+    pub const fn dates(&self) -> rung_git::RebaseDates {
+        rung_git::RebaseDates {
+            preserve_author_date: self.preserve_author_date,
+            reset_committer_date: self.reset_committer_date,
+        }
+    }
+}
+
+/// Branch protection policy, enforced by every command that rewrites,
+/// force-pushes, or deletes branches.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct PolicyConfig {
+    /// Glob patterns for branches rung must never touch (e.g. `release/*`).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub protected: Vec<String>,
+}
+
+impl PolicyConfig {
+    /// Protected branch matcher to pass to the git layer.
+    #[must_use]
+    pub fn protected_branches(&self) -> rung_git::ProtectedBranches {
+        rung_git::ProtectedBranches::new(&self.protected)
+    }
+}
+
+/// User scripts run around commands, keyed `pre_<command>` or `post_<command>`.
+///
+/// Each value is a shell command run from the repository root with a JSON
+/// description of the invocation on stdin.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct HooksConfig {
+    /// Hook commands by name (e.g. `pre_submit = "./scripts/lint.sh"`).
+    #[serde(flatten)]
+    pub commands: BTreeMap<String, String>,
+}
+
+impl HooksConfig {
+    /// The shell command configured for a hook, if any.
+    #[must_use]
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.commands
+            .get(name)
+            .map(String::as_str)
+            .filter(|cmd| !cmd.trim().is_empty())
+    }
+}
+
+/// Submit settings.
+#[allow(clippy::struct_excessive_bools)] // Settings map directly to flags
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubmitConfig {
+    /// Create new PRs as drafts (as with `--draft`).
+    #[serde(default)]
+    pub draft: bool,
+    /// Open the bottom N branches of the stack for review and create new
+    /// PRs above them as drafts. Unset leaves `draft` in charge.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub draft_above_depth: Option<usize>,
+    /// Regenerate existing PRs' titles (as with `--update-titles`).
+    #[serde(default)]
+    pub update_titles: bool,
+    /// Regenerate existing PRs' bodies (as with `--update-bodies`).
+    #[serde(default)]
+    pub update_bodies: bool,
+    /// Keep the stack navigation in a section of each PR's body rather
+    /// than in a comment.
+    #[serde(default)]
+    pub stack_in_body: bool,
+    /// Stacks with more PRs than this get a collapsed stack comment that
+    /// only lists the PRs around the current one.
+    #[serde(default = "default_collapse_stack_above")]
+    pub collapse_stack_above: usize,
+    /// Set a `rung/stack` commit status on each PR's head giving its
+    /// position in the stack, pending until its parent merges.
+    #[serde(default)]
+    pub commit_status: bool,
+}
+
+impl Default for SubmitConfig {
+    fn default() -> Self {
+        Self {
+            draft: false,
+            draft_above_depth: None,
+            update_titles: false,
+            update_bodies: false,
+            stack_in_body: false,
+            collapse_stack_above: default_collapse_stack_above(),
+            commit_status: false,
+        }
+    }
+}
+
+const fn default_collapse_stack_above() -> usize {
+    8
+}
+
+/// Sync settings.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct SyncConfig {
+    /// Which branches `rung sync` pushes after rebasing (as with `--push`).
+    #[serde(default)]
+    pub push: PushMode,
+}
+
+/// Which stack branches `rung sync` pushes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PushMode {
+    /// Every branch in the stack.
+    #[default]
+    All,
+    /// Only branches that have a PR.
+    Existing,
+    /// Only the branches below the current one.
+    Ancestors,
+    /// Ask for each branch.
+    Prompt,
+    /// Nothing (as with `--no-push`).
+    None,
+}
+
+impl std::str::FromStr for PushMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "all" => Ok(Self::All),
+            "existing" => Ok(Self::Existing),
+            "ancestors" => Ok(Self::Ancestors),
+            "prompt" => Ok(Self::Prompt),
+            "none" => Ok(Self::None),
+            _ => Err(format!(
+                "expected all, existing, ancestors, prompt, or none, got '{s}'"
+            )),
+        }
+    }
+}
+
+impl std::fmt::Display for PushMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::All => "all",
+            Self::Existing => "existing",
+            Self::Ancestors => "ancestors",
+            Self::Prompt => "prompt",
+            Self::None => "none",
+        })
+    }
+}
+
+/// Merge settings.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MergeConfig {
+    /// Delete the merged branch locally (as with `--delete-local`).
+    /// When false, the branch is kept but no longer tracked in the stack.
+    #[serde(default = "default_true")]
+    pub delete_local: bool,
+
+    /// Webhook URLs and shell commands told about each merge. URLs get the
+    /// payload as a JSON POST; commands get it on stdin.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub notify: Vec<String>,
+}
+
+impl Default for MergeConfig {
+    fn default() -> Self {
+        Self {
+            delete_local: true,
+            notify: Vec::new(),
+        }
+    }
+}
+
+/// Create settings.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateConfig {
+    /// Template for branch names derived from `-m`, e.g. `{user}/{slug}`.
+    /// See [`crate::expand_branch_template`] for the placeholders.
+    #[serde(default = "default_branch_template")]
+    pub branch_template: String,
+}
+
+impl Default for CreateConfig {
+    fn default() -> Self {
+        Self {
+            branch_template: default_branch_template(),
+        }
+    }
+}
+
+fn default_branch_template() -> String {
+    "{slug}".into()
+}
+
+/// Status settings.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StatusConfig {
+    /// Fetch before showing status when the remote data is older than this
+    /// (e.g. `30m`, `2h`, `1d`). Unset means never fetch automatically.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_age"
+    )]
+    pub auto_fetch: Option<String>,
+    /// Flag branches whose tip commit is older than this many days, and
+    /// open PRs without a review in as long. 0 turns the warnings off.
+    #[serde(default = "default_stale_after_days")]
+    pub stale_after_days: usize,
+}
+
+impl Default for StatusConfig {
+    fn default() -> Self {
+        Self {
+            auto_fetch: None,
+            stale_after_days: default_stale_after_days(),
+        }
+    }
+}
+
+const fn default_stale_after_days() -> usize {
+    14
+}
+
+impl StatusConfig {
+    /// How old remote data may get before status fetches on its own.
+    #[must_use]
+    pub fn auto_fetch_after(&self) -> Option<chrono::TimeDelta> {
+        self.auto_fetch.as_deref().and_then(parse_age)
+    }
+
+    /// How old a branch's last commit, or an open PR's last review, may get
+    /// before status flags it as stale.
+    #[must_use]
+    pub fn stale_after(&self) -> Option<chrono::TimeDelta> {
+        let days = i64::try_from(self.stale_after_days).ok()?;
+        (days > 0)
+            .then(|| chrono::TimeDelta::try_days(days))
+            .flatten()
+    }
+}
+
+/// Doctor settings.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct DoctorConfig {
+    /// Lowest issue severity that makes `rung doctor` exit nonzero
+    /// (as with `--fail-on`).
+    #[serde(default)]
+    pub fail_on: FailOn,
+}
+
+/// Terminal output settings.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OutputConfig {
+    /// Use Unicode glyphs like ✓ and box drawing. When false, output is
+    /// plain ASCII (as with `--ascii`).
+    #[serde(default = "default_true")]
+    pub unicode: bool,
+}
+
+impl Default for OutputConfig {
+    fn default() -> Self {
+        Self { unicode: true }
+    }
+}
+
+/// Lowest issue severity that fails `rung doctor`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FailOn {
+    /// Exit 1 on warnings and 2 on errors.
+    #[default]
+    Warning,
+    /// Exit 2 on errors; warnings still exit 0.
+    Error,
+    /// Always exit 0.
+    Never,
+}
+
+impl std::str::FromStr for FailOn {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "warning" | "warnings" => Ok(Self::Warning),
+            "error" | "errors" => Ok(Self::Error),
+            "never" => Ok(Self::Never),
+            _ => Err(format!("expected warning, error, or never, got '{s}'")),
+        }
+    }
+}
+
+impl FailOn {
+    /// Exit code for a diagnosis with these issue counts.
+    #[must_use]
+    pub const fn exit_code(self, errors: usize, warnings: usize) -> i32 {
+        match self {
+            Self::Never => 0,
+            _ if errors > 0 => 2,
+            Self::Warning if warnings > 0 => 1,
+            _ => 0,
+        }
+    }
+}
+
+/// Self-update settings.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct UpdateConfig {
+    /// Release channel `rung update` installs from (as with `--channel`).
+    #[serde(default)]
+    pub channel: UpdateChannel,
+}
+
+/// Release channel for `rung update`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UpdateChannel {
+    /// Stable releases only.
+    #[default]
+    Stable,
+    /// The newest release, prereleases included.
+    Nightly,
+}
+
+impl std::str::FromStr for UpdateChannel {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "stable" => Ok(Self::Stable),
+            "nightly" => Ok(Self::Nightly),
+            _ => Err(format!("expected stable or nightly, got '{s}'")),
+        }
+    }
+}
+
+impl std::fmt::Display for UpdateChannel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Stable => "stable",
+            Self::Nightly => "nightly",
+        })
+    }
+}
+
+/// Parse an age like `45s`, `30m`, `2h`, `1d`, or `1w`.
+#[must_use]
+pub fn parse_age(value: &str) -> Option<chrono::TimeDelta> {
+    let value = value.trim();
+    let split = value.find(|c: char| !c.is_ascii_digit())?;
+    let amount: i64 = value[..split].parse().ok()?;
+    match value[split..].trim() {
+        "s" => chrono::TimeDelta::try_seconds(amount),
+        "m" => chrono::TimeDelta::try_minutes(amount),
+        "h" => chrono::TimeDelta::try_hours(amount),
+        "d" => chrono::TimeDelta::try_days(amount),
+        "w" => chrono::TimeDelta::try_weeks(amount),
+        _ => None,
+    }
+}
+
+/// Accept only ages [`parse_age`] understands, so typos fail at load time.
+fn deserialize_age<'de, D>(deserializer: D) -> std::result::Result<Option<String>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let value = Option::<String>::deserialize(deserializer)?;
+    if let Some(age) = &value
+        && parse_age(age).is_none()
+    {
+        return Err(serde::de::Error::custom(format!(
+            "invalid duration '{age}': expected something like 30m, 2h, or 1d"
+        )));
+    }
+    Ok(value)
+}
+
+const fn default_true() -> bool {
+    true
+}
+
+/// Split a comma-separated list override, dropping empty entries.
+fn split_list(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+/// Parse a boolean override, accepting `true`/`false`, `1`/`0`, `yes`/`no`, and `on`/`off`.
+fn parse_bool(var: String, value: String) -> Result<bool> {
+    match value.to_lowercase().as_str() {
+        "true" | "1" | "yes" | "on" => Ok(true),
+        "false" | "0" | "no" | "off" | "" => Ok(false),
+        _ => Err(Error::InvalidEnvOverride {
+            var,
+            value,
+            expected: "true or false",
+        }),
+    }
+}
+
+/// Parse a non-negative number override.
+fn parse_number(var: String, value: String) -> Result<usize> {
+    value.parse().map_err(|_| Error::InvalidEnvOverride {
+        var,
+        value,
+        expected: "a number",
+    })
+}
+
+/// Parse an environment override naming one of a fixed set of values.
+fn parse_choice<T: std::str::FromStr>(
+    var: String,
+    value: String,
+    expected: &'static str,
+) -> Result<T> {
+    value.parse().map_err(|_| Error::InvalidEnvOverride {
+        var,
+        value,
+        expected,
+    })
 }
 
 #[cfg(test)]
@@ -104,9 +770,87 @@ mod tests {
     #[test]
     fn test_default_config() {
         let config = Config::default();
-        assert_eq!(config.general.default_remote, "origin");
+        assert_eq!(config.general.default_remote, None);
         assert_eq!(config.general.backup_retention, 5);
         assert!(!config.general.auto_sync);
+        assert!(config.rebase.preserve_author_date);
+        assert!(config.rebase.reset_committer_date);
+        assert!(config.merge.delete_local);
+        assert_eq!(config.create.branch_template, "{slug}");
+        assert_eq!(config.submit.collapse_stack_above, 8);
+        assert!(config.output.unicode);
+    }
+
+    #[test]
+    fn test_partial_rebase_section_keeps_defaults() {
+        let config: Config = toml::from_str("[rebase]\nreset_committer_date = false\n").unwrap();
+        assert!(config.rebase.preserve_author_date);
+        assert!(!config.rebase.reset_committer_date);
+    }
+
+    #[test]
+    fn test_general_remote_alias() {
+        let config: Config = toml::from_str("[general]\nremote = \"github\"\n").unwrap();
+        assert_eq!(config.general.default_remote.as_deref(), Some("github"));
+    }
+
+    #[test]
+    fn test_policy_protected_branches() {
+        let config: Config =
+            toml::from_str("[policy]\nprotected = [\"main\", \"release/*\"]\n").unwrap();
+        let protected = config.policy.protected_branches();
+        assert!(protected.is_protected("release/2.0"));
+        assert!(!protected.is_protected("feature"));
+        assert!(Config::default().policy.protected.is_empty());
+    }
+
+    #[test]
+    fn test_status_auto_fetch() {
+        assert_eq!(parse_age("30m"), chrono::TimeDelta::try_minutes(30));
+        assert_eq!(parse_age("1d"), chrono::TimeDelta::try_days(1));
+        assert_eq!(parse_age("2x"), None);
+        assert_eq!(parse_age("h"), None);
+        assert!(Config::default().status.auto_fetch_after().is_none());
+        assert_eq!(
+            Config::default().status.stale_after(),
+            chrono::TimeDelta::try_days(14)
+        );
+
+        let err = toml::from_str::<Config>("[status]\nauto_fetch = \"soon\"\n").unwrap_err();
+        assert!(err.to_string().contains("invalid duration 'soon'"));
+    }
+
+    #[test]
+    fn test_doctor_fail_on() {
+        assert_eq!(Config::default().doctor.fail_on, FailOn::Warning);
+        let config: Config = toml::from_str("[doctor]\nfail_on = \"never\"\n").unwrap();
+        assert_eq!(config.doctor.fail_on, FailOn::Never);
+        assert_eq!("Errors".parse(), Ok(FailOn::Error));
+
+        assert_eq!(FailOn::Warning.exit_code(0, 0), 0);
+        assert_eq!(FailOn::Warning.exit_code(0, 3), 1);
+        assert_eq!(FailOn::Warning.exit_code(1, 3), 2);
+        assert_eq!(FailOn::Error.exit_code(0, 3), 0);
+        assert_eq!(FailOn::Error.exit_code(1, 0), 2);
+        assert_eq!(FailOn::Never.exit_code(1, 3), 0);
+    }
+
+    #[test]
+    fn test_sync_push() {
+        assert_eq!(Config::default().sync.push, PushMode::All);
+        let config: Config = toml::from_str("[sync]\npush = \"existing\"\n").unwrap();
+        assert_eq!(config.sync.push, PushMode::Existing);
+        assert_eq!("Ancestors".parse(), Ok(PushMode::Ancestors));
+        assert!("some".parse::<PushMode>().is_err());
+    }
+
+    #[test]
+    fn test_hooks_section() {
+        let config: Config =
+            toml::from_str("[hooks]\npre_submit = \"./lint.sh\"\npost_sync = \"\"\n").unwrap();
+        assert_eq!(config.hooks.get("pre_submit"), Some("./lint.sh"));
+        assert_eq!(config.hooks.get("post_sync"), None);
+        assert_eq!(config.hooks.get("pre_sync"), None);
     }
 
     #[test]
@@ -116,33 +860,183 @@ mod tests {
 
         let config = Config {
             general: GeneralConfig {
-                default_remote: "upstream".into(),
+                default_remote: Some("upstream".into()),
                 default_branch: Some("develop".into()),
                 backup_retention: 10,
+                audit_retention_days: 30,
                 auto_sync: true,
             },
             github: GitHubConfig {
                 api_url: Some("https://github.example.com/api/v3".into()),
+                repo: Some("org/app".into()),
+            },
+            absorb: AbsorbConfig { restack: true },
+            rebase: RebaseConfig {
+                preserve_author_date: false,
+                reset_committer_date: false,
+            },
+            policy: PolicyConfig {
+                protected: vec!["main".into(), "release/*".into()],
+            },
+            hooks: HooksConfig {
+                commands: BTreeMap::from([("pre_submit".into(), "./lint.sh".into())]),
+            },
+            sync: SyncConfig {
+                push: PushMode::Prompt,
+            },
+            submit: SubmitConfig {
+                draft: true,
+                draft_above_depth: Some(2),
+                update_titles: true,
+                update_bodies: false,
+                stack_in_body: true,
+                collapse_stack_above: 12,
+                commit_status: true,
+            },
+            merge: MergeConfig {
+                delete_local: false,
+                notify: vec!["./scripts/announce.sh".into()],
+            },
+            create: CreateConfig {
+                branch_template: "{user}/{slug}".into(),
+            },
+            status: StatusConfig {
+                auto_fetch: Some("2h".into()),
+                stale_after_days: 0,
+            },
+            doctor: DoctorConfig {
+                fail_on: FailOn::Error,
+            },
+            output: OutputConfig { unicode: false },
+            update: UpdateConfig {
+                channel: UpdateChannel::Nightly,
             },
         };
 
         config.save(&path).unwrap();
         let loaded = Config::load(&path).unwrap();
 
-        assert_eq!(loaded.general.default_remote, "upstream");
+        assert_eq!(loaded.general.default_remote.as_deref(), Some("upstream"));
         assert_eq!(loaded.general.default_branch, Some("develop".into()));
         assert_eq!(loaded.general.backup_retention, 10);
+        assert_eq!(
+            loaded.general.audit_max_age(),
+            chrono::TimeDelta::try_days(30)
+        );
         assert!(loaded.general.auto_sync);
+        assert!(loaded.absorb.restack);
+        assert!(!loaded.rebase.preserve_author_date);
+        assert!(!loaded.rebase.reset_committer_date);
+        assert_eq!(loaded.policy.protected, vec!["main", "release/*"]);
+        assert_eq!(loaded.hooks.get("pre_submit"), Some("./lint.sh"));
+        assert_eq!(loaded.sync.push, PushMode::Prompt);
+        assert!(loaded.submit.draft);
+        assert_eq!(loaded.submit.draft_above_depth, Some(2));
+        assert!(loaded.submit.update_titles);
+        assert!(!loaded.submit.update_bodies);
+        assert!(loaded.submit.stack_in_body);
+        assert_eq!(loaded.submit.collapse_stack_above, 12);
+        assert!(loaded.submit.commit_status);
+        assert!(!loaded.merge.delete_local);
+        assert_eq!(loaded.create.branch_template, "{user}/{slug}");
+        assert_eq!(
+            loaded.status.auto_fetch_after(),
+            chrono::TimeDelta::try_hours(2)
+        );
+        assert!(loaded.status.stale_after().is_none());
+        assert_eq!(loaded.doctor.fail_on, FailOn::Error);
+        assert!(!loaded.output.unicode);
+        assert_eq!(loaded.update.channel, UpdateChannel::Nightly);
         assert_eq!(
             loaded.github.api_url,
             Some("https://github.example.com/api/v3".into())
         );
+        assert_eq!(loaded.github.repo.as_deref(), Some("org/app"));
+        assert_eq!(loaded.merge.notify, vec!["./scripts/announce.sh"]);
+    }
+
+    fn env(vars: &[(&str, &str)]) -> BTreeMap<String, String> {
+        vars.iter()
+            .map(|(k, v)| ((*k).to_string(), (*v).to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_env_overrides() {
+        let mut config: Config =
+            toml::from_str("[general]\ndefault_branch = \"main\"\n[absorb]\nrestack = true\n")
+                .unwrap();
+        config
+            .apply_overrides(&env(&[
+                ("RUNG_GENERAL_DEFAULT_BRANCH", "develop"),
+                ("RUNG_REMOTE", "upstream"),
+                ("RUNG_SUBMIT_DRAFT", "true"),
+                ("RUNG_SYNC_PUSH", "none"),
+                ("RUNG_SUBMIT_DRAFT_ABOVE_DEPTH", "1"),
+                ("RUNG_SUBMIT_UPDATE_BODIES", "yes"),
+                ("RUNG_SUBMIT_COMMIT_STATUS", "on"),
+                ("RUNG_ABSORB_RESTACK", "0"),
+                ("RUNG_GENERAL_BACKUP_RETENTION", "3"),
+                ("RUNG_POLICY_PROTECTED", "main, release/*"),
+                ("RUNG_HOOKS_PRE_SUBMIT", "./lint.sh"),
+                ("RUNG_MERGE_DELETE_LOCAL", "no"),
+                (
+                    "RUNG_MERGE_NOTIFY",
+                    "https://hooks.example.com/x,./announce.sh",
+                ),
+                ("RUNG_CREATE_BRANCH_TEMPLATE", "{date}-{slug}"),
+            ]))
+            .unwrap();
+
+        assert_eq!(config.general.default_branch, Some("develop".into()));
+        assert_eq!(config.general.default_remote.as_deref(), Some("upstream"));
+        assert!(config.submit.draft);
+        assert_eq!(config.sync.push, PushMode::None);
+        assert_eq!(config.submit.draft_above_depth, Some(1));
+        assert!(config.submit.update_bodies);
+        assert!(config.submit.commit_status);
+        assert!(!config.absorb.restack);
+        assert_eq!(config.general.backup_retention, 3);
+        assert_eq!(config.policy.protected, vec!["main", "release/*"]);
+        assert_eq!(config.hooks.get("pre_submit"), Some("./lint.sh"));
+        assert!(!config.merge.delete_local);
+        assert_eq!(
+            config.merge.notify,
+            vec!["https://hooks.example.com/x", "./announce.sh"]
+        );
+        assert_eq!(config.create.branch_template, "{date}-{slug}");
+    }
+
+    #[test]
+    fn test_env_full_name_wins_over_alias() {
+        let mut config = Config::default();
+        config
+            .apply_overrides(&env(&[
+                ("RUNG_BASE_BRANCH", "trunk"),
+                ("RUNG_GENERAL_DEFAULT_BRANCH", "develop"),
+            ]))
+            .unwrap();
+        assert_eq!(config.general.default_branch, Some("develop".into()));
+    }
+
+    #[test]
+    fn test_env_invalid_value() {
+        let mut config = Config::default();
+        let err = config
+            .apply_overrides(&env(&[("RUNG_SUBMIT_DRAFT", "maybe")]))
+            .unwrap_err();
+        assert!(err.to_string().contains("RUNG_SUBMIT_DRAFT"));
+
+        let err = config
+            .apply_overrides(&env(&[("RUNG_GENERAL_BACKUP_RETENTION", "lots")]))
+            .unwrap_err();
+        assert!(err.to_string().contains("a number"));
     }
 
     #[test]
     fn test_missing_config_returns_default() {
         let config = Config::load("/nonexistent/path/config.toml").unwrap();
-        assert_eq!(config.general.default_remote, "origin");
+        assert_eq!(config.general.default_remote, None);
         assert_eq!(config.general.default_branch, None);
     }
 }
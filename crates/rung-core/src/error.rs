@@ -33,6 +33,15 @@ pub enum Error {
     #[error("branch '{0}' is not part of a rung stack")]
     NotInStack(String),
 
+    /// A branch locked with `rung lock` would be rewritten.
+    #[error("refusing to {action} locked branch '{branch}' - run `rung unlock {branch}` first")]
+    BranchLocked {
+        /// The locked branch.
+        branch: String,
+        /// What was refused.
+        action: &'static str,
+    },
+
     /// Cyclic dependency detected in stack.
     #[error("cyclic dependency detected: {0}")]
     CyclicDependency(String),
@@ -53,10 +62,51 @@ pub enum Error {
     #[error("no backup found - nothing to undo")]
     NoBackupFound,
 
+    /// No audit log entry with this id.
+    #[error("no operation #{0} in the audit log - see `rung audit`")]
+    OperationNotFound(usize),
+
+    /// A targeted undo would clobber later changes.
+    #[error("operation #{0} can't be undone without discarding later changes")]
+    UndoBlocked(usize),
+
+    /// No snapshot with this name.
+    #[error("no snapshot named '{0}' - see `rung snapshot list`")]
+    SnapshotNotFound(String),
+
+    /// A snapshot name without any letters or digits.
+    #[error("invalid snapshot name '{0}': use letters, digits, spaces or dashes")]
+    InvalidSnapshotName(String),
+
+    /// A state bundle can't be read.
+    #[error("invalid bundle: {0}")]
+    InvalidBundle(String),
+
+    /// Recovering an interrupted operation would discard uncommitted changes.
+    #[error(
+        "can't recover interrupted `rung {operation}`: '{branch}' has uncommitted changes - commit or stash them first"
+    )]
+    RecoveryBlocked {
+        /// The interrupted command.
+        operation: String,
+        /// The checked-out branch recovery would reset.
+        branch: String,
+    },
+
+    /// A redacted bundle would replace the stack with placeholder names.
+    #[error(
+        "bundle was exported with --redact, so its branch names are placeholders - pass --allow-redacted to import it anyway"
+    )]
+    RedactedBundle,
+
     /// Sync already in progress.
     #[error("sync already in progress - run `rung sync --continue` or `rung sync --abort`")]
     SyncInProgress,
 
+    /// Another rung process holds the state lock.
+    #[error("another rung operation is in progress - wait for it to finish or rerun with `--wait`")]
+    Locked,
+
     /// Sync operation failed.
     #[error("sync failed: {0}")]
     SyncFailed(String),
@@ -73,6 +123,17 @@ pub enum Error {
     #[error("json error: {0}")]
     Json(#[from] serde_json::Error),
 
+    /// An environment variable override has an invalid value.
+    #[error("invalid value '{value}' for {var}: expected {expected}")]
+    InvalidEnvOverride {
+        /// The environment variable.
+        var: String,
+        /// The value it was set to.
+        value: String,
+        /// What the variable accepts.
+        expected: &'static str,
+    },
+
     /// TOML parsing error.
     #[error("toml error: {0}")]
     Toml(#[from] toml::de::Error),
@@ -0,0 +1,342 @@
+//! Housekeeping for `.git/rung`.
+//!
+//! Sync backups and the audit log grow with every operation, and the PR
+//! cache keeps entries for branches long merged. [`State::gc`] trims them
+//! and removes temporary files left by writes that were interrupted.
+
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use chrono::{DateTime, TimeDelta, Utc};
+use serde::Serialize;
+
+use crate::error::Result;
+use crate::state::{State, write_atomic};
+
+/// Temporary files younger than this may still belong to a running write.
+const LEFTOVER_MIN_AGE: Duration = Duration::from_secs(60 * 60);
+
+/// What [`State::gc`] keeps.
+#[derive(Debug, Clone, Default)]
+pub struct GcOptions {
+    /// Sync backups to keep, newest first. Backups of an operation in
+    /// progress are always kept.
+    pub backup_retention: usize,
+
+    /// Drop audit log entries older than this; `None` keeps them all.
+    pub audit_max_age: Option<TimeDelta>,
+
+    /// Remotes that still exist; fetch times of any others are dropped.
+    pub remotes: Vec<String>,
+
+    /// Only count what would be removed.
+    pub dry_run: bool,
+}
+
+/// What [`State::gc`] removed, or would remove in a dry run.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct GcReport {
+    /// Sync backups deleted.
+    pub backups: usize,
+    /// Audit log entries dropped.
+    pub audit_entries: usize,
+    /// PR cache and fetch time entries dropped.
+    pub cache_entries: usize,
+    /// Temporary files and directories from interrupted writes deleted.
+    pub leftovers: usize,
+    /// Disk space freed, in bytes.
+    pub bytes: u64,
+}
+
+impl GcReport {
+    /// Whether there was nothing to clean up.
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.backups == 0
+            && self.audit_entries == 0
+            && self.cache_entries == 0
+            && self.leftovers == 0
+    }
+}
+
+impl State {
+    /// Prune old backups, audit entries, and cache entries, and remove
+    /// leftovers of interrupted writes.
+    ///
+    /// Callers should hold the state lock.
+    ///
+    /// # Errors
+    /// Returns error if the state can't be read or a file can't be removed.
+    pub fn gc(&self, options: &GcOptions, now: DateTime<Utc>) -> Result<GcReport> {
+        let mut report = GcReport::default();
+        self.gc_backups(options, &mut report)?;
+        self.gc_audit(options, now, &mut report)?;
+        self.gc_caches(options, &mut report)?;
+        self.gc_leftovers(options, &mut report)?;
+        Ok(report)
+    }
+
+    /// Delete the oldest backups past the retention count.
+    fn gc_backups(&self, options: &GcOptions, report: &mut GcReport) -> Result<()> {
+        let in_use: BTreeSet<String> = [
+            self.load_sync_state().ok().map(|s| s.backup_id),
+            self.load_restack_state().ok().map(|s| s.backup_id),
+            self.load_split_state().ok().map(|s| s.backup_id),
+            self.load_fold_state().ok().map(|s| s.backup_id),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+
+        let backups = self.list_backups()?;
+        let excess = backups.len().saturating_sub(options.backup_retention);
+        for id in backups
+            .iter()
+            .filter(|id| !in_use.contains(*id))
+            .take(excess)
+        {
+            report.bytes += size_of(&self.refs_dir().join(id));
+            report.backups += 1;
+            if !options.dry_run {
+                self.delete_backup(id)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Drop audit entries older than the maximum age.
+    fn gc_audit(
+        &self,
+        options: &GcOptions,
+        now: DateTime<Utc>,
+        report: &mut GcReport,
+    ) -> Result<()> {
+        let Some(max_age) = options.audit_max_age else {
+            return Ok(());
+        };
+        let mut entries = self.load_audit()?;
+        let before = entries.len();
+        entries.retain(|entry| now - entry.timestamp <= max_age);
+        if entries.len() == before {
+            return Ok(());
+        }
+
+        report.audit_entries += before - entries.len();
+        let old_size = size_of(&self.audit_path());
+        if !options.dry_run {
+            self.replace_audit(&entries)?;
+            report.bytes += old_size.saturating_sub(size_of(&self.audit_path()));
+        }
+        Ok(())
+    }
+
+    /// Drop cached PR states for PRs no longer in the stack, and fetch
+    /// times of remotes that are gone.
+    fn gc_caches(&self, options: &GcOptions, report: &mut GcReport) -> Result<()> {
+        if let Some(mut cache) = self.load_pr_cache()? {
+            let prs: BTreeSet<u64> = self
+                .load_stack()?
+                .branches
+                .iter()
+                .filter_map(|b| b.pr)
+                .collect();
+            let before = cache.prs.len() + cache.checks.len();
+            cache.prs.retain(|number, _| prs.contains(number));
+            cache.checks.retain(|number, _| prs.contains(number));
+            let removed = before - cache.prs.len() - cache.checks.len();
+            if removed > 0 {
+                report.cache_entries += removed;
+                let old_size = size_of(&self.pr_cache_path());
+                if !options.dry_run {
+                    self.save_pr_cache(&cache)?;
+                    report.bytes += old_size.saturating_sub(size_of(&self.pr_cache_path()));
+                }
+            }
+        }
+
+        let mut times = self.load_fetch_times()?;
+        let before = times.len();
+        times.retain(|remote, _| options.remotes.contains(remote));
+        if times.len() < before {
+            report.cache_entries += before - times.len();
+            let old_size = size_of(&self.fetch_times_path());
+            if !options.dry_run {
+                write_atomic(
+                    &self.fetch_times_path(),
+                    serde_json::to_string_pretty(&times)?,
+                )?;
+                report.bytes += old_size.saturating_sub(size_of(&self.fetch_times_path()));
+            }
+        }
+        Ok(())
+    }
+
+    /// Remove temporary files of atomic writes and staging directories of
+    /// backups that never finished.
+    fn gc_leftovers(&self, options: &GcOptions, report: &mut GcReport) -> Result<()> {
+        let mut leftovers = Vec::new();
+        for dir in [self.rung_dir().to_path_buf(), self.snapshots_dir()] {
+            leftovers
+                .extend(entries(&dir).filter(|(path, name)| {
+                    path.is_file() && name.starts_with('.') && is_tmp(path)
+                }));
+        }
+        leftovers
+            .extend(entries(&self.refs_dir()).filter(|(path, _)| path.is_dir() && is_tmp(path)));
+
+        let cutoff = SystemTime::now() - LEFTOVER_MIN_AGE;
+        for (path, _) in leftovers {
+            let modified = fs::metadata(&path).and_then(|m| m.modified());
+            if modified.is_ok_and(|at| at > cutoff) {
+                continue;
+            }
+            report.bytes += size_of(&path);
+            report.leftovers += 1;
+            if options.dry_run {
+                continue;
+            }
+            if path.is_dir() {
+                fs::remove_dir_all(&path)?;
+            } else {
+                fs::remove_file(&path)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Paths and names of a directory's entries; none if it can't be read.
+fn entries(dir: &Path) -> impl Iterator<Item = (PathBuf, String)> {
+    fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .filter_map(std::result::Result::ok)
+        .filter_map(|e| Some((e.path(), e.file_name().to_str()?.to_string())))
+}
+
+/// Whether a path has the `.tmp` extension of an unfinished write.
+fn is_tmp(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext == "tmp")
+}
+
+/// Size of a file, or of everything under a directory.
+fn size_of(path: &Path) -> u64 {
+    let Ok(metadata) = fs::metadata(path) else {
+        return 0;
+    };
+    if metadata.is_dir() {
+        entries(path).map(|(child, _)| size_of(&child)).sum()
+    } else {
+        metadata.len()
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::state::{AuditEntry, PrCache, SyncState};
+    use crate::{Stack, StackBranch};
+    use std::collections::BTreeMap;
+    use tempfile::TempDir;
+
+    fn setup() -> (TempDir, State) {
+        let temp = TempDir::new().unwrap();
+        fs::create_dir(temp.path().join(".git")).unwrap();
+        let state = State::new(temp.path()).unwrap();
+        state.init().unwrap();
+        (temp, state)
+    }
+
+    fn audit_entry(timestamp: DateTime<Utc>) -> AuditEntry {
+        AuditEntry {
+            timestamp,
+            user: "Test <test@example.com>".into(),
+            command: "rung sync".into(),
+            success: true,
+            refs: vec![],
+        }
+    }
+
+    #[test]
+    fn test_gc_backups_keeps_recent_and_in_use() {
+        let (_temp, state) = setup();
+        for id in ["100", "200", "300", "400"] {
+            state.write_backup(id, &[("a", "abc")]).unwrap();
+        }
+        state
+            .save_sync_state(&SyncState::new("100".into(), vec!["a".into()]))
+            .unwrap();
+
+        let options = GcOptions {
+            backup_retention: 2,
+            ..GcOptions::default()
+        };
+        let report = state.gc(&options, Utc::now()).unwrap();
+
+        assert_eq!(report.backups, 2);
+        assert!(report.bytes > 0);
+        assert_eq!(state.list_backups().unwrap(), vec!["100", "400"]);
+    }
+
+    #[test]
+    fn test_gc_audit_caches_and_leftovers() {
+        let (_temp, state) = setup();
+        let now = Utc::now();
+        state
+            .append_audit(&audit_entry(now - TimeDelta::days(200)))
+            .unwrap();
+        state.append_audit(&audit_entry(now)).unwrap();
+
+        let mut stack = Stack::default();
+        let mut branch = StackBranch::try_new("a", None::<&str>).unwrap();
+        branch.pr = Some(1);
+        stack.add_branch(branch);
+        state.save_stack(&stack).unwrap();
+        state
+            .save_pr_cache(&PrCache {
+                fetched_at: now,
+                prs: BTreeMap::from([(1, "open".into()), (2, "merged".into())]),
+                checks: BTreeMap::new(),
+            })
+            .unwrap();
+        state.record_fetch("origin").unwrap();
+        state.record_fetch("old-fork").unwrap();
+
+        // Leftovers are only removed once they're old; a fresh one may
+        // still be in use
+        fs::write(state.rung_dir().join(".stack.json.123.tmp"), "{}").unwrap();
+
+        let options = GcOptions {
+            backup_retention: 5,
+            audit_max_age: TimeDelta::try_days(90),
+            remotes: vec!["origin".into()],
+            dry_run: true,
+        };
+        let dry = state.gc(&options, now).unwrap();
+        assert_eq!(
+            (dry.audit_entries, dry.cache_entries, dry.leftovers),
+            (1, 2, 0)
+        );
+        assert_eq!(state.load_audit().unwrap().len(), 2);
+
+        let report = state
+            .gc(
+                &GcOptions {
+                    dry_run: false,
+                    ..options
+                },
+                now,
+            )
+            .unwrap();
+        assert_eq!(report.audit_entries, 1);
+        assert_eq!(state.load_audit().unwrap().len(), 1);
+        let cache = state.load_pr_cache().unwrap().unwrap();
+        assert_eq!(cache.prs.keys().copied().collect::<Vec<_>>(), vec![1]);
+        assert!(state.last_fetch("old-fork").unwrap().is_none());
+        assert!(state.last_fetch("origin").unwrap().is_some());
+        assert!(state.rung_dir().join(".stack.json.123.tmp").exists());
+    }
+}
@@ -0,0 +1,457 @@
+//! Write-ahead journal for operations that move several branches.
+//!
+//! Before a multi-branch operation touches any ref it records every branch it
+//! may move in `.git/rung/journal.json`, and removes the journal once the
+//! operation finishes. If rung dies part-way through, the next command that
+//! takes the state lock finds the journal and recovers with [`recover`]:
+//!
+//! - a [`JournalMode::Replay`] journal knows every branch's target up front,
+//!   so the remaining updates are applied;
+//! - a [`JournalMode::Rollback`] journal only knows where branches started
+//!   (rebases produce their targets as they go), so every branch is moved
+//!   back.
+//!
+//! Either way the operation ends up applied completely or not at all.
+//! Operations paused for conflict resolution mark their journal as paused;
+//! those are finished with `--continue` or `--abort` rather than recovered.
+
+use chrono::{DateTime, Utc};
+use rung_git::{GitOps, Oid};
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Error, Result};
+use crate::traits::StateStore;
+
+/// How to recover an interrupted operation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum JournalMode {
+    /// Move every branch to its `new` commit.
+    Replay,
+    /// Move every branch back to its `old` commit.
+    Rollback,
+}
+
+/// The ref updates of one in-flight operation; stored as `journal.json`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Journal {
+    /// The command that wrote the journal, e.g. `sync`.
+    pub operation: String,
+
+    /// When the operation started.
+    pub started_at: DateTime<Utc>,
+
+    /// How to recover if the operation is interrupted.
+    pub mode: JournalMode,
+
+    /// Whether the operation is paused waiting for the user, rather than
+    /// running.
+    #[serde(default)]
+    pub paused: bool,
+
+    /// The branch the operation is rebasing, if any. Recovery only aborts a
+    /// rebase of this branch; any other rebase in progress is the user's.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rebasing: Option<String>,
+
+    /// The branches the operation may move.
+    pub refs: Vec<JournalRef>,
+}
+
+/// One branch an operation may move.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JournalRef {
+    /// Branch name.
+    pub branch: String,
+
+    /// Tip before the operation, or `None` if the branch didn't exist.
+    pub old: Option<String>,
+
+    /// Tip the operation moves it to, or `None` to delete it. Only used by
+    /// replay journals.
+    pub new: Option<String>,
+}
+
+/// What [`recover`] did with an interrupted operation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Recovery {
+    /// The interrupted command.
+    pub operation: String,
+
+    /// Whether it was finished or undone.
+    pub mode: JournalMode,
+
+    /// Number of branches recovered.
+    pub branches: usize,
+}
+
+impl Journal {
+    /// A journal for an operation whose targets aren't known up front.
+    ///
+    /// `refs` are (`branch`, `commit_sha`) pairs, as stored in backups.
+    #[must_use]
+    pub fn rollback(operation: &str, refs: impl IntoIterator<Item = (String, String)>) -> Self {
+        let refs = refs
+            .into_iter()
+            .map(|(branch, sha)| JournalRef {
+                branch,
+                old: Some(sha),
+                new: None,
+            })
+            .collect();
+        Self::new(operation, JournalMode::Rollback, refs)
+    }
+
+    /// A journal for an operation that moves branches to known commits.
+    #[must_use]
+    pub fn replay(operation: &str, refs: Vec<JournalRef>) -> Self {
+        Self::new(operation, JournalMode::Replay, refs)
+    }
+
+    fn new(operation: &str, mode: JournalMode, refs: Vec<JournalRef>) -> Self {
+        Self {
+            operation: operation.to_string(),
+            started_at: Utc::now(),
+            mode,
+            paused: false,
+            rebasing: None,
+            refs,
+        }
+    }
+
+    /// Where recovery moves `entry`: its `new` tip when replaying, its `old`
+    /// tip when rolling back.
+    fn target<'a>(&self, entry: &'a JournalRef) -> Option<&'a str> {
+        match self.mode {
+            JournalMode::Replay => entry.new.as_deref(),
+            JournalMode::Rollback => entry.old.as_deref(),
+        }
+    }
+}
+
+/// Move branches to the targets of a replay journal, journaling the updates.
+///
+/// # Errors
+/// Returns error if the journal can't be written or a branch can't be moved.
+/// The journal is left in place in that case, so the next command finishes
+/// the updates.
+pub fn apply(repo: &impl GitOps, state: &impl StateStore, journal: &Journal) -> Result<()> {
+    state.save_journal(journal)?;
+    for entry in &journal.refs {
+        move_branch(repo, &entry.branch, entry.new.as_deref())?;
+    }
+    state.clear_journal()
+}
+
+/// Reset branches to backed-up commits, journaling the updates.
+///
+/// `refs` are (`branch`, `commit_sha`) pairs, as stored in backups.
+///
+/// # Errors
+/// Returns error if the journal can't be written or a branch can't be moved.
+pub fn restore(
+    repo: &impl GitOps,
+    state: &impl StateStore,
+    operation: &str,
+    refs: Vec<(String, String)>,
+) -> Result<()> {
+    let refs = refs
+        .into_iter()
+        .map(|(branch, sha)| JournalRef {
+            old: repo.branch_commit(&branch).ok().map(|oid| oid.to_string()),
+            branch,
+            new: Some(sha),
+        })
+        .collect();
+    apply(repo, state, &Journal::replay(operation, refs))
+}
+
+/// Move every branch in a journal back to where it started.
+///
+/// # Errors
+/// Returns error if a branch can't be moved.
+pub fn roll_back(repo: &impl GitOps, journal: &Journal) -> Result<()> {
+    for entry in &journal.refs {
+        move_branch(repo, &entry.branch, entry.old.as_deref())?;
+    }
+    Ok(())
+}
+
+/// Finish or undo an operation interrupted by a crash.
+///
+/// Does nothing if there's no journal or the operation is paused. Otherwise
+/// aborts the rebase the operation left behind, applies the journal, clears
+/// the state of a rolled-back sync or restack, and removes the journal.
+///
+/// Call with the state lock held, before starting a new operation.
+///
+/// # Errors
+/// Returns error if the journal can't be read or a branch can't be moved.
+/// Returns `RecoveryBlocked`, without moving anything, if the checked-out
+/// branch would move while the worktree has uncommitted changes.
+pub fn recover(repo: &impl GitOps, state: &impl StateStore) -> Result<Option<Recovery>> {
+    let Some(journal) = state.load_journal()? else {
+        return Ok(None);
+    };
+    if journal.paused {
+        return Ok(None);
+    }
+
+    // A rebase of any other branch was started by the user after the crash
+    if journal.rebasing.is_some() && repo.rebasing_branch() == journal.rebasing {
+        repo.rebase_abort()?;
+    }
+
+    // Moving the checked-out branch resets the worktree along with it
+    if let Ok(current) = repo.current_branch()
+        && journal.refs.iter().any(|entry| {
+            entry.branch == current
+                && repo
+                    .branch_commit(&current)
+                    .ok()
+                    .map(|oid| oid.to_string())
+                    .as_deref()
+                    != journal.target(entry)
+        })
+        && !repo.is_clean()?
+    {
+        return Err(Error::RecoveryBlocked {
+            operation: journal.operation,
+            branch: current,
+        });
+    }
+
+    match journal.mode {
+        JournalMode::Replay => {
+            for entry in &journal.refs {
+                move_branch(repo, &entry.branch, journal.target(entry))?;
+            }
+        }
+        JournalMode::Rollback => {
+            roll_back(repo, &journal)?;
+            match journal.operation.as_str() {
+                "sync" => state.clear_sync_state()?,
+                "restack" => revert_restack(state)?,
+                _ => {}
+            }
+        }
+    }
+    state.clear_journal()?;
+
+    Ok(Some(Recovery {
+        operation: journal.operation,
+        mode: journal.mode,
+        branches: journal.refs.len(),
+    }))
+}
+
+/// Undo the reparent of an interrupted restack and clear its state.
+fn revert_restack(state: &impl StateStore) -> Result<()> {
+    if !state.is_restack_in_progress() {
+        return Ok(());
+    }
+    let restack_state = state.load_restack_state()?;
+    if restack_state.stack_updated {
+        let mut stack = state.load_stack()?;
+        stack.reparent(
+            &restack_state.target_branch,
+            restack_state.old_parent.as_deref(),
+        )?;
+        state.save_stack(&stack)?;
+    }
+    state.clear_restack_state()
+}
+
+/// Point `branch` at `target`, or delete it if `target` is `None`.
+fn move_branch(repo: &impl GitOps, branch: &str, target: Option<&str>) -> Result<()> {
+    match target {
+        Some(sha) => {
+            let oid = Oid::from_str(sha).map_err(|e| {
+                Error::SyncFailed(format!("invalid commit '{sha}' for branch '{branch}': {e}"))
+            })?;
+            if repo.branch_commit(branch).ok() != Some(oid) {
+                repo.reset_branch(branch, oid)?;
+            }
+        }
+        None if repo.branch_exists(branch) => repo.delete_branch(branch)?,
+        None => {}
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::stack::{Stack, StackBranch};
+    use crate::state::{RestackState, State, SyncState};
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn setup() -> (TempDir, rung_git::Repository, git2::Repository, State) {
+        let temp = TempDir::new().unwrap();
+        let git_repo = git2::Repository::init(temp.path()).unwrap();
+        let sig = git2::Signature::now("Test", "test@example.com").unwrap();
+        fs::write(temp.path().join("README.md"), "# Test").unwrap();
+        let mut index = git_repo.index().unwrap();
+        index.add_path(std::path::Path::new("README.md")).unwrap();
+        index.write().unwrap();
+        let tree = git_repo.find_tree(index.write_tree().unwrap()).unwrap();
+        git_repo
+            .commit(Some("HEAD"), &sig, &sig, "Initial commit", &tree, &[])
+            .unwrap();
+        drop(tree);
+
+        let repo = rung_git::Repository::open(temp.path()).unwrap();
+        let state = State::new(temp.path()).unwrap();
+        state.init().unwrap();
+        (temp, repo, git_repo, state)
+    }
+
+    fn commit(git_repo: &git2::Repository, message: &str) -> String {
+        let sig = git2::Signature::now("Test", "test@example.com").unwrap();
+        let head = git_repo.head().unwrap().peel_to_commit().unwrap();
+        let tree = head.tree().unwrap();
+        git_repo
+            .commit(None, &sig, &sig, message, &tree, &[&head])
+            .unwrap()
+            .to_string()
+    }
+
+    fn branch(git_repo: &git2::Repository, name: &str, sha: &str) {
+        let commit = git_repo
+            .find_commit(git2::Oid::from_str(sha).unwrap())
+            .unwrap();
+        git_repo.branch(name, &commit, true).unwrap();
+    }
+
+    #[test]
+    fn test_recover_rolls_back_interrupted_restack() {
+        let (_temp, repo, git_repo, state) = setup();
+        let before = commit(&git_repo, "before");
+        let after = commit(&git_repo, "after");
+        branch(&git_repo, "feature", &before);
+        branch(&git_repo, "other", &before);
+
+        let mut stack = Stack::default();
+        stack.add_branch(StackBranch::try_new("other", Some("main")).unwrap());
+        stack.add_branch(StackBranch::try_new("feature", Some("other")).unwrap());
+        state.save_stack(&stack).unwrap();
+
+        // The restack moved `feature` and reparented it, then died
+        let mut restack_state = RestackState::new(
+            "backup".to_string(),
+            "feature".to_string(),
+            "main".to_string(),
+            Some("other".to_string()),
+            "main".to_string(),
+            vec!["feature".to_string()],
+            vec![],
+        );
+        restack_state.mark_stack_updated();
+        state.save_restack_state(&restack_state).unwrap();
+        stack.reparent("feature", Some("main")).unwrap();
+        state.save_stack(&stack).unwrap();
+        state
+            .save_journal(&Journal::rollback(
+                "restack",
+                [("feature".to_string(), before.clone())],
+            ))
+            .unwrap();
+        branch(&git_repo, "feature", &after);
+
+        let recovery = recover(&repo, &state).unwrap().unwrap();
+        assert_eq!(recovery.mode, JournalMode::Rollback);
+        assert_eq!(recovery.branches, 1);
+        assert_eq!(repo.branch_commit("feature").unwrap().to_string(), before);
+        assert!(!state.is_restack_in_progress());
+        assert!(state.load_journal().unwrap().is_none());
+        let stack = state.load_stack().unwrap();
+        assert_eq!(
+            stack.find_branch("feature").unwrap().parent.as_deref(),
+            Some("other")
+        );
+
+        // Nothing left to recover
+        assert!(recover(&repo, &state).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_recover_replays_and_skips_paused() {
+        let (_temp, repo, git_repo, state) = setup();
+        let before = commit(&git_repo, "before");
+        let after = commit(&git_repo, "after");
+        branch(&git_repo, "a", &before);
+        branch(&git_repo, "b", &before);
+
+        // A paused sync is left for --continue / --abort
+        let mut journal = Journal::rollback("sync", [("a".to_string(), before.clone())]);
+        journal.paused = true;
+        state.save_journal(&journal).unwrap();
+        state
+            .save_sync_state(&SyncState::new("backup".to_string(), vec!["a".to_string()]))
+            .unwrap();
+        assert!(recover(&repo, &state).unwrap().is_none());
+        assert!(state.is_sync_in_progress());
+
+        // A replay journal finishes the updates, including deletions
+        let journal = Journal::replay(
+            "undo",
+            vec![
+                JournalRef {
+                    branch: "a".to_string(),
+                    old: Some(before.clone()),
+                    new: Some(after.clone()),
+                },
+                JournalRef {
+                    branch: "b".to_string(),
+                    old: Some(before),
+                    new: None,
+                },
+            ],
+        );
+        state.save_journal(&journal).unwrap();
+        let recovery = recover(&repo, &state).unwrap().unwrap();
+        assert_eq!(recovery.mode, JournalMode::Replay);
+        assert_eq!(repo.branch_commit("a").unwrap().to_string(), after);
+        assert!(!repo.branch_exists("b"));
+        assert!(state.load_journal().unwrap().is_none());
+    }
+
+    #[test]
+    fn test_recover_leaves_user_rebase_and_dirty_worktree_alone() {
+        let (temp, repo, git_repo, state) = setup();
+        let before = commit(&git_repo, "before");
+        branch(&git_repo, "feature", &before);
+        let current = repo.current_branch().unwrap();
+        let tip = repo.branch_commit(&current).unwrap().to_string();
+
+        // The user rebased another branch after the crash
+        let rebase_dir = temp.path().join(".git/rebase-merge");
+        fs::create_dir_all(&rebase_dir).unwrap();
+        fs::write(rebase_dir.join("head-name"), "refs/heads/other\n").unwrap();
+        let mut journal = Journal::rollback("sync", [("feature".to_string(), before.clone())]);
+        journal.rebasing = Some("feature".to_string());
+        state.save_journal(&journal).unwrap();
+        assert!(recover(&repo, &state).unwrap().is_some());
+        assert!(rebase_dir.exists());
+        fs::remove_dir_all(&rebase_dir).unwrap();
+
+        // Rolling back the checked-out branch would discard the edit
+        state
+            .save_journal(&Journal::rollback(
+                "sync",
+                [(current.clone(), before.clone())],
+            ))
+            .unwrap();
+        fs::write(temp.path().join("README.md"), "# Edited").unwrap();
+        let err = recover(&repo, &state).unwrap_err();
+        assert!(matches!(err, Error::RecoveryBlocked { ref branch, .. } if *branch == current));
+        assert_eq!(repo.branch_commit(&current).unwrap().to_string(), tip);
+        assert!(state.load_journal().unwrap().is_some());
+
+        fs::write(temp.path().join("README.md"), "# Test").unwrap();
+        assert!(recover(&repo, &state).unwrap().is_some());
+        assert_eq!(repo.branch_commit(&current).unwrap().to_string(), before);
+    }
+}
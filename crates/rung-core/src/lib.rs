@@ -6,23 +6,33 @@
 //! # Architecture
 //!
 //! The crate provides both a concrete [`State`] implementation and
-//! a [`StateStore`] trait for dependency injection and testing.
+//! a [`StateStore`] trait for dependency injection and testing. The
+//! `test-utils` feature adds an in-memory [`StateStore`] in [`testing`].
 
 pub mod absorb;
 pub mod branch_name;
+pub mod bundle;
 pub mod config;
 pub mod error;
+pub mod gc;
+pub mod journal;
 pub mod stack;
 pub mod state;
 pub mod sync;
+#[cfg(any(test, feature = "test-utils"))]
+pub mod testing;
 mod traits;
 
 pub use absorb::{AbsorbPlan, AbsorbResult, UnmapReason};
-pub use branch_name::{BranchName, slugify};
+pub use branch_name::{BranchName, expand_branch_template, slugify};
+pub use bundle::{BundleManifest, BundleSummary};
 pub use config::Config;
 pub use error::{Error, Result};
-pub use stack::{BranchState, Stack, StackBranch};
+pub use gc::{GcOptions, GcReport};
+pub use journal::{Journal, JournalMode, JournalRef, Recovery};
+pub use stack::{BranchState, Stack, StackBranch, StackIndex};
 pub use state::{
-    DivergenceRecord, FoldState, RestackState, SplitPoint, SplitState, State, SyncState,
+    AuditEntry, CachedChecks, DivergenceRecord, EditState, FoldState, PrCache, RefChange,
+    RestackState, Snapshot, SplitPoint, SplitState, State, StateLock, SyncState,
 };
 pub use traits::StateStore;
@@ -1,5 +1,7 @@
 //! Stack data model representing a chain of dependent branches.
 
+use std::collections::{HashMap, HashSet, VecDeque};
+
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
@@ -93,6 +95,15 @@ impl Stack {
         }
     }
 
+    /// Build a lookup index over this stack's parent/child relationships.
+    ///
+    /// Prefer this over repeated [`Stack::children_of`] or [`Stack::descendants`]
+    /// calls when walking many branches, since each of those rebuilds the index.
+    #[must_use]
+    pub fn index(&self) -> StackIndex<'_> {
+        StackIndex::new(self)
+    }
+
     /// Get all children of a branch.
     #[must_use]
     pub fn children_of(&self, name: &str) -> Vec<&StackBranch> {
@@ -107,38 +118,29 @@ impl Stack {
     /// This includes children, grandchildren, etc. The branch itself is NOT included.
     #[must_use]
     pub fn descendants(&self, name: &str) -> Vec<&StackBranch> {
-        let mut result = Vec::new();
-        let mut stack = vec![name];
-
-        while let Some(current_parent) = stack.pop() {
-            for branch in &self.branches {
-                if branch.parent.as_deref() == Some(current_parent) {
-                    result.push(branch);
-                    stack.push(&branch.name);
-                }
-            }
-        }
-        result
+        self.index().descendants(name)
     }
 
     /// Get the ancestry chain for a branch (from root to the branch).
     #[must_use]
     pub fn ancestry(&self, name: &str) -> Vec<&StackBranch> {
-        let mut chain = vec![];
-        let mut current = name;
+        self.index().ancestry(name)
+    }
 
-        while let Some(branch) = self.find_branch(current) {
-            chain.push(branch);
-            match &branch.parent {
-                Some(parent) if self.find_branch(parent).is_some() => {
-                    current = parent;
-                }
-                _ => break,
-            }
+    /// Fail if `branch` is locked.
+    ///
+    /// `action` describes what was refused, e.g. "rebase".
+    ///
+    /// # Errors
+    /// Returns `BranchLocked` if the branch is in the stack and locked.
+    pub fn check_unlocked(&self, branch: &str, action: &'static str) -> crate::Result<()> {
+        if self.find_branch(branch).is_some_and(|b| b.locked) {
+            return Err(crate::Error::BranchLocked {
+                branch: branch.to_string(),
+                action,
+            });
         }
-
-        chain.reverse();
-        chain
+        Ok(())
     }
 
     /// Check if the stack is empty.
@@ -165,9 +167,7 @@ impl Stack {
         }
 
         // Check if new_parent is a descendant of branch
-        self.descendants(branch)
-            .iter()
-            .any(|b| b.name == new_parent)
+        self.index().is_descendant(new_parent, branch)
     }
 
     /// Reparent a branch to a new parent.
@@ -209,6 +209,152 @@ impl Default for Stack {
     }
 }
 
+/// Lookup tables over a stack's parent/child relationships.
+///
+/// Built in a single pass over the branches so that tree walks cost time
+/// proportional to the part of the tree they visit, rather than rescanning
+/// every branch at each step. The index borrows the stack, so it is rebuilt
+/// after any mutation.
+#[derive(Debug)]
+pub struct StackIndex<'a> {
+    by_name: HashMap<&'a str, &'a StackBranch>,
+    children: HashMap<&'a str, Vec<&'a StackBranch>>,
+}
+
+impl<'a> StackIndex<'a> {
+    /// Index the branches of a stack.
+    #[must_use]
+    pub fn new(stack: &'a Stack) -> Self {
+        let mut by_name = HashMap::with_capacity(stack.branches.len());
+        let mut children: HashMap<&str, Vec<&StackBranch>> = HashMap::new();
+
+        for branch in &stack.branches {
+            by_name.insert(branch.name.as_str(), branch);
+            if let Some(parent) = &branch.parent {
+                children.entry(parent.as_str()).or_default().push(branch);
+            }
+        }
+
+        Self { by_name, children }
+    }
+
+    /// Find a branch by name.
+    #[must_use]
+    pub fn get(&self, name: &str) -> Option<&'a StackBranch> {
+        self.by_name.get(name).copied()
+    }
+
+    /// Check if a branch is tracked in the stack.
+    #[must_use]
+    pub fn contains(&self, name: &str) -> bool {
+        self.by_name.contains_key(name)
+    }
+
+    /// Get the children of a branch, in stack order.
+    ///
+    /// `name` does not need to be in the stack, so this also finds the
+    /// branches based directly on trunk or on a merged branch.
+    #[must_use]
+    pub fn children_of(&self, name: &str) -> &[&'a StackBranch] {
+        self.children.get(name).map_or(&[], Vec::as_slice)
+    }
+
+    /// Get all descendants of a branch in topological order (parents before children).
+    ///
+    /// The branch itself is NOT included. Each branch is visited at most once,
+    /// so malformed stacks containing a cycle still terminate.
+    #[must_use]
+    pub fn descendants(&self, name: &str) -> Vec<&'a StackBranch> {
+        let mut result = Vec::new();
+        let mut visited = HashSet::from([name]);
+        let mut stack = vec![name];
+
+        while let Some(current_parent) = stack.pop() {
+            for &branch in self.children_of(current_parent) {
+                if visited.insert(branch.name.as_str()) {
+                    result.push(branch);
+                    stack.push(branch.name.as_str());
+                }
+            }
+        }
+        result
+    }
+
+    /// Get the ancestry chain for a branch (from root to the branch).
+    #[must_use]
+    pub fn ancestry(&self, name: &str) -> Vec<&'a StackBranch> {
+        let mut chain = vec![];
+        let mut visited = HashSet::new();
+        let mut current = self.get(name);
+
+        while let Some(branch) = current {
+            if !visited.insert(branch.name.as_str()) {
+                break;
+            }
+            chain.push(branch);
+            current = branch.parent.as_deref().and_then(|p| self.get(p));
+        }
+
+        chain.reverse();
+        chain
+    }
+
+    /// Check if `name` is a (transitive) descendant of `ancestor`.
+    ///
+    /// Walks up from `name`, so this costs the depth of `name` rather than
+    /// the size of the subtree under `ancestor`.
+    #[must_use]
+    pub fn is_descendant(&self, name: &str, ancestor: &str) -> bool {
+        let mut visited = HashSet::new();
+        let mut current = self.get(name).and_then(|b| b.parent.as_deref());
+
+        while let Some(parent) = current {
+            if parent == ancestor {
+                return true;
+            }
+            if !visited.insert(parent) {
+                return false;
+            }
+            current = self.get(parent).and_then(|b| b.parent.as_deref());
+        }
+        false
+    }
+
+    /// Get the depth of a branch: the number of its ancestors in the stack.
+    ///
+    /// Branches based on trunk (or on any branch outside the stack) have
+    /// depth 0. Returns `None` if the branch is not in the stack.
+    #[must_use]
+    pub fn depth(&self, name: &str) -> Option<usize> {
+        self.contains(name).then(|| self.ancestry(name).len() - 1)
+    }
+
+    /// Compute the depth of every branch in the stack in a single pass.
+    ///
+    /// Branches caught in a parent cycle are unreachable from a root and
+    /// are left out of the map.
+    #[must_use]
+    pub fn depths(&self) -> HashMap<&'a str, usize> {
+        let mut depths = HashMap::with_capacity(self.by_name.len());
+        let mut queue: VecDeque<(&'a StackBranch, usize)> = self
+            .by_name
+            .values()
+            .filter(|b| !b.parent.as_deref().is_some_and(|p| self.contains(p)))
+            .map(|&b| (b, 0))
+            .collect();
+
+        while let Some((branch, depth)) = queue.pop_front() {
+            if depths.insert(branch.name.as_str(), depth).is_some() {
+                continue;
+            }
+            for &child in self.children_of(&branch.name) {
+                queue.push_back((child, depth + 1));
+            }
+        }
+        depths
+    }
+}
+
 /// A branch within a stack.
 ///
 /// Branch names are validated at construction time to prevent:
@@ -227,8 +373,17 @@ pub struct StackBranch {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub pr: Option<u64>,
 
+    /// Parent commit this branch was last based on, used to detect a parent
+    /// that was rewritten (e.g. force-pushed) underneath it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base: Option<String>,
+
     /// When this branch was added to the stack.
     pub created: DateTime<Utc>,
+
+    /// Locked with `rung lock`: commands that rewrite branches leave it alone.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub locked: bool,
 }
 
 impl StackBranch {
@@ -239,7 +394,9 @@ impl StackBranch {
             name,
             parent,
             pr: None,
+            base: None,
             created: Utc::now(),
+            locked: false,
         }
     }
 
@@ -381,6 +538,24 @@ mod tests {
         assert!(descendants.is_empty());
     }
 
+    #[test]
+    fn test_check_unlocked() {
+        let mut stack = Stack::new();
+        stack.add_branch(StackBranch::try_new("a", Some("main")).unwrap());
+        assert!(stack.check_unlocked("a", "rebase").is_ok());
+
+        stack.find_branch_mut("a").unwrap().locked = true;
+        let err = stack.check_unlocked("a", "rebase").unwrap_err();
+        assert!(err.to_string().contains("rung unlock a"));
+        assert!(stack.check_unlocked("main", "rebase").is_ok());
+
+        // Unlocked branches keep stack.json unchanged
+        let json = serde_json::to_string(&stack).unwrap();
+        assert!(json.contains("\"locked\":true"));
+        stack.find_branch_mut("a").unwrap().locked = false;
+        assert!(!serde_json::to_string(&stack).unwrap().contains("locked"));
+    }
+
     #[test]
     fn test_branch_state() {
         assert!(BranchState::Synced.is_healthy());
@@ -496,4 +671,53 @@ mod tests {
         let result = stack.reparent("nonexistent", Some("a"));
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_index_children_and_depths() {
+        let mut stack = Stack::new();
+        // Create tree: main → a → b → c
+        //                    ↘ d
+        stack.add_branch(StackBranch::try_new("a", Some("main")).unwrap());
+        stack.add_branch(StackBranch::try_new("b", Some("a")).unwrap());
+        stack.add_branch(StackBranch::try_new("c", Some("b")).unwrap());
+        stack.add_branch(StackBranch::try_new("d", Some("a")).unwrap());
+
+        let index = stack.index();
+        let children: Vec<&str> = index
+            .children_of("a")
+            .iter()
+            .map(|b| b.name.as_str())
+            .collect();
+        assert_eq!(children, vec!["b", "d"]);
+        assert_eq!(index.children_of("main").len(), 1);
+        assert!(index.children_of("c").is_empty());
+
+        assert!(index.is_descendant("c", "a"));
+        assert!(!index.is_descendant("a", "c"));
+        assert!(!index.is_descendant("d", "b"));
+
+        assert_eq!(index.depth("a"), Some(0));
+        assert_eq!(index.depth("c"), Some(2));
+        assert_eq!(index.depth("missing"), None);
+
+        let depths = index.depths();
+        assert_eq!(depths.len(), 4);
+        assert_eq!(depths["b"], 1);
+        assert_eq!(depths["d"], 1);
+        assert_eq!(depths["c"], 2);
+    }
+
+    #[test]
+    fn test_index_terminates_on_cycle() {
+        let mut stack = Stack::new();
+        stack.add_branch(StackBranch::try_new("a", Some("b")).unwrap());
+        stack.add_branch(StackBranch::try_new("b", Some("a")).unwrap());
+
+        let index = stack.index();
+        assert_eq!(index.descendants("a").len(), 1);
+        assert_eq!(index.ancestry("a").len(), 2);
+        assert!(index.is_descendant("a", "b"));
+        assert!(!index.is_descendant("a", "c"));
+        assert!(index.depths().is_empty());
+    }
 }
@@ -1,14 +1,18 @@
 //! State persistence for .git/rung/ directory.
 
-use std::collections::VecDeque;
-use std::fs;
+use std::collections::{BTreeMap, VecDeque};
+use std::fs::{self, File};
+use std::io::Write;
 use std::path::{Path, PathBuf};
 
 use chrono::{DateTime, Utc};
+use fs4::fs_std::FileExt;
 use serde::{Deserialize, Serialize};
 
 use crate::error::{Error, Result};
+use crate::journal::Journal;
 use crate::stack::Stack;
+use crate::sync::{SyncAction, SyncPlan};
 
 /// Manages the .git/rung/ directory state.
 #[derive(Debug)]
@@ -25,15 +29,41 @@ impl State {
     const RESTACK_STATE_FILE: &'static str = "restack_state";
     const SPLIT_STATE_FILE: &'static str = "split_state";
     const FOLD_STATE_FILE: &'static str = "fold_state";
+    const EDIT_STATE_FILE: &'static str = "edit_state";
+    const JOURNAL_FILE: &'static str = "journal.json";
     const REFS_DIR: &'static str = "refs";
+    const LOCK_FILE: &'static str = "lock";
+    const AUDIT_FILE: &'static str = "audit.jsonl";
+    const PR_CACHE_FILE: &'static str = "pr_cache.json";
+    const FETCH_TIMES_FILE: &'static str = "fetch_times.json";
+    const SNAPSHOTS_DIR: &'static str = "snapshots";
 
     /// Create a new State instance for the given repository.
     ///
+    /// In a linked worktree, where `.git` is a file pointing at the
+    /// worktree's private git dir, the state lives in the common git dir so
+    /// all worktrees share one stack.
+    ///
     /// # Errors
-    /// Returns error if the path doesn't contain a .git directory.
+    /// Returns error if the path doesn't contain a .git directory or file.
     pub fn new(repo_path: impl AsRef<Path>) -> Result<Self> {
-        let git_dir = repo_path.as_ref().join(".git");
-        if !git_dir.exists() {
+        let dot_git = repo_path.as_ref().join(".git");
+        if dot_git.is_file() {
+            return Self::from_git_dir(common_dir_of(&dot_git)?);
+        }
+        Self::from_git_dir(dot_git)
+    }
+
+    /// Create a new State instance rooted at a git directory.
+    ///
+    /// Pass the repository's common git dir (shared by all worktrees, and the
+    /// repository itself for bare repos).
+    ///
+    /// # Errors
+    /// Returns error if the git directory doesn't exist.
+    pub fn from_git_dir(git_dir: impl AsRef<Path>) -> Result<Self> {
+        let git_dir = git_dir.as_ref();
+        if !git_dir.is_dir() {
             return Err(Error::NotARepository);
         }
 
@@ -96,7 +126,7 @@ impl State {
     /// Returns error if serialization or write fails.
     pub fn save_stack(&self, stack: &Stack) -> Result<()> {
         let content = serde_json::to_string_pretty(stack)?;
-        fs::write(self.stack_path(), content)?;
+        write_atomic(&self.stack_path(), content)?;
         Ok(())
     }
 
@@ -106,14 +136,16 @@ impl State {
         self.rung_dir.join(Self::CONFIG_FILE)
     }
 
-    /// Load the config from disk.
+    /// Load the config from disk, with `RUNG_*` environment overrides applied.
     ///
     /// Returns default config if file doesn't exist.
     ///
     /// # Errors
-    /// Returns error if file exists but can't be parsed.
+    /// Returns error if file exists but can't be parsed, or an override is invalid.
     pub fn load_config(&self) -> Result<crate::config::Config> {
-        crate::config::Config::load(self.config_path())
+        let mut config = crate::config::Config::load(self.config_path())?;
+        config.apply_env()?;
+        Ok(config)
     }
 
     /// Save the config to disk.
@@ -168,7 +200,7 @@ impl State {
     /// Returns error if serialization or write fails.
     pub fn save_sync_state(&self, state: &SyncState) -> Result<()> {
         let content = serde_json::to_string_pretty(state)?;
-        fs::write(self.sync_state_path(), content)?;
+        write_atomic(&self.sync_state_path(), content)?;
         Ok(())
     }
 
@@ -216,7 +248,7 @@ impl State {
     /// Returns error if serialization or write fails.
     pub fn save_restack_state(&self, state: &RestackState) -> Result<()> {
         let content = serde_json::to_string_pretty(state)?;
-        fs::write(self.restack_state_path(), content)?;
+        write_atomic(&self.restack_state_path(), content)?;
         Ok(())
     }
 
@@ -264,7 +296,7 @@ impl State {
     /// Returns error if serialization or write fails.
     pub fn save_split_state(&self, state: &SplitState) -> Result<()> {
         let content = serde_json::to_string_pretty(state)?;
-        fs::write(self.split_state_path(), content)?;
+        write_atomic(&self.split_state_path(), content)?;
         Ok(())
     }
 
@@ -312,7 +344,7 @@ impl State {
     /// Returns error if serialization or write fails.
     pub fn save_fold_state(&self, state: &FoldState) -> Result<()> {
         let content = serde_json::to_string_pretty(state)?;
-        fs::write(self.fold_state_path(), content)?;
+        write_atomic(&self.fold_state_path(), content)?;
         Ok(())
     }
 
@@ -328,9 +360,97 @@ impl State {
         Ok(())
     }
 
+    // === Edit state operations ===
+
+    fn edit_state_path(&self) -> PathBuf {
+        self.rung_dir.join(Self::EDIT_STATE_FILE)
+    }
+
+    /// Check if a `rung edit` session is in progress.
+    #[must_use]
+    pub fn is_edit_in_progress(&self) -> bool {
+        self.edit_state_path().exists()
+    }
+
+    /// Load the current edit session.
+    ///
+    /// # Errors
+    /// Returns error if no edit is in progress or file can't be read.
+    pub fn load_edit_state(&self) -> Result<EditState> {
+        if !self.is_edit_in_progress() {
+            return Err(Error::NoBackupFound);
+        }
+
+        let content = fs::read_to_string(self.edit_state_path())?;
+        let state: EditState = serde_json::from_str(&content)?;
+        Ok(state)
+    }
+
+    /// Save the edit session (called by `rung edit`).
+    ///
+    /// # Errors
+    /// Returns error if serialization or write fails.
+    pub fn save_edit_state(&self, state: &EditState) -> Result<()> {
+        let content = serde_json::to_string_pretty(state)?;
+        write_atomic(&self.edit_state_path(), content)?;
+        Ok(())
+    }
+
+    /// Clear the edit session (called when it is finished).
+    ///
+    /// # Errors
+    /// Returns error if file removal fails.
+    pub fn clear_edit_state(&self) -> Result<()> {
+        let path = self.edit_state_path();
+        if path.exists() {
+            fs::remove_file(path)?;
+        }
+        Ok(())
+    }
+
+    // === Journal operations ===
+
+    fn journal_path(&self) -> PathBuf {
+        self.rung_dir.join(Self::JOURNAL_FILE)
+    }
+
+    /// Load the journal of the operation in flight, if any.
+    ///
+    /// # Errors
+    /// Returns error if the journal exists but can't be read or parsed.
+    pub fn load_journal(&self) -> Result<Option<Journal>> {
+        let path = self.journal_path();
+        if !path.exists() {
+            return Ok(None);
+        }
+        let content = fs::read_to_string(path)?;
+        Ok(Some(serde_json::from_str(&content)?))
+    }
+
+    /// Save the journal (called before an operation moves any branch).
+    ///
+    /// # Errors
+    /// Returns error if serialization or write fails.
+    pub fn save_journal(&self, journal: &Journal) -> Result<()> {
+        let content = serde_json::to_string_pretty(journal)?;
+        write_atomic(&self.journal_path(), content)
+    }
+
+    /// Clear the journal (called when an operation completes or aborts).
+    ///
+    /// # Errors
+    /// Returns error if file removal fails.
+    pub fn clear_journal(&self) -> Result<()> {
+        let path = self.journal_path();
+        if path.exists() {
+            fs::remove_file(path)?;
+        }
+        Ok(())
+    }
+
     // === Backup operations ===
 
-    fn refs_dir(&self) -> PathBuf {
+    pub(crate) fn refs_dir(&self) -> PathBuf {
         self.rung_dir.join(Self::REFS_DIR)
     }
 
@@ -342,25 +462,48 @@ impl State {
     /// Returns error if directory creation or file write fails.
     pub fn create_backup(&self, branches: &[(&str, &str)]) -> Result<String> {
         let backup_id = Utc::now().timestamp().to_string();
-        let backup_dir = self.refs_dir().join(&backup_id);
-        fs::create_dir_all(&backup_dir)?;
+        self.write_backup(&backup_id, branches)?;
+        Ok(backup_id)
+    }
+
+    /// Write a backup with the given ID, replacing any backup with that ID.
+    pub(crate) fn write_backup(&self, backup_id: &str, branches: &[(&str, &str)]) -> Result<()> {
+        let refs_dir = self.refs_dir();
+        let backup_dir = refs_dir.join(backup_id);
+
+        // Populate a staging directory and rename it into place, so an
+        // interrupted backup never shows up as a partial (but "latest") one.
+        // The staging name isn't numeric, so backup listing ignores it.
+        let staging_dir = refs_dir.join(format!("{backup_id}.tmp"));
+        if staging_dir.exists() {
+            fs::remove_dir_all(&staging_dir)?;
+        }
+        fs::create_dir_all(&staging_dir)?;
 
         for (branch_name, commit_sha) in branches {
-            let safe_name = branch_name.replace('/', "-");
-            fs::write(backup_dir.join(safe_name), commit_sha)?;
+            // Escape rather than flatten '/' so `a/b` and `a-b` don't collide;
+            // the exact name is stored on the second line.
+            let safe_name = branch_name.replace('%', "%25").replace('/', "%2F");
+            let mut file = File::create(staging_dir.join(safe_name))?;
+            file.write_all(format!("{commit_sha}\n{branch_name}\n").as_bytes())?;
+            file.sync_all()?;
         }
 
-        Ok(backup_id)
+        if backup_dir.exists() {
+            fs::remove_dir_all(&backup_dir)?;
+        }
+        fs::rename(&staging_dir, &backup_dir)?;
+        sync_dir(&refs_dir)
     }
 
-    /// Get the most recent backup ID.
+    /// List backup IDs, oldest first.
     ///
     /// # Errors
-    /// Returns error if no backups exist.
-    pub fn latest_backup(&self) -> Result<String> {
+    /// Returns error if the backup directory can't be read.
+    pub fn list_backups(&self) -> Result<Vec<String>> {
         let refs_dir = self.refs_dir();
         if !refs_dir.exists() {
-            return Err(Error::NoBackupFound);
+            return Ok(vec![]);
         }
 
         let mut backups: Vec<_> = fs::read_dir(&refs_dir)?
@@ -369,14 +512,17 @@ impl State {
             .filter_map(|e| e.file_name().to_str().map(String::from))
             .filter_map(|name| name.parse::<i64>().ok().map(|ts| (ts, name)))
             .collect();
+        backups.sort_by_key(|(ts, _)| *ts);
 
-        backups.sort_by_key(|(ts, _)| std::cmp::Reverse(*ts));
+        Ok(backups.into_iter().map(|(_, name)| name).collect())
+    }
 
-        backups
-            .into_iter()
-            .next()
-            .map(|(_, name)| name)
-            .ok_or(Error::NoBackupFound)
+    /// Get the most recent backup ID.
+    ///
+    /// # Errors
+    /// Returns error if no backups exist.
+    pub fn latest_backup(&self) -> Result<String> {
+        self.list_backups()?.pop().ok_or(Error::NoBackupFound)
     }
 
     /// Load a backup's branch refs.
@@ -395,15 +541,21 @@ impl State {
         for entry in fs::read_dir(&backup_dir)? {
             let entry = entry?;
             if entry.path().is_file() {
-                let name = entry
+                let file_name = entry
                     .file_name()
                     .to_str()
                     .ok_or_else(|| Error::StateParseError {
                         file: entry.path(),
                         message: "invalid filename".into(),
                     })?
-                    .replace('-', "/");
-                let sha = fs::read_to_string(entry.path())?.trim().to_string();
+                    .to_string();
+                let contents = fs::read_to_string(entry.path())?;
+                let mut lines = contents.lines();
+                let sha = lines.next().unwrap_or_default().trim().to_string();
+                // Older backups hold only the SHA, with '/' flattened to '-'
+                let name = lines
+                    .next()
+                    .map_or_else(|| file_name.replace('-', "/"), str::to_string);
                 refs.push((name, sha));
             }
         }
@@ -452,6 +604,317 @@ impl State {
 
         Ok(())
     }
+
+    // === Snapshots ===
+
+    pub(crate) fn snapshots_dir(&self) -> PathBuf {
+        self.rung_dir.join(Self::SNAPSHOTS_DIR)
+    }
+
+    /// Snapshot file for a name; names that slugify alike share a file, so
+    /// `before rebase` and `before-rebase` are the same snapshot.
+    fn snapshot_path(&self, name: &str) -> Result<PathBuf> {
+        let slug = crate::slugify(name);
+        if slug.is_empty() {
+            return Err(Error::InvalidSnapshotName(name.to_string()));
+        }
+        Ok(self.snapshots_dir().join(format!("{slug}.json")))
+    }
+
+    /// Check whether a snapshot with this name exists.
+    #[must_use]
+    pub fn has_snapshot(&self, name: &str) -> bool {
+        self.snapshot_path(name).is_ok_and(|path| path.exists())
+    }
+
+    /// Save a snapshot, replacing any snapshot with the same name.
+    ///
+    /// # Errors
+    /// Returns error if the name is empty or the write fails.
+    pub fn save_snapshot(&self, snapshot: &Snapshot) -> Result<()> {
+        let path = self.snapshot_path(&snapshot.name)?;
+        fs::create_dir_all(self.snapshots_dir())?;
+        let content = serde_json::to_string_pretty(snapshot)?;
+        write_atomic(&path, content)
+    }
+
+    /// Load a snapshot by name.
+    ///
+    /// # Errors
+    /// Returns error if there's no such snapshot or it can't be parsed.
+    pub fn load_snapshot(&self, name: &str) -> Result<Snapshot> {
+        let path = self.snapshot_path(name)?;
+        if !path.exists() {
+            return Err(Error::SnapshotNotFound(name.to_string()));
+        }
+        let content = fs::read_to_string(&path)?;
+        serde_json::from_str(&content).map_err(|e| Error::StateParseError {
+            file: path,
+            message: e.to_string(),
+        })
+    }
+
+    /// Load all snapshots, oldest first.
+    ///
+    /// # Errors
+    /// Returns error if a snapshot can't be read or parsed.
+    pub fn list_snapshots(&self) -> Result<Vec<Snapshot>> {
+        let dir = self.snapshots_dir();
+        if !dir.exists() {
+            return Ok(vec![]);
+        }
+
+        let mut snapshots = vec![];
+        for entry in fs::read_dir(&dir)? {
+            let path = entry?.path();
+            if path.extension().is_some_and(|ext| ext == "json") {
+                let content = fs::read_to_string(&path)?;
+                snapshots.push(serde_json::from_str::<Snapshot>(&content).map_err(|e| {
+                    Error::StateParseError {
+                        file: path,
+                        message: e.to_string(),
+                    }
+                })?);
+            }
+        }
+        snapshots.sort_by_key(|s| s.created_at);
+        Ok(snapshots)
+    }
+
+    /// Delete a snapshot.
+    ///
+    /// # Errors
+    /// Returns error if there's no such snapshot or it can't be removed.
+    pub fn delete_snapshot(&self, name: &str) -> Result<()> {
+        let path = self.snapshot_path(name)?;
+        if !path.exists() {
+            return Err(Error::SnapshotNotFound(name.to_string()));
+        }
+        fs::remove_file(path)?;
+        Ok(())
+    }
+
+    // === PR status cache ===
+
+    pub(crate) fn pr_cache_path(&self) -> PathBuf {
+        self.rung_dir.join(Self::PR_CACHE_FILE)
+    }
+
+    /// Load the last fetched PR statuses, if any were saved.
+    ///
+    /// # Errors
+    /// Returns error if the cache exists but can't be read or parsed.
+    pub fn load_pr_cache(&self) -> Result<Option<PrCache>> {
+        let path = self.pr_cache_path();
+        if !path.exists() {
+            return Ok(None);
+        }
+        let content = fs::read_to_string(path)?;
+        Ok(Some(serde_json::from_str(&content)?))
+    }
+
+    /// Save fetched PR statuses for use when the forge can't be reached.
+    ///
+    /// # Errors
+    /// Returns error if serialization or write fails.
+    pub fn save_pr_cache(&self, cache: &PrCache) -> Result<()> {
+        let content = serde_json::to_string_pretty(cache)?;
+        write_atomic(&self.pr_cache_path(), content)
+    }
+
+    // === Remote fetch times ===
+
+    pub(crate) fn fetch_times_path(&self) -> PathBuf {
+        self.rung_dir.join(Self::FETCH_TIMES_FILE)
+    }
+
+    pub(crate) fn load_fetch_times(&self) -> Result<BTreeMap<String, DateTime<Utc>>> {
+        let path = self.fetch_times_path();
+        if !path.exists() {
+            return Ok(BTreeMap::new());
+        }
+        let content = fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    /// When rung last fetched `remote`, if it has.
+    ///
+    /// # Errors
+    /// Returns error if the record exists but can't be read or parsed.
+    pub fn last_fetch(&self, remote: &str) -> Result<Option<DateTime<Utc>>> {
+        Ok(self.load_fetch_times()?.get(remote).copied())
+    }
+
+    /// Record that `remote` was just fetched.
+    ///
+    /// # Errors
+    /// Returns error if the record can't be read or written.
+    pub fn record_fetch(&self, remote: &str) -> Result<()> {
+        let mut times = self.load_fetch_times()?;
+        times.insert(remote.to_string(), Utc::now());
+        let content = serde_json::to_string_pretty(&times)?;
+        write_atomic(&self.fetch_times_path(), content)
+    }
+
+    // === Audit log ===
+
+    pub(crate) fn audit_path(&self) -> PathBuf {
+        self.rung_dir.join(Self::AUDIT_FILE)
+    }
+
+    /// Append an entry to the audit log.
+    ///
+    /// The log is append-only JSON Lines, one operation per line, and is
+    /// only ever rewritten wholesale when `gc` prunes it.
+    ///
+    /// # Errors
+    /// Returns error if serialization or the write fails.
+    pub fn append_audit(&self, entry: &AuditEntry) -> Result<()> {
+        let mut line = serde_json::to_string(entry)?;
+        line.push('\n');
+        let mut file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.audit_path())?;
+        file.write_all(line.as_bytes())?;
+        Ok(())
+    }
+
+    /// Replace the whole audit log, as when pruning it.
+    pub(crate) fn replace_audit(&self, entries: &[AuditEntry]) -> Result<()> {
+        let mut content = String::new();
+        for entry in entries {
+            content.push_str(&serde_json::to_string(entry)?);
+            content.push('\n');
+        }
+        write_atomic(&self.audit_path(), content)
+    }
+
+    /// Load all audit log entries, oldest first.
+    ///
+    /// # Errors
+    /// Returns error if the log can't be read or a line can't be parsed.
+    pub fn load_audit(&self) -> Result<Vec<AuditEntry>> {
+        let path = self.audit_path();
+        if !path.exists() {
+            return Ok(vec![]);
+        }
+
+        fs::read_to_string(&path)?
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| {
+                serde_json::from_str(line).map_err(|e| Error::StateParseError {
+                    file: path.clone(),
+                    message: e.to_string(),
+                })
+            })
+            .collect()
+    }
+
+    // === Locking ===
+
+    fn open_lock_file(&self) -> Result<File> {
+        fs::create_dir_all(&self.rung_dir)?;
+        Ok(File::options()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(self.rung_dir.join(Self::LOCK_FILE))?)
+    }
+
+    /// Acquire the state lock without blocking.
+    ///
+    /// The lock is advisory: it only excludes other rung processes that also
+    /// take it. Mutating commands should hold it for their whole run so two
+    /// invocations can't interleave writes to the stack or operation state.
+    ///
+    /// # Errors
+    /// Returns [`Error::Locked`] if another process holds the lock.
+    pub fn try_lock(&self) -> Result<StateLock> {
+        let file = self.open_lock_file()?;
+        if file.try_lock_exclusive()? {
+            Ok(StateLock { _file: file })
+        } else {
+            Err(Error::Locked)
+        }
+    }
+
+    /// Acquire the state lock, blocking until any other holder releases it.
+    ///
+    /// # Errors
+    /// Returns error if the lock file cannot be opened or locked.
+    pub fn lock(&self) -> Result<StateLock> {
+        let file = self.open_lock_file()?;
+        file.lock_exclusive()?;
+        Ok(StateLock { _file: file })
+    }
+}
+
+/// Resolve the common git dir from a linked worktree's `.git` file.
+///
+/// The file contains `gitdir: <path>` pointing at `.git/worktrees/<name>`,
+/// which in turn has a `commondir` file with the (usually relative) path to
+/// the shared git dir.
+fn common_dir_of(dot_git_file: &Path) -> Result<PathBuf> {
+    let base = dot_git_file.parent().unwrap_or_else(|| Path::new("."));
+    let contents = fs::read_to_string(dot_git_file)?;
+    let gitdir = contents
+        .lines()
+        .find_map(|line| line.strip_prefix("gitdir:"))
+        .map(|path| base.join(path.trim()))
+        .ok_or(Error::NotARepository)?;
+
+    match fs::read_to_string(gitdir.join("commondir")) {
+        Ok(common) => Ok(gitdir.join(common.trim())),
+        // Submodules use a `.git` file without a commondir
+        Err(_) => Ok(gitdir),
+    }
+}
+
+/// Write a file atomically: write a sibling temp file, fsync it, then rename
+/// it over `path`.
+///
+/// Readers (and a later run after a crash or Ctrl-C) see either the old
+/// contents or the new contents, never a truncated file.
+pub(crate) fn write_atomic(path: &Path, contents: impl AsRef<[u8]>) -> Result<()> {
+    let dir = path.parent().unwrap_or_else(|| Path::new("."));
+    let file_name = path
+        .file_name()
+        .map_or_else(|| "state".into(), |n| n.to_string_lossy());
+    let tmp_path = dir.join(format!(".{file_name}.{}.tmp", std::process::id()));
+
+    let result = (|| {
+        let mut file = File::create(&tmp_path)?;
+        file.write_all(contents.as_ref())?;
+        file.sync_all()?;
+        fs::rename(&tmp_path, path)
+    })();
+
+    if let Err(e) = result {
+        let _ = fs::remove_file(&tmp_path);
+        return Err(e.into());
+    }
+
+    sync_dir(dir)
+}
+
+/// Flush a directory entry update (e.g. a rename) to disk.
+///
+/// Only meaningful on Unix; Windows can't open directories as files.
+fn sync_dir(dir: &Path) -> Result<()> {
+    #[cfg(unix)]
+    File::open(dir)?.sync_all()?;
+    #[cfg(not(unix))]
+    let _ = dir;
+    Ok(())
+}
+
+/// Guard for the state lock; the lock is released when this is dropped.
+#[derive(Debug)]
+#[must_use = "the state lock is released as soon as the guard is dropped"]
+pub struct StateLock {
+    _file: File,
 }
 
 // === Trait Implementation ===
@@ -555,6 +1018,18 @@ impl StateStore for State {
         Self::clear_fold_state(self)
     }
 
+    fn load_journal(&self) -> Result<Option<Journal>> {
+        Self::load_journal(self)
+    }
+
+    fn save_journal(&self, journal: &Journal) -> Result<()> {
+        Self::save_journal(self, journal)
+    }
+
+    fn clear_journal(&self) -> Result<()> {
+        Self::clear_journal(self)
+    }
+
     fn create_backup(&self, branches: &[(&str, &str)]) -> Result<String> {
         Self::create_backup(self, branches)
     }
@@ -594,6 +1069,19 @@ pub struct SyncState {
     /// Branches remaining to be rebased.
     /// Uses `VecDeque` for O(1) `pop_front()` in `advance()`.
     pub remaining: VecDeque<String>,
+
+    /// Branches left untouched with `--skip`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub skipped: Vec<String>,
+
+    /// Trees put off after a conflict so unrelated trees could sync first.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub deferred: Vec<String>,
+
+    /// The plan the sync started with, so `--continue` rebases the remaining
+    /// branches exactly as planned. Missing in syncs paused by older versions.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub plan: Option<SyncPlan>,
 }
 
 impl SyncState {
@@ -609,9 +1097,32 @@ impl SyncState {
             current_branch: current,
             completed: vec![],
             remaining,
+            skipped: vec![],
+            deferred: vec![],
+            plan: None,
+        }
+    }
+
+    /// Create the sync state for a plan, keeping the plan for `--continue`.
+    #[must_use]
+    pub fn for_plan(backup_id: String, plan: SyncPlan) -> Self {
+        let branches = plan.branches.iter().map(|a| a.branch.clone()).collect();
+        Self {
+            plan: Some(plan),
+            ..Self::new(backup_id, branches)
         }
     }
 
+    /// The planned rebase for a branch, if the plan was saved.
+    #[must_use]
+    pub fn planned_action(&self, branch: &str) -> Option<&SyncAction> {
+        self.plan
+            .as_ref()?
+            .branches
+            .iter()
+            .find(|action| action.branch == branch)
+    }
+
     /// Mark current branch as complete and move to next.
     pub fn advance(&mut self) {
         if !self.current_branch.is_empty() {
@@ -626,6 +1137,140 @@ impl SyncState {
     pub fn is_complete(&self) -> bool {
         self.current_branch.is_empty() && self.remaining.is_empty()
     }
+
+    /// Move the current branch's tree behind the branches of other trees,
+    /// and move to the next branch.
+    ///
+    /// Returns false, changing nothing, when no other tree is left or this
+    /// tree was already deferred once.
+    pub fn defer_tree(&mut self) -> bool {
+        let tree_of = |branch: &str| self.planned_action(branch).map(|a| a.tree.clone());
+        let Some(tree) = tree_of(&self.current_branch).filter(|t| !t.is_empty()) else {
+            return false;
+        };
+        if self.deferred.contains(&tree) {
+            return false;
+        }
+        let (mut later, others): (VecDeque<String>, VecDeque<String>) = self
+            .remaining
+            .iter()
+            .cloned()
+            .partition(|branch| tree_of(branch).as_ref() == Some(&tree));
+        if others.is_empty() {
+            return false;
+        }
+
+        later.push_front(std::mem::take(&mut self.current_branch));
+        self.remaining = others;
+        self.remaining.extend(later);
+        self.current_branch = self.remaining.pop_front().unwrap_or_default();
+        self.deferred.push(tree);
+        true
+    }
+
+    /// Leave the current branch, and `also` if still remaining, untouched
+    /// and move to the next branch.
+    pub fn skip(&mut self, also: &[String]) {
+        skip_branches(
+            &mut self.current_branch,
+            &mut self.remaining,
+            &mut self.skipped,
+            also,
+        );
+    }
+}
+
+/// Move the current branch and the remaining branches in `also` to
+/// `skipped`, then pop the next branch to process.
+fn skip_branches(
+    current: &mut String,
+    remaining: &mut VecDeque<String>,
+    skipped: &mut Vec<String>,
+    also: &[String],
+) {
+    if !current.is_empty() {
+        skipped.push(std::mem::take(current));
+    }
+    remaining.retain(|branch| {
+        let skip = also.contains(branch);
+        if skip {
+            skipped.push(branch.clone());
+        }
+        !skip
+    });
+    *current = remaining.pop_front().unwrap_or_default();
+}
+
+/// A labeled record of the stack and its branch tips, saved on request.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Snapshot {
+    /// Name as given, e.g. `before big rebase`.
+    pub name: String,
+
+    /// When the snapshot was taken.
+    pub created_at: DateTime<Utc>,
+
+    /// Tip commit of each stack branch, by branch name.
+    pub branches: BTreeMap<String, String>,
+
+    /// The stack as it was.
+    pub stack: Stack,
+}
+
+/// PR statuses as last fetched from the forge.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PrCache {
+    /// When the statuses were fetched.
+    pub fetched_at: DateTime<Utc>,
+
+    /// Status of each PR by number: `open`, `draft`, `merged`, or `closed`.
+    pub prs: BTreeMap<u64, String>,
+
+    /// Combined CI result of each PR's head commit, by PR number.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub checks: BTreeMap<u64, CachedChecks>,
+}
+
+/// A PR's combined CI result, as last fetched.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CachedChecks {
+    /// Commit the checks ran on; the result is stale once the branch moves.
+    pub sha: String,
+
+    /// `success`, `failure`, `pending`, or `none` when no checks ran.
+    pub conclusion: String,
+}
+
+/// One operation recorded in the audit log.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditEntry {
+    /// When the operation finished.
+    pub timestamp: DateTime<Utc>,
+
+    /// Git identity that ran it (`Name <email>`).
+    pub user: String,
+
+    /// The command line, e.g. `rung sync --base main`.
+    pub command: String,
+
+    /// Whether the command succeeded.
+    pub success: bool,
+
+    /// Local branches the command created, moved, or deleted.
+    pub refs: Vec<RefChange>,
+}
+
+/// A branch ref changed by an audited operation.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RefChange {
+    /// Branch name.
+    pub branch: String,
+
+    /// Commit before the operation (`None` if the branch was created).
+    pub old: Option<String>,
+
+    /// Commit after the operation (`None` if the branch was deleted).
+    pub new: Option<String>,
 }
 
 /// Info about a branch that diverged from its remote tracking branch.
@@ -672,6 +1317,10 @@ pub struct RestackState {
     /// Whether the stack.json has been updated with new parent.
     pub stack_updated: bool,
 
+    /// Branches left untouched with `--skip`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub skipped: Vec<String>,
+
     /// Branches that were diverged from remote when restack started (--force was used).
     #[serde(default)]
     pub diverged_branches: Vec<DivergenceRecord>,
@@ -703,6 +1352,7 @@ impl RestackState {
             completed: vec![],
             remaining,
             stack_updated: false,
+            skipped: vec![],
             diverged_branches,
         }
     }
@@ -722,6 +1372,17 @@ impl RestackState {
         self.current_branch.is_empty() && self.remaining.is_empty()
     }
 
+    /// Leave the current branch, and `also` if still remaining, untouched
+    /// and move to the next branch.
+    pub fn skip(&mut self, also: &[String]) {
+        skip_branches(
+            &mut self.current_branch,
+            &mut self.remaining,
+            &mut self.skipped,
+            also,
+        );
+    }
+
     /// Mark the stack as updated.
     pub const fn mark_stack_updated(&mut self) {
         self.stack_updated = true;
@@ -894,6 +1555,36 @@ impl FoldState {
     }
 }
 
+/// A branch checked out with `rung edit`, whose descendants are restacked
+/// once the edit is finished.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EditState {
+    /// When the edit started.
+    pub started_at: DateTime<Utc>,
+
+    /// The branch being edited.
+    pub branch: String,
+
+    /// Tip of the branch when the edit started.
+    pub tip: String,
+
+    /// Branch the user was on before `rung edit` (returned to by `rung done`).
+    pub original_branch: String,
+}
+
+impl EditState {
+    /// Create a new edit session.
+    #[must_use]
+    pub fn new(branch: String, tip: String, original_branch: String) -> Self {
+        Self {
+            started_at: Utc::now(),
+            branch,
+            tip,
+            original_branch,
+        }
+    }
+}
+
 #[cfg(test)]
 #[allow(clippy::unwrap_used)]
 mod tests {
@@ -907,6 +1598,33 @@ mod tests {
         (temp, state)
     }
 
+    #[test]
+    fn test_linked_worktree_shares_common_state() {
+        let temp = TempDir::new().unwrap();
+        let main = temp.path().join("main");
+        let private = main.join(".git/worktrees/wt");
+        fs::create_dir_all(&private).unwrap();
+        fs::write(private.join("commondir"), "../..\n").unwrap();
+
+        let worktree = temp.path().join("wt");
+        fs::create_dir(&worktree).unwrap();
+        fs::write(
+            worktree.join(".git"),
+            format!("gitdir: {}\n", private.display()),
+        )
+        .unwrap();
+
+        let main_state = State::new(&main).unwrap();
+        main_state.init().unwrap();
+
+        let wt_state = State::new(&worktree).unwrap();
+        assert!(wt_state.is_initialized());
+        assert_eq!(
+            wt_state.rung_dir().canonicalize().unwrap(),
+            main_state.rung_dir().canonicalize().unwrap()
+        );
+    }
+
     #[test]
     fn test_init_and_check() {
         let (_temp, state) = setup_test_repo();
@@ -916,6 +1634,36 @@ mod tests {
         assert!(state.is_initialized());
     }
 
+    #[test]
+    fn test_lock_is_exclusive() {
+        let (_temp, state) = setup_test_repo();
+        state.init().unwrap();
+
+        let guard = state.try_lock().unwrap();
+        assert!(matches!(state.try_lock(), Err(Error::Locked)));
+
+        drop(guard);
+        assert!(state.try_lock().is_ok());
+    }
+
+    #[test]
+    fn test_write_atomic_replaces_file() {
+        let (temp, _state) = setup_test_repo();
+        let path = temp.path().join("data.json");
+
+        write_atomic(&path, "old").unwrap();
+        write_atomic(&path, "new").unwrap();
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "new");
+        // No temp files left behind
+        let entries: Vec<_> = fs::read_dir(temp.path())
+            .unwrap()
+            .filter_map(std::result::Result::ok)
+            .filter(|e| e.file_name().to_string_lossy().ends_with(".tmp"))
+            .collect();
+        assert!(entries.is_empty());
+    }
+
     #[test]
     fn test_stack_persistence() {
         let (_temp, state) = setup_test_repo();
@@ -931,6 +1679,208 @@ mod tests {
         assert_eq!(loaded.branches[0].name, "feature/test");
     }
 
+    #[test]
+    fn test_edit_state_roundtrip() {
+        let (_temp, state) = setup_test_repo();
+        state.init().unwrap();
+        assert!(!state.is_edit_in_progress());
+
+        let edit = EditState::new("feature/a".into(), "abc123".into(), "feature/b".into());
+        state.save_edit_state(&edit).unwrap();
+        assert!(state.is_edit_in_progress());
+        let loaded = state.load_edit_state().unwrap();
+        assert_eq!(loaded.branch, "feature/a");
+        assert_eq!(loaded.original_branch, "feature/b");
+
+        state.clear_edit_state().unwrap();
+        assert!(!state.is_edit_in_progress());
+    }
+
+    #[test]
+    fn test_backup_keeps_hyphenated_names() {
+        let (_temp, state) = setup_test_repo();
+        state.init().unwrap();
+
+        let branches = vec![("feature-1", "abc123"), ("feature/1", "def456")];
+        let backup_id = state.create_backup(&branches).unwrap();
+
+        let mut loaded = state.load_backup(&backup_id).unwrap();
+        loaded.sort();
+        assert_eq!(
+            loaded,
+            vec![
+                ("feature-1".to_string(), "abc123".to_string()),
+                ("feature/1".to_string(), "def456".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_pr_cache_roundtrip() {
+        let (_temp, state) = setup_test_repo();
+        state.init().unwrap();
+        assert!(state.load_pr_cache().unwrap().is_none());
+
+        let cache = PrCache {
+            fetched_at: Utc::now(),
+            prs: BTreeMap::from([(1, "open".to_string()), (2, "merged".to_string())]),
+            checks: BTreeMap::from([(
+                1,
+                CachedChecks {
+                    sha: "abc123".to_string(),
+                    conclusion: "success".to_string(),
+                },
+            )]),
+        };
+        state.save_pr_cache(&cache).unwrap();
+
+        let loaded = state.load_pr_cache().unwrap().unwrap();
+        assert_eq!(loaded.prs, cache.prs);
+        assert_eq!(loaded.checks, cache.checks);
+    }
+
+    #[test]
+    fn test_snapshots() {
+        let (_temp, state) = setup_test_repo();
+        state.init().unwrap();
+        assert!(state.list_snapshots().unwrap().is_empty());
+
+        let snapshot = Snapshot {
+            name: "Before big rebase".to_string(),
+            created_at: Utc::now(),
+            branches: BTreeMap::from([("feature".to_string(), "abc123".to_string())]),
+            stack: Stack::new(),
+        };
+        state.save_snapshot(&snapshot).unwrap();
+
+        // Looked up by any name with the same slug
+        assert!(state.has_snapshot("before-big-rebase"));
+        let loaded = state.load_snapshot("before big rebase").unwrap();
+        assert_eq!(loaded.name, "Before big rebase");
+        assert_eq!(loaded.branches, snapshot.branches);
+        assert_eq!(state.list_snapshots().unwrap().len(), 1);
+
+        state.delete_snapshot("before big rebase").unwrap();
+        assert!(matches!(
+            state.load_snapshot("before big rebase"),
+            Err(Error::SnapshotNotFound(_))
+        ));
+        assert!(matches!(
+            state.save_snapshot(&Snapshot {
+                name: "!!!".to_string(),
+                ..snapshot
+            }),
+            Err(Error::InvalidSnapshotName(_))
+        ));
+    }
+
+    #[test]
+    fn test_record_fetch() {
+        let (_temp, state) = setup_test_repo();
+        state.init().unwrap();
+        assert!(state.last_fetch("origin").unwrap().is_none());
+
+        let before = Utc::now();
+        state.record_fetch("origin").unwrap();
+        assert!(state.last_fetch("origin").unwrap().unwrap() >= before);
+        assert!(state.last_fetch("upstream").unwrap().is_none());
+    }
+
+    #[test]
+    fn test_audit_log_appends() {
+        let (_temp, state) = setup_test_repo();
+        state.init().unwrap();
+        assert!(state.load_audit().unwrap().is_empty());
+
+        for command in ["rung sync", "rung undo"] {
+            state
+                .append_audit(&AuditEntry {
+                    timestamp: Utc::now(),
+                    user: "Test <test@example.com>".into(),
+                    command: command.into(),
+                    success: true,
+                    refs: vec![RefChange {
+                        branch: "feature".into(),
+                        old: Some("abc123".into()),
+                        new: None,
+                    }],
+                })
+                .unwrap();
+        }
+
+        let entries = state.load_audit().unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].command, "rung sync");
+        assert_eq!(entries[1].command, "rung undo");
+        assert_eq!(entries[1].refs[0].new, None);
+    }
+
+    #[test]
+    fn test_sync_state_skip() {
+        let branches = ["a", "b", "c", "d"].map(String::from).to_vec();
+        let mut sync_state = SyncState::new("backup".to_string(), branches);
+        sync_state.advance();
+
+        // Skip `b` and its descendant `d`, but not the unrelated `c`
+        sync_state.skip(&["d".to_string()]);
+        assert_eq!(sync_state.completed, vec!["a"]);
+        assert_eq!(sync_state.skipped, vec!["b", "d"]);
+        assert_eq!(sync_state.current_branch, "c");
+        assert!(sync_state.remaining.is_empty());
+
+        sync_state.advance();
+        assert!(sync_state.is_complete());
+    }
+
+    #[test]
+    fn test_sync_state_defer_tree() {
+        let action = |branch: &str, tree: &str| SyncAction {
+            branch: branch.into(),
+            old_base: String::new(),
+            new_base: String::new(),
+            parent_branch: String::new(),
+            applied_through: None,
+            applied_commits: 0,
+            parent_rewritten: false,
+            tree: tree.into(),
+        };
+        let plan = SyncPlan {
+            branches: vec![
+                action("a", "a"),
+                action("a2", "a"),
+                action("a3", "a"),
+                action("b", "b"),
+            ],
+        };
+        let mut sync_state = SyncState::for_plan("backup".to_string(), plan);
+        sync_state.advance();
+
+        // A conflict in `a2` puts the rest of tree `a` behind tree `b`
+        assert!(sync_state.defer_tree());
+        assert_eq!(sync_state.current_branch, "b");
+        assert_eq!(sync_state.remaining, ["a2", "a3"]);
+
+        // Back in tree `a`, nothing else is left to go first
+        sync_state.advance();
+        assert!(!sync_state.defer_tree());
+        assert_eq!(sync_state.current_branch, "a2");
+    }
+
+    #[test]
+    fn test_sync_state_without_plan() {
+        // Sync state written before plans were saved
+        let json = r#"{
+            "started_at": "2026-01-01T00:00:00Z",
+            "backup_id": "1700000000",
+            "current_branch": "feature",
+            "completed": [],
+            "remaining": ["child"]
+        }"#;
+        let sync_state: SyncState = serde_json::from_str(json).unwrap();
+        assert!(sync_state.plan.is_none());
+        assert!(sync_state.planned_action("feature").is_none());
+    }
+
     #[test]
     fn test_backup_operations() {
         let (_temp, state) = setup_test_repo();
@@ -941,6 +1891,7 @@ mod tests {
 
         let loaded = state.load_backup(&backup_id).unwrap();
         assert_eq!(loaded.len(), 2);
+        assert!(loaded.contains(&("feature/a".to_string(), "abc123".to_string())));
 
         let latest = state.latest_backup().unwrap();
         assert_eq!(latest, backup_id);
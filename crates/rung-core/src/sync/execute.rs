@@ -1,11 +1,14 @@
-use super::types::{SyncPlan, SyncResult};
+use super::types::{SkippedCommits, SyncAction, SyncPlan, SyncResult};
 use crate::error::Result;
+use crate::journal::{self, Journal};
 use crate::state::SyncState;
 use crate::traits::StateStore;
 /// Execute a sync operation.
 ///
 /// Rebases all branches in the plan onto their new bases. If a conflict occurs,
 /// the sync is paused and can be continued with `continue_sync` after resolution.
+/// A conflicted tree is first put off once, so the other trees of a wide stack
+/// sync before the sync pauses.
 ///
 /// # Errors
 /// Returns error if sync fails.
@@ -36,66 +39,192 @@ pub fn execute_sync(
 
     let backup_id = state.create_backup(&backup_refs)?;
 
+    // Journal the branches before moving any of them
+    let journal = Journal::rollback("sync", branches_to_backup);
+    state.save_journal(&journal)?;
+
     // Save original branch to restore later
     let original_branch = repo.current_branch().ok();
 
-    // Create sync state
-    let branch_names: Vec<String> = plan.branches.iter().map(|a| a.branch.clone()).collect();
-    let mut sync_state = SyncState::new(backup_id.clone(), branch_names);
+    // Create sync state, keeping the plan for --continue
+    let sync_state = SyncState::for_plan(backup_id, plan);
     state.save_sync_state(&sync_state)?;
 
-    // Execute each rebase
-    for action in plan.branches {
-        // Checkout the branch
-        repo.checkout(&action.branch)?;
+    let result = rebase_remaining(repo, state, sync_state, journal);
 
-        // Get target commit
-        let new_base = rung_git::Oid::from_str(&action.new_base).map_err(|e| {
+    // Restore original branch unless paused on a conflict (best effort)
+    if !matches!(result, Ok(SyncResult::Paused { .. }))
+        && let Some(branch) = original_branch
+    {
+        let _ = repo.checkout(&branch);
+    }
+    result
+}
+
+/// Rebase the checked-out branch for one plan action.
+///
+/// A branch whose parent was already rebased earlier in this sync is moved
+/// onto the parent's new tip, replaying only the commits past the parent's old
+/// tip. Commits already applied on the new base (a squash-merged parent) are
+/// dropped by starting the rebase after `applied_through`, and a branch whose
+/// parent was rewritten is replayed from its recorded base.
+///
+/// Returns the range that was replayed, for reporting dropped commits.
+fn rebase_action(
+    repo: &impl rung_git::GitOps,
+    action: &SyncAction,
+    original_tips: &[(String, String)],
+) -> Result<Replay> {
+    let parse = |sha: &str| {
+        rung_git::Oid::from_str(sha).map_err(|e| {
             crate::error::Error::SyncFailed(format!(
-                "invalid commit '{}' for branch '{}': {e}",
-                action.new_base, action.branch
+                "invalid commit '{sha}' for branch '{}': {e}",
+                action.branch
             ))
-        })?;
+        })
+    };
 
-        // Rebase onto new base
-        match repo.rebase_onto(new_base) {
-            Ok(()) => {
-                // Success - mark as complete and save state
-                sync_state.advance();
-                state.save_sync_state(&sync_state)?;
-            }
-            Err(rung_git::Error::RebaseConflict(files)) => {
-                // Conflict - save state and return Paused
-                state.save_sync_state(&sync_state)?;
-                return Ok(SyncResult::Paused {
-                    at_branch: action.branch,
-                    conflict_files: files,
-                    backup_id,
-                });
+    let old_tip = repo.branch_commit(&action.branch)?;
+    let parent_old_tip = original_tips
+        .iter()
+        .find(|(name, _)| *name == action.parent_branch)
+        .map(|(_, sha)| sha.as_str());
+
+    // Replay only the commits after an explicit upstream where one is known:
+    // the parent's old tip (cascade), the recorded base of a rewritten parent,
+    // or the last commit already applied on the new base.
+    let (upstream, onto) = if let Some(parent_old_tip) = parent_old_tip {
+        let new_base = repo.branch_commit(&action.parent_branch)?;
+        let upstream = if action.parent_rewritten || parent_old_tip == action.old_base {
+            Some(parse(&action.old_base)?)
+        } else {
+            None
+        };
+        (upstream, new_base)
+    } else {
+        let upstream = match &action.applied_through {
+            Some(applied) => Some(parse(applied)?),
+            None if action.parent_rewritten => Some(parse(&action.old_base)?),
+            None => None,
+        };
+        (upstream, parse(&action.new_base)?)
+    };
+
+    let from = if let Some(upstream) = upstream {
+        skip_applied(repo, repo.rebase_onto_from(onto, upstream))?;
+        upstream
+    } else {
+        let from = repo.merge_base(old_tip, onto)?;
+        skip_applied(repo, repo.rebase_onto(onto))?;
+        from
+    };
+    Ok(Replay {
+        from,
+        old_tip,
+        onto,
+    })
+}
+
+/// Rebase the checked-out branch onto its parent's current tip.
+///
+/// Used to continue syncs paused before the plan was saved with the sync
+/// state.
+fn rebase_onto_parent(
+    repo: &impl rung_git::GitOps,
+    stack: &crate::stack::Stack,
+    default_branch: &str,
+    branch_name: &str,
+) -> Result<Replay> {
+    let branch = stack
+        .find_branch(branch_name)
+        .ok_or_else(|| crate::error::Error::NotInStack(branch_name.to_string()))?;
+
+    let parent_name = branch.parent.as_deref().unwrap_or(default_branch);
+    let parent_commit = repo.branch_commit(parent_name)?;
+    let old_tip = repo.branch_commit(branch_name)?;
+    let replay = Replay {
+        from: repo.merge_base(old_tip, parent_commit)?,
+        old_tip,
+        onto: parent_commit,
+    };
+    skip_applied(repo, repo.rebase_onto(parent_commit))?;
+    Ok(replay)
+}
+
+/// The commits a rebase replayed: `from..old_tip`, written on top of `onto`.
+struct Replay {
+    from: rung_git::Oid,
+    old_tip: rung_git::Oid,
+    onto: rung_git::Oid,
+}
+
+/// Resolve a rebase conflict by skipping commits whose changes are already on
+/// the new base, passing through any conflict that remains.
+fn skip_applied(
+    repo: &impl rung_git::GitOps,
+    rebase: rung_git::Result<()>,
+) -> rung_git::Result<()> {
+    match rebase {
+        Err(rung_git::Error::RebaseConflict(files)) => {
+            if repo.skip_applied_commits()? == 0 {
+                return Err(rung_git::Error::RebaseConflict(files));
             }
-            Err(e) => {
-                // Other error - best effort cleanup, preserve original error
-                if repo.is_rebasing() {
-                    let _ = repo.rebase_abort();
-                }
-                let _ = state.clear_sync_state();
-                return Err(e.into());
+            if repo.is_rebasing() {
+                return Err(rung_git::Error::RebaseConflict(repo.conflicting_files()?));
             }
+            Ok(())
         }
+        other => other,
     }
+}
 
-    // All done - clean up sync state
-    state.clear_sync_state()?;
+/// Record the commits a completed rebase dropped from `branch`.
+fn record_dropped(
+    repo: &impl rung_git::GitOps,
+    skipped: &mut Vec<SkippedCommits>,
+    branch: &str,
+    replay: &Replay,
+) -> Result<()> {
+    let new_tip = repo.branch_commit(branch)?;
+    let commits = repo.dropped_commits(replay.from, replay.old_tip, replay.onto, new_tip)?;
+    if !commits.is_empty() {
+        skipped.push(SkippedCommits {
+            branch: branch.to_string(),
+            commits,
+        });
+    }
+    Ok(())
+}
 
-    // Restore original branch if possible
-    if let Some(branch) = original_branch {
-        let _ = repo.checkout(&branch); // Best effort
+/// Record each branch's parent tip as the base it is now built on.
+///
+/// Called after branches were rebased onto their parents, so that a parent
+/// rewritten later (e.g. force-pushed) can be detected by the next sync.
+///
+/// # Errors
+/// Returns error if the stack cannot be loaded or saved.
+pub fn record_bases(
+    repo: &impl rung_git::GitOps,
+    state: &impl StateStore,
+    branches: &[String],
+) -> Result<()> {
+    if branches.is_empty() {
+        return Ok(());
     }
 
-    Ok(SyncResult::Complete {
-        branches_rebased: sync_state.completed.len(),
-        backup_id,
-    })
+    let default_branch = state.default_branch()?;
+    let mut stack = state.load_stack()?;
+    for name in branches {
+        let Some(branch) = stack.find_branch_mut(name) else {
+            continue;
+        };
+        let parent = branch
+            .parent
+            .as_ref()
+            .map_or_else(|| default_branch.clone(), ToString::to_string);
+        branch.base = repo.branch_commit(&parent).ok().map(|oid| oid.to_string());
+    }
+    state.save_stack(&stack)
 }
 
 /// Continue a paused sync after conflict resolution.
@@ -115,7 +244,8 @@ pub fn continue_sync(repo: &impl rung_git::GitOps, state: &impl StateStore) -> R
     // If user ran `git rebase --continue` manually, there won't be one
     if repo.is_rebasing() {
         // Continue the current rebase
-        match repo.rebase_continue() {
+        let mut journal = resume_journal(state, &sync_state)?;
+        match skip_applied(repo, repo.rebase_continue()) {
             Ok(()) => {
                 // Success - mark current branch as complete
                 sync_state.advance();
@@ -123,6 +253,8 @@ pub fn continue_sync(repo: &impl rung_git::GitOps, state: &impl StateStore) -> R
             }
             Err(rung_git::Error::RebaseConflict(files)) => {
                 // More conflicts
+                journal.paused = true;
+                state.save_journal(&journal)?;
                 return Ok(SyncResult::Paused {
                     at_branch: sync_state.current_branch.clone(),
                     conflict_files: files,
@@ -130,9 +262,8 @@ pub fn continue_sync(repo: &impl rung_git::GitOps, state: &impl StateStore) -> R
                 });
             }
             Err(e) => {
-                // Best effort cleanup, preserve original error
-                let _ = repo.rebase_abort();
-                let _ = state.clear_sync_state();
+                // Best effort rollback, preserve original error
+                fail_sync(repo, state, &journal);
                 return Err(e.into());
             }
         }
@@ -165,35 +296,103 @@ pub fn continue_sync(repo: &impl rung_git::GitOps, state: &impl StateStore) -> R
         sync_state.advance();
         state.save_sync_state(&sync_state)?;
     }
+    let journal = resume_journal(state, &sync_state)?;
+    rebase_remaining(repo, state, sync_state, journal)
+}
+
+/// Skip the conflicted branch of a paused sync and continue with the rest.
+///
+/// The branch is left at its tip from before the sync. With `descendants`,
+/// the branches stacked on it are left untouched too; otherwise they are
+/// still rebased, onto the skipped branch as it is.
+///
+/// # Errors
+/// Returns error if no sync in progress or continuation fails.
+pub fn skip_sync(
+    repo: &impl rung_git::GitOps,
+    state: &impl StateStore,
+    descendants: bool,
+) -> Result<SyncResult> {
+    let mut sync_state = state.load_sync_state()?;
+    let branch = sync_state.current_branch.clone();
+
+    // Put the branch back where it was before the sync
+    if repo.is_rebasing() {
+        repo.rebase_abort()?;
+    }
+    let original_tips = state.load_backup(&sync_state.backup_id)?;
+    if let Some(tip) = original_tips.into_iter().find(|(name, _)| *name == branch) {
+        // Already covered by the sync's own journal
+        journal::roll_back(repo, &Journal::rollback("sync", [tip]))?;
+    }
+
+    let also: Vec<String> = if descendants {
+        state
+            .load_stack()?
+            .descendants(&branch)
+            .iter()
+            .map(|b| b.name.to_string())
+            .collect()
+    } else {
+        vec![]
+    };
+    sync_state.skip(&also);
+    state.save_sync_state(&sync_state)?;
+
+    let journal = resume_journal(state, &sync_state)?;
+    rebase_remaining(repo, state, sync_state, journal)
+}
+
+/// Rebase the branches a resumed sync has left, then finish it.
+fn rebase_remaining(
+    repo: &impl rung_git::GitOps,
+    state: &impl StateStore,
+    mut sync_state: SyncState,
+    mut journal: Journal,
+) -> Result<SyncResult> {
+    let backup_id = sync_state.backup_id.clone();
 
     // Process remaining branches (including the one moved to current_branch by advance())
     // Use while loop since advance() moves next branch from remaining to current_branch
-    // Load stack and default branch once before the loop for efficiency
+    // Load stack, default branch and original tips once before the loop for efficiency
     let stack = state.load_stack()?;
     let default_branch = state.default_branch()?;
+    let original_tips = state.load_backup(&backup_id)?;
+    let mut skipped = Vec::new();
 
     while !sync_state.current_branch.is_empty() {
         let branch_name = sync_state.current_branch.clone();
 
+        // Recovery aborts this rebase if rung dies part-way through it
+        journal.rebasing = Some(branch_name.clone());
+        state.save_journal(&journal)?;
+
         // Checkout the branch
         repo.checkout(&branch_name)?;
 
-        // Get parent's current tip (we need to look this up from the stack)
-        let branch = stack
-            .find_branch(&branch_name)
-            .ok_or_else(|| crate::error::Error::NotInStack(branch_name.clone()))?;
-
-        let parent_name = branch.parent.as_deref().unwrap_or(&default_branch);
-        let parent_commit = repo.branch_commit(parent_name)?;
-
-        // Rebase onto parent's tip
-        match repo.rebase_onto(parent_commit) {
-            Ok(()) => {
+        // Rebase as planned, or onto the parent's tip if the plan wasn't saved
+        let rebase = sync_state.planned_action(&branch_name).map_or_else(
+            || rebase_onto_parent(repo, &stack, &default_branch, &branch_name),
+            |action| rebase_action(repo, action, &original_tips),
+        );
+        match rebase {
+            Ok(replay) => {
+                record_dropped(repo, &mut skipped, &branch_name, &replay)?;
                 sync_state.advance();
                 state.save_sync_state(&sync_state)?;
             }
-            Err(rung_git::Error::RebaseConflict(files)) => {
+            Err(crate::error::Error::Git(rung_git::Error::RebaseConflict(_)))
+                if sync_state.defer_tree() =>
+            {
+                // Let the other trees sync first; this conflict comes back
+                // when the sync returns to the tree
+                repo.rebase_abort()?;
                 state.save_sync_state(&sync_state)?;
+            }
+            Err(crate::error::Error::Git(rung_git::Error::RebaseConflict(files))) => {
+                state.save_sync_state(&sync_state)?;
+                journal.paused = true;
+                state.save_journal(&journal)?;
                 return Ok(SyncResult::Paused {
                     at_branch: branch_name,
                     conflict_files: files,
@@ -201,22 +400,23 @@ pub fn continue_sync(repo: &impl rung_git::GitOps, state: &impl StateStore) -> R
                 });
             }
             Err(e) => {
-                // Best effort cleanup, preserve original error
-                if repo.is_rebasing() {
-                    let _ = repo.rebase_abort();
-                }
-                let _ = state.clear_sync_state();
-                return Err(e.into());
+                // Best effort rollback, preserve original error
+                fail_sync(repo, state, &journal);
+                return Err(e);
             }
         }
     }
 
     // All done
+    state.clear_journal()?;
+    record_bases(repo, state, &sync_state.completed)?;
     state.clear_sync_state()?;
 
     Ok(SyncResult::Complete {
         branches_rebased: sync_state.completed.len(),
         backup_id,
+        skipped,
+        skipped_branches: sync_state.skipped,
     })
 }
 
@@ -235,17 +435,39 @@ pub fn abort_sync(repo: &impl rung_git::GitOps, state: &impl StateStore) -> Resu
 
     // Restore all branches from backup
     let refs = state.load_backup(&sync_state.backup_id)?;
-    for (branch_name, sha) in refs {
-        let oid = rung_git::Oid::from_str(&sha).map_err(|e| {
-            crate::error::Error::SyncFailed(format!(
-                "invalid backup commit '{sha}' for branch '{branch_name}': {e}"
-            ))
-        })?;
-        repo.reset_branch(&branch_name, oid)?;
-    }
+    journal::restore(repo, state, "sync --abort", refs)?;
 
     // Clear sync state
     state.clear_sync_state()?;
 
     Ok(())
 }
+
+/// Load the journal of a paused sync and mark it running again, rebasing
+/// the branch it paused on.
+///
+/// Syncs paused by an older rung have no journal, so one is rebuilt from the
+/// sync's backup.
+fn resume_journal(state: &impl StateStore, sync_state: &SyncState) -> Result<Journal> {
+    let mut journal = match state.load_journal()? {
+        Some(journal) => journal,
+        None => Journal::rollback("sync", state.load_backup(&sync_state.backup_id)?),
+    };
+    journal.paused = false;
+    journal.rebasing = Some(sync_state.current_branch.clone()).filter(|b| !b.is_empty());
+    state.save_journal(&journal)?;
+    Ok(journal)
+}
+
+/// Roll back a sync that failed for a reason other than a conflict.
+///
+/// Best effort: the caller reports the original error.
+fn fail_sync(repo: &impl rung_git::GitOps, state: &impl StateStore, journal: &Journal) {
+    if repo.is_rebasing() {
+        let _ = repo.rebase_abort();
+    }
+    if journal::roll_back(repo, journal).is_ok() {
+        let _ = state.clear_journal();
+    }
+    let _ = state.clear_sync_state();
+}
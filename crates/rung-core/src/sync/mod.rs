@@ -14,11 +14,11 @@ mod undo;
 pub use types::*;
 
 // Re-export all public functions
-pub use execute::{abort_sync, continue_sync, execute_sync};
-pub use plan::create_sync_plan;
+pub use execute::{abort_sync, continue_sync, execute_sync, record_bases, skip_sync};
+pub use plan::{create_sync_plan, rewritten_parent_base};
 pub use predict::predict_sync_conflicts;
 pub use reconcile::{reconcile_merged, remove_stale_branches};
-pub use undo::undo_sync;
+pub use undo::{plan_operation_undo, preview_undo, undo_operation, undo_sync};
 
 #[cfg(test)]
 #[allow(clippy::unwrap_used)]
@@ -55,8 +55,19 @@ mod tests {
 
     /// Add a commit to the current branch
     fn add_commit(temp: &TempDir, git_repo: &git2::Repository, filename: &str, message: &str) {
+        commit_file(temp, git_repo, filename, "content", message);
+    }
+
+    /// Commit a file with the given contents to the current branch
+    fn commit_file(
+        temp: &TempDir,
+        git_repo: &git2::Repository,
+        filename: &str,
+        contents: &str,
+        message: &str,
+    ) {
         let sig = git2::Signature::now("Test", "test@example.com").unwrap();
-        fs::write(temp.path().join(filename), "content").unwrap();
+        fs::write(temp.path().join(filename), contents).unwrap();
 
         let mut index = git_repo.index().unwrap();
         index.add_path(std::path::Path::new(filename)).unwrap();
@@ -220,6 +231,252 @@ mod tests {
         assert_eq!(plan.branches[3].branch, "feature-d");
     }
 
+    /// Check out a branch, discarding any working tree changes.
+    fn checkout(git_repo: &git2::Repository, branch: &str) {
+        git_repo.set_head(&format!("refs/heads/{branch}")).unwrap();
+        git_repo
+            .checkout_head(Some(git2::build::CheckoutBuilder::new().force()))
+            .unwrap();
+    }
+
+    /// Configure a committer identity so `git rebase` can run.
+    fn set_identity(git_repo: &git2::Repository) {
+        let mut config = git_repo.config().unwrap();
+        config.set_str("user.name", "Test").unwrap();
+        config.set_str("user.email", "test@example.com").unwrap();
+    }
+
+    #[test]
+    fn test_sync_cascade_moves_children_onto_rebased_parent() {
+        let (temp, rung_repo, git_repo) = init_test_repo();
+        set_identity(&git_repo);
+        let state = State::new(temp.path()).unwrap();
+        state.init().unwrap();
+        let main_branch = rung_repo.current_branch().unwrap();
+
+        // main → feature-a → feature-b, then main moves
+        let head = git_repo.head().unwrap().peel_to_commit().unwrap();
+        git_repo.branch("feature-a", &head, false).unwrap();
+        checkout(&git_repo, "feature-a");
+        add_commit(&temp, &git_repo, "a.txt", "A commit");
+        let head = git_repo.head().unwrap().peel_to_commit().unwrap();
+        git_repo.branch("feature-b", &head, false).unwrap();
+        checkout(&git_repo, "feature-b");
+        add_commit(&temp, &git_repo, "b.txt", "B commit");
+        checkout(&git_repo, &main_branch);
+        add_commit(&temp, &git_repo, "main-update.txt", "Update main");
+
+        let mut stack = Stack::new();
+        stack.add_branch(StackBranch::try_new("feature-a", Some(main_branch.clone())).unwrap());
+        stack.add_branch(StackBranch::try_new("feature-b", Some("feature-a")).unwrap());
+        state.save_stack(&stack).unwrap();
+
+        let plan = create_sync_plan(&rung_repo, &stack, &main_branch).unwrap();
+        let result = execute_sync(&rung_repo, &state, plan).unwrap();
+        assert!(matches!(result, SyncResult::Complete { .. }), "{result:?}");
+
+        let main = rung_repo.branch_commit(&main_branch).unwrap();
+        let a = rung_repo.branch_commit("feature-a").unwrap();
+        let b = rung_repo.branch_commit("feature-b").unwrap();
+        assert_eq!(rung_repo.merge_base(a, b).unwrap(), a);
+        assert_eq!(rung_repo.merge_base(main, b).unwrap(), main);
+        assert_eq!(rung_repo.count_commits_between(a, b).unwrap(), 1);
+    }
+
+    #[test]
+    fn test_sync_drops_commits_of_squash_merged_parent() {
+        let (temp, rung_repo, git_repo) = init_test_repo();
+        set_identity(&git_repo);
+        let state = State::new(temp.path()).unwrap();
+        state.init().unwrap();
+        let main_branch = rung_repo.current_branch().unwrap();
+
+        // feature-a has two commits, feature-b is stacked on top
+        let head = git_repo.head().unwrap().peel_to_commit().unwrap();
+        git_repo.branch("feature-a", &head, false).unwrap();
+        checkout(&git_repo, "feature-a");
+        commit_file(&temp, &git_repo, "a.txt", "draft", "A commit 1");
+        commit_file(&temp, &git_repo, "a.txt", "final", "A commit 2");
+        let a_tip = git_repo.head().unwrap().peel_to_commit().unwrap();
+        git_repo.branch("feature-b", &a_tip, false).unwrap();
+        checkout(&git_repo, "feature-b");
+        add_commit(&temp, &git_repo, "b.txt", "B commit");
+
+        // feature-a is squash-merged into main as a single commit; replaying
+        // its first commit on top of that would conflict
+        checkout(&git_repo, &main_branch);
+        commit_file(&temp, &git_repo, "a.txt", "final", "Feature A (#1)");
+
+        // Reconcile re-parented feature-b onto main
+        let mut stack = Stack::new();
+        stack.add_branch(StackBranch::try_new("feature-b", Some(main_branch.clone())).unwrap());
+        state.save_stack(&stack).unwrap();
+
+        let plan = create_sync_plan(&rung_repo, &stack, &main_branch).unwrap();
+        assert_eq!(plan.branches.len(), 1);
+        assert_eq!(
+            plan.branches[0].applied_through.as_deref(),
+            Some(a_tip.id().to_string().as_str())
+        );
+        assert_eq!(plan.branches[0].applied_commits, 2);
+
+        let result = execute_sync(&rung_repo, &state, plan).unwrap();
+        assert!(matches!(result, SyncResult::Complete { .. }), "{result:?}");
+
+        // Only feature-b's own commit is replayed on top of main
+        let main = rung_repo.branch_commit(&main_branch).unwrap();
+        let b = rung_repo.branch_commit("feature-b").unwrap();
+        assert_eq!(rung_repo.merge_base(main, b).unwrap(), main);
+        assert_eq!(rung_repo.count_commits_between(main, b).unwrap(), 1);
+    }
+
+    #[test]
+    fn test_sync_skips_commits_already_applied_upstream() {
+        let (temp, rung_repo, git_repo) = init_test_repo();
+        set_identity(&git_repo);
+        let state = State::new(temp.path()).unwrap();
+        state.init().unwrap();
+        let main_branch = rung_repo.current_branch().unwrap();
+        commit_file(&temp, &git_repo, "app.txt", "1\n2\n3\n4\n5\n", "Add app");
+
+        // main → feature-a → feature-b, where feature-b carries a hotfix
+        let head = git_repo.head().unwrap().peel_to_commit().unwrap();
+        git_repo.branch("feature-a", &head, false).unwrap();
+        checkout(&git_repo, "feature-a");
+        add_commit(&temp, &git_repo, "a.txt", "A commit");
+        let head = git_repo.head().unwrap().peel_to_commit().unwrap();
+        git_repo.branch("feature-b", &head, false).unwrap();
+        checkout(&git_repo, "feature-b");
+        commit_file(&temp, &git_repo, "app.txt", "1\n2\nfix\n4\n5\n", "Hotfix");
+        let hotfix = git_repo.head().unwrap().peel_to_commit().unwrap();
+        add_commit(&temp, &git_repo, "b.txt", "B commit");
+
+        // The hotfix is cherry-picked to main and then refined there, so
+        // replaying it onto main conflicts
+        checkout(&git_repo, &main_branch);
+        let main_head = git_repo.head().unwrap().peel_to_commit().unwrap();
+        let mut index = git_repo
+            .cherrypick_commit(&hotfix, &main_head, 0, None)
+            .unwrap();
+        let tree = git_repo
+            .find_tree(index.write_tree_to(&git_repo).unwrap())
+            .unwrap();
+        let sig = git2::Signature::now("Test", "test@example.com").unwrap();
+        git_repo
+            .commit(Some("HEAD"), &sig, &sig, "Hotfix", &tree, &[&main_head])
+            .unwrap();
+        git_repo
+            .checkout_head(Some(git2::build::CheckoutBuilder::new().force()))
+            .unwrap();
+        commit_file(
+            &temp,
+            &git_repo,
+            "app.txt",
+            "1\n2\nfix!\n4\n5\n",
+            "Refine fix",
+        );
+
+        let mut stack = Stack::new();
+        stack.add_branch(StackBranch::try_new("feature-a", Some(main_branch.clone())).unwrap());
+        stack.add_branch(StackBranch::try_new("feature-b", Some("feature-a")).unwrap());
+        state.save_stack(&stack).unwrap();
+
+        let plan = create_sync_plan(&rung_repo, &stack, &main_branch).unwrap();
+        let result = execute_sync(&rung_repo, &state, plan).unwrap();
+        let SyncResult::Complete { skipped, .. } = result else {
+            panic!("Expected sync to complete, got {result:?}");
+        };
+
+        assert_eq!(skipped.len(), 1);
+        assert_eq!(skipped[0].branch, "feature-b");
+        assert_eq!(skipped[0].commits.len(), 1);
+        assert!(skipped[0].commits[0].ends_with("Hotfix"));
+
+        let a = rung_repo.branch_commit("feature-a").unwrap();
+        let b = rung_repo.branch_commit("feature-b").unwrap();
+        assert_eq!(rung_repo.count_commits_between(a, b).unwrap(), 1);
+        assert!(!rung_repo.is_rebasing());
+    }
+
+    #[test]
+    fn test_sync_replays_onto_rewritten_parent_from_recorded_base() {
+        let (temp, rung_repo, git_repo) = init_test_repo();
+        set_identity(&git_repo);
+        let state = State::new(temp.path()).unwrap();
+        state.init().unwrap();
+        let main_branch = rung_repo.current_branch().unwrap();
+
+        // main → feature-a → feature-b, with feature-b's base recorded
+        let head = git_repo.head().unwrap().peel_to_commit().unwrap();
+        git_repo.branch("feature-a", &head, false).unwrap();
+        checkout(&git_repo, "feature-a");
+        commit_file(&temp, &git_repo, "a.txt", "first draft", "A commit");
+        let old_a = git_repo.head().unwrap().peel_to_commit().unwrap();
+        git_repo.branch("feature-b", &old_a, false).unwrap();
+        checkout(&git_repo, "feature-b");
+        add_commit(&temp, &git_repo, "b.txt", "B commit");
+
+        // feature-a is rewritten (amended and force-pushed by a teammate)
+        checkout(&git_repo, "feature-a");
+        git_repo
+            .reset(head.as_object(), git2::ResetType::Hard, None)
+            .unwrap();
+        commit_file(
+            &temp,
+            &git_repo,
+            "a.txt",
+            "second draft",
+            "A commit (amended)",
+        );
+
+        let mut stack = Stack::new();
+        stack.add_branch(StackBranch::try_new("feature-a", Some(main_branch.clone())).unwrap());
+        let mut b = StackBranch::try_new("feature-b", Some("feature-a")).unwrap();
+        b.base = Some(old_a.id().to_string());
+        stack.add_branch(b);
+        state.save_stack(&stack).unwrap();
+
+        let plan = create_sync_plan(&rung_repo, &stack, &main_branch).unwrap();
+        assert_eq!(plan.branches.len(), 1);
+        assert!(plan.branches[0].parent_rewritten);
+        assert_eq!(plan.branches[0].old_base, old_a.id().to_string());
+
+        let result = execute_sync(&rung_repo, &state, plan).unwrap();
+        assert!(matches!(result, SyncResult::Complete { .. }), "{result:?}");
+
+        // Only feature-b's own commit sits on the rewritten parent
+        let a = rung_repo.branch_commit("feature-a").unwrap();
+        let b = rung_repo.branch_commit("feature-b").unwrap();
+        assert_eq!(rung_repo.merge_base(a, b).unwrap(), a);
+        assert_eq!(rung_repo.count_commits_between(a, b).unwrap(), 1);
+
+        // The new base is recorded for the next sync
+        let stack = state.load_stack().unwrap();
+        let recorded = stack.find_branch("feature-b").unwrap().base.clone();
+        assert_eq!(recorded, Some(a.to_string()));
+    }
+
+    #[test]
+    fn test_sync_plan_no_applied_commits_without_squash_merge() {
+        let (temp, rung_repo, git_repo) = init_test_repo();
+        let main_branch = rung_repo.current_branch().unwrap();
+
+        let head = git_repo.head().unwrap().peel_to_commit().unwrap();
+        git_repo.branch("feature-a", &head, false).unwrap();
+        checkout(&git_repo, "feature-a");
+        add_commit(&temp, &git_repo, "a.txt", "A commit");
+        checkout(&git_repo, &main_branch);
+        add_commit(&temp, &git_repo, "main-update.txt", "Update main");
+
+        let mut stack = Stack::new();
+        stack.add_branch(StackBranch::try_new("feature-a", Some(main_branch.clone())).unwrap());
+
+        let plan = create_sync_plan(&rung_repo, &stack, &main_branch).unwrap();
+        assert_eq!(plan.branches.len(), 1);
+        assert!(plan.branches[0].applied_through.is_none());
+        assert_eq!(plan.branches[0].applied_commits, 0);
+    }
+
     #[test]
     fn test_remove_stale_branches() {
         let (temp, rung_repo, git_repo) = init_test_repo();
@@ -340,6 +597,58 @@ mod tests {
         }
 
         assert!(state.is_sync_in_progress());
+
+        // The plan is saved for --continue, and the journal waits for it
+        let sync_state = state.load_sync_state().unwrap();
+        let action = sync_state.planned_action("feature-a").unwrap();
+        assert_eq!(action.parent_branch, main_branch);
+        assert!(state.load_journal().unwrap().unwrap().paused);
+    }
+
+    #[test]
+    fn test_execute_sync_defers_conflicted_tree() {
+        let (temp, rung_repo, git_repo) = init_test_repo();
+        set_identity(&git_repo);
+        let state = State::new(temp.path()).unwrap();
+        state.init().unwrap();
+        let main_branch = rung_repo.current_branch().unwrap();
+
+        // Two trees off main; only auth touches the file main changes
+        commit_file(&temp, &git_repo, "shared.txt", "Original\n", "Shared");
+        let head = git_repo.head().unwrap().peel_to_commit().unwrap();
+        git_repo.branch("auth", &head, false).unwrap();
+        git_repo.branch("billing", &head, false).unwrap();
+        commit_file(&temp, &git_repo, "shared.txt", "Main\n", "Main change");
+
+        for (branch, file, contents) in [
+            ("auth", "shared.txt", "Auth\n"),
+            ("billing", "billing.txt", "Billing\n"),
+        ] {
+            checkout(&git_repo, branch);
+            commit_file(&temp, &git_repo, file, contents, branch);
+        }
+        checkout(&git_repo, &main_branch);
+
+        let mut stack = Stack::new();
+        stack.add_branch(StackBranch::try_new("auth", Some(main_branch.clone())).unwrap());
+        stack.add_branch(StackBranch::try_new("billing", Some(main_branch.clone())).unwrap());
+        state.save_stack(&stack).unwrap();
+
+        let plan = create_sync_plan(&rung_repo, &stack, &main_branch).unwrap();
+        assert_eq!(plan.branches[0].tree, "auth");
+        let result = execute_sync(&rung_repo, &state, plan).unwrap();
+        assert!(
+            matches!(&result, SyncResult::Paused { at_branch, .. } if at_branch == "auth"),
+            "expected pause at auth, got {result:?}"
+        );
+
+        // billing synced even though auth came first in the plan
+        let main_tip = rung_repo.branch_commit(&main_branch).unwrap();
+        let billing = rung_repo.branch_commit("billing").unwrap();
+        assert_eq!(rung_repo.merge_base(billing, main_tip).unwrap(), main_tip);
+        let sync_state = state.load_sync_state().unwrap();
+        assert_eq!(sync_state.completed, vec!["billing"]);
+        assert_eq!(sync_state.deferred, vec!["auth"]);
     }
 
     #[test]
@@ -447,6 +756,17 @@ mod tests {
         stack.add_branch(StackBranch::try_new("feature-a", Some(main_branch)).unwrap());
         state.save_stack(&stack).unwrap();
 
+        // A preview lists the branch without touching the backup
+        let preview = preview_undo(&rung_repo, &state).unwrap();
+        assert_eq!(preview.backup_id, backup_id);
+        assert_eq!(preview.branches.len(), 1);
+        assert_eq!(preview.branches[0].name, "feature-a");
+        assert_eq!(preview.branches[0].restore_to, new_sha);
+        assert_eq!(
+            preview.branches[0].current.as_deref(),
+            Some(new_sha.as_str())
+        );
+
         // Now undo should restore from the backup
         let result = undo_sync(&rung_repo, &state).unwrap();
 
@@ -454,6 +774,81 @@ mod tests {
         assert_eq!(result.backup_id, backup_id);
     }
 
+    #[test]
+    fn test_undo_operation() {
+        use crate::state::{AuditEntry, RefChange};
+
+        let (temp, rung_repo, git_repo) = init_test_repo();
+        let state = State::new(temp.path()).unwrap();
+        state.init().unwrap();
+        state.save_stack(&Stack::new()).unwrap();
+
+        let c0 = git_repo.head().unwrap().peel_to_commit().unwrap();
+        git_repo.branch("feature-b", &c0, false).unwrap();
+        let c0 = c0.id().to_string();
+        add_commit(&temp, &git_repo, "main.txt", "Update main");
+        let c1 = git_repo.head().unwrap().peel_to_commit().unwrap();
+        git_repo.branch("feature-a", &c1, false).unwrap();
+        let c1 = c1.id().to_string();
+        rung_repo
+            .reset_branch("feature-b", rung_git::Oid::from_str(&c1).unwrap())
+            .unwrap();
+
+        let entry = |command: &str, branch: &str, old: Option<&str>, new: &str| AuditEntry {
+            timestamp: chrono::Utc::now(),
+            user: "Test <test@example.com>".into(),
+            command: command.into(),
+            success: true,
+            refs: vec![RefChange {
+                branch: branch.into(),
+                old: old.map(String::from),
+                new: Some(new.into()),
+            }],
+        };
+        let entries = vec![
+            entry("rung create feature-a", "feature-a", None, &c1),
+            entry("rung sync", "feature-b", Some(&c0), &c1),
+            entry("rung restack", "feature-b", Some(&c1), &c1),
+        ];
+
+        // A later operation touched the same branch
+        let plan = plan_operation_undo(&rung_repo, &state, &entries, 2).unwrap();
+        assert_eq!(
+            plan.blockers,
+            vec![crate::sync::UndoBlocker::LaterOperation {
+                branch: "feature-b".into(),
+                id: 3,
+                command: "rung restack".into(),
+            }]
+        );
+        assert!(matches!(
+            undo_operation(&rung_repo, &state, &plan),
+            Err(crate::Error::UndoBlocked(2))
+        ));
+
+        // Undoing the create deletes the branch
+        let plan = plan_operation_undo(&rung_repo, &state, &entries, 1).unwrap();
+        assert!(plan.is_safe());
+        assert_eq!(plan.branches[0].restore_to, None);
+        undo_operation(&rung_repo, &state, &plan).unwrap();
+        assert!(!rung_repo.branch_exists("feature-a"));
+
+        // ...after which the branch no longer matches the log
+        let plan = plan_operation_undo(&rung_repo, &state, &entries, 1).unwrap();
+        assert_eq!(
+            plan.blockers,
+            vec![crate::sync::UndoBlocker::MovedOutside {
+                branch: "feature-a".into()
+            }]
+        );
+
+        assert!(matches!(
+            plan_operation_undo(&rung_repo, &state, &entries, 4),
+            Err(crate::Error::OperationNotFound(4))
+        ));
+        assert!(plan_operation_undo(&rung_repo, &state, &entries, 0).is_err());
+    }
+
     #[test]
     fn test_sync_plan_base_branch_not_found() {
         let (_temp, rung_repo, git_repo) = init_test_repo();
@@ -1,3 +1,5 @@
+use std::collections::HashSet;
+
 use super::types::{SyncAction, SyncPlan};
 use crate::error::Result;
 use crate::stack::{Stack, StackBranch};
@@ -10,6 +12,17 @@ use crate::stack::{Stack, StackBranch};
 ///
 /// Branches are processed in topological order (parents before children) to ensure
 /// each branch is rebased onto the correct target and cascade marking works correctly.
+/// Each tree of the stack - a branch on the base and everything stacked on it -
+/// is planned as one contiguous run, so a conflict in one tree can be put off
+/// while unrelated trees sync.
+///
+/// Branches whose parent already contains some of their commits as a single
+/// commit (a squash-merged parent PR) record the last such commit in
+/// `applied_through`, so the rebase replays only their own commits.
+///
+/// A branch whose parent was rewritten (e.g. force-pushed) since it was last
+/// based on it is detected from the base recorded in the stack, and marked
+/// `parent_rewritten` so only its own commits are replayed.
 ///
 /// Stale branches (in stack but not in git) are detected and can be cleaned up
 /// by calling `remove_stale_branches`.
@@ -26,11 +39,13 @@ pub fn create_sync_plan(
     // Track branches that need rebasing (including cascaded descendants)
     let mut needs_rebase: std::collections::HashSet<String> = std::collections::HashSet::new();
 
+    let index = stack.index();
+
     // Sort branches topologically (parents before children) to ensure cascade marking works
-    let sorted_branches = topological_sort(&stack.branches, base_branch);
+    let sorted_branches = topological_sort(stack, base_branch);
 
     // Process branches in topological order
-    for branch in sorted_branches {
+    for (branch, tree) in sorted_branches {
         // Skip branches that don't exist locally (stale branches)
         // These will be handled separately by remove_stale_branches
         if !repo.branch_exists(&branch.name) {
@@ -57,7 +72,15 @@ pub fn create_sync_plan(
         let parent_commit = repo.branch_commit(parent_name)?;
 
         // Find where this branch diverged from parent
-        let merge_base = repo.merge_base(branch_commit, parent_commit)?;
+        let mut merge_base = repo.merge_base(branch_commit, parent_commit)?;
+
+        // If the parent was rewritten since this branch was last based on it,
+        // its old commits are no longer reachable from the parent; replay only
+        // what came after the recorded base.
+        let rewritten_base = rewritten_parent_base(repo, branch, branch_commit, parent_commit);
+        if let Some(base) = rewritten_base {
+            merge_base = base;
+        }
 
         // Determine if this branch needs rebasing:
         // 1. Its merge_base differs from parent tip (direct divergence), OR
@@ -66,17 +89,38 @@ pub fn create_sync_plan(
         let needs_cascade_rebase = needs_rebase.contains(branch.name.as_str());
 
         if needs_direct_rebase || needs_cascade_rebase {
+            // When the parent moved on its own (e.g. a parent PR was squash-merged
+            // into it), drop the commits whose changes it already contains.
+            // Cascaded branches are rebased past their parent's old tip instead.
+            let applied = if needs_cascade_rebase || rewritten_base.is_some() {
+                None
+            } else {
+                repo.find_squash_applied(merge_base, branch_commit, parent_commit)?
+            };
+            let applied_commits = match applied {
+                Some(oid) => repo.count_commits_between(merge_base, oid)?,
+                None => 0,
+            };
+
             actions.push(SyncAction {
                 branch: branch.name.to_string(),
                 old_base: merge_base.to_string(),
                 new_base: parent_commit.to_string(),
                 parent_branch: parent_name.to_string(),
+                applied_through: applied.map(|oid| oid.to_string()),
+                applied_commits,
+                parent_rewritten: rewritten_base.is_some(),
+                tree: tree.to_string(),
             });
 
             // Proactive cascade: mark all descendants as needing rebase
-            // This ensures the entire sub-tree is synced in one pass
-            for descendant in stack.descendants(&branch.name) {
-                needs_rebase.insert(descendant.name.to_string());
+            // This ensures the entire sub-tree is synced in one pass. A branch
+            // that was itself cascade-marked already had its sub-tree marked
+            // by an ancestor, so skip the walk to keep long chains linear.
+            if !needs_cascade_rebase {
+                for descendant in index.descendants(&branch.name) {
+                    needs_rebase.insert(descendant.name.to_string());
+                }
             }
         }
     }
@@ -84,40 +128,55 @@ pub fn create_sync_plan(
     Ok(SyncPlan { branches: actions })
 }
 
-/// Sort branches topologically so parents come before children.
+/// Return the recorded base of `branch` if its parent was rewritten since.
 ///
-/// Uses Kahn's algorithm: repeatedly find branches whose parent is either
-/// the base branch or already processed, ensuring correct ordering.
-fn topological_sort<'a>(branches: &'a [StackBranch], base_branch: &str) -> Vec<&'a StackBranch> {
-    let mut result = Vec::with_capacity(branches.len());
-    let mut remaining: Vec<&StackBranch> = branches.iter().collect();
-    let mut processed: std::collections::HashSet<&str> = std::collections::HashSet::new();
-
-    // The base branch is considered "processed" (it's external to the stack)
-    processed.insert(base_branch);
-
-    // Repeatedly find branches whose parent is already processed
-    while !remaining.is_empty() {
-        let prev_len = remaining.len();
-
-        remaining.retain(|branch| {
-            let parent = branch.parent.as_deref().unwrap_or(base_branch);
-            if processed.contains(parent) {
-                processed.insert(&branch.name);
-                result.push(*branch);
-                false // Remove from remaining
-            } else {
-                true // Keep in remaining
-            }
-        });
+/// The parent counts as rewritten when the recorded base is still contained in
+/// the branch but no longer reachable from the parent's tip.
+#[must_use]
+pub fn rewritten_parent_base(
+    repo: &impl rung_git::GitOps,
+    branch: &StackBranch,
+    branch_commit: rung_git::Oid,
+    parent_commit: rung_git::Oid,
+) -> Option<rung_git::Oid> {
+    let base = rung_git::Oid::from_str(branch.base.as_deref()?).ok()?;
+    let is_ancestor = |of: rung_git::Oid| repo.merge_base(base, of).is_ok_and(|mb| mb == base);
+    (base != parent_commit && is_ancestor(branch_commit) && !is_ancestor(parent_commit))
+        .then_some(base)
+}
 
-        // If no progress was made, there's a cycle or orphaned branches
-        // Just add remaining branches in their original order to avoid infinite loop
-        if remaining.len() == prev_len {
-            result.append(&mut remaining);
-            break;
+/// Sort branches so parents come before children, one tree at a time.
+///
+/// Each root (a branch on the base branch, or whose parent isn't in the
+/// stack) is followed by its descendants depth-first, and every branch is
+/// paired with its root. Branches no root reaches - a cycle - come last, as
+/// their own trees.
+fn topological_sort<'a>(stack: &'a Stack, base_branch: &str) -> Vec<(&'a StackBranch, &'a str)> {
+    let index = stack.index();
+    let mut result = Vec::with_capacity(stack.len());
+    let mut seen: HashSet<&str> = HashSet::new();
+
+    let roots = stack.branches.iter().filter(|branch| {
+        branch
+            .parent
+            .as_deref()
+            .is_none_or(|parent| parent == base_branch || !index.contains(parent))
+    });
+    for root in roots {
+        let mut pending = vec![root];
+        while let Some(branch) = pending.pop() {
+            if !seen.insert(branch.name.as_str()) {
+                continue;
+            }
+            result.push((branch, root.name.as_str()));
+            pending.extend(index.children_of(&branch.name).iter().rev());
         }
     }
 
+    for branch in &stack.branches {
+        if !seen.contains(branch.name.as_str()) {
+            result.push((branch, branch.name.as_str()));
+        }
+    }
     result
 }
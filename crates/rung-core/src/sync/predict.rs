@@ -112,6 +112,10 @@ mod tests {
             false
         }
 
+        fn rebasing_branch(&self) -> Option<String> {
+            None
+        }
+
         fn branch_exists(&self, _name: &str) -> bool {
             true
         }
@@ -132,6 +136,10 @@ mod tests {
             Ok(vec![])
         }
 
+        fn branch_description(&self, _branch: &str) -> Option<String> {
+            None
+        }
+
         fn branch_commit(&self, _branch: &str) -> rung_git::Result<rung_git::Oid> {
             Ok(rung_git::Oid::zero())
         }
@@ -160,6 +168,14 @@ mod tests {
             Ok(vec![])
         }
 
+        fn commit_diff(
+            &self,
+            _commit: rung_git::Oid,
+            _format: rung_git::DiffFormat,
+        ) -> rung_git::Result<String> {
+            Ok(String::new())
+        }
+
         fn count_commits_between(
             &self,
             _from: rung_git::Oid,
@@ -168,6 +184,25 @@ mod tests {
             Ok(0)
         }
 
+        fn find_squash_applied(
+            &self,
+            _fork: rung_git::Oid,
+            _branch: rung_git::Oid,
+            _upstream: rung_git::Oid,
+        ) -> rung_git::Result<Option<rung_git::Oid>> {
+            Ok(None)
+        }
+
+        fn dropped_commits(
+            &self,
+            _from: rung_git::Oid,
+            _to: rung_git::Oid,
+            _onto: rung_git::Oid,
+            _new_tip: rung_git::Oid,
+        ) -> rung_git::Result<Vec<String>> {
+            Ok(vec![])
+        }
+
         fn is_clean(&self) -> rung_git::Result<bool> {
             Ok(true)
         }
@@ -192,6 +227,21 @@ mod tests {
             Ok(rung_git::Oid::zero())
         }
 
+        fn identity(&self) -> rung_git::Result<rung_git::Identity> {
+            Ok(rung_git::Identity {
+                name: "Test".to_string(),
+                email: "test@example.com".to_string(),
+            })
+        }
+
+        fn signing_config(&self) -> rung_git::Result<rung_git::SigningConfig> {
+            Ok(rung_git::SigningConfig::default())
+        }
+
+        fn is_commit_signed(&self, _commit: rung_git::Oid) -> rung_git::Result<bool> {
+            Ok(false)
+        }
+
         fn rebase_onto(&self, _target: rung_git::Oid) -> rung_git::Result<()> {
             Ok(())
         }
@@ -224,6 +274,10 @@ mod tests {
             Ok(())
         }
 
+        fn skip_applied_commits(&self) -> rung_git::Result<usize> {
+            Ok(0)
+        }
+
         fn origin_url(&self) -> rung_git::Result<String> {
             Ok(String::new())
         }
@@ -240,6 +294,10 @@ mod tests {
             Ok(())
         }
 
+        fn remote_branches(&self) -> rung_git::Result<Vec<String>> {
+            Ok(vec![])
+        }
+
         fn fetch_all(&self) -> rung_git::Result<()> {
             Ok(())
         }
@@ -266,6 +324,10 @@ mod tests {
                 old_base: "abc1234".to_string(),
                 new_base: "0000000000000000000000000000000000000000".to_string(),
                 parent_branch: "main".to_string(),
+                applied_through: None,
+                applied_commits: 0,
+                parent_rewritten: false,
+                tree: String::new(),
             }],
         };
 
@@ -291,6 +353,10 @@ mod tests {
                 old_base: "abc1234".to_string(),
                 new_base: "0000000000000000000000000000000000000000".to_string(),
                 parent_branch: "main".to_string(),
+                applied_through: None,
+                applied_commits: 0,
+                parent_rewritten: false,
+                tree: String::new(),
             }],
         };
 
@@ -330,18 +396,30 @@ mod tests {
                     old_base: "abc1234".to_string(),
                     new_base: "0000000000000000000000000000000000000000".to_string(),
                     parent_branch: "main".to_string(),
+                    applied_through: None,
+                    applied_commits: 0,
+                    parent_rewritten: false,
+                    tree: String::new(),
                 },
                 SyncAction {
                     branch: "feature-b".to_string(),
                     old_base: "def5678".to_string(),
                     new_base: "0000000000000000000000000000000000000000".to_string(),
                     parent_branch: "main".to_string(),
+                    applied_through: None,
+                    applied_commits: 0,
+                    parent_rewritten: false,
+                    tree: String::new(),
                 },
                 SyncAction {
                     branch: "feature-c".to_string(), // No conflicts for this one
                     old_base: "ghi9012".to_string(),
                     new_base: "0000000000000000000000000000000000000000".to_string(),
                     parent_branch: "main".to_string(),
+                    applied_through: None,
+                    applied_commits: 0,
+                    parent_rewritten: false,
+                    tree: String::new(),
                 },
             ],
         };
@@ -365,6 +443,10 @@ mod tests {
                 old_base: "abc1234".to_string(),
                 new_base: "invalid-not-a-valid-oid".to_string(), // Invalid OID
                 parent_branch: "main".to_string(),
+                applied_through: None,
+                applied_commits: 0,
+                parent_rewritten: false,
+                tree: String::new(),
             }],
         };
 
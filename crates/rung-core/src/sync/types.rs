@@ -1,3 +1,5 @@
+use serde::{Deserialize, Serialize};
+
 /// Result of a sync operation.
 #[derive(Debug)]
 pub enum SyncResult {
@@ -10,6 +12,10 @@ pub enum SyncResult {
         branches_rebased: usize,
         /// Backup ID that can be used for undo.
         backup_id: String,
+        /// Commits dropped per branch because their changes were already upstream.
+        skipped: Vec<SkippedCommits>,
+        /// Branches left untouched with `--skip`.
+        skipped_branches: Vec<String>,
     },
 
     /// Sync paused due to conflict.
@@ -23,15 +29,28 @@ pub enum SyncResult {
     },
 }
 
-/// Plan for syncing a stack.
+/// Commits a rebase dropped from a branch because an equivalent change had
+/// already landed on its new base (e.g. a cherry-picked hotfix).
 #[derive(Debug, Clone)]
+pub struct SkippedCommits {
+    /// Branch the commits were dropped from.
+    pub branch: String,
+    /// Dropped commits, as `"<short-sha> <summary>"`.
+    pub commits: Vec<String>,
+}
+
+/// Plan for syncing a stack.
+///
+/// Saved with the sync state, so a paused sync resumes with the plan it
+/// started with.
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SyncPlan {
     /// Branches to rebase, in order.
     pub branches: Vec<SyncAction>,
 }
 
 /// A single rebase action in the sync plan.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SyncAction {
     /// Branch to rebase.
     pub branch: String,
@@ -39,8 +58,22 @@ pub struct SyncAction {
     pub old_base: String,
     /// New base commit (parent's new tip).
     pub new_base: String,
-    /// Parent branch name (for display purposes).
+    /// Parent branch name.
     pub parent_branch: String,
+    /// Last commit whose changes already landed on the new base (e.g. a
+    /// squash-merged parent PR). It and its ancestors are dropped on rebase.
+    pub applied_through: Option<String>,
+    /// Number of commits dropped because they are already on the new base.
+    pub applied_commits: usize,
+    /// The parent was rewritten (e.g. force-pushed) since this branch was last
+    /// based on it; `old_base` is the recorded base and only the commits after
+    /// it are replayed.
+    pub parent_rewritten: bool,
+    /// Root of the tree the branch belongs to: the stack branch it grows
+    /// from that sits directly on the base branch. Empty in plans saved by
+    /// older versions.
+    #[serde(default)]
+    pub tree: String,
 }
 
 impl SyncPlan {
@@ -49,6 +82,11 @@ impl SyncPlan {
     pub const fn is_empty(&self) -> bool {
         self.branches.is_empty()
     }
+
+    /// Keep only the branches of the tree rooted at `tree`.
+    pub fn retain_tree(&mut self, tree: &str) {
+        self.branches.retain(|action| action.tree == tree);
+    }
 }
 
 /// Branches that were found to be stale (in stack but not in git).
@@ -113,6 +151,110 @@ pub struct UndoResult {
     pub backup_id: String,
 }
 
+/// What an undo would restore, computed without changing anything.
+#[derive(Debug)]
+pub struct UndoPreview {
+    /// The backup ID that would be used.
+    pub backup_id: String,
+    /// Branches that would be reset.
+    pub branches: Vec<UndoBranch>,
+}
+
+/// A branch an undo would reset.
+#[derive(Debug)]
+pub struct UndoBranch {
+    /// Branch name.
+    pub name: String,
+    /// Current tip, if the branch still exists.
+    pub current: Option<String>,
+    /// Commit the branch would be reset to.
+    pub restore_to: String,
+}
+
+/// Undoing one operation from the audit log, planned by
+/// [`plan_operation_undo`](super::plan_operation_undo).
+#[derive(Debug)]
+pub struct OperationUndo {
+    /// Operation id: its 1-based position in the audit log.
+    pub id: usize,
+    /// The command that ran, e.g. `rung sync`.
+    pub command: String,
+    /// Branches that would be put back.
+    pub branches: Vec<OperationUndoBranch>,
+    /// Why the operation can't be undone on its own; empty if it can.
+    pub blockers: Vec<UndoBlocker>,
+}
+
+impl OperationUndo {
+    /// Whether the undo can be applied.
+    #[must_use]
+    pub const fn is_safe(&self) -> bool {
+        self.blockers.is_empty()
+    }
+}
+
+/// A branch a targeted undo would put back.
+#[derive(Debug)]
+pub struct OperationUndoBranch {
+    /// Branch name.
+    pub name: String,
+    /// Current tip, if the branch exists.
+    pub current: Option<String>,
+    /// Commit to reset to; `None` deletes a branch the operation created.
+    pub restore_to: Option<String>,
+}
+
+/// Why an operation can't be undone without clobbering later work.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UndoBlocker {
+    /// A later operation changed the same branch.
+    LaterOperation {
+        /// Branch both operations changed.
+        branch: String,
+        /// Id of the later operation.
+        id: usize,
+        /// Its command line.
+        command: String,
+    },
+    /// The branch moved outside rung since the operation (e.g. a plain `git commit`).
+    MovedOutside {
+        /// Branch that moved.
+        branch: String,
+    },
+    /// The operation created the branch, which is checked out now.
+    CheckedOut {
+        /// Branch that would be deleted.
+        branch: String,
+    },
+    /// The operation created the branch, and other stack branches are based on it.
+    HasChildren {
+        /// Branch that would be deleted.
+        branch: String,
+    },
+}
+
+impl std::fmt::Display for UndoBlocker {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::LaterOperation {
+                branch,
+                id,
+                command,
+            } => write!(f, "'{branch}' was changed again by #{id} ({command})"),
+            Self::MovedOutside { branch } => {
+                write!(f, "'{branch}' has moved since, outside of rung")
+            }
+            Self::CheckedOut { branch } => {
+                write!(f, "'{branch}' would be deleted but is checked out")
+            }
+            Self::HasChildren { branch } => write!(
+                f,
+                "'{branch}' would be deleted but other stack branches are based on it"
+            ),
+        }
+    }
+}
+
 /// Predicted conflicts for a sync operation.
 #[derive(Debug, Default)]
 pub struct SyncConflictPrediction {
@@ -1,5 +1,9 @@
-use super::types::UndoResult;
-use crate::error::Result;
+use super::types::{
+    OperationUndo, OperationUndoBranch, UndoBlocker, UndoBranch, UndoPreview, UndoResult,
+};
+use crate::error::{Error, Result};
+use crate::journal::{self, Journal, JournalRef};
+use crate::state::AuditEntry;
 use crate::traits::StateStore;
 
 /// Undo the last sync operation.
@@ -14,16 +18,8 @@ pub fn undo_sync(repo: &impl rung_git::GitOps, state: &impl StateStore) -> Resul
     let refs = state.load_backup(&backup_id)?;
 
     // Reset each branch to its saved SHA
-    for (branch_name, sha) in &refs {
-        let oid = rung_git::Oid::from_str(sha).map_err(|e| {
-            crate::error::Error::SyncFailed(format!(
-                "invalid backup commit '{sha}' for branch '{branch_name}': {e}"
-            ))
-        })?;
-        repo.reset_branch(branch_name, oid)?;
-    }
-
     let branches_restored = refs.len();
+    journal::restore(repo, state, "undo", refs)?;
 
     // Delete the backup after successful restore
     state.delete_backup(&backup_id)?;
@@ -33,3 +29,136 @@ pub fn undo_sync(repo: &impl rung_git::GitOps, state: &impl StateStore) -> Resul
         backup_id,
     })
 }
+
+/// Show what [`undo_sync`] would restore without changing anything.
+///
+/// # Errors
+/// Returns error if no backup found.
+pub fn preview_undo(repo: &impl rung_git::GitOps, state: &impl StateStore) -> Result<UndoPreview> {
+    let backup_id = state.latest_backup()?;
+    let branches = state
+        .load_backup(&backup_id)?
+        .into_iter()
+        .map(|(name, sha)| UndoBranch {
+            current: repo.branch_commit(&name).ok().map(|oid| oid.to_string()),
+            name,
+            restore_to: sha,
+        })
+        .collect();
+
+    Ok(UndoPreview {
+        backup_id,
+        branches,
+    })
+}
+
+/// Work out what undoing operation `id` of the audit log would do.
+///
+/// The operation's branch changes are reversed: moved and deleted branches
+/// go back to their old commit, created branches are deleted. It's only safe
+/// when nothing has touched those branches since, so every later operation
+/// changing the same branch, or a branch that moved outside rung, is reported
+/// as a blocker instead.
+///
+/// # Errors
+/// Returns error if there's no operation with this id.
+pub fn plan_operation_undo(
+    repo: &impl rung_git::GitOps,
+    state: &impl StateStore,
+    entries: &[AuditEntry],
+    id: usize,
+) -> Result<OperationUndo> {
+    let entry = id
+        .checked_sub(1)
+        .and_then(|index| entries.get(index))
+        .ok_or(Error::OperationNotFound(id))?;
+    let later = &entries[id..];
+    let current_branch = repo.current_branch().ok();
+    let stack = state.load_stack()?;
+
+    let mut branches = Vec::new();
+    let mut blockers = Vec::new();
+    for change in &entry.refs {
+        let current = repo
+            .branch_commit(&change.branch)
+            .ok()
+            .map(|oid| oid.to_string());
+
+        if let Some((offset, op)) = later
+            .iter()
+            .enumerate()
+            .find(|(_, op)| op.refs.iter().any(|r| r.branch == change.branch))
+        {
+            blockers.push(UndoBlocker::LaterOperation {
+                branch: change.branch.clone(),
+                id: id + 1 + offset,
+                command: op.command.clone(),
+            });
+        } else if current != change.new {
+            blockers.push(UndoBlocker::MovedOutside {
+                branch: change.branch.clone(),
+            });
+        } else if change.old.is_none() {
+            if current_branch.as_deref() == Some(change.branch.as_str()) {
+                blockers.push(UndoBlocker::CheckedOut {
+                    branch: change.branch.clone(),
+                });
+            } else if !stack.children_of(&change.branch).is_empty() {
+                blockers.push(UndoBlocker::HasChildren {
+                    branch: change.branch.clone(),
+                });
+            }
+        }
+
+        branches.push(OperationUndoBranch {
+            name: change.branch.clone(),
+            current,
+            restore_to: change.old.clone(),
+        });
+    }
+
+    Ok(OperationUndo {
+        id,
+        command: entry.command.clone(),
+        branches,
+        blockers,
+    })
+}
+
+/// Apply a targeted undo planned by [`plan_operation_undo`].
+///
+/// Branches the operation created are also dropped from the stack.
+///
+/// # Errors
+/// Returns error if the plan has blockers or a branch can't be restored.
+pub fn undo_operation(
+    repo: &impl rung_git::GitOps,
+    state: &impl StateStore,
+    plan: &OperationUndo,
+) -> Result<usize> {
+    if !plan.is_safe() {
+        return Err(Error::UndoBlocked(plan.id));
+    }
+
+    let refs = plan
+        .branches
+        .iter()
+        .map(|branch| JournalRef {
+            branch: branch.name.clone(),
+            old: branch.current.clone(),
+            new: branch.restore_to.clone(),
+        })
+        .collect();
+    journal::apply(repo, state, &Journal::replay("undo", refs))?;
+
+    // Branches the operation created are gone again
+    let mut stack = state.load_stack()?;
+    for branch in &plan.branches {
+        if branch.restore_to.is_none() {
+            stack.remove_branch(&branch.name);
+        }
+    }
+    state.save_stack(&stack)?;
+
+    Ok(plan.branches.len())
+}
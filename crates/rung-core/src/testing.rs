@@ -0,0 +1,222 @@
+//! Test doubles for code written against [`StateStore`].
+//!
+//! Enabled by the `test-utils` feature. [`MockStateStore`] keeps the stack,
+//! config, and in-progress operation state in memory instead of under
+//! `.git/rung`.
+
+use std::cell::RefCell;
+use std::path::{Path, PathBuf};
+
+use crate::config::Config;
+use crate::stack::Stack;
+use crate::state::{FoldState, RestackState, SplitState, SyncState};
+use crate::{Error, Journal, Result, StateStore};
+
+/// Mock implementation of `StateStore` for testing.
+pub struct MockStateStore {
+    pub stack: RefCell<Stack>,
+    pub config: RefCell<Config>,
+    pub initialized: bool,
+    pub default_branch: String,
+    pub rung_dir: PathBuf,
+    pub sync_in_progress: RefCell<bool>,
+    pub sync_state: RefCell<Option<SyncState>>,
+    pub restack_in_progress: RefCell<bool>,
+    pub restack_state: RefCell<Option<RestackState>>,
+    pub journal: RefCell<Option<Journal>>,
+}
+
+impl Default for MockStateStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MockStateStore {
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            stack: RefCell::new(Stack::default()),
+            config: RefCell::new(Config::default()),
+            initialized: true,
+            default_branch: "main".to_string(),
+            rung_dir: std::env::temp_dir().join("mock-rung"),
+            sync_in_progress: RefCell::new(false),
+            sync_state: RefCell::new(None),
+            restack_in_progress: RefCell::new(false),
+            restack_state: RefCell::new(None),
+            journal: RefCell::new(None),
+        }
+    }
+
+    #[must_use]
+    pub fn with_stack(self, stack: Stack) -> Self {
+        *self.stack.borrow_mut() = stack;
+        self
+    }
+
+    #[must_use]
+    pub fn with_restack_state(self, state: RestackState) -> Self {
+        *self.restack_state.borrow_mut() = Some(state);
+        *self.restack_in_progress.borrow_mut() = true;
+        self
+    }
+}
+
+impl StateStore for MockStateStore {
+    fn is_initialized(&self) -> bool {
+        self.initialized
+    }
+
+    fn init(&self) -> Result<()> {
+        Ok(())
+    }
+
+    fn rung_dir(&self) -> &Path {
+        &self.rung_dir
+    }
+
+    fn load_stack(&self) -> Result<Stack> {
+        Ok(self.stack.borrow().clone())
+    }
+
+    fn save_stack(&self, stack: &Stack) -> Result<()> {
+        *self.stack.borrow_mut() = stack.clone();
+        Ok(())
+    }
+
+    fn load_config(&self) -> Result<Config> {
+        Ok(self.config.borrow().clone())
+    }
+
+    fn save_config(&self, config: &Config) -> Result<()> {
+        *self.config.borrow_mut() = config.clone();
+        Ok(())
+    }
+
+    fn default_branch(&self) -> Result<String> {
+        Ok(self.default_branch.clone())
+    }
+
+    fn is_sync_in_progress(&self) -> bool {
+        *self.sync_in_progress.borrow()
+    }
+
+    fn load_sync_state(&self) -> Result<SyncState> {
+        // Return custom state if set, otherwise return a default
+        if let Some(state) = self.sync_state.borrow().as_ref() {
+            return Ok(state.clone());
+        }
+        Ok(SyncState::new("test-backup".to_string(), vec![]))
+    }
+
+    fn save_sync_state(&self, state: &SyncState) -> Result<()> {
+        *self.sync_state.borrow_mut() = Some(state.clone());
+        *self.sync_in_progress.borrow_mut() = true;
+        Ok(())
+    }
+
+    fn clear_sync_state(&self) -> Result<()> {
+        *self.sync_state.borrow_mut() = None;
+        *self.sync_in_progress.borrow_mut() = false;
+        Ok(())
+    }
+
+    fn is_restack_in_progress(&self) -> bool {
+        *self.restack_in_progress.borrow()
+    }
+
+    fn load_restack_state(&self) -> Result<RestackState> {
+        // Return custom state if set, otherwise return a default
+        if let Some(state) = self.restack_state.borrow().as_ref() {
+            return Ok(state.clone());
+        }
+        Ok(RestackState::new(
+            "test-backup".to_string(),
+            "feature".to_string(),
+            "main".to_string(),
+            Some("develop".to_string()),
+            "main".to_string(),
+            vec![],
+            vec![],
+        ))
+    }
+
+    fn save_restack_state(&self, state: &RestackState) -> Result<()> {
+        *self.restack_state.borrow_mut() = Some(state.clone());
+        *self.restack_in_progress.borrow_mut() = true;
+        Ok(())
+    }
+
+    fn clear_restack_state(&self) -> Result<()> {
+        *self.restack_state.borrow_mut() = None;
+        *self.restack_in_progress.borrow_mut() = false;
+        Ok(())
+    }
+
+    fn load_journal(&self) -> Result<Option<Journal>> {
+        Ok(self.journal.borrow().clone())
+    }
+
+    fn save_journal(&self, journal: &Journal) -> Result<()> {
+        *self.journal.borrow_mut() = Some(journal.clone());
+        Ok(())
+    }
+
+    fn clear_journal(&self) -> Result<()> {
+        *self.journal.borrow_mut() = None;
+        Ok(())
+    }
+
+    fn create_backup(&self, _refs: &[(&str, &str)]) -> Result<String> {
+        Ok("mock-backup-id".to_string())
+    }
+
+    fn latest_backup(&self) -> Result<String> {
+        Ok("mock-backup-id".to_string())
+    }
+
+    fn load_backup(&self, _id: &str) -> Result<Vec<(String, String)>> {
+        Ok(vec![])
+    }
+
+    fn delete_backup(&self, _id: &str) -> Result<()> {
+        Ok(())
+    }
+
+    fn cleanup_backups(&self, _keep: usize) -> Result<()> {
+        Ok(())
+    }
+
+    fn is_split_in_progress(&self) -> bool {
+        false
+    }
+
+    fn load_split_state(&self) -> Result<SplitState> {
+        Err(Error::NoBackupFound)
+    }
+
+    fn save_split_state(&self, _state: &SplitState) -> Result<()> {
+        Ok(())
+    }
+
+    fn clear_split_state(&self) -> Result<()> {
+        Ok(())
+    }
+
+    fn is_fold_in_progress(&self) -> bool {
+        false
+    }
+
+    fn load_fold_state(&self) -> Result<FoldState> {
+        Err(Error::NoBackupFound)
+    }
+
+    fn save_fold_state(&self, _state: &FoldState) -> Result<()> {
+        Ok(())
+    }
+
+    fn clear_fold_state(&self) -> Result<()> {
+        Ok(())
+    }
+}
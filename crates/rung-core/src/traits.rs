@@ -7,6 +7,7 @@ use std::path::Path;
 
 use crate::Result;
 use crate::config::Config;
+use crate::journal::Journal;
 use crate::stack::Stack;
 use crate::state::{FoldState, RestackState, SplitState, SyncState};
 
@@ -107,6 +108,17 @@ pub trait StateStore {
     /// Clear fold state (called when fold completes or aborts).
     fn clear_fold_state(&self) -> Result<()>;
 
+    // === Journal Operations ===
+
+    /// Load the journal of the operation in flight, if any.
+    fn load_journal(&self) -> Result<Option<Journal>>;
+
+    /// Save the journal (called before an operation moves any branch).
+    fn save_journal(&self, journal: &Journal) -> Result<()>;
+
+    /// Clear the journal (called when an operation completes or aborts).
+    fn clear_journal(&self) -> Result<()>;
+
     // === Backup Operations ===
 
     /// Create a backup of branch refs.
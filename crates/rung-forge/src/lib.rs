@@ -19,6 +19,7 @@ pub use remote::{ForgeKind, RemoteInfo, parse_remote};
 pub use repo_id::RepoId;
 pub use traits::ForgeApi;
 pub use types::{
-    CheckRun, CheckStatus, CreateComment, CreatePullRequest, IssueComment, MergeMethod,
-    MergePullRequest, MergeResult, PullRequest, PullRequestState, UpdateComment, UpdatePullRequest,
+    CheckRun, CheckStatus, CommitState, CommitStatus, CreateComment, CreatePullRequest,
+    IssueComment, MergeMethod, MergePullRequest, MergeResult, PullRequest, PullRequestState,
+    UpdateComment, UpdatePullRequest,
 };
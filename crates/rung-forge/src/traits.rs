@@ -8,8 +8,8 @@
 use std::collections::HashMap;
 
 use crate::{
-    CheckRun, CreateComment, CreatePullRequest, IssueComment, MergePullRequest, MergeResult,
-    PullRequest, RepoId, Result, UpdateComment, UpdatePullRequest,
+    CheckRun, CommitStatus, CreateComment, CreatePullRequest, IssueComment, MergeMethod,
+    MergePullRequest, MergeResult, PullRequest, RepoId, Result, UpdateComment, UpdatePullRequest,
 };
 
 /// Trait for forge (code-hosting) API operations.
@@ -49,6 +49,16 @@ pub trait ForgeApi: Send + Sync {
         branch: &str,
     ) -> impl std::future::Future<Output = Result<Option<PullRequest>>> + Send;
 
+    /// List every open PR whose head is `branch`.
+    ///
+    /// Normally at most one; more means PRs were opened by hand against
+    /// several bases.
+    fn find_open_prs_for_branch(
+        &self,
+        repo: &RepoId,
+        branch: &str,
+    ) -> impl std::future::Future<Output = Result<Vec<PullRequest>>> + Send;
+
     /// Create a pull request.
     fn create_pr(
         &self,
@@ -73,6 +83,15 @@ pub trait ForgeApi: Send + Sync {
         commit_sha: &str,
     ) -> impl std::future::Future<Output = Result<Vec<CheckRun>>> + Send;
 
+    /// Set a commit status on a commit, replacing any earlier status with
+    /// the same context.
+    fn set_commit_status(
+        &self,
+        repo: &RepoId,
+        commit_sha: &str,
+        status: CommitStatus,
+    ) -> impl std::future::Future<Output = Result<()>> + Send;
+
     // === Merge Operations ===
 
     /// Merge a pull request.
@@ -100,6 +119,12 @@ pub trait ForgeApi: Send + Sync {
         repo: &RepoId,
     ) -> impl std::future::Future<Output = Result<String>> + Send;
 
+    /// Get the merge methods the repository allows, in order of preference.
+    fn get_merge_methods(
+        &self,
+        repo: &RepoId,
+    ) -> impl std::future::Future<Output = Result<Vec<MergeMethod>>> + Send;
+
     // === Comment Operations ===
 
     /// List comments on a pull request.
@@ -1,5 +1,6 @@
 //! Forge-agnostic pull/merge request and CI types.
 
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
 /// A pull request (GitHub) / merge request (GitLab).
@@ -34,6 +35,15 @@ pub struct PullRequest {
 
     /// The mergeable state (e.g., "clean", "dirty", "blocked", "behind").
     pub mergeable_state: Option<String>,
+
+    /// When the PR was opened (None if the forge didn't report it).
+    #[serde(default)]
+    pub created_at: Option<DateTime<Utc>>,
+
+    /// When the PR's most recent review was submitted (None if it has no
+    /// reviews, or the forge didn't report them).
+    #[serde(default)]
+    pub last_reviewed_at: Option<DateTime<Utc>>,
 }
 
 /// State of a pull request.
@@ -99,6 +109,32 @@ impl CheckStatus {
     }
 }
 
+/// A commit status to set on a commit, e.g. rung's position in the stack.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CommitStatus {
+    /// Status state.
+    pub state: CommitState,
+
+    /// Short description shown next to the status.
+    pub description: String,
+
+    /// Label telling this status apart from others; setting a status with
+    /// the same context replaces the earlier one.
+    pub context: String,
+}
+
+/// State of a commit status.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CommitState {
+    /// Waiting on something; blocks merging when the status is required.
+    Pending,
+    /// Good to go.
+    Success,
+    /// Failed.
+    Failure,
+}
+
 /// Request to create a pull request.
 #[derive(Debug, Serialize)]
 pub struct CreatePullRequest {
@@ -147,6 +183,18 @@ pub enum MergeMethod {
     Rebase,
 }
 
+impl MergeMethod {
+    /// Lowercase name, as accepted by `--method`.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Merge => "merge",
+            Self::Squash => "squash",
+            Self::Rebase => "rebase",
+        }
+    }
+}
+
 /// Request to merge a pull request.
 #[derive(Debug, Serialize)]
 pub struct MergePullRequest {
@@ -280,6 +328,17 @@ mod tests {
         );
     }
 
+    #[test]
+    #[allow(clippy::unwrap_used)]
+    fn test_merge_method_as_str_matches_serialization() {
+        for method in [MergeMethod::Merge, MergeMethod::Squash, MergeMethod::Rebase] {
+            assert_eq!(
+                serde_json::to_string(&method).unwrap(),
+                format!("\"{}\"", method.as_str())
+            );
+        }
+    }
+
     #[test]
     #[allow(clippy::unwrap_used)]
     fn test_merge_method_serialization() {
@@ -2,6 +2,9 @@
 //!
 //! Provides diff parsing, blame queries, and fixup commit creation.
 
+use std::collections::HashMap;
+use std::path::Path;
+
 use crate::Repository;
 use crate::error::{Error, Result};
 use crate::traits::AbsorbOps;
@@ -12,6 +15,8 @@ use git2::Oid;
 pub struct Hunk {
     /// File path relative to repository root.
     pub file_path: String,
+    /// Path in `HEAD` when the file is being renamed in the index.
+    pub old_path: Option<String>,
     /// Starting line in the original file (1-indexed, before changes).
     pub old_start: u32,
     /// Number of lines in the original file.
@@ -47,7 +52,7 @@ impl Repository {
         let workdir = self.workdir().ok_or(Error::NotARepository)?;
 
         let output = std::process::Command::new("git")
-            .args(["diff", "--cached", "-U0", "--no-color"])
+            .args(["diff", "--cached", "-U0", "--no-color", "-M"])
             .current_dir(workdir)
             .output()
             .map_err(|e| Error::Git2(git2::Error::from_str(&e.to_string())))?;
@@ -64,7 +69,12 @@ impl Repository {
     /// Query git blame for a specific line range in a file.
     ///
     /// Returns the commits that last modified lines in the given range.
-    /// Uses `git blame -L <start>,<end>` for targeted queries.
+    /// Uses `git blame -L <start>,<end> HEAD` for targeted queries against the
+    /// committed file, since hunk ranges refer to its line numbers. Blame
+    /// follows file renames on its own; `-M` also tracks lines moved within
+    /// the file. Cross-file copy detection (`-C`) is deliberately not used: it
+    /// would target commits where the lines lived in another file, and the
+    /// fixup could not be squashed there.
     ///
     /// # Errors
     /// Returns error if blame fails or commit cannot be found.
@@ -74,7 +84,17 @@ impl Repository {
         // Use -l for full commit hashes, -s for suppressing author/date
         let line_range = format!("{start},{end}");
         let output = std::process::Command::new("git")
-            .args(["blame", "-l", "-s", "-L", &line_range, "--", file_path])
+            .args([
+                "blame",
+                "-l",
+                "-s",
+                "-M",
+                "-L",
+                &line_range,
+                "HEAD",
+                "--",
+                file_path,
+            ])
             .current_dir(workdir)
             .output()
             .map_err(|e| Error::Git2(git2::Error::from_str(&e.to_string())))?;
@@ -88,6 +108,60 @@ impl Repository {
         self.parse_blame_output(&stdout)
     }
 
+    /// Query git blame for several line ranges of one file in a single pass.
+    ///
+    /// Runs one `git blame --porcelain -M` with an `-L` per range, so the
+    /// file's history is walked once no matter how many hunks it has, and
+    /// commit summaries come straight from the blame output. Renames and
+    /// moved lines are followed as in [`Self::blame_lines`].
+    ///
+    /// # Errors
+    /// Returns error if blame fails.
+    pub fn blame_ranges(
+        &self,
+        file_path: &str,
+        ranges: &[(u32, u32)],
+    ) -> Result<Vec<Vec<BlameResult>>> {
+        if ranges.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let workdir = self.workdir().ok_or(Error::NotARepository)?;
+
+        let mut args = vec![
+            "blame".to_string(),
+            "--porcelain".to_string(),
+            "-M".to_string(),
+        ];
+        for (start, end) in ranges {
+            args.push("-L".to_string());
+            args.push(format!("{start},{end}"));
+        }
+        // Hunk ranges are old-side line numbers, so blame the committed file
+        args.push("HEAD".to_string());
+        args.push("--".to_string());
+        args.push(file_path.to_string());
+
+        let output = std::process::Command::new("git")
+            .args(&args)
+            .current_dir(workdir)
+            .output()
+            .map_err(|e| Error::Git2(git2::Error::from_str(&e.to_string())))?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(Error::Git2(git2::Error::from_str(&stderr)));
+        }
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let blame = parse_blame_porcelain(&stdout);
+
+        ranges
+            .iter()
+            .map(|&(start, end)| blame.results_for(start, end))
+            .collect()
+    }
+
     /// Create a fixup commit targeting the specified commit.
     ///
     /// Equivalent to `git commit --fixup=<target>`.
@@ -97,6 +171,7 @@ impl Repository {
     /// Returns error if commit creation fails.
     pub fn create_fixup_commit(&self, target: Oid) -> Result<Oid> {
         let workdir = self.workdir().ok_or(Error::NotARepository)?;
+        self.identity()?;
 
         let output = std::process::Command::new("git")
             .args(["commit", "--fixup", &target.to_string()])
@@ -180,6 +255,322 @@ impl Repository {
     pub fn is_ancestor(&self, ancestor: Oid, descendant: Oid) -> Result<bool> {
         Ok(self.inner().graph_descendant_of(descendant, ancestor)?)
     }
+
+    /// Create a fixup commit from the staged changes on another branch.
+    ///
+    /// The staged diff is applied to `branch` through a temporary index, so
+    /// the branch is extended with a `fixup! <subject>` commit without being
+    /// checked out. The absorbed paths are then restored to `HEAD` in the
+    /// current index and working tree; they return once the current branch
+    /// is restacked onto `branch`.
+    ///
+    /// # Errors
+    /// Returns error if the staged paths also have unstaged edits, if the
+    /// staged diff does not apply cleanly to `branch`, or if a git command fails.
+    pub fn create_fixup_commit_on(&self, branch: &str, target: Oid) -> Result<Oid> {
+        let staged = self.git_output(&["diff", "--cached", "--name-only", "-z"], None, None)?;
+        let staged: Vec<&str> = split_nul(&staged);
+        let unstaged = self.git_output(&["diff", "--name-only", "-z"], None, None)?;
+        if let Some(path) = split_nul(&unstaged).iter().find(|p| staged.contains(p)) {
+            return Err(Error::Git2(git2::Error::from_str(&format!(
+                "'{path}' has unstaged changes - stash or stage them before absorbing into '{branch}'"
+            ))));
+        }
+
+        let tip = self.branch_commit(branch)?;
+        let subject = self
+            .inner()
+            .find_commit(target)?
+            .summary()
+            .unwrap_or_default()
+            .to_string();
+        let patch = self.git_output(&["diff", "--cached", "--binary"], None, None)?;
+
+        let index_path = self.inner().path().join("rung-fixup-index");
+        let commit = self.commit_patch_on(&index_path, tip, &patch, &format!("fixup! {subject}"));
+        let _ = std::fs::remove_file(&index_path);
+        let commit = commit.map_err(|e| {
+            Error::Git2(git2::Error::from_str(&format!(
+                "staged changes could not be applied to '{branch}': {e}"
+            )))
+        })?;
+
+        self.git_output(
+            &[
+                "update-ref",
+                &format!("refs/heads/{branch}"),
+                &commit.to_string(),
+                &tip.to_string(),
+            ],
+            None,
+            None,
+        )?;
+
+        let mut restore = vec!["restore", "--source=HEAD", "--staged", "--worktree", "--"];
+        restore.extend(staged.iter().copied());
+        self.git_output(&restore, None, None)?;
+
+        Ok(commit)
+    }
+
+    /// Commit `patch` on top of `branch` without checking it out.
+    ///
+    /// The patch is applied to the branch's tree in a scratch index, so the
+    /// working tree is left alone - unless `branch` is checked out, in which
+    /// case it is moved to the new commit with `git reset --keep`.
+    ///
+    /// # Errors
+    /// Returns error if the patch does not apply cleanly to `branch`, or if
+    /// checked-out files it touches have local changes.
+    pub fn commit_patch_to(&self, branch: &str, patch: &[u8], message: &str) -> Result<Oid> {
+        let tip = self.branch_commit(branch)?;
+        let index_path = self.inner().path().join("rung-apply-index");
+        let commit = self.commit_patch_on(&index_path, tip, patch, message);
+        let _ = std::fs::remove_file(&index_path);
+        let commit = commit.map_err(|e| {
+            Error::Git2(git2::Error::from_str(&format!(
+                "patch does not apply to '{branch}': {e}"
+            )))
+        })?;
+
+        if self.current_branch().ok().as_deref() == Some(branch) {
+            self.git_output(&["reset", "--keep", &commit.to_string()], None, None)?;
+        } else {
+            self.git_output(
+                &[
+                    "update-ref",
+                    &format!("refs/heads/{branch}"),
+                    &commit.to_string(),
+                    &tip.to_string(),
+                ],
+                None,
+                None,
+            )?;
+        }
+        Ok(commit)
+    }
+
+    /// Build a commit on top of `parent` from `patch`, using a scratch index.
+    fn commit_patch_on(
+        &self,
+        index: &Path,
+        parent: Oid,
+        patch: &[u8],
+        message: &str,
+    ) -> Result<Oid> {
+        let parent = parent.to_string();
+        self.git_output(&["read-tree", &parent], Some(index), None)?;
+        self.git_output(&["apply", "--cached", "-"], Some(index), Some(patch))?;
+        let tree = self.git_output(&["write-tree"], Some(index), None)?;
+        let tree = String::from_utf8_lossy(&tree).trim().to_string();
+        let commit = self.git_output(
+            &["commit-tree", &tree, "-p", &parent, "-m", message],
+            None,
+            None,
+        )?;
+        Oid::from_str(String::from_utf8_lossy(&commit).trim()).map_err(Error::Git2)
+    }
+
+    /// Count `fixup!` commits in `from..to` that have a target in the same range.
+    ///
+    /// A fixup only counts when `git rebase --autosquash` over the range
+    /// would fold it into another commit.
+    ///
+    /// # Errors
+    /// Returns error if the revision walk fails.
+    pub fn count_fixup_commits(&self, from: Oid, to: Oid) -> Result<usize> {
+        let summaries = self
+            .commits_between(from, to)?
+            .into_iter()
+            .map(|oid| {
+                let commit = self.inner().find_commit(oid)?;
+                Ok(commit.summary().unwrap_or_default().to_string())
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(summaries
+            .iter()
+            .filter_map(|s| s.strip_prefix("fixup! "))
+            .filter(|target| {
+                summaries
+                    .iter()
+                    .any(|s| !s.starts_with("fixup! ") && s.starts_with(target))
+            })
+            .count())
+    }
+
+    /// Replay the current branch's commits after `upstream` onto `onto`,
+    /// squashing `fixup!` commits into their targets.
+    ///
+    /// This is `git rebase -i --autosquash --onto <onto> <upstream>` with the
+    /// todo list accepted as generated.
+    ///
+    /// # Errors
+    /// Returns error if the rebase fails or conflicts occur.
+    pub fn rebase_autosquash(&self, onto: Oid, upstream: Oid) -> Result<()> {
+        self.check_rebase_allowed()?;
+        let workdir = self.workdir().ok_or(Error::NotARepository)?;
+
+        let output = std::process::Command::new("git")
+            .arg("rebase")
+            .args(self.rebase_date_args())
+            .args([
+                "--interactive",
+                "--autosquash",
+                "--onto",
+                &onto.to_string(),
+                &upstream.to_string(),
+            ])
+            .env("GIT_SEQUENCE_EDITOR", "true")
+            .current_dir(workdir)
+            .output()
+            .map_err(|e| Error::RebaseFailed(e.to_string()))?;
+
+        if output.status.success() {
+            return Ok(());
+        }
+
+        if self.is_rebasing() {
+            let conflicts = self.conflicting_files()?;
+            return Err(Error::RebaseConflict(conflicts));
+        }
+
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        Err(Error::RebaseFailed(stderr.to_string()))
+    }
+
+    /// Run a git command in the working directory and return its stdout.
+    pub(crate) fn git_output(
+        &self,
+        args: &[&str],
+        index: Option<&Path>,
+        stdin: Option<&[u8]>,
+    ) -> Result<Vec<u8>> {
+        use std::io::Write;
+        use std::process::{Command, Stdio};
+
+        let workdir = self.workdir().ok_or(Error::NotARepository)?;
+        let to_error = |e: std::io::Error| Error::Git2(git2::Error::from_str(&e.to_string()));
+
+        let mut command = Command::new("git");
+        command
+            .args(args)
+            .current_dir(workdir)
+            .stdin(if stdin.is_some() {
+                Stdio::piped()
+            } else {
+                Stdio::null()
+            })
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+        if let Some(index) = index {
+            command.env("GIT_INDEX_FILE", index);
+        }
+
+        let mut child = command.spawn().map_err(to_error)?;
+        if let (Some(input), Some(mut pipe)) = (stdin, child.stdin.take()) {
+            pipe.write_all(input).map_err(to_error)?;
+        }
+        let output = child.wait_with_output().map_err(to_error)?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(Error::Git2(git2::Error::from_str(stderr.trim())));
+        }
+        Ok(output.stdout)
+    }
+}
+
+/// Split NUL-terminated `git -z` output into paths.
+fn split_nul(output: &[u8]) -> Vec<&str> {
+    output
+        .split(|&b| b == 0)
+        .filter(|p| !p.is_empty())
+        .filter_map(|p| std::str::from_utf8(p).ok())
+        .collect()
+}
+
+/// Parsed `git blame --porcelain` output.
+#[derive(Debug, Default)]
+struct PorcelainBlame {
+    /// Commit SHA for each final line number.
+    lines: HashMap<u32, String>,
+    /// Summary and boundary flag for each commit seen.
+    commits: HashMap<String, (String, bool)>,
+}
+
+impl PorcelainBlame {
+    /// Collect the distinct non-boundary commits for a line range, in line order.
+    fn results_for(&self, start: u32, end: u32) -> Result<Vec<BlameResult>> {
+        let mut results: Vec<BlameResult> = Vec::new();
+
+        for line in start..=end {
+            let Some(sha) = self.lines.get(&line) else {
+                continue;
+            };
+            let (summary, boundary) = self.commits.get(sha).cloned().unwrap_or_default();
+            if boundary {
+                continue;
+            }
+
+            let oid = Oid::from_str(sha)
+                .map_err(|e| Error::Git2(git2::Error::from_str(&e.to_string())))?;
+            if !results.iter().any(|r| r.commit == oid) {
+                results.push(BlameResult {
+                    commit: oid,
+                    message: summary,
+                });
+            }
+        }
+
+        Ok(results)
+    }
+}
+
+/// Parse `git blame --porcelain` output.
+///
+/// Each blamed line starts with a `<sha> <orig-line> <final-line>` header;
+/// the first time a commit appears it is followed by `key value` fields
+/// (including `summary` and, for boundary commits, `boundary`). Content
+/// lines are prefixed with a tab.
+fn parse_blame_porcelain(output: &str) -> PorcelainBlame {
+    let mut blame = PorcelainBlame::default();
+    let mut current_sha: Option<String> = None;
+
+    for line in output.lines() {
+        if line.starts_with('\t') {
+            continue;
+        }
+
+        let mut parts = line.split(' ');
+        let first = parts.next().unwrap_or_default();
+        let is_header = first.len() == 40 && first.chars().all(|c| c.is_ascii_hexdigit());
+
+        if is_header {
+            let final_line = parts.nth(1).and_then(|n| n.parse::<u32>().ok());
+            if let Some(final_line) = final_line {
+                blame.lines.insert(final_line, first.to_string());
+            }
+            blame.commits.entry(first.to_string()).or_default();
+            current_sha = Some(first.to_string());
+            continue;
+        }
+
+        let Some(sha) = &current_sha else {
+            continue;
+        };
+        if let Some(summary) = line.strip_prefix("summary ") {
+            if let Some(entry) = blame.commits.get_mut(sha) {
+                entry.0 = summary.to_string();
+            }
+        } else if line == "boundary"
+            && let Some(entry) = blame.commits.get_mut(sha)
+        {
+            entry.1 = true;
+        }
+    }
+
+    blame
 }
 
 impl AbsorbOps for Repository {
@@ -191,6 +582,14 @@ impl AbsorbOps for Repository {
         Self::blame_lines(self, file_path, start, end)
     }
 
+    fn blame_ranges(
+        &self,
+        file_path: &str,
+        ranges: &[(u32, u32)],
+    ) -> Result<Vec<Vec<BlameResult>>> {
+        Self::blame_ranges(self, file_path, ranges)
+    }
+
     fn is_ancestor(&self, ancestor: Oid, descendant: Oid) -> Result<bool> {
         Self::is_ancestor(self, ancestor, descendant)
     }
@@ -198,6 +597,18 @@ impl AbsorbOps for Repository {
     fn create_fixup_commit(&self, target: Oid) -> Result<Oid> {
         Self::create_fixup_commit(self, target)
     }
+
+    fn create_fixup_commit_on(&self, branch: &str, target: Oid) -> Result<Oid> {
+        Self::create_fixup_commit_on(self, branch, target)
+    }
+
+    fn count_fixup_commits(&self, from: Oid, to: Oid) -> Result<usize> {
+        Self::count_fixup_commits(self, from, to)
+    }
+
+    fn rebase_autosquash(&self, onto: Oid, upstream: Oid) -> Result<()> {
+        Self::rebase_autosquash(self, onto, upstream)
+    }
 }
 
 /// Parse unified diff output into hunks.
@@ -207,6 +618,7 @@ fn parse_diff_hunks(diff: &str) -> Vec<Hunk> {
     let mut hunk_content = String::new();
     let mut current_hunk: Option<(u32, u32, u32, u32)> = None;
     let mut is_new_file = false;
+    let mut old_path: Option<String> = None;
 
     for line in diff.lines() {
         // New file header: diff --git a/path b/path
@@ -217,6 +629,7 @@ fn parse_diff_hunks(diff: &str) -> Vec<Hunk> {
             {
                 hunks.push(Hunk {
                     file_path: file.clone(),
+                    old_path: old_path.clone(),
                     old_start,
                     old_lines,
                     new_start,
@@ -228,6 +641,7 @@ fn parse_diff_hunks(diff: &str) -> Vec<Hunk> {
             hunk_content.clear();
             current_hunk = None;
             is_new_file = false;
+            old_path = None;
 
             // Parse file path from "diff --git a/path b/path"
             // Use robust parsing to handle paths containing " b/"
@@ -241,6 +655,15 @@ fn parse_diff_hunks(diff: &str) -> Vec<Hunk> {
             continue;
         }
 
+        // Staged renames: hunk line numbers refer to the file's old path
+        if let Some(path) = line.strip_prefix("rename from ") {
+            old_path = Some(path.to_string());
+            continue;
+        }
+        if line.starts_with("rename to ") || line.starts_with("similarity index ") {
+            continue;
+        }
+
         // Hunk header: @@ -old_start,old_lines +new_start,new_lines @@
         if line.starts_with("@@ ") {
             // Save previous hunk if exists
@@ -249,6 +672,7 @@ fn parse_diff_hunks(diff: &str) -> Vec<Hunk> {
             {
                 hunks.push(Hunk {
                     file_path: file.clone(),
+                    old_path: old_path.clone(),
                     old_start,
                     old_lines,
                     new_start,
@@ -288,6 +712,7 @@ fn parse_diff_hunks(diff: &str) -> Vec<Hunk> {
     {
         hunks.push(Hunk {
             file_path: file.clone(),
+            old_path,
             old_start,
             old_lines,
             new_start,
@@ -486,4 +911,55 @@ index 0000000..abc123
         assert_eq!(hunks[0].new_lines, 3);
         assert!(hunks[0].is_new_file);
     }
+
+    #[test]
+    fn test_parse_diff_hunks_staged_rename() {
+        let diff = r"diff --git a/old.txt b/new.txt
+similarity index 90%
+rename from old.txt
+rename to new.txt
+index abc123..def456 100644
+--- a/old.txt
++++ b/new.txt
+@@ -2 +2 @@ one
+-two
++TWO
+";
+
+        let hunks = parse_diff_hunks(diff);
+        assert_eq!(hunks.len(), 1);
+        assert_eq!(hunks[0].file_path, "new.txt");
+        assert_eq!(hunks[0].old_path.as_deref(), Some("old.txt"));
+        assert_eq!(hunks[0].content, "-two\n+TWO\n");
+        assert!(!hunks[0].is_new_file);
+    }
+
+    #[test]
+    fn test_parse_blame_porcelain() {
+        let a = "a".repeat(40);
+        let b = "b".repeat(40);
+        let root = "c".repeat(40);
+        let output = format!(
+            "{a} 1 1 2\nauthor Test\nsummary First change\nfilename f.txt\n\tline1\n\
+             {a} 2 2\n\tline2\n\
+             {root} 3 3 1\nauthor Test\nsummary Initial\nboundary\nfilename f.txt\n\tline3\n\
+             {b} 9 10 1\nauthor Test\nsummary Second change\nfilename f.txt\n\tline10\n"
+        );
+
+        let blame = parse_blame_porcelain(&output);
+        assert_eq!(blame.lines.len(), 4);
+
+        let first = blame.results_for(1, 2).unwrap();
+        assert_eq!(first.len(), 1);
+        assert_eq!(first[0].commit, Oid::from_str(&a).unwrap());
+        assert_eq!(first[0].message, "First change");
+
+        // Boundary commits are skipped
+        assert!(blame.results_for(3, 3).unwrap().is_empty());
+
+        // Ranges spanning several commits return each once, in line order
+        let spanning = blame.results_for(1, 10).unwrap();
+        let messages: Vec<&str> = spanning.iter().map(|r| r.message.as_str()).collect();
+        assert_eq!(messages, vec!["First change", "Second change"]);
+    }
 }
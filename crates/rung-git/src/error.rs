@@ -30,6 +30,29 @@ pub enum Error {
     #[error("rebase failed: {0}")]
     RebaseFailed(String),
 
+    /// A native git operation (merge, cherry-pick, ...) is in progress.
+    #[error("a git {0} is in progress - {hint}", hint = .0.hint())]
+    OperationInProgress(crate::GitOperation),
+
+    /// The operation would rewrite, force-push, or delete a protected branch.
+    #[error(
+        "policy violation: refusing to {action} protected branch '{branch}' (matches '{pattern}')"
+    )]
+    ProtectedBranch {
+        /// The protected branch.
+        branch: String,
+        /// The `policy.protected` pattern it matched.
+        pattern: String,
+        /// What was refused.
+        action: &'static str,
+    },
+
+    /// No author/committer identity is configured.
+    #[error(
+        "{0} is not set - run `git config --global {0} <value>`, or set it for this repository without --global"
+    )]
+    MissingIdentity(&'static str),
+
     /// Working directory is dirty.
     #[error("working directory has uncommitted changes")]
     DirtyWorkingDirectory,
@@ -7,15 +7,23 @@
 //! # Architecture
 //!
 //! The crate provides both a concrete [`Repository`] implementation and
-//! a [`GitOps`] trait for dependency injection and testing.
+//! a [`GitOps`] trait for dependency injection and testing. The
+//! `test-utils` feature adds an in-memory [`GitOps`] in [`testing`].
 
 mod absorb;
 mod error;
 mod repository;
+#[cfg(any(test, feature = "test-utils"))]
+pub mod testing;
+mod toolchain;
 mod traits;
 
 pub use absorb::{BlameResult, Hunk};
 pub use error::{Error, Result};
 pub use git2::Oid;
-pub use repository::{ConflictPrediction, RemoteDivergence, Repository};
+pub use repository::{
+    ConflictPrediction, DiffFormat, GitOperation, Identity, ProtectedBranches, RebaseDates,
+    RemoteDivergence, Repository, SigningConfig, WorktreeStatus, cleanup_message,
+};
+pub use toolchain::{MIN_GIT_VERSION, Toolchain};
 pub use traits::{AbsorbOps, GitOps};
@@ -1,12 +1,15 @@
 //! Repository wrapper providing high-level git operations.
 
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use git2::{BranchType, Oid, RepositoryState, Signature};
 
 use crate::error::{Error, Result};
 use crate::traits::GitOps;
 
+/// The remote rung uses unless told otherwise.
+const DEFAULT_REMOTE: &str = "origin";
+
 /// Predicted conflict for a single commit during a rebase operation.
 ///
 /// This is used by the conflict prediction system to warn users about
@@ -21,7 +24,7 @@ pub struct ConflictPrediction {
     pub conflicting_files: Vec<String>,
 }
 
-/// Divergence state between a local branch and its tracking remote (upstream, falls back to origin).
+/// Divergence state between a local branch and its tracking remote (upstream, falls back to rung's remote).
 ///
 /// This is distinct from `BranchState::Diverged` which tracks divergence from the
 /// *parent branch* (needs sync). `RemoteDivergence` tracks local vs remote (needs push/pull).
@@ -48,11 +51,300 @@ pub enum RemoteDivergence {
     },
     /// No remote tracking branch exists (first push).
     NoRemote,
+    /// An upstream is configured but its remote tracking branch is gone,
+    /// i.e. the remote branch was deleted and the ref pruned.
+    Gone,
+}
+
+/// File counts from the working tree and index, as `git status` reports them.
+///
+/// A file with both staged and unstaged changes counts in both `staged` and
+/// `modified`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct WorktreeStatus {
+    /// Files with changes in the index.
+    pub staged: usize,
+    /// Tracked files with changes not yet staged.
+    pub modified: usize,
+    /// Untracked files (ignored files are not counted).
+    pub untracked: usize,
+}
+
+/// How much of a commit's change [`Repository::commit_diff`] renders.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffFormat {
+    /// The full patch, as `git show --format=` prints it.
+    Patch,
+    /// Per-file line counts, as `git show --stat` prints them.
+    Stat,
+}
+
+impl WorktreeStatus {
+    /// Whether there are no staged or unstaged changes to tracked files.
+    #[must_use]
+    pub const fn is_clean(&self) -> bool {
+        self.staged == 0 && self.modified == 0
+    }
+}
+
+/// A native git operation left in progress in the repository.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GitOperation {
+    /// `git merge` stopped (usually on conflicts).
+    Merge,
+    /// `git revert` stopped.
+    Revert,
+    /// `git cherry-pick` stopped.
+    CherryPick,
+    /// `git bisect` session.
+    Bisect,
+    /// `git rebase` stopped.
+    Rebase,
+    /// `git am` applying patches.
+    ApplyMailbox,
+}
+
+impl GitOperation {
+    /// How to finish or abort the operation.
+    #[must_use]
+    pub const fn hint(self) -> &'static str {
+        match self {
+            Self::Merge => "finish with `git commit` or abort with `git merge --abort`",
+            Self::Revert => {
+                "finish with `git revert --continue` or abort with `git revert --abort`"
+            }
+            Self::CherryPick => {
+                "finish with `git cherry-pick --continue` or abort with `git cherry-pick --abort`"
+            }
+            Self::Bisect => "end the session with `git bisect reset`",
+            Self::Rebase => {
+                "finish with `git rebase --continue` or abort with `git rebase --abort`"
+            }
+            Self::ApplyMailbox => "finish with `git am --continue` or abort with `git am --abort`",
+        }
+    }
+}
+
+impl std::fmt::Display for GitOperation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Merge => "merge",
+            Self::Revert => "revert",
+            Self::CherryPick => "cherry-pick",
+            Self::Bisect => "bisect",
+            Self::Rebase => "rebase",
+            Self::ApplyMailbox => "am",
+        })
+    }
+}
+
+/// How commit dates are set when rung rebases commits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RebaseDates {
+    /// Keep each commit's original author date (git's default).
+    /// When false, author dates are reset to the time of the rebase.
+    pub preserve_author_date: bool,
+    /// Set committer dates to the time of the rebase (git's default).
+    /// When false, each commit's committer date is set to its author date.
+    pub reset_committer_date: bool,
+}
+
+impl Default for RebaseDates {
+    fn default() -> Self {
+        Self {
+            preserve_author_date: true,
+            reset_committer_date: true,
+        }
+    }
+}
+
+impl RebaseDates {
+    /// Extra `git rebase` arguments implementing these settings.
+    fn args(self) -> Vec<&'static str> {
+        let mut args = Vec::new();
+        if !self.preserve_author_date {
+            args.push("--reset-author-date");
+        }
+        if !self.reset_committer_date {
+            args.push("--committer-date-is-author-date");
+        }
+        args
+    }
+}
+
+/// The name and email commits are made as.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Identity {
+    /// `user.name`, or the role's override.
+    pub name: String,
+    /// `user.email`, or the role's override.
+    pub email: String,
+}
+
+/// Commit signing settings read from git config.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SigningConfig {
+    /// Whether git signs new commits (`commit.gpgsign`).
+    pub enabled: bool,
+    /// Signature format (`gpg.format`): `openpgp`, `ssh`, or `x509`.
+    pub format: String,
+    /// Signing key (`user.signingkey`), if configured.
+    pub key: Option<String>,
+    /// Program git runs to sign (`gpg.program` or the format-specific setting).
+    pub program: String,
+}
+
+impl Default for SigningConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            format: "openpgp".into(),
+            key: None,
+            program: "gpg".into(),
+        }
+    }
+}
+
+impl SigningConfig {
+    /// Check whether the signing program can be found.
+    #[must_use]
+    pub fn program_available(&self) -> bool {
+        let program = Path::new(&self.program);
+        if program.components().count() > 1 {
+            return program.is_file();
+        }
+        std::env::var_os("PATH").is_some_and(|paths| {
+            std::env::split_paths(&paths).any(|dir| {
+                dir.join(program).is_file()
+                    || (cfg!(windows) && dir.join(program).with_extension("exe").is_file())
+            })
+        })
+    }
+
+    /// Check whether the configured signing key can be used.
+    ///
+    /// SSH signing requires a key: either a literal public key or a key file.
+    /// For GPG formats an unset key falls back to the committer identity, so
+    /// only an explicitly configured key is looked up in the keyring.
+    #[must_use]
+    pub fn key_available(&self) -> bool {
+        if self.format == "ssh" {
+            return self.key.as_deref().is_some_and(|key| {
+                key.starts_with("key::")
+                    || key.starts_with("ssh-")
+                    || Path::new(&expand_home(key)).is_file()
+            });
+        }
+
+        self.key.as_deref().is_none_or(|key| {
+            std::process::Command::new(&self.program)
+                .args(["--list-secret-keys", key])
+                .stdout(std::process::Stdio::null())
+                .stderr(std::process::Stdio::null())
+                .status()
+                .is_ok_and(|status| status.success())
+        })
+    }
+}
+
+/// Clean up an edited commit message like git does: drop `#` comment lines
+/// and trim surrounding whitespace.
+#[must_use]
+pub fn cleanup_message(message: &str) -> String {
+    message
+        .lines()
+        .filter(|line| !line.starts_with('#'))
+        .collect::<Vec<_>>()
+        .join("\n")
+        .trim()
+        .to_string()
+}
+
+/// Expand a leading `~/` to the user's home directory.
+fn expand_home(path: &str) -> PathBuf {
+    match (path.strip_prefix("~/"), std::env::var_os("HOME")) {
+        (Some(rest), Some(home)) => PathBuf::from(home).join(rest),
+        _ => PathBuf::from(path),
+    }
+}
+
+/// Branch name patterns that rung must never rewrite, force-push, or delete.
+///
+/// Patterns are matched against the full branch name. `*` matches any run of
+/// characters within one path segment, `**` also crosses `/`, and `?` matches
+/// a single character.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProtectedBranches {
+    patterns: Vec<String>,
+}
+
+impl ProtectedBranches {
+    /// Create a policy from a list of glob patterns.
+    #[must_use]
+    pub fn new(patterns: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        Self {
+            patterns: patterns.into_iter().map(Into::into).collect(),
+        }
+    }
+
+    /// The first pattern that matches `branch`, if any.
+    #[must_use]
+    pub fn matching(&self, branch: &str) -> Option<&str> {
+        self.patterns
+            .iter()
+            .find(|pattern| {
+                let pattern: Vec<char> = pattern.chars().collect();
+                let name: Vec<char> = branch.chars().collect();
+                glob_match(&pattern, &name)
+            })
+            .map(String::as_str)
+    }
+
+    /// Check whether `branch` is protected.
+    #[must_use]
+    pub fn is_protected(&self, branch: &str) -> bool {
+        self.matching(branch).is_some()
+    }
+
+    /// Fail with a policy violation if `branch` is protected.
+    ///
+    /// `action` describes what was refused, e.g. "force-push".
+    ///
+    /// # Errors
+    /// Returns `ProtectedBranch` if the branch matches a protected pattern.
+    pub fn check(&self, branch: &str, action: &'static str) -> Result<()> {
+        self.matching(branch).map_or(Ok(()), |pattern| {
+            Err(Error::ProtectedBranch {
+                branch: branch.to_string(),
+                pattern: pattern.to_string(),
+                action,
+            })
+        })
+    }
+}
+
+/// Match a branch name against a glob pattern (`*`, `**`, `?`).
+fn glob_match(pattern: &[char], name: &[char]) -> bool {
+    match pattern {
+        [] => name.is_empty(),
+        ['*', '*', rest @ ..] => (0..=name.len()).any(|i| glob_match(rest, &name[i..])),
+        ['*', rest @ ..] => {
+            let segment = name.iter().position(|&c| c == '/').unwrap_or(name.len());
+            (0..=segment).any(|i| glob_match(rest, &name[i..]))
+        }
+        ['?', rest @ ..] => {
+            matches!(name, [c, tail @ ..] if *c != '/' && glob_match(rest, tail))
+        }
+        [p, rest @ ..] => matches!(name, [c, tail @ ..] if c == p && glob_match(rest, tail)),
+    }
 }
 
 /// High-level wrapper around a git repository.
 pub struct Repository {
     inner: git2::Repository,
+    rebase_dates: RebaseDates,
+    protected: ProtectedBranches,
+    remote: String,
 }
 
 impl Repository {
@@ -62,7 +354,12 @@ impl Repository {
     /// Returns error if no repository found at path or any parent.
     pub fn open(path: impl AsRef<Path>) -> Result<Self> {
         let inner = git2::Repository::discover(path)?;
-        Ok(Self { inner })
+        Ok(Self {
+            inner,
+            rebase_dates: RebaseDates::default(),
+            protected: ProtectedBranches::default(),
+            remote: DEFAULT_REMOTE.to_string(),
+        })
     }
 
     /// Open the repository containing the current directory.
@@ -85,6 +382,37 @@ impl Repository {
         self.inner.path()
     }
 
+    /// Get the git directory shared by all worktrees of this repository.
+    ///
+    /// Same as [`git_dir`](Self::git_dir) in the main worktree; in a linked
+    /// worktree, `git_dir` is the worktree's private directory under
+    /// `.git/worktrees/` while this is the main `.git` directory.
+    #[must_use]
+    pub fn common_dir(&self) -> PathBuf {
+        let git_dir = self.inner.path();
+        std::fs::read_to_string(git_dir.join("commondir")).map_or_else(
+            |_| git_dir.to_path_buf(),
+            |common| git_dir.join(common.trim()),
+        )
+    }
+
+    /// When `remote` was last fetched with git, going by `FETCH_HEAD`.
+    ///
+    /// Returns `None` if nothing has been fetched yet, or the most recent
+    /// fetch was from a different remote.
+    #[must_use]
+    pub fn last_fetched(&self, remote: &str) -> Option<std::time::SystemTime> {
+        let remote = self.inner.find_remote(remote).ok()?;
+        // Git records the URL without a trailing `.git`
+        let url = remote.url()?.trim_end_matches('/').trim_end_matches(".git");
+        let path = self.git_dir().join("FETCH_HEAD");
+        let content = std::fs::read_to_string(&path).ok()?;
+        if !content.lines().any(|line| line.ends_with(url)) {
+            return None;
+        }
+        std::fs::metadata(&path).and_then(|m| m.modified()).ok()
+    }
+
     /// Get the current repository state.
     #[must_use]
     pub fn state(&self) -> RepositoryState {
@@ -102,6 +430,50 @@ impl Repository {
         )
     }
 
+    /// The branch a rebase in progress is rewriting, if any.
+    ///
+    /// `None` when no rebase is in progress or it started on a detached HEAD.
+    #[must_use]
+    pub fn rebasing_branch(&self) -> Option<String> {
+        ["rebase-merge", "rebase-apply"].iter().find_map(|dir| {
+            let head_name =
+                std::fs::read_to_string(self.inner.path().join(dir).join("head-name")).ok()?;
+            head_name
+                .trim()
+                .strip_prefix("refs/heads/")
+                .map(String::from)
+        })
+    }
+
+    /// Get the native git operation in progress, if any.
+    #[must_use]
+    pub fn operation_in_progress(&self) -> Option<GitOperation> {
+        match self.state() {
+            RepositoryState::Clean => None,
+            RepositoryState::Merge => Some(GitOperation::Merge),
+            RepositoryState::Revert | RepositoryState::RevertSequence => Some(GitOperation::Revert),
+            RepositoryState::CherryPick | RepositoryState::CherryPickSequence => {
+                Some(GitOperation::CherryPick)
+            }
+            RepositoryState::Bisect => Some(GitOperation::Bisect),
+            RepositoryState::Rebase
+            | RepositoryState::RebaseInteractive
+            | RepositoryState::RebaseMerge => Some(GitOperation::Rebase),
+            RepositoryState::ApplyMailbox | RepositoryState::ApplyMailboxOrRebase => {
+                Some(GitOperation::ApplyMailbox)
+            }
+        }
+    }
+
+    /// Ensure no native git operation is in progress.
+    ///
+    /// # Errors
+    /// Returns `OperationInProgress` naming the operation and how to resolve it.
+    pub fn require_no_operation(&self) -> Result<()> {
+        self.operation_in_progress()
+            .map_or(Ok(()), |op| Err(Error::OperationInProgress(op)))
+    }
+
     /// Check if HEAD is detached (not pointing at a branch).
     ///
     /// # Errors
@@ -111,6 +483,14 @@ impl Repository {
         Ok(!head.is_branch())
     }
 
+    /// Get the commit HEAD points at, whether or not it is on a branch.
+    ///
+    /// # Errors
+    /// Returns error if HEAD cannot be resolved to a commit.
+    pub fn head_commit(&self) -> Result<Oid> {
+        Ok(self.inner.head()?.peel_to_commit()?.id())
+    }
+
     // === Branch operations ===
 
     /// Get the name of the current branch.
@@ -146,15 +526,16 @@ impl Repository {
 
     /// Get the commit ID of a remote branch tip.
     ///
-    /// Uses the configured upstream if set, otherwise falls back to `origin/<branch>`.
+    /// Uses the configured upstream if set, otherwise falls back to
+    /// `<remote>/<branch>` on rung's remote.
     ///
     /// # Errors
     /// Returns error if branch not found.
     pub fn remote_branch_commit(&self, branch_name: &str) -> Result<Oid> {
-        // Try configured upstream first, fall back to origin/<branch>
+        // Try configured upstream first, fall back to <remote>/<branch>
         let remote_ref = self
             .branch_upstream_ref(branch_name)
-            .unwrap_or_else(|| format!("refs/remotes/origin/{branch_name}"));
+            .unwrap_or_else(|| format!("refs/remotes/{}/{branch_name}", self.remote));
 
         let reference = self
             .inner
@@ -229,11 +610,51 @@ impl Repository {
         self.inner.find_branch(name, BranchType::Local).is_ok()
     }
 
+    /// Get a branch's description (`branch.<name>.description`, as set by
+    /// `git branch --edit-description`).
+    ///
+    /// Returns `None` if the branch has no description or it's blank.
+    #[must_use]
+    pub fn branch_description(&self, name: &str) -> Option<String> {
+        let description = self
+            .inner
+            .config()
+            .ok()?
+            .get_string(&format!("branch.{name}.description"))
+            .ok()?;
+        let description = description.trim();
+        (!description.is_empty()).then(|| description.to_string())
+    }
+
+    /// Set a branch's description, or remove it when `description` is blank.
+    ///
+    /// # Errors
+    /// Returns error if the branch doesn't exist or the config can't be written.
+    pub fn set_branch_description(&self, name: &str, description: &str) -> Result<()> {
+        if !self.branch_exists(name) {
+            return Err(Error::BranchNotFound(name.into()));
+        }
+        let key = format!("branch.{name}.description");
+        let mut config = self.inner.config()?.open_level(git2::ConfigLevel::Local)?;
+        let description = description.trim();
+        if description.is_empty() {
+            match config.remove(&key) {
+                Err(e) if e.code() != git2::ErrorCode::NotFound => return Err(e.into()),
+                _ => {}
+            }
+        } else {
+            // Stored with a trailing newline, as git does
+            config.set_str(&key, &format!("{description}\n"))?;
+        }
+        Ok(())
+    }
+
     /// Delete a local branch.
     ///
     /// # Errors
-    /// Returns error if branch deletion fails.
+    /// Returns error if the branch is protected or deletion fails.
     pub fn delete_branch(&self, name: &str) -> Result<()> {
+        self.protected.check(name, "delete")?;
         let mut branch = self.inner.find_branch(name, BranchType::Local)?;
         branch.delete()?;
         Ok(())
@@ -246,9 +667,19 @@ impl Repository {
     /// Untracked files are ignored - only tracked files that have been
     /// modified or staged count as "dirty".
     ///
+    /// When `core.fsmonitor` is configured, this asks the git CLI instead of
+    /// libgit2: libgit2 doesn't speak the fsmonitor protocol and would stat
+    /// every tracked file, which is slow in very large worktrees.
+    ///
     /// # Errors
     /// Returns error if status check fails.
     pub fn is_clean(&self) -> Result<bool> {
+        if self.fsmonitor_enabled()
+            && let Some(clean) = self.is_clean_via_cli()
+        {
+            return Ok(clean);
+        }
+
         let mut opts = git2::StatusOptions::new();
         opts.include_untracked(false)
             .include_ignored(false)
@@ -277,6 +708,48 @@ impl Repository {
         Ok(true)
     }
 
+    /// Whether `core.fsmonitor` is set to `true` (builtin daemon) or a hook path.
+    fn fsmonitor_enabled(&self) -> bool {
+        let Ok(config) = self.inner.config() else {
+            return false;
+        };
+        if let Ok(enabled) = config.get_bool("core.fsmonitor") {
+            return enabled;
+        }
+        config
+            .get_string("core.fsmonitor")
+            .is_ok_and(|hook| !hook.trim().is_empty())
+    }
+
+    /// Check cleanliness with `git status`, which uses the fsmonitor.
+    ///
+    /// Returns `None` if the command can't be run, so the caller can fall
+    /// back to libgit2.
+    fn is_clean_via_cli(&self) -> Option<bool> {
+        let workdir = self.workdir()?;
+        let output = std::process::Command::new("git")
+            .args([
+                "status",
+                "--porcelain",
+                "--untracked-files=no",
+                "--ignore-submodules",
+            ])
+            .current_dir(workdir)
+            .output()
+            .ok()?;
+
+        if !output.status.success() {
+            return None;
+        }
+
+        // Unmerged entries don't count as dirty, matching the libgit2 path
+        let dirty = String::from_utf8_lossy(&output.stdout).lines().any(|line| {
+            let xy = line.get(..2).unwrap_or_default();
+            !matches!(xy, "DD" | "AU" | "UD" | "UA" | "DU" | "AA" | "UU")
+        });
+        Some(!dirty)
+    }
+
     /// Ensure working directory is clean, returning error if not.
     ///
     /// # Errors
@@ -314,6 +787,75 @@ impl Repository {
         }
     }
 
+    /// Stage modifications and deletions of tracked files.
+    ///
+    /// Equivalent to `git add -u`; untracked files are left alone.
+    ///
+    /// # Errors
+    /// Returns error if staging fails.
+    pub fn stage_tracked(&self) -> Result<()> {
+        let workdir = self.workdir().ok_or(Error::NotARepository)?;
+
+        let output = std::process::Command::new("git")
+            .args(["add", "-u"])
+            .current_dir(workdir)
+            .output()
+            .map_err(|e| Error::Git2(git2::Error::from_str(&e.to_string())))?;
+
+        if output.status.success() {
+            Ok(())
+        } else {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            Err(Error::Git2(git2::Error::from_str(&stderr)))
+        }
+    }
+
+    /// Interactively choose hunks to stage.
+    ///
+    /// Runs `git add -p` attached to the terminal.
+    ///
+    /// # Errors
+    /// Returns error if git cannot be run or exits with failure.
+    pub fn stage_interactive(&self) -> Result<()> {
+        let workdir = self.workdir().ok_or(Error::NotARepository)?;
+
+        let status = std::process::Command::new("git")
+            .args(["add", "-p"])
+            .current_dir(workdir)
+            .status()
+            .map_err(|e| Error::Git2(git2::Error::from_str(&e.to_string())))?;
+
+        if status.success() {
+            Ok(())
+        } else {
+            Err(Error::Git2(git2::Error::from_str("git add -p failed")))
+        }
+    }
+
+    /// Write the current index to a tree, so it can be restored later.
+    ///
+    /// # Errors
+    /// Returns error if the index cannot be read or written.
+    pub fn snapshot_index(&self) -> Result<Oid> {
+        let mut index = self.inner.index()?;
+        index.read(false)?;
+        Ok(index.write_tree()?)
+    }
+
+    /// Reset the index to a tree taken with [`Self::snapshot_index`].
+    ///
+    /// The working tree is not touched.
+    ///
+    /// # Errors
+    /// Returns error if the tree cannot be found or the index cannot be written.
+    pub fn restore_index(&self, tree: Oid) -> Result<()> {
+        let tree = self.inner.find_tree(tree)?;
+        let mut index = self.inner.index()?;
+        index.read_tree(&tree)?;
+        index.write()?;
+        Ok(())
+    }
+
     /// Check if there are staged changes ready to commit.
     ///
     /// # Errors
@@ -340,6 +882,146 @@ impl Repository {
         Ok(false)
     }
 
+    /// Count staged, modified and untracked files.
+    ///
+    /// # Errors
+    /// Returns error if the status cannot be read.
+    pub fn worktree_status(&self) -> Result<WorktreeStatus> {
+        let mut opts = git2::StatusOptions::new();
+        opts.include_untracked(true)
+            .recurse_untracked_dirs(true)
+            .include_ignored(false)
+            .include_unmodified(false)
+            .exclude_submodules(true);
+        let statuses = self.inner.statuses(Some(&mut opts))?;
+
+        let mut summary = WorktreeStatus::default();
+        for entry in statuses.iter() {
+            let status = entry.status();
+            if status.intersects(
+                git2::Status::INDEX_NEW
+                    | git2::Status::INDEX_MODIFIED
+                    | git2::Status::INDEX_DELETED
+                    | git2::Status::INDEX_RENAMED
+                    | git2::Status::INDEX_TYPECHANGE,
+            ) {
+                summary.staged += 1;
+            }
+            if status.intersects(
+                git2::Status::WT_MODIFIED
+                    | git2::Status::WT_DELETED
+                    | git2::Status::WT_TYPECHANGE
+                    | git2::Status::WT_RENAMED,
+            ) {
+                summary.modified += 1;
+            }
+            if status.contains(git2::Status::WT_NEW) {
+                summary.untracked += 1;
+            }
+        }
+        Ok(summary)
+    }
+
+    /// Paths with staged changes, as `git diff --cached --name-only` lists them.
+    ///
+    /// # Errors
+    /// Returns error if the status cannot be read.
+    pub fn staged_files(&self) -> Result<Vec<String>> {
+        let mut opts = git2::StatusOptions::new();
+        opts.include_untracked(false)
+            .include_ignored(false)
+            .include_unmodified(false)
+            .renames_head_to_index(true);
+        let statuses = self.inner.statuses(Some(&mut opts))?;
+
+        Ok(statuses
+            .iter()
+            .filter(|entry| {
+                entry.status().intersects(
+                    git2::Status::INDEX_NEW
+                        | git2::Status::INDEX_MODIFIED
+                        | git2::Status::INDEX_DELETED
+                        | git2::Status::INDEX_RENAMED
+                        | git2::Status::INDEX_TYPECHANGE,
+                )
+            })
+            .filter_map(|entry| {
+                entry
+                    .head_to_index()
+                    .and_then(|delta| delta.new_file().path().or_else(|| delta.old_file().path()))
+                    .map(|path| path.to_string_lossy().into_owned())
+            })
+            .collect())
+    }
+
+    /// Let the user write a message in their git editor.
+    ///
+    /// Writes `template` to `.git/RUNG_EDITMSG`, opens it in the editor git
+    /// would use (`GIT_EDITOR`, `core.editor`, `VISUAL`, `EDITOR`), and
+    /// returns the result with `#` comment lines removed and whitespace
+    /// trimmed. An empty string means the user aborted.
+    ///
+    /// # Errors
+    /// Returns error if the editor cannot be resolved or exits with failure.
+    pub fn edit_message(&self, template: &str) -> Result<String> {
+        let workdir = self.workdir().ok_or(Error::NotARepository)?;
+        let to_error = |e: std::io::Error| Error::Git2(git2::Error::from_str(&e.to_string()));
+
+        let output = std::process::Command::new("git")
+            .args(["var", "GIT_EDITOR"])
+            .current_dir(workdir)
+            .output()
+            .map_err(to_error)?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(Error::Git2(git2::Error::from_str(&stderr)));
+        }
+        let editor = String::from_utf8_lossy(&output.stdout).trim().to_string();
+
+        self.edit_message_with(&editor, template)
+    }
+
+    /// The pager git would use: `GIT_PAGER`, `core.pager`, `PAGER`, then `less`.
+    ///
+    /// Returns `None` if paging is turned off (an empty pager or `cat`) or
+    /// git can't be asked.
+    #[must_use]
+    pub fn pager(&self) -> Option<String> {
+        let output = std::process::Command::new("git")
+            .args(["var", "GIT_PAGER"])
+            .current_dir(self.workdir()?)
+            .output()
+            .ok()?;
+        let pager = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        (output.status.success() && !pager.is_empty() && pager != "cat").then_some(pager)
+    }
+
+    /// [`Self::edit_message`] with an already resolved editor command.
+    fn edit_message_with(&self, editor: &str, template: &str) -> Result<String> {
+        let workdir = self.workdir().ok_or(Error::NotARepository)?;
+        let to_error = |e: std::io::Error| Error::Git2(git2::Error::from_str(&e.to_string()));
+
+        let path = self.git_dir().join("RUNG_EDITMSG");
+        std::fs::write(&path, template).map_err(to_error)?;
+
+        // Run through the shell like git does, so editors with arguments work
+        let status = std::process::Command::new("sh")
+            .args(["-c", &format!("{editor} \"$@\""), editor])
+            .arg(&path)
+            .current_dir(workdir)
+            .status()
+            .map_err(to_error)?;
+        if !status.success() {
+            return Err(Error::Git2(git2::Error::from_str(&format!(
+                "editor '{editor}' exited with {status}"
+            ))));
+        }
+
+        let edited = std::fs::read_to_string(&path).map_err(to_error)?;
+        let _ = std::fs::remove_file(&path);
+        Ok(cleanup_message(&edited))
+    }
+
     /// Create a commit with the given message on HEAD.
     ///
     /// Handles both normal commits (with parent) and initial commits (no parent).
@@ -347,7 +1029,8 @@ impl Repository {
     /// # Errors
     /// Returns error if commit creation fails.
     pub fn create_commit(&self, message: &str) -> Result<Oid> {
-        let sig = self.signature()?;
+        let author = self.author_signature()?;
+        let committer = self.signature()?;
         let mut index = self.inner.index()?;
         // Reload index from disk in case it was modified by external commands (e.g., git add)
         index.read(false)?;
@@ -356,14 +1039,18 @@ impl Repository {
 
         // Handle initial commit case (unborn HEAD)
         let oid = match self.inner.head().and_then(|h| h.peel_to_commit()) {
-            Ok(parent) => {
-                self.inner
-                    .commit(Some("HEAD"), &sig, &sig, message, &tree, &[&parent])?
-            }
+            Ok(parent) => self.inner.commit(
+                Some("HEAD"),
+                &author,
+                &committer,
+                message,
+                &tree,
+                &[&parent],
+            )?,
             Err(_) => {
                 // Initial commit - no parent
                 self.inner
-                    .commit(Some("HEAD"), &sig, &sig, message, &tree, &[])?
+                    .commit(Some("HEAD"), &author, &committer, message, &tree, &[])?
             }
         };
 
@@ -376,9 +1063,12 @@ impl Repository {
     /// `git commit --amend -m "message"` if a new message is provided.
     ///
     /// # Errors
-    /// Returns error if amend fails or no commits exist.
+    /// Returns error if amend fails, no commits exist, or no identity is
+    /// configured.
     pub fn amend_commit(&self, new_message: Option<&str>) -> Result<Oid> {
         let workdir = self.workdir().ok_or(Error::NotARepository)?;
+        // Fail clearly up front rather than with git's identity prompt
+        self.identity()?;
 
         let mut args = vec!["commit", "--amend"];
 
@@ -403,6 +1093,73 @@ impl Repository {
         }
     }
 
+    // === Signing ===
+
+    /// Read the commit signing settings from git config.
+    ///
+    /// # Errors
+    /// Returns error if git config cannot be read.
+    pub fn signing_config(&self) -> Result<SigningConfig> {
+        let config = self.inner.config()?;
+        let format = config
+            .get_string("gpg.format")
+            .unwrap_or_else(|_| "openpgp".into());
+        let (program_key, default_program) = match format.as_str() {
+            "ssh" => ("gpg.ssh.program", "ssh-keygen"),
+            "x509" => ("gpg.x509.program", "gpgsm"),
+            _ => ("gpg.openpgp.program", "gpg"),
+        };
+        let program = config
+            .get_string(program_key)
+            .or_else(|_| config.get_string("gpg.program"))
+            .unwrap_or_else(|_| default_program.into());
+
+        Ok(SigningConfig {
+            enabled: config.get_bool("commit.gpgsign").unwrap_or(false),
+            format,
+            key: config.get_string("user.signingkey").ok(),
+            program,
+        })
+    }
+
+    /// Where git gets credentials for HTTPS remotes: a credential helper
+    /// (`credential.helper` or `credential.<url>.helper`), or an auth header
+    /// (`http.<url>.extraheader`, as CI checkouts set). `None` if neither is
+    /// configured.
+    #[must_use]
+    pub fn https_credentials(&self) -> Option<String> {
+        let config = self.inner.config().ok()?;
+        let mut helper = None;
+        if let Ok(mut entries) = config.entries(Some(r"^credential\..*helper$")) {
+            while let Some(Ok(entry)) = entries.next() {
+                // An empty value resets the helpers configured before it
+                helper = entry
+                    .value()
+                    .filter(|v| !v.trim().is_empty())
+                    .map(|v| format!("credential helper '{v}'"));
+            }
+        }
+        helper.or_else(|| {
+            let mut headers = config.entries(Some(r"^http\..*extraheader$")).ok()?;
+            headers
+                .next()
+                .is_some_and(|entry| entry.is_ok())
+                .then(|| "http.extraheader".to_string())
+        })
+    }
+
+    /// Check whether a commit carries a signature.
+    ///
+    /// # Errors
+    /// Returns error if the commit cannot be read.
+    pub fn is_commit_signed(&self, commit: Oid) -> Result<bool> {
+        match self.inner.extract_signature(&commit, None) {
+            Ok(_) => Ok(true),
+            Err(e) if e.code() == git2::ErrorCode::NotFound => Ok(false),
+            Err(e) => Err(e.into()),
+        }
+    }
+
     // === Commit operations ===
 
     /// Get a commit by its SHA.
@@ -413,6 +1170,54 @@ impl Repository {
         Ok(self.inner.find_commit(oid)?)
     }
 
+    /// Render the change a commit makes against its first parent.
+    ///
+    /// A root commit is diffed against the empty tree.
+    ///
+    /// # Errors
+    /// Returns error if the commit or its trees can't be read.
+    pub fn commit_diff(&self, commit: Oid, format: DiffFormat) -> Result<String> {
+        let commit = self.inner.find_commit(commit)?;
+        let parent_tree = match commit.parent(0) {
+            Ok(parent) => Some(parent.tree()?),
+            Err(_) => None,
+        };
+        let diff =
+            self.inner
+                .diff_tree_to_tree(parent_tree.as_ref(), Some(&commit.tree()?), None)?;
+
+        match format {
+            DiffFormat::Stat => {
+                let stats = diff.stats()?;
+                let buf = stats.to_buf(git2::DiffStatsFormat::FULL, 80)?;
+                Ok(buf.as_str().unwrap_or_default().to_string())
+            }
+            DiffFormat::Patch => {
+                let mut patch = String::new();
+                diff.print(git2::DiffFormat::Patch, |_, _, line| {
+                    if matches!(line.origin(), '+' | '-' | ' ') {
+                        patch.push(line.origin());
+                    }
+                    patch.push_str(&String::from_utf8_lossy(line.content()));
+                    true
+                })?;
+                Ok(patch)
+            }
+        }
+    }
+
+    /// Resolve a revision (branch, tag, SHA, `HEAD~2`, ...) to a commit.
+    ///
+    /// # Errors
+    /// Returns `RefNotFound` if the revision doesn't name a commit.
+    pub fn resolve_commit(&self, rev: &str) -> Result<Oid> {
+        self.inner
+            .revparse_single(rev)
+            .and_then(|object| object.peel_to_commit())
+            .map(|commit| commit.id())
+            .map_err(|_| Error::RefNotFound(rev.to_string()))
+    }
+
     /// Get the commit message from a branch's tip commit.
     ///
     /// # Errors
@@ -426,6 +1231,19 @@ impl Repository {
             .ok_or_else(|| Error::Git2(git2::Error::from_str("commit has no message")))
     }
 
+    /// When a commit was authored.
+    ///
+    /// Author dates survive rebases and amends, so this tells when the work
+    /// itself last changed.
+    ///
+    /// # Errors
+    /// Returns error if the commit doesn't exist.
+    pub fn commit_author_time(&self, oid: Oid) -> Result<std::time::SystemTime> {
+        let commit = self.inner.find_commit(oid)?;
+        let seconds = u64::try_from(commit.author().when().seconds()).unwrap_or_default();
+        Ok(std::time::UNIX_EPOCH + std::time::Duration::from_secs(seconds))
+    }
+
     /// Get the merge base between two commits.
     ///
     /// # Errors
@@ -464,13 +1282,93 @@ impl Repository {
         Ok(commits)
     }
 
+    /// Find the last commit in `fork..branch` whose changes already landed on
+    /// `upstream` as a single commit, e.g. because a parent PR was squash-merged.
+    ///
+    /// Walks the branch oldest first and compares the patch-id of the cumulative
+    /// diff since the previous match against the patch-ids of the commits in
+    /// `fork..upstream`, so a squash-merged parent and grandparent are matched
+    /// one after the other. Returns `None` when nothing was applied upstream.
+    ///
+    /// # Errors
+    /// Returns error if revwalk or patch-id computation fails.
+    pub fn find_squash_applied(
+        &self,
+        fork: Oid,
+        branch: Oid,
+        upstream: Oid,
+    ) -> Result<Option<Oid>> {
+        let range = format!("{fork}..{upstream}");
+        let log = self.git_output(
+            &[
+                "log",
+                "--no-merges",
+                "--no-color",
+                "--no-ext-diff",
+                "-p",
+                &range,
+            ],
+            None,
+            None,
+        )?;
+        let upstream_ids = self.patch_ids(&log)?;
+        if upstream_ids.is_empty() {
+            return Ok(None);
+        }
+
+        let mut revwalk = self.inner.revwalk()?;
+        revwalk.set_sorting(git2::Sort::TOPOLOGICAL | git2::Sort::REVERSE)?;
+        revwalk.push(branch)?;
+        revwalk.hide(fork)?;
+
+        let mut start = fork;
+        let mut applied = None;
+        for oid in revwalk {
+            let oid = oid?;
+            let diff = self.git_output(
+                &[
+                    "diff",
+                    "--no-color",
+                    "--no-ext-diff",
+                    &start.to_string(),
+                    &oid.to_string(),
+                ],
+                None,
+                None,
+            )?;
+            let matched = self
+                .patch_ids(&diff)?
+                .first()
+                .is_some_and(|id| upstream_ids.contains(id));
+            if matched {
+                start = oid;
+                applied = Some(oid);
+            }
+        }
+
+        Ok(applied)
+    }
+
+    /// Compute stable patch-ids for a `git log -p` or `git diff` output.
+    fn patch_ids(&self, patch: &[u8]) -> Result<Vec<String>> {
+        if patch.is_empty() {
+            return Ok(Vec::new());
+        }
+        let output = self.git_output(&["patch-id", "--stable"], None, Some(patch))?;
+        Ok(String::from_utf8_lossy(&output)
+            .lines()
+            .filter_map(|line| line.split_whitespace().next().map(String::from))
+            .collect())
+    }
+
     // === Reset operations ===
 
     /// Hard reset a branch to a specific commit.
     ///
     /// # Errors
-    /// Returns error if reset fails.
+    /// Returns error if the branch is protected or reset fails.
     pub fn reset_branch(&self, branch_name: &str, target: Oid) -> Result<()> {
+        self.protected.check(branch_name, "reset")?;
         let commit = self.inner.find_commit(target)?;
         let reference_name = format!("refs/heads/{branch_name}");
 
@@ -492,18 +1390,122 @@ impl Repository {
         Ok(())
     }
 
+    /// Soft reset the current branch to a specific commit.
+    ///
+    /// The changes of the commits dropped from the branch stay staged and the
+    /// working tree is untouched. The old tip is recorded in `ORIG_HEAD`, as
+    /// `git reset` does.
+    ///
+    /// # Errors
+    /// Returns error if HEAD is detached, the branch is protected, or the reset fails.
+    pub fn soft_reset(&self, target: Oid) -> Result<()> {
+        let branch = self.current_branch()?;
+        self.protected.check(&branch, "reset")?;
+        let old_head = self.head_commit()?;
+        let commit = self.inner.find_commit(target)?;
+
+        self.inner
+            .reference("ORIG_HEAD", old_head, true, "rung: uncommit")?;
+        self.inner
+            .reset(commit.as_object(), git2::ResetType::Soft, None)?;
+        Ok(())
+    }
+
     // === Signature ===
 
-    /// Get the default signature for commits.
+    /// Get the committer signature for commits.
+    ///
+    /// # Errors
+    /// Returns [`Error::MissingIdentity`] if no committer identity is configured.
+    pub fn signature(&self) -> Result<Signature<'static>> {
+        let identity = self.identity()?;
+        Ok(Signature::now(&identity.name, &identity.email)?)
+    }
+
+    /// Get the author signature for new commits.
+    ///
+    /// # Errors
+    /// Returns [`Error::MissingIdentity`] if no author identity is configured.
+    pub fn author_signature(&self) -> Result<Signature<'static>> {
+        let identity = self.role_identity("AUTHOR", "author")?;
+        Ok(Signature::now(&identity.name, &identity.email)?)
+    }
+
+    /// The committer identity, resolved the way git does: `GIT_COMMITTER_*`,
+    /// then `committer.*`, then `user.*` from the repository's config
+    /// (including per-repo and conditional includes), then `EMAIL`.
     ///
     /// # Errors
-    /// Returns error if git config doesn't have user.name/email.
-    pub fn signature(&self) -> Result<Signature<'_>> {
-        Ok(self.inner.signature()?)
+    /// Returns [`Error::MissingIdentity`] naming the first unset key.
+    pub fn identity(&self) -> Result<Identity> {
+        self.role_identity("COMMITTER", "committer")
+    }
+
+    fn role_identity(&self, env_role: &str, config_role: &str) -> Result<Identity> {
+        let config = self.inner.config()?.snapshot()?;
+        let lookup = |field: &str| {
+            std::env::var(format!("GIT_{env_role}_{}", field.to_uppercase()))
+                .ok()
+                .or_else(|| config.get_string(&format!("{config_role}.{field}")).ok())
+                .or_else(|| config.get_string(&format!("user.{field}")).ok())
+                .filter(|value| !value.trim().is_empty())
+        };
+        let name = lookup("name").ok_or(Error::MissingIdentity("user.name"))?;
+        let email = lookup("email")
+            .or_else(|| std::env::var("EMAIL").ok().filter(|e| !e.trim().is_empty()))
+            .ok_or(Error::MissingIdentity("user.email"))?;
+        Ok(Identity { name, email })
+    }
+
+    /// The commit message template (`commit.template`), if one is set and
+    /// readable.
+    #[must_use]
+    pub fn commit_template(&self) -> Option<String> {
+        let path = self
+            .inner
+            .config()
+            .ok()?
+            .get_string("commit.template")
+            .ok()?;
+        let path = expand_home(&path);
+        // A relative path is relative to the worktree, like git's
+        let path = match self.workdir() {
+            Some(workdir) if path.is_relative() => workdir.join(path),
+            _ => path,
+        };
+        std::fs::read_to_string(path).ok()
     }
 
     // === Rebase operations ===
 
+    /// Set how commit dates are handled by the rebases rung runs.
+    pub const fn set_rebase_dates(&mut self, dates: RebaseDates) {
+        self.rebase_dates = dates;
+    }
+
+    /// Extra `git rebase` arguments for the configured date handling.
+    pub(crate) fn rebase_date_args(&self) -> Vec<&'static str> {
+        self.rebase_dates.args()
+    }
+
+    /// Set the branches rung must never rewrite, force-push, or delete.
+    pub fn set_protected_branches(&mut self, protected: ProtectedBranches) {
+        self.protected = protected;
+    }
+
+    /// The configured protected branch policy.
+    #[must_use]
+    pub const fn protected_branches(&self) -> &ProtectedBranches {
+        &self.protected
+    }
+
+    /// Refuse to rebase the current branch if it is protected.
+    pub(crate) fn check_rebase_allowed(&self) -> Result<()> {
+        // A detached HEAD has no branch to protect
+        self.current_branch()
+            .map_or(Ok(()), |branch| self.protected.check(&branch, "rebase"))
+    }
+
     /// Rebase the current branch onto a target commit.
     ///
     /// Returns `Ok(())` on success, or `Err(RebaseConflict)` if there are conflicts.
@@ -511,10 +1513,13 @@ impl Repository {
     /// # Errors
     /// Returns error if rebase fails or conflicts occur.
     pub fn rebase_onto(&self, target: Oid) -> Result<()> {
+        self.check_rebase_allowed()?;
         let workdir = self.workdir().ok_or(Error::NotARepository)?;
 
         let output = std::process::Command::new("git")
-            .args(["rebase", &target.to_string()])
+            .arg("rebase")
+            .args(self.rebase_date_args())
+            .arg(target.to_string())
             .current_dir(workdir)
             .output()
             .map_err(|e| Error::RebaseFailed(e.to_string()))?;
@@ -542,15 +1547,13 @@ impl Repository {
     /// # Errors
     /// Returns error if rebase fails or conflicts occur.
     pub fn rebase_onto_from(&self, new_base: Oid, old_base: Oid) -> Result<()> {
+        self.check_rebase_allowed()?;
         let workdir = self.workdir().ok_or(Error::NotARepository)?;
 
         let output = std::process::Command::new("git")
-            .args([
-                "rebase",
-                "--onto",
-                &new_base.to_string(),
-                &old_base.to_string(),
-            ])
+            .arg("rebase")
+            .args(self.rebase_date_args())
+            .args(["--onto", &new_base.to_string(), &old_base.to_string()])
             .current_dir(workdir)
             .output()
             .map_err(|e| Error::RebaseFailed(e.to_string()))?;
@@ -583,6 +1586,20 @@ impl Repository {
         Ok(conflicts)
     }
 
+    /// Get the commit an in-progress rebase stopped on (`REBASE_HEAD`).
+    ///
+    /// Returns `None` when no rebase is stopped on a commit.
+    #[must_use]
+    pub fn rebase_stopped_commit(&self) -> Option<Oid> {
+        if !self.is_rebasing() {
+            return None;
+        }
+        self.inner
+            .revparse_single("REBASE_HEAD")
+            .ok()
+            .map(|object| object.id())
+    }
+
     /// Predict conflicts that would occur when rebasing a branch onto a target.
     ///
     /// This simulates the rebase by using `git merge-tree` to check if each
@@ -722,6 +1739,9 @@ impl Repository {
             .map_err(|e| Error::RebaseFailed(e.to_string()))?;
 
         if output.status.success() {
+            // git rewrote the index behind libgit2's back; drop the cached
+            // copy so the conflicts it held don't block the next checkout
+            self.inner.index()?.read(true)?;
             Ok(())
         } else {
             let stderr = String::from_utf8_lossy(&output.stderr);
@@ -756,11 +1776,119 @@ impl Repository {
         Err(Error::RebaseFailed(stderr.to_string()))
     }
 
+    /// Skip rebase stops on commits whose changes are already on the new base.
+    ///
+    /// A change that landed upstream separately (e.g. a cherry-picked hotfix that
+    /// was later edited further) makes the replayed commit conflict even though it
+    /// has nothing left to contribute. While the rebase is stopped on a commit whose
+    /// patch-id matches a commit already in the rebased history, it is skipped with
+    /// `git rebase --skip`. Returns the number of commits skipped.
+    ///
+    /// # Errors
+    /// Returns error if the patch-id comparison fails.
+    pub fn skip_applied_commits(&self) -> Result<usize> {
+        let mut skipped = 0;
+        while self.is_rebasing() {
+            let Ok(stopped) = self.inner.revparse_single("REBASE_HEAD") else {
+                break;
+            };
+            if !self.is_already_applied(stopped.id())? {
+                break;
+            }
+            // A non-zero exit means the next commit stopped too; loop to check it
+            let _ = self.git_output(&["rebase", "--skip"], None, None);
+            skipped += 1;
+        }
+        Ok(skipped)
+    }
+
+    /// Check whether a commit with the same patch-id as `commit` is already in
+    /// HEAD's history since the two diverged.
+    fn is_already_applied(&self, commit: Oid) -> Result<bool> {
+        let head = self.inner.head()?.peel_to_commit()?.id();
+        let fork = self.inner.merge_base(commit, head)?;
+
+        let commit_patch = self.git_output(
+            &[
+                "log",
+                "-1",
+                "--no-color",
+                "--no-ext-diff",
+                "-p",
+                &commit.to_string(),
+            ],
+            None,
+            None,
+        )?;
+        let Some(id) = self.patch_ids(&commit_patch)?.into_iter().next() else {
+            return Ok(false);
+        };
+
+        let range = format!("{fork}..{head}");
+        let upstream = self.git_output(
+            &[
+                "log",
+                "--no-merges",
+                "--no-color",
+                "--no-ext-diff",
+                "-p",
+                &range,
+            ],
+            None,
+            None,
+        )?;
+        Ok(self.patch_ids(&upstream)?.contains(&id))
+    }
+
+    /// List commits of `from..to` that did not make it into `onto..new_tip`
+    /// after a rebase, as `"<short-sha> <summary>"`.
+    ///
+    /// Rebasing preserves author, author date and message, so a commit counts
+    /// as replayed when a commit with the same triple was written on top of `onto`.
+    ///
+    /// # Errors
+    /// Returns error if revwalk fails.
+    pub fn dropped_commits(
+        &self,
+        from: Oid,
+        to: Oid,
+        onto: Oid,
+        new_tip: Oid,
+    ) -> Result<Vec<String>> {
+        let key = |commit: &git2::Commit<'_>| {
+            let author = commit.author();
+            (
+                author.email().unwrap_or_default().to_string(),
+                author.when().seconds(),
+                commit.message().unwrap_or_default().to_string(),
+            )
+        };
+
+        let mut replayed = std::collections::HashSet::new();
+        for oid in self.commits_between(onto, new_tip)? {
+            replayed.insert(key(&self.inner.find_commit(oid)?));
+        }
+
+        let mut dropped = Vec::new();
+        for oid in self.commits_between(from, to)?.into_iter().rev() {
+            let commit = self.inner.find_commit(oid)?;
+            if !replayed.contains(&key(&commit)) {
+                let sha = oid.to_string();
+                dropped.push(format!(
+                    "{} {}",
+                    sha.get(..7).unwrap_or(&sha),
+                    commit.summary().unwrap_or_default()
+                ));
+            }
+        }
+        Ok(dropped)
+    }
+
     // === Remote operations ===
 
     /// Check how a local branch relates to its remote counterpart.
     ///
-    /// Uses the configured upstream if set, otherwise falls back to `origin/<branch>`.
+    /// Uses the configured upstream if set, otherwise falls back to `<remote>/<branch>`.
     /// Compares the local branch tip with the remote tracking branch to determine
     /// if the local branch is ahead, behind, diverged, or in sync with the remote.
     ///
@@ -771,9 +1899,12 @@ impl Repository {
     pub fn remote_divergence(&self, branch: &str) -> Result<RemoteDivergence> {
         let local = self.branch_commit(branch)?;
 
-        // Try to get remote - NoRemote if doesn't exist
+        // Try to get remote - NoRemote if it was never pushed, Gone if it was
         let remote = match self.remote_branch_commit(branch) {
             Ok(oid) => oid,
+            Err(Error::BranchNotFound(_)) if self.branch_upstream_ref(branch).is_some() => {
+                return Ok(RemoteDivergence::Gone);
+            }
             Err(Error::BranchNotFound(_)) => return Ok(RemoteDivergence::NoRemote),
             Err(e) => return Err(e),
         };
@@ -817,53 +1948,129 @@ impl Repository {
         Ok(revwalk.count())
     }
 
-    /// Get the URL of the origin remote.
+    /// Set the remote rung pushes to and fetches from (`origin` by default).
+    pub fn set_remote(&mut self, remote: impl Into<String>) {
+        self.remote = remote.into();
+    }
+
+    /// The remote rung pushes to and fetches from.
+    #[must_use]
+    pub fn remote(&self) -> &str {
+        &self.remote
+    }
+
+    /// Get the URL of rung's remote.
     ///
     /// # Errors
-    /// Returns error if origin remote is not found.
+    /// Returns error if the remote is not found.
     pub fn origin_url(&self) -> Result<String> {
+        self.remote_url(&self.remote)
+    }
+
+    /// Get the URL of a remote.
+    ///
+    /// # Errors
+    /// Returns error if the remote is not found.
+    pub fn remote_url(&self, name: &str) -> Result<String> {
         let remote = self
             .inner
-            .find_remote("origin")
-            .map_err(|_| Error::RemoteNotFound("origin".into()))?;
+            .find_remote(name)
+            .map_err(|_| Error::RemoteNotFound(name.into()))?;
 
         remote
             .url()
             .map(String::from)
-            .ok_or_else(|| Error::RemoteNotFound("origin".into()))
+            .ok_or_else(|| Error::RemoteNotFound(name.into()))
     }
 
     /// Detect the default branch from the remote's HEAD.
     ///
-    /// Checks `refs/remotes/origin/HEAD` to determine the remote's default branch.
+    /// Checks `refs/remotes/<remote>/HEAD` to determine the remote's default branch.
     /// Returns `None` if the remote HEAD is not set (e.g., fresh clone without `--set-upstream`).
     #[must_use]
     pub fn detect_default_branch(&self) -> Option<String> {
-        // Try to resolve refs/remotes/origin/HEAD which points to the default branch
-        let reference = self.inner.find_reference("refs/remotes/origin/HEAD").ok()?;
+        // Try to resolve refs/remotes/<remote>/HEAD which points to the default branch
+        let prefix = format!("refs/remotes/{}/", self.remote);
+        let reference = self.inner.find_reference(&format!("{prefix}HEAD")).ok()?;
 
         // Resolve the symbolic reference to get the actual branch
         let resolved = reference.resolve().ok()?;
         let name = resolved.name()?;
 
         // Extract branch name from "refs/remotes/origin/main" -> "main"
-        name.strip_prefix("refs/remotes/origin/").map(String::from)
+        name.strip_prefix(&prefix).map(String::from)
+    }
+
+    /// Push a branch to the remote.
+    ///
+    /// # Errors
+    /// Returns error if push fails, or if `force` is set for a protected branch.
+    pub fn push(&self, branch: &str, force: bool) -> Result<()> {
+        if force {
+            self.protected.check(branch, "force-push")?;
+        }
+        let workdir = self.workdir().ok_or(Error::NotARepository)?;
+
+        let mut args = vec!["push", "-u", &self.remote, branch];
+        if force {
+            args.insert(1, "--force-with-lease");
+        }
+
+        let output = std::process::Command::new("git")
+            .args(&args)
+            .current_dir(workdir)
+            .output()
+            .map_err(|e| Error::PushFailed(e.to_string()))?;
+
+        if output.status.success() {
+            Ok(())
+        } else {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            Err(Error::PushFailed(stderr.to_string()))
+        }
+    }
+
+    /// Point each branch without an upstream at its remote tracking branch,
+    /// so plain `git push` and `git status` work alongside rung.
+    ///
+    /// Branches that already have an upstream, or that aren't on rung's
+    /// remote yet, are left alone. Returns the branches that were set up.
+    ///
+    /// # Errors
+    /// Returns error if the tracking config can't be written.
+    pub fn set_upstreams<S: AsRef<str>>(&self, branches: &[S]) -> Result<Vec<String>> {
+        let mut configured = Vec::new();
+        for name in branches.iter().map(AsRef::as_ref) {
+            let Ok(mut branch) = self.inner.find_branch(name, BranchType::Local) else {
+                continue;
+            };
+            if self.branch_upstream_ref(name).is_some() {
+                continue;
+            }
+            let tracking = format!("{}/{name}", self.remote);
+            if self
+                .inner
+                .find_branch(&tracking, BranchType::Remote)
+                .is_err()
+            {
+                continue;
+            }
+            branch.set_upstream(Some(&tracking))?;
+            configured.push(name.to_string());
+        }
+        Ok(configured)
     }
 
-    /// Push a branch to the remote.
+    /// Delete a branch on rung's remote.
     ///
     /// # Errors
-    /// Returns error if push fails.
-    pub fn push(&self, branch: &str, force: bool) -> Result<()> {
+    /// Returns error if the push fails, or if the branch is protected.
+    pub fn delete_remote_branch(&self, branch: &str) -> Result<()> {
+        self.protected.check(branch, "delete")?;
         let workdir = self.workdir().ok_or(Error::NotARepository)?;
 
-        let mut args = vec!["push", "-u", "origin", branch];
-        if force {
-            args.insert(1, "--force-with-lease");
-        }
-
         let output = std::process::Command::new("git")
-            .args(&args)
+            .args(["push", &self.remote, "--delete", branch])
             .current_dir(workdir)
             .output()
             .map_err(|e| Error::PushFailed(e.to_string()))?;
@@ -876,7 +2083,40 @@ impl Repository {
         }
     }
 
-    /// Fetch all remote tracking refs from origin.
+    /// Names of the configured remotes.
+    ///
+    /// # Errors
+    /// Returns error if the remotes can't be read from the config.
+    pub fn remote_names(&self) -> Result<Vec<String>> {
+        Ok(self
+            .inner
+            .remotes()?
+            .iter()
+            .flatten()
+            .map(String::from)
+            .collect())
+    }
+
+    /// List the branches that currently exist on rung's remote.
+    ///
+    /// Queries the remote directly (`git ls-remote --heads`), so it also sees
+    /// branches deleted since the last fetch.
+    ///
+    /// # Errors
+    /// Returns error if the remote cannot be reached.
+    pub fn remote_branches(&self) -> Result<Vec<String>> {
+        let output = self
+            .git_output(&["ls-remote", "--heads", &self.remote], None, None)
+            .map_err(|e| Error::FetchFailed(e.to_string()))?;
+        Ok(String::from_utf8_lossy(&output)
+            .lines()
+            .filter_map(|line| line.split('\t').nth(1))
+            .filter_map(|name| name.strip_prefix("refs/heads/"))
+            .map(String::from)
+            .collect())
+    }
+
+    /// Fetch all remote tracking refs from rung's remote.
     ///
     /// # Errors
     /// Returns error if fetch fails.
@@ -884,7 +2124,7 @@ impl Repository {
         let workdir = self.workdir().ok_or(Error::NotARepository)?;
 
         let output = std::process::Command::new("git")
-            .args(["fetch", "origin", "--prune"])
+            .args(["fetch", &self.remote, "--prune"])
             .current_dir(workdir)
             .output()
             .map_err(|e| Error::FetchFailed(e.to_string()))?;
@@ -897,7 +2137,27 @@ impl Repository {
         }
     }
 
-    /// Fetch a branch from origin.
+    /// Delete remote tracking refs whose branch no longer exists on rung's
+    /// remote.
+    ///
+    /// Returns the names of the pruned branches (without the `<remote>/` prefix).
+    ///
+    /// # Errors
+    /// Returns error if the remote cannot be reached.
+    pub fn prune_remote(&self) -> Result<Vec<String>> {
+        let output = self
+            .git_output(&["remote", "prune", &self.remote], None, None)
+            .map_err(|e| Error::FetchFailed(e.to_string()))?;
+        let prefix = format!("{}/", self.remote);
+        Ok(String::from_utf8_lossy(&output)
+            .lines()
+            .filter_map(|line| line.split_once("[pruned] "))
+            .map(|(_, name)| name.trim())
+            .map(|name| name.strip_prefix(&prefix).unwrap_or(name).to_string())
+            .collect())
+    }
+
+    /// Fetch a branch from rung's remote.
     ///
     /// # Errors
     /// Returns error if fetch fails.
@@ -905,10 +2165,10 @@ impl Repository {
         let workdir = self.workdir().ok_or(Error::NotARepository)?;
 
         // Use refspec to update both remote tracking branch and local branch
-        // Format: origin/branch:refs/heads/branch
+        // Format: branch:refs/heads/branch
         let refspec = format!("{branch}:refs/heads/{branch}");
         let output = std::process::Command::new("git")
-            .args(["fetch", "origin", &refspec])
+            .args(["fetch", &self.remote, &refspec])
             .current_dir(workdir)
             .output()
             .map_err(|e| Error::FetchFailed(e.to_string()))?;
@@ -921,9 +2181,9 @@ impl Repository {
         }
     }
 
-    /// Pull (fast-forward only) the current branch from origin.
+    /// Pull (fast-forward only) the current branch from its upstream.
     ///
-    /// This fetches and merges `origin/<branch>` into the current branch,
+    /// This fetches and merges the upstream into the current branch,
     /// but only if it can be fast-forwarded.
     ///
     /// # Errors
@@ -983,6 +2243,10 @@ impl GitOps for Repository {
         Self::is_rebasing(self)
     }
 
+    fn rebasing_branch(&self) -> Option<String> {
+        Self::rebasing_branch(self)
+    }
+
     fn branch_exists(&self, name: &str) -> bool {
         Self::branch_exists(self, name)
     }
@@ -1003,6 +2267,10 @@ impl GitOps for Repository {
         Self::list_branches(self)
     }
 
+    fn branch_description(&self, branch: &str) -> Option<String> {
+        Self::branch_description(self, branch)
+    }
+
     fn branch_commit(&self, branch: &str) -> Result<Oid> {
         Self::branch_commit(self, branch)
     }
@@ -1023,10 +2291,22 @@ impl GitOps for Repository {
         Self::commits_between(self, from, to)
     }
 
+    fn commit_diff(&self, commit: Oid, format: DiffFormat) -> Result<String> {
+        Self::commit_diff(self, commit, format)
+    }
+
     fn count_commits_between(&self, from: Oid, to: Oid) -> Result<usize> {
         Self::count_commits_between(self, from, to)
     }
 
+    fn find_squash_applied(&self, fork: Oid, branch: Oid, upstream: Oid) -> Result<Option<Oid>> {
+        Self::find_squash_applied(self, fork, branch, upstream)
+    }
+
+    fn dropped_commits(&self, from: Oid, to: Oid, onto: Oid, new_tip: Oid) -> Result<Vec<String>> {
+        Self::dropped_commits(self, from, to, onto, new_tip)
+    }
+
     fn is_clean(&self) -> Result<bool> {
         Self::is_clean(self)
     }
@@ -1075,6 +2355,22 @@ impl GitOps for Repository {
         Self::rebase_continue(self)
     }
 
+    fn skip_applied_commits(&self) -> Result<usize> {
+        Self::skip_applied_commits(self)
+    }
+
+    fn identity(&self) -> Result<Identity> {
+        Self::identity(self)
+    }
+
+    fn signing_config(&self) -> Result<SigningConfig> {
+        Self::signing_config(self)
+    }
+
+    fn is_commit_signed(&self, commit: Oid) -> Result<bool> {
+        Self::is_commit_signed(self, commit)
+    }
+
     fn origin_url(&self) -> Result<String> {
         Self::origin_url(self)
     }
@@ -1091,6 +2387,10 @@ impl GitOps for Repository {
         Self::push(self, branch, force)
     }
 
+    fn remote_branches(&self) -> Result<Vec<String>> {
+        Self::remote_branches(self)
+    }
+
     fn fetch_all(&self) -> Result<()> {
         Self::fetch_all(self)
     }
@@ -1132,10 +2432,112 @@ mod tests {
             .unwrap();
         drop(tree);
 
-        let wrapped = Repository { inner: repo };
+        let wrapped = Repository {
+            inner: repo,
+            rebase_dates: RebaseDates::default(),
+            protected: ProtectedBranches::default(),
+            remote: DEFAULT_REMOTE.to_string(),
+        };
         (temp, wrapped)
     }
 
+    #[test]
+    fn test_configured_remote() {
+        let (_temp, mut repo) = init_test_repo();
+        let remote_dir = TempDir::new().unwrap();
+        git2::Repository::init_bare(remote_dir.path()).unwrap();
+        repo.inner
+            .remote("upstream", remote_dir.path().to_str().unwrap())
+            .unwrap();
+        repo.create_branch("feature").unwrap();
+
+        // No `origin` to fall back on
+        assert!(repo.push("feature", false).is_err());
+
+        repo.set_remote("upstream");
+        repo.push("feature", false).unwrap();
+        assert_eq!(repo.remote_branches().unwrap(), vec!["feature"]);
+        assert_eq!(
+            repo.origin_url().unwrap(),
+            remote_dir.path().to_str().unwrap()
+        );
+        assert_eq!(
+            repo.remote_divergence("feature").unwrap(),
+            RemoteDivergence::InSync
+        );
+    }
+
+    #[test]
+    fn test_remote_divergence_gone_after_prune() {
+        let (temp, repo) = init_test_repo();
+        let remote_dir = TempDir::new().unwrap();
+        git2::Repository::init_bare(remote_dir.path()).unwrap();
+        repo.inner
+            .remote("origin", remote_dir.path().to_str().unwrap())
+            .unwrap();
+
+        repo.create_branch("feature").unwrap();
+        assert_eq!(
+            repo.remote_divergence("feature").unwrap(),
+            RemoteDivergence::NoRemote
+        );
+
+        repo.push("feature", false).unwrap();
+        assert_eq!(
+            repo.remote_divergence("feature").unwrap(),
+            RemoteDivergence::InSync
+        );
+
+        // Delete the branch on the remote; the stale tracking ref survives until pruned
+        std::process::Command::new("git")
+            .args(["push", "origin", "--delete", "feature"])
+            .current_dir(temp.path())
+            .output()
+            .unwrap();
+        std::process::Command::new("git")
+            .args(["update-ref", "refs/remotes/origin/feature", "HEAD"])
+            .current_dir(temp.path())
+            .output()
+            .unwrap();
+
+        assert_eq!(repo.prune_remote().unwrap(), vec!["feature"]);
+        assert_eq!(
+            repo.remote_divergence("feature").unwrap(),
+            RemoteDivergence::Gone
+        );
+    }
+
+    #[test]
+    fn test_set_upstreams() {
+        let (temp, repo) = init_test_repo();
+        let remote_dir = TempDir::new().unwrap();
+        git2::Repository::init_bare(remote_dir.path()).unwrap();
+        repo.inner
+            .remote("origin", remote_dir.path().to_str().unwrap())
+            .unwrap();
+
+        for name in ["pushed", "tracked", "local"] {
+            repo.create_branch(name).unwrap();
+        }
+        repo.push("tracked", false).unwrap();
+        // Pushed outside rung, without -u
+        std::process::Command::new("git")
+            .args(["push", "origin", "pushed"])
+            .current_dir(temp.path())
+            .output()
+            .unwrap();
+        assert_eq!(repo.branch_upstream_ref("pushed"), None);
+
+        let branches = ["pushed", "tracked", "local", "missing"];
+        assert_eq!(repo.set_upstreams(&branches).unwrap(), vec!["pushed"]);
+        assert_eq!(
+            repo.branch_upstream_ref("pushed").as_deref(),
+            Some("refs/remotes/origin/pushed")
+        );
+        assert_eq!(repo.branch_upstream_ref("local"), None);
+        assert!(repo.set_upstreams(&branches).unwrap().is_empty());
+    }
+
     #[test]
     fn test_current_branch() {
         let (_temp, repo) = init_test_repo();
@@ -1144,6 +2546,140 @@ mod tests {
         assert!(branch == "main" || branch == "master");
     }
 
+    #[test]
+    fn test_common_dir_of_linked_worktree() {
+        let (temp, repo) = init_test_repo();
+        let wt_path = temp.path().join("wt");
+        repo.inner.worktree("wt", &wt_path, None).unwrap();
+
+        let worktree = Repository::open(&wt_path).unwrap();
+        assert_ne!(worktree.git_dir(), repo.git_dir());
+        assert_eq!(
+            worktree.common_dir().canonicalize().unwrap(),
+            repo.common_dir().canonicalize().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_signing_config() {
+        let (_temp, repo) = init_test_repo();
+        let config = repo.signing_config().unwrap();
+        assert!(!config.enabled);
+        assert_eq!(config.format, "openpgp");
+        assert_eq!(config.program, "gpg");
+
+        let mut git_config = repo.inner.config().unwrap();
+        git_config.set_bool("commit.gpgsign", true).unwrap();
+        git_config.set_str("gpg.format", "ssh").unwrap();
+        git_config
+            .set_str("user.signingkey", "~/.ssh/id_ed25519.pub")
+            .unwrap();
+
+        let config = repo.signing_config().unwrap();
+        assert!(config.enabled);
+        assert_eq!(config.format, "ssh");
+        assert_eq!(config.program, "ssh-keygen");
+        assert_eq!(config.key.as_deref(), Some("~/.ssh/id_ed25519.pub"));
+
+        let head = repo.inner.head().unwrap().target().unwrap();
+        assert!(!repo.is_commit_signed(head).unwrap());
+    }
+
+    #[test]
+    fn test_identity_overrides() {
+        let (temp, repo) = init_test_repo();
+        let mut git_config = repo.inner.config().unwrap();
+        git_config.set_str("user.name", "Repo User").unwrap();
+        git_config
+            .set_str("user.email", "repo@example.com")
+            .unwrap();
+        git_config
+            .set_str("committer.name", "Repo Committer")
+            .unwrap();
+
+        let committer = repo.identity().unwrap();
+        assert_eq!(committer.name, "Repo Committer");
+        assert_eq!(committer.email, "repo@example.com");
+        let author = repo.author_signature().unwrap();
+        assert_eq!(author.name(), Some("Repo User"));
+
+        let oid = repo.create_commit("Identity").unwrap();
+        let commit = repo.inner.find_commit(oid).unwrap();
+        assert_eq!(commit.author().name(), Some("Repo User"));
+        assert_eq!(commit.committer().name(), Some("Repo Committer"));
+
+        assert!(repo.commit_template().is_none());
+        fs::write(temp.path().join("template.txt"), "Summary\n\n# Why?\n").unwrap();
+        git_config
+            .set_str("commit.template", "template.txt")
+            .unwrap();
+        assert_eq!(
+            repo.commit_template().map(|t| cleanup_message(&t)),
+            Some("Summary".to_string())
+        );
+    }
+
+    #[test]
+    fn test_operation_in_progress() {
+        let (_temp, repo) = init_test_repo();
+        assert_eq!(repo.operation_in_progress(), None);
+        assert!(repo.require_no_operation().is_ok());
+
+        // A leftover MERGE_HEAD is what git uses to mark an unfinished merge
+        let head = repo.inner.head().unwrap().target().unwrap();
+        fs::write(repo.git_dir().join("MERGE_HEAD"), format!("{head}\n")).unwrap();
+        assert_eq!(repo.operation_in_progress(), Some(GitOperation::Merge));
+
+        let err = repo.require_no_operation().unwrap_err().to_string();
+        assert!(err.contains("git merge is in progress"));
+        assert!(err.contains("git merge --abort"));
+    }
+
+    #[test]
+    fn test_protected_branch_patterns() {
+        let protected = ProtectedBranches::new(["main", "release/*", "hotfix/**", "v?"]);
+
+        assert!(protected.is_protected("main"));
+        assert!(!protected.is_protected("main2"));
+        assert_eq!(protected.matching("release/1.0"), Some("release/*"));
+        assert!(!protected.is_protected("release/1.0/fix"));
+        assert!(protected.is_protected("hotfix/a/b"));
+        assert!(protected.is_protected("v1"));
+        assert!(!protected.is_protected("v10"));
+        assert!(!ProtectedBranches::default().is_protected("main"));
+    }
+
+    #[test]
+    fn test_protected_branch_enforced() {
+        let (temp, mut repo) = init_test_repo();
+        let main_branch = repo.current_branch().unwrap();
+        let base = repo.branch_commit(&main_branch).unwrap();
+        repo.create_branch("release/1").unwrap();
+        repo.set_protected_branches(ProtectedBranches::new(["release/*"]));
+
+        let err = repo.delete_branch("release/1").unwrap_err().to_string();
+        assert!(err.contains("refusing to delete protected branch 'release/1'"));
+        assert!(err.contains("matches 'release/*'"));
+        assert!(repo.reset_branch("release/1", base).is_err());
+        assert!(repo.push("release/1", true).is_err());
+
+        repo.checkout("release/1").unwrap();
+        let err = repo.rebase_onto(base).unwrap_err();
+        assert!(matches!(
+            err,
+            Error::ProtectedBranch {
+                action: "rebase",
+                ..
+            }
+        ));
+
+        // Unprotected branches are unaffected
+        repo.checkout(&main_branch).unwrap();
+        create_commit_with_file(&temp, &repo, "a.txt", "a", "A");
+        repo.create_branch("feature").unwrap();
+        repo.delete_branch("feature").unwrap();
+    }
+
     #[test]
     fn test_create_and_checkout_branch() {
         let (_temp, repo) = init_test_repo();
@@ -1184,6 +2720,51 @@ mod tests {
         assert!(!repo.is_clean().unwrap());
     }
 
+    #[cfg(unix)]
+    #[test]
+    fn test_is_clean_with_fsmonitor_hook() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let (temp, repo) = init_test_repo();
+
+        fs::write(temp.path().join("test.txt"), "initial").unwrap();
+        {
+            let mut index = repo.inner.index().unwrap();
+            index.add_path(std::path::Path::new("test.txt")).unwrap();
+            index.write().unwrap();
+            let tree_id = index.write_tree().unwrap();
+            let tree = repo.inner.find_tree(tree_id).unwrap();
+            let parent = repo.inner.head().unwrap().peel_to_commit().unwrap();
+            let sig = git2::Signature::now("Test", "test@example.com").unwrap();
+            repo.inner
+                .commit(Some("HEAD"), &sig, &sig, "Add test file", &tree, &[&parent])
+                .unwrap();
+        }
+
+        // A hook that always fails makes git fall back to a full scan,
+        // which still exercises the CLI path
+        let hook = temp.path().join(".git").join("fsmonitor-hook");
+        fs::write(&hook, "#!/bin/sh\nexit 1\n").unwrap();
+        fs::set_permissions(&hook, fs::Permissions::from_mode(0o755)).unwrap();
+        repo.inner
+            .config()
+            .unwrap()
+            .set_str("core.fsmonitor", hook.to_str().unwrap())
+            .unwrap();
+
+        assert!(repo.fsmonitor_enabled());
+        assert_eq!(repo.is_clean_via_cli(), Some(true));
+        assert!(repo.is_clean().unwrap());
+
+        // Untracked files don't count
+        fs::write(temp.path().join("untracked.txt"), "new").unwrap();
+        assert!(repo.is_clean().unwrap());
+
+        fs::write(temp.path().join("test.txt"), "modified").unwrap();
+        assert_eq!(repo.is_clean_via_cli(), Some(false));
+        assert!(!repo.is_clean().unwrap());
+    }
+
     #[test]
     fn test_list_branches() {
         let (_temp, repo) = init_test_repo();
@@ -1265,6 +2846,107 @@ mod tests {
         assert!(repo.is_clean().unwrap());
     }
 
+    #[test]
+    fn test_staged_files() {
+        let (temp, repo) = init_test_repo();
+        fs::write(temp.path().join("staged.txt"), "a").unwrap();
+        fs::write(temp.path().join("untracked.txt"), "b").unwrap();
+        std::process::Command::new("git")
+            .args(["add", "staged.txt"])
+            .current_dir(temp.path())
+            .output()
+            .unwrap();
+
+        assert_eq!(repo.staged_files().unwrap(), vec!["staged.txt"]);
+    }
+
+    #[test]
+    fn test_worktree_status() {
+        let (temp, repo) = init_test_repo();
+        let git = |args: &[&str]| {
+            std::process::Command::new("git")
+                .args(args)
+                .current_dir(temp.path())
+                .output()
+                .unwrap();
+        };
+        fs::write(temp.path().join("tracked.txt"), "a").unwrap();
+        git(&["add", "tracked.txt"]);
+        git(&["commit", "-m", "Add tracked"]);
+        assert!(repo.worktree_status().unwrap().is_clean());
+
+        fs::write(temp.path().join("tracked.txt"), "changed").unwrap();
+        fs::write(temp.path().join("staged.txt"), "a").unwrap();
+        fs::write(temp.path().join("untracked.txt"), "b").unwrap();
+        git(&["add", "staged.txt"]);
+
+        assert_eq!(
+            repo.worktree_status().unwrap(),
+            WorktreeStatus {
+                staged: 1,
+                modified: 1,
+                untracked: 1,
+            }
+        );
+    }
+
+    #[test]
+    fn test_https_credentials() {
+        let (temp, repo) = init_test_repo();
+        let mut config = git2::Repository::open(temp.path())
+            .unwrap()
+            .config()
+            .unwrap();
+
+        // The empty helper also masks any helper from the global config
+        config.set_str("credential.helper", "").unwrap();
+        config
+            .set_str(
+                "http.https://github.com/.extraheader",
+                "AUTHORIZATION: basic xyz",
+            )
+            .unwrap();
+        assert_eq!(
+            repo.https_credentials().as_deref(),
+            Some("http.extraheader")
+        );
+
+        config.set_str("credential.helper", "store").unwrap();
+        assert_eq!(
+            repo.https_credentials().as_deref(),
+            Some("credential helper 'store'")
+        );
+    }
+
+    #[test]
+    fn test_commit_diff() {
+        let (temp, repo) = init_test_repo();
+        fs::write(temp.path().join("file.txt"), "one\ntwo\n").unwrap();
+        std::process::Command::new("git")
+            .args(["add", "file.txt"])
+            .current_dir(temp.path())
+            .output()
+            .unwrap();
+        let commit = repo.create_commit("Add file").unwrap();
+
+        let patch = repo.commit_diff(commit, DiffFormat::Patch).unwrap();
+        assert!(patch.contains("diff --git a/file.txt b/file.txt"));
+        assert!(patch.contains("+one\n+two\n"));
+
+        let stat = repo.commit_diff(commit, DiffFormat::Stat).unwrap();
+        assert!(stat.contains("file.txt | 2 ++"));
+        assert!(stat.contains("1 file changed, 2 insertions(+)"));
+    }
+
+    #[test]
+    fn test_edit_message_strips_comments() {
+        let (_temp, repo) = init_test_repo();
+        let editor = "printf 'Add feature\\n\\nDetails\\n# ignored\\n' >>";
+
+        let message = repo.edit_message_with(editor, "\n# Lines starting with '#' are ignored\n");
+        assert_eq!(message.unwrap(), "Add feature\n\nDetails");
+    }
+
     // === Conflict Prediction Tests ===
 
     /// Helper to create a commit with a specific file content
@@ -1300,6 +2982,48 @@ mod tests {
             .unwrap();
     }
 
+    #[test]
+    fn test_rebase_dates_committer_date_from_author_date() {
+        let (temp, mut repo) = init_test_repo();
+        let main_branch = repo.current_branch().unwrap();
+
+        repo.create_branch("feature").unwrap();
+        repo.checkout("feature").unwrap();
+
+        // Commit on feature with an author date well in the past
+        fs::write(temp.path().join("feature.txt"), "feature").unwrap();
+        repo.stage_all().unwrap();
+        let old = git2::Signature::new(
+            "Test",
+            "test@example.com",
+            &git2::Time::new(1_000_000_000, 0),
+        )
+        .unwrap();
+        {
+            let mut index = repo.inner.index().unwrap();
+            let tree = repo.inner.find_tree(index.write_tree().unwrap()).unwrap();
+            let parent = repo.inner.head().unwrap().peel_to_commit().unwrap();
+            repo.inner
+                .commit(Some("HEAD"), &old, &old, "Feature", &tree, &[&parent])
+                .unwrap();
+        }
+
+        repo.checkout(&main_branch).unwrap();
+        let main_tip = create_commit_with_file(&temp, &repo, "main.txt", "main", "Main");
+        repo.checkout("feature").unwrap();
+
+        repo.set_rebase_dates(RebaseDates {
+            preserve_author_date: true,
+            reset_committer_date: false,
+        });
+        repo.rebase_onto(main_tip).unwrap();
+
+        let head = repo.inner.head().unwrap().peel_to_commit().unwrap();
+        assert_eq!(head.parent_id(0).unwrap(), main_tip);
+        assert_eq!(head.author().when().seconds(), 1_000_000_000);
+        assert_eq!(head.committer().when().seconds(), 1_000_000_000);
+    }
+
     #[test]
     fn test_predict_rebase_conflicts_no_conflicts() {
         let (temp, repo) = init_test_repo();
@@ -1391,6 +3115,52 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_rebase_stopped_commit() {
+        let (temp, repo) = init_test_repo();
+        let main_branch = repo.current_branch().unwrap();
+        create_commit_with_file(&temp, &repo, "shared.txt", "original\n", "Initial shared");
+
+        repo.create_branch("feature").unwrap();
+        repo.checkout("feature").unwrap();
+        let feature_commit =
+            create_commit_with_file(&temp, &repo, "shared.txt", "feature\n", "Feature change");
+
+        force_checkout(&repo, &main_branch);
+        let main_tip = create_commit_with_file(&temp, &repo, "shared.txt", "main\n", "Main change");
+
+        assert_eq!(repo.rebase_stopped_commit(), None);
+
+        repo.checkout("feature").unwrap();
+        assert!(matches!(
+            repo.rebase_onto(main_tip),
+            Err(Error::RebaseConflict(_))
+        ));
+        assert_eq!(repo.rebase_stopped_commit(), Some(feature_commit));
+        assert_eq!(repo.rebasing_branch().as_deref(), Some("feature"));
+
+        repo.rebase_abort().unwrap();
+        assert_eq!(repo.rebase_stopped_commit(), None);
+        assert_eq!(repo.rebasing_branch(), None);
+    }
+
+    #[test]
+    fn test_soft_reset_keeps_changes_staged() {
+        let (temp, repo) = init_test_repo();
+        let base = create_commit_with_file(&temp, &repo, "file.txt", "one\n", "One");
+        let tip = create_commit_with_file(&temp, &repo, "file.txt", "two\n", "Two");
+
+        repo.soft_reset(base).unwrap();
+
+        assert_eq!(repo.head_commit().unwrap(), base);
+        assert_eq!(repo.inner().revparse_single("ORIG_HEAD").unwrap().id(), tip);
+        assert_eq!(
+            fs::read_to_string(temp.path().join("file.txt")).unwrap(),
+            "two\n"
+        );
+        assert!(!repo.is_clean().unwrap());
+    }
+
     #[test]
     fn test_predict_rebase_conflicts_multiple_commits() {
         let (temp, repo) = init_test_repo();
@@ -0,0 +1,401 @@
+//! Test doubles for code written against [`GitOps`].
+//!
+//! Enabled by the `test-utils` feature. [`MockGitOps`] keeps branches and
+//! working-tree flags in memory, so services can be unit tested without a
+//! real repository.
+
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+use crate::{GitOps, Identity, Oid, RemoteDivergence, Result, SigningConfig};
+
+/// Mock implementation of `GitOps` for testing.
+pub struct MockGitOps {
+    pub current_branch: RefCell<String>,
+    pub branches: RefCell<HashMap<String, Oid>>,
+    pub branch_exists_map: RefCell<HashMap<String, bool>>,
+    pub remote_divergence_map: RefCell<HashMap<String, RemoteDivergence>>,
+    pub is_clean: RefCell<bool>,
+    pub is_rebasing: RefCell<bool>,
+    pub push_results: RefCell<HashMap<String, bool>>,
+    pub has_staged_changes: RefCell<bool>,
+    pub rebase_should_fail: RefCell<bool>,
+    pub remote_branches: RefCell<Option<Vec<String>>>,
+    pub branch_descriptions: RefCell<HashMap<String, String>>,
+    pub identity: RefCell<Option<Identity>>,
+    pub signing_config: RefCell<SigningConfig>,
+    pub signed_commits: RefCell<HashSet<Oid>>,
+}
+
+impl Default for MockGitOps {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MockGitOps {
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            current_branch: RefCell::new("main".to_string()),
+            branches: RefCell::new(HashMap::new()),
+            branch_exists_map: RefCell::new(HashMap::new()),
+            remote_divergence_map: RefCell::new(HashMap::new()),
+            is_clean: RefCell::new(true),
+            is_rebasing: RefCell::new(false),
+            push_results: RefCell::new(HashMap::new()),
+            has_staged_changes: RefCell::new(false),
+            rebase_should_fail: RefCell::new(false),
+            remote_branches: RefCell::new(None),
+            branch_descriptions: RefCell::new(HashMap::new()),
+            identity: RefCell::new(Some(Identity {
+                name: "Test".to_string(),
+                email: "test@example.com".to_string(),
+            })),
+            signing_config: RefCell::new(SigningConfig::default()),
+            signed_commits: RefCell::new(HashSet::new()),
+        }
+    }
+
+    #[must_use]
+    pub fn with_staged_changes(self, has_staged: bool) -> Self {
+        *self.has_staged_changes.borrow_mut() = has_staged;
+        self
+    }
+
+    #[must_use]
+    pub fn with_clean(self, clean: bool) -> Self {
+        *self.is_clean.borrow_mut() = clean;
+        self
+    }
+
+    #[must_use]
+    pub fn with_branch(self, name: &str, oid: Oid) -> Self {
+        self.branches.borrow_mut().insert(name.to_string(), oid);
+        self.branch_exists_map
+            .borrow_mut()
+            .insert(name.to_string(), true);
+        self
+    }
+
+    #[must_use]
+    pub fn with_current_branch(self, name: &str) -> Self {
+        *self.current_branch.borrow_mut() = name.to_string();
+        self
+    }
+
+    #[must_use]
+    pub fn with_push_result(self, branch: &str, success: bool) -> Self {
+        self.push_results
+            .borrow_mut()
+            .insert(branch.to_string(), success);
+        self
+    }
+
+    /// Set the branches that exist on the remote (defaults to all local branches).
+    #[must_use]
+    pub fn with_remote_branches(self, branches: &[&str]) -> Self {
+        *self.remote_branches.borrow_mut() =
+            Some(branches.iter().map(ToString::to_string).collect());
+        self
+    }
+
+    /// Set a branch's description.
+    #[must_use]
+    pub fn with_branch_description(self, branch: &str, description: &str) -> Self {
+        self.branch_descriptions
+            .borrow_mut()
+            .insert(branch.to_string(), description.to_string());
+        self
+    }
+
+    /// Set the configured identity; `None` for a repo without one.
+    #[must_use]
+    pub fn with_identity(self, identity: Option<Identity>) -> Self {
+        *self.identity.borrow_mut() = identity;
+        self
+    }
+
+    #[must_use]
+    pub fn with_signing_config(self, config: SigningConfig) -> Self {
+        *self.signing_config.borrow_mut() = config;
+        self
+    }
+
+    /// Mark a commit as carrying a signature.
+    #[must_use]
+    pub fn with_signed_commit(self, oid: Oid) -> Self {
+        self.signed_commits.borrow_mut().insert(oid);
+        self
+    }
+
+    #[must_use]
+    pub fn with_rebase_failure(self) -> Self {
+        *self.rebase_should_fail.borrow_mut() = true;
+        self
+    }
+}
+
+impl GitOps for MockGitOps {
+    fn workdir(&self) -> Option<&Path> {
+        None
+    }
+
+    fn current_branch(&self) -> Result<String> {
+        Ok(self.current_branch.borrow().clone())
+    }
+
+    fn head_detached(&self) -> Result<bool> {
+        Ok(false)
+    }
+
+    fn is_rebasing(&self) -> bool {
+        *self.is_rebasing.borrow()
+    }
+
+    fn rebasing_branch(&self) -> Option<String> {
+        self.is_rebasing
+            .borrow()
+            .then(|| self.current_branch.borrow().clone())
+    }
+
+    fn branch_exists(&self, name: &str) -> bool {
+        self.branch_exists_map
+            .borrow()
+            .get(name)
+            .copied()
+            .unwrap_or(false)
+    }
+
+    fn create_branch(&self, name: &str) -> Result<Oid> {
+        let oid = Oid::zero();
+        self.branches.borrow_mut().insert(name.to_string(), oid);
+        self.branch_exists_map
+            .borrow_mut()
+            .insert(name.to_string(), true);
+        Ok(oid)
+    }
+
+    fn checkout(&self, branch: &str) -> Result<()> {
+        *self.current_branch.borrow_mut() = branch.to_string();
+        Ok(())
+    }
+
+    fn delete_branch(&self, name: &str) -> Result<()> {
+        self.branches.borrow_mut().remove(name);
+        self.branch_exists_map.borrow_mut().remove(name);
+        Ok(())
+    }
+
+    fn list_branches(&self) -> Result<Vec<String>> {
+        let mut branches: Vec<String> = self.branches.borrow().keys().cloned().collect();
+        branches.sort();
+        Ok(branches)
+    }
+
+    fn branch_description(&self, branch: &str) -> Option<String> {
+        self.branch_descriptions.borrow().get(branch).cloned()
+    }
+
+    fn branch_commit(&self, branch: &str) -> Result<Oid> {
+        self.branches
+            .borrow()
+            .get(branch)
+            .copied()
+            .ok_or_else(|| crate::Error::BranchNotFound(branch.to_string()))
+    }
+
+    fn remote_branch_commit(&self, branch: &str) -> Result<Oid> {
+        self.branch_commit(branch)
+    }
+
+    fn branch_commit_message(&self, _branch: &str) -> Result<String> {
+        Ok("Test commit message".to_string())
+    }
+
+    fn merge_base(&self, one: Oid, _two: Oid) -> Result<Oid> {
+        Ok(one)
+    }
+
+    fn commits_between(&self, _from: Oid, _to: Oid) -> Result<Vec<Oid>> {
+        Ok(vec![])
+    }
+
+    fn commit_diff(&self, _commit: Oid, _format: crate::DiffFormat) -> Result<String> {
+        Ok(String::new())
+    }
+
+    fn count_commits_between(&self, _from: Oid, _to: Oid) -> Result<usize> {
+        Ok(0)
+    }
+
+    fn find_squash_applied(&self, _fork: Oid, _branch: Oid, _upstream: Oid) -> Result<Option<Oid>> {
+        Ok(None)
+    }
+
+    fn dropped_commits(
+        &self,
+        _from: Oid,
+        _to: Oid,
+        _onto: Oid,
+        _new_tip: Oid,
+    ) -> Result<Vec<String>> {
+        Ok(vec![])
+    }
+
+    fn is_clean(&self) -> Result<bool> {
+        Ok(*self.is_clean.borrow())
+    }
+
+    fn require_clean(&self) -> Result<()> {
+        if *self.is_clean.borrow() {
+            Ok(())
+        } else {
+            Err(crate::Error::DirtyWorkingDirectory)
+        }
+    }
+
+    fn stage_all(&self) -> Result<()> {
+        Ok(())
+    }
+
+    fn has_staged_changes(&self) -> Result<bool> {
+        Ok(*self.has_staged_changes.borrow())
+    }
+
+    fn create_commit(&self, _message: &str) -> Result<Oid> {
+        Ok(Oid::zero())
+    }
+
+    fn amend_commit(&self, _new_message: Option<&str>) -> Result<Oid> {
+        Ok(Oid::zero())
+    }
+
+    fn identity(&self) -> Result<Identity> {
+        self.identity
+            .borrow()
+            .clone()
+            .ok_or(crate::Error::MissingIdentity("user.name"))
+    }
+
+    fn signing_config(&self) -> Result<SigningConfig> {
+        Ok(self.signing_config.borrow().clone())
+    }
+
+    fn is_commit_signed(&self, commit: Oid) -> Result<bool> {
+        Ok(self.signed_commits.borrow().contains(&commit))
+    }
+
+    fn rebase_onto(&self, _target: Oid) -> Result<()> {
+        if *self.rebase_should_fail.borrow() {
+            *self.is_rebasing.borrow_mut() = true;
+            return Err(crate::Error::RebaseConflict(vec![
+                "conflict.rs".to_string(),
+            ]));
+        }
+        Ok(())
+    }
+
+    fn rebase_onto_from(&self, _onto: Oid, _from: Oid) -> Result<()> {
+        if *self.rebase_should_fail.borrow() {
+            *self.is_rebasing.borrow_mut() = true;
+            return Err(crate::Error::RebaseConflict(vec![
+                "conflict.rs".to_string(),
+            ]));
+        }
+        Ok(())
+    }
+
+    fn conflicting_files(&self) -> Result<Vec<String>> {
+        if *self.rebase_should_fail.borrow() {
+            Ok(vec!["conflict.rs".to_string()])
+        } else {
+            Ok(vec![])
+        }
+    }
+
+    fn predict_rebase_conflicts(
+        &self,
+        _branch: &str,
+        _onto: Oid,
+    ) -> Result<Vec<crate::ConflictPrediction>> {
+        // Return empty by default - no predicted conflicts
+        Ok(vec![])
+    }
+
+    fn rebase_abort(&self) -> Result<()> {
+        *self.is_rebasing.borrow_mut() = false;
+        Ok(())
+    }
+
+    fn rebase_continue(&self) -> Result<()> {
+        *self.is_rebasing.borrow_mut() = false;
+        Ok(())
+    }
+
+    fn skip_applied_commits(&self) -> Result<usize> {
+        Ok(0)
+    }
+
+    fn origin_url(&self) -> Result<String> {
+        Ok("https://github.com/test/repo.git".to_string())
+    }
+
+    fn remote_divergence(&self, branch: &str) -> Result<RemoteDivergence> {
+        Ok(self
+            .remote_divergence_map
+            .borrow()
+            .get(branch)
+            .cloned()
+            .unwrap_or(RemoteDivergence::InSync))
+    }
+
+    fn detect_default_branch(&self) -> Option<String> {
+        Some("main".to_string())
+    }
+
+    fn push(&self, branch: &str, _force: bool) -> Result<()> {
+        if self
+            .push_results
+            .borrow()
+            .get(branch)
+            .copied()
+            .unwrap_or(true)
+        {
+            Ok(())
+        } else {
+            Err(crate::Error::PushFailed("mock push failed".to_string()))
+        }
+    }
+
+    fn remote_branches(&self) -> Result<Vec<String>> {
+        Ok(self
+            .remote_branches
+            .borrow()
+            .clone()
+            .unwrap_or_else(|| self.branches.borrow().keys().cloned().collect()))
+    }
+
+    fn fetch_all(&self) -> Result<()> {
+        Ok(())
+    }
+
+    fn fetch(&self, _branch: &str) -> Result<()> {
+        Ok(())
+    }
+
+    fn pull_ff(&self) -> Result<()> {
+        Ok(())
+    }
+
+    fn reset_branch(&self, branch: &str, commit: Oid) -> Result<()> {
+        self.branches
+            .borrow_mut()
+            .insert(branch.to_string(), commit);
+        self.branch_exists_map
+            .borrow_mut()
+            .insert(branch.to_string(), true);
+        Ok(())
+    }
+}
@@ -0,0 +1,108 @@
+//! Versions and features of the git tooling rung runs on.
+
+/// Oldest `git` that supports everything rung shells out for
+/// (`git merge-tree --write-tree`, used by `rung sync --check`).
+pub const MIN_GIT_VERSION: (u32, u32, u32) = (2, 38, 0);
+
+/// The `git` binary and the bundled libgit2.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Toolchain {
+    /// `git --version`, e.g. `2.43.0`; `None` if git isn't on `PATH`.
+    pub git: Option<String>,
+    /// libgit2 version, e.g. `1.8.1`.
+    pub libgit2: String,
+    /// Whether libgit2 was built with HTTPS support.
+    pub https: bool,
+    /// Whether libgit2 was built with SSH support.
+    pub ssh: bool,
+}
+
+impl Toolchain {
+    /// Detect the installed `git` and the linked libgit2.
+    #[must_use]
+    pub fn detect() -> Self {
+        let git = std::process::Command::new("git")
+            .arg("--version")
+            .output()
+            .ok()
+            .filter(|output| output.status.success())
+            .and_then(|output| parse_git_version(&String::from_utf8_lossy(&output.stdout)));
+
+        let libgit2 = git2::Version::get();
+        let (major, minor, patch) = libgit2.libgit2_version();
+        Self {
+            git,
+            libgit2: format!("{major}.{minor}.{patch}"),
+            https: libgit2.https(),
+            ssh: libgit2.ssh(),
+        }
+    }
+
+    /// Whether the `git` binary is at least [`MIN_GIT_VERSION`].
+    ///
+    /// Unparseable versions are given the benefit of the doubt.
+    #[must_use]
+    pub fn git_is_supported(&self) -> bool {
+        self.git
+            .as_deref()
+            .is_some_and(|v| version_triple(v).is_none_or(|v| v >= MIN_GIT_VERSION))
+    }
+}
+
+/// Pull the version out of `git version 2.39.3 (Apple Git-145)`.
+fn parse_git_version(output: &str) -> Option<String> {
+    output
+        .trim()
+        .strip_prefix("git version ")?
+        .split_whitespace()
+        .next()
+        .map(String::from)
+}
+
+/// Parse the leading `major.minor.patch` of a version like `2.45.1.windows.1`.
+fn version_triple(version: &str) -> Option<(u32, u32, u32)> {
+    let mut parts = version.split('.').map(str::parse::<u32>);
+    let major = parts.next()?.ok()?;
+    let minor = parts.next()?.ok()?;
+    let patch = parts.next().and_then(Result::ok).unwrap_or(0);
+    Some((major, minor, patch))
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_git_version() {
+        assert_eq!(
+            parse_git_version("git version 2.43.0\n").as_deref(),
+            Some("2.43.0")
+        );
+        assert_eq!(
+            parse_git_version("git version 2.39.3 (Apple Git-145)").as_deref(),
+            Some("2.39.3")
+        );
+        assert_eq!(parse_git_version("not git"), None);
+
+        assert_eq!(version_triple("2.45.1.windows.1"), Some((2, 45, 1)));
+        assert_eq!(version_triple("2.38"), Some((2, 38, 0)));
+        assert_eq!(version_triple("dev"), None);
+    }
+
+    #[test]
+    fn test_git_is_supported() {
+        let toolchain = |git: Option<&str>| Toolchain {
+            git: git.map(String::from),
+            libgit2: "1.8.1".into(),
+            https: true,
+            ssh: true,
+        };
+        assert!(toolchain(Some("2.43.0")).git_is_supported());
+        assert!(toolchain(Some("2.38.0")).git_is_supported());
+        assert!(!toolchain(Some("2.34.1")).git_is_supported());
+        assert!(!toolchain(None).git_is_supported());
+
+        assert!(Toolchain::detect().git.is_some());
+    }
+}
@@ -7,7 +7,10 @@ use std::path::Path;
 
 use git2::Oid;
 
-use crate::{BlameResult, ConflictPrediction, Hunk, RemoteDivergence, Result};
+use crate::{
+    BlameResult, ConflictPrediction, DiffFormat, Hunk, Identity, RemoteDivergence, Result,
+    SigningConfig,
+};
 
 /// Trait for git repository operations.
 ///
@@ -36,6 +39,9 @@ pub trait GitOps {
     /// Check if a rebase is in progress.
     fn is_rebasing(&self) -> bool;
 
+    /// Get the branch a rebase in progress is rewriting, if any.
+    fn rebasing_branch(&self) -> Option<String>;
+
     // === Branch Operations ===
 
     /// Check if a branch exists.
@@ -55,6 +61,9 @@ pub trait GitOps {
     /// List all local branches.
     fn list_branches(&self) -> Result<Vec<String>>;
 
+    /// Get a branch's description, if it has a non-blank one.
+    fn branch_description(&self, branch: &str) -> Option<String>;
+
     // === Commit Operations ===
 
     /// Get the commit ID for a branch.
@@ -72,9 +81,19 @@ pub trait GitOps {
     /// Get commits between two OIDs.
     fn commits_between(&self, from: Oid, to: Oid) -> Result<Vec<Oid>>;
 
+    /// Render a commit's change against its first parent as a patch or diffstat.
+    fn commit_diff(&self, commit: Oid, format: DiffFormat) -> Result<String>;
+
     /// Count commits between two OIDs.
     fn count_commits_between(&self, from: Oid, to: Oid) -> Result<usize>;
 
+    /// Find the last commit in `fork..branch` whose changes already landed on
+    /// `upstream` as a single commit (e.g. a squash-merged parent PR).
+    fn find_squash_applied(&self, fork: Oid, branch: Oid, upstream: Oid) -> Result<Option<Oid>>;
+
+    /// List commits of `from..to` that a rebase onto `onto` dropped from `new_tip`.
+    fn dropped_commits(&self, from: Oid, to: Oid, onto: Oid, new_tip: Oid) -> Result<Vec<String>>;
+
     // === Working Directory ===
 
     /// Check if the working directory is clean.
@@ -95,6 +114,19 @@ pub trait GitOps {
     /// Amend the last commit with staged changes.
     fn amend_commit(&self, new_message: Option<&str>) -> Result<Oid>;
 
+    // === Identity ===
+
+    /// The identity commits are made as, or [`crate::Error::MissingIdentity`].
+    fn identity(&self) -> Result<Identity>;
+
+    // === Signing ===
+
+    /// Read the commit signing settings from git config.
+    fn signing_config(&self) -> Result<SigningConfig>;
+
+    /// Check whether a commit carries a signature.
+    fn is_commit_signed(&self, commit: Oid) -> Result<bool>;
+
     // === Rebase Operations ===
 
     /// Rebase the current branch onto a target commit.
@@ -118,9 +150,12 @@ pub trait GitOps {
     /// Continue a rebase after resolving conflicts.
     fn rebase_continue(&self) -> Result<()>;
 
+    /// Skip rebase stops on commits whose changes are already on the new base.
+    fn skip_applied_commits(&self) -> Result<usize>;
+
     // === Remote Operations ===
 
-    /// Get the origin URL.
+    /// Get the URL of rung's remote (`origin` unless configured otherwise).
     fn origin_url(&self) -> Result<String>;
 
     /// Check divergence between local and remote branch.
@@ -134,6 +169,9 @@ pub trait GitOps {
     /// Push a branch to the remote.
     fn push(&self, branch: &str, force: bool) -> Result<()>;
 
+    /// List the branches that currently exist on rung's remote (queries the remote).
+    fn remote_branches(&self) -> Result<Vec<String>>;
+
     /// Fetch all remotes.
     fn fetch_all(&self) -> Result<()>;
 
@@ -159,9 +197,36 @@ pub trait AbsorbOps: GitOps {
     /// Query git blame for a specific line range in a file.
     fn blame_lines(&self, file_path: &str, start: u32, end: u32) -> Result<Vec<BlameResult>>;
 
+    /// Query git blame for several line ranges in one file.
+    ///
+    /// Returns one result list per range, in the same order as `ranges`.
+    /// The default implementation issues one [`AbsorbOps::blame_lines`]
+    /// query per range.
+    fn blame_ranges(
+        &self,
+        file_path: &str,
+        ranges: &[(u32, u32)],
+    ) -> Result<Vec<Vec<BlameResult>>> {
+        ranges
+            .iter()
+            .map(|&(start, end)| self.blame_lines(file_path, start, end))
+            .collect()
+    }
+
     /// Check if a commit is an ancestor of another commit.
     fn is_ancestor(&self, ancestor: Oid, descendant: Oid) -> Result<bool>;
 
     /// Create a fixup commit targeting the specified commit.
     fn create_fixup_commit(&self, target: Oid) -> Result<Oid>;
+
+    /// Create a fixup commit for `target` from the staged changes on `branch`,
+    /// without checking it out.
+    fn create_fixup_commit_on(&self, branch: &str, target: Oid) -> Result<Oid>;
+
+    /// Count `fixup!` commits in `from..to` whose target is also in that range.
+    fn count_fixup_commits(&self, from: Oid, to: Oid) -> Result<usize>;
+
+    /// Rebase the current branch onto `onto`, replaying commits after
+    /// `upstream` and squashing `fixup!` commits into their targets.
+    fn rebase_autosquash(&self, onto: Oid, upstream: Oid) -> Result<()>;
 }
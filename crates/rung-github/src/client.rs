@@ -1,14 +1,15 @@
 //! GitHub API client.
 
+use chrono::{DateTime, Utc};
 use reqwest::Client;
 use reqwest::header::{ACCEPT, AUTHORIZATION, HeaderMap, HeaderValue, USER_AGENT};
 use secrecy::{ExposeSecret, SecretString};
 use serde::de::DeserializeOwned;
 
 use rung_forge::{
-    CheckRun, CreateComment, CreatePullRequest, ForgeApi, ForgeError as Error, IssueComment,
-    MergePullRequest, MergeResult, PullRequest, PullRequestState, RepoId, Result, UpdateComment,
-    UpdatePullRequest,
+    CheckRun, CommitStatus, CreateComment, CreatePullRequest, ForgeApi, ForgeError as Error,
+    IssueComment, MergeMethod, MergePullRequest, MergeResult, PullRequest, PullRequestState,
+    RepoId, Result, UpdateComment, UpdatePullRequest,
 };
 
 use crate::auth::Auth;
@@ -33,6 +34,9 @@ struct ApiPullRequest {
     mergeable: Option<bool>,
     /// The mergeable state (e.g., "clean", "dirty", "blocked", "behind").
     mergeable_state: Option<String>,
+    /// When the PR was opened.
+    #[serde(default)]
+    created_at: Option<DateTime<Utc>>,
 }
 
 /// Internal representation of a branch ref from the GitHub API.
@@ -66,6 +70,8 @@ impl ApiPullRequest {
             html_url: self.html_url,
             mergeable: self.mergeable,
             mergeable_state: self.mergeable_state,
+            created_at: self.created_at,
+            last_reviewed_at: None,
         }
     }
 
@@ -82,6 +88,8 @@ impl ApiPullRequest {
             html_url: self.html_url,
             mergeable: self.mergeable,
             mergeable_state: self.mergeable_state,
+            created_at: self.created_at,
+            last_reviewed_at: None,
         }
     }
 }
@@ -113,6 +121,23 @@ struct GraphQLPullRequest {
     head_ref_name: String,
     base_ref_name: String,
     url: String,
+    #[serde(default)]
+    created_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    reviews: Option<GraphQLReviews>,
+}
+
+/// The `reviews(last: 1)` connection of a GraphQL PR.
+#[derive(serde::Deserialize)]
+struct GraphQLReviews {
+    nodes: Vec<GraphQLReview>,
+}
+
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GraphQLReview {
+    /// Unset while a review is pending.
+    submitted_at: Option<DateTime<Utc>>,
 }
 
 impl GraphQLPullRequest {
@@ -136,6 +161,10 @@ impl GraphQLPullRequest {
             html_url: self.url,
             mergeable: None, // Not fetched in batch query
             mergeable_state: None,
+            created_at: self.created_at,
+            last_reviewed_at: self
+                .reviews
+                .and_then(|r| r.nodes.into_iter().find_map(|review| review.submitted_at)),
         }
     }
 }
@@ -156,6 +185,28 @@ struct GraphQLError {
     message: String,
 }
 
+/// The account and permissions behind a GitHub token.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TokenInfo {
+    /// Login of the token's owner.
+    pub login: String,
+    /// OAuth scopes of a classic token; `None` for fine-grained and app
+    /// tokens, which GitHub doesn't report scopes for.
+    pub scopes: Option<Vec<String>>,
+}
+
+impl TokenInfo {
+    /// Whether the token can push and open PRs: classic tokens need `repo`
+    /// (or `public_repo` for public repositories). Tokens without reported
+    /// scopes get the benefit of the doubt.
+    #[must_use]
+    pub fn can_write_repos(&self) -> bool {
+        self.scopes
+            .as_ref()
+            .is_none_or(|scopes| scopes.iter().any(|s| s == "repo" || s == "public_repo"))
+    }
+}
+
 /// GitHub API client.
 pub struct GitHubClient {
     client: Client,
@@ -498,6 +549,28 @@ impl GitHubClient {
             .map(|api_pr| api_pr.into_pull_request_with_state(PullRequestState::Open)))
     }
 
+    /// List every open PR whose head is `branch`.
+    ///
+    /// # Errors
+    /// Returns error if request fails.
+    pub async fn find_open_prs_for_branch(
+        &self,
+        owner: &str,
+        repo: &str,
+        branch: &str,
+    ) -> Result<Vec<PullRequest>> {
+        let prs: Vec<ApiPullRequest> = self
+            .get(&format!(
+                "/repos/{owner}/{repo}/pulls?head={owner}:{branch}&state=open"
+            ))
+            .await?;
+
+        Ok(prs
+            .into_iter()
+            .map(|api_pr| api_pr.into_pull_request_with_state(PullRequestState::Open))
+            .collect())
+    }
+
     /// Create a pull request.
     ///
     /// # Errors
@@ -584,6 +657,28 @@ impl GitHubClient {
             .collect())
     }
 
+    /// Set a commit status on a commit.
+    ///
+    /// Uses the commit statuses API rather than check runs, which only
+    /// GitHub Apps can create.
+    ///
+    /// # Errors
+    /// Returns error if request fails.
+    pub async fn set_commit_status(
+        &self,
+        owner: &str,
+        repo: &str,
+        commit_sha: &str,
+        status: &CommitStatus,
+    ) -> Result<()> {
+        self.post::<serde::de::IgnoredAny, _>(
+            &format!("/repos/{owner}/{repo}/statuses/{commit_sha}"),
+            status,
+        )
+        .await?;
+        Ok(())
+    }
+
     // === Merge Operations ===
 
     /// Merge a pull request.
@@ -615,6 +710,49 @@ impl GitHubClient {
             .await
     }
 
+    // === Token Operations ===
+
+    /// Look up who the token belongs to and which OAuth scopes it has.
+    ///
+    /// # Errors
+    /// Returns [`Error::AuthenticationFailed`] if the token is invalid or
+    /// expired, or an error if the API call fails.
+    pub async fn token_info(&self) -> Result<TokenInfo> {
+        #[derive(serde::Deserialize)]
+        struct User {
+            login: String,
+        }
+
+        let url = format!("{}/user", self.base_url);
+        let response = self
+            .client
+            .get(&url)
+            .header(
+                AUTHORIZATION,
+                format!("Bearer {}", self.token.expose_secret()),
+            )
+            .send()
+            .await?;
+
+        // Only classic tokens report scopes; fine-grained and app tokens don't
+        let scopes = response
+            .headers()
+            .get("x-oauth-scopes")
+            .and_then(|v| v.to_str().ok())
+            .map(|v| {
+                v.split(',')
+                    .map(str::trim)
+                    .filter(|s| !s.is_empty())
+                    .map(String::from)
+                    .collect()
+            });
+        let user: User = self.handle_response(response).await?;
+        Ok(TokenInfo {
+            login: user.login,
+            scopes,
+        })
+    }
+
     // === Repository Operations ===
 
     /// Get the repository's default branch name.
@@ -631,6 +769,34 @@ impl GitHubClient {
         Ok(info.default_branch)
     }
 
+    /// Get the merge methods enabled in the repository settings.
+    ///
+    /// Methods are ordered squash, merge, rebase. GitHub omits the settings
+    /// for tokens without push access; a missing setting counts as allowed.
+    ///
+    /// # Errors
+    /// Returns error if API call fails.
+    pub async fn get_merge_methods(&self, owner: &str, repo: &str) -> Result<Vec<MergeMethod>> {
+        #[derive(serde::Deserialize)]
+        #[allow(clippy::struct_field_names)]
+        struct RepoSettings {
+            allow_squash_merge: Option<bool>,
+            allow_merge_commit: Option<bool>,
+            allow_rebase_merge: Option<bool>,
+        }
+
+        let settings: RepoSettings = self.get(&format!("/repos/{owner}/{repo}")).await?;
+        Ok([
+            (settings.allow_squash_merge, MergeMethod::Squash),
+            (settings.allow_merge_commit, MergeMethod::Merge),
+            (settings.allow_rebase_merge, MergeMethod::Rebase),
+        ]
+        .into_iter()
+        .filter(|(allowed, _)| allowed.unwrap_or(true))
+        .map(|(_, method)| method)
+        .collect())
+    }
+
     // === Comment Operations ===
 
     /// List comments on a pull request.
@@ -697,7 +863,8 @@ impl std::fmt::Debug for GitHubClient {
 
 /// Build a GraphQL query to fetch multiple PRs in a single request.
 fn build_graphql_pr_query(numbers: &[u64]) -> String {
-    const PR_FIELDS: &str = "number state merged isDraft headRefName baseRefName url";
+    const PR_FIELDS: &str = "number state merged isDraft headRefName baseRefName url createdAt \
+                             reviews(last: 1) { nodes { submittedAt } }";
 
     let pr_queries: Vec<String> = numbers
         .iter()
@@ -746,6 +913,15 @@ impl ForgeApi for GitHubClient {
         self.find_pr_for_branch(owner, name, branch).await
     }
 
+    async fn find_open_prs_for_branch(
+        &self,
+        repo: &RepoId,
+        branch: &str,
+    ) -> Result<Vec<PullRequest>> {
+        let (owner, name) = github_parts(repo)?;
+        self.find_open_prs_for_branch(owner, name, branch).await
+    }
+
     async fn create_pr(&self, repo: &RepoId, pr: CreatePullRequest) -> Result<PullRequest> {
         let (owner, name) = github_parts(repo)?;
         self.create_pr(owner, name, pr).await
@@ -766,6 +942,17 @@ impl ForgeApi for GitHubClient {
         self.get_check_runs(owner, name, commit_sha).await
     }
 
+    async fn set_commit_status(
+        &self,
+        repo: &RepoId,
+        commit_sha: &str,
+        status: CommitStatus,
+    ) -> Result<()> {
+        let (owner, name) = github_parts(repo)?;
+        self.set_commit_status(owner, name, commit_sha, &status)
+            .await
+    }
+
     async fn merge_pr(
         &self,
         repo: &RepoId,
@@ -786,6 +973,11 @@ impl ForgeApi for GitHubClient {
         self.get_default_branch(owner, name).await
     }
 
+    async fn get_merge_methods(&self, repo: &RepoId) -> Result<Vec<MergeMethod>> {
+        let (owner, name) = github_parts(repo)?;
+        self.get_merge_methods(owner, name).await
+    }
+
     async fn list_pr_comments(&self, repo: &RepoId, pr_number: u64) -> Result<Vec<IssueComment>> {
         let (owner, name) = github_parts(repo)?;
         self.list_pr_comments(owner, name, pr_number).await
@@ -820,7 +1012,7 @@ mod tests {
     use super::*;
     use rung_forge::{CheckStatus, MergeMethod};
     use secrecy::SecretString;
-    use wiremock::matchers::{header, method, path, query_param};
+    use wiremock::matchers::{body_json, header, method, path, query_param};
     use wiremock::{Mock, MockServer, ResponseTemplate};
 
     #[test]
@@ -1063,6 +1255,78 @@ mod tests {
         assert!(pr.is_none());
     }
 
+    #[tokio::test]
+    async fn test_find_open_prs_for_branch_lists_all() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/repos/owner/repo/pulls"))
+            .and(query_param("head", "owner:feature"))
+            .and(query_param("state", "open"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([
+                pr_response_json(42, "open", false),
+                pr_response_json(43, "open", false)
+            ])))
+            .mount(&mock_server)
+            .await;
+
+        let client = test_client(&mock_server.uri());
+        let prs = client
+            .find_open_prs_for_branch("owner", "repo", "feature")
+            .await
+            .unwrap();
+
+        let numbers: Vec<u64> = prs.iter().map(|pr| pr.number).collect();
+        assert_eq!(numbers, vec![42, 43]);
+    }
+
+    #[tokio::test]
+    async fn test_token_info_reads_scopes() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/user"))
+            .and(header("authorization", "Bearer test-token"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("x-oauth-scopes", "read:org, gist")
+                    .set_body_json(serde_json::json!({ "login": "octocat" })),
+            )
+            .mount(&mock_server)
+            .await;
+
+        let client = test_client(&mock_server.uri());
+        let info = client.token_info().await.unwrap();
+
+        assert_eq!(info.login, "octocat");
+        assert_eq!(info.scopes, Some(vec!["read:org".into(), "gist".into()]));
+        assert!(!info.can_write_repos());
+        assert!(
+            TokenInfo {
+                login: "octocat".into(),
+                scopes: None
+            }
+            .can_write_repos()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_token_info_invalid_token() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/user"))
+            .respond_with(ResponseTemplate::new(401))
+            .mount(&mock_server)
+            .await;
+
+        let client = test_client(&mock_server.uri());
+        assert!(matches!(
+            client.token_info().await,
+            Err(Error::AuthenticationFailed)
+        ));
+    }
+
     // === Create PR Tests ===
 
     #[tokio::test]
@@ -1171,6 +1435,37 @@ mod tests {
         assert_eq!(checks[2].status, CheckStatus::Queued);
     }
 
+    #[tokio::test]
+    async fn test_set_commit_status() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("POST"))
+            .and(path("/repos/owner/repo/statuses/abc123"))
+            .and(body_json(serde_json::json!({
+                "state": "pending",
+                "description": "2 of 3 in stack, waiting on #41",
+                "context": "rung/stack"
+            })))
+            .respond_with(ResponseTemplate::new(201).set_body_json(serde_json::json!({
+                "id": 1,
+                "state": "pending"
+            })))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let client = test_client(&mock_server.uri());
+        let status = CommitStatus {
+            state: rung_forge::CommitState::Pending,
+            description: "2 of 3 in stack, waiting on #41".to_string(),
+            context: "rung/stack".to_string(),
+        };
+        client
+            .set_commit_status("owner", "repo", "abc123", &status)
+            .await
+            .unwrap();
+    }
+
     #[tokio::test]
     async fn test_get_check_runs_various_statuses() {
         let mock_server = MockServer::start().await;
@@ -1306,6 +1601,48 @@ mod tests {
         assert_eq!(branch, "main");
     }
 
+    #[tokio::test]
+    async fn test_get_merge_methods_from_settings() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/repos/owner/repo"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "default_branch": "main",
+                "allow_squash_merge": false,
+                "allow_merge_commit": true,
+                "allow_rebase_merge": true
+            })))
+            .mount(&mock_server)
+            .await;
+
+        let client = test_client(&mock_server.uri());
+        let methods = client.get_merge_methods("owner", "repo").await.unwrap();
+
+        assert_eq!(methods, vec![MergeMethod::Merge, MergeMethod::Rebase]);
+    }
+
+    #[tokio::test]
+    async fn test_get_merge_methods_missing_settings_allow_all() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/repos/owner/repo"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "default_branch": "main"
+            })))
+            .mount(&mock_server)
+            .await;
+
+        let client = test_client(&mock_server.uri());
+        let methods = client.get_merge_methods("owner", "repo").await.unwrap();
+
+        assert_eq!(
+            methods,
+            vec![MergeMethod::Squash, MergeMethod::Merge, MergeMethod::Rebase]
+        );
+    }
+
     // === Comment Tests ===
 
     #[tokio::test]
@@ -1410,7 +1747,9 @@ mod tests {
                             "isDraft": false,
                             "headRefName": "feature-1",
                             "baseRefName": "main",
-                            "url": "https://github.com/owner/repo/pull/1"
+                            "url": "https://github.com/owner/repo/pull/1",
+                            "createdAt": "2026-01-02T03:04:05Z",
+                            "reviews": { "nodes": [{ "submittedAt": "2026-01-05T00:00:00Z" }] }
                         },
                         "pr1": {
                             "number": 2,
@@ -1438,6 +1777,14 @@ mod tests {
         assert_eq!(result.get(&1).unwrap().state, PullRequestState::Open);
         assert_eq!(result.get(&2).unwrap().state, PullRequestState::Merged);
         assert!(!result.contains_key(&999)); // PR 999 was null
+
+        let opened = result.get(&1).unwrap();
+        assert_eq!(
+            opened.created_at.map(|at| at.to_rfc3339()).as_deref(),
+            Some("2026-01-02T03:04:05+00:00")
+        );
+        assert!(opened.last_reviewed_at.is_some());
+        assert!(result.get(&2).unwrap().last_reviewed_at.is_none());
     }
 
     #[tokio::test]
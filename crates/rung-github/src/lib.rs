@@ -7,7 +7,8 @@
 //!
 //! This crate provides the concrete [`GitHubClient`], which implements the
 //! [`ForgeApi`] trait defined in the `rung-forge` contract crate. The forge
-//! types and trait are re-exported here for convenience.
+//! types and trait are re-exported here for convenience. The `test-utils`
+//! feature adds an in-memory [`ForgeApi`] in [`testing`].
 //!
 //! # Security
 //!
@@ -16,15 +17,17 @@
 
 mod auth;
 mod client;
+#[cfg(any(test, feature = "test-utils"))]
+pub mod testing;
 
 pub use auth::Auth;
-pub use client::GitHubClient;
+pub use client::{GitHubClient, TokenInfo};
 // Re-export SecretString for constructing Auth::Token
 pub use secrecy::SecretString;
 // Re-export the forge contract so existing `rung_github::{...}` paths keep working.
 // `ForgeError` is re-exported as `Error` for backward compatibility.
 pub use rung_forge::{
-    CheckRun, CheckStatus, CreateComment, CreatePullRequest, ForgeApi, ForgeError as Error,
-    IssueComment, MergeMethod, MergePullRequest, MergeResult, PullRequest, PullRequestState,
-    RepoId, Result, UpdateComment, UpdatePullRequest,
+    CheckRun, CheckStatus, CommitState, CommitStatus, CreateComment, CreatePullRequest, ForgeApi,
+    ForgeError as Error, IssueComment, MergeMethod, MergePullRequest, MergeResult, PullRequest,
+    PullRequestState, RepoId, Result, UpdateComment, UpdatePullRequest,
 };
@@ -0,0 +1,462 @@
+//! Test doubles for code written against [`ForgeApi`].
+//!
+//! Enabled by the `test-utils` feature. [`MockGitHubClient`] is an in-memory
+//! forge: pull requests, comments, check runs, and commit statuses live in
+//! the mock, so
+//! services can be unit tested without a network or a token. Builders seed
+//! it and accessors inspect what the code under test did.
+
+use std::collections::{BTreeMap, HashMap};
+use std::future::{Future, ready};
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+use crate::{
+    CheckRun, CommitStatus, CreateComment, CreatePullRequest, Error, ForgeApi, IssueComment,
+    MergeMethod, MergePullRequest, MergeResult, PullRequest, PullRequestState, RepoId, Result,
+    UpdateComment, UpdatePullRequest,
+};
+
+/// In-memory implementation of [`ForgeApi`] for testing.
+pub struct MockGitHubClient {
+    default_branch: String,
+    merge_methods: Vec<MergeMethod>,
+    inner: Mutex<Inner>,
+}
+
+#[derive(Default)]
+struct Inner {
+    prs: BTreeMap<u64, PullRequest>,
+    /// Comment ID to the PR it's on and the comment.
+    comments: BTreeMap<u64, (u64, IssueComment)>,
+    check_runs: HashMap<String, Vec<CheckRun>>,
+    statuses: HashMap<String, Vec<CommitStatus>>,
+    deleted_refs: Vec<String>,
+}
+
+impl Default for MockGitHubClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MockGitHubClient {
+    /// An empty forge whose repositories default to `main` and allow every
+    /// merge method.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            default_branch: "main".to_string(),
+            merge_methods: vec![MergeMethod::Squash, MergeMethod::Merge, MergeMethod::Rebase],
+            inner: Mutex::new(Inner::default()),
+        }
+    }
+
+    /// Seed an existing pull request.
+    #[must_use]
+    pub fn with_pr(self, pr: PullRequest) -> Self {
+        self.lock().prs.insert(pr.number, pr);
+        self
+    }
+
+    /// Seed a comment on a pull request, after any seeded before it.
+    #[must_use]
+    pub fn with_comment(self, pr_number: u64, body: &str) -> Self {
+        let mut inner = self.lock();
+        let id = inner.comments.keys().next_back().map_or(1, |last| last + 1);
+        let comment = IssueComment {
+            id,
+            body: Some(body.to_string()),
+        };
+        inner.comments.insert(id, (pr_number, comment));
+        drop(inner);
+        self
+    }
+
+    /// Seed the check runs reported for a commit.
+    #[must_use]
+    pub fn with_check_runs(self, commit_sha: &str, runs: Vec<CheckRun>) -> Self {
+        self.lock().check_runs.insert(commit_sha.to_string(), runs);
+        self
+    }
+
+    /// Set the repository's default branch.
+    #[must_use]
+    pub fn with_default_branch(mut self, branch: &str) -> Self {
+        self.default_branch = branch.to_string();
+        self
+    }
+
+    /// Set the merge methods the repository allows.
+    #[must_use]
+    pub fn with_merge_methods(mut self, methods: Vec<MergeMethod>) -> Self {
+        self.merge_methods = methods;
+        self
+    }
+
+    /// A pull request as it is now.
+    #[must_use]
+    pub fn pr(&self, number: u64) -> Option<PullRequest> {
+        self.lock().prs.get(&number).cloned()
+    }
+
+    /// Every pull request, by number.
+    #[must_use]
+    pub fn prs(&self) -> Vec<PullRequest> {
+        self.lock().prs.values().cloned().collect()
+    }
+
+    /// Comments on a pull request, oldest first.
+    #[must_use]
+    pub fn comments(&self, pr_number: u64) -> Vec<IssueComment> {
+        self.lock()
+            .comments
+            .values()
+            .filter(|(pr, _)| *pr == pr_number)
+            .map(|(_, comment)| comment.clone())
+            .collect()
+    }
+
+    /// Commit statuses set on a commit, one per context.
+    #[must_use]
+    pub fn statuses(&self, commit_sha: &str) -> Vec<CommitStatus> {
+        self.lock()
+            .statuses
+            .get(commit_sha)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Refs deleted through [`ForgeApi::delete_ref`], in order.
+    #[must_use]
+    pub fn deleted_refs(&self) -> Vec<String> {
+        self.lock().deleted_refs.clone()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Inner> {
+        self.inner.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl ForgeApi for MockGitHubClient {
+    fn get_pr(
+        &self,
+        _repo: &RepoId,
+        number: u64,
+    ) -> impl Future<Output = Result<PullRequest>> + Send {
+        ready(self.pr(number).ok_or(Error::PrNotFound(number)))
+    }
+
+    fn get_prs_batch(
+        &self,
+        _repo: &RepoId,
+        numbers: &[u64],
+    ) -> impl Future<Output = Result<HashMap<u64, PullRequest>>> + Send {
+        let inner = self.lock();
+        let prs = numbers
+            .iter()
+            .filter_map(|number| inner.prs.get(number).map(|pr| (*number, pr.clone())))
+            .collect();
+        ready(Ok(prs))
+    }
+
+    fn find_pr_for_branch(
+        &self,
+        repo: &RepoId,
+        branch: &str,
+    ) -> impl Future<Output = Result<Option<PullRequest>>> + Send {
+        let open = self.open_prs(repo, branch);
+        ready(Ok(open.into_iter().next()))
+    }
+
+    fn find_open_prs_for_branch(
+        &self,
+        repo: &RepoId,
+        branch: &str,
+    ) -> impl Future<Output = Result<Vec<PullRequest>>> + Send {
+        ready(Ok(self.open_prs(repo, branch)))
+    }
+
+    fn create_pr(
+        &self,
+        repo: &RepoId,
+        pr: CreatePullRequest,
+    ) -> impl Future<Output = Result<PullRequest>> + Send {
+        let mut inner = self.lock();
+        let number = inner.prs.keys().next_back().map_or(1, |last| last + 1);
+        let created = PullRequest {
+            number,
+            title: pr.title,
+            body: Some(pr.body),
+            state: PullRequestState::Open,
+            draft: pr.draft,
+            head_branch: pr.head,
+            base_branch: pr.base,
+            html_url: format!("https://github.com/{repo}/pull/{number}"),
+            mergeable: None,
+            mergeable_state: None,
+            created_at: None,
+            last_reviewed_at: None,
+        };
+        inner.prs.insert(number, created.clone());
+        drop(inner);
+        ready(Ok(created))
+    }
+
+    fn update_pr(
+        &self,
+        _repo: &RepoId,
+        number: u64,
+        update: UpdatePullRequest,
+    ) -> impl Future<Output = Result<PullRequest>> + Send {
+        let mut inner = self.lock();
+        let result = inner
+            .prs
+            .get_mut(&number)
+            .map(|pr| {
+                if let Some(title) = update.title {
+                    pr.title = title;
+                }
+                if update.body.is_some() {
+                    pr.body = update.body;
+                }
+                if let Some(base) = update.base {
+                    pr.base_branch = base;
+                }
+                pr.clone()
+            })
+            .ok_or(Error::PrNotFound(number));
+        drop(inner);
+        ready(result)
+    }
+
+    fn get_check_runs(
+        &self,
+        _repo: &RepoId,
+        commit_sha: &str,
+    ) -> impl Future<Output = Result<Vec<CheckRun>>> + Send {
+        let runs = self.lock().check_runs.get(commit_sha).cloned();
+        ready(Ok(runs.unwrap_or_default()))
+    }
+
+    fn set_commit_status(
+        &self,
+        _repo: &RepoId,
+        commit_sha: &str,
+        status: CommitStatus,
+    ) -> impl Future<Output = Result<()>> + Send {
+        let mut inner = self.lock();
+        let statuses = inner.statuses.entry(commit_sha.to_string()).or_default();
+        statuses.retain(|existing| existing.context != status.context);
+        statuses.push(status);
+        drop(inner);
+        ready(Ok(()))
+    }
+
+    fn merge_pr(
+        &self,
+        _repo: &RepoId,
+        number: u64,
+        _merge: MergePullRequest,
+    ) -> impl Future<Output = Result<MergeResult>> + Send {
+        let mut inner = self.lock();
+        let result = match inner.prs.get_mut(&number) {
+            None => Err(Error::PrNotFound(number)),
+            Some(pr) if pr.state != PullRequestState::Open => Err(Error::ApiError {
+                status: 405,
+                message: "Pull Request is not mergeable".to_string(),
+            }),
+            Some(pr) => {
+                pr.state = PullRequestState::Merged;
+                Ok(MergeResult {
+                    sha: format!("{number:040x}"),
+                    merged: true,
+                    message: "Pull Request successfully merged".to_string(),
+                })
+            }
+        };
+        drop(inner);
+        ready(result)
+    }
+
+    fn delete_ref(
+        &self,
+        _repo: &RepoId,
+        ref_name: &str,
+    ) -> impl Future<Output = Result<()>> + Send {
+        self.lock().deleted_refs.push(ref_name.to_string());
+        ready(Ok(()))
+    }
+
+    fn get_default_branch(&self, _repo: &RepoId) -> impl Future<Output = Result<String>> + Send {
+        ready(Ok(self.default_branch.clone()))
+    }
+
+    fn get_merge_methods(
+        &self,
+        _repo: &RepoId,
+    ) -> impl Future<Output = Result<Vec<MergeMethod>>> + Send {
+        ready(Ok(self.merge_methods.clone()))
+    }
+
+    fn list_pr_comments(
+        &self,
+        _repo: &RepoId,
+        pr_number: u64,
+    ) -> impl Future<Output = Result<Vec<IssueComment>>> + Send {
+        ready(Ok(self.comments(pr_number)))
+    }
+
+    fn create_pr_comment(
+        &self,
+        _repo: &RepoId,
+        pr_number: u64,
+        comment: CreateComment,
+    ) -> impl Future<Output = Result<IssueComment>> + Send {
+        let mut inner = self.lock();
+        let id = inner.comments.keys().next_back().map_or(1, |last| last + 1);
+        let created = IssueComment {
+            id,
+            body: Some(comment.body),
+        };
+        inner.comments.insert(id, (pr_number, created.clone()));
+        drop(inner);
+        ready(Ok(created))
+    }
+
+    fn update_pr_comment(
+        &self,
+        _repo: &RepoId,
+        comment_id: u64,
+        comment: UpdateComment,
+    ) -> impl Future<Output = Result<IssueComment>> + Send {
+        let mut inner = self.lock();
+        let result = inner
+            .comments
+            .get_mut(&comment_id)
+            .map(|(_, existing)| {
+                existing.body = Some(comment.body);
+                existing.clone()
+            })
+            .ok_or_else(|| Error::ApiError {
+                status: 404,
+                message: "Not Found".to_string(),
+            });
+        drop(inner);
+        ready(result)
+    }
+}
+
+impl MockGitHubClient {
+    /// Open PRs whose head is `branch`.
+    fn open_prs(&self, _repo: &RepoId, branch: &str) -> Vec<PullRequest> {
+        self.lock()
+            .prs
+            .values()
+            .filter(|pr| pr.state == PullRequestState::Open && pr.head_branch == branch)
+            .cloned()
+            .collect()
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn new_pr(head: &str, base: &str) -> CreatePullRequest {
+        CreatePullRequest {
+            title: format!("Add {head}"),
+            body: String::new(),
+            head: head.to_string(),
+            base: base.to_string(),
+            draft: false,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_pr_lifecycle() {
+        let forge = MockGitHubClient::new();
+        let repo = RepoId::new("owner/repo");
+
+        let a = forge.create_pr(&repo, new_pr("a", "main")).await.unwrap();
+        let b = forge.create_pr(&repo, new_pr("b", "a")).await.unwrap();
+        assert_eq!((a.number, b.number), (1, 2));
+        assert_eq!(b.html_url, "https://github.com/owner/repo/pull/2");
+        assert_eq!(
+            forge
+                .find_pr_for_branch(&repo, "b")
+                .await
+                .unwrap()
+                .map(|pr| pr.number),
+            Some(2)
+        );
+
+        forge
+            .merge_pr(
+                &repo,
+                1,
+                MergePullRequest {
+                    commit_title: None,
+                    commit_message: None,
+                    merge_method: MergeMethod::Squash,
+                },
+            )
+            .await
+            .unwrap();
+        let update = UpdatePullRequest {
+            title: None,
+            body: None,
+            base: Some("main".to_string()),
+        };
+        forge.update_pr(&repo, 2, update).await.unwrap();
+
+        assert_eq!(forge.pr(1).unwrap().state, PullRequestState::Merged);
+        assert!(
+            forge
+                .find_pr_for_branch(&repo, "a")
+                .await
+                .unwrap()
+                .is_none()
+        );
+        assert_eq!(forge.pr(2).unwrap().base_branch, "main");
+        assert!(matches!(
+            forge.get_pr(&repo, 3).await,
+            Err(Error::PrNotFound(3))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_comments() {
+        let forge = MockGitHubClient::new();
+        let repo = RepoId::new("owner/repo");
+
+        let comment = forge
+            .create_pr_comment(&repo, 7, CreateComment { body: "one".into() })
+            .await
+            .unwrap();
+        forge
+            .update_pr_comment(&repo, comment.id, UpdateComment { body: "two".into() })
+            .await
+            .unwrap();
+
+        let comments = forge.list_pr_comments(&repo, 7).await.unwrap();
+        assert_eq!(comments.len(), 1);
+        assert_eq!(comments[0].body.as_deref(), Some("two"));
+        assert!(forge.comments(8).is_empty());
+
+        // Seeded comments get their own ids
+        let forge = forge.with_comment(8, "three");
+        let created = forge
+            .create_pr_comment(
+                &repo,
+                8,
+                CreateComment {
+                    body: "four".into(),
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(created.id, 3);
+        assert_eq!(forge.comments(8).len(), 2);
+    }
+}
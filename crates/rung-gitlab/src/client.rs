@@ -14,8 +14,9 @@ use secrecy::{ExposeSecret, SecretString};
 use serde::Deserialize;
 
 use rung_forge::{
-    CheckRun, CreateComment, CreatePullRequest, ForgeApi, ForgeError as Error, IssueComment,
-    MergePullRequest, MergeResult, PullRequest, RepoId, Result, UpdateComment, UpdatePullRequest,
+    CheckRun, CommitStatus, CreateComment, CreatePullRequest, ForgeApi, ForgeError as Error,
+    IssueComment, MergeMethod, MergePullRequest, MergeResult, PullRequest, RepoId, Result,
+    UpdateComment, UpdatePullRequest,
 };
 
 use crate::auth::Auth;
@@ -164,6 +165,14 @@ impl ForgeApi for GitLabClient {
         unimplemented!("GitLab find_pr_for_branch: see #170")
     }
 
+    async fn find_open_prs_for_branch(
+        &self,
+        _repo: &RepoId,
+        _branch: &str,
+    ) -> Result<Vec<PullRequest>> {
+        unimplemented!("GitLab find_open_prs_for_branch: see #170")
+    }
+
     async fn create_pr(&self, _repo: &RepoId, _pr: CreatePullRequest) -> Result<PullRequest> {
         unimplemented!("GitLab create_pr: see #170")
     }
@@ -181,6 +190,15 @@ impl ForgeApi for GitLabClient {
         unimplemented!("GitLab get_check_runs: see #170")
     }
 
+    async fn set_commit_status(
+        &self,
+        _repo: &RepoId,
+        _commit_sha: &str,
+        _status: CommitStatus,
+    ) -> Result<()> {
+        unimplemented!("GitLab set_commit_status: see #170")
+    }
+
     async fn merge_pr(
         &self,
         _repo: &RepoId,
@@ -198,6 +216,10 @@ impl ForgeApi for GitLabClient {
         unimplemented!("GitLab get_default_branch: see #170")
     }
 
+    async fn get_merge_methods(&self, _repo: &RepoId) -> Result<Vec<MergeMethod>> {
+        unimplemented!("GitLab get_merge_methods: see #170")
+    }
+
     async fn list_pr_comments(&self, _repo: &RepoId, _pr_number: u64) -> Result<Vec<IssueComment>> {
         unimplemented!("GitLab list_pr_comments: see #170")
     }
@@ -39,6 +39,8 @@ export default defineConfig({
             { label: "init", slug: "commands/init" },
             { label: "create", slug: "commands/create" },
             { label: "adopt", slug: "commands/adopt" },
+            { label: "track / untrack", slug: "commands/track" },
+            { label: "lock / unlock", slug: "commands/lock" },
             { label: "status", slug: "commands/status" },
             { label: "sync", slug: "commands/sync" },
             { label: "submit", slug: "commands/submit" },
@@ -50,12 +52,23 @@ export default defineConfig({
               label: "navigation (nxt, prv, move)",
               slug: "commands/navigation",
             },
+            { label: "switch", slug: "commands/switch" },
             { label: "log", slug: "commands/log" },
+            { label: "audit", slug: "commands/audit" },
             { label: "absorb", slug: "commands/absorb" },
+            { label: "apply", slug: "commands/apply" },
+            { label: "uncommit", slug: "commands/uncommit" },
+            { label: "edit / done", slug: "commands/edit" },
+            { label: "describe", slug: "commands/describe" },
             { label: "undo", slug: "commands/undo" },
+            { label: "snapshot", slug: "commands/snapshot" },
+            { label: "backup", slug: "commands/backup" },
+            { label: "gc", slug: "commands/gc" },
             { label: "doctor", slug: "commands/doctor" },
+            { label: "ci", slug: "commands/ci" },
             { label: "update", slug: "commands/update" },
             { label: "completions", slug: "commands/completions" },
+            { label: "telemetry", slug: "commands/telemetry" },
           ],
         },
         {
@@ -67,6 +80,7 @@ export default defineConfig({
               label: "Conflict Resolution",
               slug: "guides/conflict-resolution",
             },
+            { label: "Plugins", slug: "guides/plugins" },
           ],
         },
         {